@@ -123,6 +123,12 @@ pub enum LauncherError {
 
     #[error("P2P connection error: {0}")]
     P2PConnection(String),
+
+    #[error("Authentication failed: {0}")]
+    AuthFailed(String),
+
+    #[error("Remote storage error: {0}")]
+    RemoteStorage(String),
 }
 
 impl LauncherError {
@@ -401,6 +407,22 @@ impl LauncherError {
                     }
                 }
             }
+            LauncherError::AuthFailed(msg) => match lang {
+                Language::Russian => ErrorInfo::new("AUTH_FAILED", "Ошибка авторизации")
+                    .with_hint("Войдите в аккаунт заново в настройках лаунчера")
+                    .with_details(msg.clone()),
+                Language::English => ErrorInfo::new("AUTH_FAILED", "Authentication failed")
+                    .with_hint("Sign in to your account again in launcher settings")
+                    .with_details(msg.clone()),
+            }
+            LauncherError::RemoteStorage(msg) => match lang {
+                Language::Russian => ErrorInfo::new("REMOTE_STORAGE_ERROR", "Ошибка удалённого хранилища")
+                    .with_hint("Проверьте адрес, учётные данные и доступность хранилища")
+                    .with_details(msg.clone()),
+                Language::English => ErrorInfo::new("REMOTE_STORAGE_ERROR", "Remote storage error")
+                    .with_hint("Check the address, credentials and that the storage is reachable")
+                    .with_details(msg.clone()),
+            }
         }
     }
 }
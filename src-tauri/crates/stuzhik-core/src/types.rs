@@ -88,6 +88,11 @@ pub enum LoaderType {
     NeoForge,
     Fabric,
     Quilt,
+    // Серверные платформы с плагинами (только для серверов)
+    Paper,
+    Purpur,
+    Folia,
+    Velocity,
 }
 
 impl LoaderType {
@@ -98,6 +103,10 @@ impl LoaderType {
             Self::NeoForge => "neoforge",
             Self::Fabric => "fabric",
             Self::Quilt => "quilt",
+            Self::Paper => "paper",
+            Self::Purpur => "purpur",
+            Self::Folia => "folia",
+            Self::Velocity => "velocity",
         }
     }
 
@@ -108,9 +117,18 @@ impl LoaderType {
             "neoforge" => Some(Self::NeoForge),
             "fabric" => Some(Self::Fabric),
             "quilt" => Some(Self::Quilt),
+            "paper" => Some(Self::Paper),
+            "purpur" => Some(Self::Purpur),
+            "folia" => Some(Self::Folia),
+            "velocity" => Some(Self::Velocity),
             _ => None,
         }
     }
+
+    /// Платформа с плагинами вместо модов (Paper, Purpur, Folia, Velocity)
+    pub fn is_plugin_platform(&self) -> bool {
+        matches!(self, Self::Paper | Self::Purpur | Self::Folia | Self::Velocity)
+    }
 }
 
 impl FromSql for LoaderType {
@@ -121,6 +139,10 @@ impl FromSql for LoaderType {
             "neoforge" => Ok(LoaderType::NeoForge),
             "fabric" => Ok(LoaderType::Fabric),
             "quilt" => Ok(LoaderType::Quilt),
+            "paper" => Ok(LoaderType::Paper),
+            "purpur" => Ok(LoaderType::Purpur),
+            "folia" => Ok(LoaderType::Folia),
+            "velocity" => Ok(LoaderType::Velocity),
             other => Err(FromSqlError::Other(Box::new(std::io::Error::new(
                 std::io::ErrorKind::InvalidData,
                 format!("Unknown LoaderType: {}", other),
@@ -137,6 +159,10 @@ impl ToSql for LoaderType {
             LoaderType::NeoForge => "neoforge",
             LoaderType::Fabric => "fabric",
             LoaderType::Quilt => "quilt",
+            LoaderType::Paper => "paper",
+            LoaderType::Purpur => "purpur",
+            LoaderType::Folia => "folia",
+            LoaderType::Velocity => "velocity",
         };
         Ok(ToSqlOutput::from(s))
     }
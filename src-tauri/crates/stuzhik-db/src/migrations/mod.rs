@@ -408,6 +408,113 @@ pub const MIGRATIONS: &[Migration] = &[
             CREATE INDEX IF NOT EXISTS idx_instances_game_type ON instances(game_type);
         "#,
     },
+    Migration {
+        version: 20,
+        description: "Create accounts table and per-instance account binding",
+        sql: r#"
+            -- Launcher accounts: offline, Ely.by, custom Yggdrasil server
+            -- Tokens are NOT stored here - they live in SecureVault (account_{id}_*)
+            CREATE TABLE IF NOT EXISTS accounts (
+                id TEXT PRIMARY KEY,
+                kind TEXT NOT NULL,
+                username TEXT NOT NULL,
+                uuid TEXT,
+                server_url TEXT,
+                is_default INTEGER NOT NULL DEFAULT 0,
+                created_at TEXT NOT NULL,
+                last_used_at TEXT
+            );
+
+            -- Optional per-instance account (NULL = default account)
+            ALTER TABLE instances ADD COLUMN account_id TEXT;
+        "#,
+    },
+    Migration {
+        version: 21,
+        description: "Create mod version store for update rollback",
+        sql: r#"
+            -- Previous jars kept after update_mod (files live in <instance>/.stuzhik/mod_versions)
+            CREATE TABLE IF NOT EXISTS mod_version_store (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                instance_id TEXT NOT NULL,
+                slug TEXT NOT NULL,
+                version TEXT NOT NULL,
+                file_name TEXT NOT NULL,
+                file_hash TEXT,
+                mod_row TEXT NOT NULL,
+                replaced_by TEXT,
+                batch_id TEXT,
+                stored_at TEXT NOT NULL,
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+            CREATE INDEX IF NOT EXISTS idx_mod_version_store_slug ON mod_version_store(instance_id, slug);
+            CREATE INDEX IF NOT EXISTS idx_mod_version_store_batch ON mod_version_store(batch_id);
+
+            -- Version skipped by update checks after a rollback
+            ALTER TABLE mods ADD COLUMN update_hold TEXT;
+
+            -- Last check_mod_updates run, groups updates into a batch
+            ALTER TABLE instances ADD COLUMN update_batch_id TEXT;
+        "#,
+    },
+    Migration {
+        version: 22,
+        description: "Create per-mod update policies",
+        sql: r#"
+            -- Keyed by slug: update_mod recreates the mods row
+            CREATE TABLE IF NOT EXISTS mod_update_policies (
+                instance_id TEXT NOT NULL,
+                slug TEXT NOT NULL,
+                channel TEXT,
+                pinned_version TEXT,
+                version_cap TEXT,
+                hold_reason TEXT,
+                hold_until TEXT,
+                updated_at TEXT NOT NULL,
+                PRIMARY KEY (instance_id, slug),
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+
+            -- Policy JSON for modpack project mods (exported to .stzhk ModEntry)
+            ALTER TABLE modpack_project_mods ADD COLUMN update_policy TEXT;
+        "#,
+    },
+    Migration {
+        version: 23,
+        description: "Create crash bisection sessions",
+        sql: r#"
+            -- One session per instance; state holds the JSON search state and step log
+            CREATE TABLE IF NOT EXISTS mod_bisect_sessions (
+                instance_id TEXT PRIMARY KEY,
+                id TEXT NOT NULL,
+                state TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+        "#,
+    },
+    Migration {
+        version: 24,
+        description: "Create backups table with world, schedule and storage columns",
+        sql: r#"
+            -- world: world backups, schedule_id: scheduled backups, storage: chunked/archive
+            CREATE TABLE IF NOT EXISTS backups (
+                id TEXT PRIMARY KEY,
+                instance_id TEXT NOT NULL,
+                trigger_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                includes_saves INTEGER NOT NULL,
+                file_count INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                world TEXT,
+                schedule_id TEXT,
+                storage TEXT,
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            );
+        "#,
+    },
 ];
 
 /// Initialize migrations table
@@ -662,6 +769,87 @@ pub fn run_migrations(conn: &Connection) -> rusqlite::Result<()> {
             if !column_exists(conn, "mods", "latest_changelog")? {
                 conn.execute("ALTER TABLE mods ADD COLUMN latest_changelog TEXT", [])?;
             }
+        } else if migration.version == 20 {
+            // Special handling for v20 - table is idempotent, column might already exist
+            conn.execute_batch(
+                r#"
+                CREATE TABLE IF NOT EXISTS accounts (
+                    id TEXT PRIMARY KEY,
+                    kind TEXT NOT NULL,
+                    username TEXT NOT NULL,
+                    uuid TEXT,
+                    server_url TEXT,
+                    is_default INTEGER NOT NULL DEFAULT 0,
+                    created_at TEXT NOT NULL,
+                    last_used_at TEXT
+                );
+                "#,
+            )?;
+            if !column_exists(conn, "instances", "account_id")? {
+                conn.execute("ALTER TABLE instances ADD COLUMN account_id TEXT", [])?;
+            }
+        } else if migration.version == 21 {
+            // Special handling for v21 - table is idempotent, columns might already exist
+            conn.execute_batch(
+                r#"
+                CREATE TABLE IF NOT EXISTS mod_version_store (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    instance_id TEXT NOT NULL,
+                    slug TEXT NOT NULL,
+                    version TEXT NOT NULL,
+                    file_name TEXT NOT NULL,
+                    file_hash TEXT,
+                    mod_row TEXT NOT NULL,
+                    replaced_by TEXT,
+                    batch_id TEXT,
+                    stored_at TEXT NOT NULL,
+                    FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+                );
+                CREATE INDEX IF NOT EXISTS idx_mod_version_store_slug ON mod_version_store(instance_id, slug);
+                CREATE INDEX IF NOT EXISTS idx_mod_version_store_batch ON mod_version_store(batch_id);
+                "#,
+            )?;
+            if !column_exists(conn, "mods", "update_hold")? {
+                conn.execute("ALTER TABLE mods ADD COLUMN update_hold TEXT", [])?;
+            }
+            if !column_exists(conn, "instances", "update_batch_id")? {
+                conn.execute("ALTER TABLE instances ADD COLUMN update_batch_id TEXT", [])?;
+            }
+        } else if migration.version == 22 {
+            // Special handling for v22 - table is idempotent, column might already exist
+            conn.execute_batch(
+                r#"
+                CREATE TABLE IF NOT EXISTS mod_update_policies (
+                    instance_id TEXT NOT NULL,
+                    slug TEXT NOT NULL,
+                    channel TEXT,
+                    pinned_version TEXT,
+                    version_cap TEXT,
+                    hold_reason TEXT,
+                    hold_until TEXT,
+                    updated_at TEXT NOT NULL,
+                    PRIMARY KEY (instance_id, slug),
+                    FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+                );
+                "#,
+            )?;
+            if !column_exists(conn, "modpack_project_mods", "update_policy")? {
+                conn.execute(
+                    "ALTER TABLE modpack_project_mods ADD COLUMN update_policy TEXT",
+                    [],
+                )?;
+            }
+        } else if migration.version == 24 {
+            // Special handling for v24 - older launchers created backups lazily without these columns
+            conn.execute_batch(migration.sql)?;
+            for column in ["world", "schedule_id", "storage"] {
+                if !column_exists(conn, "backups", column)? {
+                    conn.execute(
+                        &format!("ALTER TABLE backups ADD COLUMN {} TEXT", column),
+                        [],
+                    )?;
+                }
+            }
         } else {
             // Normal migration - just execute SQL
             conn.execute_batch(migration.sql)?;
@@ -0,0 +1,576 @@
+//! Launcher accounts (offline / Ely.by / custom Yggdrasil)
+//!
+//! Аккаунты хранятся в таблице `accounts`, токены — в `SecureVault` под ключами
+//! `account_{id}_*`. Экземпляр может быть привязан к аккаунту (`instances.account_id`),
+//! иначе при запуске используется аккаунт по умолчанию.
+
+use super::yggdrasil::{GameProfile, YggdrasilClient};
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::secrets::SecureVault;
+use crate::settings::SettingsManager;
+use chrono::Utc;
+use rusqlite::{params, Connection, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use uuid::Uuid;
+
+/// Сервер Ely.by по умолчанию
+pub const ELY_BY_SERVER_URL: &str = "https://authserver.ely.by";
+
+/// Тип аккаунта
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountKind {
+    Offline,
+    ElyBy,
+    /// Любой совместимый Yggdrasil сервер (Blessing Skin, drasl и т.д.)
+    Yggdrasil,
+}
+
+impl AccountKind {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            AccountKind::Offline => "offline",
+            AccountKind::ElyBy => "ely_by",
+            AccountKind::Yggdrasil => "yggdrasil",
+        }
+    }
+
+    pub fn from_str(s: &str) -> Self {
+        match s {
+            "ely_by" => AccountKind::ElyBy,
+            "yggdrasil" => AccountKind::Yggdrasil,
+            _ => AccountKind::Offline,
+        }
+    }
+}
+
+/// Аккаунт лаунчера (без токенов)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Account {
+    pub id: String,
+    pub kind: AccountKind,
+    pub username: String,
+    /// UUID профиля без дефисов (None для offline — вычисляется из ника)
+    pub uuid: Option<String>,
+    pub server_url: Option<String>,
+    pub is_default: bool,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+impl Account {
+    /// URL сервера авторизации (None для offline)
+    pub fn auth_server_url(&self) -> Option<&str> {
+        match self.kind {
+            AccountKind::Offline => None,
+            AccountKind::ElyBy => Some(self.server_url.as_deref().unwrap_or(ELY_BY_SERVER_URL)),
+            AccountKind::Yggdrasil => self.server_url.as_deref(),
+        }
+    }
+
+    /// Профиль из последнего успешного входа
+    pub fn cached_profile(&self) -> Option<GameProfile> {
+        self.uuid.as_ref().map(|id| GameProfile {
+            id: id.clone(),
+            name: self.username.clone(),
+        })
+    }
+}
+
+/// Запрос на добавление Ely.by / Yggdrasil аккаунта
+#[derive(Debug, Clone, Deserialize)]
+pub struct AddYggdrasilAccountRequest {
+    pub kind: AccountKind,
+    /// Обязателен для `yggdrasil`, для `ely_by` можно не указывать
+    pub server_url: Option<String>,
+    pub username: String,
+    pub password: String,
+}
+
+/// Проверка offline-ника: 3-16 символов, латиница/цифры/подчёркивание (как в vanilla)
+fn validate_offline_username(username: &str) -> Result<()> {
+    let valid_len = (3..=16).contains(&username.len());
+    let valid_chars = username
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || c == '_');
+
+    if valid_len && valid_chars {
+        Ok(())
+    } else {
+        Err(LauncherError::InvalidConfig(format!(
+            "Invalid offline username '{}': use 3-16 latin letters, digits or '_'",
+            username
+        )))
+    }
+}
+
+const ACCOUNT_COLUMNS: &str =
+    "id, kind, username, uuid, server_url, is_default, created_at, last_used_at";
+
+fn row_to_account(row: &rusqlite::Row) -> rusqlite::Result<Account> {
+    Ok(Account {
+        id: row.get(0)?,
+        kind: AccountKind::from_str(&row.get::<_, String>(1)?),
+        username: row.get(2)?,
+        uuid: row.get(3)?,
+        server_url: row.get(4)?,
+        is_default: row.get::<_, i32>(5)? != 0,
+        created_at: row.get(6)?,
+        last_used_at: row.get(7)?,
+    })
+}
+
+/// Account manager
+pub struct AccountManager;
+
+impl AccountManager {
+    /// Список аккаунтов (аккаунт по умолчанию первым)
+    pub fn list_accounts() -> Result<Vec<Account>> {
+        let conn = get_db_conn()?;
+        Self::import_legacy_accounts(&conn)?;
+
+        let mut stmt = conn.prepare(&format!(
+            "SELECT {} FROM accounts ORDER BY is_default DESC, created_at ASC",
+            ACCOUNT_COLUMNS
+        ))?;
+
+        let accounts = stmt
+            .query_map([], row_to_account)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(accounts)
+    }
+
+    /// Получить аккаунт по ID
+    pub fn get_account(id: &str) -> Result<Account> {
+        let conn = get_db_conn()?;
+
+        conn.query_row(
+            &format!("SELECT {} FROM accounts WHERE id = ?1", ACCOUNT_COLUMNS),
+            params![id],
+            row_to_account,
+        )
+        .optional()?
+        .ok_or_else(|| LauncherError::NotFound(format!("Account {}", id)))
+    }
+
+    /// Аккаунт по умолчанию (None если аккаунтов нет)
+    pub fn get_default_account() -> Result<Option<Account>> {
+        let conn = get_db_conn()?;
+        Self::import_legacy_accounts(&conn)?;
+
+        let account = conn
+            .query_row(
+                &format!(
+                    "SELECT {} FROM accounts ORDER BY is_default DESC, created_at ASC LIMIT 1",
+                    ACCOUNT_COLUMNS
+                ),
+                [],
+                row_to_account,
+            )
+            .optional()?;
+
+        Ok(account)
+    }
+
+    /// Аккаунт для запуска экземпляра: привязка экземпляра → аккаунт по умолчанию
+    pub fn resolve_for_instance(instance_id: &str) -> Result<Option<Account>> {
+        if let Some(account_id) = Self::get_instance_account_id(instance_id)? {
+            match Self::get_account(&account_id) {
+                Ok(account) => return Ok(Some(account)),
+                Err(LauncherError::NotFound(_)) => {
+                    log::warn!(
+                        "Instance {} is bound to missing account {}, using default",
+                        instance_id,
+                        account_id
+                    );
+                }
+                Err(e) => return Err(e),
+            }
+        }
+
+        Self::get_default_account()
+    }
+
+    /// Добавить offline аккаунт
+    pub fn add_offline_account(username: &str) -> Result<Account> {
+        let username = username.trim();
+        validate_offline_username(username)?;
+
+        let conn = get_db_conn()?;
+        Self::insert_account(&conn, AccountKind::Offline, username, None, None)
+    }
+
+    /// Войти в Ely.by / Yggdrasil сервер и сохранить аккаунт
+    pub async fn add_yggdrasil_account(request: AddYggdrasilAccountRequest) -> Result<Account> {
+        let server_url = match request.kind {
+            AccountKind::Offline => {
+                return Err(LauncherError::InvalidConfig(
+                    "Offline accounts don't need a server login".to_string(),
+                ))
+            }
+            AccountKind::ElyBy => request
+                .server_url
+                .clone()
+                .unwrap_or_else(|| ELY_BY_SERVER_URL.to_string()),
+            AccountKind::Yggdrasil => request
+                .server_url
+                .clone()
+                .filter(|u| !u.trim().is_empty())
+                .ok_or_else(|| {
+                    LauncherError::InvalidConfig(
+                        "Yggdrasil server URL is required".to_string(),
+                    )
+                })?,
+        };
+
+        let client = YggdrasilClient::discover(&server_url).await;
+        let client_token = Uuid::new_v4().simple().to_string();
+
+        let response = client
+            .authenticate(&request.username, &request.password, &client_token)
+            .await?;
+
+        let profile = response
+            .selected_profile
+            .clone()
+            .or_else(|| response.available_profiles.first().cloned())
+            .ok_or_else(|| {
+                LauncherError::AuthFailed("Account has no Minecraft profile".to_string())
+            })?;
+
+        let conn = get_db_conn()?;
+
+        // Повторный вход в тот же профиль обновляет существующий аккаунт
+        let existing: Option<String> = conn
+            .query_row(
+                "SELECT id FROM accounts WHERE kind = ?1 AND uuid = ?2 AND server_url = ?3",
+                params![request.kind.as_str(), profile.id, server_url],
+                |row| row.get(0),
+            )
+            .optional()?;
+
+        let account = match existing {
+            Some(id) => {
+                Self::update_profile(&id, &profile)?;
+                Self::get_account(&id)?
+            }
+            None => Self::insert_account(
+                &conn,
+                request.kind,
+                &profile.name,
+                Some(&profile.id),
+                Some(&server_url),
+            )?,
+        };
+
+        SecureVault::store_account_access_token(&account.id, &response.access_token)?;
+        SecureVault::store_account_client_token(&account.id, &response.client_token)?;
+
+        log::info!(
+            "Signed in account '{}' ({}) via {}",
+            account.username,
+            account.kind.as_str(),
+            client.api_root()
+        );
+        Ok(account)
+    }
+
+    fn insert_account(
+        conn: &Connection,
+        kind: AccountKind,
+        username: &str,
+        uuid: Option<&str>,
+        server_url: Option<&str>,
+    ) -> Result<Account> {
+        let id = Uuid::new_v4().to_string();
+        let now = Utc::now().to_rfc3339();
+
+        // Первый аккаунт автоматически становится аккаунтом по умолчанию
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM accounts", [], |row| row.get(0))?;
+        let is_default = count == 0;
+
+        conn.execute(
+            "INSERT INTO accounts (id, kind, username, uuid, server_url, is_default, created_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)",
+            params![
+                id,
+                kind.as_str(),
+                username,
+                uuid,
+                server_url,
+                is_default as i32,
+                now
+            ],
+        )?;
+
+        Ok(Account {
+            id,
+            kind,
+            username: username.to_string(),
+            uuid: uuid.map(String::from),
+            server_url: server_url.map(String::from),
+            is_default,
+            created_at: now,
+            last_used_at: None,
+        })
+    }
+
+    /// Обновить ник/UUID после refresh (ник на сервере мог смениться)
+    pub fn update_profile(id: &str, profile: &GameProfile) -> Result<()> {
+        let conn = get_db_conn()?;
+        conn.execute(
+            "UPDATE accounts SET username = ?1, uuid = ?2 WHERE id = ?3",
+            params![profile.name, profile.id, id],
+        )?;
+        Ok(())
+    }
+
+    /// Удалить аккаунт вместе с токенами и привязками экземпляров
+    pub fn remove_account(id: &str) -> Result<()> {
+        let account = Self::get_account(id)?;
+        let mut conn = get_db_conn()?;
+        let tx = conn.transaction()?;
+
+        tx.execute("DELETE FROM accounts WHERE id = ?1", params![id])?;
+        tx.execute(
+            "UPDATE instances SET account_id = NULL WHERE account_id = ?1",
+            params![id],
+        )?;
+
+        // Аккаунт по умолчанию удалён — назначаем самый старый из оставшихся
+        if account.is_default {
+            tx.execute(
+                "UPDATE accounts SET is_default = 1
+                 WHERE id = (SELECT id FROM accounts ORDER BY created_at ASC LIMIT 1)",
+                [],
+            )?;
+        }
+        tx.commit()?;
+
+        SecureVault::delete_account_secrets(id)?;
+        Ok(())
+    }
+
+    /// Выйти из аккаунта: отозвать токен на сервере и удалить аккаунт
+    pub async fn sign_out(id: &str) -> Result<()> {
+        let account = Self::get_account(id)?;
+        if let Some(server_url) = account.auth_server_url() {
+            if let (Ok(access_token), Ok(client_token)) = (
+                SecureVault::get_account_access_token(id),
+                SecureVault::get_account_client_token(id),
+            ) {
+                let client = YggdrasilClient::discover(server_url).await;
+                if let Err(e) = client.invalidate(&access_token, &client_token).await {
+                    log::warn!(
+                        "Failed to invalidate token of '{}': {}",
+                        account.username,
+                        e
+                    );
+                }
+            }
+        }
+
+        Self::remove_account(id)
+    }
+
+    /// Ely.by аккаунт для старых команд `ely_by_*`: аккаунт по умолчанию, если он
+    /// Ely.by, иначе первый Ely.by аккаунт
+    pub fn ely_by_account() -> Result<Option<Account>> {
+        Ok(Self::list_accounts()?
+            .into_iter()
+            .find(|a| a.kind == AccountKind::ElyBy))
+    }
+
+    /// Сделать аккаунт аккаунтом по умолчанию
+    pub fn set_default_account(id: &str) -> Result<()> {
+        Self::get_account(id)?;
+        let conn = get_db_conn()?;
+        conn.execute(
+            "UPDATE accounts SET is_default = (id = ?1)",
+            params![id],
+        )?;
+        Ok(())
+    }
+
+    /// Отметить время последнего запуска
+    pub fn mark_used(id: &str) -> Result<()> {
+        let conn = get_db_conn()?;
+        conn.execute(
+            "UPDATE accounts SET last_used_at = ?1 WHERE id = ?2",
+            params![Utc::now().to_rfc3339(), id],
+        )?;
+        Ok(())
+    }
+
+    /// ID аккаунта, привязанного к экземпляру
+    pub fn get_instance_account_id(instance_id: &str) -> Result<Option<String>> {
+        let conn = get_db_conn()?;
+        let account_id = conn
+            .query_row(
+                "SELECT account_id FROM instances WHERE id = ?1",
+                params![instance_id],
+                |row| row.get::<_, Option<String>>(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(account_id)
+    }
+
+    /// Привязать экземпляр к аккаунту (None = использовать аккаунт по умолчанию)
+    pub fn set_instance_account(instance_id: &str, account_id: Option<&str>) -> Result<()> {
+        if let Some(id) = account_id {
+            Self::get_account(id)?;
+        }
+
+        let conn = get_db_conn()?;
+        let updated = conn.execute(
+            "UPDATE instances SET account_id = ?1 WHERE id = ?2",
+            params![account_id, instance_id],
+        )?;
+
+        if updated == 0 {
+            return Err(LauncherError::InstanceNotFound(instance_id.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Перенести старые настройки (default_username + единственная сессия Ely.by)
+    /// в таблицу аккаунтов. Выполняется один раз — пока таблица пуста.
+    fn import_legacy_accounts(conn: &Connection) -> Result<()> {
+        let count: i64 = conn.query_row("SELECT COUNT(*) FROM accounts", [], |row| row.get(0))?;
+        if count > 0 {
+            return Ok(());
+        }
+
+        let ely_by_default = SettingsManager::get_auth_type()? == "ely_by";
+
+        if let Some((id, name)) = SettingsManager::get_ely_by_profile()? {
+            if let (Ok(access_token), Ok(client_token)) = (
+                SecureVault::get_ely_by_access_token(),
+                SecureVault::get_auth_token(),
+            ) {
+                let server_url = SettingsManager::get_ely_by_server_url()?;
+                let account = Self::insert_account(
+                    conn,
+                    AccountKind::ElyBy,
+                    &name,
+                    Some(&id),
+                    Some(&server_url),
+                )?;
+                SecureVault::store_account_access_token(&account.id, &access_token)?;
+                SecureVault::store_account_client_token(&account.id, &client_token)?;
+                log::info!("Imported Ely.by session '{}' as account", name);
+            }
+        }
+
+        if let Some(username) = SettingsManager::get_default_username()? {
+            if validate_offline_username(&username).is_ok() {
+                let account =
+                    Self::insert_account(conn, AccountKind::Offline, &username, None, None)?;
+                if !ely_by_default {
+                    conn.execute(
+                        "UPDATE accounts SET is_default = (id = ?1)",
+                        params![account.id],
+                    )?;
+                }
+                log::info!("Imported default username '{}' as offline account", username);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub fn list_accounts() -> Result<Vec<Account>> {
+    AccountManager::list_accounts()
+}
+
+#[tauri::command]
+pub fn add_offline_account(username: String) -> Result<Account> {
+    AccountManager::add_offline_account(&username)
+}
+
+#[tauri::command]
+pub async fn add_yggdrasil_account(request: AddYggdrasilAccountRequest) -> Result<Account> {
+    AccountManager::add_yggdrasil_account(request).await
+}
+
+#[tauri::command]
+pub fn remove_account(id: String) -> Result<()> {
+    AccountManager::remove_account(&id)
+}
+
+#[tauri::command]
+pub fn set_default_account(id: String) -> Result<()> {
+    AccountManager::set_default_account(&id)
+}
+
+#[tauri::command]
+pub fn get_instance_account(instance_id: String) -> Result<Option<String>> {
+    AccountManager::get_instance_account_id(&instance_id)
+}
+
+#[tauri::command]
+pub fn set_instance_account(instance_id: String, account_id: Option<String>) -> Result<()> {
+    AccountManager::set_instance_account(&instance_id, account_id.as_deref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_account_kind_roundtrip() {
+        for kind in [
+            AccountKind::Offline,
+            AccountKind::ElyBy,
+            AccountKind::Yggdrasil,
+        ] {
+            assert_eq!(AccountKind::from_str(kind.as_str()), kind);
+        }
+        assert_eq!(AccountKind::from_str("unknown"), AccountKind::Offline);
+    }
+
+    #[test]
+    fn test_validate_offline_username() {
+        assert!(validate_offline_username("Steve").is_ok());
+        assert!(validate_offline_username("alt_account_01").is_ok());
+        assert!(validate_offline_username("ab").is_err());
+        assert!(validate_offline_username("name with spaces").is_err());
+        assert!(validate_offline_username("Стив").is_err());
+        assert!(validate_offline_username("a_very_long_username_x").is_err());
+    }
+
+    #[test]
+    fn test_auth_server_url() {
+        let mut account = Account {
+            id: "1".to_string(),
+            kind: AccountKind::ElyBy,
+            username: "Steve".to_string(),
+            uuid: None,
+            server_url: None,
+            is_default: true,
+            created_at: String::new(),
+            last_used_at: None,
+        };
+        assert_eq!(account.auth_server_url(), Some(ELY_BY_SERVER_URL));
+
+        account.kind = AccountKind::Offline;
+        assert_eq!(account.auth_server_url(), None);
+
+        account.kind = AccountKind::Yggdrasil;
+        account.server_url = Some("https://skins.example.com/api/yggdrasil".to_string());
+        assert_eq!(
+            account.auth_server_url(),
+            Some("https://skins.example.com/api/yggdrasil")
+        );
+    }
+}
@@ -0,0 +1,117 @@
+//! authlib-injector download and `-javaagent` wiring
+//!
+//! authlib-injector подменяет Mojang authlib на лету, направляя вход на серверы,
+//! скины и проверку сессий на указанный Yggdrasil API root (Ely.by и т.п.).
+
+use crate::downloader::{fetch_json, DownloadManager};
+use crate::error::{LauncherError, Result};
+use crate::paths::{find_newest_file_sync, has_extension};
+use serde::Deserialize;
+use std::path::{Path, PathBuf};
+
+/// Метаданные последней сборки authlib-injector
+const LATEST_ARTIFACT_URL: &str = "https://authlib-injector.yushi.moe/artifact/latest.json";
+
+#[derive(Debug, Clone, Deserialize)]
+struct ArtifactMetadata {
+    version: String,
+    download_url: String,
+    checksums: ArtifactChecksums,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct ArtifactChecksums {
+    sha256: String,
+}
+
+/// Директория с jar'ами authlib-injector (общая для всех экземпляров)
+pub fn injector_dir() -> PathBuf {
+    crate::paths::shared_dir().join("authlib-injector")
+}
+
+/// Убедиться, что authlib-injector скачан, и вернуть путь к jar.
+///
+/// Если сервер метаданных недоступен — используем последнюю уже скачанную сборку,
+/// чтобы вход через Ely.by работал без интернета до authlib-injector.yushi.moe.
+pub async fn ensure_authlib_injector(download_manager: &DownloadManager) -> Result<PathBuf> {
+    let dir = injector_dir();
+    tokio::fs::create_dir_all(&dir).await?;
+
+    let metadata = match fetch_json::<ArtifactMetadata>(LATEST_ARTIFACT_URL).await {
+        Ok(m) => m,
+        Err(e) => {
+            log::warn!("Failed to fetch authlib-injector metadata: {}", e);
+            return find_newest_file_sync(&dir, has_extension("jar")).ok_or_else(|| {
+                LauncherError::DownloadFailed(format!(
+                    "authlib-injector is not downloaded and metadata is unavailable: {}",
+                    e
+                ))
+            });
+        }
+    };
+
+    let jar_path = dir.join(format!("authlib-injector-{}.jar", metadata.version));
+
+    if tokio::fs::try_exists(&jar_path).await.unwrap_or(false) {
+        let path = jar_path.clone();
+        let expected = metadata.checksums.sha256.clone();
+        let valid = tokio::task::spawn_blocking(move || {
+            crate::utils::verify_file_hash(&path, &expected).unwrap_or(false)
+        })
+        .await?;
+
+        if valid {
+            return Ok(jar_path);
+        }
+        log::warn!("authlib-injector jar is corrupted, re-downloading");
+        tokio::fs::remove_file(&jar_path).await.ok();
+    }
+
+    log::info!("Downloading authlib-injector {}", metadata.version);
+    download_manager
+        .download(
+            &metadata.download_url,
+            &jar_path,
+            "authlib-injector",
+            Some(&metadata.checksums.sha256),
+        )
+        .await?;
+
+    Ok(jar_path)
+}
+
+/// Сформировать JVM аргумент `-javaagent:<jar>=<api_root>`
+pub fn javaagent_arg(jar_path: &Path, api_root: &str) -> String {
+    format!("-javaagent:{}={}", jar_path.display(), api_root)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_javaagent_arg() {
+        let arg = javaagent_arg(
+            Path::new("/data/shared/authlib-injector/authlib-injector-1.2.5.jar"),
+            "https://authserver.ely.by/api/authlib-injector",
+        );
+        assert_eq!(
+            arg,
+            "-javaagent:/data/shared/authlib-injector/authlib-injector-1.2.5.jar=https://authserver.ely.by/api/authlib-injector"
+        );
+    }
+
+    #[test]
+    fn test_artifact_metadata_parsing() {
+        let json = r#"{
+            "build_number": 55,
+            "version": "1.2.5",
+            "release_time": "2024-05-07T13:04:04.590Z",
+            "download_url": "https://authlib-injector.yushi.moe/artifact/55/authlib-injector-1.2.5.jar",
+            "checksums": { "sha256": "3bc9ebdc583b36abd2a65b626c4b9f35f21177fbf42a851606eaaea3fd42ee0f" }
+        }"#;
+        let meta: ArtifactMetadata = serde_json::from_str(json).unwrap();
+        assert_eq!(meta.version, "1.2.5");
+        assert_eq!(meta.checksums.sha256.len(), 64);
+    }
+}
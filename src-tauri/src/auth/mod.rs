@@ -0,0 +1,352 @@
+//! Authentication for game launch
+//!
+//! - `offline`: детерминированный UUID `OfflinePlayer:<name>` (как в vanilla)
+//! - `ely_by` / `yggdrasil`: Yggdrasil сервер + authlib-injector как `-javaagent`
+//!
+//! Аккаунт для запуска выбирается через [`accounts::AccountManager`]: привязка экземпляра →
+//! `Instance.username` (offline override) → аккаунт по умолчанию → старые настройки.
+
+pub mod accounts;
+pub mod authlib_injector;
+pub mod yggdrasil;
+
+use crate::downloader::DownloadManager;
+use crate::error::{LauncherError, Result};
+use crate::secrets::{SecretError, SecureVault};
+use crate::settings::SettingsManager;
+use crate::types::{Instance, InstanceType};
+use accounts::{
+    Account, AccountKind, AccountManager, AddYggdrasilAccountRequest, ELY_BY_SERVER_URL,
+};
+use serde::Serialize;
+use yggdrasil::{AuthResponse, GameProfile, YggdrasilClient};
+
+/// Данные авторизации, которые подставляются в аргументы запуска
+#[derive(Debug, Clone)]
+pub struct LaunchAuth {
+    pub username: String,
+    /// UUID с дефисами
+    pub uuid: String,
+    pub access_token: String,
+    /// `${user_type}`: "legacy" для offline, "mojang" для Yggdrasil
+    pub user_type: &'static str,
+    /// Готовый `-javaagent:...` аргумент (только для Yggdrasil)
+    pub javaagent: Option<String>,
+}
+
+impl LaunchAuth {
+    /// Offline-авторизация: UUID v5 от `OfflinePlayer:<name>`, токен = UUID без дефисов
+    pub fn offline(username: &str) -> Self {
+        let uuid_string = format!("OfflinePlayer:{}", username);
+        let uuid = uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_DNS, uuid_string.as_bytes());
+
+        Self {
+            username: username.to_string(),
+            uuid: uuid.to_string(),
+            access_token: uuid.to_string().replace('-', ""),
+            user_type: "legacy",
+            javaagent: None,
+        }
+    }
+}
+
+/// Состояние входа в Ely.by (без токенов) для UI
+#[derive(Debug, Clone, Serialize)]
+pub struct ElyBySession {
+    pub logged_in: bool,
+    pub username: Option<String>,
+    pub uuid: Option<String>,
+    pub server_url: String,
+}
+
+/// Ник для offline-запуска: override экземпляра → глобальный → "Player"
+fn offline_username(instance: &Instance) -> Result<String> {
+    Ok(match instance.username {
+        Some(ref u) => u.clone(),
+        None => SettingsManager::get_default_username()?.unwrap_or_else(|| "Player".to_string()),
+    })
+}
+
+/// Где лежат токены Yggdrasil-сессии
+enum SessionStore<'a> {
+    /// Единственная сессия из настроек (`ely_by_login`, до менеджера аккаунтов)
+    Legacy,
+    /// Аккаунт из таблицы `accounts`
+    Account(&'a str),
+}
+
+impl SessionStore<'_> {
+    fn access_token(&self) -> std::result::Result<String, SecretError> {
+        match self {
+            SessionStore::Legacy => SecureVault::get_ely_by_access_token(),
+            SessionStore::Account(id) => SecureVault::get_account_access_token(id),
+        }
+    }
+
+    fn client_token(&self) -> std::result::Result<String, SecretError> {
+        match self {
+            SessionStore::Legacy => SecureVault::get_auth_token(),
+            SessionStore::Account(id) => SecureVault::get_account_client_token(id),
+        }
+    }
+
+    fn clear_access_token(&self) -> Result<()> {
+        match self {
+            SessionStore::Legacy => SecureVault::delete_ely_by_access_token()?,
+            SessionStore::Account(id) => SecureVault::delete_account_access_token(id)?,
+        }
+        Ok(())
+    }
+
+    /// Сохранить токены и профиль после refresh
+    fn persist(&self, response: &AuthResponse) -> Result<Option<GameProfile>> {
+        match self {
+            SessionStore::Legacy => persist_session(response),
+            SessionStore::Account(id) => {
+                SecureVault::store_account_access_token(id, &response.access_token)?;
+                SecureVault::store_account_client_token(id, &response.client_token)?;
+                let profile = response.selected_profile.clone();
+                if let Some(ref p) = profile {
+                    AccountManager::update_profile(id, p)?;
+                }
+                Ok(profile)
+            }
+        }
+    }
+}
+
+/// Определить авторизацию для запуска экземпляра
+pub async fn resolve_launch_auth(
+    instance: &Instance,
+    download_manager: &DownloadManager,
+) -> Result<LaunchAuth> {
+    // Серверам авторизация не нужна — аргументы игрока не используются
+    if matches!(instance.instance_type, InstanceType::Server) {
+        return Ok(LaunchAuth::offline(&offline_username(instance)?));
+    }
+
+    // Явная привязка экземпляра важнее старого поля username
+    let bound = AccountManager::get_instance_account_id(&instance.id)?.is_some();
+    if !bound {
+        if let Some(ref username) = instance.username {
+            return Ok(LaunchAuth::offline(username));
+        }
+    }
+
+    if let Some(account) = AccountManager::resolve_for_instance(&instance.id)? {
+        let auth = account_launch_auth(&account, download_manager).await?;
+        AccountManager::mark_used(&account.id)?;
+        return Ok(auth);
+    }
+
+    match SettingsManager::get_auth_type()?.as_str() {
+        "ely_by" => {
+            let client =
+                YggdrasilClient::discover(&SettingsManager::get_ely_by_server_url()?).await;
+            let cached_profile = SettingsManager::get_ely_by_profile()?
+                .map(|(id, name)| GameProfile { id, name });
+            let (profile, access_token) =
+                ensure_session(&client, &SessionStore::Legacy, cached_profile).await?;
+
+            yggdrasil_launch_auth(&client, profile, access_token, download_manager).await
+        }
+        "offline" => Ok(LaunchAuth::offline(&offline_username(instance)?)),
+        other => {
+            log::warn!(
+                "Auth type '{}' is not supported for launch yet, falling back to offline",
+                other
+            );
+            Ok(LaunchAuth::offline(&offline_username(instance)?))
+        }
+    }
+}
+
+/// Авторизация для запуска от имени аккаунта
+async fn account_launch_auth(
+    account: &Account,
+    download_manager: &DownloadManager,
+) -> Result<LaunchAuth> {
+    log::info!(
+        "Launching as account '{}' ({})",
+        account.username,
+        account.kind.as_str()
+    );
+
+    let server_url = match (account.kind, account.auth_server_url()) {
+        (AccountKind::Offline, _) => return Ok(LaunchAuth::offline(&account.username)),
+        (_, Some(url)) => url,
+        (_, None) => {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Account '{}' has no authentication server",
+                account.username
+            )))
+        }
+    };
+
+    let client = YggdrasilClient::discover(server_url).await;
+    let (profile, access_token) = ensure_session(
+        &client,
+        &SessionStore::Account(&account.id),
+        account.cached_profile(),
+    )
+    .await?;
+
+    yggdrasil_launch_auth(&client, profile, access_token, download_manager).await
+}
+
+async fn yggdrasil_launch_auth(
+    client: &YggdrasilClient,
+    profile: GameProfile,
+    access_token: String,
+    download_manager: &DownloadManager,
+) -> Result<LaunchAuth> {
+    let jar = authlib_injector::ensure_authlib_injector(download_manager).await?;
+
+    Ok(LaunchAuth {
+        username: profile.name.clone(),
+        uuid: profile.dashed_uuid(),
+        access_token,
+        user_type: "mojang",
+        javaagent: Some(authlib_injector::javaagent_arg(&jar, client.api_root())),
+    })
+}
+
+/// Получить действующую сессию: validate → refresh → ошибка «войдите заново»
+async fn ensure_session(
+    client: &YggdrasilClient,
+    store: &SessionStore<'_>,
+    cached_profile: Option<GameProfile>,
+) -> Result<(GameProfile, String)> {
+    let not_logged_in = || LauncherError::AuthFailed("Not signed in, sign in again".to_string());
+
+    let access_token = match store.access_token() {
+        Ok(token) => token,
+        Err(SecretError::NotFound(_)) => return Err(not_logged_in()),
+        Err(e) => return Err(e.into()),
+    };
+    let client_token = match store.client_token() {
+        Ok(token) => token,
+        Err(SecretError::NotFound(_)) => return Err(not_logged_in()),
+        Err(e) => return Err(e.into()),
+    };
+    let cached_profile = cached_profile.ok_or_else(not_logged_in)?;
+
+    match client.validate(&access_token, &client_token).await {
+        Ok(true) => return Ok((cached_profile, access_token)),
+        Ok(false) => log::info!("Yggdrasil access token expired, refreshing..."),
+        Err(e) => {
+            // Сервер авторизации недоступен — запускаемся с кэшированным токеном,
+            // одиночная игра работает, а сервера сами проверят сессию
+            log::warn!("Yggdrasil validate unavailable, using cached token: {}", e);
+            return Ok((cached_profile, access_token));
+        }
+    }
+
+    match client.refresh(&access_token, &client_token).await {
+        Ok(response) => {
+            let profile = store.persist(&response)?.unwrap_or(cached_profile);
+            Ok((profile, response.access_token))
+        }
+        Err(LauncherError::AuthFailed(msg)) => {
+            store.clear_access_token()?;
+            Err(LauncherError::AuthFailed(format!(
+                "Session expired, sign in again: {}",
+                msg
+            )))
+        }
+        Err(e) => Err(e),
+    }
+}
+
+/// Сохранить токены и профиль после authenticate/refresh
+fn persist_session(response: &AuthResponse) -> Result<Option<GameProfile>> {
+    SecureVault::store_ely_by_access_token(&response.access_token)?;
+    SecureVault::store_auth_token(&response.client_token)?;
+
+    let profile = response
+        .selected_profile
+        .clone()
+        .or_else(|| response.available_profiles.first().cloned());
+
+    if let Some(ref p) = profile {
+        SettingsManager::set_ely_by_profile(Some((&p.id, &p.name)))?;
+    }
+
+    Ok(profile)
+}
+
+/// Состояние входа для UI по Ely.by аккаунту
+fn ely_by_session(account: Option<&Account>) -> Result<ElyBySession> {
+    Ok(match account {
+        Some(account) => ElyBySession {
+            logged_in: SecureVault::get_account_access_token(&account.id).is_ok(),
+            username: Some(account.username.clone()),
+            uuid: account.uuid.clone(),
+            server_url: account
+                .auth_server_url()
+                .unwrap_or(ELY_BY_SERVER_URL)
+                .to_string(),
+        },
+        None => ElyBySession {
+            logged_in: false,
+            username: None,
+            uuid: None,
+            server_url: SettingsManager::get_ely_by_server_url()?,
+        },
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// Войти в Ely.by (или совместимый Yggdrasil сервер из настроек).
+/// Вход сохраняется как Ely.by аккаунт и становится аккаунтом по умолчанию.
+#[tauri::command]
+pub async fn ely_by_login(username: String, password: String) -> Result<ElyBySession> {
+    let account = AccountManager::add_yggdrasil_account(AddYggdrasilAccountRequest {
+        kind: AccountKind::ElyBy,
+        server_url: Some(SettingsManager::get_ely_by_server_url()?),
+        username,
+        password,
+    })
+    .await?;
+    AccountManager::set_default_account(&account.id)?;
+
+    ely_by_session(Some(&account))
+}
+
+/// Выйти: отозвать токен на сервере и удалить Ely.by аккаунт
+#[tauri::command]
+pub async fn ely_by_logout() -> Result<()> {
+    if let Some(account) = AccountManager::ely_by_account()? {
+        AccountManager::sign_out(&account.id).await?;
+    }
+
+    // Остатки сессии из настроек, чтобы импорт не вернул её
+    SecureVault::delete_ely_by_access_token()?;
+    SettingsManager::set_ely_by_profile(None)?;
+    Ok(())
+}
+
+/// Текущее состояние входа в Ely.by
+#[tauri::command]
+pub async fn get_ely_by_session() -> Result<ElyBySession> {
+    ely_by_session(AccountManager::ely_by_account()?.as_ref())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_offline_auth_is_deterministic() {
+        let a = LaunchAuth::offline("Steve");
+        let b = LaunchAuth::offline("Steve");
+        assert_eq!(a.uuid, b.uuid);
+        assert_eq!(a.access_token, a.uuid.replace('-', ""));
+        assert_eq!(a.user_type, "legacy");
+        assert!(a.javaagent.is_none());
+        assert_ne!(a.uuid, LaunchAuth::offline("Alex").uuid);
+    }
+}
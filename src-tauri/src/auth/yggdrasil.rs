@@ -0,0 +1,365 @@
+//! Yggdrasil authentication protocol client
+//!
+//! Реализует authserver-часть спецификации authlib-injector:
+//! `{api_root}/authserver/{authenticate,refresh,validate,invalidate}`.
+//! Ely.by и любые совместимые сервера (Blessing Skin, drasl и т.д.) работают через один клиент.
+
+use crate::error::{LauncherError, Result};
+use serde::{Deserialize, Serialize};
+
+/// Ely.by host, который одновременно является legacy authserver и хостом authlib-injector API
+const ELY_BY_AUTHSERVER_HOST: &str = "authserver.ely.by";
+
+/// Путь authlib-injector API на Ely.by
+const ELY_BY_API_ROOT: &str = "https://authserver.ely.by/api/authlib-injector";
+
+/// Заголовок API Location Indication (ALI) из спецификации authlib-injector
+const ALI_HEADER: &str = "x-authlib-injector-api-location";
+
+/// Игровой профиль (uuid без дефисов + ник)
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct GameProfile {
+    pub id: String,
+    pub name: String,
+}
+
+impl GameProfile {
+    /// UUID профиля в каноническом виде (с дефисами), как его ожидает `--uuid`
+    pub fn dashed_uuid(&self) -> String {
+        uuid::Uuid::parse_str(&self.id)
+            .map(|u| u.hyphenated().to_string())
+            .unwrap_or_else(|_| self.id.clone())
+    }
+}
+
+/// Ответ authenticate/refresh
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuthResponse {
+    pub access_token: String,
+    pub client_token: String,
+    #[serde(default)]
+    pub available_profiles: Vec<GameProfile>,
+    pub selected_profile: Option<GameProfile>,
+}
+
+/// Тело ошибки Yggdrasil (`{"error": "...", "errorMessage": "..."}`)
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct YggdrasilError {
+    error: String,
+    #[serde(default)]
+    error_message: Option<String>,
+}
+
+#[derive(Serialize)]
+struct Agent {
+    name: &'static str,
+    version: u32,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct AuthenticateRequest<'a> {
+    agent: Agent,
+    username: &'a str,
+    password: &'a str,
+    client_token: &'a str,
+    request_user: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct RefreshRequest<'a> {
+    access_token: &'a str,
+    client_token: &'a str,
+    request_user: bool,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct TokenPair<'a> {
+    access_token: &'a str,
+    client_token: &'a str,
+}
+
+/// Нормализовать URL сервера авторизации в API root authlib-injector.
+///
+/// - `https://authserver.ely.by` / `ely.by` → `https://authserver.ely.by/api/authlib-injector`
+/// - URL без схемы получает `https://`
+/// - завершающий `/` отбрасывается
+pub fn normalize_api_root(server_url: &str) -> String {
+    let trimmed = server_url.trim().trim_end_matches('/');
+
+    let with_scheme = if trimmed.contains("://") {
+        trimmed.to_string()
+    } else {
+        format!("https://{}", trimmed)
+    };
+
+    let host_and_path = with_scheme
+        .split_once("://")
+        .map(|(_, rest)| rest)
+        .unwrap_or(&with_scheme);
+
+    if host_and_path == ELY_BY_AUTHSERVER_HOST || host_and_path == "ely.by" {
+        return ELY_BY_API_ROOT.to_string();
+    }
+
+    with_scheme
+}
+
+/// Клиент Yggdrasil authserver
+pub struct YggdrasilClient {
+    api_root: String,
+    http: reqwest::Client,
+}
+
+impl YggdrasilClient {
+    pub fn new(api_root: impl Into<String>) -> Self {
+        Self {
+            api_root: api_root.into().trim_end_matches('/').to_string(),
+            http: crate::utils::SHARED_HTTP_CLIENT.clone(),
+        }
+    }
+
+    /// Создать клиент по URL из настроек, учитывая ALI-редирект сервера
+    pub async fn discover(server_url: &str) -> Self {
+        let api_root = normalize_api_root(server_url);
+        let client = Self::new(api_root.clone());
+
+        match client.resolve_ali().await {
+            Some(resolved) if resolved != api_root => {
+                log::info!("Yggdrasil API root resolved via ALI: {}", resolved);
+                Self::new(resolved)
+            }
+            _ => client,
+        }
+    }
+
+    pub fn api_root(&self) -> &str {
+        &self.api_root
+    }
+
+    fn endpoint(&self, method: &str) -> String {
+        format!("{}/authserver/{}", self.api_root, method)
+    }
+
+    /// Проверить заголовок ALI: сервер может указать настоящий API root
+    async fn resolve_ali(&self) -> Option<String> {
+        let response = self.http.get(&self.api_root).send().await.ok()?;
+        let location = response.headers().get(ALI_HEADER)?.to_str().ok()?;
+        let base = reqwest::Url::parse(&self.api_root).ok()?;
+        let resolved = base.join(location).ok()?;
+        Some(resolved.as_str().trim_end_matches('/').to_string())
+    }
+
+    /// Войти по логину/паролю. Для Ely.by с 2FA пароль передаётся как `password:totp`.
+    pub async fn authenticate(
+        &self,
+        username: &str,
+        password: &str,
+        client_token: &str,
+    ) -> Result<AuthResponse> {
+        let body = AuthenticateRequest {
+            agent: Agent {
+                name: "Minecraft",
+                version: 1,
+            },
+            username,
+            password,
+            client_token,
+            request_user: true,
+        };
+
+        self.post_json(&self.endpoint("authenticate"), &body).await
+    }
+
+    /// Обновить access token (старый становится недействительным)
+    pub async fn refresh(&self, access_token: &str, client_token: &str) -> Result<AuthResponse> {
+        let body = RefreshRequest {
+            access_token,
+            client_token,
+            request_user: true,
+        };
+
+        self.post_json(&self.endpoint("refresh"), &body).await
+    }
+
+    /// Проверить access token. `Ok(false)` — токен недействителен, `Err` — сервер недоступен.
+    pub async fn validate(&self, access_token: &str, client_token: &str) -> Result<bool> {
+        let body = TokenPair {
+            access_token,
+            client_token,
+        };
+
+        let response = self
+            .http
+            .post(self.endpoint("validate"))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| LauncherError::ApiError(format!("Yggdrasil validate failed: {}", e)))?;
+
+        let status = response.status();
+        if status.is_success() {
+            Ok(true)
+        } else if status.is_client_error() {
+            Ok(false)
+        } else {
+            Err(LauncherError::ApiError(format!(
+                "Yggdrasil validate returned {}",
+                status
+            )))
+        }
+    }
+
+    /// Отозвать access token
+    pub async fn invalidate(&self, access_token: &str, client_token: &str) -> Result<()> {
+        let body = TokenPair {
+            access_token,
+            client_token,
+        };
+
+        self.http
+            .post(self.endpoint("invalidate"))
+            .json(&body)
+            .send()
+            .await
+            .map_err(|e| LauncherError::ApiError(format!("Yggdrasil invalidate failed: {}", e)))?;
+
+        Ok(())
+    }
+
+    async fn post_json<B: Serialize>(&self, url: &str, body: &B) -> Result<AuthResponse> {
+        let response = self
+            .http
+            .post(url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| LauncherError::ApiError(format!("Yggdrasil request failed: {}", e)))?;
+
+        let status = response.status();
+        let text = response.text().await?;
+
+        if status.is_success() {
+            return serde_json::from_str(&text).map_err(LauncherError::from);
+        }
+
+        // 4xx от authserver = неверные учётные данные / протухший токен
+        match serde_json::from_str::<YggdrasilError>(&text) {
+            Ok(err) if status.is_client_error() => Err(LauncherError::AuthFailed(
+                err.error_message.unwrap_or(err.error),
+            )),
+            _ => Err(LauncherError::ApiError(format!(
+                "Yggdrasil returned {}: {}",
+                status, text
+            ))),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    /// Минимальный Yggdrasil stand-in: отвечает на каждый запрос заданным статусом и телом
+    async fn spawn_stub(status: &'static str, body: &'static str) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buf = vec![0u8; 8192];
+                let _ = socket.read(&mut buf).await;
+                let response = format!(
+                    "HTTP/1.1 {}\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                );
+                let _ = socket.write_all(response.as_bytes()).await;
+            }
+        });
+
+        format!("http://{}", addr)
+    }
+
+    #[test]
+    fn test_normalize_api_root() {
+        assert_eq!(
+            normalize_api_root("https://authserver.ely.by/"),
+            ELY_BY_API_ROOT
+        );
+        assert_eq!(normalize_api_root("ely.by"), ELY_BY_API_ROOT);
+        assert_eq!(
+            normalize_api_root("skins.example.com/api/yggdrasil/"),
+            "https://skins.example.com/api/yggdrasil"
+        );
+        assert_eq!(
+            normalize_api_root("http://127.0.0.1:8080"),
+            "http://127.0.0.1:8080"
+        );
+    }
+
+    #[test]
+    fn test_dashed_uuid() {
+        let profile = GameProfile {
+            id: "069a79f444e94726a5befca90e38aaf5".to_string(),
+            name: "Notch".to_string(),
+        };
+        assert_eq!(
+            profile.dashed_uuid(),
+            "069a79f4-44e9-4726-a5be-fca90e38aaf5"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_authenticate_against_stub() {
+        let root = spawn_stub(
+            "200 OK",
+            r#"{"accessToken":"acc","clientToken":"cli","selectedProfile":{"id":"069a79f444e94726a5befca90e38aaf5","name":"Notch"},"availableProfiles":[]}"#,
+        )
+        .await;
+
+        let client = YggdrasilClient::new(root);
+        let response = client.authenticate("user", "pass", "cli").await.unwrap();
+        assert_eq!(response.access_token, "acc");
+        assert_eq!(response.selected_profile.unwrap().name, "Notch");
+    }
+
+    #[tokio::test]
+    async fn test_invalid_credentials_map_to_auth_error() {
+        let root = spawn_stub(
+            "403 Forbidden",
+            r#"{"error":"ForbiddenOperationException","errorMessage":"Invalid credentials. Invalid username or password."}"#,
+        )
+        .await;
+
+        let client = YggdrasilClient::new(root);
+        let err = client.authenticate("user", "bad", "cli").await.unwrap_err();
+        assert!(matches!(err, LauncherError::AuthFailed(_)));
+    }
+
+    #[tokio::test]
+    async fn test_validate_status_codes() {
+        let ok_root = spawn_stub("204 No Content", "").await;
+        assert!(YggdrasilClient::new(ok_root)
+            .validate("acc", "cli")
+            .await
+            .unwrap());
+
+        let bad_root = spawn_stub(
+            "403 Forbidden",
+            r#"{"error":"ForbiddenOperationException","errorMessage":"Token expired."}"#,
+        )
+        .await;
+        assert!(!YggdrasilClient::new(bad_root)
+            .validate("acc", "cli")
+            .await
+            .unwrap());
+    }
+}
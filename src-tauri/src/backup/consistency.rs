@@ -0,0 +1,138 @@
+//! Консистентные снимки миров запущенного сервера
+//!
+//! Перед копированием мира отключаем автосохранение (`save-off`) и сбрасываем
+//! чанки на диск (`save-all flush`), после — включаем обратно (`save-on`).
+//! Команды идут через RCON, если он подключён, иначе через stdin консоли.
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::server::console::get_console;
+use crate::server::rcon::RCON_CONNECTIONS;
+use crate::server::RconClient;
+
+/// Сколько ждать сообщения о завершении сохранения в консоли
+const SAVE_TIMEOUT: Duration = Duration::from_secs(60);
+const POLL_INTERVAL: Duration = Duration::from_millis(250);
+
+/// Маркер завершения `save-all` в логе (vanilla, Paper, Forge, Fabric)
+const SAVED_MARKER: &str = "Saved the game";
+
+enum Channel {
+    Rcon(Arc<RconClient>),
+    Console,
+}
+
+/// Пока guard жив, сервер не пишет в мир. Обязательно вызвать [`WorldSaveGuard::release`].
+pub struct WorldSaveGuard {
+    instance_id: String,
+    channel: Channel,
+}
+
+impl WorldSaveGuard {
+    /// Заморозить сохранение мира. None — сервер не запущен или не принимает команды,
+    /// копируем как есть.
+    pub async fn acquire(instance_id: &str) -> Option<Self> {
+        let console = get_console(instance_id).await;
+        let (running, has_stdin) = {
+            let console = console.read().await;
+            (console.is_running(), console.can_send_commands())
+        };
+        if !running {
+            return None;
+        }
+
+        let rcon = RCON_CONNECTIONS.read().await.get(instance_id).cloned();
+        let channel = match rcon {
+            Some(client) => Channel::Rcon(client),
+            None if has_stdin => Channel::Console,
+            None => {
+                log::warn!(
+                    "Server {} is running but accepts no commands, world snapshot may be inconsistent",
+                    instance_id
+                );
+                return None;
+            }
+        };
+
+        let guard = Self {
+            instance_id: instance_id.to_string(),
+            channel,
+        };
+
+        if let Err(e) = guard.send("save-off").await {
+            log::warn!("[{}] save-off failed: {}", instance_id, e);
+            return None;
+        }
+        if let Err(e) = guard.flush().await {
+            // Снимок всё равно лучше, чем ничего — продолжаем с выключенным автосохранением
+            log::warn!("[{}] save-all did not complete: {}", instance_id, e);
+        }
+
+        log::info!("[{}] World saving paused for backup", instance_id);
+        Some(guard)
+    }
+
+    /// Вернуть автосохранение
+    pub async fn release(self) {
+        match self.send("save-on").await {
+            Ok(()) => log::info!("[{}] World saving resumed", self.instance_id),
+            Err(e) => log::error!(
+                "[{}] Failed to send save-on, auto-save stays disabled: {}",
+                self.instance_id,
+                e
+            ),
+        }
+    }
+
+    async fn send(&self, command: &str) -> Result<(), String> {
+        match &self.channel {
+            Channel::Rcon(client) => client
+                .command(command)
+                .await
+                .map(|_| ())
+                .map_err(|e| e.to_string()),
+            Channel::Console => get_console(&self.instance_id)
+                .await
+                .write()
+                .await
+                .send_command(command)
+                .map_err(|e| e.to_string()),
+        }
+    }
+
+    /// `save-all flush` и ожидание его завершения
+    async fn flush(&self) -> Result<(), String> {
+        let since = chrono::Utc::now().timestamp_millis() - 1;
+
+        // RCON отвечает только после выполнения команды на главном потоке сервера
+        if let Channel::Rcon(client) = &self.channel {
+            let response = client
+                .command("save-all flush")
+                .await
+                .map_err(|e| e.to_string())?;
+            if response.contains(SAVED_MARKER) {
+                return Ok(());
+            }
+        } else {
+            self.send("save-all flush").await?;
+        }
+
+        let console = get_console(&self.instance_id).await;
+        let deadline = tokio::time::Instant::now() + SAVE_TIMEOUT;
+        while tokio::time::Instant::now() < deadline {
+            let saved = console
+                .read()
+                .await
+                .get_logs_since(since)
+                .iter()
+                .any(|entry| entry.line.contains(SAVED_MARKER));
+            if saved {
+                return Ok(());
+            }
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        Err(format!("no '{}' within {:?}", SAVED_MARKER, SAVE_TIMEOUT))
+    }
+}
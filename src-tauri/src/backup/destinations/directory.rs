@@ -0,0 +1,113 @@
+//! Выгрузка в локальную папку (второй диск, NAS, облачная синхронизация)
+
+use std::path::{Path, PathBuf};
+use tokio::fs;
+
+use crate::backup::store::{relative_to_string, safe_join};
+use crate::error::{LauncherError, Result};
+
+pub struct DirectoryRemote {
+    root: PathBuf,
+}
+
+impl DirectoryRemote {
+    pub fn new(root: PathBuf) -> Self {
+        Self { root }
+    }
+
+    fn path(&self, key: &str) -> Result<PathBuf> {
+        safe_join(&self.root, key)
+    }
+
+    pub async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.path(key)?;
+        let tmp = tmp_path(&path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::write(&tmp, data).await?;
+        replace(&tmp, &path).await
+    }
+
+    pub async fn put_file(&self, key: &str, source: &Path) -> Result<()> {
+        let path = self.path(key)?;
+        let tmp = tmp_path(&path);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent).await?;
+        }
+        fs::copy(source, &tmp).await?;
+        replace(&tmp, &path).await
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let path = self.path(key)?;
+        fs::read(&path).await.map_err(|e| not_found(e, key))
+    }
+
+    pub async fn get_file(&self, key: &str, target: &Path) -> Result<()> {
+        let path = self.path(key)?;
+        let tmp = tmp_path(target);
+        fs::copy(&path, &tmp).await.map_err(|e| not_found(e, key))?;
+        replace(&tmp, target).await
+    }
+
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let root = self.root.clone();
+        let dir = self.path(prefix.trim_end_matches('/'))?;
+
+        tokio::task::spawn_blocking(move || {
+            let mut keys = Vec::new();
+            if !dir.exists() {
+                return Ok(keys);
+            }
+            for entry in walkdir::WalkDir::new(&dir) {
+                let entry = entry.map_err(|e| LauncherError::RemoteStorage(e.to_string()))?;
+                if !entry.file_type().is_file() {
+                    continue;
+                }
+                let name = entry.file_name().to_string_lossy();
+                // Недописанные файлы
+                if name.contains(".tmp-") {
+                    continue;
+                }
+                if let Ok(relative) = entry.path().strip_prefix(&root) {
+                    keys.push(relative_to_string(relative));
+                }
+            }
+            Ok(keys)
+        })
+        .await?
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        match fs::remove_file(self.path(key)?).await {
+            Ok(()) => Ok(()),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+            Err(e) => Err(e.into()),
+        }
+    }
+}
+
+fn tmp_path(path: &Path) -> PathBuf {
+    let name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    path.with_file_name(format!("{}.tmp-{}", name, uuid::Uuid::new_v4()))
+}
+
+async fn replace(tmp: &Path, path: &Path) -> Result<()> {
+    if let Err(e) = fs::rename(tmp, path).await {
+        let _ = fs::remove_file(tmp).await;
+        return Err(e.into());
+    }
+    Ok(())
+}
+
+fn not_found(e: std::io::Error, key: &str) -> LauncherError {
+    if e.kind() == std::io::ErrorKind::NotFound {
+        LauncherError::NotFound(format!("Remote backup file {}", key))
+    } else {
+        e.into()
+    }
+}
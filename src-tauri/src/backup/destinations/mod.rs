@@ -0,0 +1,923 @@
+//! Внешние копии бэкапов: папка, SFTP, S3-совместимое хранилище
+//!
+//! Раскладка на стороне хранилища повторяет локальное хранилище чанков, поэтому
+//! повторная выгрузка передаёт только новые чанки:
+//!
+//! ```text
+//! chunks/ab/<blake3>        сжатые чанки
+//! manifests/<backup_id>.json
+//! archives/<backup_id>.tar.zst  старые бэкапы-архивы
+//! records/<backup_id>.json  запись о бэкапе, пишется последней
+//! ```
+//!
+//! Бэкап без `records/<id>.json` считается недогруженным и в списке не показывается.
+//! Пароли и ключи хранятся в `SecureVault`, в БД — только параметры подключения.
+
+mod directory;
+mod s3;
+mod sftp;
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+
+use super::store::{is_chunk_hash, ChunkStore, Manifest};
+use super::{BackupManager, BackupRecord, BackupStorage, STORE_LOCK};
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::paths::backup_store_dir;
+use crate::secrets::{SecretError, SecureVault};
+use directory::DirectoryRemote;
+use s3::S3Remote;
+use sftp::SftpRemote;
+
+/// Куда выгружать бэкапы
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum DestinationConfig {
+    /// Папка на другом диске, NAS или синхронизируемая облаком директория
+    Directory { path: String },
+    /// SFTP. Секрет — пароль или парольная фраза ключа; без них используется ssh-agent.
+    Sftp {
+        host: String,
+        #[serde(default = "default_ssh_port")]
+        port: u16,
+        username: String,
+        #[serde(default)]
+        remote_dir: String,
+        #[serde(default)]
+        private_key_path: Option<String>,
+        /// SHA-256 ключа хоста (hex). Запоминается при первом подключении.
+        #[serde(default)]
+        host_key_sha256: Option<String>,
+    },
+    /// S3-совместимое хранилище (AWS, MinIO, Backblaze B2, ...). Секрет — secret access key.
+    S3 {
+        endpoint: String,
+        #[serde(default = "default_region")]
+        region: String,
+        bucket: String,
+        #[serde(default)]
+        prefix: String,
+        access_key_id: String,
+        /// `endpoint/bucket/key` вместо `bucket.endpoint/key` (MinIO и большинство self-hosted)
+        #[serde(default = "default_path_style")]
+        path_style: bool,
+    },
+}
+
+fn default_ssh_port() -> u16 {
+    22
+}
+
+fn default_region() -> String {
+    "us-east-1".to_string()
+}
+
+fn default_path_style() -> bool {
+    true
+}
+
+impl DestinationConfig {
+    pub fn validate(&self) -> Result<()> {
+        let invalid = |msg: &str| Err(LauncherError::InvalidConfig(msg.to_string()));
+        match self {
+            DestinationConfig::Directory { path } => {
+                if !Path::new(path).is_absolute() {
+                    return invalid("Destination folder must be an absolute path");
+                }
+            }
+            DestinationConfig::Sftp { host, username, .. } => {
+                if host.trim().is_empty() || username.trim().is_empty() {
+                    return invalid("SFTP host and username are required");
+                }
+            }
+            DestinationConfig::S3 {
+                endpoint,
+                bucket,
+                access_key_id,
+                ..
+            } => {
+                let url = reqwest::Url::parse(endpoint).map_err(|e| {
+                    LauncherError::InvalidConfig(format!("Invalid S3 endpoint: {}", e))
+                })?;
+                if !matches!(url.scheme(), "http" | "https") {
+                    return invalid("S3 endpoint must be an http(s) URL");
+                }
+                if bucket.trim().is_empty() || access_key_id.trim().is_empty() {
+                    return invalid("S3 bucket and access key are required");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Настроенное место выгрузки бэкапов
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupDestination {
+    pub id: String,
+    pub name: String,
+    pub config: DestinationConfig,
+    pub enabled: bool,
+    /// Выгружать каждый новый бэкап автоматически
+    pub auto_upload: bool,
+    /// Сохранён ли пароль/ключ в SecureVault
+    pub has_secret: bool,
+    pub last_upload_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+/// Параметры создания/изменения места выгрузки
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackupDestinationInput {
+    pub name: String,
+    pub config: DestinationConfig,
+    /// Новый секрет. None — оставить сохранённый, пустая строка — удалить.
+    #[serde(default)]
+    pub secret: Option<String>,
+    #[serde(default = "default_true")]
+    pub enabled: bool,
+    #[serde(default = "default_true")]
+    pub auto_upload: bool,
+}
+
+fn default_true() -> bool {
+    true
+}
+
+/// Результат выгрузки бэкапа
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UploadReport {
+    pub backup_id: String,
+    pub uploaded_chunks: u32,
+    /// Чанки, которые уже были в хранилище
+    pub skipped_chunks: u32,
+    pub uploaded_bytes: u64,
+}
+
+/// Подключение к хранилищу. Ключи — пути с `/` относительно корня хранилища.
+enum Remote {
+    Directory(DirectoryRemote),
+    Sftp(SftpRemote),
+    S3(S3Remote),
+}
+
+impl Remote {
+    async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        match self {
+            Remote::Directory(r) => r.put(key, data).await,
+            Remote::Sftp(r) => r.put(key, data).await,
+            Remote::S3(r) => r.put(key, data).await,
+        }
+    }
+
+    async fn put_file(&self, key: &str, path: &Path) -> Result<()> {
+        match self {
+            Remote::Directory(r) => r.put_file(key, path).await,
+            Remote::Sftp(r) => r.put_file(key, path).await,
+            Remote::S3(r) => r.put_file(key, path).await,
+        }
+    }
+
+    async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        match self {
+            Remote::Directory(r) => r.get(key).await,
+            Remote::Sftp(r) => r.get(key).await,
+            Remote::S3(r) => r.get(key).await,
+        }
+    }
+
+    async fn get_file(&self, key: &str, path: &Path) -> Result<()> {
+        match self {
+            Remote::Directory(r) => r.get_file(key, path).await,
+            Remote::Sftp(r) => r.get_file(key, path).await,
+            Remote::S3(r) => r.get_file(key, path).await,
+        }
+    }
+
+    /// Все ключи под префиксом (рекурсивно)
+    async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        match self {
+            Remote::Directory(r) => r.list(prefix).await,
+            Remote::Sftp(r) => r.list(prefix).await,
+            Remote::S3(r) => r.list(prefix).await,
+        }
+    }
+
+    async fn delete(&self, key: &str) -> Result<()> {
+        match self {
+            Remote::Directory(r) => r.delete(key).await,
+            Remote::Sftp(r) => r.delete(key).await,
+            Remote::S3(r) => r.delete(key).await,
+        }
+    }
+}
+
+fn record_key(backup_id: &str) -> String {
+    format!("records/{}.json", backup_id)
+}
+
+fn manifest_key(backup_id: &str) -> String {
+    format!("manifests/{}.json", backup_id)
+}
+
+fn archive_key(backup_id: &str) -> String {
+    format!("archives/{}.tar.zst", backup_id)
+}
+
+fn chunk_key(hash: &str) -> String {
+    format!("chunks/{}/{}", &hash[..2], hash)
+}
+
+/// ID бэкапа используется в именах файлов — только `[A-Za-z0-9_-]`
+fn validate_backup_id(backup_id: &str) -> Result<()> {
+    let valid = !backup_id.is_empty()
+        && backup_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(LauncherError::InvalidConfig(format!(
+            "Invalid backup id: {}",
+            backup_id
+        )))
+    }
+}
+
+/// Менеджер мест выгрузки
+pub struct DestinationManager;
+
+impl DestinationManager {
+    /// Создать таблицу мест выгрузки если не существует
+    fn ensure_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            r#"CREATE TABLE IF NOT EXISTS backup_destinations (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                config_json TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                auto_upload INTEGER NOT NULL DEFAULT 1,
+                last_upload_at TEXT,
+                last_error TEXT,
+                created_at TEXT NOT NULL
+            )"#,
+            [],
+        )?;
+        Ok(())
+    }
+
+    pub fn list() -> Result<Vec<BackupDestination>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, name, config_json, enabled, auto_upload, last_upload_at, last_error,
+                      created_at
+               FROM backup_destinations ORDER BY created_at"#,
+        )?;
+
+        let destinations = stmt
+            .query_map([], row_to_destination)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(destinations)
+    }
+
+    pub fn get(destination_id: &str) -> Result<BackupDestination> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        conn.query_row(
+            r#"SELECT id, name, config_json, enabled, auto_upload, last_upload_at, last_error,
+                      created_at
+               FROM backup_destinations WHERE id = ?1"#,
+            [destination_id],
+            row_to_destination,
+        )
+        .optional()?
+        .ok_or_else(|| LauncherError::NotFound(format!("Backup destination {}", destination_id)))
+    }
+
+    pub fn create(input: BackupDestinationInput) -> Result<BackupDestination> {
+        input.config.validate()?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        {
+            let conn = get_db_conn()?;
+            Self::ensure_table(&conn)?;
+            conn.execute(
+                r#"INSERT INTO backup_destinations
+                    (id, name, config_json, enabled, auto_upload, created_at)
+                    VALUES (?1, ?2, ?3, ?4, ?5, ?6)"#,
+                params![
+                    id,
+                    input.name,
+                    serde_json::to_string(&input.config)?,
+                    input.enabled,
+                    input.auto_upload,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+        }
+        Self::store_secret(&id, input.secret.as_deref())?;
+
+        Self::get(&id)
+    }
+
+    pub fn update(
+        destination_id: &str,
+        input: BackupDestinationInput,
+    ) -> Result<BackupDestination> {
+        input.config.validate()?;
+
+        {
+            let conn = get_db_conn()?;
+            Self::ensure_table(&conn)?;
+            let updated = conn.execute(
+                r#"UPDATE backup_destinations
+                   SET name = ?2, config_json = ?3, enabled = ?4, auto_upload = ?5
+                   WHERE id = ?1"#,
+                params![
+                    destination_id,
+                    input.name,
+                    serde_json::to_string(&input.config)?,
+                    input.enabled,
+                    input.auto_upload,
+                ],
+            )?;
+            if updated == 0 {
+                return Err(LauncherError::NotFound(format!(
+                    "Backup destination {}",
+                    destination_id
+                )));
+            }
+        }
+        Self::store_secret(destination_id, input.secret.as_deref())?;
+
+        Self::get(destination_id)
+    }
+
+    /// Удалить место выгрузки. Уже выгруженные бэкапы в хранилище остаются.
+    pub fn delete(destination_id: &str) -> Result<()> {
+        {
+            let conn = get_db_conn()?;
+            Self::ensure_table(&conn)?;
+            conn.execute(
+                "DELETE FROM backup_destinations WHERE id = ?1",
+                [destination_id],
+            )?;
+        }
+        Self::store_secret(destination_id, Some(""))
+    }
+
+    fn store_secret(destination_id: &str, secret: Option<&str>) -> Result<()> {
+        match secret {
+            None => {}
+            Some("") => SecureVault::delete_backup_destination_secret(destination_id)?,
+            Some(secret) => SecureVault::store_backup_destination_secret(destination_id, secret)?,
+        }
+        Ok(())
+    }
+
+    fn load_secret(destination_id: &str) -> Result<Option<String>> {
+        match SecureVault::get_backup_destination_secret(destination_id) {
+            Ok(secret) => Ok(Some(secret)),
+            Err(SecretError::NotFound(_)) => Ok(None),
+            Err(e) => Err(e.into()),
+        }
+    }
+
+    async fn connect(destination: &BackupDestination) -> Result<Remote> {
+        let secret = Self::load_secret(&destination.id)?;
+
+        match &destination.config {
+            DestinationConfig::Directory { path } => {
+                Ok(Remote::Directory(DirectoryRemote::new(PathBuf::from(path))))
+            }
+            DestinationConfig::Sftp {
+                host,
+                port,
+                username,
+                remote_dir,
+                private_key_path,
+                host_key_sha256,
+            } => {
+                let (remote, host_key) = SftpRemote::connect(
+                    host,
+                    *port,
+                    username,
+                    remote_dir,
+                    private_key_path.as_deref(),
+                    secret,
+                    host_key_sha256.as_deref(),
+                )
+                .await?;
+
+                // Trust on first use: запоминаем ключ хоста
+                if host_key_sha256.is_none() {
+                    Self::pin_host_key(destination, &host_key)?;
+                }
+                Ok(Remote::Sftp(remote))
+            }
+            DestinationConfig::S3 {
+                endpoint,
+                region,
+                bucket,
+                prefix,
+                access_key_id,
+                path_style,
+            } => {
+                let secret_key = secret.ok_or_else(|| {
+                    LauncherError::InvalidConfig("S3 secret access key is not set".to_string())
+                })?;
+                Ok(Remote::S3(S3Remote::new(
+                    endpoint,
+                    region,
+                    bucket,
+                    prefix,
+                    access_key_id,
+                    &secret_key,
+                    *path_style,
+                )?))
+            }
+        }
+    }
+
+    fn pin_host_key(destination: &BackupDestination, host_key: &str) -> Result<()> {
+        let mut config = destination.config.clone();
+        if let DestinationConfig::Sftp {
+            host_key_sha256, ..
+        } = &mut config
+        {
+            *host_key_sha256 = Some(host_key.to_string());
+        }
+
+        let conn = get_db_conn()?;
+        conn.execute(
+            "UPDATE backup_destinations SET config_json = ?2 WHERE id = ?1",
+            params![destination.id, serde_json::to_string(&config)?],
+        )?;
+        log::info!(
+            "Pinned SFTP host key for destination {}: {}",
+            destination.id,
+            host_key
+        );
+        Ok(())
+    }
+
+    fn store_upload_state(destination_id: &str, error: Option<String>) -> Result<()> {
+        let conn = get_db_conn()?;
+        conn.execute(
+            r#"UPDATE backup_destinations
+               SET last_upload_at = CASE WHEN ?2 IS NULL THEN ?3 ELSE last_upload_at END,
+                   last_error = ?2
+               WHERE id = ?1"#,
+            params![destination_id, error, Utc::now().to_rfc3339()],
+        )?;
+        Ok(())
+    }
+
+    /// Проверить подключение: записать, прочитать и удалить пробный файл
+    pub async fn test(destination_id: &str) -> Result<()> {
+        let destination = Self::get(destination_id)?;
+        let remote = Self::connect(&destination).await?;
+
+        let key = format!(".stuzhik-probe-{}", uuid::Uuid::new_v4());
+        let probe = b"stuzhik".to_vec();
+        remote.put(&key, probe.clone()).await?;
+        let read = remote.get(&key).await;
+        remote.delete(&key).await?;
+
+        if read? != probe {
+            return Err(LauncherError::RemoteStorage(
+                "Probe file was read back with different content".to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    /// Выгрузить бэкап в место выгрузки
+    pub async fn upload(destination_id: &str, backup_id: &str) -> Result<UploadReport> {
+        let destination = Self::get(destination_id)?;
+        let record = BackupManager::get_backup(backup_id)?;
+
+        let result: Result<UploadReport> = async {
+            let remote = Self::connect(&destination).await?;
+            // GC не должен удалить чанки, пока они выгружаются
+            let _lock = STORE_LOCK.read().await;
+            upload_record(&remote, &ChunkStore::new(backup_store_dir()), &record).await
+        }
+        .await;
+
+        Self::store_upload_state(destination_id, result.as_ref().err().map(|e| e.to_string()))?;
+        let report = result?;
+
+        log::info!(
+            "Uploaded backup {} to {}: {} chunks ({} bytes), {} already present",
+            backup_id,
+            destination.name,
+            report.uploaded_chunks,
+            report.uploaded_bytes,
+            report.skipped_chunks
+        );
+        Ok(report)
+    }
+
+    /// Выгрузить новый бэкап во все места с автовыгрузкой (в фоне)
+    pub fn upload_in_background(record: &BackupRecord) {
+        let destinations: Vec<BackupDestination> = match Self::list() {
+            Ok(list) => list
+                .into_iter()
+                .filter(|d| d.enabled && d.auto_upload)
+                .collect(),
+            Err(e) => {
+                log::warn!("Failed to load backup destinations: {}", e);
+                return;
+            }
+        };
+        if destinations.is_empty() {
+            return;
+        }
+
+        let backup_id = record.id.clone();
+        tauri::async_runtime::spawn(async move {
+            for destination in destinations {
+                if let Err(e) = Self::upload(&destination.id, &backup_id).await {
+                    log::warn!(
+                        "Failed to upload backup {} to {}: {}",
+                        backup_id,
+                        destination.name,
+                        e
+                    );
+                }
+            }
+        });
+    }
+
+    /// Бэкапы в месте выгрузки, новые первыми
+    pub async fn list_remote(destination_id: &str) -> Result<Vec<BackupRecord>> {
+        let destination = Self::get(destination_id)?;
+        let remote = Self::connect(&destination).await?;
+
+        let mut records = Vec::new();
+        for key in remote.list("records/").await? {
+            if !key.ends_with(".json") {
+                continue;
+            }
+            match serde_json::from_slice::<BackupRecord>(&remote.get(&key).await?) {
+                Ok(record) => records.push(record),
+                Err(e) => log::warn!("Skipping unreadable remote record {}: {}", key, e),
+            }
+        }
+
+        records.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+        Ok(records)
+    }
+
+    /// Восстановить бэкап из места выгрузки. Бэкап скачивается в локальное хранилище
+    /// и регистрируется как обычный, затем восстанавливается. `target_instance_id`
+    /// позволяет восстановить в другой экземпляр (например, после переустановки).
+    pub async fn restore_remote(
+        destination_id: &str,
+        backup_id: &str,
+        target_instance_id: Option<&str>,
+    ) -> Result<BackupRecord> {
+        validate_backup_id(backup_id)?;
+
+        // Бэкап ещё есть локально — скачивать нечего
+        if let Ok(local) = BackupManager::get_backup(backup_id) {
+            let same_target = target_instance_id.is_none_or(|t| t == local.instance_id);
+            if same_target && Path::new(&local.path).exists() {
+                BackupManager::restore_backup(backup_id).await?;
+                return Ok(local);
+            }
+            return Err(LauncherError::InvalidConfig(format!(
+                "Backup {} already exists locally for instance {}",
+                backup_id, local.instance_id
+            )));
+        }
+
+        let destination = Self::get(destination_id)?;
+        let remote = Self::connect(&destination).await?;
+        let remote_record: BackupRecord =
+            serde_json::from_slice(&remote.get(&record_key(backup_id)).await?)?;
+
+        let instance_id = target_instance_id
+            .map(str::to_string)
+            .unwrap_or_else(|| remote_record.instance_id.clone());
+        let instance_exists = {
+            let conn = get_db_conn()?;
+            conn.query_row(
+                "SELECT 1 FROM instances WHERE id = ?1",
+                [&instance_id],
+                |_| Ok(()),
+            )
+            .optional()?
+            .is_some()
+        };
+        if !instance_exists {
+            return Err(LauncherError::InstanceNotFound(instance_id));
+        }
+
+        let store_lock = STORE_LOCK.read().await;
+        let store_root = backup_store_dir();
+        let path = fetch_backup(
+            &remote,
+            &ChunkStore::new(&store_root),
+            &store_root.join("archives"),
+            &remote_record,
+        )
+        .await?;
+
+        let record = BackupRecord {
+            instance_id,
+            path: path.to_string_lossy().to_string(),
+            ..remote_record
+        };
+        BackupManager::save_backup_record(&record)?;
+        drop(store_lock);
+
+        log::info!(
+            "Downloaded backup {} from {} for instance {}",
+            backup_id,
+            destination.name,
+            record.instance_id
+        );
+
+        BackupManager::restore_backup(backup_id).await?;
+        Ok(record)
+    }
+}
+
+/// Выгрузить файлы бэкапа и его запись. Запись пишется последней.
+async fn upload_record(
+    remote: &Remote,
+    store: &ChunkStore,
+    record: &BackupRecord,
+) -> Result<UploadReport> {
+    validate_backup_id(&record.id)?;
+    let path = PathBuf::from(&record.path);
+    let mut report = UploadReport {
+        backup_id: record.id.clone(),
+        ..Default::default()
+    };
+
+    match record.storage {
+        BackupStorage::Chunked => {
+            let remote_chunks: HashSet<String> = remote
+                .list("chunks/")
+                .await?
+                .into_iter()
+                .filter_map(|key| key.rsplit('/').next().map(str::to_string))
+                .collect();
+
+            let manifest_path = path.clone();
+            let manifest =
+                tokio::task::spawn_blocking(move || ChunkStore::read_manifest(&manifest_path))
+                    .await??;
+
+            let mut seen = HashSet::new();
+            for hash in manifest.files.iter().flat_map(|f| &f.chunks) {
+                if !seen.insert(hash.as_str()) {
+                    continue;
+                }
+                if remote_chunks.contains(hash) {
+                    report.skipped_chunks += 1;
+                    continue;
+                }
+                let data = tokio::fs::read(store.chunk_path(hash)).await?;
+                report.uploaded_bytes += data.len() as u64;
+                remote.put(&chunk_key(hash), data).await?;
+                report.uploaded_chunks += 1;
+            }
+
+            remote.put_file(&manifest_key(&record.id), &path).await?;
+        }
+        BackupStorage::Archive => {
+            report.uploaded_bytes += tokio::fs::metadata(&path).await?.len();
+            remote.put_file(&archive_key(&record.id), &path).await?;
+        }
+    }
+
+    remote
+        .put(&record_key(&record.id), serde_json::to_vec(record)?)
+        .await?;
+    Ok(report)
+}
+
+/// Скачать бэкап в локальное хранилище. Возвращает локальный путь манифеста/архива.
+async fn fetch_backup(
+    remote: &Remote,
+    store: &ChunkStore,
+    archives_dir: &Path,
+    record: &BackupRecord,
+) -> Result<PathBuf> {
+    validate_backup_id(&record.id)?;
+
+    match record.storage {
+        BackupStorage::Chunked => {
+            let manifest: Manifest =
+                serde_json::from_slice(&remote.get(&manifest_key(&record.id)).await?)?;
+            if manifest.backup_id != record.id {
+                return Err(LauncherError::RemoteStorage(format!(
+                    "Manifest of {} belongs to backup {}",
+                    record.id, manifest.backup_id
+                )));
+            }
+
+            let mut seen = HashSet::new();
+            for hash in manifest.files.iter().flat_map(|f| &f.chunks) {
+                if !is_chunk_hash(hash) {
+                    return Err(LauncherError::RemoteStorage(format!(
+                        "Invalid chunk hash in manifest of {}",
+                        record.id
+                    )));
+                }
+                if !seen.insert(hash.as_str()) || store.has_chunk(hash) {
+                    continue;
+                }
+                let data = remote.get(&chunk_key(hash)).await?;
+                store.import_chunk(hash, &data)?;
+            }
+
+            // Манифест пишем после чанков: он проверяется при чтении версии.
+            // Локальной записи нет — оставшийся манифест осиротел и ждёт GC.
+            let orphan = store.manifest_path(&record.id);
+            if orphan.exists() {
+                std::fs::remove_file(&orphan)?;
+            }
+            let path = store.write_manifest(&manifest)?;
+            ChunkStore::read_manifest(&path)?;
+            Ok(path)
+        }
+        BackupStorage::Archive => {
+            tokio::fs::create_dir_all(archives_dir).await?;
+            let path = archives_dir.join(format!("{}.tar.zst", record.id));
+            remote.get_file(&archive_key(&record.id), &path).await?;
+            Ok(path)
+        }
+    }
+}
+
+fn row_to_destination(row: &rusqlite::Row) -> rusqlite::Result<BackupDestination> {
+    let id: String = row.get(0)?;
+    let config_json: String = row.get(2)?;
+    let config = serde_json::from_str(&config_json).map_err(|e| {
+        rusqlite::Error::FromSqlConversionFailure(2, rusqlite::types::Type::Text, Box::new(e))
+    })?;
+
+    Ok(BackupDestination {
+        has_secret: SecureVault::exists(&SecureVault::backup_destination_secret_key(&id)),
+        id,
+        name: row.get(1)?,
+        config,
+        enabled: row.get::<_, i32>(3)? != 0,
+        auto_upload: row.get::<_, i32>(4)? != 0,
+        last_upload_at: row.get(5)?,
+        last_error: row.get(6)?,
+        created_at: row.get(7)?,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_backup_destinations() -> Result<Vec<BackupDestination>> {
+    DestinationManager::list()
+}
+
+#[tauri::command]
+pub async fn create_backup_destination(input: BackupDestinationInput) -> Result<BackupDestination> {
+    DestinationManager::create(input)
+}
+
+#[tauri::command]
+pub async fn update_backup_destination(
+    destination_id: String,
+    input: BackupDestinationInput,
+) -> Result<BackupDestination> {
+    DestinationManager::update(&destination_id, input)
+}
+
+#[tauri::command]
+pub async fn delete_backup_destination(destination_id: String) -> Result<()> {
+    DestinationManager::delete(&destination_id)
+}
+
+#[tauri::command]
+pub async fn test_backup_destination(destination_id: String) -> Result<()> {
+    DestinationManager::test(&destination_id).await
+}
+
+#[tauri::command]
+pub async fn upload_backup(destination_id: String, backup_id: String) -> Result<UploadReport> {
+    DestinationManager::upload(&destination_id, &backup_id).await
+}
+
+#[tauri::command]
+pub async fn list_remote_backups(destination_id: String) -> Result<Vec<BackupRecord>> {
+    DestinationManager::list_remote(&destination_id).await
+}
+
+#[tauri::command]
+pub async fn restore_remote_backup(
+    destination_id: String,
+    backup_id: String,
+    target_instance_id: Option<String>,
+) -> Result<BackupRecord> {
+    DestinationManager::restore_remote(&destination_id, &backup_id, target_instance_id.as_deref())
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::BackupTrigger;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("stuzhik_{}_{}", name, uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn chunked_backup(store: &ChunkStore, instance: &Path, id: &str) -> BackupRecord {
+        let files: Vec<PathBuf> = ["mods/a.jar", "config/a.toml"]
+            .iter()
+            .map(|p| instance.join(p))
+            .collect();
+        let (entries, _) = store.store_files(instance, &files, None).unwrap();
+        let manifest = Manifest::new(id, "inst", "2024-06-01T00:00:00Z", entries);
+        let path = store.write_manifest(&manifest).unwrap();
+
+        BackupRecord {
+            id: id.to_string(),
+            instance_id: "inst".to_string(),
+            trigger: BackupTrigger::Manual,
+            created_at: "2024-06-01T00:00:00Z".to_string(),
+            size_bytes: manifest.total_size(),
+            includes_saves: false,
+            file_count: 2,
+            path: path.to_string_lossy().to_string(),
+            world: None,
+            schedule_id: None,
+            storage: BackupStorage::Chunked,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upload_is_incremental_and_fetch_restores() {
+        let instance = temp_dir("instance");
+        for (path, data) in [("mods/a.jar", "jar"), ("config/a.toml", "cfg")] {
+            let path = instance.join(path);
+            std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+            std::fs::write(path, data).unwrap();
+        }
+        let local = temp_dir("local");
+        let store = ChunkStore::new(local.join("store"));
+        let remote_dir = temp_dir("remote");
+        let remote = Remote::Directory(DirectoryRemote::new(remote_dir.clone()));
+
+        let first = chunked_backup(&store, &instance, "b1");
+        let report = upload_record(&remote, &store, &first).await.unwrap();
+        assert_eq!((report.uploaded_chunks, report.skipped_chunks), (2, 0));
+
+        // Изменился только конфиг — выгружается один чанк
+        std::fs::write(instance.join("config/a.toml"), "cfg v2").unwrap();
+        let second = chunked_backup(&store, &instance, "b2");
+        let report = upload_record(&remote, &store, &second).await.unwrap();
+        assert_eq!((report.uploaded_chunks, report.skipped_chunks), (1, 1));
+
+        // Скачиваем в пустое хранилище и восстанавливаем
+        let other = temp_dir("other");
+        let other_store = ChunkStore::new(other.join("store"));
+        let path = fetch_backup(&remote, &other_store, &other.join("archives"), &second)
+            .await
+            .unwrap();
+        let target = other.join("instance");
+        let manifest = ChunkStore::read_manifest(&path).unwrap();
+        other_store.restore(&manifest, &target, |_| true).unwrap();
+        assert_eq!(
+            std::fs::read_to_string(target.join("config/a.toml")).unwrap(),
+            "cfg v2"
+        );
+
+        for dir in [instance, local, remote_dir, other] {
+            let _ = std::fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn test_validate_backup_id() {
+        assert!(validate_backup_id("world_20240601_120000_abc123_New_World").is_ok());
+        assert!(validate_backup_id("../records/x").is_err());
+        assert!(validate_backup_id("").is_err());
+    }
+}
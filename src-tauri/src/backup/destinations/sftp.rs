@@ -0,0 +1,312 @@
+//! Выгрузка на SFTP
+//!
+//! libssh2 синхронный, поэтому все операции идут через `spawn_blocking`
+//! по одной сессии под мьютексом.
+
+use ssh2::{ErrorCode, HashType, Session, Sftp};
+use std::collections::HashSet;
+use std::io::{Read, Write};
+use std::net::{TcpStream, ToSocketAddrs};
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use crate::error::{LauncherError, Result};
+
+const CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+/// Таймаут операций libssh2 (мс)
+const SESSION_TIMEOUT_MS: u32 = 60_000;
+/// LIBSSH2_FX_NO_SUCH_FILE
+const FX_NO_SUCH_FILE: i32 = 2;
+
+struct Connection {
+    // Сессия должна жить, пока жив канал SFTP
+    _session: Session,
+    sftp: Sftp,
+    /// Уже существующие директории, чтобы не проверять их на каждый чанк
+    known_dirs: HashSet<String>,
+}
+
+pub struct SftpRemote {
+    connection: Arc<Mutex<Connection>>,
+    root: String,
+}
+
+impl SftpRemote {
+    /// Подключиться и проверить ключ хоста. Возвращает SHA-256 ключа хоста (hex).
+    pub async fn connect(
+        host: &str,
+        port: u16,
+        username: &str,
+        remote_dir: &str,
+        private_key_path: Option<&str>,
+        secret: Option<String>,
+        expected_host_key: Option<&str>,
+    ) -> Result<(Self, String)> {
+        let host = host.to_string();
+        let username = username.to_string();
+        let private_key_path = private_key_path.map(str::to_string);
+        let expected_host_key = expected_host_key.map(str::to_lowercase);
+        let root = remote_dir.trim_end_matches('/').to_string();
+
+        tokio::task::spawn_blocking(move || {
+            let tcp = connect_tcp(&host, port)?;
+            let mut session = Session::new().map_err(ssh_error)?;
+            session.set_tcp_stream(tcp);
+            session.set_timeout(SESSION_TIMEOUT_MS);
+            session.handshake().map_err(ssh_error)?;
+
+            let host_key = session
+                .host_key_hash(HashType::Sha256)
+                .map(hex::encode)
+                .ok_or_else(|| {
+                    LauncherError::RemoteStorage("SFTP server sent no host key".to_string())
+                })?;
+            if let Some(expected) = expected_host_key {
+                if expected != host_key {
+                    return Err(LauncherError::RemoteStorage(format!(
+                        "SFTP host key changed: expected {}, got {}. Possible MITM attack",
+                        expected, host_key
+                    )));
+                }
+            }
+
+            let auth = match (&private_key_path, &secret) {
+                (Some(key), passphrase) => session.userauth_pubkey_file(
+                    &username,
+                    None,
+                    Path::new(key),
+                    passphrase.as_deref(),
+                ),
+                (None, Some(password)) => session.userauth_password(&username, password),
+                (None, None) => session.userauth_agent(&username),
+            };
+            if let Err(e) = auth {
+                return Err(LauncherError::AuthFailed(format!("SFTP: {}", e)));
+            }
+            if !session.authenticated() {
+                return Err(LauncherError::AuthFailed(
+                    "SFTP server rejected credentials".to_string(),
+                ));
+            }
+
+            let sftp = session.sftp().map_err(ssh_error)?;
+            let remote = Self {
+                connection: Arc::new(Mutex::new(Connection {
+                    _session: session,
+                    sftp,
+                    known_dirs: HashSet::new(),
+                })),
+                root,
+            };
+            Ok((remote, host_key))
+        })
+        .await?
+    }
+
+    fn path(&self, key: &str) -> String {
+        if self.root.is_empty() {
+            key.to_string()
+        } else {
+            format!("{}/{}", self.root, key)
+        }
+    }
+
+    /// Выполнить операцию с SFTP в пуле блокирующих потоков
+    async fn with_connection<T: Send + 'static>(
+        &self,
+        task: impl FnOnce(&mut Connection) -> Result<T> + Send + 'static,
+    ) -> Result<T> {
+        let connection = self.connection.clone();
+        tokio::task::spawn_blocking(move || {
+            let mut connection = connection.lock().map_err(|_| {
+                LauncherError::RemoteStorage("SFTP connection is poisoned".to_string())
+            })?;
+            task(&mut connection)
+        })
+        .await?
+    }
+
+    pub async fn put(&self, key: &str, data: Vec<u8>) -> Result<()> {
+        let path = self.path(key);
+        self.with_connection(move |conn| upload(conn, &path, &mut data.as_slice()))
+            .await
+    }
+
+    pub async fn put_file(&self, key: &str, source: &Path) -> Result<()> {
+        let path = self.path(key);
+        let source = source.to_owned();
+        self.with_connection(move |conn| {
+            let mut file = std::fs::File::open(&source)?;
+            upload(conn, &path, &mut file)
+        })
+        .await
+    }
+
+    pub async fn get(&self, key: &str) -> Result<Vec<u8>> {
+        let path = self.path(key);
+        self.with_connection(move |conn| {
+            let mut file = conn
+                .sftp
+                .open(Path::new(&path))
+                .map_err(|e| not_found(e, &path))?;
+            let mut data = Vec::new();
+            file.read_to_end(&mut data)?;
+            Ok(data)
+        })
+        .await
+    }
+
+    pub async fn get_file(&self, key: &str, target: &Path) -> Result<()> {
+        let path = self.path(key);
+        let target = target.to_owned();
+        self.with_connection(move |conn| {
+            let mut remote = conn
+                .sftp
+                .open(Path::new(&path))
+                .map_err(|e| not_found(e, &path))?;
+            let tmp = target.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+            let copied = std::fs::File::create(&tmp)
+                .and_then(|mut local| std::io::copy(&mut remote, &mut local))
+                .and_then(|_| std::fs::rename(&tmp, &target));
+            if let Err(e) = copied {
+                let _ = std::fs::remove_file(&tmp);
+                return Err(e.into());
+            }
+            Ok(())
+        })
+        .await
+    }
+
+    pub async fn list(&self, prefix: &str) -> Result<Vec<String>> {
+        let prefix = prefix.trim_end_matches('/').to_string();
+        let dir = self.path(&prefix);
+        self.with_connection(move |conn| {
+            let mut keys = Vec::new();
+            // (путь на сервере, ключ)
+            let mut pending = vec![(dir, prefix)];
+            while let Some((dir, key)) = pending.pop() {
+                let entries = match conn.sftp.readdir(Path::new(&dir)) {
+                    Ok(entries) => entries,
+                    Err(e) if is_no_such_file(&e) => continue,
+                    Err(e) => return Err(ssh_error(e)),
+                };
+                for (path, stat) in entries {
+                    // readdir склеивает путь через Path::join — берём только имя
+                    let Some(name) = path.file_name().map(|n| n.to_string_lossy().to_string())
+                    else {
+                        continue;
+                    };
+                    if name == "." || name == ".." || name.contains(".tmp-") {
+                        continue;
+                    }
+                    let child_key = format!("{}/{}", key, name);
+                    if stat.is_dir() {
+                        pending.push((format!("{}/{}", dir, name), child_key));
+                    } else {
+                        keys.push(child_key);
+                    }
+                }
+            }
+            Ok(keys)
+        })
+        .await
+    }
+
+    pub async fn delete(&self, key: &str) -> Result<()> {
+        let path = self.path(key);
+        self.with_connection(move |conn| match conn.sftp.unlink(Path::new(&path)) {
+            Ok(()) => Ok(()),
+            Err(e) if is_no_such_file(&e) => Ok(()),
+            Err(e) => Err(ssh_error(e)),
+        })
+        .await
+    }
+}
+
+/// Записать во временный файл и переименовать, чтобы не оставить обрезанный файл
+fn upload(conn: &mut Connection, path: &str, source: &mut impl Read) -> Result<()> {
+    if let Some((parent, _)) = path.rsplit_once('/') {
+        ensure_dir(conn, parent)?;
+    }
+
+    let tmp = format!("{}.tmp-{}", path, uuid::Uuid::new_v4());
+    let written = conn
+        .sftp
+        .create(Path::new(&tmp))
+        .map_err(ssh_error)
+        .and_then(|mut file| {
+            std::io::copy(source, &mut file)?;
+            file.flush()?;
+            Ok(())
+        });
+    if let Err(e) = written {
+        let _ = conn.sftp.unlink(Path::new(&tmp));
+        return Err(e);
+    }
+
+    // SFTPv3 rename не перезаписывает существующий файл
+    if conn
+        .sftp
+        .rename(Path::new(&tmp), Path::new(path), None)
+        .is_err()
+    {
+        let _ = conn.sftp.unlink(Path::new(path));
+        if let Err(e) = conn.sftp.rename(Path::new(&tmp), Path::new(path), None) {
+            let _ = conn.sftp.unlink(Path::new(&tmp));
+            return Err(ssh_error(e));
+        }
+    }
+    Ok(())
+}
+
+/// `mkdir -p`
+fn ensure_dir(conn: &mut Connection, dir: &str) -> Result<()> {
+    if dir.is_empty() || conn.known_dirs.contains(dir) {
+        return Ok(());
+    }
+    if let Some((parent, _)) = dir.rsplit_once('/') {
+        ensure_dir(conn, parent)?;
+    }
+
+    let exists = conn
+        .sftp
+        .stat(Path::new(dir))
+        .map(|stat| stat.is_dir())
+        .unwrap_or(false);
+    if !exists {
+        conn.sftp.mkdir(Path::new(dir), 0o755).map_err(ssh_error)?;
+    }
+    conn.known_dirs.insert(dir.to_string());
+    Ok(())
+}
+
+fn connect_tcp(host: &str, port: u16) -> Result<TcpStream> {
+    let mut last_error = None;
+    for addr in (host, port).to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, CONNECT_TIMEOUT) {
+            Ok(stream) => return Ok(stream),
+            Err(e) => last_error = Some(e),
+        }
+    }
+    Err(match last_error {
+        Some(e) => e.into(),
+        None => LauncherError::RemoteStorage(format!("Cannot resolve SFTP host {}", host)),
+    })
+}
+
+fn is_no_such_file(e: &ssh2::Error) -> bool {
+    matches!(e.code(), ErrorCode::SFTP(FX_NO_SUCH_FILE))
+}
+
+fn not_found(e: ssh2::Error, path: &str) -> LauncherError {
+    if is_no_such_file(&e) {
+        LauncherError::NotFound(format!("Remote backup file {}", path))
+    } else {
+        ssh_error(e)
+    }
+}
+
+fn ssh_error(e: ssh2::Error) -> LauncherError {
+    LauncherError::RemoteStorage(format!("SFTP: {}", e))
+}
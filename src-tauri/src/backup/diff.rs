@@ -0,0 +1,405 @@
+//! Просмотр содержимого бэкапа и сравнение с текущим состоянием экземпляра
+
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::io::Read;
+use std::path::{Path, PathBuf};
+
+use super::store::{self, ChunkStore};
+use super::{collect_files_recursive, BackupRecord, BackupStorage};
+use crate::error::{LauncherError, Result};
+use crate::launch_tracker::{extract_mod_slug, ModUpdateInfo};
+use crate::paths::instance_dir;
+
+/// Файл внутри бэкапа
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct BackupFileEntry {
+    /// Путь относительно экземпляра, разделитель `/`
+    pub path: String,
+    pub size: u64,
+}
+
+/// Отличия текущего состояния экземпляра от бэкапа
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct BackupDiff {
+    pub backup_id: String,
+    /// Появились после бэкапа
+    pub added: Vec<String>,
+    /// Есть в бэкапе, сейчас отсутствуют
+    pub removed: Vec<String>,
+    /// Содержимое отличается от бэкапа
+    pub modified: Vec<String>,
+    pub unchanged_count: usize,
+    /// Моды (по имени файла в mods/), как в сравнении снимков запусков
+    pub mods_added: Vec<String>,
+    pub mods_removed: Vec<String>,
+    pub mods_updated: Vec<ModUpdateInfo>,
+}
+
+/// Чем сравнивать живой файл с копией в бэкапе
+enum Fingerprint {
+    /// Хэши чанков из манифеста + mtime для быстрого пропуска
+    Chunks { chunks: Vec<String>, modified: i64 },
+    /// blake3 всего файла (посчитан при чтении tar архива)
+    Blake3(String),
+}
+
+struct IndexedFile {
+    size: u64,
+    fingerprint: Fingerprint,
+}
+
+/// Содержимое бэкапа (только пути и размеры)
+pub async fn list_contents(record: &BackupRecord) -> Result<Vec<BackupFileEntry>> {
+    let path = PathBuf::from(&record.path);
+    let storage = record.storage;
+
+    run_blocking(move || match storage {
+        BackupStorage::Chunked => Ok(ChunkStore::read_manifest(&path)?
+            .files
+            .into_iter()
+            .map(|f| BackupFileEntry {
+                path: f.path,
+                size: f.size,
+            })
+            .collect()),
+        BackupStorage::Archive => {
+            let mut entries = Vec::new();
+            for_each_archive_file(&path, |entry_path, size, _| {
+                entries.push(BackupFileEntry {
+                    path: entry_path,
+                    size,
+                });
+                Ok(())
+            })?;
+            Ok(entries)
+        }
+    })
+    .await
+}
+
+/// Сравнить бэкап с текущим состоянием. Сравниваются только области, которые бэкап
+/// покрывает (mods/, config/, миры), остальные файлы экземпляра не учитываются.
+pub async fn diff_with_live(record: &BackupRecord) -> Result<BackupDiff> {
+    let instance_path = instance_dir(&record.instance_id);
+    let index = load_index(record).await?;
+    let roots = backup_roots(record, index.keys().map(String::as_str));
+
+    let mut live_files = Vec::new();
+    for root in &roots {
+        collect_live(&instance_path, root, &mut live_files).await?;
+    }
+
+    let backup_id = record.id.clone();
+    run_blocking(move || {
+        let mut diff = BackupDiff {
+            backup_id,
+            ..Default::default()
+        };
+        let mut seen = HashSet::new();
+
+        for file in &live_files {
+            let Ok(relative) = file.strip_prefix(&instance_path) else {
+                continue;
+            };
+            let relative = store::relative_to_string(relative);
+            match index.get(&relative) {
+                None => diff.added.push(relative.clone()),
+                Some(indexed) => {
+                    if is_modified(file, indexed)? {
+                        diff.modified.push(relative.clone());
+                    } else {
+                        diff.unchanged_count += 1;
+                    }
+                }
+            }
+            seen.insert(relative);
+        }
+        diff.removed = index
+            .keys()
+            .filter(|path| !seen.contains(*path))
+            .cloned()
+            .collect();
+        diff.added.sort();
+        diff.modified.sort();
+
+        let backup_mods = mod_files(index.keys().map(String::as_str));
+        let live_mods = mod_files(seen.iter().map(String::as_str));
+        let (added, removed, updated) = mod_changes(&backup_mods, &live_mods);
+        diff.mods_added = added;
+        diff.mods_removed = removed;
+        diff.mods_updated = updated;
+
+        Ok(diff)
+    })
+    .await
+}
+
+/// Области экземпляра, которые покрывает бэкап
+pub(crate) fn backup_roots<'a>(
+    record: &BackupRecord,
+    paths: impl Iterator<Item = &'a str>,
+) -> Vec<String> {
+    let mut roots: Vec<String> = Vec::new();
+    if record.world.is_none() {
+        roots.push("mods".to_string());
+        roots.push("config".to_string());
+        if record.includes_saves {
+            roots.push("saves".to_string());
+        }
+    }
+
+    for path in paths {
+        let mut parts = path.split('/');
+        let Some(first) = parts.next() else { continue };
+        // Бэкап мира покрывает только saves/<world>, а не все saves/
+        let root = match (first, record.world.is_some(), parts.next()) {
+            ("saves", true, Some(world)) => format!("saves/{}", world),
+            _ => first.to_string(),
+        };
+        if !roots.contains(&root) {
+            roots.push(root);
+        }
+    }
+
+    roots
+}
+
+/// Собрать файлы по относительному пути (файл или директория)
+pub(crate) async fn collect_live(
+    instance_path: &Path,
+    relative: &str,
+    files: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let path = store::safe_join(instance_path, relative)?;
+    match tokio::fs::metadata(&path).await {
+        Ok(metadata) if metadata.is_dir() => collect_files_recursive(&path, files).await,
+        Ok(_) => {
+            files.push(path);
+            Ok(())
+        }
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn load_index(record: &BackupRecord) -> Result<BTreeMap<String, IndexedFile>> {
+    let path = PathBuf::from(&record.path);
+    let storage = record.storage;
+
+    run_blocking(move || {
+        let mut index = BTreeMap::new();
+        match storage {
+            BackupStorage::Chunked => {
+                for file in ChunkStore::read_manifest(&path)?.files {
+                    index.insert(
+                        file.path,
+                        IndexedFile {
+                            size: file.size,
+                            fingerprint: Fingerprint::Chunks {
+                                chunks: file.chunks,
+                                modified: file.modified,
+                            },
+                        },
+                    );
+                }
+            }
+            BackupStorage::Archive => {
+                for_each_archive_file(&path, |entry_path, size, reader| {
+                    let mut hasher = blake3::Hasher::new();
+                    std::io::copy(reader, &mut hasher)?;
+                    index.insert(
+                        entry_path,
+                        IndexedFile {
+                            size,
+                            fingerprint: Fingerprint::Blake3(
+                                hasher.finalize().to_hex().to_string(),
+                            ),
+                        },
+                    );
+                    Ok(())
+                })?;
+            }
+        }
+        Ok(index)
+    })
+    .await
+}
+
+fn is_modified(path: &Path, indexed: &IndexedFile) -> Result<bool> {
+    let metadata = std::fs::metadata(path)?;
+    if metadata.len() != indexed.size {
+        return Ok(true);
+    }
+
+    match &indexed.fingerprint {
+        Fingerprint::Chunks { chunks, modified } => {
+            if *modified == store::modified_nanos(&metadata) {
+                return Ok(false);
+            }
+            Ok(store::chunk_hashes(path)? != *chunks)
+        }
+        Fingerprint::Blake3(hash) => {
+            let mut hasher = blake3::Hasher::new();
+            std::io::copy(&mut std::fs::File::open(path)?, &mut hasher)?;
+            Ok(hasher.finalize().to_hex().as_str() != hash)
+        }
+    }
+}
+
+/// Обойти файлы tar.zst архива старого формата
+fn for_each_archive_file(
+    archive_path: &Path,
+    mut visit: impl FnMut(String, u64, &mut dyn Read) -> Result<()>,
+) -> Result<()> {
+    let file = std::fs::File::open(archive_path)?;
+    let decoder = zstd::stream::Decoder::new(file)?;
+    let mut archive = tar::Archive::new(decoder);
+
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        if !entry.header().entry_type().is_file() {
+            continue;
+        }
+        let path = store::relative_to_string(&entry.path()?);
+        let size = entry.header().size()?;
+        visit(path, size, &mut entry)?;
+    }
+
+    Ok(())
+}
+
+/// Имена файлов модов (прямые потомки mods/)
+fn mod_files<'a>(paths: impl Iterator<Item = &'a str>) -> Vec<String> {
+    paths
+        .filter_map(|p| p.strip_prefix("mods/"))
+        .filter(|name| !name.contains('/'))
+        .filter(|name| name.ends_with(".jar") || name.ends_with(".jar.disabled"))
+        .map(str::to_string)
+        .collect()
+}
+
+/// Изменения модов от бэкапа к текущему состоянию: (добавлены, удалены, обновлены)
+fn mod_changes(
+    backup: &[String],
+    live: &[String],
+) -> (Vec<String>, Vec<String>, Vec<ModUpdateInfo>) {
+    let backup_set: HashSet<&str> = backup.iter().map(String::as_str).collect();
+    let live_set: HashSet<&str> = live.iter().map(String::as_str).collect();
+
+    let gone: Vec<&str> = backup
+        .iter()
+        .map(String::as_str)
+        .filter(|f| !live_set.contains(f))
+        .collect();
+    let new: Vec<&str> = live
+        .iter()
+        .map(String::as_str)
+        .filter(|f| !backup_set.contains(f))
+        .collect();
+
+    // Тот же slug с другим именем файла — обновление, а не удаление+добавление
+    let gone_by_slug: HashMap<String, &str> =
+        gone.iter().map(|f| (extract_mod_slug(f), *f)).collect();
+
+    let mut added = Vec::new();
+    let mut updated = Vec::new();
+    let mut updated_old = HashSet::new();
+    for filename in new {
+        let slug = extract_mod_slug(filename);
+        match gone_by_slug.get(&slug) {
+            Some(old) if !updated_old.contains(old) => {
+                updated_old.insert(*old);
+                updated.push(ModUpdateInfo {
+                    old_filename: old.to_string(),
+                    new_filename: filename.to_string(),
+                    mod_slug: slug,
+                });
+            }
+            _ => added.push(filename.to_string()),
+        }
+    }
+
+    let mut removed: Vec<String> = gone
+        .into_iter()
+        .filter(|f| !updated_old.contains(f))
+        .map(str::to_string)
+        .collect();
+
+    added.sort();
+    removed.sort();
+    updated.sort_by(|a, b| a.mod_slug.cmp(&b.mod_slug));
+    (added, removed, updated)
+}
+
+async fn run_blocking<T: Send + 'static>(
+    task: impl FnOnce() -> Result<T> + Send + 'static,
+) -> Result<T> {
+    tokio::task::spawn_blocking(task)
+        .await
+        .map_err(|e| LauncherError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::backup::BackupTrigger;
+
+    fn strings(items: &[&str]) -> Vec<String> {
+        items.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_mod_changes() {
+        let backup = strings(&[
+            "sodium-fabric-0.5.3.jar",
+            "lithium-0.11.jar",
+            "jei-15.2.jar",
+        ]);
+        let live = strings(&[
+            "sodium-fabric-0.5.8.jar",
+            "jei-15.2.jar",
+            "create-0.5.1.jar",
+        ]);
+
+        let (added, removed, updated) = mod_changes(&backup, &live);
+        assert_eq!(added, vec!["create-0.5.1.jar"]);
+        assert_eq!(removed, vec!["lithium-0.11.jar"]);
+        assert_eq!(updated.len(), 1);
+        assert_eq!(updated[0].old_filename, "sodium-fabric-0.5.3.jar");
+        assert_eq!(updated[0].new_filename, "sodium-fabric-0.5.8.jar");
+    }
+
+    #[test]
+    fn test_backup_roots() {
+        let mut record = BackupRecord {
+            id: "b".into(),
+            instance_id: "i".into(),
+            trigger: BackupTrigger::Manual,
+            created_at: String::new(),
+            size_bytes: 0,
+            includes_saves: false,
+            file_count: 0,
+            path: String::new(),
+            world: Some("New World".into()),
+            schedule_id: None,
+            storage: BackupStorage::Chunked,
+        };
+
+        let paths = [
+            "saves/New World/level.dat",
+            "saves/New World/region/r.0.0.mca",
+        ];
+        assert_eq!(
+            backup_roots(&record, paths.into_iter()),
+            vec!["saves/New World"]
+        );
+
+        record.world = None;
+        let paths = ["mods/a.jar", "world/level.dat"];
+        assert_eq!(
+            backup_roots(&record, paths.into_iter()),
+            vec!["mods", "config", "world"]
+        );
+    }
+}
@@ -0,0 +1,1274 @@
+//! Smart Backup System
+//!
+//! Умные бэкапы с:
+//! - Детекцией backup модов (не дублируем бэкапы миров)
+//! - Инкрементальными бэкапами (content-addressed хранилище с дедупликацией)
+//! - Автоочисткой старых бэкапов
+//! - Сжатием zstd
+//! - Бэкапами отдельных миров и расписаниями с политиками хранения
+
+pub mod consistency;
+pub mod destinations;
+pub mod diff;
+pub mod retention;
+pub mod schedule;
+pub mod store;
+
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::paths::{backup_store_dir, instance_dir};
+use crate::settings::SettingsManager;
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use tokio::fs;
+use tokio::sync::RwLock;
+
+use consistency::WorldSaveGuard;
+use diff::{BackupDiff, BackupFileEntry};
+use store::{ChunkStore, GcReport, Manifest, ManifestEntry, VerifyReport};
+
+/// Запись в хранилище чанков (shared) не должна пересекаться со сборкой мусора (exclusive)
+static STORE_LOCK: LazyLock<RwLock<()>> = LazyLock::new(|| RwLock::new(()));
+
+/// Известные моды бэкапов (если установлены - не бэкапим saves/)
+const KNOWN_BACKUP_MODS: &[&str] = &[
+    // Fabric/Forge
+    "ftb-backups",
+    "ftbbackups",
+    "ftb-backups-2",
+    "ftbbackups2",
+    // AromaBackup
+    "aromabackup",
+    "aroma-backup",
+    "aroma1997s-backup",
+    // Simple Backups
+    "simple-backups",
+    "simplebackups",
+    // Easy Backups
+    "easy-backups",
+    "easybackups",
+    // World Backup
+    "world-backup",
+    "worldbackup",
+    // Server side
+    "essential-backup",
+    "backup-mod",
+    "server-backup",
+];
+
+/// Триггер бэкапа
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupTrigger {
+    /// Перед установкой мода
+    BeforeModInstall { mod_name: String },
+    /// Перед удалением мода
+    BeforeModRemove { mod_name: String },
+    /// Перед обновлением мода
+    BeforeModUpdate {
+        mod_name: String,
+        from: String,
+        to: String,
+    },
+    /// Перед применением автофикса
+    BeforeAutoFix { fix_type: String },
+    /// Ручной бэкап
+    Manual,
+    /// Бэкап по расписанию
+    Scheduled { schedule_id: String },
+    /// Бэкап из задачи сервера
+    Task { task_id: String },
+    /// Страховочный бэкап перед выборочным восстановлением
+    BeforeRestore { backup_id: String },
+    /// Перед обрезкой или обновлением мира
+    BeforeWorldEdit { world: String, action: String },
+}
+
+/// Что входит в бэкап
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum BackupScope {
+    /// mods/, config/ и миры (если включено в настройках и нет backup мода)
+    #[default]
+    Instance,
+    /// Один мир: `saves/<world>` у клиента или `<world>` в корне сервера
+    World { world: String },
+    /// Произвольные файлы и директории относительно экземпляра
+    Paths { paths: Vec<String> },
+}
+
+/// Формат хранения бэкапа
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BackupStorage {
+    /// Отдельный tar.zst архив (бэкапы старых версий)
+    #[default]
+    Archive,
+    /// Манифест в общем content-addressed хранилище
+    Chunked,
+}
+
+impl BackupStorage {
+    fn as_str(&self) -> &'static str {
+        match self {
+            BackupStorage::Archive => "archive",
+            BackupStorage::Chunked => "chunked",
+        }
+    }
+
+    fn from_db(value: Option<&str>) -> Self {
+        match value {
+            Some("chunked") => BackupStorage::Chunked,
+            _ => BackupStorage::Archive,
+        }
+    }
+}
+
+/// Запись о бэкапе
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupRecord {
+    pub id: String,
+    pub instance_id: String,
+    pub trigger: BackupTrigger,
+    pub created_at: String,
+    pub size_bytes: u64,
+    pub includes_saves: bool,
+    pub file_count: u32,
+    pub path: String,
+    /// Мир, если это бэкап отдельного мира
+    #[serde(default)]
+    pub world: Option<String>,
+    /// Расписание, создавшее бэкап
+    #[serde(default)]
+    pub schedule_id: Option<String>,
+    #[serde(default)]
+    pub storage: BackupStorage,
+}
+
+/// Результат выборочного восстановления
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RestoreReport {
+    /// Бэкап текущего состояния, сделанный перед перезаписью
+    pub safety_backup_id: Option<String>,
+    pub restored_files: u32,
+    /// Файлы, появившиеся после бэкапа в выбранных путях
+    pub removed_files: u32,
+}
+
+/// Статус детекции backup мода
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupModStatus {
+    pub has_backup_mod: bool,
+    pub detected_mod: Option<String>,
+    pub message: String,
+}
+
+/// Менеджер бэкапов
+pub struct BackupManager;
+
+impl BackupManager {
+    /// Проверить, установлен ли мод бэкапов
+    pub async fn detect_backup_mod(instance_id: &str) -> Result<BackupModStatus> {
+        let conn = get_db_conn()?;
+
+        // Получаем список установленных модов
+        let mut stmt =
+            conn.prepare("SELECT slug, name FROM mods WHERE instance_id = ?1 AND enabled = 1")?;
+
+        let mods: Vec<(String, String)> = stmt
+            .query_map([instance_id], |row| {
+                Ok((row.get::<_, String>(0)?, row.get::<_, String>(1)?))
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        // Проверяем на известные backup моды
+        for (slug, name) in &mods {
+            let slug_lower = slug.to_lowercase();
+            let name_lower = name.to_lowercase();
+
+            for known in KNOWN_BACKUP_MODS {
+                if slug_lower.contains(known) || name_lower.contains(known) {
+                    return Ok(BackupModStatus {
+                        has_backup_mod: true,
+                        detected_mod: Some(name.clone()),
+                        message: format!(
+                            "Обнаружен мод бэкапов '{}'. Миры бэкапятся им, мы бэкапим только моды и конфиги.",
+                            name
+                        ),
+                    });
+                }
+            }
+        }
+
+        Ok(BackupModStatus {
+            has_backup_mod: false,
+            detected_mod: None,
+            message: "Мод бэкапов не обнаружен. Бэкапим моды, конфиги и миры.".to_string(),
+        })
+    }
+
+    /// Проверить, нужно ли делать бэкап для экземпляра
+    pub async fn should_backup(instance_id: &str) -> Result<bool> {
+        // Проверяем глобальную настройку
+        let settings = SettingsManager::get_all()?;
+        if !settings.backup_enabled {
+            return Ok(false);
+        }
+
+        // Проверяем override на уровне экземпляра
+        let conn = get_db_conn()?;
+        let instance_backup: Option<Option<i32>> = conn
+            .query_row(
+                "SELECT backup_enabled FROM instances WHERE id = ?1",
+                [instance_id],
+                |row| row.get(0),
+            )
+            .ok();
+
+        match instance_backup.flatten() {
+            Some(0) => Ok(false),                // Явно отключено для экземпляра
+            Some(1) => Ok(true),                 // Явно включено для экземпляра
+            None => Ok(settings.backup_enabled), // Используем глобальную настройку
+            _ => Ok(settings.backup_enabled),
+        }
+    }
+
+    /// Создать бэкап экземпляра
+    pub async fn create_backup(instance_id: &str, trigger: BackupTrigger) -> Result<BackupRecord> {
+        // Проверяем, нужно ли делать бэкап
+        if !Self::should_backup(instance_id).await? {
+            return Err(LauncherError::InvalidConfig(
+                "Бэкапы отключены для этого экземпляра".to_string(),
+            ));
+        }
+
+        Self::create_scoped_backup(instance_id, trigger, &BackupScope::Instance, None).await
+    }
+
+    /// Создать бэкап без проверки should_backup.
+    /// Бэкапы расписаний чистит их политика хранения, остальные — `backup_max_count`.
+    pub async fn create_scoped_backup(
+        instance_id: &str,
+        trigger: BackupTrigger,
+        scope: &BackupScope,
+        schedule_id: Option<&str>,
+    ) -> Result<BackupRecord> {
+        let record = Self::write_backup(instance_id, trigger, scope, schedule_id).await?;
+
+        // Очищаем старые бэкапы
+        if schedule_id.is_none() {
+            let settings = SettingsManager::get_all()?;
+            Self::cleanup_old_backups(instance_id, settings.backup_max_count as usize).await?;
+        }
+
+        destinations::DestinationManager::upload_in_background(&record);
+
+        Ok(record)
+    }
+
+    /// Записать бэкап в хранилище и БД, без очистки старых
+    async fn write_backup(
+        instance_id: &str,
+        trigger: BackupTrigger,
+        scope: &BackupScope,
+        schedule_id: Option<&str>,
+    ) -> Result<BackupRecord> {
+        let settings = SettingsManager::get_all()?;
+        let instance_path = instance_dir(instance_id);
+
+        // Собираем директории для бэкапа
+        let mut sources: Vec<PathBuf> = Vec::new();
+        let (include_saves, world) = match scope {
+            BackupScope::Instance => {
+                // Всегда бэкапим mods/ и config/
+                sources.push(instance_path.join("mods"));
+                sources.push(instance_path.join("config"));
+
+                // Миры — если нет backup мода
+                let backup_mod_status = Self::detect_backup_mod(instance_id).await?;
+                let include_saves =
+                    settings.backup_include_saves && !backup_mod_status.has_backup_mod;
+                if include_saves {
+                    sources.push(instance_path.join("saves"));
+                    sources.extend(server_world_dirs(&instance_path).await?);
+                }
+                (include_saves, None)
+            }
+            BackupScope::World { world } => {
+                sources.push(Self::world_dir(instance_id, world).await?);
+                (true, Some(world.clone()))
+            }
+            BackupScope::Paths { paths } => {
+                let mut include_saves = false;
+                for path in paths {
+                    let source = store::safe_join(&instance_path, path)?;
+                    let root = path.split('/').next().unwrap_or_default();
+                    include_saves |= root == "saves"
+                        || fs::try_exists(instance_path.join(root).join("level.dat"))
+                            .await
+                            .unwrap_or(false);
+                    sources.push(source);
+                }
+                (include_saves, None)
+            }
+        };
+
+        // Генерируем ID бэкапа
+        let backup_id = match &world {
+            Some(world) => format!(
+                "{}_{}",
+                new_backup_id("world", instance_id),
+                sanitize_file_name(world)
+            ),
+            None => new_backup_id("backup", instance_id),
+        };
+
+        let now = Utc::now().to_rfc3339();
+        let store_lock = STORE_LOCK.read().await;
+
+        // Запущенный сервер не должен писать в мир во время копирования
+        let guard = if include_saves {
+            WorldSaveGuard::acquire(instance_id).await
+        } else {
+            None
+        };
+        let stored =
+            store_directories(instance_id, &backup_id, &now, &instance_path, &sources).await;
+        if let Some(guard) = guard {
+            guard.release().await;
+        }
+        let (manifest_path, total_size, file_count) = stored?;
+
+        // Сохраняем запись в БД
+        let record = BackupRecord {
+            id: backup_id.clone(),
+            instance_id: instance_id.to_string(),
+            trigger,
+            created_at: now.clone(),
+            size_bytes: total_size,
+            includes_saves: include_saves,
+            file_count,
+            path: manifest_path.to_string_lossy().to_string(),
+            world,
+            schedule_id: schedule_id.map(str::to_string),
+            storage: BackupStorage::Chunked,
+        };
+
+        Self::save_backup_record(&record)?;
+        drop(store_lock);
+
+        log::info!(
+            "Created backup {} for instance {} ({} files, {} bytes)",
+            backup_id,
+            instance_id,
+            file_count,
+            total_size
+        );
+
+        Ok(record)
+    }
+
+    /// Найти директорию мира: `saves/<world>` (клиент) или `<world>` (сервер)
+    pub(crate) async fn world_dir(instance_id: &str, world: &str) -> Result<PathBuf> {
+        if world.is_empty() || world == "." || world == ".." || world.contains(['/', '\\']) {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Invalid world name: {}",
+                world
+            )));
+        }
+
+        let instance_path = instance_dir(instance_id);
+        for candidate in [
+            instance_path.join("saves").join(world),
+            instance_path.join(world),
+        ] {
+            if fs::try_exists(candidate.join("level.dat"))
+                .await
+                .unwrap_or(false)
+            {
+                return Ok(candidate);
+            }
+        }
+
+        Err(LauncherError::NotFound(format!(
+            "World '{}' not found in instance {}",
+            world, instance_id
+        )))
+    }
+
+    /// Список миров экземпляра (директории с level.dat)
+    pub async fn list_worlds(instance_id: &str) -> Result<Vec<String>> {
+        let instance_path = instance_dir(instance_id);
+        let mut worlds = Vec::new();
+
+        let saves_dir = instance_path.join("saves");
+        if fs::try_exists(&saves_dir).await.unwrap_or(false) {
+            worlds.extend(world_dirs_in(&saves_dir).await?);
+        }
+        worlds.extend(server_world_dirs(&instance_path).await?);
+
+        let mut names: Vec<String> = worlds
+            .iter()
+            .filter_map(|p| p.file_name().map(|n| n.to_string_lossy().to_string()))
+            .collect();
+        names.sort();
+        names.dedup();
+        Ok(names)
+    }
+
+    /// Сохранить запись о бэкапе в БД
+    pub(crate) fn save_backup_record(record: &BackupRecord) -> Result<()> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        conn.execute(
+            r#"INSERT INTO backups
+                (id, instance_id, trigger_json, created_at, size_bytes, includes_saves, file_count, path,
+                 world, schedule_id, storage)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11)"#,
+            params![
+                record.id,
+                record.instance_id,
+                serde_json::to_string(&record.trigger).unwrap_or_default(),
+                record.created_at,
+                record.size_bytes as i64,
+                if record.includes_saves { 1 } else { 0 },
+                record.file_count as i32,
+                record.path,
+                record.world,
+                record.schedule_id,
+                record.storage.as_str(),
+            ],
+        )?;
+
+        Ok(())
+    }
+
+    /// Создать таблицу backups если не существует
+    fn ensure_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            r#"CREATE TABLE IF NOT EXISTS backups (
+                id TEXT PRIMARY KEY,
+                instance_id TEXT NOT NULL,
+                trigger_json TEXT NOT NULL,
+                created_at TEXT NOT NULL,
+                size_bytes INTEGER NOT NULL,
+                includes_saves INTEGER NOT NULL,
+                file_count INTEGER NOT NULL,
+                path TEXT NOT NULL,
+                world TEXT,
+                schedule_id TEXT,
+                storage TEXT,
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            )"#,
+            [],
+        )?;
+        // Колонки старых БД добавляет миграция v24
+
+        Ok(())
+    }
+
+    /// Получить список бэкапов для экземпляра
+    pub fn list_backups(instance_id: &str) -> Result<Vec<BackupRecord>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, instance_id, trigger_json, created_at, size_bytes,
+                      includes_saves, file_count, path, world, schedule_id, storage
+               FROM backups
+               WHERE instance_id = ?1
+               ORDER BY created_at DESC"#,
+        )?;
+
+        let records = stmt
+            .query_map([instance_id], |row| {
+                let trigger_json: String = row.get(2)?;
+                let trigger: BackupTrigger =
+                    serde_json::from_str(&trigger_json).unwrap_or(BackupTrigger::Manual);
+
+                Ok(BackupRecord {
+                    id: row.get(0)?,
+                    instance_id: row.get(1)?,
+                    trigger,
+                    created_at: row.get(3)?,
+                    size_bytes: row.get::<_, i64>(4)? as u64,
+                    includes_saves: row.get::<_, i32>(5)? != 0,
+                    file_count: row.get::<_, i32>(6)? as u32,
+                    path: row.get(7)?,
+                    world: row.get(8)?,
+                    schedule_id: row.get(9)?,
+                    storage: BackupStorage::from_db(row.get::<_, Option<String>>(10)?.as_deref()),
+                })
+            })?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(records)
+    }
+
+    /// Удалить старые бэкапы, оставив только max_count последних.
+    /// Бэкапы расписаний и задач сервера не считаются — у них своя политика хранения.
+    pub(crate) async fn cleanup_old_backups(instance_id: &str, max_count: usize) -> Result<()> {
+        let backups: Vec<BackupRecord> = Self::list_backups(instance_id)?
+            .into_iter()
+            .filter(|b| b.schedule_id.is_none() && !matches!(b.trigger, BackupTrigger::Task { .. }))
+            .collect();
+
+        if backups.len() <= max_count {
+            return Ok(());
+        }
+
+        // Удаляем старые (backups уже отсортированы по дате DESC)
+        let to_delete = &backups[max_count..];
+
+        for backup in to_delete {
+            // Удаляем файл
+            let path = Path::new(&backup.path);
+            if fs::try_exists(path).await.unwrap_or(false) {
+                if let Err(e) = fs::remove_file(path).await {
+                    log::warn!("Failed to delete backup file {}: {}", backup.path, e);
+                }
+            }
+
+            // Удаляем запись из БД
+            let conn = get_db_conn()?;
+            if let Err(e) = conn.execute("DELETE FROM backups WHERE id = ?1", [&backup.id]) {
+                log::warn!("Failed to delete backup record {}: {}", backup.id, e);
+            }
+        }
+
+        log::info!(
+            "Cleaned up {} old backups for instance {}",
+            to_delete.len(),
+            instance_id
+        );
+
+        // Освобождаем чанки, на которые больше никто не ссылается
+        if let Err(e) = Self::collect_garbage().await {
+            log::warn!("Backup store GC failed: {}", e);
+        }
+
+        Ok(())
+    }
+
+    /// ID всех бэкапов всех экземпляров
+    fn all_backup_ids() -> Result<HashSet<String>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare("SELECT id FROM backups")?;
+        let ids = stmt
+            .query_map([], |row| row.get::<_, String>(0))?
+            .filter_map(|r| r.ok())
+            .collect();
+        Ok(ids)
+    }
+
+    /// Сборка мусора в хранилище: манифесты без записи в БД и чанки без ссылок
+    pub async fn collect_garbage() -> Result<GcReport> {
+        let _lock = STORE_LOCK.write().await;
+        let live = Self::all_backup_ids()?;
+
+        let report =
+            tokio::task::spawn_blocking(move || ChunkStore::new(backup_store_dir()).gc(&live))
+                .await
+                .map_err(|e| {
+                    LauncherError::Io(std::io::Error::new(std::io::ErrorKind::Other, e))
+                })??;
+
+        if report.removed_chunks > 0 || report.removed_manifests > 0 {
+            log::info!(
+                "Backup store GC: removed {} manifests and {} chunks ({} bytes)",
+                report.removed_manifests,
+                report.removed_chunks,
+                report.freed_bytes
+            );
+        }
+        Ok(report)
+    }
+
+    /// Проверить целостность бэкапов экземпляра (или всего хранилища)
+    pub async fn verify_backups(instance_id: Option<&str>) -> Result<VerifyReport> {
+        let _lock = STORE_LOCK.read().await;
+
+        let manifest_paths: Option<Vec<(String, PathBuf)>> = match instance_id {
+            Some(instance_id) => Some(
+                Self::list_backups(instance_id)?
+                    .into_iter()
+                    .filter(|b| b.storage == BackupStorage::Chunked)
+                    .map(|b| (b.id, PathBuf::from(b.path)))
+                    .collect(),
+            ),
+            None => None,
+        };
+
+        tokio::task::spawn_blocking(move || {
+            let store = ChunkStore::new(backup_store_dir());
+            let mut lost = Vec::new();
+            let manifests = match manifest_paths {
+                Some(paths) => paths
+                    .into_iter()
+                    .filter_map(|(id, path)| match ChunkStore::read_manifest(&path) {
+                        Ok(manifest) => Some(manifest),
+                        Err(_) => {
+                            lost.push(id);
+                            None
+                        }
+                    })
+                    .collect(),
+                None => store.manifests()?,
+            };
+
+            let mut report = store.verify(&manifests);
+            // Бэкап без манифеста восстановить нельзя вовсе
+            report.damaged_backups.extend(lost);
+            if !report.is_ok() || !report.damaged_backups.is_empty() {
+                log::warn!(
+                    "Backup verification: {} missing and {} corrupted chunks, damaged backups: {:?}",
+                    report.missing_chunks.len(),
+                    report.corrupted_chunks.len(),
+                    report.damaged_backups
+                );
+            }
+            Ok(report)
+        })
+        .await
+        .map_err(|e| LauncherError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
+    }
+
+    /// Получить запись о бэкапе
+    pub fn get_backup(backup_id: &str) -> Result<BackupRecord> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let instance_id: String = conn
+            .query_row(
+                "SELECT instance_id FROM backups WHERE id = ?1",
+                [backup_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| LauncherError::NotFound(format!("Backup {}", backup_id)))?;
+
+        Self::list_backups(&instance_id)?
+            .into_iter()
+            .find(|b| b.id == backup_id)
+            .ok_or_else(|| LauncherError::NotFound(format!("Backup {}", backup_id)))
+    }
+
+    /// Нельзя перезаписывать файлы запущенного экземпляра
+    pub(crate) async fn ensure_not_running(instance_id: &str) -> Result<()> {
+        let status: Option<String> = {
+            let conn = get_db_conn()?;
+            conn.query_row(
+                "SELECT status FROM instances WHERE id = ?1",
+                [instance_id],
+                |row| row.get(0),
+            )
+            .optional()?
+        };
+
+        let server_running = crate::server::console::get_console(instance_id)
+            .await
+            .read()
+            .await
+            .is_running();
+
+        if server_running || matches!(status.as_deref(), Some("running" | "starting" | "stopping"))
+        {
+            return Err(LauncherError::InstanceAlreadyRunning);
+        }
+        Ok(())
+    }
+
+    /// Восстановить только выбранные файлы/директории (пути относительно экземпляра,
+    /// например `config` или `saves/New World`). Выбранные директории приводятся к
+    /// состоянию бэкапа: файлы, появившиеся после него, удаляются. Перед перезаписью
+    /// текущее состояние этих путей сохраняется страховочным бэкапом.
+    pub async fn restore_selected(backup_id: &str, paths: &[String]) -> Result<RestoreReport> {
+        let record = Self::get_backup(backup_id)?;
+        let instance_id = record.instance_id.clone();
+        let instance_path = instance_dir(&instance_id);
+
+        let selectors: Vec<String> = paths
+            .iter()
+            .map(|p| p.trim_matches('/').replace('\\', "/"))
+            .collect();
+        if selectors.is_empty() {
+            return Err(LauncherError::InvalidConfig(
+                "No paths selected for restore".to_string(),
+            ));
+        }
+        for selector in &selectors {
+            store::safe_join(&instance_path, selector)?;
+        }
+
+        let backup_path = PathBuf::from(&record.path);
+        if !fs::try_exists(&backup_path).await.unwrap_or(false) {
+            return Err(LauncherError::NotFound(format!(
+                "Backup file not found: {}",
+                record.path
+            )));
+        }
+
+        let contents = diff::list_contents(&record).await?;
+        let roots = diff::backup_roots(&record, contents.iter().map(|e| e.path.as_str()));
+        // Путь вне областей бэкапа восстанавливать нечем, а удалять его файлы нельзя
+        if let Some(outside) = selectors.iter().find(|s| {
+            !is_selected(&roots, s)
+                && !roots
+                    .iter()
+                    .any(|r| is_selected(std::slice::from_ref(*s), r))
+        }) {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Path {} is not covered by backup {}",
+                outside, backup_id
+            )));
+        }
+
+        Self::ensure_not_running(&instance_id).await?;
+
+        let mut live_files = Vec::new();
+        for selector in &selectors {
+            diff::collect_live(&instance_path, selector, &mut live_files).await?;
+        }
+
+        // Страховочный бэкап текущего состояния выбранных путей
+        let safety_backup_id = if live_files.is_empty() {
+            None
+        } else {
+            let safety = Self::write_backup(
+                &instance_id,
+                BackupTrigger::BeforeRestore {
+                    backup_id: backup_id.to_string(),
+                },
+                &BackupScope::Paths {
+                    paths: selectors.clone(),
+                },
+                None,
+            )
+            .await?;
+            Some(safety.id)
+        };
+
+        let in_backup: HashSet<String> = contents
+            .into_iter()
+            .map(|e| e.path)
+            .filter(|p| is_selected(&selectors, p))
+            .collect();
+
+        let filter_selectors = selectors.clone();
+        let restored_files = match record.storage {
+            BackupStorage::Chunked => {
+                restore_from_store(&backup_path, &instance_path, move |entry| {
+                    is_selected(&filter_selectors, &entry.path)
+                })
+                .await?
+            }
+            BackupStorage::Archive => {
+                extract_archive_filtered(&backup_path, &instance_path, move |path| {
+                    is_selected(&filter_selectors, path)
+                })
+                .await?
+            }
+        };
+
+        // Удаляем то, чего не было на момент бэкапа, но только в покрытых им областях
+        let mut removed_files = 0;
+        for file in &live_files {
+            let Ok(relative) = file.strip_prefix(&instance_path) else {
+                continue;
+            };
+            let relative = store::relative_to_string(relative);
+            if is_selected(&roots, &relative) && !in_backup.contains(&relative) {
+                fs::remove_file(file).await?;
+                removed_files += 1;
+            }
+        }
+
+        log::info!(
+            "Restored {:?} from backup {} for instance {}: {} files restored, {} removed",
+            selectors,
+            backup_id,
+            instance_id,
+            restored_files,
+            removed_files
+        );
+
+        // Лимит backup_max_count здесь не применяем: чистка могла бы удалить сам
+        // восстанавливаемый бэкап. Лишнее уберёт следующий обычный бэкап.
+
+        Ok(RestoreReport {
+            safety_backup_id,
+            restored_files,
+            removed_files,
+        })
+    }
+
+    /// Восстановить из бэкапа
+    pub async fn restore_backup(backup_id: &str) -> Result<()> {
+        // Получаем информацию о бэкапе (conn не должен жить через await)
+        let (instance_id, path, storage): (String, String, Option<String>) = {
+            let conn = get_db_conn()?;
+            Self::ensure_table(&conn)?;
+            conn.query_row(
+                "SELECT instance_id, path, storage FROM backups WHERE id = ?1",
+                [backup_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )?
+        };
+
+        // Перезапись файлов под работающим процессом ломает миры и моды
+        Self::ensure_not_running(&instance_id).await?;
+
+        let backup_path = Path::new(&path);
+        if !fs::try_exists(backup_path).await.unwrap_or(false) {
+            return Err(LauncherError::NotFound(format!(
+                "Backup file not found: {}",
+                path
+            )));
+        }
+
+        let instance_path = instance_dir(&instance_id);
+
+        match BackupStorage::from_db(storage.as_deref()) {
+            // Распаковываем архив
+            BackupStorage::Archive => {
+                extract_compressed_archive(backup_path, &instance_path).await?;
+            }
+            // Собираем файлы из хранилища
+            BackupStorage::Chunked => {
+                restore_from_store(backup_path, &instance_path, |_| true).await?;
+            }
+        }
+
+        log::info!("Restored backup {} for instance {}", backup_id, instance_id);
+
+        Ok(())
+    }
+
+    /// Удалить конкретный бэкап
+    pub async fn delete_backup(backup_id: &str) -> Result<()> {
+        // Scope DB access before async operations (rusqlite Connection is !Send)
+        let path: String = {
+            let conn = get_db_conn()?;
+            conn.query_row(
+                "SELECT path FROM backups WHERE id = ?1",
+                [backup_id],
+                |row| row.get(0),
+            )?
+        };
+
+        // Удаляем файл (async I/O — conn must be dropped by now)
+        let backup_path = Path::new(&path);
+        if fs::try_exists(backup_path).await.unwrap_or(false) {
+            fs::remove_file(backup_path).await?;
+        }
+
+        // Re-acquire conn for DB delete
+        {
+            let conn = get_db_conn()?;
+            conn.execute("DELETE FROM backups WHERE id = ?1", [backup_id])?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Рекурсивно собрать все файлы в директории
+async fn collect_files_recursive(dir: &Path, files: &mut Vec<PathBuf>) -> Result<()> {
+    let mut entries = fs::read_dir(dir).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        let metadata = entry.metadata().await?;
+
+        if metadata.is_file() {
+            files.push(path);
+        } else if metadata.is_dir() {
+            // Пропускаем скрытые директории и .backup
+            let name = entry.file_name();
+            let name_str = name.to_string_lossy();
+            if !name_str.starts_with('.') {
+                Box::pin(collect_files_recursive(&path, files)).await?;
+            }
+        }
+    }
+
+    Ok(())
+}
+
+/// Миры сервера: директории с level.dat в корне экземпляра
+async fn server_world_dirs(instance_path: &Path) -> Result<Vec<PathBuf>> {
+    if !fs::try_exists(instance_path).await.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+    world_dirs_in(instance_path).await
+}
+
+/// Поддиректории с level.dat
+async fn world_dirs_in(dir: &Path) -> Result<Vec<PathBuf>> {
+    let mut worlds = Vec::new();
+    let mut entries = fs::read_dir(dir).await?;
+
+    while let Some(entry) = entries.next_entry().await? {
+        let path = entry.path();
+        if entry.file_type().await?.is_dir()
+            && fs::try_exists(path.join("level.dat"))
+                .await
+                .unwrap_or(false)
+        {
+            worlds.push(path);
+        }
+    }
+
+    worlds.sort();
+    Ok(worlds)
+}
+
+fn sanitize_file_name(name: &str) -> String {
+    name.chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+/// Записать директории в хранилище чанков. Возвращает (путь манифеста, размер, число файлов).
+/// Вызывать под `STORE_LOCK.read()`.
+async fn store_directories(
+    instance_id: &str,
+    backup_id: &str,
+    created_at: &str,
+    base_path: &Path,
+    sources: &[PathBuf],
+) -> Result<(PathBuf, u64, u32)> {
+    let mut files: Vec<PathBuf> = Vec::new();
+    for source in sources {
+        match fs::metadata(source).await {
+            Ok(metadata) if metadata.is_dir() => {
+                collect_files_recursive(source, &mut files).await?
+            }
+            Ok(_) => files.push(source.clone()),
+            Err(_) => {}
+        }
+    }
+
+    // Файлы с тем же размером и mtime, что в последнем бэкапе, не перечитываем
+    let previous = BackupManager::list_backups(instance_id)?
+        .into_iter()
+        .find(|b| b.storage == BackupStorage::Chunked)
+        .map(|b| PathBuf::from(b.path));
+
+    let instance_id = instance_id.to_string();
+    let backup_id = backup_id.to_string();
+    let created_at = created_at.to_string();
+    let base_path = base_path.to_owned();
+
+    tokio::task::spawn_blocking(move || {
+        let store = ChunkStore::new(backup_store_dir());
+        let previous = previous.and_then(|path| ChunkStore::read_manifest(&path).ok());
+
+        let (entries, stats) = store.store_files(&base_path, &files, previous.as_ref())?;
+        let manifest = Manifest::new(&backup_id, &instance_id, &created_at, entries);
+        let manifest_path = store.write_manifest(&manifest)?;
+
+        log::debug!(
+            "Backup {}: {} unchanged files, {} new chunks ({} bytes)",
+            backup_id,
+            stats.unchanged_files,
+            stats.new_chunks,
+            stats.new_bytes
+        );
+
+        Ok((
+            manifest_path,
+            manifest.total_size(),
+            manifest.files.len() as u32,
+        ))
+    })
+    .await
+    .map_err(|e| LauncherError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
+}
+
+/// Восстановить файлы бэкапа из хранилища чанков
+async fn restore_from_store(
+    manifest_path: &Path,
+    target_dir: &Path,
+    filter: impl Fn(&ManifestEntry) -> bool + Send + 'static,
+) -> Result<u32> {
+    let manifest_path = manifest_path.to_owned();
+    let target_dir = target_dir.to_owned();
+    let _lock = STORE_LOCK.read().await;
+
+    tokio::task::spawn_blocking(move || {
+        let manifest = ChunkStore::read_manifest(&manifest_path)?;
+        ChunkStore::new(backup_store_dir()).restore(&manifest, &target_dir, filter)
+    })
+    .await
+    .map_err(|e| LauncherError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
+}
+
+/// Путь выбран, если совпадает с одним из выбранных или лежит внутри него
+fn is_selected(selectors: &[String], path: &str) -> bool {
+    selectors.iter().any(|s| {
+        path == s
+            || path
+                .strip_prefix(s.as_str())
+                .is_some_and(|rest| rest.starts_with('/'))
+    })
+}
+
+/// Распаковать из tar.zst архива только файлы, прошедшие фильтр
+async fn extract_archive_filtered(
+    archive_path: &Path,
+    target_dir: &Path,
+    filter: impl Fn(&str) -> bool + Send + 'static,
+) -> Result<u32> {
+    let archive_path = archive_path.to_owned();
+    let target_dir = target_dir.to_owned();
+
+    tokio::task::spawn_blocking(move || {
+        let archive_file = std::fs::File::open(&archive_path)?;
+        let decoder = zstd::stream::Decoder::new(archive_file)?;
+        let mut archive = tar::Archive::new(decoder);
+
+        let mut restored = 0;
+        for entry in archive.entries()? {
+            let mut entry = entry?;
+            let path = store::relative_to_string(&entry.path()?);
+            if entry.header().entry_type().is_file() && filter(&path) {
+                // unpack_in не даёт выйти за пределы target_dir
+                if entry.unpack_in(&target_dir)? {
+                    restored += 1;
+                }
+            }
+        }
+
+        Ok(restored)
+    })
+    .await
+    .map_err(|e| LauncherError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
+}
+
+/// Распаковать tar.zst архив
+/// Использует spawn_blocking так как tar/zstd библиотеки требуют синхронного I/O
+async fn extract_compressed_archive(archive_path: &Path, target_dir: &Path) -> Result<()> {
+    let archive_path = archive_path.to_owned();
+    let target_dir = target_dir.to_owned();
+
+    tokio::task::spawn_blocking(move || {
+        let archive_file = std::fs::File::open(&archive_path)?;
+        let decoder = zstd::stream::Decoder::new(archive_file)?;
+        let mut archive = tar::Archive::new(decoder);
+
+        archive.unpack(&target_dir)?;
+
+        Ok(())
+    })
+    .await
+    .map_err(|e| LauncherError::Io(std::io::Error::new(std::io::ErrorKind::Other, e)))?
+}
+
+// ============================================================================
+// Internal API (for other modules)
+// ============================================================================
+
+/// Создать бэкап (внутренний API без проверки should_backup)
+/// ID бэкапа: время до секунды + случайный суффикс, чтобы два бэкапа
+/// одного экземпляра в одну секунду не делили манифест
+fn new_backup_id(prefix: &str, instance_id: &str) -> String {
+    format!(
+        "{}_{}_{}_{}",
+        prefix,
+        Utc::now().format("%Y%m%d_%H%M%S"),
+        &instance_id[..6.min(instance_id.len())],
+        &uuid::Uuid::new_v4().simple().to_string()[..8]
+    )
+}
+
+/// Используется модулем sync для создания бэкапа перед синхронизацией
+pub async fn create_backup_internal(
+    instance_id: &str,
+    trigger: BackupTrigger,
+    _include_saves: bool, // Пока не используется, но для совместимости API
+) -> Result<BackupRecord> {
+    let settings = SettingsManager::get_all()?;
+    let instance_path = instance_dir(instance_id);
+
+    // Генерируем ID бэкапа
+    let backup_id = new_backup_id("backup", instance_id);
+
+    let now = Utc::now().to_rfc3339();
+    let store_lock = STORE_LOCK.read().await;
+
+    // Бэкапим только конфиги, не saves
+    let sources = [instance_path.join("config")];
+    let (manifest_path, total_size, file_count) =
+        store_directories(instance_id, &backup_id, &now, &instance_path, &sources).await?;
+
+    // Сохраняем запись в БД
+    let record = BackupRecord {
+        id: backup_id.clone(),
+        instance_id: instance_id.to_string(),
+        trigger,
+        created_at: now.clone(),
+        size_bytes: total_size,
+        includes_saves: false,
+        file_count,
+        path: manifest_path.to_string_lossy().to_string(),
+        world: None,
+        schedule_id: None,
+        storage: BackupStorage::Chunked,
+    };
+
+    BackupManager::save_backup_record(&record)?;
+    drop(store_lock);
+
+    // Очищаем старые бэкапы
+    BackupManager::cleanup_old_backups(instance_id, settings.backup_max_count as usize).await?;
+
+    log::info!(
+        "Created internal backup {} for instance {} ({} files, {} bytes)",
+        backup_id,
+        instance_id,
+        file_count,
+        total_size
+    );
+
+    Ok(record)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn detect_backup_mod(instance_id: String) -> Result<BackupModStatus> {
+    BackupManager::detect_backup_mod(&instance_id).await
+}
+
+#[tauri::command]
+pub async fn should_backup(instance_id: String) -> Result<bool> {
+    BackupManager::should_backup(&instance_id).await
+}
+
+#[tauri::command]
+pub async fn create_backup(instance_id: String, trigger: BackupTrigger) -> Result<BackupRecord> {
+    BackupManager::create_backup(&instance_id, trigger).await
+}
+
+#[tauri::command]
+pub async fn create_world_backup(instance_id: String, world: String) -> Result<BackupRecord> {
+    BackupManager::create_scoped_backup(
+        &instance_id,
+        BackupTrigger::Manual,
+        &BackupScope::World { world },
+        None,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn list_instance_worlds(instance_id: String) -> Result<Vec<String>> {
+    BackupManager::list_worlds(&instance_id).await
+}
+
+#[tauri::command]
+pub async fn list_backups(instance_id: String) -> Result<Vec<BackupRecord>> {
+    BackupManager::list_backups(&instance_id)
+}
+
+#[tauri::command]
+pub async fn restore_backup(backup_id: String) -> Result<()> {
+    BackupManager::restore_backup(&backup_id).await
+}
+
+#[tauri::command]
+pub async fn list_backup_contents(backup_id: String) -> Result<Vec<BackupFileEntry>> {
+    let record = BackupManager::get_backup(&backup_id)?;
+    diff::list_contents(&record).await
+}
+
+#[tauri::command]
+pub async fn diff_backup(backup_id: String) -> Result<BackupDiff> {
+    let record = BackupManager::get_backup(&backup_id)?;
+    diff::diff_with_live(&record).await
+}
+
+#[tauri::command]
+pub async fn restore_backup_paths(backup_id: String, paths: Vec<String>) -> Result<RestoreReport> {
+    BackupManager::restore_selected(&backup_id, &paths).await
+}
+
+#[tauri::command]
+pub async fn delete_backup(backup_id: String) -> Result<()> {
+    BackupManager::delete_backup(&backup_id).await?;
+    if let Err(e) = BackupManager::collect_garbage().await {
+        log::warn!("Backup store GC failed: {}", e);
+    }
+    Ok(())
+}
+
+#[tauri::command]
+pub async fn gc_backup_store() -> Result<GcReport> {
+    BackupManager::collect_garbage().await
+}
+
+#[tauri::command]
+pub async fn verify_backups(instance_id: Option<String>) -> Result<VerifyReport> {
+    BackupManager::verify_backups(instance_id.as_deref()).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_backup_mod_detection_patterns() {
+        // Проверяем что паттерны корректные
+        assert!(KNOWN_BACKUP_MODS.contains(&"ftb-backups"));
+        assert!(KNOWN_BACKUP_MODS.contains(&"aromabackup"));
+        assert!(KNOWN_BACKUP_MODS.contains(&"simple-backups"));
+    }
+
+    #[test]
+    fn test_backup_trigger_serialization() {
+        let trigger = BackupTrigger::BeforeModInstall {
+            mod_name: "sodium".to_string(),
+        };
+
+        let json = serde_json::to_string(&trigger).unwrap();
+        assert!(json.contains("before_mod_install"));
+        assert!(json.contains("sodium"));
+
+        let parsed: BackupTrigger = serde_json::from_str(&json).unwrap();
+        match parsed {
+            BackupTrigger::BeforeModInstall { mod_name } => {
+                assert_eq!(mod_name, "sodium");
+            }
+            _ => panic!("Wrong trigger type"),
+        }
+    }
+
+    #[test]
+    fn test_is_selected() {
+        let selectors = vec!["config".to_string(), "saves/New World".to_string()];
+        assert!(is_selected(&selectors, "config"));
+        assert!(is_selected(&selectors, "config/sodium.json"));
+        assert!(is_selected(&selectors, "saves/New World/level.dat"));
+        assert!(!is_selected(&selectors, "configs/other.json"));
+        assert!(!is_selected(&selectors, "saves/New World 2/level.dat"));
+        assert!(!is_selected(&selectors, "mods/sodium.jar"));
+    }
+}
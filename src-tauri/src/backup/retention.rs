@@ -0,0 +1,182 @@
+//! Политики хранения бэкапов
+//!
+//! Бэкап сохраняется, если его оставляет хотя бы одно правило:
+//! последние N, моложе N дней или GFS-ротация (по одному на час/день/неделю/месяц).
+
+use chrono::{DateTime, Datelike, Duration, Local, Timelike};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// Политика хранения. Пустая политика (все поля None) ничего не удаляет.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default)]
+pub struct RetentionPolicy {
+    /// Последние N бэкапов
+    pub keep_last: Option<u32>,
+    /// Все бэкапы моложе N дней
+    pub keep_within_days: Option<u32>,
+    /// Последний бэкап каждого из N последних часов
+    pub keep_hourly: Option<u32>,
+    /// Последний бэкап каждого из N последних дней
+    pub keep_daily: Option<u32>,
+    /// Последний бэкап каждой из N последних недель (ISO)
+    pub keep_weekly: Option<u32>,
+    /// Последний бэкап каждого из N последних месяцев
+    pub keep_monthly: Option<u32>,
+}
+
+impl RetentionPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.keep_last.is_none()
+            && self.keep_within_days.is_none()
+            && self.keep_hourly.is_none()
+            && self.keep_daily.is_none()
+            && self.keep_weekly.is_none()
+            && self.keep_monthly.is_none()
+    }
+}
+
+/// Бэкап с точки зрения политики хранения
+#[derive(Debug, Clone)]
+pub struct RetentionEntry {
+    pub id: String,
+    pub created_at: DateTime<Local>,
+}
+
+/// Выбрать ID бэкапов, которые политика разрешает удалить
+pub fn select_for_pruning(
+    entries: &[RetentionEntry],
+    policy: &RetentionPolicy,
+    now: DateTime<Local>,
+) -> Vec<String> {
+    if policy.is_empty() {
+        return Vec::new();
+    }
+
+    let mut sorted: Vec<&RetentionEntry> = entries.iter().collect();
+    sorted.sort_by(|a, b| b.created_at.cmp(&a.created_at));
+
+    let mut keep: HashSet<&str> = HashSet::new();
+
+    if let Some(n) = policy.keep_last {
+        keep.extend(sorted.iter().take(n as usize).map(|e| e.id.as_str()));
+    }
+
+    if let Some(days) = policy.keep_within_days {
+        let cutoff = now - Duration::days(days as i64);
+        keep.extend(
+            sorted
+                .iter()
+                .filter(|e| e.created_at >= cutoff)
+                .map(|e| e.id.as_str()),
+        );
+    }
+
+    let buckets: [(Option<u32>, fn(&DateTime<Local>) -> (i32, u32, u32)); 4] = [
+        (policy.keep_hourly, |t| (t.year(), t.ordinal(), t.hour())),
+        (policy.keep_daily, |t| (t.year(), t.ordinal(), 0)),
+        (policy.keep_weekly, |t| {
+            let week = t.iso_week();
+            (week.year(), week.week(), 0)
+        }),
+        (policy.keep_monthly, |t| (t.year(), t.month(), 0)),
+    ];
+
+    for (limit, bucket_of) in buckets {
+        let Some(limit) = limit else { continue };
+        let mut last_bucket = None;
+        let mut taken = 0;
+
+        // Самый новый бэкап каждого периода, пока не наберём limit периодов
+        for entry in &sorted {
+            if taken >= limit {
+                break;
+            }
+            let bucket = bucket_of(&entry.created_at);
+            if last_bucket != Some(bucket) {
+                keep.insert(entry.id.as_str());
+                last_bucket = Some(bucket);
+                taken += 1;
+            }
+        }
+    }
+
+    sorted
+        .into_iter()
+        .filter(|e| !keep.contains(e.id.as_str()))
+        .map(|e| e.id.clone())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    fn local(y: i32, mo: u32, d: u32, h: u32) -> DateTime<Local> {
+        Local.with_ymd_and_hms(y, mo, d, h, 0, 0).single().unwrap()
+    }
+
+    /// Бэкапы каждые 6 часов за 30 дней до 2024-06-30 18:00
+    fn every_six_hours() -> Vec<RetentionEntry> {
+        let newest = local(2024, 6, 30, 18);
+        (0..120)
+            .map(|i| {
+                let created_at = newest - Duration::hours(6 * i);
+                RetentionEntry {
+                    id: created_at.format("%m-%d %H").to_string(),
+                    created_at,
+                }
+            })
+            .collect()
+    }
+
+    fn kept(entries: &[RetentionEntry], policy: &RetentionPolicy) -> Vec<String> {
+        let pruned = select_for_pruning(entries, policy, local(2024, 6, 30, 20));
+        entries
+            .iter()
+            .filter(|e| !pruned.contains(&e.id))
+            .map(|e| e.id.clone())
+            .collect()
+    }
+
+    #[test]
+    fn test_empty_policy_keeps_everything() {
+        let entries = every_six_hours();
+        assert!(select_for_pruning(&entries, &RetentionPolicy::default(), Local::now()).is_empty());
+    }
+
+    #[test]
+    fn test_gfs_rotation() {
+        let entries = every_six_hours();
+        let policy = RetentionPolicy {
+            keep_hourly: Some(2),
+            keep_daily: Some(3),
+            keep_weekly: Some(2),
+            ..Default::default()
+        };
+
+        // Часовые: 30.06 18:00 и 12:00. Дневные: последние за 30, 29, 28 июня.
+        // Недельные: воскресенье 30.06 (уже есть) и последний бэкап недели 17–23 июня.
+        assert_eq!(
+            kept(&entries, &policy),
+            vec!["06-30 18", "06-30 12", "06-29 18", "06-28 18", "06-23 18"]
+        );
+    }
+
+    #[test]
+    fn test_keep_last_and_within_days() {
+        let entries = every_six_hours();
+        let policy = RetentionPolicy {
+            keep_last: Some(1),
+            keep_within_days: Some(1),
+            ..Default::default()
+        };
+
+        // Сутки до 30.06 20:00 — четыре бэкапа 30 июня, 29.06 18:00 уже старше
+        assert_eq!(
+            kept(&entries, &policy),
+            vec!["06-30 18", "06-30 12", "06-30 06", "06-30 00"]
+        );
+    }
+}
@@ -0,0 +1,465 @@
+//! Бэкапы по расписанию
+//!
+//! Расписание — интервал или cron-выражение на экземпляр. Фоновый цикл раз в
+//! [`TICK_INTERVAL`] запускает просроченные расписания и применяет их политику хранения.
+//! Пропущенные пока лаунчер был закрыт запуски выполняются один раз, без догоняния.
+
+use chrono::{DateTime, Duration, Local, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use super::retention::{select_for_pruning, RetentionEntry, RetentionPolicy};
+use super::{BackupManager, BackupRecord, BackupScope, BackupTrigger};
+use crate::cron::CronExpr;
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+
+/// Как часто проверять расписания
+const TICK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(30);
+
+/// Когда запускать
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ScheduleSpec {
+    /// Каждые N минут
+    Interval { minutes: u32 },
+    /// Cron-выражение (5 полей, локальное время)
+    Cron { expression: String },
+}
+
+impl ScheduleSpec {
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            ScheduleSpec::Interval { minutes } if *minutes == 0 => Err(
+                LauncherError::InvalidConfig("Backup interval must be positive".to_string()),
+            ),
+            ScheduleSpec::Interval { .. } => Ok(()),
+            ScheduleSpec::Cron { expression } => expression.parse::<CronExpr>().map(|_| ()),
+        }
+    }
+
+    /// Следующий запуск после `after`
+    pub fn next_run(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        match self {
+            ScheduleSpec::Interval { minutes } => Some(after + Duration::minutes(*minutes as i64)),
+            ScheduleSpec::Cron { expression } => {
+                expression.parse::<CronExpr>().ok()?.next_after(&after)
+            }
+        }
+    }
+}
+
+/// Расписание бэкапов экземпляра
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupSchedule {
+    pub id: String,
+    pub instance_id: String,
+    pub enabled: bool,
+    pub schedule: ScheduleSpec,
+    pub scope: BackupScope,
+    pub retention: RetentionPolicy,
+    pub last_run_at: Option<String>,
+    pub next_run_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+/// Параметры создания/изменения расписания
+#[derive(Debug, Clone, Deserialize)]
+pub struct BackupScheduleInput {
+    pub instance_id: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub schedule: ScheduleSpec,
+    #[serde(default)]
+    pub scope: BackupScope,
+    #[serde(default)]
+    pub retention: RetentionPolicy,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Планировщик бэкапов
+pub struct BackupScheduler;
+
+impl BackupScheduler {
+    /// Запустить фоновый цикл планировщика
+    pub fn start() {
+        tauri::async_runtime::spawn(async {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = Self::run_due().await {
+                    log::warn!("Backup scheduler tick failed: {}", e);
+                }
+            }
+        });
+    }
+
+    /// Выполнить все просроченные расписания
+    pub async fn run_due() -> Result<()> {
+        let now = Local::now();
+        let due: Vec<BackupSchedule> = Self::list(None)?
+            .into_iter()
+            .filter(|s| s.enabled)
+            .filter(|s| match s.next_run_at.as_deref().and_then(parse_time) {
+                Some(next) => next <= now,
+                // Расписание без next_run_at (например, после ошибки в выражении) — пересчитать
+                None => true,
+            })
+            .collect();
+
+        for schedule in due {
+            if schedule.next_run_at.is_none() {
+                Self::store_run_state(&schedule.id, None, now, None)?;
+                continue;
+            }
+            if let Err(e) = Self::run(&schedule).await {
+                log::warn!("Scheduled backup {} failed: {}", schedule.id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Выполнить расписание вне очереди
+    pub async fn run_now(schedule_id: &str) -> Result<BackupRecord> {
+        let schedule = Self::get(schedule_id)?;
+        Self::run(&schedule).await
+    }
+
+    async fn run(schedule: &BackupSchedule) -> Result<BackupRecord> {
+        let trigger = BackupTrigger::Scheduled {
+            schedule_id: schedule.id.clone(),
+        };
+        let result = BackupManager::create_scoped_backup(
+            &schedule.instance_id,
+            trigger,
+            &schedule.scope,
+            Some(&schedule.id),
+        )
+        .await;
+
+        let finished = Local::now();
+        let error = result.as_ref().err().map(|e| e.to_string());
+        Self::store_run_state(&schedule.id, Some(finished), finished, error)?;
+
+        let record = result?;
+        match Self::apply_retention(schedule).await {
+            Ok(0) => {}
+            Ok(pruned) => log::info!(
+                "Retention for schedule {} removed {} backups",
+                schedule.id,
+                pruned
+            ),
+            Err(e) => log::warn!("Retention for schedule {} failed: {}", schedule.id, e),
+        }
+
+        Ok(record)
+    }
+
+    /// Удалить бэкапы расписания, которые не оставляет его политика. Возвращает число удалённых.
+    pub async fn apply_retention(schedule: &BackupSchedule) -> Result<usize> {
+        Self::prune(
+            &schedule.instance_id,
+            |b| b.schedule_id.as_deref() == Some(schedule.id.as_str()),
+            &schedule.retention,
+        )
+        .await
+    }
+
+    /// То же для бэкапов задачи сервера (`BackupTrigger::Task`)
+    pub async fn apply_task_retention(
+        instance_id: &str,
+        task_id: &str,
+        retention: &RetentionPolicy,
+    ) -> Result<usize> {
+        Self::prune(
+            instance_id,
+            |b| matches!(&b.trigger, BackupTrigger::Task { task_id: id } if id == task_id),
+            retention,
+        )
+        .await
+    }
+
+    async fn prune(
+        instance_id: &str,
+        owned: impl Fn(&BackupRecord) -> bool,
+        retention: &RetentionPolicy,
+    ) -> Result<usize> {
+        let entries: Vec<RetentionEntry> = BackupManager::list_backups(instance_id)?
+            .into_iter()
+            .filter(|b| owned(b))
+            .filter_map(|b| {
+                Some(RetentionEntry {
+                    created_at: parse_time(&b.created_at)?,
+                    id: b.id,
+                })
+            })
+            .collect();
+
+        let to_delete = select_for_pruning(&entries, retention, Local::now());
+        for backup_id in &to_delete {
+            BackupManager::delete_backup(backup_id).await?;
+        }
+        if !to_delete.is_empty() {
+            BackupManager::collect_garbage().await?;
+        }
+
+        Ok(to_delete.len())
+    }
+
+    /// Сохранить время запуска, ошибку и пересчитать следующий запуск
+    fn store_run_state(
+        schedule_id: &str,
+        ran_at: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+        error: Option<String>,
+    ) -> Result<()> {
+        let schedule = Self::get(schedule_id)?;
+        let next_run = schedule.schedule.next_run(now).map(|t| t.to_rfc3339());
+
+        let conn = get_db_conn()?;
+        conn.execute(
+            r#"UPDATE backup_schedules
+               SET last_run_at = COALESCE(?2, last_run_at), next_run_at = ?3,
+                   last_error = CASE WHEN ?2 IS NULL THEN last_error ELSE ?4 END
+               WHERE id = ?1"#,
+            params![schedule_id, ran_at.map(|t| t.to_rfc3339()), next_run, error],
+        )?;
+        Ok(())
+    }
+
+    /// Создать таблицу расписаний если не существует
+    fn ensure_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            r#"CREATE TABLE IF NOT EXISTS backup_schedules (
+                id TEXT PRIMARY KEY,
+                instance_id TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                schedule_json TEXT NOT NULL,
+                scope_json TEXT NOT NULL,
+                retention_json TEXT NOT NULL,
+                last_run_at TEXT,
+                next_run_at TEXT,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            )"#,
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Список расписаний (всех или одного экземпляра)
+    pub fn list(instance_id: Option<&str>) -> Result<Vec<BackupSchedule>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, instance_id, enabled, schedule_json, scope_json, retention_json,
+                      last_run_at, next_run_at, last_error, created_at
+               FROM backup_schedules
+               WHERE ?1 IS NULL OR instance_id = ?1
+               ORDER BY created_at"#,
+        )?;
+
+        let schedules = stmt
+            .query_map([instance_id], row_to_schedule)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(schedules)
+    }
+
+    pub fn get(schedule_id: &str) -> Result<BackupSchedule> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        conn.query_row(
+            r#"SELECT id, instance_id, enabled, schedule_json, scope_json, retention_json,
+                      last_run_at, next_run_at, last_error, created_at
+               FROM backup_schedules WHERE id = ?1"#,
+            [schedule_id],
+            row_to_schedule,
+        )
+        .optional()?
+        .ok_or_else(|| LauncherError::NotFound(format!("Backup schedule {}", schedule_id)))
+    }
+
+    pub fn create(input: BackupScheduleInput) -> Result<BackupSchedule> {
+        input.schedule.validate()?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let now = Local::now();
+        let next_run = input.schedule.next_run(now).map(|t| t.to_rfc3339());
+
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        conn.execute(
+            r#"INSERT INTO backup_schedules
+                (id, instance_id, enabled, schedule_json, scope_json, retention_json,
+                 next_run_at, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+            params![
+                id,
+                input.instance_id,
+                input.enabled,
+                serde_json::to_string(&input.schedule)?,
+                serde_json::to_string(&input.scope)?,
+                serde_json::to_string(&input.retention)?,
+                next_run,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+
+        Self::get(&id)
+    }
+
+    /// Изменить расписание. Следующий запуск пересчитывается от текущего момента.
+    pub fn update(schedule_id: &str, input: BackupScheduleInput) -> Result<BackupSchedule> {
+        input.schedule.validate()?;
+        let next_run = input
+            .schedule
+            .next_run(Local::now())
+            .map(|t| t.to_rfc3339());
+
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        let updated = conn.execute(
+            r#"UPDATE backup_schedules
+               SET enabled = ?2, schedule_json = ?3, scope_json = ?4, retention_json = ?5,
+                   next_run_at = ?6
+               WHERE id = ?1"#,
+            params![
+                schedule_id,
+                input.enabled,
+                serde_json::to_string(&input.schedule)?,
+                serde_json::to_string(&input.scope)?,
+                serde_json::to_string(&input.retention)?,
+                next_run,
+            ],
+        )?;
+        if updated == 0 {
+            return Err(LauncherError::NotFound(format!(
+                "Backup schedule {}",
+                schedule_id
+            )));
+        }
+
+        Self::get(schedule_id)
+    }
+
+    /// Удалить расписание. Созданные им бэкапы остаются, удалять их — вручную.
+    pub fn delete(schedule_id: &str) -> Result<()> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        conn.execute("DELETE FROM backup_schedules WHERE id = ?1", [schedule_id])?;
+        Ok(())
+    }
+}
+
+fn row_to_schedule(row: &rusqlite::Row) -> rusqlite::Result<BackupSchedule> {
+    let schedule_json: String = row.get(3)?;
+    let scope_json: String = row.get(4)?;
+    let retention_json: String = row.get(5)?;
+
+    let json_error = |idx: usize, e: serde_json::Error| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    };
+
+    Ok(BackupSchedule {
+        id: row.get(0)?,
+        instance_id: row.get(1)?,
+        enabled: row.get::<_, i32>(2)? != 0,
+        schedule: serde_json::from_str(&schedule_json).map_err(|e| json_error(3, e))?,
+        scope: serde_json::from_str(&scope_json).map_err(|e| json_error(4, e))?,
+        retention: serde_json::from_str(&retention_json).map_err(|e| json_error(5, e))?,
+        last_run_at: row.get(6)?,
+        next_run_at: row.get(7)?,
+        last_error: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Local))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_backup_schedules(instance_id: Option<String>) -> Result<Vec<BackupSchedule>> {
+    BackupScheduler::list(instance_id.as_deref())
+}
+
+#[tauri::command]
+pub async fn create_backup_schedule(input: BackupScheduleInput) -> Result<BackupSchedule> {
+    BackupScheduler::create(input)
+}
+
+#[tauri::command]
+pub async fn update_backup_schedule(
+    schedule_id: String,
+    input: BackupScheduleInput,
+) -> Result<BackupSchedule> {
+    BackupScheduler::update(&schedule_id, input)
+}
+
+#[tauri::command]
+pub async fn delete_backup_schedule(schedule_id: String) -> Result<()> {
+    BackupScheduler::delete(&schedule_id)
+}
+
+#[tauri::command]
+pub async fn run_backup_schedule_now(schedule_id: String) -> Result<BackupRecord> {
+    BackupScheduler::run_now(&schedule_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_schedule_spec_next_run() {
+        let now = Local
+            .with_ymd_and_hms(2024, 6, 1, 10, 7, 0)
+            .single()
+            .unwrap();
+
+        let interval = ScheduleSpec::Interval { minutes: 90 };
+        assert_eq!(interval.next_run(now), Some(now + Duration::minutes(90)));
+
+        let cron = ScheduleSpec::Cron {
+            expression: "0 */6 * * *".to_string(),
+        };
+        assert_eq!(
+            cron.next_run(now),
+            Local.with_ymd_and_hms(2024, 6, 1, 12, 0, 0).single()
+        );
+    }
+
+    #[test]
+    fn test_schedule_input_defaults() {
+        let input: BackupScheduleInput = serde_json::from_str(
+            r#"{"instance_id": "abc", "schedule": {"type": "cron", "expression": "@daily"},
+                "retention": {"keep_daily": 7, "keep_weekly": 4}}"#,
+        )
+        .unwrap();
+
+        assert!(input.enabled);
+        assert_eq!(input.scope, BackupScope::Instance);
+        assert_eq!(input.retention.keep_daily, Some(7));
+        assert!(input.schedule.validate().is_ok());
+
+        let invalid = ScheduleSpec::Interval { minutes: 0 };
+        assert!(invalid.validate().is_err());
+    }
+}
@@ -0,0 +1,695 @@
+//! Content-addressed хранилище бэкапов
+//!
+//! Файлы режутся на чанки фиксированного размера, каждый чанк хранится один раз
+//! под своим blake3 хэшем (`chunks/ab/abcdef...`, сжат zstd). Бэкап — это манифест
+//! (`manifests/<backup_id>.json`) со списком файлов и их чанков.
+//!
+//! Фиксированный размер выбран намеренно: region-файлы (.mca) выровнены по секторам
+//! 4 KiB и меняются на месте, поэтому изменённый регион даёт лишь несколько новых чанков.
+//! Хранилище общее для всех экземпляров — одинаковые моды хранятся один раз.
+
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::{Read, Write};
+use std::path::{Path, PathBuf};
+use std::time::UNIX_EPOCH;
+
+use crate::error::{LauncherError, Result};
+
+/// Размер чанка
+pub const CHUNK_SIZE: usize = 1024 * 1024;
+
+const MANIFEST_VERSION: u32 = 1;
+const ZSTD_LEVEL: i32 = 3;
+
+/// Файл в бэкапе
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ManifestEntry {
+    /// Путь относительно экземпляра, разделитель `/`
+    pub path: String,
+    pub size: u64,
+    /// mtime (unix, наносекунды) — для пропуска неизменённых файлов при следующем бэкапе
+    pub modified: i64,
+    /// blake3 хэши чанков по порядку
+    pub chunks: Vec<String>,
+}
+
+/// Манифест бэкапа
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Manifest {
+    pub version: u32,
+    pub backup_id: String,
+    pub instance_id: String,
+    pub created_at: String,
+    pub files: Vec<ManifestEntry>,
+}
+
+impl Manifest {
+    pub fn new(
+        backup_id: &str,
+        instance_id: &str,
+        created_at: &str,
+        files: Vec<ManifestEntry>,
+    ) -> Self {
+        Self {
+            version: MANIFEST_VERSION,
+            backup_id: backup_id.to_string(),
+            instance_id: instance_id.to_string(),
+            created_at: created_at.to_string(),
+            files,
+        }
+    }
+
+    pub fn total_size(&self) -> u64 {
+        self.files.iter().map(|f| f.size).sum()
+    }
+}
+
+/// Статистика записи бэкапа в хранилище
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct StoreStats {
+    /// Файлы, взятые из предыдущего манифеста без чтения
+    pub unchanged_files: u32,
+    /// Новые чанки, записанные в хранилище
+    pub new_chunks: u32,
+    /// Сжатый размер новых чанков
+    pub new_bytes: u64,
+}
+
+/// Результат проверки целостности
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct VerifyReport {
+    pub checked_backups: u32,
+    pub checked_chunks: u32,
+    pub missing_chunks: Vec<String>,
+    pub corrupted_chunks: Vec<String>,
+    /// Бэкапы, которые нельзя восстановить полностью
+    pub damaged_backups: Vec<String>,
+}
+
+impl VerifyReport {
+    pub fn is_ok(&self) -> bool {
+        self.missing_chunks.is_empty() && self.corrupted_chunks.is_empty()
+    }
+}
+
+/// Результат сборки мусора
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GcReport {
+    pub removed_manifests: u32,
+    pub removed_chunks: u32,
+    pub freed_bytes: u64,
+    pub kept_chunks: u32,
+}
+
+/// Хранилище чанков. Все методы синхронные — вызывать через spawn_blocking.
+pub struct ChunkStore {
+    root: PathBuf,
+}
+
+impl ChunkStore {
+    pub fn new(root: impl Into<PathBuf>) -> Self {
+        Self { root: root.into() }
+    }
+
+    fn chunks_dir(&self) -> PathBuf {
+        self.root.join("chunks")
+    }
+
+    fn manifests_dir(&self) -> PathBuf {
+        self.root.join("manifests")
+    }
+
+    pub(crate) fn chunk_path(&self, hash: &str) -> PathBuf {
+        self.chunks_dir()
+            .join(&hash[..2.min(hash.len())])
+            .join(hash)
+    }
+
+    pub fn manifest_path(&self, backup_id: &str) -> PathBuf {
+        self.manifests_dir().join(format!("{}.json", backup_id))
+    }
+
+    /// Разбить файлы на чанки и сохранить новые. Файлы с тем же размером и mtime,
+    /// что в `previous`, не читаются повторно.
+    pub fn store_files(
+        &self,
+        base: &Path,
+        files: &[PathBuf],
+        previous: Option<&Manifest>,
+    ) -> Result<(Vec<ManifestEntry>, StoreStats)> {
+        let previous: HashMap<&str, &ManifestEntry> = previous
+            .map(|m| m.files.iter().map(|f| (f.path.as_str(), f)).collect())
+            .unwrap_or_default();
+
+        let mut stats = StoreStats::default();
+        let mut entries = Vec::with_capacity(files.len());
+        let mut buffer = vec![0u8; CHUNK_SIZE];
+
+        for file_path in files {
+            let Ok(relative) = file_path.strip_prefix(base) else {
+                continue;
+            };
+            let relative = relative_to_string(relative);
+            let metadata = match fs::metadata(file_path) {
+                Ok(metadata) => metadata,
+                // Файл удалили между сбором списка и чтением
+                Err(e) if e.kind() == std::io::ErrorKind::NotFound => continue,
+                Err(e) => return Err(e.into()),
+            };
+            let size = metadata.len();
+            let modified = modified_nanos(&metadata);
+
+            if let Some(prev) = previous.get(relative.as_str()) {
+                if prev.size == size
+                    && prev.modified == modified
+                    && prev.chunks.iter().all(|h| self.chunk_path(h).exists())
+                {
+                    stats.unchanged_files += 1;
+                    entries.push((*prev).clone());
+                    continue;
+                }
+            }
+
+            let mut file = fs::File::open(file_path)?;
+            let mut chunks = Vec::new();
+            loop {
+                let read = read_full(&mut file, &mut buffer)?;
+                if read == 0 {
+                    break;
+                }
+                let (hash, written) = self.put_chunk(&buffer[..read])?;
+                if let Some(bytes) = written {
+                    stats.new_chunks += 1;
+                    stats.new_bytes += bytes;
+                }
+                chunks.push(hash);
+                if read < CHUNK_SIZE {
+                    break;
+                }
+            }
+
+            entries.push(ManifestEntry {
+                path: relative,
+                size,
+                modified,
+                chunks,
+            });
+        }
+
+        Ok((entries, stats))
+    }
+
+    /// Сохранить чанк. Возвращает хэш и сжатый размер, если чанк новый.
+    fn put_chunk(&self, data: &[u8]) -> Result<(String, Option<u64>)> {
+        let hash = blake3::hash(data).to_hex().to_string();
+        let path = self.chunk_path(&hash);
+        if path.exists() {
+            return Ok((hash, None));
+        }
+
+        let compressed = zstd::encode_all(data, ZSTD_LEVEL)?;
+        write_chunk_file(&path, &compressed)?;
+
+        Ok((hash, Some(compressed.len() as u64)))
+    }
+
+    pub fn has_chunk(&self, hash: &str) -> bool {
+        self.chunk_path(hash).exists()
+    }
+
+    /// Принять сжатый чанк из внешнего источника (удалённой копии бэкапа).
+    /// Содержимое проверяется по хэшу до записи.
+    pub fn import_chunk(&self, hash: &str, compressed: &[u8]) -> Result<()> {
+        if !is_chunk_hash(hash) {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Invalid chunk hash: {}",
+                hash
+            )));
+        }
+        let data = zstd::decode_all(compressed)?;
+        let actual = blake3::hash(&data).to_hex().to_string();
+        if actual != hash {
+            return Err(LauncherError::HashMismatch {
+                expected: hash.to_string(),
+                actual,
+            });
+        }
+
+        let path = self.chunk_path(hash);
+        if !path.exists() {
+            write_chunk_file(&path, compressed)?;
+        }
+        Ok(())
+    }
+
+    fn read_chunk(&self, hash: &str) -> Result<Vec<u8>> {
+        let compressed = fs::read(self.chunk_path(hash))?;
+        Ok(zstd::decode_all(compressed.as_slice())?)
+    }
+
+    /// Записать манифест нового бэкапа. Существующий манифест не перезаписывается:
+    /// иначе запись в БД другого бэкапа указывала бы на чужие данные.
+    pub fn write_manifest(&self, manifest: &Manifest) -> Result<PathBuf> {
+        let path = self.manifest_path(&manifest.backup_id);
+        fs::create_dir_all(self.manifests_dir())?;
+        if path.exists() {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Backup manifest {} already exists",
+                manifest.backup_id
+            )));
+        }
+
+        let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+        fs::write(&tmp, serde_json::to_vec(manifest)?)?;
+        if let Err(e) = fs::rename(&tmp, &path) {
+            let _ = fs::remove_file(&tmp);
+            return Err(e.into());
+        }
+        Ok(path)
+    }
+
+    pub fn read_manifest(path: &Path) -> Result<Manifest> {
+        let manifest: Manifest = serde_json::from_slice(&fs::read(path)?)?;
+        if manifest.version > MANIFEST_VERSION {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Backup manifest version {} is newer than supported {}",
+                manifest.version, MANIFEST_VERSION
+            )));
+        }
+        Ok(manifest)
+    }
+
+    /// Собрать файлы бэкапа в `target_dir`. `filter` отбирает восстанавливаемые записи.
+    pub fn restore(
+        &self,
+        manifest: &Manifest,
+        target_dir: &Path,
+        filter: impl Fn(&ManifestEntry) -> bool,
+    ) -> Result<u32> {
+        let mut restored = 0;
+
+        for entry in manifest.files.iter().filter(|e| filter(e)) {
+            let target = safe_join(target_dir, &entry.path)?;
+            if let Some(parent) = target.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            // Собираем во временный файл рядом, чтобы при ошибке не испортить живой файл
+            // Суффикс к полному имени: a.toml и a.json не должны делить один tmp
+            let tmp = target.with_file_name(format!(
+                "{}.stuzhik-restore",
+                target
+                    .file_name()
+                    .map(|n| n.to_string_lossy().to_string())
+                    .unwrap_or_default()
+            ));
+            let write = || -> Result<()> {
+                let mut file = fs::File::create(&tmp)?;
+                for hash in &entry.chunks {
+                    let data = self.read_chunk(hash).map_err(|e| {
+                        LauncherError::InvalidConfig(format!(
+                            "Backup chunk {} for {} is unreadable: {}",
+                            hash, entry.path, e
+                        ))
+                    })?;
+                    file.write_all(&data)?;
+                }
+                drop(file);
+                fs::rename(&tmp, &target)?;
+                Ok(())
+            };
+            if let Err(e) = write() {
+                // Не оставляем недописанный временный файл рядом с живыми
+                let _ = fs::remove_file(&tmp);
+                return Err(e);
+            }
+            restored += 1;
+        }
+
+        Ok(restored)
+    }
+
+    /// Проверить, что все чанки манифестов есть и совпадают с хэшем
+    pub fn verify(&self, manifests: &[Manifest]) -> VerifyReport {
+        let mut report = VerifyReport::default();
+        let mut status: HashMap<String, bool> = HashMap::new();
+
+        for manifest in manifests {
+            report.checked_backups += 1;
+            let mut damaged = false;
+
+            for hash in manifest.files.iter().flat_map(|f| &f.chunks) {
+                let ok = match status.get(hash) {
+                    Some(ok) => *ok,
+                    None => {
+                        report.checked_chunks += 1;
+                        let ok = match self.read_chunk(hash) {
+                            Ok(data) => blake3::hash(&data).to_hex().as_str() == hash,
+                            Err(_) if !self.chunk_path(hash).exists() => {
+                                report.missing_chunks.push(hash.clone());
+                                status.insert(hash.clone(), false);
+                                damaged = true;
+                                continue;
+                            }
+                            Err(_) => false,
+                        };
+                        if !ok {
+                            report.corrupted_chunks.push(hash.clone());
+                        }
+                        status.insert(hash.clone(), ok);
+                        ok
+                    }
+                };
+                damaged |= !ok;
+            }
+
+            if damaged {
+                report.damaged_backups.push(manifest.backup_id.clone());
+            }
+        }
+
+        report
+    }
+
+    /// Все манифесты хранилища
+    pub fn manifests(&self) -> Result<Vec<Manifest>> {
+        let dir = self.manifests_dir();
+        if !dir.exists() {
+            return Ok(Vec::new());
+        }
+
+        let mut manifests = Vec::new();
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().is_some_and(|e| e == "json") {
+                match Self::read_manifest(&path) {
+                    Ok(manifest) => manifests.push(manifest),
+                    Err(e) => log::warn!("Skipping unreadable manifest {:?}: {}", path, e),
+                }
+            }
+        }
+        Ok(manifests)
+    }
+
+    /// Удалить манифесты, которых нет в `live_backups`, и чанки, на которые никто не ссылается.
+    /// Нечитаемые манифесты не трогаем, поэтому при их наличии чанки не удаляются вовсе.
+    pub fn gc(&self, live_backups: &HashSet<String>) -> Result<GcReport> {
+        let mut report = GcReport::default();
+        let mut referenced: HashSet<String> = HashSet::new();
+
+        let dir = self.manifests_dir();
+        if dir.exists() {
+            for entry in fs::read_dir(&dir)? {
+                let path = entry?.path();
+                if path.extension().is_none_or(|e| e != "json") {
+                    continue;
+                }
+                let manifest = match Self::read_manifest(&path) {
+                    Ok(manifest) => manifest,
+                    Err(e) => {
+                        log::warn!(
+                            "Unreadable manifest {:?}, skipping chunk sweep: {}",
+                            path,
+                            e
+                        );
+                        return Ok(report);
+                    }
+                };
+                if live_backups.contains(&manifest.backup_id) {
+                    referenced.extend(manifest.files.into_iter().flat_map(|f| f.chunks));
+                } else {
+                    fs::remove_file(&path)?;
+                    report.removed_manifests += 1;
+                }
+            }
+        }
+
+        let chunks_dir = self.chunks_dir();
+        if !chunks_dir.exists() {
+            return Ok(report);
+        }
+        for prefix in fs::read_dir(&chunks_dir)? {
+            let prefix = prefix?.path();
+            if !prefix.is_dir() {
+                continue;
+            }
+            for chunk in fs::read_dir(&prefix)? {
+                let chunk = chunk?;
+                let name = chunk.file_name().to_string_lossy().to_string();
+                // Недописанные чанки (.tmp-*) тоже мусор: GC не идёт параллельно с записью
+                if referenced.contains(&name) {
+                    report.kept_chunks += 1;
+                    continue;
+                }
+                let size = chunk.metadata().map(|m| m.len()).unwrap_or(0);
+                fs::remove_file(chunk.path())?;
+                report.removed_chunks += 1;
+                report.freed_bytes += size;
+            }
+        }
+
+        Ok(report)
+    }
+}
+
+/// mtime файла в наносекундах (0 если недоступно)
+pub fn modified_nanos(metadata: &fs::Metadata) -> i64 {
+    metadata
+        .modified()
+        .ok()
+        .and_then(|t| t.duration_since(UNIX_EPOCH).ok())
+        .map(|d| d.as_nanos() as i64)
+        .unwrap_or(0)
+}
+
+/// Хэши чанков файла без записи в хранилище (для сравнения с манифестом)
+pub fn chunk_hashes(path: &Path) -> Result<Vec<String>> {
+    let mut file = fs::File::open(path)?;
+    let mut buffer = vec![0u8; CHUNK_SIZE];
+    let mut hashes = Vec::new();
+    loop {
+        let read = read_full(&mut file, &mut buffer)?;
+        if read == 0 {
+            break;
+        }
+        hashes.push(blake3::hash(&buffer[..read]).to_hex().to_string());
+        if read < CHUNK_SIZE {
+            break;
+        }
+    }
+    Ok(hashes)
+}
+
+/// blake3 в hex — 64 символа
+pub fn is_chunk_hash(value: &str) -> bool {
+    value.len() == 64 && value.bytes().all(|b| b.is_ascii_hexdigit())
+}
+
+/// Записать чанк во временный файл и переименовать, чтобы не оставить обрезанный чанк
+fn write_chunk_file(path: &Path, compressed: &[u8]) -> Result<()> {
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+
+    let tmp = path.with_extension(format!("tmp-{}", uuid::Uuid::new_v4()));
+    {
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(compressed)?;
+        file.sync_all()?;
+    }
+    if let Err(e) = fs::rename(&tmp, path) {
+        let _ = fs::remove_file(&tmp);
+        if !path.exists() {
+            return Err(e.into());
+        }
+    }
+    Ok(())
+}
+
+pub(crate) fn relative_to_string(path: &Path) -> String {
+    path.components()
+        .map(|c| c.as_os_str().to_string_lossy())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Путь из манифеста внутри `base` (защита от `..` в подложенном манифесте)
+pub(crate) fn safe_join(base: &Path, relative: &str) -> Result<PathBuf> {
+    let mut path = base.to_path_buf();
+    for part in relative.split('/') {
+        if part.is_empty()
+            || part == "."
+            || part == ".."
+            || part.contains('\\')
+            || part.contains(':')
+        {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Invalid path in backup manifest: {}",
+                relative
+            )));
+        }
+        path.push(part);
+    }
+    Ok(path)
+}
+
+/// Прочитать до заполнения буфера или конца файла
+fn read_full(file: &mut fs::File, buffer: &mut [u8]) -> std::io::Result<usize> {
+    let mut filled = 0;
+    while filled < buffer.len() {
+        match file.read(&mut buffer[filled..]) {
+            Ok(0) => break,
+            Ok(n) => filled += n,
+            Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        }
+    }
+    Ok(filled)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_dir(name: &str) -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("stuzhik_{}_{}", name, uuid::Uuid::new_v4()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    fn write(base: &Path, relative: &str, data: &[u8]) -> PathBuf {
+        let path = base.join(relative);
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, data).unwrap();
+        path
+    }
+
+    fn backup(
+        store: &ChunkStore,
+        base: &Path,
+        id: &str,
+        previous: Option<&Manifest>,
+    ) -> (Manifest, StoreStats) {
+        let files = vec![base.join("mods/big.jar"), base.join("config/a.toml")];
+        let (entries, stats) = store.store_files(base, &files, previous).unwrap();
+        let manifest = Manifest::new(id, "inst", "2024-06-01T00:00:00Z", entries);
+        store.write_manifest(&manifest).unwrap();
+        (manifest, stats)
+    }
+
+    #[test]
+    fn test_dedup_incremental_and_restore() {
+        let root = temp_dir("store");
+        let instance = temp_dir("instance");
+        let store = ChunkStore::new(root.join("store"));
+
+        // 2.5 чанка: третий короче остальных
+        let big: Vec<u8> = (0..CHUNK_SIZE * 5 / 2).map(|i| (i % 251) as u8).collect();
+        write(&instance, "mods/big.jar", &big);
+        write(&instance, "config/a.toml", b"enabled = true");
+
+        let (first, stats) = backup(&store, &instance, "b1", None);
+        assert_eq!(first.files[0].chunks.len(), 3);
+        assert_eq!(stats.new_chunks, 4);
+
+        // Повторный бэкап без изменений ничего не пишет
+        let (_, stats) = backup(&store, &instance, "b2", Some(&first));
+        assert_eq!(stats.unchanged_files, 2);
+        assert_eq!(stats.new_chunks, 0);
+
+        // Изменение во втором чанке — один новый чанк, без подсказки previous тоже
+        let mut changed = big.clone();
+        changed[CHUNK_SIZE + 10] ^= 0xff;
+        write(&instance, "mods/big.jar", &changed);
+        let (third, stats) = backup(&store, &instance, "b3", None);
+        assert_eq!(stats.new_chunks, 1);
+
+        let target = temp_dir("restore");
+        let restored = store.restore(&third, &target, |_| true).unwrap();
+        assert_eq!(restored, 2);
+        assert_eq!(fs::read(target.join("mods/big.jar")).unwrap(), changed);
+        assert_eq!(
+            fs::read(target.join("config/a.toml")).unwrap(),
+            b"enabled = true"
+        );
+
+        // Выборочное восстановление
+        let partial = temp_dir("partial");
+        store
+            .restore(&first, &partial, |e| e.path.starts_with("config/"))
+            .unwrap();
+        assert!(partial.join("config/a.toml").exists());
+        assert!(!partial.join("mods/big.jar").exists());
+
+        for dir in [root, instance, target, partial] {
+            let _ = fs::remove_dir_all(dir);
+        }
+    }
+
+    #[test]
+    fn test_verify_and_gc() {
+        let root = temp_dir("store");
+        let instance = temp_dir("instance");
+        let store = ChunkStore::new(root.join("store"));
+
+        write(&instance, "mods/big.jar", b"jar v1");
+        write(&instance, "config/a.toml", b"shared");
+        let (first, _) = backup(&store, &instance, "b1", None);
+        write(&instance, "mods/big.jar", b"jar v2");
+        let (second, _) = backup(&store, &instance, "b2", None);
+
+        assert!(store.verify(&[first.clone(), second.clone()]).is_ok());
+
+        // b1 удалён из БД: его манифест и уникальный чанк уходят, общий остаётся
+        let live: HashSet<String> = ["b2".to_string()].into_iter().collect();
+        let report = store.gc(&live).unwrap();
+        assert_eq!(report.removed_manifests, 1);
+        assert_eq!(report.removed_chunks, 1);
+        assert_eq!(report.kept_chunks, 2);
+        assert!(store.verify(&[second.clone()]).is_ok());
+
+        // Порча чанка обнаруживается
+        let hash = &second.files[0].chunks[0];
+        fs::write(
+            store.chunk_path(hash),
+            zstd::encode_all(&b"evil"[..], 3).unwrap(),
+        )
+        .unwrap();
+        let report = store.verify(&[second]);
+        assert_eq!(report.corrupted_chunks, vec![hash.clone()]);
+        assert_eq!(report.damaged_backups, vec!["b2".to_string()]);
+
+        let _ = fs::remove_dir_all(root);
+        let _ = fs::remove_dir_all(instance);
+    }
+
+    #[test]
+    fn test_import_chunk_checks_hash() {
+        let root = temp_dir("import");
+        let store = ChunkStore::new(&root);
+
+        let data = b"region data";
+        let hash = blake3::hash(data).to_hex().to_string();
+        let compressed = zstd::encode_all(&data[..], 3).unwrap();
+
+        assert!(store.import_chunk("../../escape", &compressed).is_err());
+        let other = blake3::hash(b"other").to_hex().to_string();
+        assert!(store.import_chunk(&other, &compressed).is_err());
+        assert!(!store.has_chunk(&other));
+
+        store.import_chunk(&hash, &compressed).unwrap();
+        assert_eq!(store.read_chunk(&hash).unwrap(), data);
+
+        let _ = fs::remove_dir_all(root);
+    }
+
+    #[test]
+    fn test_safe_join_rejects_traversal() {
+        let base = Path::new("/tmp/instance");
+        assert!(safe_join(base, "saves/world/level.dat").is_ok());
+        assert!(safe_join(base, "../etc/passwd").is_err());
+        assert!(safe_join(base, "config//x").is_err());
+    }
+}
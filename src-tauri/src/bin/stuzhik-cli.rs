@@ -0,0 +1,3 @@
+fn main() {
+    std::process::exit(stuzhik_lib::run_cli())
+}
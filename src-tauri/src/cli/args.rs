@@ -0,0 +1,162 @@
+//! Minimal argument parser for `stuzhik-cli`
+//!
+//! Без внешних зависимостей: опции `--name value` / `--name=value`, флаги `--flag`,
+//! всё остальное — позиционные аргументы в исходном порядке.
+
+use crate::error::{LauncherError, Result};
+
+#[derive(Debug, Clone)]
+pub struct Args {
+    items: Vec<String>,
+}
+
+impl Args {
+    pub fn new(items: impl IntoIterator<Item = String>) -> Self {
+        Self {
+            items: items.into_iter().collect(),
+        }
+    }
+
+    /// Забрать флаг (`--json`, `-v`). Возвращает true, если он был.
+    pub fn flag(&mut self, names: &[&str]) -> bool {
+        let before = self.items.len();
+        self.items.retain(|item| !names.contains(&item.as_str()));
+        self.items.len() != before
+    }
+
+    /// Сколько раз встретился флаг (`-vv` считается как два `-v`)
+    pub fn count(&mut self, short: char) -> usize {
+        let mut count = 0;
+        self.items.retain(|item| {
+            let is_short_run = item.len() > 1
+                && item.starts_with('-')
+                && !item.starts_with("--")
+                && item[1..].chars().all(|c| c == short);
+            if is_short_run {
+                count += item.len() - 1;
+            }
+            !is_short_run
+        });
+        count
+    }
+
+    /// Забрать опцию со значением (`--name value` или `--name=value`)
+    pub fn option(&mut self, name: &str) -> Result<Option<String>> {
+        let prefix = format!("{}=", name);
+
+        if let Some(pos) = self.items.iter().position(|i| i.starts_with(&prefix)) {
+            let item = self.items.remove(pos);
+            return Ok(Some(item[prefix.len()..].to_string()));
+        }
+
+        if let Some(pos) = self.items.iter().position(|i| i == name) {
+            if pos + 1 >= self.items.len() {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "Option {} requires a value",
+                    name
+                )));
+            }
+            self.items.remove(pos);
+            return Ok(Some(self.items.remove(pos)));
+        }
+
+        Ok(None)
+    }
+
+    /// Опция, разобранная в число
+    pub fn option_parsed<T: std::str::FromStr>(&mut self, name: &str) -> Result<Option<T>> {
+        match self.option(name)? {
+            Some(value) => value.parse().map(Some).map_err(|_| {
+                LauncherError::InvalidConfig(format!("Invalid value for {}: {}", name, value))
+            }),
+            None => Ok(None),
+        }
+    }
+
+    /// Следующий позиционный аргумент (None если закончились)
+    pub fn next(&mut self) -> Option<String> {
+        let pos = self
+            .items
+            .iter()
+            .position(|i| !i.starts_with('-') || i == "-")?;
+        Some(self.items.remove(pos))
+    }
+
+    /// Обязательный позиционный аргумент
+    pub fn required(&mut self, what: &str) -> Result<String> {
+        self.next()
+            .ok_or_else(|| LauncherError::InvalidConfig(format!("Missing argument: <{}>", what)))
+    }
+
+    /// Все оставшиеся позиционные аргументы
+    pub fn rest(&mut self) -> Vec<String> {
+        let mut rest = Vec::new();
+        while let Some(item) = self.next() {
+            rest.push(item);
+        }
+        rest
+    }
+
+    /// Ошибка, если остались неразобранные аргументы (опечатки в опциях и т.п.)
+    pub fn finish(&self) -> Result<()> {
+        if self.items.is_empty() {
+            Ok(())
+        } else {
+            Err(LauncherError::InvalidConfig(format!(
+                "Unexpected arguments: {}",
+                self.items.join(" ")
+            )))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Args {
+        Args::new(s.split_whitespace().map(String::from))
+    }
+
+    #[test]
+    fn test_options_and_positionals() {
+        let mut a = args("instance create MyPack --version 1.20.1 --loader=fabric --server");
+        assert_eq!(a.option("--version").unwrap().as_deref(), Some("1.20.1"));
+        assert_eq!(a.option("--loader").unwrap().as_deref(), Some("fabric"));
+        assert!(a.flag(&["--server"]));
+        assert!(!a.flag(&["--json"]));
+        assert_eq!(a.required("group").unwrap(), "instance");
+        assert_eq!(a.required("command").unwrap(), "create");
+        assert_eq!(a.required("name").unwrap(), "MyPack");
+        assert!(a.next().is_none());
+        assert!(a.finish().is_ok());
+    }
+
+    #[test]
+    fn test_option_without_value() {
+        let mut a = args("mods add abc --version");
+        assert!(a.option("--version").is_err());
+    }
+
+    #[test]
+    fn test_verbosity_count() {
+        let mut a = args("-vv instance list -v");
+        assert_eq!(a.count('v'), 3);
+        assert_eq!(a.rest(), vec!["instance", "list"]);
+    }
+
+    #[test]
+    fn test_unknown_option_is_reported() {
+        let mut a = args("instance list --jsno");
+        a.flag(&["--json"]);
+        a.rest();
+        assert!(a.finish().is_err());
+    }
+
+    #[test]
+    fn test_option_parsed() {
+        let mut a = args("--memory 4096 --port abc");
+        assert_eq!(a.option_parsed::<i32>("--memory").unwrap(), Some(4096));
+        assert!(a.option_parsed::<i32>("--port").is_err());
+    }
+}
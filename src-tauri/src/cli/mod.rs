@@ -0,0 +1,746 @@
+//! Headless command-line interface (`stuzhik-cli`)
+//!
+//! Работает с той же базой и папкой данных, что и GUI, но без Tauri окна:
+//! события прогресса уходят в лог через `EventSink::Headless`.
+//! Подходит для скриптов, CI и серверов без графики.
+
+mod args;
+
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, Instant};
+
+use args::Args;
+
+use crate::db::get_db_conn;
+use crate::downloader::DownloadManager;
+use crate::error::{LauncherError, Result};
+use crate::events::EventSink;
+use crate::instances::execution::{
+    is_process_running, spawn_instance_headless, terminate_java_process,
+    verify_and_kill_orphaned_process,
+};
+use crate::mods::ModManager;
+use crate::stzhk::{ExportOptions, StzhkManager};
+use crate::types::{CreateInstanceRequest, Instance, InstanceStatus, InstanceType};
+
+/// Tauri identifier из tauri.conf.json — должен совпадать, иначе CLI увидит другую базу
+const APP_IDENTIFIER: &str = "ru.sibxodev.stuzhik";
+
+const USAGE: &str = "\
+Usage: stuzhik-cli [--data-dir <path>] [-v...] <command>
+
+Instances:
+  instance list [--json]
+  instance create <name> --version <mc> [--loader <loader>] [--loader-version <v>]
+                  [--server] [--port <port>] [--memory <mb>] [--no-wait]
+  instance launch <instance>
+
+Mods:
+  mods list <instance> [--json]
+  mods add <instance> <slug|file.jar> [--version <version-id>]
+  mods update <instance> [<slug>...] [--check]
+  mods remove <instance> <slug|id>...
+
+Modpacks:
+  pack export <instance> [--output <dir>] [--name <n>] [--version <v>] [--author <a>]
+              [--description <d>] [--embed-mods] [--no-overrides]
+  pack install <file.stzhk> [--name <instance-name>] [--no-wait]
+
+Servers:
+  server start <instance> [--accept-eula]
+  server stop <instance> [--timeout <seconds>] [--force]
+
+Editor:
+  lsp <instance>          KubeJS language server on stdin/stdout
+
+<instance> is an instance id or its exact name.
+Data directory defaults to the launcher's own (override with STUZHIK_DATA_DIR).";
+
+/// Точка входа CLI. Возвращает код выхода процесса.
+pub fn run() -> i32 {
+    let mut args = Args::new(std::env::args().skip(1));
+
+    if args.flag(&["-h", "--help", "help"]) {
+        println!("{}", USAGE);
+        return 0;
+    }
+
+    let verbosity = args.count('v');
+    init_logging(verbosity);
+
+    let data_dir = match args.option("--data-dir") {
+        Ok(dir) => dir.map(PathBuf::from).unwrap_or_else(default_data_dir),
+        Err(e) => return usage_error(&e),
+    };
+
+    if let Err(e) = init_storage(&data_dir) {
+        eprintln!(
+            "error: failed to open launcher data at {:?}: {}",
+            data_dir, e
+        );
+        return 1;
+    }
+
+    let runtime = match tokio::runtime::Runtime::new() {
+        Ok(rt) => rt,
+        Err(e) => {
+            eprintln!("error: failed to start async runtime: {}", e);
+            return 1;
+        }
+    };
+    // Фоновые задачи установки используют tauri::async_runtime::spawn
+    tauri::async_runtime::set(runtime.handle().clone());
+
+    match runtime.block_on(dispatch(args)) {
+        Ok(code) => code,
+        Err(CliError::Usage(e)) => usage_error(&e),
+        Err(CliError::Failed(e)) => {
+            eprintln!("error: {}", e);
+            1
+        }
+    }
+}
+
+/// Ошибка команды: неверные аргументы (код 2) или ошибка выполнения (код 1)
+enum CliError {
+    Usage(LauncherError),
+    Failed(LauncherError),
+}
+
+impl From<LauncherError> for CliError {
+    fn from(e: LauncherError) -> Self {
+        CliError::Failed(e)
+    }
+}
+
+type CliResult<T> = std::result::Result<T, CliError>;
+
+/// Ошибки разбора аргументов помечаются как usage
+trait UsageExt<T> {
+    fn usage(self) -> CliResult<T>;
+}
+
+impl<T> UsageExt<T> for Result<T> {
+    fn usage(self) -> CliResult<T> {
+        self.map_err(CliError::Usage)
+    }
+}
+
+fn usage_error(e: &LauncherError) -> i32 {
+    eprintln!("error: {}\n\n{}", e, USAGE);
+    2
+}
+
+fn init_logging(verbosity: usize) {
+    let level = match verbosity {
+        0 => log::LevelFilter::Warn,
+        1 => log::LevelFilter::Info,
+        2 => log::LevelFilter::Debug,
+        _ => log::LevelFilter::Trace,
+    };
+    env_logger::Builder::new()
+        .filter_level(level)
+        .parse_default_env()
+        .format_target(false)
+        .init();
+}
+
+fn default_data_dir() -> PathBuf {
+    if let Ok(dir) = std::env::var("STUZHIK_DATA_DIR") {
+        return PathBuf::from(dir);
+    }
+    directories::BaseDirs::new()
+        .map(|dirs| dirs.data_dir().join(APP_IDENTIFIER))
+        .unwrap_or_else(|| PathBuf::from(".").join(APP_IDENTIFIER))
+}
+
+/// Та же инициализация, что и в `run()` GUI: пути и база данных
+fn init_storage(data_dir: &Path) -> Result<()> {
+    std::fs::create_dir_all(data_dir)?;
+    crate::paths::init_paths(data_dir.to_path_buf())?;
+
+    let db_path = data_dir.join("launcher.db").to_string_lossy().to_string();
+    crate::db::DB_PATH
+        .set(db_path.clone())
+        .map_err(|_| LauncherError::InvalidConfig("DB path already set".into()))?;
+    crate::db::init_db(&db_path)?;
+    Ok(())
+}
+
+async fn dispatch(mut args: Args) -> CliResult<i32> {
+    let group = args.required("command").usage()?;
+    match group.as_str() {
+        "instance" => instance_command(args).await,
+        "mods" => mods_command(args).await,
+        "pack" => pack_command(args).await,
+        "server" => server_command(args).await,
+        "lsp" => {
+            let query = args.required("instance").usage()?;
+            args.finish().usage()?;
+
+            let instance = find_instance(&query).await?;
+            crate::code_editor::language_server::server::run_stdio(&instance.id).await?;
+            Ok(0)
+        }
+        other => Err(CliError::Usage(LauncherError::InvalidConfig(format!(
+            "Unknown command: {}",
+            other
+        )))),
+    }
+}
+
+fn unknown_subcommand(group: &str, sub: &str) -> CliError {
+    CliError::Usage(LauncherError::InvalidConfig(format!(
+        "Unknown subcommand: {} {}",
+        group, sub
+    )))
+}
+
+// ========== instance ==========
+
+async fn instance_command(mut args: Args) -> CliResult<i32> {
+    let sub = args.required("subcommand").usage()?;
+    match sub.as_str() {
+        "list" => {
+            let json = args.flag(&["--json"]);
+            args.finish().usage()?;
+
+            let instances = crate::instances::list_instances().await?;
+            if json {
+                print_json(&instances)?;
+            } else {
+                print_instances(&instances);
+            }
+            Ok(0)
+        }
+        "create" => {
+            let version = args.option("--version").usage()?;
+            let loader = args.option("--loader").usage()?;
+            let loader_version = args.option("--loader-version").usage()?;
+            let port = args.option_parsed::<i32>("--port").usage()?;
+            let memory = args.option_parsed::<i32>("--memory").usage()?;
+            let server = args.flag(&["--server"]);
+            let no_wait = args.flag(&["--no-wait"]);
+            let name = args.required("name").usage()?;
+            args.finish().usage()?;
+
+            let version = version.ok_or_else(|| {
+                CliError::Usage(LauncherError::InvalidConfig("--version is required".into()))
+            })?;
+
+            let req = CreateInstanceRequest {
+                name,
+                game_type: None,
+                version,
+                loader: loader.unwrap_or_else(|| "vanilla".to_string()),
+                loader_version,
+                instance_type: if server { "server" } else { "client" }.to_string(),
+                memory_min: None,
+                memory_max: memory,
+                java_args: None,
+                game_args: None,
+                port,
+                username: None,
+                notes: None,
+            };
+
+            let instance =
+                crate::instances::lifecycle::create_instance_with_events(req, EventSink::Headless)
+                    .await?;
+            println!("Created instance {} ({})", instance.name, instance.id);
+
+            if no_wait {
+                return Ok(0);
+            }
+            wait_for_installation(&instance.id).await?;
+            Ok(0)
+        }
+        "launch" => {
+            let query = args.required("instance").usage()?;
+            args.finish().usage()?;
+
+            let instance = find_instance(&query).await?;
+            Ok(run_foreground(&instance).await?)
+        }
+        other => Err(unknown_subcommand("instance", other)),
+    }
+}
+
+fn print_instances(instances: &[Instance]) {
+    println!(
+        "{:<36}  {:<24}  {:<6}  {:<10}  {:<9}  STATUS",
+        "ID", "NAME", "TYPE", "VERSION", "LOADER"
+    );
+    for instance in instances {
+        println!(
+            "{:<36}  {:<24}  {:<6}  {:<10}  {:<9}  {}",
+            instance.id,
+            truncate(&instance.name, 24),
+            instance.instance_type.as_str(),
+            instance.version,
+            instance.loader.as_str(),
+            instance.status.as_str()
+        );
+    }
+}
+
+fn truncate(s: &str, max: usize) -> String {
+    if s.chars().count() <= max {
+        s.to_string()
+    } else {
+        let mut out: String = s.chars().take(max - 1).collect();
+        out.push('…');
+        out
+    }
+}
+
+fn print_json<T: serde::Serialize>(value: &T) -> Result<()> {
+    println!("{}", serde_json::to_string_pretty(value)?);
+    Ok(())
+}
+
+/// Найти экземпляр по id или точному имени
+async fn find_instance(query: &str) -> Result<Instance> {
+    let instances = crate::instances::list_instances().await?;
+
+    if let Some(instance) = instances.iter().find(|i| i.id == query) {
+        return Ok(instance.clone());
+    }
+
+    let by_name: Vec<&Instance> = instances
+        .iter()
+        .filter(|i| i.name.eq_ignore_ascii_case(query))
+        .collect();
+
+    match by_name.as_slice() {
+        [instance] => Ok((*instance).clone()),
+        [] => Err(LauncherError::InstanceNotFound(query.to_string())),
+        _ => Err(LauncherError::InvalidConfig(format!(
+            "Several instances are named '{}', use the id instead",
+            query
+        ))),
+    }
+}
+
+/// Установка идёт в фоне — ждём, пока статус не уйдёт из `installing`
+async fn wait_for_installation(instance_id: &str) -> Result<()> {
+    let mut last_step: Option<String> = None;
+
+    loop {
+        let instance = crate::instances::get_instance(instance_id.to_string()).await?;
+
+        if instance.installation_step != last_step {
+            if let Some(step) = &instance.installation_step {
+                eprintln!("  {}", step);
+            }
+            last_step = instance.installation_step.clone();
+        }
+
+        match instance.status {
+            InstanceStatus::Installing => {}
+            InstanceStatus::Error => {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "Installation failed: {}",
+                    instance
+                        .installation_error
+                        .unwrap_or_else(|| "unknown error".to_string())
+                )));
+            }
+            _ => {
+                println!("Instance {} is ready", instance.name);
+                return Ok(());
+            }
+        }
+
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+}
+
+// ========== foreground run ==========
+
+fn set_instance_status(instance_id: &str, status: InstanceStatus, pid: Option<u32>) -> Result<()> {
+    let conn = get_db_conn()?;
+    conn.execute(
+        "UPDATE instances SET status = ?1, pid = ?2, updated_at = ?3 WHERE id = ?4",
+        rusqlite::params![
+            status.as_str(),
+            pid,
+            chrono::Utc::now().to_rfc3339(),
+            instance_id
+        ],
+    )?;
+    Ok(())
+}
+
+fn get_instance_pid(instance_id: &str) -> Result<Option<u32>> {
+    let conn = get_db_conn()?;
+    let pid: Option<i64> = conn.query_row(
+        "SELECT pid FROM instances WHERE id = ?1",
+        [instance_id],
+        |row| row.get(0),
+    )?;
+    Ok(pid.map(|p| p as u32))
+}
+
+/// Запустить экземпляр и держать его на переднем плане до выхода.
+/// Вывод игры/сервера идёт в терминал, ввод (консоль сервера) — в процесс.
+async fn run_foreground(instance: &Instance) -> Result<i32> {
+    let download_manager = DownloadManager::headless()?;
+    let mut child = spawn_instance_headless(instance, &download_manager).await?;
+    let pid = child.id();
+
+    set_instance_status(&instance.id, InstanceStatus::Running, Some(pid))?;
+    eprintln!("Started {} (pid {})", instance.name, pid);
+
+    let stdout_thread = child.stdout.take().map(|mut out| {
+        std::thread::spawn(move || {
+            let _ = std::io::copy(&mut out, &mut std::io::stdout());
+        })
+    });
+    let stderr_thread = child.stderr.take().map(|mut err| {
+        std::thread::spawn(move || {
+            let _ = std::io::copy(&mut err, &mut std::io::stderr());
+        })
+    });
+    if let Some(mut stdin) = child.stdin.take() {
+        // Поток не join'им: он завершится вместе с CLI
+        std::thread::spawn(move || {
+            let _ = std::io::copy(&mut std::io::stdin().lock(), &mut stdin);
+        });
+    }
+
+    let mut wait = tokio::task::spawn_blocking(move || child.wait());
+    let status = loop {
+        tokio::select! {
+            result = &mut wait => break result,
+            // Ctrl+C получает и JVM (та же группа процессов) — просто ждём корректного выхода
+            _ = tokio::signal::ctrl_c() => {
+                eprintln!("Waiting for {} to shut down...", instance.name);
+            }
+        }
+    };
+
+    for thread in [stdout_thread, stderr_thread].into_iter().flatten() {
+        let _ = thread.join();
+    }
+    let _ = std::io::stdout().flush();
+
+    set_instance_status(&instance.id, InstanceStatus::Stopped, None)?;
+
+    let status =
+        status.map_err(|e| LauncherError::InvalidConfig(format!("Wait task failed: {}", e)))??;
+    let code = status.code().unwrap_or(1);
+    eprintln!("{} exited with code {}", instance.name, code);
+    Ok(code)
+}
+
+// ========== mods ==========
+
+async fn mods_command(mut args: Args) -> CliResult<i32> {
+    let sub = args.required("subcommand").usage()?;
+    match sub.as_str() {
+        "list" => {
+            let json = args.flag(&["--json"]);
+            let query = args.required("instance").usage()?;
+            args.finish().usage()?;
+
+            let instance = find_instance(&query).await?;
+            let mods = ModManager::list_mods(&instance.id)?;
+            if json {
+                print_json(&mods)?;
+                return Ok(0);
+            }
+
+            println!(
+                "{:<6}  {:<28}  {:<32}  {:<20}  ENABLED",
+                "ID", "SLUG", "NAME", "VERSION"
+            );
+            for m in &mods {
+                println!(
+                    "{:<6}  {:<28}  {:<32}  {:<20}  {}",
+                    m.id,
+                    truncate(&m.slug, 28),
+                    truncate(&m.name, 32),
+                    truncate(&m.version, 20),
+                    if m.enabled { "yes" } else { "no" }
+                );
+            }
+            Ok(0)
+        }
+        "add" => {
+            let version_id = args.option("--version").usage()?;
+            let query = args.required("instance").usage()?;
+            let target = args.required("slug|file.jar").usage()?;
+            args.finish().usage()?;
+
+            let instance = find_instance(&query).await?;
+            let path = PathBuf::from(&target);
+            let installed = if path.extension().is_some_and(|ext| ext == "jar") && path.is_file() {
+                ModManager::install_local(&instance.id, &path, false).await?
+            } else {
+                let download_manager = DownloadManager::headless()?;
+                ModManager::install_from_modrinth(
+                    &instance.id,
+                    &target,
+                    &instance.version,
+                    instance.loader.as_str(),
+                    version_id.as_deref(),
+                    &download_manager,
+                )
+                .await?
+            };
+            println!("Installed {} {}", installed.name, installed.version);
+            Ok(0)
+        }
+        "update" => {
+            let check_only = args.flag(&["--check"]);
+            let query = args.required("instance").usage()?;
+            let slugs = args.rest();
+            args.finish().usage()?;
+
+            let instance = find_instance(&query).await?;
+            let result = ModManager::check_mod_updates(
+                &instance.id,
+                &instance.version,
+                instance.loader.as_str(),
+            )
+            .await?;
+
+            let updates: Vec<_> = result
+                .mods_with_updates
+                .iter()
+                .filter(|u| slugs.is_empty() || slugs.iter().any(|s| s == &u.slug))
+                .collect();
+
+            if updates.is_empty() {
+                println!("All mods are up to date");
+                return Ok(0);
+            }
+
+            for update in &updates {
+                println!(
+                    "{}: {} -> {}",
+                    update.name, update.current_version, update.latest_version
+                );
+            }
+            if check_only {
+                return Ok(0);
+            }
+
+            let download_manager = DownloadManager::headless()?;
+            let mut failed = 0;
+            for update in &updates {
+                match ModManager::update_mod(&instance.id, update.mod_id, &download_manager).await {
+                    Ok(()) => println!("Updated {}", update.name),
+                    Err(e) => {
+                        eprintln!("Failed to update {}: {}", update.name, e);
+                        failed += 1;
+                    }
+                }
+            }
+            Ok(if failed > 0 { 1 } else { 0 })
+        }
+        "remove" => {
+            let query = args.required("instance").usage()?;
+            let targets = args.rest();
+            args.finish().usage()?;
+            if targets.is_empty() {
+                return Err(CliError::Usage(LauncherError::InvalidConfig(
+                    "Missing argument: <slug|id>".into(),
+                )));
+            }
+
+            let instance = find_instance(&query).await?;
+            let mods = ModManager::list_mods(&instance.id)?;
+
+            for target in &targets {
+                let found = mods.iter().find(|m| {
+                    m.slug == *target || m.id.to_string() == *target || m.file_name == *target
+                });
+                match found {
+                    Some(m) => {
+                        ModManager::remove_mod(&instance.id, m.id).await?;
+                        println!("Removed {}", m.name);
+                    }
+                    None => {
+                        return Err(LauncherError::ModNotFound(target.clone()).into());
+                    }
+                }
+            }
+            Ok(0)
+        }
+        other => Err(unknown_subcommand("mods", other)),
+    }
+}
+
+// ========== pack ==========
+
+async fn pack_command(mut args: Args) -> CliResult<i32> {
+    let sub = args.required("subcommand").usage()?;
+    match sub.as_str() {
+        "export" => {
+            let output = args.option("--output").usage()?;
+            let name = args.option("--name").usage()?;
+            let version = args.option("--version").usage()?;
+            let author = args.option("--author").usage()?;
+            let description = args.option("--description").usage()?;
+            let embed_mods = args.flag(&["--embed-mods"]);
+            let no_overrides = args.flag(&["--no-overrides"]);
+            let query = args.required("instance").usage()?;
+            args.finish().usage()?;
+
+            let instance = find_instance(&query).await?;
+            let options = ExportOptions {
+                name: name.unwrap_or_else(|| instance.name.clone()),
+                version: version.unwrap_or_else(|| "1.0.0".to_string()),
+                author: author.unwrap_or_else(|| "Unknown".to_string()),
+                description,
+                embed_mods,
+                include_overrides: !no_overrides,
+                excluded_mods: Vec::new(),
+                excluded_overrides: Vec::new(),
+            };
+            let output_dir = output
+                .map(PathBuf::from)
+                .unwrap_or_else(|| PathBuf::from("."));
+
+            let path = StzhkManager::export_instance(
+                &instance.id,
+                &output_dir,
+                &options,
+                &EventSink::Headless,
+            )
+            .await?;
+            println!("Exported to {}", path.display());
+            Ok(0)
+        }
+        "install" => {
+            let name = args.option("--name").usage()?;
+            let no_wait = args.flag(&["--no-wait"]);
+            let file = args.required("file.stzhk").usage()?;
+            args.finish().usage()?;
+
+            let path = PathBuf::from(&file);
+            // mrpack/CurseForge установщики пока завязаны на AppHandle
+            if path.extension().is_none_or(|ext| ext != "stzhk") {
+                return Err(LauncherError::InvalidConfig(
+                    "Only .stzhk modpacks can be installed from the CLI".into(),
+                )
+                .into());
+            }
+            let name = name.unwrap_or_else(|| {
+                path.file_stem()
+                    .map(|s| s.to_string_lossy().to_string())
+                    .unwrap_or_else(|| "Modpack".to_string())
+            });
+
+            let download_manager = DownloadManager::headless()?;
+            let instance_id =
+                StzhkManager::install(&path, name, Vec::new(), &download_manager).await?;
+            println!("Created instance {}", instance_id);
+
+            if !no_wait {
+                wait_for_installation(&instance_id).await?;
+            }
+            Ok(0)
+        }
+        other => Err(unknown_subcommand("pack", other)),
+    }
+}
+
+// ========== server ==========
+
+async fn server_command(mut args: Args) -> CliResult<i32> {
+    let sub = args.required("subcommand").usage()?;
+    match sub.as_str() {
+        "start" => {
+            let accept_eula = args.flag(&["--accept-eula"]);
+            let query = args.required("instance").usage()?;
+            args.finish().usage()?;
+
+            let instance = require_server(find_instance(&query).await?)?;
+            if accept_eula {
+                crate::server::eula::accept_eula(&instance.dir)
+                    .await
+                    .map_err(|e| LauncherError::InvalidConfig(e.to_string()))?;
+            }
+            Ok(run_foreground(&instance).await?)
+        }
+        "stop" => {
+            let timeout = args.option_parsed::<u64>("--timeout").usage()?;
+            let force = args.flag(&["--force"]);
+            let query = args.required("instance").usage()?;
+            args.finish().usage()?;
+
+            let instance = require_server(find_instance(&query).await?)?;
+            stop_server(&instance, Duration::from_secs(timeout.unwrap_or(60)), force).await?;
+            Ok(0)
+        }
+        other => Err(unknown_subcommand("server", other)),
+    }
+}
+
+fn require_server(instance: Instance) -> Result<Instance> {
+    match instance.instance_type {
+        InstanceType::Server => Ok(instance),
+        InstanceType::Client => Err(LauncherError::InvalidConfig(format!(
+            "{} is not a server instance",
+            instance.name
+        ))),
+    }
+}
+
+/// Остановка сервера: RCON `stop`, если включён, иначе SIGTERM.
+/// С `--force` процесс убивается по истечении таймаута.
+async fn stop_server(instance: &Instance, timeout: Duration, force: bool) -> Result<()> {
+    let pid = get_instance_pid(&instance.id)?
+        .filter(|pid| is_process_running(*pid))
+        .ok_or(LauncherError::InstanceNotRunning)?;
+
+    let mut requested = false;
+    if let Ok(props) = crate::server::properties::load_properties(&instance.dir).await {
+        if props.rcon_enabled() {
+            if let Some(password) = props.rcon_password() {
+                match crate::server::RconClient::connect("127.0.0.1", props.rcon_port(), password)
+                    .await
+                {
+                    Ok(client) => match client.stop().await {
+                        Ok(_) => requested = true,
+                        Err(e) => log::warn!("RCON stop failed: {}", e),
+                    },
+                    Err(e) => log::warn!("RCON connect failed: {}", e),
+                }
+            }
+        }
+    }
+
+    if !requested && !terminate_java_process(pid) {
+        return Err(LauncherError::InvalidConfig(format!(
+            "Failed to signal process {}",
+            pid
+        )));
+    }
+    eprintln!("Stopping {}...", instance.name);
+
+    let deadline = Instant::now() + timeout;
+    while is_process_running(pid) {
+        if Instant::now() >= deadline {
+            if !force {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "{} did not stop within {}s (use --force to kill it)",
+                    instance.name,
+                    timeout.as_secs()
+                )));
+            }
+            eprintln!("Timeout reached, killing process {}", pid);
+            verify_and_kill_orphaned_process(pid);
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(500)).await;
+    }
+
+    set_instance_status(&instance.id, InstanceStatus::Stopped, None)?;
+    println!("{} stopped", instance.name);
+    Ok(())
+}
@@ -1,2 +1,212 @@
-// Completion provider logic
-// TODO: Реализовать автодополнение для KubeJS
+//! Completion provider: ID предметов/блоков, тегов и модов внутри строковых литералов
+
+use std::collections::HashSet;
+
+use super::{
+    count_prefix_len, kinds, literal_at, CompletionItem, LineIndex, Position, Range, TextEdit,
+};
+use crate::code_editor::minecraft_data::{MinecraftDataCache, TagType};
+use crate::error::Result;
+
+/// Максимум вариантов за один запрос
+const COMPLETION_LIMIT: usize = 50;
+
+/// Варианты автодополнения для позиции курсора.
+/// Вне строковых литералов возвращает пустой список — там работает обычный JS completion.
+pub async fn complete(
+    cache: &MinecraftDataCache,
+    text: &str,
+    position: Position,
+) -> Result<Vec<CompletionItem>> {
+    let index = LineIndex::new(text);
+    let Some(offset) = index.offset(position) else {
+        return Ok(Vec::new());
+    };
+    let Some(literal) = literal_at(text, offset) else {
+        return Ok(Vec::new());
+    };
+
+    let id_start = literal.start + count_prefix_len(&text[literal.start..literal.end]);
+    if offset < id_start {
+        return Ok(Vec::new());
+    }
+
+    let (query_start, marker) = match text.as_bytes().get(id_start) {
+        Some(&b) if (b == b'#' || b == b'@') && id_start < offset => (id_start + 1, Some(b)),
+        _ => (id_start, None),
+    };
+    let query = &text[query_start..offset];
+    if query.chars().any(char::is_whitespace) {
+        return Ok(Vec::new());
+    }
+
+    // Заменяем весь ID до конца литерала, чтобы выбор варианта посреди строки не дублировал хвост
+    let range = index.range(query_start, literal.end);
+
+    match marker {
+        Some(b'#') => complete_tags(cache, query, range).await,
+        Some(_) => complete_mods(cache, query, range).await,
+        None => complete_ids(cache, query, range).await,
+    }
+}
+
+fn completion(
+    label: String,
+    kind: u32,
+    detail: String,
+    order: usize,
+    range: Range,
+) -> CompletionItem {
+    CompletionItem {
+        filter_text: Some(label.clone()),
+        sort_text: Some(format!("{:04}", order)),
+        text_edit: Some(TextEdit {
+            range,
+            new_text: label.clone(),
+        }),
+        label,
+        kind,
+        detail: Some(detail),
+    }
+}
+
+async fn complete_ids(
+    cache: &MinecraftDataCache,
+    query: &str,
+    range: Range,
+) -> Result<Vec<CompletionItem>> {
+    let items = cache.search_items(query, None, COMPLETION_LIMIT).await?;
+    let blocks = cache.search_blocks(query, COMPLETION_LIMIT).await?;
+
+    // Предметы в приоритете: у большинства блоков есть одноимённый BlockItem
+    let mut seen = HashSet::new();
+    let mut result = Vec::new();
+
+    for item in items {
+        if seen.insert(item.id.clone()) {
+            let detail = format!("{} · {}", item.name, item.mod_id);
+            result.push(completion(
+                item.id,
+                kinds::COMPLETION_VALUE,
+                detail,
+                result.len(),
+                range,
+            ));
+        }
+    }
+    for block in blocks {
+        if seen.insert(block.id.clone()) {
+            let detail = format!("{} · {} (block)", block.name, block.mod_id);
+            result.push(completion(
+                block.id,
+                kinds::COMPLETION_CONSTANT,
+                detail,
+                result.len(),
+                range,
+            ));
+        }
+    }
+
+    result.truncate(COMPLETION_LIMIT);
+    Ok(result)
+}
+
+async fn complete_tags(
+    cache: &MinecraftDataCache,
+    query: &str,
+    range: Range,
+) -> Result<Vec<CompletionItem>> {
+    let tags = cache.search_tags(query, None, COMPLETION_LIMIT).await?;
+
+    Ok(tags
+        .into_iter()
+        .enumerate()
+        .map(|(i, tag)| {
+            let tag_type = match tag.tag_type {
+                TagType::Item => "item",
+                TagType::Block => "block",
+            };
+            let detail = format!("{} tag · {} entries", tag_type, tag.values.len());
+            completion(tag.id, kinds::COMPLETION_ENUM_MEMBER, detail, i, range)
+        })
+        .collect())
+}
+
+async fn complete_mods(
+    cache: &MinecraftDataCache,
+    query: &str,
+    range: Range,
+) -> Result<Vec<CompletionItem>> {
+    let query = query.to_lowercase();
+    let mods = cache.get_mods().await?;
+
+    Ok(mods
+        .into_iter()
+        .filter(|m| m.mod_id.contains(&query) || m.name.to_lowercase().contains(&query))
+        .take(COMPLETION_LIMIT)
+        .enumerate()
+        .map(|(i, m)| {
+            let detail = format!("{} {}", m.name, m.version);
+            completion(m.mod_id, kinds::COMPLETION_MODULE, detail, i, range)
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests_support::sample_cache;
+    use super::*;
+
+    fn position_after(text: &str, needle: &str) -> Position {
+        let offset = text.find(needle).unwrap() + needle.len();
+        LineIndex::new(text).position(offset)
+    }
+
+    #[tokio::test]
+    async fn test_item_completion_inside_literal() {
+        let cache = sample_cache().await;
+        let text = "ServerEvents.recipes(event => {\n  event.shapeless('create:cog', [])\n})";
+
+        let items = complete(&cache, text, position_after(text, "'create:co"))
+            .await
+            .unwrap();
+        let labels: Vec<&str> = items.iter().map(|i| i.label.as_str()).collect();
+        assert_eq!(labels, vec!["create:cogwheel"]);
+
+        // Замена покрывает весь ID внутри кавычек
+        let edit = items[0].text_edit.as_ref().unwrap();
+        let index = LineIndex::new(text);
+        let start = index.offset(edit.range.start).unwrap();
+        let end = index.offset(edit.range.end).unwrap();
+        assert_eq!(&text[start..end], "create:cog");
+    }
+
+    #[tokio::test]
+    async fn test_tag_and_mod_completion() {
+        let cache = sample_cache().await;
+
+        let text = "Ingredient.of('#c:ingo')";
+        let tags = complete(&cache, text, position_after(text, "#c:ingo"))
+            .await
+            .unwrap();
+        assert_eq!(tags.len(), 1);
+        assert_eq!(tags[0].label, "c:ingots/iron");
+
+        let text = "event.remove({ mod: '@cre' })";
+        let mods = complete(&cache, text, position_after(text, "@cre"))
+            .await
+            .unwrap();
+        assert_eq!(mods.len(), 1);
+        assert_eq!(mods[0].label, "create");
+    }
+
+    #[tokio::test]
+    async fn test_no_completion_outside_literal() {
+        let cache = sample_cache().await;
+        let text = "const create = 1; // 'create:cog'";
+        let items = complete(&cache, text, position_after(text, "const crea"))
+            .await
+            .unwrap();
+        assert!(items.is_empty());
+    }
+}
@@ -1,2 +1,171 @@
-// Diagnostics provider logic
-// TODO: Реализовать валидацию кода
+//! Diagnostics provider: неизвестные ID, теги и моды в KubeJS скриптах
+
+use std::collections::HashMap;
+
+use super::{
+    kinds, parse_reference, scan_string_literals, Diagnostic, LineIndex, ReferenceKind,
+    ResourceRef, DIAGNOSTIC_SOURCE,
+};
+use crate::code_editor::minecraft_data::MinecraftDataCache;
+use crate::error::Result;
+
+/// Vanilla jar не индексируется кэшем, поэтому `minecraft:*` проверяем только если
+/// в кэше всё же есть записи этого namespace
+const VANILLA_NAMESPACE: &str = "minecraft";
+
+/// Проверить документ. Пустой кэш (ещё не построен) — без диагностик, чтобы не засыпать
+/// весь скрипт ложными ошибками.
+pub async fn diagnostics(cache: &MinecraftDataCache, text: &str) -> Result<Vec<Diagnostic>> {
+    let stats = cache.get_stats().await?;
+    if stats.total_items == 0 && stats.total_blocks == 0 {
+        return Ok(Vec::new());
+    }
+
+    let index = LineIndex::new(text);
+    let mut namespaces: HashMap<String, bool> = HashMap::new();
+    let mut result = Vec::new();
+
+    // Незакрытые литералы пропускаем — пользователь ещё печатает
+    let references = scan_string_literals(text)
+        .iter()
+        .filter(|literal| !literal.unterminated)
+        .filter_map(|literal| parse_reference(text, literal))
+        .collect::<Vec<_>>();
+
+    for reference in references {
+        let namespace = reference.namespace().to_string();
+        let namespace_known = match namespaces.get(&namespace) {
+            Some(known) => *known,
+            None => {
+                let known = cache.has_namespace(&namespace).await?;
+                namespaces.insert(namespace.clone(), known);
+                known
+            }
+        };
+
+        if let Some((severity, code, message)) =
+            check_reference(cache, &reference, namespace_known).await?
+        {
+            result.push(Diagnostic {
+                range: index.range(reference.start, reference.end),
+                severity,
+                code: code.to_string(),
+                source: DIAGNOSTIC_SOURCE.to_string(),
+                message,
+            });
+        }
+    }
+
+    Ok(result)
+}
+
+async fn check_reference(
+    cache: &MinecraftDataCache,
+    reference: &ResourceRef,
+    namespace_known: bool,
+) -> Result<Option<(u32, &'static str, String)>> {
+    let namespace = reference.namespace();
+    if namespace == VANILLA_NAMESPACE && !namespace_known {
+        return Ok(None);
+    }
+
+    let issue = match reference.kind {
+        ReferenceKind::Id => {
+            if cache.get_item(&reference.id).await?.is_some()
+                || cache.get_block(&reference.id).await?.is_some()
+            {
+                None
+            } else if namespace_known {
+                Some((
+                    kinds::SEVERITY_ERROR,
+                    "unknown-id",
+                    format!("Unknown item or block '{}'", reference.id),
+                ))
+            } else {
+                Some((
+                    kinds::SEVERITY_WARNING,
+                    "unknown-namespace",
+                    format!(
+                        "Unknown namespace '{}': no installed mod provides '{}'",
+                        namespace, reference.id
+                    ),
+                ))
+            }
+        }
+        ReferenceKind::Tag => {
+            // Теги могут добавляться датапаками и самим KubeJS — только предупреждение
+            if cache.get_tag(&reference.id).await?.is_some() {
+                None
+            } else {
+                Some((
+                    kinds::SEVERITY_WARNING,
+                    "unknown-tag",
+                    format!("Unknown tag '#{}'", reference.id),
+                ))
+            }
+        }
+        ReferenceKind::Mod => {
+            if namespace_known {
+                None
+            } else {
+                Some((
+                    kinds::SEVERITY_WARNING,
+                    "unknown-mod",
+                    format!("Mod '{}' is not installed", reference.id),
+                ))
+            }
+        }
+    };
+
+    Ok(issue)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests_support::sample_cache;
+    use super::*;
+
+    #[tokio::test]
+    async fn test_reports_unknown_entries() {
+        let cache = sample_cache().await;
+        let text = r#"
+ServerEvents.recipes(event => {
+  event.shaped('create:cogwheel', ['A'], { A: '#c:ingots/iron' })
+  event.shapeless('create:cogwhel', ['minecraft:stick', '#c:gems/ruby'])
+  event.remove({ mod: '@mekanism', output: 'mekanism:steel_ingot' })
+})"#;
+
+        let found = diagnostics(&cache, text).await.unwrap();
+        let index = LineIndex::new(text);
+        let codes: Vec<(&str, &str)> = found
+            .iter()
+            .map(|d| {
+                let start = index.offset(d.range.start).unwrap();
+                let end = index.offset(d.range.end).unwrap();
+                (d.code.as_str(), &text[start..end])
+            })
+            .collect();
+
+        assert_eq!(
+            codes,
+            vec![
+                ("unknown-id", "create:cogwhel"),
+                ("unknown-tag", "c:gems/ruby"),
+                ("unknown-mod", "mekanism"),
+                ("unknown-namespace", "mekanism:steel_ingot"),
+            ]
+        );
+        assert_eq!(found[0].severity, kinds::SEVERITY_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_skips_unterminated_and_empty_cache() {
+        let cache = sample_cache().await;
+        let found = diagnostics(&cache, "Item.of('create:cogwh").await.unwrap();
+        assert!(found.is_empty());
+
+        let empty = MinecraftDataCache::in_memory(&[]).await.unwrap();
+        let found = diagnostics(&empty, "Item.of('create:nope')").await.unwrap();
+        assert!(found.is_empty());
+    }
+}
@@ -1,2 +1,167 @@
-// Hover provider logic
-// TODO: Реализовать hover tooltips
+//! Hover provider: отображаемое имя и мод для ID, содержимое тегов
+
+use super::{find_references, Hover, LineIndex, MarkupContent, Position, ReferenceKind};
+use crate::code_editor::minecraft_data::{MinecraftDataCache, TagType};
+use crate::error::Result;
+
+/// Сколько значений тега показывать в tooltip
+const TAG_PREVIEW_LIMIT: usize = 10;
+
+/// Tooltip для ссылки под курсором (None если курсор не на ID или ID неизвестен)
+pub async fn hover(
+    cache: &MinecraftDataCache,
+    text: &str,
+    position: Position,
+) -> Result<Option<Hover>> {
+    let index = LineIndex::new(text);
+    let Some(offset) = index.offset(position) else {
+        return Ok(None);
+    };
+    let Some(reference) = find_references(text)
+        .into_iter()
+        .find(|r| r.start <= offset && offset <= r.end)
+    else {
+        return Ok(None);
+    };
+
+    let markdown = match reference.kind {
+        ReferenceKind::Id => describe_id(cache, &reference.id).await?,
+        ReferenceKind::Tag => describe_tag(cache, &reference.id).await?,
+        ReferenceKind::Mod => describe_mod(cache, &reference.id).await?,
+    };
+
+    Ok(markdown.map(|value| Hover {
+        contents: MarkupContent::markdown(value),
+        range: index.range(reference.start, reference.end),
+    }))
+}
+
+/// "Create 0.5.1" или просто mod_id, если мод не проиндексирован (например vanilla)
+async fn mod_label(cache: &MinecraftDataCache, mod_id: &str) -> Result<String> {
+    Ok(match cache.get_mod(mod_id).await? {
+        Some(info) => format!("{} {} (`{}`)", info.name, info.version, info.mod_id),
+        None => format!("`{}`", mod_id),
+    })
+}
+
+async fn describe_id(cache: &MinecraftDataCache, id: &str) -> Result<Option<String>> {
+    if let Some(item) = cache.get_item(id).await? {
+        let mut lines = vec![
+            format!("**{}**", item.name),
+            format!("`{}` · item", item.id),
+            format!("Mod: {}", mod_label(cache, &item.mod_id).await?),
+        ];
+        if item.stack_size != 64 {
+            lines.push(format!("Stack size: {}", item.stack_size));
+        }
+        if !item.tags.is_empty() {
+            lines.push(format!("Tags: {}", format_tags(&item.tags)));
+        }
+        return Ok(Some(lines.join("\n\n")));
+    }
+
+    if let Some(block) = cache.get_block(id).await? {
+        let mut lines = vec![
+            format!("**{}**", block.name),
+            format!("`{}` · block", block.id),
+            format!("Mod: {}", mod_label(cache, &block.mod_id).await?),
+        ];
+        if let Some(hardness) = block.hardness {
+            lines.push(format!("Hardness: {}", hardness));
+        }
+        if !block.tags.is_empty() {
+            lines.push(format!("Tags: {}", format_tags(&block.tags)));
+        }
+        return Ok(Some(lines.join("\n\n")));
+    }
+
+    Ok(None)
+}
+
+fn format_tags(tags: &[String]) -> String {
+    tags.iter()
+        .map(|t| format!("`#{}`", t))
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+async fn describe_tag(cache: &MinecraftDataCache, id: &str) -> Result<Option<String>> {
+    let Some(tag) = cache.get_tag(id).await? else {
+        return Ok(None);
+    };
+
+    let tag_type = match tag.tag_type {
+        TagType::Item => "item",
+        TagType::Block => "block",
+    };
+    let mut value = format!(
+        "**#{}** · {} tag\n\n{} entries",
+        tag.id,
+        tag_type,
+        tag.values.len()
+    );
+    for entry in tag.values.iter().take(TAG_PREVIEW_LIMIT) {
+        value.push_str(&format!("\n- `{}`", entry));
+    }
+    if tag.values.len() > TAG_PREVIEW_LIMIT {
+        value.push_str(&format!(
+            "\n- … and {} more",
+            tag.values.len() - TAG_PREVIEW_LIMIT
+        ));
+    }
+    Ok(Some(value))
+}
+
+async fn describe_mod(cache: &MinecraftDataCache, mod_id: &str) -> Result<Option<String>> {
+    Ok(cache.get_mod(mod_id).await?.map(|info| {
+        format!(
+            "**{}** {}\n\n`{}` · {}\n\n{} items, {} blocks",
+            info.name, info.version, info.mod_id, info.loader, info.item_count, info.block_count
+        )
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests_support::sample_cache;
+    use super::*;
+
+    fn position_of(text: &str, needle: &str) -> Position {
+        LineIndex::new(text).position(text.find(needle).unwrap() + 1)
+    }
+
+    #[tokio::test]
+    async fn test_hover_item_shows_name_and_mod() {
+        let cache = sample_cache().await;
+        let text = "event.shaped('2x create:cogwheel', ['AA'])";
+
+        let hover = hover(&cache, text, position_of(text, "cogwheel"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(hover.contents.value.contains("**Cogwheel**"));
+        assert!(hover.contents.value.contains("Create 0.5.1"));
+    }
+
+    #[tokio::test]
+    async fn test_hover_tag_lists_values() {
+        let cache = sample_cache().await;
+        let text = "Ingredient.of('#c:ingots/iron')";
+
+        let hover = hover(&cache, text, position_of(text, "c:ingots"))
+            .await
+            .unwrap()
+            .unwrap();
+        assert!(hover.contents.value.contains("`minecraft:iron_ingot`"));
+    }
+
+    #[tokio::test]
+    async fn test_hover_unknown_id() {
+        let cache = sample_cache().await;
+        let text = "Item.of('create:nothing')";
+        assert!(hover(&cache, text, position_of(text, "nothing"))
+            .await
+            .unwrap()
+            .is_none());
+    }
+}
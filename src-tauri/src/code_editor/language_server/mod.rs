@@ -1,6 +1,464 @@
-// Language Server для IntelliSense
-// TODO: Реализовать completion, hover, diagnostics
+//! Language Server для KubeJS скриптов
+//!
+//! Работает поверх `MinecraftDataCache`: находит в скрипте строковые литералы с ID
+//! предметов/блоков (`'minecraft:diamond'`, `'2x create:cogwheel'`), тегов (`'#forge:ingots'`)
+//! и модов (`'@create'`) и даёт по ним completion, hover и diagnostics.
+//!
+//! Позиции — как в LSP: строка и символ с нуля, символ считается в UTF-16 code units.
+//! Типы сериализуются в формате LSP, поэтому используются и в Tauri командах, и в stdio сервере.
 
 pub mod completion;
 pub mod diagnostics;
 pub mod hover;
+pub mod server;
+
+pub use completion::complete;
+pub use diagnostics::diagnostics;
+pub use hover::hover;
+
+use serde::{Deserialize, Serialize};
+
+/// Позиция в документе (LSP `Position`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+pub struct Position {
+    pub line: u32,
+    pub character: u32,
+}
+
+/// Диапазон в документе (LSP `Range`)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Range {
+    pub start: Position,
+    pub end: Position,
+}
+
+/// Вариант автодополнения (подмножество LSP `CompletionItem`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CompletionItem {
+    pub label: String,
+    /// LSP CompletionItemKind
+    pub kind: u32,
+    pub detail: Option<String>,
+    pub filter_text: Option<String>,
+    pub sort_text: Option<String>,
+    pub text_edit: Option<TextEdit>,
+}
+
+/// Замена текста при выборе completion
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TextEdit {
+    pub range: Range,
+    pub new_text: String,
+}
+
+/// Hover tooltip (LSP `Hover` с markdown содержимым)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Hover {
+    pub contents: MarkupContent,
+    pub range: Range,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MarkupContent {
+    /// Всегда "markdown"
+    pub kind: String,
+    pub value: String,
+}
+
+impl MarkupContent {
+    pub fn markdown(value: String) -> Self {
+        Self {
+            kind: "markdown".to_string(),
+            value,
+        }
+    }
+}
+
+/// Диагностика (LSP `Diagnostic`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub range: Range,
+    /// 1 = Error, 2 = Warning, 3 = Information, 4 = Hint
+    pub severity: u32,
+    pub code: String,
+    pub source: String,
+    pub message: String,
+}
+
+/// LSP CompletionItemKind / DiagnosticSeverity константы
+pub mod kinds {
+    pub const COMPLETION_MODULE: u32 = 9;
+    pub const COMPLETION_VALUE: u32 = 12;
+    pub const COMPLETION_ENUM_MEMBER: u32 = 20;
+    pub const COMPLETION_CONSTANT: u32 = 21;
+
+    pub const SEVERITY_ERROR: u32 = 1;
+    pub const SEVERITY_WARNING: u32 = 2;
+}
+
+/// Источник диагностик (`Diagnostic.source`)
+pub const DIAGNOSTIC_SOURCE: &str = "stuzhik-kubejs";
+
+// ========== Разбор документа ==========
+
+/// Строковый литерал в JS коде (байтовые смещения содержимого, без кавычек)
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StringLiteral {
+    pub start: usize,
+    pub end: usize,
+    /// Литерал не закрыт до конца строки (пользователь ещё печатает)
+    pub unterminated: bool,
+}
+
+/// Найти все строковые литералы, пропуская комментарии (regex-литералы не распознаются).
+/// Template literals с `${...}` пропускаются целиком — это не ID.
+pub fn scan_string_literals(text: &str) -> Vec<StringLiteral> {
+    let bytes = text.as_bytes();
+    let mut literals = Vec::new();
+    let mut i = 0;
+
+    while i < bytes.len() {
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => {
+                while i < bytes.len() && bytes[i] != b'\n' {
+                    i += 1;
+                }
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'*') => {
+                i += 2;
+                while i < bytes.len() && !(bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/')) {
+                    i += 1;
+                }
+                i += 2;
+            }
+            quote @ (b'\'' | b'"' | b'`') => {
+                let start = i + 1;
+                let mut j = start;
+                let mut unterminated = true;
+                let mut interpolated = false;
+
+                while j < bytes.len() {
+                    match bytes[j] {
+                        b'\\' => j += 1,
+                        b'\n' if quote != b'`' => break,
+                        b'$' if quote == b'`' && bytes.get(j + 1) == Some(&b'{') => {
+                            interpolated = true;
+                        }
+                        c if c == quote => {
+                            unterminated = false;
+                            break;
+                        }
+                        _ => {}
+                    }
+                    j += 1;
+                }
+
+                let end = j.min(bytes.len());
+                if !interpolated {
+                    literals.push(StringLiteral {
+                        start,
+                        end,
+                        unterminated,
+                    });
+                }
+                i = end + 1;
+            }
+            _ => i += 1,
+        }
+    }
+
+    literals
+}
+
+/// Что именно записано в литерале
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ReferenceKind {
+    /// ID предмета или блока (`minecraft:diamond`)
+    Id,
+    /// Тег (`#forge:ingots/iron`)
+    Tag,
+    /// Фильтр по моду (`@create`)
+    Mod,
+}
+
+/// Ссылка на ресурс внутри строкового литерала
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceRef {
+    pub kind: ReferenceKind,
+    /// ID без префиксов `#`/`@` и количества
+    pub id: String,
+    /// Байтовый диапазон самого ID (без префиксов)
+    pub start: usize,
+    pub end: usize,
+}
+
+impl ResourceRef {
+    pub fn namespace(&self) -> &str {
+        match self.kind {
+            ReferenceKind::Mod => &self.id,
+            _ => self.id.split_once(':').map(|(ns, _)| ns).unwrap_or(""),
+        }
+    }
+}
+
+/// Длина префикса количества KubeJS (`"2x "`) в байтах
+fn count_prefix_len(content: &str) -> usize {
+    let digits = content.bytes().take_while(|b| b.is_ascii_digit()).count();
+    if digits > 0 && content[digits..].starts_with("x ") {
+        digits + 2
+    } else {
+        0
+    }
+}
+
+fn is_namespace_char(c: char) -> bool {
+    c.is_ascii_lowercase() || c.is_ascii_digit() || matches!(c, '_' | '-' | '.')
+}
+
+fn is_path_char(c: char) -> bool {
+    is_namespace_char(c) || c == '/'
+}
+
+/// Похоже ли на resource location `namespace:path`
+pub fn is_resource_location(s: &str) -> bool {
+    match s.split_once(':') {
+        Some((ns, path)) => {
+            !ns.is_empty()
+                && !path.is_empty()
+                && ns.chars().all(is_namespace_char)
+                && path.chars().all(is_path_char)
+        }
+        None => false,
+    }
+}
+
+/// Разобрать литерал как ссылку на ресурс. Обычные строки (`'Hello'`) дают None.
+pub fn parse_reference(text: &str, literal: &StringLiteral) -> Option<ResourceRef> {
+    let content = &text[literal.start..literal.end];
+    let offset = count_prefix_len(content);
+    let rest = &content[offset..];
+
+    let (kind, skip) = match rest.as_bytes().first() {
+        Some(b'#') => (ReferenceKind::Tag, 1),
+        Some(b'@') => (ReferenceKind::Mod, 1),
+        _ => (ReferenceKind::Id, 0),
+    };
+    let id = &rest[skip..];
+
+    let valid = match kind {
+        ReferenceKind::Mod => !id.is_empty() && id.chars().all(is_namespace_char),
+        _ => is_resource_location(id),
+    };
+    if !valid {
+        return None;
+    }
+
+    let start = literal.start + offset + skip;
+    Some(ResourceRef {
+        kind,
+        id: id.to_string(),
+        start,
+        end: start + id.len(),
+    })
+}
+
+/// Все ссылки на ресурсы в документе
+pub fn find_references(text: &str) -> Vec<ResourceRef> {
+    scan_string_literals(text)
+        .iter()
+        .filter_map(|literal| parse_reference(text, literal))
+        .collect()
+}
+
+/// Перевод между байтовыми смещениями и LSP позициями
+pub struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    pub fn new(text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(i, _)| i + 1));
+        Self { text, line_starts }
+    }
+
+    pub fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|&start| start <= offset) - 1;
+        let line_start = self.line_starts[line];
+        let character: usize = self.text[line_start..offset]
+            .chars()
+            .map(char::len_utf16)
+            .sum();
+
+        Position {
+            line: line as u32,
+            character: character as u32,
+        }
+    }
+
+    /// None если позиция за пределами документа
+    pub fn offset(&self, position: Position) -> Option<usize> {
+        let line_start = *self.line_starts.get(position.line as usize)?;
+        let line_end = self
+            .line_starts
+            .get(position.line as usize + 1)
+            .map(|next| next - 1)
+            .unwrap_or(self.text.len());
+
+        let mut utf16 = 0u32;
+        for (i, c) in self.text[line_start..line_end].char_indices() {
+            if utf16 >= position.character {
+                return Some(line_start + i);
+            }
+            utf16 += c.len_utf16() as u32;
+        }
+        Some(line_end)
+    }
+
+    pub fn range(&self, start: usize, end: usize) -> Range {
+        Range {
+            start: self.position(start),
+            end: self.position(end),
+        }
+    }
+}
+
+/// Литерал, внутри которого стоит курсор (включая позицию сразу после последнего символа)
+pub fn literal_at(text: &str, offset: usize) -> Option<StringLiteral> {
+    scan_string_literals(text)
+        .into_iter()
+        .find(|literal| literal.start <= offset && offset <= literal.end)
+}
+
+#[cfg(test)]
+pub(crate) mod tests_support {
+    use crate::code_editor::minecraft_data::{
+        MinecraftBlock, MinecraftDataCache, MinecraftItem, MinecraftTag, ModData, ModInfo, TagType,
+    };
+
+    fn item(id: &str, name: &str) -> MinecraftItem {
+        MinecraftItem {
+            id: id.to_string(),
+            name: name.to_string(),
+            mod_id: "create".to_string(),
+            tags: Vec::new(),
+            texture_path: None,
+            stack_size: 64,
+            rarity: "common".to_string(),
+            description: None,
+        }
+    }
+
+    /// Кэш с небольшим "модом" Create
+    pub async fn sample_cache() -> MinecraftDataCache {
+        let data = ModData {
+            mod_info: Some(ModInfo {
+                mod_id: "create".to_string(),
+                name: "Create".to_string(),
+                version: "0.5.1".to_string(),
+                loader: "forge".to_string(),
+                description: None,
+                authors: None,
+                homepage: None,
+                license: None,
+                item_count: 2,
+                block_count: 1,
+            }),
+            items: vec![
+                item("create:cogwheel", "Cogwheel"),
+                item("create:andesite_alloy", "Andesite Alloy"),
+            ],
+            blocks: vec![MinecraftBlock {
+                id: "create:andesite_casing".to_string(),
+                name: "Andesite Casing".to_string(),
+                mod_id: "create".to_string(),
+                tags: Vec::new(),
+                texture_path: None,
+                hardness: Some(2.0),
+                blast_resistance: None,
+                requires_tool: None,
+            }],
+            tags: vec![MinecraftTag {
+                id: "c:ingots/iron".to_string(),
+                tag_type: TagType::Item,
+                values: vec!["minecraft:iron_ingot".to_string()],
+            }],
+        };
+
+        MinecraftDataCache::in_memory(&[data]).await.unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scan_skips_comments_and_templates() {
+        let text = "// 'not:this'\nevent.shaped('minecraft:diamond', [`a${b}`, \"#forge:ingots\"]) /* 'x:y' */";
+        let literals: Vec<&str> = scan_string_literals(text)
+            .iter()
+            .map(|l| &text[l.start..l.end])
+            .collect();
+        assert_eq!(literals, vec!["minecraft:diamond", "#forge:ingots"]);
+    }
+
+    #[test]
+    fn test_unterminated_literal() {
+        let text = "Item.of('create:cog\nnext";
+        let literals = scan_string_literals(text);
+        assert_eq!(literals.len(), 1);
+        assert!(literals[0].unterminated);
+        assert_eq!(&text[literals[0].start..literals[0].end], "create:cog");
+    }
+
+    #[test]
+    fn test_parse_references() {
+        let text = "['2x minecraft:stick', '#c:ingots/iron', '@create', 'Hello world', 'a:B']";
+        let refs = find_references(text);
+        assert_eq!(refs.len(), 3);
+
+        assert_eq!(refs[0].kind, ReferenceKind::Id);
+        assert_eq!(refs[0].id, "minecraft:stick");
+        assert_eq!(&text[refs[0].start..refs[0].end], "minecraft:stick");
+
+        assert_eq!(refs[1].kind, ReferenceKind::Tag);
+        assert_eq!(refs[1].id, "c:ingots/iron");
+        assert_eq!(refs[1].namespace(), "c");
+
+        assert_eq!(refs[2].kind, ReferenceKind::Mod);
+        assert_eq!(refs[2].namespace(), "create");
+    }
+
+    #[test]
+    fn test_line_index_utf16() {
+        let text = "// ёж 🦔\nItem.of('a:b')";
+        let index = LineIndex::new(text);
+
+        let offset = text.find("a:b").unwrap();
+        let pos = index.position(offset);
+        assert_eq!(
+            pos,
+            Position {
+                line: 1,
+                character: 9
+            }
+        );
+        assert_eq!(index.offset(pos), Some(offset));
+
+        // 🦔 занимает 2 UTF-16 code units
+        let end_of_first = index.position(text.find('\n').unwrap());
+        assert_eq!(end_of_first.character, 8);
+        assert_eq!(
+            index.offset(Position {
+                line: 5,
+                character: 0
+            }),
+            None
+        );
+    }
+}
@@ -0,0 +1,292 @@
+//! stdio LSP сервер (JSON-RPC с `Content-Length` заголовками)
+//!
+//! Поддерживает full document sync, `textDocument/completion`, `textDocument/hover`
+//! и `textDocument/publishDiagnostics`. Запуск: `stuzhik-cli lsp <instance>`.
+
+use std::collections::HashMap;
+
+use serde_json::{json, Value};
+use tokio::io::{AsyncBufReadExt, AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt, BufReader};
+
+use super::{complete, diagnostics, hover, Position};
+use crate::code_editor::minecraft_data::MinecraftDataCache;
+use crate::error::{LauncherError, Result};
+
+/// JSON-RPC коды ошибок
+const METHOD_NOT_FOUND: i64 = -32601;
+const INTERNAL_ERROR: i64 = -32603;
+
+/// Запустить сервер на stdin/stdout для кэша указанного экземпляра
+pub async fn run_stdio(instance_id: &str) -> Result<()> {
+    let cache = MinecraftDataCache::init(instance_id).await?;
+    serve(cache, tokio::io::stdin(), tokio::io::stdout()).await
+}
+
+/// Обработка сообщений до `exit` или закрытия входного потока
+pub async fn serve<R, W>(cache: MinecraftDataCache, reader: R, mut writer: W) -> Result<()>
+where
+    R: AsyncRead + Unpin,
+    W: AsyncWrite + Unpin,
+{
+    let mut reader = BufReader::new(reader);
+    let mut server = LanguageServer {
+        cache,
+        documents: HashMap::new(),
+    };
+
+    while let Some(message) = read_message(&mut reader).await? {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        if method == "exit" {
+            break;
+        }
+
+        for outgoing in server.handle(&message).await {
+            write_message(&mut writer, &outgoing).await?;
+        }
+    }
+
+    Ok(())
+}
+
+struct LanguageServer {
+    cache: MinecraftDataCache,
+    /// Открытые документы: uri -> текст
+    documents: HashMap<String, String>,
+}
+
+impl LanguageServer {
+    /// Обработать одно сообщение, вернуть ответ и/или уведомления
+    async fn handle(&mut self, message: &Value) -> Vec<Value> {
+        let method = message.get("method").and_then(Value::as_str).unwrap_or("");
+        let params = message.get("params").cloned().unwrap_or(Value::Null);
+
+        let Some(id) = message.get("id").cloned() else {
+            // Notification
+            return match self.handle_notification(method, &params).await {
+                Ok(messages) => messages,
+                Err(e) => {
+                    log::warn!("[LSP] {} failed: {}", method, e);
+                    Vec::new()
+                }
+            };
+        };
+
+        let response = match self.handle_request(method, &params).await {
+            Ok(Some(result)) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Ok(None) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": METHOD_NOT_FOUND, "message": format!("Unknown method: {}", method) }
+            }),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": INTERNAL_ERROR, "message": e.to_string() }
+            }),
+        };
+        vec![response]
+    }
+
+    /// None — метод не поддерживается
+    async fn handle_request(&mut self, method: &str, params: &Value) -> Result<Option<Value>> {
+        let result = match method {
+            "initialize" => json!({
+                "capabilities": {
+                    "textDocumentSync": 1,
+                    "completionProvider": { "triggerCharacters": [":", "#", "@", "'", "\""] },
+                    "hoverProvider": true
+                },
+                "serverInfo": { "name": "stuzhik-kubejs", "version": env!("CARGO_PKG_VERSION") }
+            }),
+            "shutdown" => Value::Null,
+            "textDocument/completion" => {
+                let (text, position) = self.document_position(params)?;
+                let items = complete(&self.cache, text, position).await?;
+                // isIncomplete: редактор перезапрашивает при каждом символе (поиск идёт в кэше)
+                json!({ "isIncomplete": true, "items": items })
+            }
+            "textDocument/hover" => {
+                let (text, position) = self.document_position(params)?;
+                serde_json::to_value(hover(&self.cache, text, position).await?)?
+            }
+            _ => return Ok(None),
+        };
+        Ok(Some(result))
+    }
+
+    async fn handle_notification(&mut self, method: &str, params: &Value) -> Result<Vec<Value>> {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        match method {
+            "textDocument/didOpen" => {
+                let text = params
+                    .pointer("/textDocument/text")
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                Ok(vec![self.publish_diagnostics(&uri).await?])
+            }
+            "textDocument/didChange" => {
+                // Full sync: последний элемент содержит весь текст
+                let text = params
+                    .get("contentChanges")
+                    .and_then(Value::as_array)
+                    .and_then(|changes| changes.last())
+                    .and_then(|change| change.get("text"))
+                    .and_then(Value::as_str)
+                    .unwrap_or_default();
+                self.documents.insert(uri.clone(), text.to_string());
+                Ok(vec![self.publish_diagnostics(&uri).await?])
+            }
+            "textDocument/didClose" => {
+                self.documents.remove(&uri);
+                Ok(vec![json!({
+                    "jsonrpc": "2.0",
+                    "method": "textDocument/publishDiagnostics",
+                    "params": { "uri": uri, "diagnostics": [] }
+                })])
+            }
+            _ => Ok(Vec::new()),
+        }
+    }
+
+    async fn publish_diagnostics(&self, uri: &str) -> Result<Value> {
+        let text = self.documents.get(uri).map(String::as_str).unwrap_or("");
+        let found = diagnostics(&self.cache, text).await?;
+        Ok(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/publishDiagnostics",
+            "params": { "uri": uri, "diagnostics": found }
+        }))
+    }
+
+    fn document_position(&self, params: &Value) -> Result<(&str, Position)> {
+        let uri = params
+            .pointer("/textDocument/uri")
+            .and_then(Value::as_str)
+            .ok_or_else(|| LauncherError::InvalidConfig("Missing textDocument.uri".into()))?;
+        let text = self
+            .documents
+            .get(uri)
+            .ok_or_else(|| LauncherError::InvalidConfig(format!("Document not open: {}", uri)))?;
+        let position: Position =
+            serde_json::from_value(params.get("position").cloned().unwrap_or(Value::Null))?;
+        Ok((text, position))
+    }
+}
+
+/// Прочитать сообщение (None при EOF)
+async fn read_message<R: AsyncRead + Unpin>(reader: &mut BufReader<R>) -> Result<Option<Value>> {
+    let mut content_length: Option<usize> = None;
+    let mut line = String::new();
+
+    loop {
+        line.clear();
+        if reader.read_line(&mut line).await? == 0 {
+            return Ok(None);
+        }
+        let header = line.trim_end();
+        if header.is_empty() {
+            break;
+        }
+        if let Some((name, value)) = header.split_once(':') {
+            if name.eq_ignore_ascii_case("Content-Length") {
+                content_length = value.trim().parse().ok();
+            }
+        }
+    }
+
+    let length = content_length
+        .ok_or_else(|| LauncherError::InvalidConfig("Missing Content-Length header".into()))?;
+    let mut body = vec![0u8; length];
+    reader.read_exact(&mut body).await?;
+    Ok(Some(serde_json::from_slice(&body)?))
+}
+
+async fn write_message<W: AsyncWrite + Unpin>(writer: &mut W, message: &Value) -> Result<()> {
+    let body = serde_json::to_vec(message)?;
+    writer
+        .write_all(format!("Content-Length: {}\r\n\r\n", body.len()).as_bytes())
+        .await?;
+    writer.write_all(&body).await?;
+    writer.flush().await?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::super::tests_support::sample_cache;
+    use super::*;
+
+    fn frame(message: Value) -> Vec<u8> {
+        let body = serde_json::to_vec(&message).unwrap();
+        let mut out = format!("Content-Length: {}\r\n\r\n", body.len()).into_bytes();
+        out.extend(body);
+        out
+    }
+
+    async fn parse_output(output: Vec<u8>) -> Vec<Value> {
+        let mut reader = BufReader::new(output.as_slice());
+        let mut messages = Vec::new();
+        while let Some(message) = read_message(&mut reader).await.unwrap() {
+            messages.push(message);
+        }
+        messages
+    }
+
+    #[tokio::test]
+    async fn test_session_roundtrip() {
+        let uri = "file:///kubejs/server_scripts/recipes.js";
+        let mut input = Vec::new();
+        input.extend(frame(
+            json!({"jsonrpc": "2.0", "id": 1, "method": "initialize", "params": {}}),
+        ));
+        input.extend(frame(
+            json!({"jsonrpc": "2.0", "method": "initialized", "params": {}}),
+        ));
+        input.extend(frame(json!({
+            "jsonrpc": "2.0",
+            "method": "textDocument/didOpen",
+            "params": { "textDocument": { "uri": uri, "languageId": "javascript", "version": 1,
+                "text": "Item.of('create:cogwhel')\nItem.of('create:cog" } }
+        })));
+        input.extend(frame(json!({
+            "jsonrpc": "2.0", "id": 2, "method": "textDocument/completion",
+            "params": { "textDocument": { "uri": uri }, "position": { "line": 1, "character": 18 } }
+        })));
+        input.extend(frame(
+            json!({"jsonrpc": "2.0", "id": 3, "method": "workspace/symbol", "params": {}}),
+        ));
+        input.extend(frame(
+            json!({"jsonrpc": "2.0", "id": 4, "method": "shutdown"}),
+        ));
+        input.extend(frame(json!({"jsonrpc": "2.0", "method": "exit"})));
+
+        let mut output = Vec::new();
+        serve(sample_cache().await, input.as_slice(), &mut output)
+            .await
+            .unwrap();
+        let messages = parse_output(output).await;
+
+        assert_eq!(messages.len(), 5);
+        assert_eq!(messages[0]["result"]["capabilities"]["hoverProvider"], true);
+
+        assert_eq!(messages[1]["method"], "textDocument/publishDiagnostics");
+        let diagnostics = messages[1]["params"]["diagnostics"].as_array().unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0]["code"], "unknown-id");
+
+        assert_eq!(messages[2]["id"], 2);
+        assert_eq!(
+            messages[2]["result"]["items"][0]["label"],
+            "create:cogwheel"
+        );
+
+        assert_eq!(messages[3]["error"]["code"], METHOD_NOT_FOUND);
+        assert_eq!(messages[4]["id"], 4);
+    }
+}
@@ -3,7 +3,7 @@ use super::types::{MinecraftBlock, MinecraftItem, MinecraftTag, ModInfo, TagType
 use crate::error::Result;
 use crate::paths::{cache_dir, instance_mods_dir};
 use chrono::Utc;
-use rusqlite::{params, Connection};
+use rusqlite::{params, Connection, OptionalExtension};
 use std::path::PathBuf;
 use std::sync::Arc;
 use tokio::sync::Mutex;
@@ -582,6 +582,202 @@ impl MinecraftDataCache {
         Ok(mods)
     }
 
+    /// Точный поиск предмета по ID
+    pub async fn get_item(&self, id: &str) -> Result<Option<MinecraftItem>> {
+        let conn = self.conn.lock().await;
+
+        let item = conn
+            .query_row(
+                "SELECT id, name, mod_id, tags, texture_path, stack_size, rarity, description
+                 FROM items WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(MinecraftItem {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        mod_id: row.get(2)?,
+                        tags: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                        texture_path: row.get(4)?,
+                        stack_size: row.get(5)?,
+                        rarity: row.get(6)?,
+                        description: row.get(7)?,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(item)
+    }
+
+    /// Точный поиск блока по ID
+    pub async fn get_block(&self, id: &str) -> Result<Option<MinecraftBlock>> {
+        let conn = self.conn.lock().await;
+
+        let block = conn
+            .query_row(
+                "SELECT id, name, mod_id, tags, texture_path, hardness, blast_resistance, requires_tool
+                 FROM blocks WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(MinecraftBlock {
+                        id: row.get(0)?,
+                        name: row.get(1)?,
+                        mod_id: row.get(2)?,
+                        tags: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                        texture_path: row.get(4)?,
+                        hardness: row.get(5)?,
+                        blast_resistance: row.get(6)?,
+                        requires_tool: row.get::<_, Option<i32>>(7)?.map(|v| v != 0),
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(block)
+    }
+
+    /// Точный поиск тега по ID (без `#`)
+    pub async fn get_tag(&self, id: &str) -> Result<Option<MinecraftTag>> {
+        let conn = self.conn.lock().await;
+
+        let tag = conn
+            .query_row(
+                "SELECT id, tag_type, tag_values FROM tags WHERE id = ?1",
+                [id],
+                |row| {
+                    Ok(MinecraftTag {
+                        id: row.get(0)?,
+                        tag_type: match row.get::<_, String>(1)?.as_str() {
+                            "block" => TagType::Block,
+                            _ => TagType::Item,
+                        },
+                        values: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(tag)
+    }
+
+    /// Информация о моде по mod_id
+    pub async fn get_mod(&self, mod_id: &str) -> Result<Option<ModInfo>> {
+        let conn = self.conn.lock().await;
+
+        let info = conn
+            .query_row(
+                "SELECT mod_id, name, version, loader, item_count, block_count
+                 FROM mods WHERE mod_id = ?1",
+                [mod_id],
+                |row| {
+                    Ok(ModInfo {
+                        mod_id: row.get(0)?,
+                        name: row.get(1)?,
+                        version: row.get(2)?,
+                        loader: row.get(3)?,
+                        description: None,
+                        authors: None,
+                        homepage: None,
+                        license: None,
+                        item_count: row.get::<_, i64>(4)? as usize,
+                        block_count: row.get::<_, i64>(5)? as usize,
+                    })
+                },
+            )
+            .optional()?;
+
+        Ok(info)
+    }
+
+    /// Все предметы кэша, отсортированные по ID (для генерации typings)
+    pub async fn all_items(&self) -> Result<Vec<MinecraftItem>> {
+        let conn = self.conn.lock().await;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, mod_id, tags, texture_path, stack_size, rarity, description
+             FROM items ORDER BY id",
+        )?;
+        let items = stmt
+            .query_map([], |row| {
+                Ok(MinecraftItem {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    mod_id: row.get(2)?,
+                    tags: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    texture_path: row.get(4)?,
+                    stack_size: row.get(5)?,
+                    rarity: row.get(6)?,
+                    description: row.get(7)?,
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(items)
+    }
+
+    /// Все блоки кэша, отсортированные по ID
+    pub async fn all_blocks(&self) -> Result<Vec<MinecraftBlock>> {
+        let conn = self.conn.lock().await;
+
+        let mut stmt = conn.prepare(
+            "SELECT id, name, mod_id, tags, texture_path, hardness, blast_resistance, requires_tool
+             FROM blocks ORDER BY id",
+        )?;
+        let blocks = stmt
+            .query_map([], |row| {
+                Ok(MinecraftBlock {
+                    id: row.get(0)?,
+                    name: row.get(1)?,
+                    mod_id: row.get(2)?,
+                    tags: serde_json::from_str(&row.get::<_, String>(3)?).unwrap_or_default(),
+                    texture_path: row.get(4)?,
+                    hardness: row.get(5)?,
+                    blast_resistance: row.get(6)?,
+                    requires_tool: row.get::<_, Option<i32>>(7)?.map(|v| v != 0),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(blocks)
+    }
+
+    /// Все теги кэша, отсортированные по ID
+    pub async fn all_tags(&self) -> Result<Vec<MinecraftTag>> {
+        let conn = self.conn.lock().await;
+
+        let mut stmt = conn.prepare("SELECT id, tag_type, tag_values FROM tags ORDER BY id")?;
+        let tags = stmt
+            .query_map([], |row| {
+                Ok(MinecraftTag {
+                    id: row.get(0)?,
+                    tag_type: match row.get::<_, String>(1)?.as_str() {
+                        "block" => TagType::Block,
+                        _ => TagType::Item,
+                    },
+                    values: serde_json::from_str(&row.get::<_, String>(2)?).unwrap_or_default(),
+                })
+            })?
+            .collect::<std::result::Result<Vec<_>, _>>()?;
+
+        Ok(tags)
+    }
+
+    /// Есть ли в кэше хоть что-то из namespace (мод установлен и проиндексирован)
+    pub async fn has_namespace(&self, namespace: &str) -> Result<bool> {
+        let conn = self.conn.lock().await;
+
+        // Сравнение префикса без LIKE: `_` и `%` в namespace не должны работать как шаблоны
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM mods WHERE mod_id = ?1)
+                 OR EXISTS(SELECT 1 FROM items WHERE substr(id, 1, length(?1) + 1) = ?1 || ':')
+                 OR EXISTS(SELECT 1 FROM blocks WHERE substr(id, 1, length(?1) + 1) = ?1 || ':')",
+            params![namespace],
+            |row| row.get(0),
+        )?;
+
+        Ok(exists)
+    }
+
     /// Получить статистику кэша
     pub async fn get_stats(&self) -> Result<CacheStats> {
         let conn = self.conn.lock().await;
@@ -611,6 +807,24 @@ impl MinecraftDataCache {
     }
 }
 
+#[cfg(test)]
+impl MinecraftDataCache {
+    /// In-memory кэш с заранее заданными данными (для тестов language server)
+    pub(crate) async fn in_memory(mod_data: &[super::types::ModData]) -> Result<Self> {
+        let conn = Connection::open_in_memory()?;
+        Self::create_tables(&conn)?;
+
+        let cache = Self {
+            instance_id: "test".to_string(),
+            conn: Arc::new(Mutex::new(conn)),
+        };
+        for data in mod_data {
+            cache.save_mod_data(data).await?;
+        }
+        Ok(cache)
+    }
+}
+
 #[derive(Debug, Clone, Default, serde::Serialize)]
 pub struct RebuildStats {
     pub total_mods: usize,
@@ -629,3 +843,16 @@ pub struct CacheStats {
     pub total_mods: usize,
     pub last_rebuild: Option<i64>,
 }
+
+#[cfg(test)]
+mod tests {
+    use crate::code_editor::language_server::tests_support::sample_cache;
+
+    #[tokio::test]
+    async fn test_namespace_is_not_a_pattern() {
+        let cache = sample_cache().await;
+        assert!(cache.has_namespace("create").await.unwrap());
+        assert!(!cache.has_namespace("creat_").await.unwrap());
+        assert!(!cache.has_namespace("cr%").await.unwrap());
+    }
+}
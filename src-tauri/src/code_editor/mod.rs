@@ -51,7 +51,18 @@ pub async fn rebuild_minecraft_data_cache(instance_id: String) -> Result<Rebuild
         .await
         .map_err(|e| e.to_string())?;
 
-    cache.rebuild().await.map_err(|e| e.to_string())
+    let stats = cache.rebuild().await.map_err(|e| e.to_string())?;
+
+    // Если KubeJS typings уже генерировались — обновляем их (только изменившиеся файлы)
+    if type_definitions::TypeDefinitionGenerator::is_generated(&instance_id) {
+        if let Err(e) =
+            type_definitions::TypeDefinitionGenerator::generate(&instance_id, &cache).await
+        {
+            log::warn!("Failed to regenerate KubeJS typings: {}", e);
+        }
+    }
+
+    Ok(stats)
 }
 
 /// Получить статистику кэша
@@ -194,3 +205,70 @@ pub async fn search_minecraft_entries(
 
     Ok(entries)
 }
+
+// ========== KubeJS Language Server ==========
+
+/// Автодополнение ID/тегов/модов в KubeJS скрипте (позиция в формате LSP)
+#[tauri::command]
+pub async fn kubejs_complete(
+    instance_id: String,
+    text: String,
+    line: u32,
+    character: u32,
+) -> Result<Vec<language_server::CompletionItem>, String> {
+    let cache = MinecraftDataCache::init(&instance_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    language_server::complete(&cache, &text, language_server::Position { line, character })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Hover tooltip для ID под курсором
+#[tauri::command]
+pub async fn kubejs_hover(
+    instance_id: String,
+    text: String,
+    line: u32,
+    character: u32,
+) -> Result<Option<language_server::Hover>, String> {
+    let cache = MinecraftDataCache::init(&instance_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    language_server::hover(&cache, &text, language_server::Position { line, character })
+        .await
+        .map_err(|e| e.to_string())
+}
+
+/// Диагностики: неизвестные ID, теги и моды
+#[tauri::command]
+pub async fn kubejs_diagnostics(
+    instance_id: String,
+    text: String,
+) -> Result<Vec<language_server::Diagnostic>, String> {
+    let cache = MinecraftDataCache::init(&instance_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    language_server::diagnostics(&cache, &text)
+        .await
+        .map_err(|e| e.to_string())
+}
+
+// ========== KubeJS Type Definitions ==========
+
+/// Сгенерировать .d.ts typings для KubeJS в `kubejs/probe/generated`
+#[tauri::command]
+pub async fn generate_kubejs_typings(
+    instance_id: String,
+) -> Result<type_definitions::GenerationStats, String> {
+    let cache = MinecraftDataCache::init(&instance_id)
+        .await
+        .map_err(|e| e.to_string())?;
+
+    type_definitions::TypeDefinitionGenerator::generate(&instance_id, &cache)
+        .await
+        .map_err(|e| e.to_string())
+}
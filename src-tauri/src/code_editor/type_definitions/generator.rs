@@ -1,2 +1,450 @@
-// Dynamic type generation from Minecraft data
-// TODO: Генерация типов из кэша предметов/блоков
+//! Генератор `.d.ts` bundle для KubeJS из кэша Minecraft данных
+//!
+//! Пишет в `kubejs/probe/generated` (как ProbeJS): baseline API из [`super::kubejs`]
+//! и по файлу `registries/<namespace>.d.ts` на каждый namespace. Файлы дополняют
+//! интерфейсы `Special.*Registry`, так что ID превращаются в union типы.
+//!
+//! Перегенерация инкрементальная: хеши файлов хранятся в манифесте, перезаписываются
+//! только изменившиеся namespaces, файлы удалённых модов удаляются.
+
+use std::collections::BTreeMap;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+
+use super::kubejs::{BASELINE_FILE, KUBEJS_BASELINE};
+use crate::code_editor::minecraft_data::{
+    MinecraftBlock, MinecraftDataCache, MinecraftItem, MinecraftTag, ModInfo, TagType,
+};
+use crate::error::{LauncherError, Result};
+use crate::paths::instance_dir;
+
+/// Версия формата — при изменении генератора все файлы пишутся заново
+const GENERATOR_VERSION: u32 = 1;
+
+const MANIFEST_FILE: &str = ".stuzhik-typings.json";
+
+const UNCHECKED_FILE: &str = "unchecked.d.ts";
+
+/// Vanilla jar не индексируется — без записей в кэше его ID не проверяем
+const VANILLA_NAMESPACE: &str = "minecraft";
+
+const GENERATED_HEADER: &str =
+    "// Generated by Stuzhik from the instance mod cache. Do not edit: regenerated automatically.\n";
+
+const JSCONFIG: &str = r#"{
+  "compilerOptions": {
+    "lib": ["ES2015"],
+    "target": "ES2015",
+    "checkJs": true,
+    "noEmit": true
+  },
+  "include": [
+    "./probe/generated/**/*.d.ts",
+    "./probe/user/**/*.d.ts",
+    "./startup_scripts/**/*.js",
+    "./server_scripts/**/*.js",
+    "./client_scripts/**/*.js"
+  ]
+}
+"#;
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct Manifest {
+    generator_version: u32,
+    /// Относительный путь -> xxh3 хеш содержимого
+    files: BTreeMap<String, String>,
+}
+
+/// Результат генерации
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct GenerationStats {
+    pub output_dir: String,
+    pub files_written: usize,
+    pub files_unchanged: usize,
+    pub files_removed: usize,
+    pub namespaces: usize,
+    pub items: usize,
+    pub blocks: usize,
+    pub tags: usize,
+    pub mods: usize,
+}
+
+pub struct TypeDefinitionGenerator;
+
+impl TypeDefinitionGenerator {
+    /// `<instance>/kubejs/probe/generated`
+    pub fn output_dir(instance_id: &str) -> PathBuf {
+        instance_dir(instance_id)
+            .join("kubejs")
+            .join("probe")
+            .join("generated")
+    }
+
+    /// Генерировались ли typings для экземпляра (есть манифест)
+    pub fn is_generated(instance_id: &str) -> bool {
+        Self::output_dir(instance_id).join(MANIFEST_FILE).exists()
+    }
+
+    /// Сгенерировать (или обновить) bundle для экземпляра
+    pub async fn generate(
+        instance_id: &str,
+        cache: &MinecraftDataCache,
+    ) -> Result<GenerationStats> {
+        let items = cache.all_items().await?;
+        let blocks = cache.all_blocks().await?;
+        let tags = cache.all_tags().await?;
+        let mods = cache.get_mods().await?;
+
+        if items.is_empty() && blocks.is_empty() {
+            return Err(LauncherError::InvalidConfig(
+                "Minecraft data cache is empty, rebuild it first".to_string(),
+            ));
+        }
+
+        let files = render_bundle(&items, &blocks, &tags, &mods);
+        let output_dir = Self::output_dir(instance_id);
+
+        let mut stats = sync_files(&output_dir, &files).await?;
+        stats.namespaces = files
+            .keys()
+            .filter(|path| path.starts_with("registries/"))
+            .count();
+        stats.items = items.len();
+        stats.blocks = blocks.len();
+        stats.tags = tags.len();
+        stats.mods = mods.len();
+
+        // jsconfig.json подключает typings в VS Code; пользовательский не трогаем
+        let jsconfig = instance_dir(instance_id)
+            .join("kubejs")
+            .join("jsconfig.json");
+        if !tokio::fs::try_exists(&jsconfig).await.unwrap_or(false) {
+            tokio::fs::write(&jsconfig, JSCONFIG).await?;
+        }
+
+        log::info!(
+            "KubeJS typings for {}: {} written, {} unchanged, {} removed",
+            instance_id,
+            stats.files_written,
+            stats.files_unchanged,
+            stats.files_removed
+        );
+
+        Ok(stats)
+    }
+}
+
+/// Записи одного namespace
+#[derive(Default)]
+struct NamespaceEntries<'a> {
+    items: Vec<&'a MinecraftItem>,
+    blocks: Vec<&'a MinecraftBlock>,
+    item_tags: Vec<&'a MinecraftTag>,
+    block_tags: Vec<&'a MinecraftTag>,
+    mods: Vec<&'a ModInfo>,
+}
+
+fn namespace_of(id: &str) -> &str {
+    id.split_once(':').map(|(ns, _)| ns).unwrap_or(id)
+}
+
+/// Имя файла для namespace (ID из jar могут содержать что угодно)
+fn namespace_file_name(namespace: &str) -> String {
+    let safe: String = namespace
+        .chars()
+        .map(|c| match c {
+            'a'..='z' | '0'..='9' | '_' | '-' | '.' => c,
+            _ => '_',
+        })
+        .collect();
+    format!("registries/{}.d.ts", safe)
+}
+
+/// Текст в JSDoc комментарии без преждевременного `*/`
+fn doc_text(text: &str) -> String {
+    text.replace("*/", "*\\/").replace('\n', " ")
+}
+
+/// Ключ интерфейса — JSON строка (корректно экранирована и для TS)
+fn key(id: &str) -> String {
+    serde_json::to_string(id).unwrap_or_else(|_| format!("\"{}\"", id))
+}
+
+/// Разложить данные кэша по файлам bundle (относительный путь -> содержимое)
+fn render_bundle(
+    items: &[MinecraftItem],
+    blocks: &[MinecraftBlock],
+    tags: &[MinecraftTag],
+    mods: &[ModInfo],
+) -> BTreeMap<String, String> {
+    let mut namespaces: BTreeMap<&str, NamespaceEntries> = BTreeMap::new();
+
+    for item in items {
+        namespaces
+            .entry(namespace_of(&item.id))
+            .or_default()
+            .items
+            .push(item);
+    }
+    for block in blocks {
+        namespaces
+            .entry(namespace_of(&block.id))
+            .or_default()
+            .blocks
+            .push(block);
+    }
+    for tag in tags {
+        let entries = namespaces.entry(namespace_of(&tag.id)).or_default();
+        match tag.tag_type {
+            TagType::Item => entries.item_tags.push(tag),
+            TagType::Block => entries.block_tags.push(tag),
+        }
+    }
+    for info in mods {
+        namespaces
+            .entry(info.mod_id.as_str())
+            .or_default()
+            .mods
+            .push(info);
+    }
+
+    let mut files = BTreeMap::new();
+    files.insert(BASELINE_FILE.to_string(), KUBEJS_BASELINE.to_string());
+
+    let vanilla_indexed = namespaces
+        .get(VANILLA_NAMESPACE)
+        .is_some_and(|e| !e.items.is_empty() || !e.blocks.is_empty());
+    files.insert(
+        UNCHECKED_FILE.to_string(),
+        render_unchecked(vanilla_indexed),
+    );
+
+    for (namespace, entries) in &namespaces {
+        files.insert(
+            namespace_file_name(namespace),
+            render_namespace(namespace, entries),
+        );
+    }
+
+    files
+}
+
+fn render_unchecked(vanilla_indexed: bool) -> String {
+    let mut out = String::from(GENERATED_HEADER);
+    out.push_str("declare namespace Special {\n    interface UncheckedNamespaces {\n");
+    if !vanilla_indexed {
+        out.push_str(&format!("        {}: true;\n", key(VANILLA_NAMESPACE)));
+    }
+    out.push_str("    }\n}\n");
+    out
+}
+
+fn render_namespace(namespace: &str, entries: &NamespaceEntries) -> String {
+    let mut out = String::from(GENERATED_HEADER);
+    out.push_str(&format!("// Namespace: {}\n\n", namespace));
+    out.push_str("declare namespace Special {\n");
+
+    if !entries.items.is_empty() {
+        out.push_str("    interface ItemRegistry {\n");
+        for item in &entries.items {
+            out.push_str(&format!(
+                "        /** {} */\n        {}: true;\n",
+                doc_text(&item.name),
+                key(&item.id)
+            ));
+        }
+        out.push_str("    }\n");
+    }
+
+    if !entries.blocks.is_empty() {
+        out.push_str("    interface BlockRegistry {\n");
+        for block in &entries.blocks {
+            out.push_str(&format!(
+                "        /** {} */\n        {}: true;\n",
+                doc_text(&block.name),
+                key(&block.id)
+            ));
+        }
+        out.push_str("    }\n");
+    }
+
+    for (interface, tags) in [
+        ("ItemTagRegistry", &entries.item_tags),
+        ("BlockTagRegistry", &entries.block_tags),
+    ] {
+        if tags.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("    interface {} {{\n", interface));
+        for tag in tags.iter() {
+            out.push_str(&format!(
+                "        /** {} entries */\n        {}: true;\n",
+                tag.values.len(),
+                key(&tag.id)
+            ));
+        }
+        out.push_str("    }\n");
+    }
+
+    if !entries.mods.is_empty() {
+        out.push_str("    interface ModRegistry {\n");
+        for info in &entries.mods {
+            out.push_str(&format!(
+                "        /** {} {} */\n        {}: true;\n",
+                doc_text(&info.name),
+                doc_text(&info.version),
+                key(&info.mod_id)
+            ));
+        }
+        out.push_str("    }\n");
+    }
+
+    out.push_str("}\n");
+    out
+}
+
+/// Привести каталог к набору файлов, перезаписывая только изменившиеся
+async fn sync_files(dir: &Path, files: &BTreeMap<String, String>) -> Result<GenerationStats> {
+    tokio::fs::create_dir_all(dir).await?;
+
+    let manifest_path = dir.join(MANIFEST_FILE);
+    let previous: Manifest = match tokio::fs::read_to_string(&manifest_path).await {
+        Ok(content) => serde_json::from_str(&content)
+            .ok()
+            .filter(|m: &Manifest| m.generator_version == GENERATOR_VERSION)
+            .unwrap_or_default(),
+        Err(_) => Manifest::default(),
+    };
+
+    let mut manifest = Manifest {
+        generator_version: GENERATOR_VERSION,
+        files: BTreeMap::new(),
+    };
+    let mut stats = GenerationStats {
+        output_dir: dir.to_string_lossy().to_string(),
+        ..Default::default()
+    };
+
+    for (relative, content) in files {
+        let hash = format!("{:016x}", xxhash_rust::xxh3::xxh3_64(content.as_bytes()));
+        let path = dir.join(relative);
+
+        let unchanged = previous.files.get(relative) == Some(&hash)
+            && tokio::fs::try_exists(&path).await.unwrap_or(false);
+        if unchanged {
+            stats.files_unchanged += 1;
+        } else {
+            if let Some(parent) = path.parent() {
+                tokio::fs::create_dir_all(parent).await?;
+            }
+            tokio::fs::write(&path, content).await?;
+            stats.files_written += 1;
+        }
+
+        manifest.files.insert(relative.clone(), hash);
+    }
+
+    // Файлы из прошлой генерации, которых больше нет (мод удалён)
+    for relative in previous.files.keys() {
+        if files.contains_key(relative) {
+            continue;
+        }
+        match tokio::fs::remove_file(dir.join(relative)).await {
+            Ok(()) => stats.files_removed += 1,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+    }
+
+    tokio::fs::write(&manifest_path, serde_json::to_string_pretty(&manifest)?).await?;
+
+    Ok(stats)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(id: &str, name: &str) -> MinecraftItem {
+        MinecraftItem {
+            id: id.to_string(),
+            name: name.to_string(),
+            mod_id: namespace_of(id).to_string(),
+            tags: Vec::new(),
+            texture_path: None,
+            stack_size: 64,
+            rarity: "common".to_string(),
+            description: None,
+        }
+    }
+
+    fn tag(id: &str) -> MinecraftTag {
+        MinecraftTag {
+            id: id.to_string(),
+            tag_type: TagType::Item,
+            values: vec!["create:cogwheel".to_string()],
+        }
+    }
+
+    #[test]
+    fn test_render_bundle_groups_by_namespace() {
+        let items = vec![
+            item("create:cogwheel", "Cogwheel */ evil"),
+            item("mekanism:steel_ingot", "Steel Ingot"),
+        ];
+        let files = render_bundle(&items, &[], &[tag("c:gears")], &[]);
+
+        let keys: Vec<&str> = files.keys().map(String::as_str).collect();
+        assert_eq!(
+            keys,
+            vec![
+                "registries/c.d.ts",
+                "registries/create.d.ts",
+                "registries/mekanism.d.ts",
+                BASELINE_FILE,
+                UNCHECKED_FILE,
+            ]
+        );
+
+        let create = &files["registries/create.d.ts"];
+        assert!(create.contains("interface ItemRegistry"));
+        assert!(create.contains("\"create:cogwheel\": true;"));
+        assert!(create.contains("Cogwheel *\\/ evil"));
+        assert!(files["registries/c.d.ts"].contains("interface ItemTagRegistry"));
+
+        // Vanilla не проиндексирован — его ID не проверяются
+        assert!(files[UNCHECKED_FILE].contains("\"minecraft\": true;"));
+    }
+
+    #[tokio::test]
+    async fn test_sync_is_incremental() {
+        let dir = std::env::temp_dir().join(format!("stuzhik_typings_{}", uuid::Uuid::new_v4()));
+
+        let items = vec![
+            item("create:cogwheel", "Cogwheel"),
+            item("ae2:fluix", "Fluix"),
+        ];
+        let files = render_bundle(&items, &[], &[], &[]);
+
+        let first = sync_files(&dir, &files).await.unwrap();
+        assert_eq!(first.files_written, files.len());
+
+        let second = sync_files(&dir, &files).await.unwrap();
+        assert_eq!(second.files_written, 0);
+        assert_eq!(second.files_unchanged, files.len());
+
+        // Мод ae2 удалён, в create добавился предмет
+        let items = vec![
+            item("create:cogwheel", "Cogwheel"),
+            item("create:shaft", "Shaft"),
+        ];
+        let files = render_bundle(&items, &[], &[], &[]);
+        let third = sync_files(&dir, &files).await.unwrap();
+        assert_eq!(third.files_written, 1);
+        assert_eq!(third.files_removed, 1);
+        assert!(!dir.join("registries/ae2.d.ts").exists());
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
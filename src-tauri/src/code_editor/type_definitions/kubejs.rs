@@ -1,2 +1,278 @@
-// KubeJS type definitions (встроенные)
-// TODO: Встроить базовые типы KubeJS API
+//! Встроенные базовые typings KubeJS API
+//!
+//! Покрывают основные события и глобальные объекты KubeJS 6 (1.19.2–1.20.x).
+//! Типы ID (`Special.Item`, `Special.ItemTag`, ...) объявлены как `keyof` пустых интерфейсов —
+//! их наполняют сгенерированные `registries/*.d.ts` через declaration merging.
+
+/// Имя файла baseline внутри `kubejs/probe/generated`
+pub const BASELINE_FILE: &str = "stuzhik-kubejs.d.ts";
+
+pub const KUBEJS_BASELINE: &str = r#"// KubeJS baseline typings bundled with Stuzhik. Do not edit: regenerated automatically.
+
+declare namespace Special {
+    /** Заполняются файлами registries/*.d.ts */
+    interface ItemRegistry {}
+    interface BlockRegistry {}
+    interface ItemTagRegistry {}
+    interface BlockTagRegistry {}
+    interface ModRegistry {}
+    /** Namespaces, которых нет в кэше (например, vanilla) — их ID не проверяются */
+    interface UncheckedNamespaces {}
+
+    type Unchecked = `${keyof UncheckedNamespaces & string}:${string}`;
+    type Item = keyof ItemRegistry | Unchecked;
+    type Block = keyof BlockRegistry | Unchecked;
+    type ItemTag = `#${keyof ItemTagRegistry & string}` | `#${Unchecked}`;
+    type BlockTag = `#${keyof BlockTagRegistry & string}` | `#${Unchecked}`;
+    type Mod = keyof ModRegistry;
+}
+
+/** Предмет с количеством в формате KubeJS: "2x minecraft:stick" */
+type ItemWithCount = `${number}x ${Special.Item}`;
+type ItemStackLike = Special.Item | ItemWithCount | Internal.ItemStack;
+type IngredientLike =
+    | Special.Item
+    | Special.ItemTag
+    | `@${Special.Mod}`
+    | ItemWithCount
+    | Internal.ItemStack
+    | Internal.Ingredient
+    | IngredientLike[];
+
+declare namespace Internal {
+    interface ItemStack {
+        readonly id: Special.Item;
+        readonly count: number;
+        getId(): Special.Item;
+        getCount(): number;
+        withCount(count: number): ItemStack;
+        withNBT(nbt: object): ItemStack;
+        withName(name: string | object): ItemStack;
+        enchant(enchantment: string, level: number): ItemStack;
+        weakNBT(): Ingredient;
+        strongNBT(): Ingredient;
+        isEmpty(): boolean;
+    }
+
+    interface Ingredient {
+        test(item: ItemStackLike): boolean;
+        withCount(count: number): Ingredient;
+        getItemIds(): Special.Item[];
+        isEmpty(): boolean;
+    }
+
+    interface RecipeJS {
+        id(id: string): this;
+        group(group: string): this;
+        keepIngredient(ingredient: IngredientLike): this;
+        damageIngredient(ingredient: IngredientLike, damage?: number): this;
+        replaceIngredient(ingredient: IngredientLike, with_: ItemStackLike): this;
+        xp(xp: number): this;
+        cookingTime(ticks: number): this;
+        noMirror(): this;
+        noShrink(): this;
+    }
+
+    interface RecipeFilter {
+        id?: string | RegExp;
+        output?: IngredientLike;
+        input?: IngredientLike;
+        mod?: Special.Mod;
+        type?: string;
+        not?: RecipeFilter | RecipeFilter[];
+        or?: RecipeFilter[];
+    }
+
+    interface RecipesEventJS {
+        shaped(output: ItemStackLike, pattern: string[], keys: Record<string, IngredientLike>): RecipeJS;
+        shapeless(output: ItemStackLike, inputs: IngredientLike[]): RecipeJS;
+        smelting(output: ItemStackLike, input: IngredientLike): RecipeJS;
+        blasting(output: ItemStackLike, input: IngredientLike): RecipeJS;
+        smoking(output: ItemStackLike, input: IngredientLike): RecipeJS;
+        campfireCooking(output: ItemStackLike, input: IngredientLike): RecipeJS;
+        stonecutting(output: ItemStackLike, input: IngredientLike): RecipeJS;
+        smithing(output: ItemStackLike, template: IngredientLike, base: IngredientLike, addition: IngredientLike): RecipeJS;
+        custom(json: object): RecipeJS;
+        remove(filter: RecipeFilter): void;
+        replaceInput(filter: RecipeFilter, from: IngredientLike, to: IngredientLike): void;
+        replaceOutput(filter: RecipeFilter, from: IngredientLike, to: ItemStackLike): void;
+        forEachRecipe(filter: RecipeFilter, callback: (recipe: RecipeJS) => void): void;
+        containsRecipe(filter: RecipeFilter): boolean;
+        /** Рецепты модов: event.recipes.create.mixing(...) */
+        readonly recipes: Record<string, Record<string, (...args: any[]) => RecipeJS>>;
+    }
+
+    interface TagWrapper<T extends string, Tag extends string> {
+        add(...ids: (T | Tag)[]): this;
+        remove(...ids: (T | Tag)[]): this;
+        removeAll(): this;
+        getObjectIds(): T[];
+    }
+
+    interface TagEventJS<T extends string, Tag extends string> {
+        add(tag: string, ...ids: (T | Tag | RegExp)[]): TagWrapper<T, Tag>;
+        remove(tag: string, ...ids: (T | Tag | RegExp)[]): TagWrapper<T, Tag>;
+        removeAll(tag: string): TagWrapper<T, Tag>;
+        removeAllTagsFrom(...ids: T[]): void;
+        get(tag: string): TagWrapper<T, Tag>;
+    }
+
+    interface RegistryEventJS {
+        create(id: string, type?: string): any;
+        createCustom(id: string, supplier: () => any): any;
+    }
+
+    interface ServerEventJS {
+        readonly server: any;
+    }
+
+    interface PlayerEventJS extends ServerEventJS {
+        readonly player: any;
+        readonly level: any;
+        readonly entity: any;
+    }
+
+    interface ItemClickedEventJS extends PlayerEventJS {
+        readonly item: ItemStack;
+        readonly hand: "MAIN_HAND" | "OFF_HAND";
+        cancel(): void;
+    }
+
+    interface BlockEventJS extends PlayerEventJS {
+        readonly block: { readonly id: Special.Block; readonly pos: any; readonly properties: Record<string, string> };
+        cancel(): void;
+    }
+
+    interface EntityEventJS extends ServerEventJS {
+        readonly entity: any;
+        readonly level: any;
+        readonly source?: any;
+        cancel(): void;
+    }
+
+    interface ChatEventJS extends PlayerEventJS {
+        readonly message: string;
+        readonly username: string;
+        cancel(): void;
+    }
+
+    interface CommandEventJS extends ServerEventJS {
+        register(command: any): void;
+        readonly commands: any;
+        readonly arguments: any;
+    }
+
+    interface ItemTooltipEventJS {
+        add(item: IngredientLike, text: string | object | (string | object)[]): void;
+        addAdvanced(item: IngredientLike, handler: (item: ItemStack, advanced: boolean, text: any[]) => void): void;
+    }
+}
+
+type EventHandler<E> = (event: E) => void;
+
+declare const StartupEvents: {
+    init(handler: EventHandler<any>): void;
+    postInit(handler: EventHandler<any>): void;
+    registry(type: "item" | "block" | "fluid" | "enchantment" | "mob_effect" | "sound_event" | string, handler: EventHandler<Internal.RegistryEventJS>): void;
+};
+
+declare const ServerEvents: {
+    loaded(handler: EventHandler<Internal.ServerEventJS>): void;
+    unloaded(handler: EventHandler<Internal.ServerEventJS>): void;
+    tick(handler: EventHandler<Internal.ServerEventJS>): void;
+    recipes(handler: EventHandler<Internal.RecipesEventJS>): void;
+    afterRecipes(handler: EventHandler<Internal.RecipesEventJS>): void;
+    tags(type: "item", handler: EventHandler<Internal.TagEventJS<Special.Item, Special.ItemTag>>): void;
+    tags(type: "block", handler: EventHandler<Internal.TagEventJS<Special.Block, Special.BlockTag>>): void;
+    tags(type: string, handler: EventHandler<Internal.TagEventJS<string, `#${string}`>>): void;
+    commandRegistry(handler: EventHandler<Internal.CommandEventJS>): void;
+    customCommand(id: string, handler: EventHandler<Internal.PlayerEventJS>): void;
+};
+
+declare const ClientEvents: {
+    init(handler: EventHandler<any>): void;
+    loggedIn(handler: EventHandler<Internal.PlayerEventJS>): void;
+    loggedOut(handler: EventHandler<Internal.PlayerEventJS>): void;
+    tick(handler: EventHandler<Internal.PlayerEventJS>): void;
+};
+
+declare const ItemEvents: {
+    tooltip(handler: EventHandler<Internal.ItemTooltipEventJS>): void;
+    rightClicked(item: Special.Item, handler: EventHandler<Internal.ItemClickedEventJS>): void;
+    rightClicked(handler: EventHandler<Internal.ItemClickedEventJS>): void;
+    pickedUp(handler: EventHandler<Internal.ItemClickedEventJS>): void;
+    dropped(handler: EventHandler<Internal.ItemClickedEventJS>): void;
+    crafted(handler: EventHandler<Internal.ItemClickedEventJS>): void;
+};
+
+declare const BlockEvents: {
+    rightClicked(block: Special.Block, handler: EventHandler<Internal.BlockEventJS>): void;
+    rightClicked(handler: EventHandler<Internal.BlockEventJS>): void;
+    leftClicked(handler: EventHandler<Internal.BlockEventJS>): void;
+    broken(block: Special.Block, handler: EventHandler<Internal.BlockEventJS>): void;
+    broken(handler: EventHandler<Internal.BlockEventJS>): void;
+    placed(handler: EventHandler<Internal.BlockEventJS>): void;
+};
+
+declare const PlayerEvents: {
+    loggedIn(handler: EventHandler<Internal.PlayerEventJS>): void;
+    loggedOut(handler: EventHandler<Internal.PlayerEventJS>): void;
+    respawned(handler: EventHandler<Internal.PlayerEventJS>): void;
+    tick(handler: EventHandler<Internal.PlayerEventJS>): void;
+    chat(handler: EventHandler<Internal.ChatEventJS>): void;
+};
+
+declare const EntityEvents: {
+    death(handler: EventHandler<Internal.EntityEventJS>): void;
+    hurt(handler: EventHandler<Internal.EntityEventJS>): void;
+    spawned(handler: EventHandler<Internal.EntityEventJS>): void;
+};
+
+declare const Item: {
+    of(item: ItemStackLike, count?: number, nbt?: object): Internal.ItemStack;
+    readonly empty: Internal.ItemStack;
+    exists(id: string): boolean;
+    getList(): Internal.ItemStack[];
+};
+
+declare const Ingredient: {
+    of(ingredient: IngredientLike, count?: number): Internal.Ingredient;
+    readonly all: Internal.Ingredient;
+    readonly none: Internal.Ingredient;
+};
+
+declare const Platform: {
+    isForge(): boolean;
+    isFabric(): boolean;
+    isClientEnvironment(): boolean;
+    isLoaded(mod: Special.Mod): boolean;
+    getMcVersion(): string;
+};
+
+declare const JsonIO: {
+    read(path: string): any;
+    write(path: string, json: object): void;
+};
+
+declare const Text: {
+    of(text: string | object): any;
+    translate(key: string, ...args: any[]): any;
+    red(text: string): any;
+    green(text: string): any;
+    gold(text: string): any;
+    gray(text: string): any;
+};
+
+declare const Utils: {
+    readonly server: any;
+    id(namespace: string, path?: string): string;
+};
+
+declare const console: {
+    log(...args: any[]): void;
+    info(...args: any[]): void;
+    warn(...args: any[]): void;
+    error(...args: any[]): void;
+    debug(...args: any[]): void;
+};
+"#;
@@ -1,5 +1,7 @@
 // TypeScript definitions generator
-// TODO: Генерация .d.ts файлов для KubeJS и Minecraft
+// .d.ts bundle для KubeJS: встроенный baseline API + union типы ID из кэша экземпляра
 
 pub mod generator;
 pub mod kubejs;
+
+pub use generator::{GenerationStats, TypeDefinitionGenerator};
@@ -0,0 +1,261 @@
+//! Минимальный парсер cron-выражений
+//!
+//! Стандартные 5 полей: `минута час день_месяца месяц день_недели`.
+//! Поддерживаются `*`, `*/n`, `a`, `a-b`, `a-b/n`, списки через запятую и алиасы
+//! `@hourly`, `@daily`, `@weekly`, `@monthly`. День недели: 0–7 (0 и 7 — воскресенье).
+//! Если ограничены и день месяца, и день недели — срабатывает по любому из них (как в cron).
+
+use chrono::{
+    DateTime, Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, TimeZone, Timelike,
+};
+use std::str::FromStr;
+
+use crate::error::LauncherError;
+
+/// Горизонт поиска следующего срабатывания (выражения вроде `0 0 30 2 *` никогда не срабатывают)
+const SEARCH_DAYS: i64 = 366 * 5;
+
+/// Разобранное cron-выражение
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CronExpr {
+    minutes: u64,
+    hours: u32,
+    days_of_month: u32,
+    months: u16,
+    days_of_week: u8,
+    dom_restricted: bool,
+    dow_restricted: bool,
+}
+
+impl CronExpr {
+    /// Ближайший момент срабатывания строго после `after` (с точностью до минуты)
+    pub fn next_after<Tz: TimeZone>(&self, after: &DateTime<Tz>) -> Option<DateTime<Tz>> {
+        let tz = after.timezone();
+        let local = after.naive_local();
+        let start =
+            local.date().and_hms_opt(local.hour(), local.minute(), 0)? + Duration::minutes(1);
+
+        let mut date = start.date();
+        let last_date = date + Duration::days(SEARCH_DAYS);
+
+        while date <= last_date {
+            if self.matches_date(date) {
+                let from = if date == start.date() {
+                    (start.hour(), start.minute())
+                } else {
+                    (0, 0)
+                };
+                for (hour, minute) in self.times_from(from) {
+                    let naive = NaiveDateTime::new(date, NaiveTime::from_hms_opt(hour, minute, 0)?);
+                    // Несуществующее время (переход на летнее) пропускаем
+                    if let Some(dt) = tz.from_local_datetime(&naive).earliest() {
+                        return Some(dt);
+                    }
+                }
+            }
+            date = date.succ_opt()?;
+        }
+
+        None
+    }
+
+    fn matches_date(&self, date: NaiveDate) -> bool {
+        if self.months & (1 << date.month()) == 0 {
+            return false;
+        }
+        let dom = self.days_of_month & (1 << date.day()) != 0;
+        let dow = self.days_of_week & (1 << date.weekday().num_days_from_sunday()) != 0;
+
+        match (self.dom_restricted, self.dow_restricted) {
+            (true, true) => dom || dow,
+            (true, false) => dom,
+            (false, true) => dow,
+            (false, false) => true,
+        }
+    }
+
+    /// Подходящие (час, минута) начиная с `from` включительно
+    fn times_from(&self, from: (u32, u32)) -> impl Iterator<Item = (u32, u32)> + '_ {
+        (from.0..24)
+            .filter(move |h| self.hours & (1 << h) != 0)
+            .flat_map(move |h| {
+                let first_minute = if h == from.0 { from.1 } else { 0 };
+                (first_minute..60)
+                    .filter(move |m| self.minutes & (1 << m) != 0)
+                    .map(move |m| (h, m))
+            })
+    }
+}
+
+impl FromStr for CronExpr {
+    type Err = LauncherError;
+
+    fn from_str(expression: &str) -> Result<Self, Self::Err> {
+        let expression = match expression.trim() {
+            "@hourly" => "0 * * * *",
+            "@daily" | "@midnight" => "0 0 * * *",
+            "@weekly" => "0 0 * * 0",
+            "@monthly" => "0 0 1 * *",
+            other => other,
+        };
+
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        if fields.len() != 5 {
+            return Err(invalid(
+                expression,
+                "expected 5 fields: minute hour day-of-month month day-of-week",
+            ));
+        }
+
+        let minutes = parse_field(fields[0], 0, 59, expression)?;
+        let hours = parse_field(fields[1], 0, 23, expression)?;
+        let days_of_month = parse_field(fields[2], 1, 31, expression)?;
+        let months = parse_field(fields[3], 1, 12, expression)?;
+        let mut days_of_week = parse_field(fields[4], 0, 7, expression)?;
+        // 7 — тоже воскресенье
+        if days_of_week & (1 << 7) != 0 {
+            days_of_week |= 1;
+        }
+
+        Ok(Self {
+            minutes,
+            hours: hours as u32,
+            days_of_month: days_of_month as u32,
+            months: months as u16,
+            days_of_week: (days_of_week & 0x7f) as u8,
+            dom_restricted: fields[2] != "*",
+            dow_restricted: fields[4] != "*",
+        })
+    }
+}
+
+fn invalid(expression: &str, reason: &str) -> LauncherError {
+    LauncherError::InvalidConfig(format!(
+        "Invalid cron expression '{}': {}",
+        expression, reason
+    ))
+}
+
+/// Разобрать одно поле в битовую маску
+fn parse_field(field: &str, min: u32, max: u32, expression: &str) -> Result<u64, LauncherError> {
+    let mut mask = 0u64;
+
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => {
+                let step: u32 = step
+                    .parse()
+                    .map_err(|_| invalid(expression, &format!("bad step '{}'", part)))?;
+                if step == 0 {
+                    return Err(invalid(expression, "step must be positive"));
+                }
+                (range, step)
+            }
+            None => (part, 1),
+        };
+
+        let (start, end) = if range == "*" {
+            (min, max)
+        } else if let Some((a, b)) = range.split_once('-') {
+            (parse_value(a, expression)?, parse_value(b, expression)?)
+        } else {
+            let value = parse_value(range, expression)?;
+            // `5/15` означает «с 5 до конца с шагом 15»
+            (value, if step > 1 { max } else { value })
+        };
+
+        if start < min || end > max || start > end {
+            return Err(invalid(
+                expression,
+                &format!("'{}' is out of range {}-{}", part, min, max),
+            ));
+        }
+
+        for value in (start..=end).step_by(step as usize) {
+            mask |= 1 << value;
+        }
+    }
+
+    Ok(mask)
+}
+
+fn parse_value(value: &str, expression: &str) -> Result<u32, LauncherError> {
+    value
+        .parse()
+        .map_err(|_| invalid(expression, &format!("'{}' is not a number", value)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn at(y: i32, mo: u32, d: u32, h: u32, mi: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(y, mo, d, h, mi, 0).unwrap()
+    }
+
+    fn next(expr: &str, after: DateTime<Utc>) -> DateTime<Utc> {
+        expr.parse::<CronExpr>()
+            .unwrap()
+            .next_after(&after)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_next_after() {
+        // Каждые 15 минут
+        assert_eq!(
+            next("*/15 * * * *", at(2024, 6, 1, 10, 7)),
+            at(2024, 6, 1, 10, 15)
+        );
+        // Строго после: ровно 10:15 -> 10:30
+        assert_eq!(
+            next("*/15 * * * *", at(2024, 6, 1, 10, 15)),
+            at(2024, 6, 1, 10, 30)
+        );
+        // Ежедневно в 04:30, переход через полночь и конец месяца
+        assert_eq!(
+            next("30 4 * * *", at(2024, 6, 30, 5, 0)),
+            at(2024, 7, 1, 4, 30)
+        );
+        // Будни в 03:00: пятница вечером -> понедельник
+        assert_eq!(
+            next("0 3 * * 1-5", at(2024, 6, 7, 22, 0)),
+            at(2024, 6, 10, 3, 0)
+        );
+        // Воскресенье как 7
+        assert_eq!(
+            next("0 0 * * 7", at(2024, 6, 3, 0, 0)),
+            at(2024, 6, 9, 0, 0)
+        );
+        // Алиас
+        assert_eq!(
+            next("@monthly", at(2024, 2, 15, 12, 0)),
+            at(2024, 3, 1, 0, 0)
+        );
+    }
+
+    #[test]
+    fn test_day_of_month_or_weekday() {
+        // 13-е число ИЛИ пятница
+        let expr = "0 12 13 * 5";
+        assert_eq!(next(expr, at(2024, 6, 10, 0, 0)), at(2024, 6, 13, 12, 0));
+        assert_eq!(next(expr, at(2024, 6, 13, 13, 0)), at(2024, 6, 14, 12, 0));
+    }
+
+    #[test]
+    fn test_invalid_expressions() {
+        for expr in [
+            "* * * *",
+            "60 * * * *",
+            "*/0 * * * *",
+            "5-1 * * * *",
+            "a * * * *",
+        ] {
+            assert!(expr.parse::<CronExpr>().is_err(), "{}", expr);
+        }
+        // 30 февраля не наступает никогда
+        let never: CronExpr = "0 0 30 2 *".parse().unwrap();
+        assert!(never.next_after(&at(2024, 1, 1, 0, 0)).is_none());
+    }
+}
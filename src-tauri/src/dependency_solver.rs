@@ -0,0 +1,1311 @@
+//! Dependency Solver - согласованный план установки/обновления модов
+//!
+//! Собирает ограничения всех включённых модов экземпляра (диапазоны Maven
+//! `[1.0,2.0)`, semver `>=1.2 <2`, `^`, `~`, несовместимости) и версии-кандидаты
+//! с Modrinth (или файлы CurseForge для обновляемого CF-мода) для версии Minecraft
+//! и загрузчика экземпляра, затем ищет одно согласованное решение.
+//!
+//! Поиск - backtracking с conflict-directed backjumping: при тупике откатываемся
+//! сразу к решению, которое участвовало в конфликте, а не к последнему принятому.
+//! Если решения нет, возвращается человекочитаемое объяснение цепочки конфликтов.
+
+use crate::api::curseforge::CurseForgeFile;
+use crate::api::modrinth::{ModrinthClient, ModrinthVersion};
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::mods::{InstalledMod, ModManager, ModMatcher};
+use rusqlite::params;
+use serde::Serialize;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use std::fmt;
+
+/// Сколько версий одного проекта рассматривает решатель
+const MAX_CANDIDATES: usize = 32;
+
+/// Сколько новых проектов можно догрузить с Modrinth за один план
+const MAX_FETCHED_PACKAGES: usize = 48;
+
+/// Предел перебора - защита от экспоненциального поиска
+const MAX_STEPS: usize = 50_000;
+
+/// Идентификаторы платформы, а не модов: MC и загрузчик уже зафиксированы
+/// фильтром Modrinth по версии игры и загрузчику экземпляра
+const PLATFORM_IDS: &[&str] = &[
+    "minecraft",
+    "java",
+    "fabricloader",
+    "fabric-loader",
+    "quilt_loader",
+    "quilt-loader",
+    "forge",
+    "neoforge",
+    "fml",
+    "javafml",
+];
+
+// ========== Версии и диапазоны ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Token {
+    Num(u64),
+    /// Pre-release квалификатор: 0 = alpha/snapshot, 1 = beta, 2 = pre/rc
+    Pre(u8),
+}
+
+/// Убирает то, что не участвует в сравнении: `+build`, префикс `mc1.20.1-`, `v`
+fn normalize_version(version: &str) -> &str {
+    let mut v = version.trim();
+    v = v.split('+').next().unwrap_or(v);
+    if let Some((mc, tail)) = v.strip_prefix("mc").and_then(|rest| rest.split_once('-')) {
+        if !tail.is_empty() && mc.chars().all(|c| c.is_ascii_digit() || c == '.') {
+            v = tail;
+        }
+    }
+    v.strip_prefix(['v', 'V']).unwrap_or(v)
+}
+
+fn qualifier_rank(word: &str) -> Option<u8> {
+    match word.to_ascii_lowercase().as_str() {
+        "snapshot" | "dev" | "alpha" | "a" => Some(0),
+        "beta" | "b" => Some(1),
+        "pre" | "rc" | "cr" => Some(2),
+        // "release", "final", имена загрузчиков и прочие слова не влияют на порядок
+        _ => None,
+    }
+}
+
+fn tokenize(version: &str) -> Vec<Token> {
+    let mut tokens = Vec::new();
+    let mut chars = normalize_version(version).chars().peekable();
+
+    while let Some(&c) = chars.peek() {
+        if c.is_ascii_digit() {
+            let mut run = String::new();
+            while let Some(&d) = chars.peek().filter(|d| d.is_ascii_digit()) {
+                run.push(d);
+                chars.next();
+            }
+            tokens.push(Token::Num(run.parse().unwrap_or(u64::MAX)));
+        } else if c.is_ascii_alphabetic() {
+            let mut run = String::new();
+            while let Some(&l) = chars.peek().filter(|l| l.is_ascii_alphabetic()) {
+                run.push(l);
+                chars.next();
+            }
+            if let Some(rank) = qualifier_rank(&run) {
+                tokens.push(Token::Pre(rank));
+            }
+        } else {
+            chars.next();
+        }
+    }
+
+    tokens
+}
+
+/// Сравнение версий модов, терпимое к форматам авторов
+///
+/// Числовые сегменты сравниваются как числа (`1.10 > 1.9`), недостающие считаются
+/// нулями (`1.0 == 1.0.0`), alpha/beta/rc идут раньше релиза (`1.0-beta.2 < 1.0`).
+pub fn compare_mod_versions(a: &str, b: &str) -> Ordering {
+    let (a, b) = (tokenize(a), tokenize(b));
+
+    for i in 0..a.len().max(b.len()) {
+        let ordering = match (a.get(i), b.get(i)) {
+            (Some(Token::Num(x)), Some(Token::Num(y))) => x.cmp(y),
+            (Some(Token::Pre(x)), Some(Token::Pre(y))) => x.cmp(y),
+            (Some(Token::Num(_)), Some(Token::Pre(_))) => Ordering::Greater,
+            (Some(Token::Pre(_)), Some(Token::Num(_))) => Ordering::Less,
+            (Some(Token::Num(x)), None) => x.cmp(&0),
+            (None, Some(Token::Num(y))) => 0.cmp(y),
+            (Some(Token::Pre(_)), None) => Ordering::Less,
+            (None, Some(Token::Pre(_))) => Ordering::Greater,
+            (None, None) => Ordering::Equal,
+        };
+        if ordering != Ordering::Equal {
+            return ordering;
+        }
+    }
+
+    Ordering::Equal
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Eq,
+    Gt,
+    Ge,
+    Lt,
+    Le,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Comparator {
+    op: Op,
+    version: String,
+}
+
+impl Comparator {
+    fn new(op: Op, version: impl Into<String>) -> Self {
+        Self {
+            op,
+            version: version.into(),
+        }
+    }
+
+    fn matches(&self, version: &str) -> bool {
+        let ordering = compare_mod_versions(version, &self.version);
+        match self.op {
+            Op::Eq => ordering == Ordering::Equal,
+            Op::Gt => ordering == Ordering::Greater,
+            Op::Ge => ordering != Ordering::Less,
+            Op::Lt => ordering == Ordering::Less,
+            Op::Le => ordering != Ordering::Greater,
+        }
+    }
+}
+
+/// Требование к версии зависимости
+///
+/// Понимает Maven-диапазоны из mods.toml (`[1.0,2.0)`, `(,1.5]`, `[1.2]`, объединения
+/// через запятую) и semver-выражения из fabric.mod.json (`>=1.0 <2`, `^1.2`, `~1.2.3`,
+/// `1.20.x`, `||`). Голая версия означает точное совпадение.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VersionReq {
+    raw: String,
+    /// Дизъюнкция конъюнкций: подходит, если выполнена любая группа целиком
+    alternatives: Vec<Vec<Comparator>>,
+}
+
+impl VersionReq {
+    /// Разбор требования; `None` если формат не распознан
+    pub fn parse(raw: &str) -> Option<Self> {
+        let trimmed = raw.trim();
+        let alternatives = if trimmed.starts_with('[') || trimmed.starts_with('(') {
+            parse_maven(trimmed)?
+        } else {
+            parse_semver(trimmed)?
+        };
+
+        Some(Self {
+            raw: trimmed.to_string(),
+            alternatives,
+        })
+    }
+
+    pub fn matches(&self, version: &str) -> bool {
+        self.alternatives
+            .iter()
+            .any(|group| group.iter().all(|c| c.matches(version)))
+    }
+
+    /// Требование без ограничений (`*` или пустая строка)
+    pub fn is_any(&self) -> bool {
+        self.alternatives.iter().any(|group| group.is_empty())
+    }
+}
+
+impl fmt::Display for VersionReq {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+fn is_version(version: &str) -> bool {
+    normalize_version(version)
+        .chars()
+        .next()
+        .is_some_and(|c| c.is_ascii_digit())
+}
+
+fn parse_maven(spec: &str) -> Option<Vec<Vec<Comparator>>> {
+    let mut alternatives = Vec::new();
+    let mut rest = spec.trim();
+
+    while !rest.is_empty() {
+        let inclusive_low = match rest.chars().next()? {
+            '[' => true,
+            '(' => false,
+            _ => return None,
+        };
+        let close = rest.find([']', ')'])?;
+        let inclusive_high = rest[close..].starts_with(']');
+        let inner = &rest[1..close];
+        rest = rest[close + 1..]
+            .trim_start()
+            .trim_start_matches(',')
+            .trim_start();
+
+        let mut group = Vec::new();
+        match inner.split_once(',') {
+            None => {
+                let version = inner.trim();
+                if !(inclusive_low && inclusive_high) || !is_version(version) {
+                    return None;
+                }
+                group.push(Comparator::new(Op::Eq, version));
+            }
+            Some((low, high)) => {
+                let (low, high) = (low.trim(), high.trim());
+                if !low.is_empty() {
+                    if !is_version(low) {
+                        return None;
+                    }
+                    group.push(Comparator::new(
+                        if inclusive_low { Op::Ge } else { Op::Gt },
+                        low,
+                    ));
+                }
+                if !high.is_empty() {
+                    if !is_version(high) {
+                        return None;
+                    }
+                    group.push(Comparator::new(
+                        if inclusive_high { Op::Le } else { Op::Lt },
+                        high,
+                    ));
+                }
+            }
+        }
+        alternatives.push(group);
+    }
+
+    (!alternatives.is_empty()).then_some(alternatives)
+}
+
+fn parse_semver(spec: &str) -> Option<Vec<Vec<Comparator>>> {
+    let mut alternatives = Vec::new();
+
+    for alternative in spec.split("||") {
+        let mut group = Vec::new();
+        let mut pending_op = String::new();
+
+        for word in alternative.split_whitespace() {
+            // ">= 1.0" - оператор отдельным словом
+            if word
+                .chars()
+                .all(|c| matches!(c, '<' | '>' | '=' | '^' | '~'))
+            {
+                pending_op.push_str(word);
+                continue;
+            }
+            let token = format!("{}{}", std::mem::take(&mut pending_op), word);
+            group.extend(parse_comparator(&token)?);
+        }
+        if !pending_op.is_empty() {
+            return None;
+        }
+        alternatives.push(group);
+    }
+
+    Some(alternatives)
+}
+
+/// Числовой префикс версии: "1.20.x" -> [1, 20], "0.5.3-beta" -> [0, 5, 3]
+fn numeric_parts(version: &str) -> Vec<u64> {
+    normalize_version(version)
+        .split(['.', '-'])
+        .map_while(|part| part.parse().ok())
+        .collect()
+}
+
+/// Верхняя граница для `^`/`~`/`x`: увеличивает сегмент `index`, отбрасывая младшие
+fn bump(parts: &[u64], index: usize) -> String {
+    let mut bumped: Vec<u64> = parts[..=index].to_vec();
+    bumped[index] += 1;
+    bumped
+        .iter()
+        .map(|p| p.to_string())
+        .collect::<Vec<_>>()
+        .join(".")
+}
+
+fn parse_comparator(token: &str) -> Option<Vec<Comparator>> {
+    if matches!(token, "*" | "x" | "X") {
+        return Some(Vec::new());
+    }
+
+    let (op, version) = [">=", "<=", ">", "<", "=", "^", "~"]
+        .iter()
+        .find_map(|op| token.strip_prefix(op).map(|v| (*op, v)))
+        .unwrap_or(("", token));
+
+    // "1.20.x" / "1.20.*" - префикс без хвостовых wildcard-сегментов
+    let wildcard_at = version
+        .split('.')
+        .position(|part| matches!(part, "x" | "X" | "*"));
+    let version = match wildcard_at {
+        Some(0) => return Some(Vec::new()),
+        Some(n) => version.split('.').take(n).collect::<Vec<_>>().join("."),
+        None => version.to_string(),
+    };
+    if !is_version(&version) {
+        return None;
+    }
+    let parts = numeric_parts(&version);
+    let last = parts.len().checked_sub(1);
+
+    Some(match (op, wildcard_at) {
+        ("" | "=", Some(_)) => vec![
+            Comparator::new(Op::Ge, version.clone()),
+            Comparator::new(Op::Lt, bump(&parts, last?)),
+        ],
+        ("" | "=", None) => vec![Comparator::new(Op::Eq, version)],
+        (">=", _) => vec![Comparator::new(Op::Ge, version)],
+        ("<=", _) => vec![Comparator::new(Op::Le, version)],
+        (">", _) => vec![Comparator::new(Op::Gt, version)],
+        ("<", _) => vec![Comparator::new(Op::Lt, version)],
+        ("^", _) => {
+            let index = parts.iter().position(|p| *p != 0).unwrap_or(last?);
+            vec![
+                Comparator::new(Op::Ge, version.clone()),
+                Comparator::new(Op::Lt, bump(&parts, index)),
+            ]
+        }
+        ("~", _) => vec![
+            Comparator::new(Op::Ge, version.clone()),
+            Comparator::new(Op::Lt, bump(&parts, last?.min(1))),
+        ],
+        _ => return None,
+    })
+}
+
+// ========== Решатель ==========
+
+/// Ограничение на версию пакета
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Requirement {
+    Any,
+    Range(VersionReq),
+    /// Конкретная версия Modrinth; кандидаты без version_id (установленные) подходят
+    VersionId(String),
+}
+
+impl Requirement {
+    /// Из строки `version_requirement` в БД
+    pub fn from_db(requirement: Option<&str>) -> Self {
+        requirement
+            .and_then(VersionReq::parse)
+            .filter(|req| !req.is_any())
+            .map_or(Self::Any, Self::Range)
+    }
+
+    fn allows(&self, candidate: &Candidate) -> bool {
+        match self {
+            Self::Any => true,
+            Self::Range(req) => req.matches(&candidate.version),
+            Self::VersionId(id) => candidate.version_id.as_ref().map_or(true, |c| c == id),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DependencyKind {
+    Required,
+    Incompatible,
+}
+
+#[derive(Debug, Clone)]
+pub struct Dependency {
+    pub package: String,
+    pub kind: DependencyKind,
+    pub requirement: Requirement,
+}
+
+impl Dependency {
+    pub fn required(package: impl Into<String>, requirement: Requirement) -> Self {
+        Self {
+            package: package.into(),
+            kind: DependencyKind::Required,
+            requirement,
+        }
+    }
+
+    pub fn incompatible(package: impl Into<String>, requirement: Requirement) -> Self {
+        Self {
+            package: package.into(),
+            kind: DependencyKind::Incompatible,
+            requirement,
+        }
+    }
+
+    /// Допускает ли ограничение выбор этого кандидата
+    fn permits(&self, candidate: &Candidate) -> bool {
+        match self.kind {
+            DependencyKind::Required => self.requirement.allows(candidate),
+            DependencyKind::Incompatible => !self.requirement.allows(candidate),
+        }
+    }
+}
+
+/// Одна версия пакета
+#[derive(Debug, Clone)]
+pub struct Candidate {
+    pub version: String,
+    pub version_id: Option<String>,
+    pub dependencies: Vec<Dependency>,
+}
+
+/// Пакет и его версии в порядке предпочтения (лучшая первой)
+#[derive(Debug, Clone)]
+pub struct Package {
+    pub name: String,
+    pub candidates: Vec<Candidate>,
+}
+
+/// Задача для решателя: пакеты и корневые требования
+///
+/// Зависимости на пакеты, которых нет в задаче, игнорируются - за полноту
+/// отвечает тот, кто собирает задачу.
+#[derive(Debug, Default)]
+pub struct Problem {
+    packages: HashMap<String, Package>,
+    /// (кто требует, требование) - "the request", "the instance"
+    root: Vec<(String, Dependency)>,
+}
+
+#[derive(Clone, Copy)]
+enum Origin<'a> {
+    Root(&'a str),
+    Package(&'a str, &'a Candidate),
+}
+
+struct Failure<'a> {
+    /// Пакеты, чьи выбранные версии участвуют в конфликте
+    culprits: HashSet<&'a str>,
+    explanation: String,
+}
+
+struct Search<'a> {
+    problem: &'a Problem,
+    assigned: Vec<(&'a str, &'a Candidate)>,
+    steps: usize,
+    exhausted: bool,
+}
+
+impl Problem {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn add_package(&mut self, key: impl Into<String>, package: Package) {
+        self.packages.insert(key.into(), package);
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.packages.contains_key(key)
+    }
+
+    /// Корневое требование; `origin` попадает в объяснение конфликта
+    pub fn require(&mut self, origin: impl Into<String>, dependency: Dependency) {
+        self.root.push((origin.into(), dependency));
+    }
+
+    /// Ищет согласованный набор версий; при неудаче - текст объяснения
+    pub fn solve(&self) -> std::result::Result<BTreeMap<String, Candidate>, String> {
+        let mut search = Search {
+            problem: self,
+            assigned: Vec::new(),
+            steps: 0,
+            exhausted: false,
+        };
+
+        match search.run() {
+            Ok(()) => Ok(search
+                .assigned
+                .iter()
+                .map(|(key, candidate)| (key.to_string(), (*candidate).clone()))
+                .collect()),
+            Err(_) if search.exhausted => Err(format!(
+                "Gave up after trying {} version combinations; install fewer mods at once",
+                MAX_STEPS
+            )),
+            Err(failure) => Err(failure.explanation),
+        }
+    }
+}
+
+impl<'a> Search<'a> {
+    fn name(&self, key: &str) -> &'a str {
+        self.problem
+            .packages
+            .get_key_value(key)
+            .map_or("?", |(k, p)| {
+                if p.name.is_empty() {
+                    k.as_str()
+                } else {
+                    p.name.as_str()
+                }
+            })
+    }
+
+    fn selected(&self, key: &str) -> Option<(&'a str, &'a Candidate)> {
+        self.assigned.iter().find(|(k, _)| *k == key).copied()
+    }
+
+    /// Все активные ограничения: корневые и от уже выбранных версий
+    fn active(&self) -> impl Iterator<Item = (Origin<'a>, &'a Dependency)> + '_ {
+        let root = self
+            .problem
+            .root
+            .iter()
+            .map(|(origin, dep)| (Origin::Root(origin.as_str()), dep));
+        let chosen = self.assigned.iter().flat_map(|&(key, candidate)| {
+            candidate
+                .dependencies
+                .iter()
+                .map(move |dep| (Origin::Package(key, candidate), dep))
+        });
+        root.chain(chosen)
+    }
+
+    fn constraints_on(&self, key: &str) -> Vec<(Origin<'a>, &'a Dependency)> {
+        self.active()
+            .filter(|(_, dep)| dep.package == key)
+            .collect()
+    }
+
+    /// Следующий пакет: обязательный, ещё не выбранный, с наименьшим числом вариантов
+    fn next_package(&self) -> Option<&'a str> {
+        let mut pending: Vec<&'a str> = Vec::new();
+        for (_, dep) in self.active() {
+            if dep.kind != DependencyKind::Required {
+                continue;
+            }
+            let Some((key, _)) = self.problem.packages.get_key_value(dep.package.as_str()) else {
+                continue;
+            };
+            let key = key.as_str();
+            if self.selected(key).is_none() && !pending.contains(&key) {
+                pending.push(key);
+            }
+        }
+
+        pending.into_iter().min_by_key(|key| {
+            let constraints = self.constraints_on(key);
+            self.problem.packages[*key]
+                .candidates
+                .iter()
+                .filter(|c| constraints.iter().all(|(_, dep)| dep.permits(c)))
+                .count()
+        })
+    }
+
+    fn describe(&self, origin: Origin<'a>, dep: &Dependency) -> String {
+        let who = match origin {
+            Origin::Root(label) => label.to_string(),
+            Origin::Package(key, candidate) => format!("{} {}", self.name(key), candidate.version),
+        };
+        format!("{} {}", who, self.relation(dep))
+    }
+
+    /// "requires Lib >=2.0" / "is incompatible with Lib"
+    fn relation(&self, dep: &Dependency) -> String {
+        let verb = match dep.kind {
+            DependencyKind::Required => "requires",
+            DependencyKind::Incompatible => "is incompatible with",
+        };
+        let range = match &dep.requirement {
+            Requirement::Any => String::new(),
+            Requirement::Range(req) => format!(" {}", req),
+            Requirement::VersionId(id) => format!(" (version {})", id),
+        };
+        format!("{} {}{}", verb, self.name(&dep.package), range)
+    }
+
+    /// Конфликт зависимостей кандидата с уже выбранными версиями
+    fn clash(&self, candidate: &'a Candidate) -> Option<(&'a str, String)> {
+        candidate.dependencies.iter().find_map(|dep| {
+            let (key, chosen) = self.selected(&dep.package)?;
+            (!dep.permits(chosen)).then(|| {
+                let reason = format!(
+                    "{}, but {} {} is selected",
+                    self.relation(dep),
+                    self.name(key),
+                    chosen.version
+                );
+                (key, reason)
+            })
+        })
+    }
+
+    fn run(&mut self) -> std::result::Result<(), Failure<'a>> {
+        let Some(key) = self.next_package() else {
+            return Ok(());
+        };
+        let problem = self.problem;
+        let package = &problem.packages[key];
+        let constraints = self.constraints_on(key);
+
+        let mut culprits = HashSet::new();
+        let mut rejected = Vec::new();
+        let mut deeper: Option<String> = None;
+
+        for candidate in &package.candidates {
+            self.steps += 1;
+            if self.steps > MAX_STEPS {
+                self.exhausted = true;
+                return Err(Failure {
+                    culprits: HashSet::new(),
+                    explanation: String::new(),
+                });
+            }
+
+            if let Some((origin, dep)) = constraints.iter().find(|(_, dep)| !dep.permits(candidate))
+            {
+                if let Origin::Package(culprit, _) = origin {
+                    culprits.insert(*culprit);
+                }
+                rejected.push(format!(
+                    "{}: {}",
+                    candidate.version,
+                    self.describe(*origin, dep)
+                ));
+                continue;
+            }
+            if let Some((culprit, reason)) = self.clash(candidate) {
+                culprits.insert(culprit);
+                rejected.push(format!("{}: {}", candidate.version, reason));
+                continue;
+            }
+
+            self.assigned.push((key, candidate));
+            match self.run() {
+                Ok(()) => return Ok(()),
+                Err(failure) => {
+                    self.assigned.pop();
+                    // Выбор этой версии не причастен к конфликту - прыгаем выше
+                    if !failure.culprits.contains(&key) {
+                        return Err(failure);
+                    }
+                    culprits.extend(failure.culprits.into_iter().filter(|c| *c != key));
+                    rejected.push(format!(
+                        "{}: leads to the conflict below",
+                        candidate.version
+                    ));
+                    deeper = Some(failure.explanation);
+                }
+            }
+        }
+
+        // Те, кто сделал пакет обязательным, тоже часть конфликта
+        for (origin, dep) in &constraints {
+            if let (Origin::Package(culprit, _), DependencyKind::Required) = (origin, dep.kind) {
+                culprits.insert(*culprit);
+            }
+        }
+
+        let mut lines = vec![format!("Cannot pick a version of {}:", self.name(key))];
+        for (origin, dep) in &constraints {
+            lines.push(format!("  - {}", self.describe(*origin, dep)));
+        }
+        if package.candidates.is_empty() {
+            lines.push(
+                "  - no versions are available for this Minecraft version and loader".to_string(),
+            );
+        }
+        let shown = rejected.len().min(5);
+        for line in &rejected[..shown] {
+            lines.push(format!("  - version {}", line));
+        }
+        if rejected.len() > shown {
+            lines.push(format!(
+                "  - ... and {} more versions",
+                rejected.len() - shown
+            ));
+        }
+        if let Some(deeper) = deeper {
+            lines.push(deeper);
+        }
+
+        Err(Failure {
+            culprits,
+            explanation: lines.join("\n"),
+        })
+    }
+}
+
+// ========== План для экземпляра ==========
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PlanAction {
+    Install,
+    Upgrade,
+    Downgrade,
+}
+
+/// Шаг плана: что поставить и какой версией
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanStep {
+    /// Slug или project_id Modrinth
+    pub slug: String,
+    pub name: String,
+    pub action: PlanAction,
+    pub from_version: Option<String>,
+    pub to_version: String,
+    /// id версии Modrinth или id файла CurseForge
+    pub version_id: String,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct InstallPlan {
+    pub steps: Vec<PlanStep>,
+}
+
+impl InstallPlan {
+    pub fn step(&self, slug: &str) -> Option<&PlanStep> {
+        self.steps.iter().find(|s| s.slug == slug)
+    }
+}
+
+/// Допустимые версии цели (новые первыми)
+#[derive(Debug, Clone)]
+pub enum TargetVersions {
+    Modrinth(Vec<ModrinthVersion>),
+    /// Файлы CurseForge; версией кандидата считается имя файла, как в `mods.version`
+    CurseForge(Vec<CurseForgeFile>),
+}
+
+/// Проект, который план должен поставить или обновить
+#[derive(Debug, Clone)]
+pub struct PlanTarget {
+    pub slug: String,
+    /// Допустимые версии в порядке предпочтения
+    pub versions: TargetVersions,
+    /// Текущая версия, если мод уже установлен (обновление)
+    pub installed_version: Option<String>,
+}
+
+#[derive(Debug, Clone)]
+pub struct PlanRequest<'a> {
+    pub instance_id: &'a str,
+    pub minecraft_version: &'a str,
+    pub loader: &'a str,
+    pub targets: Vec<PlanTarget>,
+    /// Догружать с Modrinth новые зависимости выбранных версий
+    pub follow_dependencies: bool,
+    /// Ставить недостающие обязательные зависимости уже установленных модов
+    pub resolve_missing: bool,
+}
+
+/// Сопоставление идентификаторов зависимостей с ключами пакетов
+struct KeyResolver<'a> {
+    mods: &'a [InstalledMod],
+    aliases: HashMap<String, String>,
+}
+
+impl KeyResolver<'_> {
+    /// `None` - платформа (MC, Java, загрузчик), а не мод
+    fn resolve(&self, dep_id: &str) -> Option<String> {
+        let lower = dep_id.to_lowercase();
+        if PLATFORM_IDS.contains(&lower.as_str()) {
+            return None;
+        }
+        if let Some(key) = self.aliases.get(&lower) {
+            return Some(key.clone());
+        }
+        Some(ModMatcher::resolve_to_slug(self.mods, dep_id))
+    }
+}
+
+fn candidate_from_modrinth(version: &ModrinthVersion, keys: &KeyResolver<'_>) -> Candidate {
+    let dependencies = version
+        .dependencies
+        .iter()
+        .filter_map(|dep| {
+            let key = keys.resolve(dep.project_id.as_deref()?)?;
+            let requirement = dep
+                .version_id
+                .clone()
+                .map_or(Requirement::Any, Requirement::VersionId);
+            match dep.dependency_type.as_str() {
+                "required" => Some(Dependency::required(key, requirement)),
+                "incompatible" => Some(Dependency::incompatible(key, requirement)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    Candidate {
+        version: version.version_number.clone(),
+        version_id: Some(version.id.clone()),
+        dependencies,
+    }
+}
+
+fn candidate_from_curseforge(file: &CurseForgeFile, keys: &KeyResolver<'_>) -> Candidate {
+    // CurseForge указывает зависимость проектом, без версии
+    let dependencies = file
+        .dependencies
+        .iter()
+        .filter_map(|dep| {
+            let key = keys.resolve(&dep.mod_id.to_string())?;
+            match dep.relation_type {
+                3 => Some(Dependency::required(key, Requirement::Any)),
+                5 => Some(Dependency::incompatible(key, Requirement::Any)),
+                _ => None,
+            }
+        })
+        .collect();
+
+    Candidate {
+        version: file.file_name.clone(),
+        version_id: Some(file.id.to_string()),
+        dependencies,
+    }
+}
+
+/// Release-версии вперёд, внутри групп порядок Modrinth (новые первыми)
+fn prefer_releases(versions: &[ModrinthVersion]) -> Vec<&ModrinthVersion> {
+    let (mut releases, others): (Vec<_>, Vec<_>) =
+        versions.iter().partition(|v| v.version_type == "release");
+    releases.extend(others);
+    releases.truncate(MAX_CANDIDATES);
+    releases
+}
+
+/// То же для файлов CurseForge (release_type 1 - release)
+fn prefer_release_files(files: &[CurseForgeFile]) -> Vec<&CurseForgeFile> {
+    let (mut releases, others): (Vec<_>, Vec<_>) = files.iter().partition(|f| f.release_type == 1);
+    releases.extend(others);
+    releases.truncate(MAX_CANDIDATES);
+    releases
+}
+
+/// Кандидаты цели в порядке предпочтения
+fn target_candidates(target: &PlanTarget, keys: &KeyResolver<'_>) -> Vec<Candidate> {
+    match &target.versions {
+        TargetVersions::Modrinth(versions) => prefer_releases(versions)
+            .into_iter()
+            .map(|v| candidate_from_modrinth(v, keys))
+            .collect(),
+        TargetVersions::CurseForge(files) => prefer_release_files(files)
+            .into_iter()
+            .map(|f| candidate_from_curseforge(f, keys))
+            .collect(),
+    }
+}
+
+fn installed_dependencies(mod_id: i64) -> Result<Vec<(String, String, Option<String>)>> {
+    let conn = get_db_conn()?;
+    let mut stmt = conn.prepare(
+        "SELECT dependency_slug, dependency_type, version_requirement
+         FROM mod_dependencies WHERE mod_id = ?1",
+    )?;
+    let rows = stmt.query_map(params![mod_id], |row| {
+        Ok((
+            row.get::<_, String>(0)?,
+            row.get::<_, String>(1)?,
+            row.get::<_, Option<String>>(2)?,
+        ))
+    })?;
+    Ok(rows.collect::<std::result::Result<Vec<_>, _>>()?)
+}
+
+pub struct DependencySolver;
+
+impl DependencySolver {
+    /// Строит согласованный план установки/обновления для экземпляра
+    ///
+    /// Включённые моды, не входящие в `targets`, зафиксированы на своих версиях:
+    /// план может только добавить моды или поменять версии целей. Ограничения
+    /// между зафиксированными модами не проверяются - их не меняет ни один план,
+    /// о них сообщает `check_dependencies`.
+    pub async fn plan(request: &PlanRequest<'_>) -> Result<InstallPlan> {
+        let all_mods = ModManager::list_mods(request.instance_id)?;
+        let mods: Vec<InstalledMod> = all_mods.into_iter().filter(|m| m.enabled).collect();
+
+        let target_slugs: HashSet<&str> = request.targets.iter().map(|t| t.slug.as_str()).collect();
+        let locked: Vec<&InstalledMod> = mods
+            .iter()
+            .filter(|m| !target_slugs.contains(m.slug.as_str()))
+            .collect();
+
+        let mut aliases = HashMap::new();
+        for target in &request.targets {
+            aliases.insert(target.slug.to_lowercase(), target.slug.clone());
+            if let TargetVersions::Modrinth(versions) = &target.versions {
+                for version in versions {
+                    aliases.insert(version.project_id.to_lowercase(), target.slug.clone());
+                }
+            }
+        }
+        // Зависимости CurseForge ссылаются на числовой id проекта (source_id)
+        for installed in mods
+            .iter()
+            .filter(|m| target_slugs.contains(m.slug.as_str()))
+        {
+            if let Some(source_id) = &installed.source_id {
+                aliases.insert(source_id.to_lowercase(), installed.slug.clone());
+            }
+        }
+        let keys = KeyResolver {
+            mods: &mods,
+            aliases,
+        };
+        let locked_keys: HashSet<&str> = locked.iter().map(|m| m.slug.as_str()).collect();
+
+        let mut problem = Problem::new();
+        let mut queue: VecDeque<String> = VecDeque::new();
+        let enqueue = |key: &str, queue: &mut VecDeque<String>| {
+            if !locked_keys.contains(key)
+                && !target_slugs.contains(key)
+                && !queue.iter().any(|k| k == key)
+            {
+                queue.push_back(key.to_string());
+            }
+        };
+
+        for target in &request.targets {
+            let candidates = target_candidates(target, &keys);
+            if request.follow_dependencies {
+                for dep in candidates.iter().flat_map(|c| &c.dependencies) {
+                    if dep.kind == DependencyKind::Required {
+                        enqueue(&dep.package, &mut queue);
+                    }
+                }
+            }
+            problem.add_package(
+                target.slug.clone(),
+                Package {
+                    name: target.slug.clone(),
+                    candidates,
+                },
+            );
+            problem.require(
+                "the request",
+                Dependency::required(&target.slug, Requirement::Any),
+            );
+        }
+
+        for installed in &locked {
+            let mut dependencies = Vec::new();
+            for (dep_id, dep_type, requirement) in installed_dependencies(installed.id)? {
+                let Some(key) = keys.resolve(&dep_id) else {
+                    continue;
+                };
+                if key == installed.slug || locked_keys.contains(key.as_str()) {
+                    continue;
+                }
+                let requirement = Requirement::from_db(requirement.as_deref());
+                match dep_type.as_str() {
+                    "required" => {
+                        let is_target = target_slugs.contains(key.as_str());
+                        // Уже стоящие зависимости (в т.ч. Fabric API через Connector) не трогаем
+                        if !is_target
+                            && (!request.resolve_missing
+                                || ModMatcher::is_installed(&mods, &dep_id))
+                        {
+                            continue;
+                        }
+                        if !is_target {
+                            enqueue(&key, &mut queue);
+                        }
+                        dependencies.push(Dependency::required(key, requirement));
+                    }
+                    "incompatible" => dependencies.push(Dependency::incompatible(key, requirement)),
+                    _ => {}
+                }
+            }
+
+            problem.add_package(
+                installed.slug.clone(),
+                Package {
+                    name: installed.name.clone(),
+                    candidates: vec![Candidate {
+                        version: installed.version.clone(),
+                        version_id: None,
+                        dependencies,
+                    }],
+                },
+            );
+            problem.require(
+                "the instance",
+                Dependency::required(&installed.slug, Requirement::Any),
+            );
+        }
+
+        // Догружаем версии новых зависимостей (в ширину, с ограничением)
+        let mut fetched: Vec<String> = Vec::new();
+        let mut seen: HashSet<String> = HashSet::new();
+        while let Some(key) = queue.pop_front() {
+            if problem.contains(&key) || !seen.insert(key.clone()) {
+                continue;
+            }
+            if fetched.len() >= MAX_FETCHED_PACKAGES {
+                log::warn!("Dependency plan: too many new projects, ignoring {}", key);
+                continue;
+            }
+            let versions = match ModrinthClient::get_project_versions(
+                &key,
+                Some(request.minecraft_version),
+                Some(request.loader),
+            )
+            .await
+            {
+                Ok(versions) => versions,
+                Err(e) => {
+                    // Не проект Modrinth (например mod_id из JAR) - не можем ни поставить, ни проверить
+                    log::debug!("Dependency plan: cannot fetch versions of {}: {}", key, e);
+                    continue;
+                }
+            };
+
+            let candidates: Vec<Candidate> = prefer_releases(&versions)
+                .into_iter()
+                .map(|v| candidate_from_modrinth(v, &keys))
+                .collect();
+            for dep in candidates.iter().flat_map(|c| &c.dependencies) {
+                if dep.kind == DependencyKind::Required && !problem.contains(&dep.package) {
+                    enqueue(&dep.package, &mut queue);
+                }
+            }
+            problem.add_package(
+                key.clone(),
+                Package {
+                    name: String::new(),
+                    candidates,
+                },
+            );
+            fetched.push(key);
+        }
+
+        // Человеческие имена и slug для новых проектов (ключи часто project_id)
+        let mut projects: HashMap<String, (String, String)> = HashMap::new();
+        if !fetched.is_empty() {
+            match ModrinthClient::get_projects(&fetched).await {
+                Ok(list) => {
+                    for project in list {
+                        let entry = (project.slug.clone(), project.title.clone());
+                        projects.insert(project.id.clone(), entry.clone());
+                        projects.insert(project.slug.clone(), entry);
+                    }
+                }
+                Err(e) => log::debug!("Dependency plan: cannot fetch project names: {}", e),
+            }
+            for key in &fetched {
+                if let (Some(package), Some((_, title))) =
+                    (problem.packages.get_mut(key), projects.get(key))
+                {
+                    package.name = title.clone();
+                }
+            }
+        }
+
+        let solution = problem.solve().map_err(LauncherError::DependencyConflict)?;
+
+        let mut steps = Vec::new();
+        for (key, candidate) in solution {
+            let Some(version_id) = candidate.version_id.clone() else {
+                continue; // установленный мод остаётся как есть
+            };
+            let installed_version = request
+                .targets
+                .iter()
+                .find(|t| t.slug == key)
+                .and_then(|t| t.installed_version.clone());
+            let action = match installed_version.as_deref() {
+                None => PlanAction::Install,
+                Some(current) if current == candidate.version => continue,
+                Some(current) => match compare_mod_versions(&candidate.version, current) {
+                    Ordering::Less => PlanAction::Downgrade,
+                    _ => PlanAction::Upgrade,
+                },
+            };
+            let (slug, name) = projects
+                .get(&key)
+                .cloned()
+                .unwrap_or_else(|| (key.clone(), problem.packages[&key].name.clone()));
+
+            steps.push(PlanStep {
+                slug,
+                name,
+                action,
+                from_version: installed_version,
+                to_version: candidate.version,
+                version_id,
+            });
+        }
+
+        Ok(InstallPlan { steps })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::api::curseforge::CurseForgeDependency;
+    use crate::utils::version_matches_requirement;
+
+    fn candidate(version: &str, dependencies: Vec<Dependency>) -> Candidate {
+        Candidate {
+            version: version.to_string(),
+            version_id: Some(format!("id-{}", version)),
+            dependencies,
+        }
+    }
+
+    fn range(spec: &str) -> Requirement {
+        Requirement::Range(VersionReq::parse(spec).unwrap())
+    }
+
+    #[test]
+    fn version_ranges() {
+        assert_eq!(compare_mod_versions("1.10.0", "1.9.2"), Ordering::Greater);
+        assert_eq!(compare_mod_versions("1.0", "1.0.0"), Ordering::Equal);
+        assert_eq!(
+            compare_mod_versions("1.0.0-beta.2", "1.0.0"),
+            Ordering::Less
+        );
+        assert_eq!(
+            compare_mod_versions("mc1.20.1-0.5.3", "0.5.3+fabric"),
+            Ordering::Equal
+        );
+
+        assert!(version_matches_requirement("1.5", "[1.0,2.0)"));
+        assert!(!version_matches_requirement("2.0", "[1.0,2.0)"));
+        assert!(version_matches_requirement("47.2.0", "[47,)"));
+        assert!(version_matches_requirement("3.1", "(,1.0],[3.0,)"));
+        assert!(version_matches_requirement("0.15.11", ">=0.15.0 <0.16"));
+        assert!(version_matches_requirement("1.20.4", "1.20.x"));
+        assert!(!version_matches_requirement("1.21", "~1.20.1"));
+        assert!(version_matches_requirement("1.9.9", "^1.2 || ^2"));
+        assert!(!version_matches_requirement("0.3.0", "^0.2.1"));
+        assert!(version_matches_requirement(
+            "anything",
+            "${file.jarVersion}"
+        ));
+    }
+
+    #[test]
+    fn curseforge_files_become_candidates() {
+        let file = |id: u64, release_type: u8, dependencies| CurseForgeFile {
+            id,
+            display_name: format!("JEI {}", id),
+            file_name: format!("jei-{}.jar", id),
+            file_date: String::new(),
+            file_length: 0,
+            download_url: None,
+            game_versions: Vec::new(),
+            dependencies,
+            hashes: Vec::new(),
+            download_count: 0,
+            release_type,
+        };
+        let keys = KeyResolver {
+            mods: &[],
+            aliases: HashMap::from([("238222".to_string(), "jei".to_string())]),
+        };
+        let target = PlanTarget {
+            slug: "jei-addon".to_string(),
+            versions: TargetVersions::CurseForge(vec![
+                file(2, 2, Vec::new()),
+                file(
+                    1,
+                    1,
+                    vec![
+                        CurseForgeDependency {
+                            mod_id: 238222,
+                            relation_type: 3,
+                        },
+                        CurseForgeDependency {
+                            mod_id: 1,
+                            relation_type: 2,
+                        },
+                    ],
+                ),
+            ]),
+            installed_version: None,
+        };
+
+        // Release впереди beta, версия - имя файла, id - id файла
+        let candidates = target_candidates(&target, &keys);
+        assert_eq!(candidates[0].version, "jei-1.jar");
+        assert_eq!(candidates[0].version_id.as_deref(), Some("1"));
+        assert_eq!(candidates[0].dependencies.len(), 1);
+        assert_eq!(candidates[0].dependencies[0].package, "jei");
+        assert_eq!(candidates[1].version, "jei-2.jar");
+    }
+
+    #[test]
+    fn picks_older_version_to_satisfy_installed_constraint() {
+        let mut problem = Problem::new();
+        problem.add_package(
+            "sodium",
+            Package {
+                name: "Sodium".to_string(),
+                candidates: vec![candidate("0.6.0", vec![]), candidate("0.5.8", vec![])],
+            },
+        );
+        problem.add_package(
+            "iris",
+            Package {
+                name: "Iris".to_string(),
+                candidates: vec![candidate(
+                    "1.7.0",
+                    vec![Dependency::required("sodium", range("[0.5,0.6)"))],
+                )],
+            },
+        );
+        problem.require(
+            "the instance",
+            Dependency::required("iris", Requirement::Any),
+        );
+        problem.require(
+            "the request",
+            Dependency::required("sodium", Requirement::Any),
+        );
+
+        let solution = problem.solve().unwrap();
+        assert_eq!(solution["sodium"].version, "0.5.8");
+    }
+
+    #[test]
+    fn explains_unsatisfiable_constraints() {
+        let mut problem = Problem::new();
+        problem.add_package(
+            "lib",
+            Package {
+                name: "Lib".to_string(),
+                candidates: vec![candidate("2.0", vec![]), candidate("1.0", vec![])],
+            },
+        );
+        problem.add_package(
+            "a",
+            Package {
+                name: "A".to_string(),
+                candidates: vec![candidate(
+                    "1.0",
+                    vec![Dependency::required("lib", range(">=2.0"))],
+                )],
+            },
+        );
+        problem.add_package(
+            "b",
+            Package {
+                name: "B".to_string(),
+                candidates: vec![
+                    candidate("3.0", vec![Dependency::required("lib", range("<2.0"))]),
+                    candidate("2.0", vec![Dependency::incompatible("a", Requirement::Any)]),
+                ],
+            },
+        );
+        problem.require("the instance", Dependency::required("a", Requirement::Any));
+        problem.require("the request", Dependency::required("b", Requirement::Any));
+
+        let explanation = problem.solve().unwrap_err();
+        assert!(
+            explanation.contains("Cannot pick a version of B"),
+            "{}",
+            explanation
+        );
+        assert!(
+            explanation.contains("is incompatible with A"),
+            "{}",
+            explanation
+        );
+        assert!(explanation.contains("requires Lib <2.0"), "{}", explanation);
+    }
+}
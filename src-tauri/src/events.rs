@@ -0,0 +1,54 @@
+//! Event sink for progress events
+//!
+//! В GUI события уходят во фронтенд через `AppHandle::emit`, в headless режиме
+//! (`stuzhik-cli`) — только в лог. Позволяет переиспользовать установку экземпляров,
+//! загрузки и модпаки без запущенного Tauri приложения.
+
+use serde::Serialize;
+use tauri::Emitter;
+
+/// Куда отправлять события прогресса
+#[derive(Clone)]
+pub enum EventSink {
+    /// Фронтенд Tauri приложения
+    App(tauri::AppHandle),
+    /// Без UI: события пишутся в лог на уровне debug
+    Headless,
+}
+
+impl EventSink {
+    /// Отправить событие (ошибки доставки игнорируются, как и раньше с `app_handle.emit`)
+    pub fn emit<S: Serialize + Clone>(&self, event: &str, payload: S) {
+        match self {
+            EventSink::App(app_handle) => {
+                let _ = app_handle.emit(event, payload);
+            }
+            EventSink::Headless => {
+                if log::log_enabled!(log::Level::Debug) {
+                    let json = serde_json::to_string(&payload).unwrap_or_default();
+                    log::debug!("[event] {}: {}", event, json);
+                }
+            }
+        }
+    }
+
+    /// AppHandle, если работаем внутри GUI
+    pub fn app_handle(&self) -> Option<&tauri::AppHandle> {
+        match self {
+            EventSink::App(app_handle) => Some(app_handle),
+            EventSink::Headless => None,
+        }
+    }
+}
+
+impl From<tauri::AppHandle> for EventSink {
+    fn from(app_handle: tauri::AppHandle) -> Self {
+        EventSink::App(app_handle)
+    }
+}
+
+impl From<&tauri::AppHandle> for EventSink {
+    fn from(app_handle: &tauri::AppHandle) -> Self {
+        EventSink::App(app_handle.clone())
+    }
+}
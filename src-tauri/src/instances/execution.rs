@@ -4,7 +4,7 @@ use std::collections::HashMap;
 use std::io::{BufRead, BufReader};
 use std::path::PathBuf;
 use std::process::{Child, Command, Stdio};
-use std::sync::{Arc, Mutex};
+use std::sync::{Arc, LazyLock, Mutex};
 use std::thread;
 use tauri::{Emitter, State};
 
@@ -17,9 +17,34 @@ use crate::server::console as server_console;
 #[cfg(windows)]
 const CREATE_NO_WINDOW: u32 = 0x08000000;
 
+/// Аргументы сервера только для следующего запуска (например, `--forceUpgrade`)
+static NEXT_LAUNCH_SERVER_ARGS: LazyLock<Mutex<HashMap<String, Vec<String>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Добавить аргументы к следующему запуску сервера. Пустой список отменяет ранее заданные.
+pub(crate) fn set_next_launch_server_args(instance_id: &str, args: Vec<String>) {
+    let mut pending = NEXT_LAUNCH_SERVER_ARGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner());
+    if args.is_empty() {
+        pending.remove(instance_id);
+    } else {
+        pending.insert(instance_id.to_string(), args);
+    }
+}
+
+fn take_next_launch_server_args(instance_id: &str) -> Vec<String> {
+    NEXT_LAUNCH_SERVER_ARGS
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .remove(instance_id)
+        .unwrap_or_default()
+}
+
 use tokio_util::sync::CancellationToken;
 
 use crate::db::get_db_conn;
+use crate::auth::{resolve_launch_auth, LaunchAuth};
 use crate::downloader::DownloadManager;
 use crate::error::{LauncherError, Result};
 use crate::gpu;
@@ -32,6 +57,7 @@ use crate::tray;
 use crate::types::{Instance, InstanceType, LoaderType};
 
 use super::lifecycle::{get_instance, ChildMap};
+use super::watchdog::{ExitDecision, ServerWatchdog};
 
 /// Quick patterns for real-time error detection in stdout
 fn is_error_line(line: &str) -> bool {
@@ -50,22 +76,8 @@ fn is_crash_indicator(line: &str) -> bool {
         || line.contains("The game has crashed")
 }
 
-#[tauri::command]
-pub async fn start_instance(
-    id: String,
-    app_handle: tauri::AppHandle,
-    state: State<'_, ChildMap>,
-) -> Result<()> {
-    {
-        let map = state.lock().unwrap_or_else(|e| e.into_inner());
-        if map.contains_key(&id) {
-            return Err(LauncherError::InstanceAlreadyRunning);
-        }
-    }
-
-    let instance = get_instance(id.clone()).await?;
-
-    // Проверяем статус экземпляра - нельзя запускать если он устанавливается или уже запускается
+/// Проверяет, что экземпляр можно запустить (не устанавливается / не запущен)
+fn ensure_launchable(instance: &Instance) -> Result<()> {
     match instance.status.as_str() {
         "installing" => {
             log::warn!(
@@ -98,19 +110,15 @@ pub async fn start_instance(
         }
         _ => {}
     }
+    Ok(())
+}
 
-    log::info!("Starting instance: {} ({})", instance.name, instance.id);
-    log::info!(
-        "Instance loader: {:?}, version: {}",
-        instance.loader,
-        instance.version
-    );
-    log::info!("Instance dir: {}", instance.dir);
-
-    let download_manager = DownloadManager::new(app_handle.clone())?;
-    // Создаём токен отмены для start операций (не используется активно при запуске, но нужен для API)
-    let cancel_token = CancellationToken::new();
-
+/// Java для экземпляра: пользовательский путь или автоматическая установка нужной версии
+async fn resolve_java_path(
+    instance: &Instance,
+    download_manager: &DownloadManager,
+    cancel_token: &CancellationToken,
+) -> Result<PathBuf> {
     log::info!("Ensuring Java availability...");
     let java_path = if let Some(custom_path) = &instance.java_path {
         log::info!("Using custom Java path: {}", custom_path);
@@ -119,16 +127,66 @@ pub async fn start_instance(
         log::info!("Auto-detecting Java for version: {}", instance.version);
         JavaManager::ensure_java(
             &instance.version,
-            &download_manager,
-            &cancel_token,
+            download_manager,
+            cancel_token,
             Some(&instance.id),
         )
         .await?
     };
     log::info!("Java path resolved: {:?}", java_path);
+    Ok(java_path)
+}
+
+/// Запуск без GUI (stuzhik-cli): Java + авторизация + процесс с piped stdin/stdout/stderr.
+/// Мониторинг, консоль и статус в БД — на стороне вызывающего.
+pub(crate) async fn spawn_instance_headless(
+    instance: &Instance,
+    download_manager: &DownloadManager,
+) -> Result<Child> {
+    ensure_launchable(instance)?;
+
+    let cancel_token = CancellationToken::new();
+    let java_path = resolve_java_path(instance, download_manager, &cancel_token).await?;
+    let auth = resolve_launch_auth(instance, download_manager).await?;
+
+    spawn_instance_process(instance, &java_path, &auth).await
+}
+
+#[tauri::command]
+pub async fn start_instance(
+    id: String,
+    app_handle: tauri::AppHandle,
+    state: State<'_, ChildMap>,
+) -> Result<()> {
+    {
+        let map = state.lock().unwrap_or_else(|e| e.into_inner());
+        if map.contains_key(&id) {
+            return Err(LauncherError::InstanceAlreadyRunning);
+        }
+    }
+
+    let instance = get_instance(id.clone()).await?;
+
+    ensure_launchable(&instance)?;
+
+    log::info!("Starting instance: {} ({})", instance.name, instance.id);
+    log::info!(
+        "Instance loader: {:?}, version: {}",
+        instance.loader,
+        instance.version
+    );
+    log::info!("Instance dir: {}", instance.dir);
+
+    let download_manager = DownloadManager::new(app_handle.clone())?;
+    // Создаём токен отмены для start операций (не используется активно при запуске, но нужен для API)
+    let cancel_token = CancellationToken::new();
+
+    let java_path = resolve_java_path(&instance, &download_manager, &cancel_token).await?;
+
+    let auth = resolve_launch_auth(&instance, &download_manager).await?;
 
     log::info!("Spawning instance process...");
-    let mut child = spawn_instance_process(&instance, &java_path).await?;
+    let mut child = spawn_instance_process(&instance, &java_path, &auth).await?;
     let child_pid = child.id();
 
     log::info!("Process started with PID: {}", child_pid);
@@ -171,7 +229,7 @@ pub async fn start_instance(
                 );
 
                 // Пересоздаем команду для диагностики
-                let diagnostic_output = spawn_instance_process(&instance, &java_path).await;
+                let diagnostic_output = spawn_instance_process(&instance, &java_path, &auth).await;
                 match diagnostic_output {
                     Ok(diag_child) => {
                         // wait_with_output() - это блокирующий вызов, запускаем в отдельном потоке
@@ -300,6 +358,16 @@ pub async fn start_instance(
         map.insert(id, child);
     }
 
+    // Зависания отслеживаем только у серверов
+    if !is_client {
+        ServerWatchdog::watch_hangs(
+            app_handle.clone(),
+            instance_id.clone(),
+            child_pid,
+            java_path.clone(),
+        );
+    }
+
     // Создаем файлы логов для отладки
     let log_dir = crate::paths::instance_logs_dir(&instance_id);
     let stdout_log_path = log_dir.join("latest-stdout.log");
@@ -521,7 +589,7 @@ pub async fn start_instance(
         }
 
         // Check auto-restart for servers
-        let should_auto_restart = if is_crash && !is_client_monitor {
+        let auto_restart_enabled = if is_crash && !is_client_monitor {
             // Check if auto_restart is enabled in DB
             if let Ok(conn) = get_db_conn() {
                 conn.query_row(
@@ -538,6 +606,14 @@ pub async fn start_instance(
             false
         };
 
+        // Watchdog: backoff между рестартами, остановка при crash loop
+        let exit_decision = if is_client_monitor {
+            ExitDecision::Stay
+        } else {
+            ServerWatchdog::on_exit(&instance_id, is_crash, auto_restart_enabled)
+        };
+        let should_auto_restart = matches!(exit_decision, ExitDecision::Restart { .. });
+
         if let Ok(conn) = get_db_conn() {
             // If auto-restart is enabled, set status to "restarting" instead of "crashed"
             let status = if should_auto_restart {
@@ -573,10 +649,12 @@ pub async fn start_instance(
         );
 
         // Auto-restart server if enabled
-        if should_auto_restart {
+        if let ExitDecision::Restart { delay, attempt } = exit_decision {
             log::info!(
-                "Auto-restart enabled for server {}, restarting in 5 seconds...",
-                instance_id
+                "Auto-restart enabled for server {}, restarting in {} seconds (attempt {})...",
+                instance_id,
+                delay.as_secs(),
+                attempt
             );
 
             // Emit event to notify UI
@@ -584,23 +662,49 @@ pub async fn start_instance(
                 "server-auto-restart",
                 serde_json::json!({
                     "instance_id": instance_id,
-                    "delay_seconds": 5
+                    "delay_seconds": delay.as_secs(),
+                    "attempt": attempt
                 }),
             );
 
-            // Wait before restarting to prevent rapid restart loops
-            std::thread::sleep(std::time::Duration::from_secs(5));
-
-            // Trigger restart via event - the frontend will handle the actual restart
-            // This is safer than trying to restart directly from the monitoring thread
-            let _ = app_handle_monitor.emit(
-                "server-restart-now",
-                serde_json::json!({
-                    "instance_id": instance_id
-                }),
-            );
+            // Exponential backoff between restarts
+            std::thread::sleep(delay);
+
+            // Пользователь мог остановить или запустить сервер вручную, пока мы ждали
+            let still_restarting = get_db_conn()
+                .and_then(|conn| {
+                    conn.query_row(
+                        "SELECT status FROM instances WHERE id = ?1",
+                        params![&instance_id],
+                        |row| row.get::<_, String>(0),
+                    )
+                })
+                .map(|status| status == "restarting")
+                .unwrap_or(false);
+
+            if still_restarting {
+                // Trigger restart via event - the frontend will handle the actual restart
+                // This is safer than trying to restart directly from the monitoring thread
+                let _ = app_handle_monitor.emit(
+                    "server-restart-now",
+                    serde_json::json!({
+                        "instance_id": instance_id
+                    }),
+                );
 
-            log::info!("Auto-restart event emitted for server {}", instance_id);
+                log::info!("Auto-restart event emitted for server {}", instance_id);
+            } else {
+                log::info!(
+                    "Server {} is no longer restarting, auto-restart cancelled",
+                    instance_id
+                );
+            }
+        } else if let ExitDecision::CrashLoop { crashes } = exit_decision {
+            tauri::async_runtime::spawn(ServerWatchdog::diagnose_crash_loop(
+                app_handle_monitor.clone(),
+                instance_id.clone(),
+                crashes,
+            ));
         }
 
         // Emit live-crash-event "stopped" for UI indicator (only for clients)
@@ -696,7 +800,12 @@ fn preflight_checks(instance: &Instance, java_path: &PathBuf) -> Result<()> {
 
 /// Check EULA acceptance for server instances
 /// Returns error if EULA is not accepted, prompting user to accept in settings
-fn check_server_eula(instance_path: &PathBuf) -> Result<()> {
+fn check_server_eula(instance_path: &PathBuf, loader: &LoaderType) -> Result<()> {
+    // Velocity — прокси, Minecraft сервер не запускает и EULA не требует
+    if matches!(loader, LoaderType::Velocity) {
+        return Ok(());
+    }
+
     let eula_path = instance_path.join("eula.txt");
 
     if eula_path.exists() {
@@ -729,7 +838,7 @@ fn check_server_run_script(
     java_path: &PathBuf,
 ) -> Result<Option<Command>> {
     // Check EULA - must be explicitly accepted before server can start
-    check_server_eula(instance_path)?;
+    check_server_eula(instance_path, &instance.loader)?;
 
     // Write user_jvm_args.txt with memory settings and encoding
     let user_jvm_args = instance_path.join("user_jvm_args.txt");
@@ -794,6 +903,7 @@ fn check_server_run_script(
 
             // Add nogui for headless server
             cmd.arg("--nogui");
+            cmd.args(take_next_launch_server_args(&instance.id));
 
             log::info!("Launching with argfiles, Java: {:?}", java_path);
             return Ok(Some(cmd));
@@ -823,7 +933,11 @@ fn spawn_with_script(mut cmd: Command, instance_path: &PathBuf) -> Result<Child>
     cmd.spawn().map_err(LauncherError::from)
 }
 
-async fn spawn_instance_process(instance: &Instance, java_path: &PathBuf) -> Result<Child> {
+async fn spawn_instance_process(
+    instance: &Instance,
+    java_path: &PathBuf,
+    auth: &LaunchAuth,
+) -> Result<Child> {
     // Выполняем pre-flight проверки
     preflight_checks(instance, java_path)?;
 
@@ -835,6 +949,11 @@ async fn spawn_instance_process(instance: &Instance, java_path: &PathBuf) -> Res
 
     let instance_path = PathBuf::from(&instance.dir);
 
+    // Resource pack сервера: пересобрать и обновить server.properties до старта
+    if matches!(instance.instance_type, InstanceType::Server) {
+        crate::server::resource_pack::ResourcePackManager::sync_before_start(&instance.id).await;
+    }
+
     // Note: Client mods scanning is done separately via UI, not during launch
     // This avoids blocking server startup with API calls
 
@@ -915,13 +1034,19 @@ async fn spawn_instance_process(instance: &Instance, java_path: &PathBuf) -> Res
         }
     }
 
+    // authlib-injector должен стоять до -cp, чтобы подменить authlib при загрузке
+    if let Some(ref javaagent) = auth.javaagent {
+        log::info!("Auth: authlib-injector enabled");
+        cmd.arg(javaagent);
+    }
+
     // Classpath и запуск
     match instance.instance_type {
         InstanceType::Server => {
             spawn_server_jar(&mut cmd, instance, &instance_path)?;
         }
         InstanceType::Client => {
-            spawn_client_process(&mut cmd, instance, &instance_path).await?;
+            spawn_client_process(&mut cmd, instance, &instance_path, auth).await?;
         }
     }
 
@@ -1005,7 +1130,7 @@ async fn spawn_instance_process(instance: &Instance, java_path: &PathBuf) -> Res
 fn spawn_server_jar(cmd: &mut Command, instance: &Instance, instance_path: &PathBuf) -> Result<()> {
     log::info!("spawn_server_jar: Checking EULA...");
     // Check EULA - must be explicitly accepted before server can start
-    check_server_eula(instance_path)?;
+    check_server_eula(instance_path, &instance.loader)?;
     log::info!("spawn_server_jar: EULA check passed");
 
     log::info!(
@@ -1028,6 +1153,10 @@ fn spawn_server_jar(cmd: &mut Command, instance: &Instance, instance_path: &Path
                         || name.contains("neoforge")
                         || name.contains("fabric")
                         || name.contains("quilt")
+                        || name.contains("paper")
+                        || name.contains("purpur")
+                        || name.contains("folia")
+                        || name.contains("velocity")
                         || name.contains("minecraft"))
             })
             .collect();
@@ -1060,6 +1189,13 @@ fn spawn_server_jar(cmd: &mut Command, instance: &Instance, instance_path: &Path
                 let n = e.file_name().to_string_lossy().to_lowercase();
                 n == "server.jar" || n.contains("minecraft")
             }),
+            LoaderType::Paper | LoaderType::Purpur | LoaderType::Folia | LoaderType::Velocity => {
+                let prefix = format!("{}-", instance.loader.as_str());
+                entries.iter().find(|e| {
+                    let n = e.file_name().to_string_lossy().to_lowercase();
+                    n.starts_with(&prefix)
+                })
+            }
         };
 
         // Use preferred or first found
@@ -1099,6 +1235,7 @@ fn spawn_server_jar(cmd: &mut Command, instance: &Instance, instance_path: &Path
 
     log::info!("spawn_server_jar: Launching with JAR: {:?}", jar_path);
     cmd.arg("-jar").arg(jar_path).arg("nogui");
+    cmd.args(take_next_launch_server_args(&instance.id));
     Ok(())
 }
 
@@ -1106,6 +1243,7 @@ async fn spawn_client_process(
     cmd: &mut Command,
     instance: &Instance,
     instance_path: &PathBuf,
+    auth: &LaunchAuth,
 ) -> Result<()> {
     let (main_class, classpath, game_args) = match instance.loader {
         LoaderType::Vanilla => {
@@ -1113,7 +1251,7 @@ async fn spawn_client_process(
             let version_json =
                 crate::minecraft::VersionJson::load_with_inheritance(&instance.version).await?;
             let classpath = MinecraftInstaller::generate_classpath_from_json(&version_json)?;
-            let game_args = get_game_arguments_for_client(instance, instance_path)?;
+            let game_args = get_game_arguments_for_client(instance, instance_path, auth)?;
 
             log::info!("Vanilla main class: {}", version_json.main_class);
             log::info!(
@@ -1138,7 +1276,7 @@ async fn spawn_client_process(
             let profile: crate::loaders::FabricProfile = serde_json::from_str(&content)?;
 
             let classpath = generate_loader_classpath(&instance.version, &profile.libraries)?;
-            let game_args = get_game_arguments_for_client(instance, instance_path)?;
+            let game_args = get_game_arguments_for_client(instance, instance_path, auth)?;
 
             log::info!("Fabric main class: {}", profile.main_class);
 
@@ -1157,7 +1295,7 @@ async fn spawn_client_process(
             let profile: crate::loaders::FabricProfile = serde_json::from_str(&content)?;
 
             let classpath = generate_loader_classpath(&instance.version, &profile.libraries)?;
-            let game_args = get_game_arguments_for_client(instance, instance_path)?;
+            let game_args = get_game_arguments_for_client(instance, instance_path, auth)?;
 
             log::info!("Quilt main class: {}", profile.main_class);
 
@@ -1214,21 +1352,9 @@ async fn spawn_client_process(
 
             // ИСПРАВЛЕНИЕ: Используем profile.id (версия NeoForge) вместо instance.version (версия Minecraft)
             // для генерации правильных игровых аргументов
-            let username = if let Some(ref u) = instance.username {
-                u.clone()
-            } else {
-                SettingsManager::get_default_username()?.unwrap_or_else(|| "Player".to_string())
-            };
-
-            let uuid_string = format!("OfflinePlayer:{}", username);
-            let uuid = uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_DNS, uuid_string.as_bytes());
-            let access_token = uuid.to_string().replace("-", "");
-
             let game_args = match MinecraftInstaller::get_game_arguments(
                 &profile.id, // Используем NeoForge версию, не Minecraft версию
-                &username,
-                &uuid.to_string(),
-                &access_token,
+                auth,
                 instance_path,
                 &crate::paths::assets_dir(),
             ) {
@@ -1502,21 +1628,9 @@ async fn spawn_client_process(
                 )?;
 
                 // Генерируем game arguments используя Forge version ID
-                let username = if let Some(ref u) = instance.username {
-                    u.clone()
-                } else {
-                    SettingsManager::get_default_username()?.unwrap_or_else(|| "Player".to_string())
-                };
-
-                let uuid_string = format!("OfflinePlayer:{}", username);
-                let uuid = uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_DNS, uuid_string.as_bytes());
-                let access_token = uuid.to_string().replace("-", "");
-
                 let game_args = MinecraftInstaller::get_game_arguments(
                     &forge_id,
-                    &username,
-                    &uuid.to_string(),
-                    &access_token,
+                    auth,
                     instance_path,
                     &crate::paths::assets_dir(),
                 )?;
@@ -1737,11 +1851,17 @@ async fn spawn_client_process(
                 let version_json =
                     crate::minecraft::VersionJson::load_with_inheritance(&instance.version).await?;
                 let classpath = MinecraftInstaller::generate_classpath(&instance.version)?;
-                let game_args = get_game_arguments_for_client(instance, instance_path)?;
+                let game_args = get_game_arguments_for_client(instance, instance_path, auth)?;
 
                 (version_json.main_class, classpath, game_args)
             }
         }
+        LoaderType::Paper | LoaderType::Purpur | LoaderType::Folia | LoaderType::Velocity => {
+            return Err(LauncherError::InvalidConfig(format!(
+                "{} — серверная платформа и не запускается как клиент",
+                instance.loader.as_str()
+            )));
+        }
     };
 
     // Логируем полную команду запуска
@@ -1775,22 +1895,11 @@ async fn spawn_client_process(
 fn get_game_arguments_for_client(
     instance: &Instance,
     instance_path: &PathBuf,
+    auth: &LaunchAuth,
 ) -> Result<Vec<String>> {
-    let username = if let Some(ref u) = instance.username {
-        u.clone()
-    } else {
-        SettingsManager::get_default_username()?.unwrap_or_else(|| "Player".to_string())
-    };
-
-    let uuid_string = format!("OfflinePlayer:{}", username);
-    let uuid = uuid::Uuid::new_v5(&uuid::Uuid::NAMESPACE_DNS, uuid_string.as_bytes());
-    let access_token = uuid.to_string().replace("-", "");
-
     let game_args = MinecraftInstaller::get_game_arguments(
         &instance.version,
-        &username,
-        &uuid.to_string(),
-        &access_token,
+        auth,
         instance_path,
         &crate::paths::assets_dir(),
     )?;
@@ -1932,6 +2041,36 @@ fn kill_process_by_pid(pid: u32) -> bool {
     }
 }
 
+/// Graceful termination by PID (SIGTERM on Unix) after verifying it's a Java process.
+/// Minecraft server saves worlds in its shutdown hook, so this is safe for servers.
+pub(crate) fn terminate_java_process(pid: u32) -> bool {
+    if !is_process_running(pid) || !is_java_process(pid) {
+        log::warn!("PID {} is not a running Java process, not terminating", pid);
+        return false;
+    }
+
+    #[cfg(windows)]
+    {
+        use std::process::Command;
+        // Without /F taskkill asks the process to close
+        Command::new("taskkill")
+            .args(["/PID", &pid.to_string()])
+            .creation_flags(CREATE_NO_WINDOW)
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+    #[cfg(not(windows))]
+    {
+        use std::process::Command;
+        Command::new("kill")
+            .args(["-TERM", &pid.to_string()])
+            .output()
+            .map(|o| o.status.success())
+            .unwrap_or(false)
+    }
+}
+
 /// Force kill a server (SIGKILL) - for when graceful stop doesn't work
 #[tauri::command]
 pub fn force_kill_server(
@@ -2195,7 +2334,7 @@ pub fn cleanup_orphaned_processes() {
 }
 
 /// Check if a process with the given PID is running
-fn is_process_running(pid: u32) -> bool {
+pub(crate) fn is_process_running(pid: u32) -> bool {
     #[cfg(windows)]
     {
         use std::process::Command;
@@ -2258,7 +2397,7 @@ fn is_java_process(pid: u32) -> bool {
 /// Returns true if the process was killed, false otherwise.
 /// This is the safe way to kill orphaned processes — it prevents
 /// accidentally killing unrelated processes after OS PID reuse.
-fn verify_and_kill_orphaned_process(pid: u32) -> bool {
+pub(crate) fn verify_and_kill_orphaned_process(pid: u32) -> bool {
     if !is_process_running(pid) {
         log::info!("Orphaned PID {} is no longer running, nothing to kill", pid);
         return false;
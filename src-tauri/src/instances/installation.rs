@@ -7,6 +7,7 @@ use crate::cancellation;
 use crate::db::get_db_conn;
 use crate::downloader::DownloadManager;
 use crate::error::{LauncherError, Result};
+use crate::events::EventSink;
 use crate::java::JavaManager;
 use crate::loaders::LoaderManager;
 use crate::minecraft::MinecraftInstaller;
@@ -130,12 +131,12 @@ async fn install_instance_async(instance: &Instance, app_handle: tauri::AppHandl
 }
 
 /// Версия install_instance_async с поддержкой отмены и параллельной загрузкой
-pub(super) async fn install_instance_async_cancellable(
+pub(crate) async fn install_instance_async_cancellable(
     instance: &Instance,
-    app_handle: tauri::AppHandle,
+    events: EventSink,
     cancel_token: &tokio_util::sync::CancellationToken,
 ) -> Result<()> {
-    let download_manager = DownloadManager::new(app_handle.clone())?;
+    let download_manager = DownloadManager::with_events(events.clone())?;
 
     // Проверка отмены в начале
     if cancel_token.is_cancelled() {
@@ -165,7 +166,7 @@ pub(super) async fn install_instance_async_cancellable(
         String::new()
     };
 
-    let _ = app_handle.emit(
+    events.emit(
         "instance-install-progress",
         serde_json::json!({
             "id": instance.id,
@@ -189,8 +190,8 @@ pub(super) async fn install_instance_async_cancellable(
     let custom_java_path = instance.java_path.clone();
     let cancel_java = cancel_token.clone();
     let cancel_mc = cancel_token.clone();
-    let app_java = app_handle.clone();
-    let app_mc = app_handle.clone();
+    let events_java = events.clone();
+    let events_mc = events.clone();
     let instance_id_java = instance.id.clone();
     let instance_id_mc = instance.id.clone();
 
@@ -215,7 +216,7 @@ pub(super) async fn install_instance_async_cancellable(
         log::info!("Java installed at: {:?}", path);
 
         // Отправляем событие что Java готова
-        let _ = app_java.emit(
+        events_java.emit(
             "instance-install-progress",
             serde_json::json!({
                 "id": instance_id_java,
@@ -234,7 +235,7 @@ pub(super) async fn install_instance_async_cancellable(
         }
 
         // Обновляем прогресс
-        let _ = app_mc.emit(
+        events_mc.emit(
             "instance-install-progress",
             serde_json::json!({
                 "id": instance_id_mc,
@@ -272,7 +273,7 @@ pub(super) async fn install_instance_async_cancellable(
             );
         }
 
-        let _ = app_handle.emit(
+        events.emit(
             "instance-install-progress",
             serde_json::json!({
                 "id": instance.id,
@@ -301,7 +302,7 @@ pub(super) async fn install_instance_async_cancellable(
     // Notify frontend that instance installation (Java/MC/Loader) is done
     // This clears the "Загрузка библиотек Forge (29/29)..." message
     // so that modpack mod download progress becomes visible
-    let _ = app_handle.emit(
+    events.emit(
         "instance-install-progress",
         serde_json::json!({
             "id": instance.id,
@@ -358,7 +359,7 @@ pub async fn reinstall_instance(
     tauri::async_runtime::spawn(async move {
         let result = install_instance_async_cancellable(
             &instance_clone,
-            app_handle_clone.clone(),
+            app_handle_clone.clone().into(),
             &cancel_token,
         )
         .await;
@@ -642,6 +643,13 @@ async fn repair_instance_async(
                 let mc_version = instance.version.clone();
                 check_forge_version_exists(&versions_dir, &mc_version).await
             }
+            LoaderType::Paper | LoaderType::Purpur | LoaderType::Folia | LoaderType::Velocity => {
+                crate::server::installer::find_platform_jar(
+                    &instance_path,
+                    crate::server::ServerLoader::from(&instance.loader),
+                )
+                .is_some()
+            }
             LoaderType::Vanilla => true,
         };
 
@@ -778,7 +786,7 @@ pub async fn retry_instance_installation(
 
         let result = install_instance_async_cancellable(
             &instance_clone,
-            app_handle_clone.clone(),
+            app_handle_clone.clone().into(),
             &cancel_token,
         )
         .await;
@@ -8,6 +8,7 @@ use tauri::{Emitter, State};
 use crate::cancellation;
 use crate::db::get_db_conn;
 use crate::error::{LauncherError, Result};
+use crate::events::EventSink;
 use crate::java::JavaManager;
 use crate::modpacks;
 use crate::paths::{create_instance_structure, instance_dir};
@@ -148,6 +149,15 @@ pub async fn get_instance(id: String) -> Result<Instance> {
 pub async fn create_instance(
     req: CreateInstanceRequest,
     app_handle: tauri::AppHandle,
+) -> Result<Instance> {
+    create_instance_with_events(req, app_handle.into()).await
+}
+
+/// Создание экземпляра с произвольным получателем событий (GUI или CLI).
+/// Установка идёт в фоне — статус экземпляра в БД меняется с `installing` по завершении.
+pub(crate) async fn create_instance_with_events(
+    req: CreateInstanceRequest,
+    events: EventSink,
 ) -> Result<Instance> {
     let id = gen_short_id(12);
 
@@ -251,7 +261,7 @@ pub async fn create_instance(
     let operation_id = format!("instance-install-{}", id);
     let cancel_token = cancellation::create_token(&operation_id);
 
-    let _ = app_handle.emit(
+    events.emit(
         "instance-creating",
         serde_json::json!({
             "id": id,
@@ -261,7 +271,7 @@ pub async fn create_instance(
     );
 
     // Отправляем ID операции клиенту для возможности отмены
-    let _ = app_handle.emit(
+    events.emit(
         "instance-operation-started",
         serde_json::json!({
             "operation_id": operation_id,
@@ -269,7 +279,7 @@ pub async fn create_instance(
     );
 
     let instance_clone = instance.clone();
-    let app_handle_clone = app_handle.clone();
+    let events_clone = events.clone();
     let operation_id_clone = operation_id.clone();
 
     tauri::async_runtime::spawn(async move {
@@ -280,7 +290,7 @@ pub async fn create_instance(
 
         let result = install_instance_async_cancellable(
             &instance_clone,
-            app_handle_clone.clone(),
+            events_clone.clone(),
             &cancel_token,
         )
         .await;
@@ -341,7 +351,7 @@ pub async fn create_instance(
                     }
                 }
 
-                let _ = app_handle_clone.emit(
+                events_clone.emit(
                     "instance-created",
                     serde_json::json!({
                         "id": instance_clone.id,
@@ -370,7 +380,7 @@ pub async fn create_instance(
                 // instance-removed: frontend удалит экземпляр из списка.
                 // Может быть дубликатом (handle_cancellation уже отправил),
                 // но повторное удаление безопасно — filter просто не найдёт элемент.
-                let _ = app_handle_clone.emit(
+                events_clone.emit(
                     "instance-removed",
                     serde_json::json!({
                         "id": instance_clone.id
@@ -388,7 +398,7 @@ pub async fn create_instance(
                     );
                 }
 
-                let _ = app_handle_clone.emit(
+                events_clone.emit(
                     "instance-creation-failed",
                     serde_json::json!({
                         "id": instance_clone.id,
@@ -938,7 +948,7 @@ pub async fn reset_instance_version(
 
         let result = install_instance_async_cancellable(
             &instance_for_install,
-            app_handle_clone.clone(),
+            app_handle_clone.clone().into(),
             &cancel_token,
         )
         .await;
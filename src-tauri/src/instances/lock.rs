@@ -0,0 +1,1014 @@
+//! instance.lock — точная фиксация содержимого экземпляра
+//!
+//! Lock-файл лежит в корне экземпляра и перезаписывается после каждого изменения
+//! модов, ресурспаков и шейдеров. Для каждого файла хранится источник (версия
+//! Modrinth, файл CurseForge или прямая ссылка), sha1/sha512, сторона и состояние
+//! enabled — этого достаточно, чтобы закоммитить его в git и восстановить
+//! экземпляр байт-в-байт на другой машине.
+
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::path::{Component, Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::Duration;
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::api::modrinth::ModrinthClient;
+use crate::db::get_db_conn;
+use crate::downloader::DownloadManager;
+use crate::error::{LauncherError, Result};
+use crate::mods::ModManager;
+use crate::paths::instance_dir;
+use crate::resources::{ResourceManager, ResourceType};
+use crate::server::client_mods::{cached_side_info, compute_file_hashes};
+use crate::server::console::get_console;
+use crate::stzhk::{ModSide, VerificationFailure, VerificationResult};
+use crate::utils::atomic_write;
+
+pub const LOCK_FILE_NAME: &str = "instance.lock";
+const LOCK_VERSION: u32 = 1;
+const DISABLED_SUFFIX: &str = ".disabled";
+/// Папка внутри `.stuzhik` для файлов, убранных при восстановлении
+const BACKUP_DIR: &str = "lock_backups";
+
+/// Отслеживаемые папки и расширения файлов в них
+const TRACKED_DIRS: [(&str, &str); 3] = [
+    ("mods", ".jar"),
+    ("resourcepacks", ".zip"),
+    ("shaderpacks", ".zip"),
+];
+
+/// Пачка изменений (batch install, массовый toggle) даёт одну запись
+const WRITE_DEBOUNCE: Duration = Duration::from_millis(750);
+
+static PENDING_WRITES: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Экземпляры, которые сейчас восстанавливаются из lock
+static RESTORING: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Содержимое instance.lock
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct InstanceLock {
+    pub lock_version: u32,
+    pub minecraft_version: String,
+    pub loader: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub loader_version: Option<String>,
+    #[serde(default, rename = "file")]
+    pub files: Vec<LockedFile>,
+}
+
+/// Зафиксированный файл экземпляра
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct LockedFile {
+    /// Путь относительно папки экземпляра, без суффикса .disabled
+    pub path: String,
+    pub name: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+    pub enabled: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub side: Option<ModSide>,
+    pub size: u64,
+    pub sha1: String,
+    pub sha512: String,
+    pub source: LockSource,
+}
+
+impl LockedFile {
+    /// Путь на диске с учётом состояния enabled
+    fn disk_path(&self, root: &Path, enabled: bool) -> PathBuf {
+        if enabled {
+            root.join(&self.path)
+        } else {
+            root.join(format!("{}{}", self.path, DISABLED_SUFFIX))
+        }
+    }
+}
+
+/// Откуда файл можно скачать заново
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase")]
+pub enum LockSource {
+    Modrinth {
+        project_id: String,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        version_id: Option<String>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    CurseForge {
+        project_id: u64,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        file_id: Option<u64>,
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        url: Option<String>,
+    },
+    Url {
+        url: String,
+    },
+    /// Файл добавлен вручную — восстановить можно только из копии
+    Local,
+}
+
+impl LockSource {
+    fn from_db(source: &str, source_id: Option<&str>, url: Option<&str>) -> Self {
+        let url_owned = url.map(String::from);
+        match (source, source_id) {
+            ("modrinth", Some(project_id)) => LockSource::Modrinth {
+                project_id: project_id.to_string(),
+                version_id: url.and_then(modrinth_version_id),
+                url: url_owned,
+            },
+            ("curseforge", Some(project_id)) => match project_id.parse() {
+                Ok(project_id) => LockSource::CurseForge {
+                    project_id,
+                    file_id: url.and_then(curseforge_file_id),
+                    url: url_owned,
+                },
+                Err(_) => Self::from_url(url_owned),
+            },
+            _ => Self::from_url(url_owned),
+        }
+    }
+
+    fn from_url(url: Option<String>) -> Self {
+        match url {
+            Some(url) => LockSource::Url { url },
+            None => LockSource::Local,
+        }
+    }
+
+    fn source_name(&self) -> &'static str {
+        match self {
+            LockSource::Modrinth { .. } => "modrinth",
+            LockSource::CurseForge { .. } => "curseforge",
+            LockSource::Url { .. } | LockSource::Local => "local",
+        }
+    }
+
+    fn source_id(&self) -> Option<String> {
+        match self {
+            LockSource::Modrinth { project_id, .. } => Some(project_id.clone()),
+            LockSource::CurseForge { project_id, .. } => Some(project_id.to_string()),
+            LockSource::Url { .. } | LockSource::Local => None,
+        }
+    }
+
+    fn url(&self) -> Option<&str> {
+        match self {
+            LockSource::Modrinth { url, .. } | LockSource::CurseForge { url, .. } => url.as_deref(),
+            LockSource::Url { url } => Some(url),
+            LockSource::Local => None,
+        }
+    }
+}
+
+/// `https://cdn.modrinth.com/data/{project}/versions/{version}/{file}`
+fn modrinth_version_id(url: &str) -> Option<String> {
+    let mut parts = url.split('/');
+    parts.find(|p| *p == "versions")?;
+    parts.next().filter(|id| !id.is_empty()).map(String::from)
+}
+
+/// `https://edge.forgecdn.net/files/{id / 1000}/{id % 1000}/{file}`
+fn curseforge_file_id(url: &str) -> Option<u64> {
+    if !url.contains("forgecdn.net") {
+        return None;
+    }
+    let mut parts = url.split('/');
+    parts.find(|p| *p == "files")?;
+    let high: u64 = parts.next()?.parse().ok()?;
+    let low: u64 = parts.next()?.parse().ok()?;
+    Some(high * 1000 + low)
+}
+
+/// Расхождение папки экземпляра с lock-файлом
+#[derive(Debug, Clone, Serialize)]
+pub struct LockDrift {
+    pub has_lock: bool,
+    pub in_sync: bool,
+    /// Файлы из lock, которых нет на диске
+    pub missing: Vec<String>,
+    /// Файлы с другим содержимым
+    pub modified: Vec<VerificationFailure>,
+    /// Файлы, у которых состояние enabled отличается от lock
+    pub state_changed: Vec<String>,
+    /// Файлы на диске, которых нет в lock
+    pub untracked: Vec<String>,
+}
+
+/// Результат восстановления из lock
+#[derive(Debug, Clone, Serialize)]
+pub struct RestoreReport {
+    pub downloaded: Vec<String>,
+    pub toggled: Vec<String>,
+    /// Лишние и заменённые файлы, перенесённые в `backup_dir`
+    pub backed_up: Vec<String>,
+    pub backup_dir: Option<String>,
+    /// Файлы без источника для скачивания или с ошибкой загрузки
+    pub unrestorable: Vec<String>,
+    pub verification: VerificationResult,
+}
+
+/// Данные из БД, которые попадают в lock
+struct DbEntry {
+    name: String,
+    version: Option<String>,
+    source: LockSource,
+    sha1: Option<String>,
+}
+
+pub struct LockManager;
+
+impl LockManager {
+    pub fn lock_path(instance_id: &str) -> PathBuf {
+        instance_dir(instance_id).join(LOCK_FILE_NAME)
+    }
+
+    /// Запланировать перезапись lock после изменения (с debounce)
+    pub fn schedule_write(instance_id: &str) {
+        if Self::is_restoring(instance_id) {
+            return;
+        }
+        {
+            let mut pending = PENDING_WRITES.lock().unwrap_or_else(|e| e.into_inner());
+            if !pending.insert(instance_id.to_string()) {
+                return;
+            }
+        }
+
+        let instance_id = instance_id.to_string();
+        tauri::async_runtime::spawn(async move {
+            tokio::time::sleep(WRITE_DEBOUNCE).await;
+            PENDING_WRITES
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(&instance_id);
+            if Self::is_restoring(&instance_id) {
+                return;
+            }
+
+            if let Err(e) = Self::write(&instance_id).await {
+                log::warn!(
+                    "Failed to write {} for {}: {}",
+                    LOCK_FILE_NAME,
+                    instance_id,
+                    e
+                );
+            }
+        });
+    }
+
+    fn is_restoring(instance_id: &str) -> bool {
+        RESTORING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(instance_id)
+    }
+
+    /// Прочитать lock экземпляра (None если его ещё нет)
+    pub async fn read(instance_id: &str) -> Result<Option<InstanceLock>> {
+        let path = Self::lock_path(instance_id);
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            return Ok(None);
+        }
+
+        let content = tokio::fs::read_to_string(&path).await?;
+        let lock: InstanceLock = toml::from_str(&content).map_err(|e| {
+            LauncherError::InvalidConfig(format!("Invalid {}: {}", LOCK_FILE_NAME, e))
+        })?;
+
+        if lock.lock_version > LOCK_VERSION {
+            return Err(LauncherError::InvalidConfig(format!(
+                "{} version {} is newer than supported {}",
+                LOCK_FILE_NAME, lock.lock_version, LOCK_VERSION
+            )));
+        }
+
+        Ok(Some(lock))
+    }
+
+    /// Собрать lock по текущему состоянию папки и записать его
+    pub async fn write(instance_id: &str) -> Result<InstanceLock> {
+        let previous = Self::read(instance_id).await.ok().flatten();
+        let lock = Self::build(instance_id, previous.as_ref()).await?;
+
+        if previous.as_ref() != Some(&lock) {
+            let content = toml::to_string_pretty(&lock).map_err(|e| {
+                LauncherError::InvalidConfig(format!("Failed to serialize lock: {}", e))
+            })?;
+            atomic_write(&Self::lock_path(instance_id), content.as_bytes())?;
+            log::debug!(
+                "Wrote {} for {} ({} files)",
+                LOCK_FILE_NAME,
+                instance_id,
+                lock.files.len()
+            );
+        }
+
+        Ok(lock)
+    }
+
+    async fn build(instance_id: &str, previous: Option<&InstanceLock>) -> Result<InstanceLock> {
+        let (minecraft_version, loader, loader_version, db_entries) = {
+            let conn = get_db_conn()?;
+            let (minecraft_version, loader, loader_version): (String, String, Option<String>) =
+                conn.query_row(
+                    "SELECT version, loader, loader_version FROM instances WHERE id = ?1",
+                    [instance_id],
+                    |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+                )
+                .optional()?
+                .ok_or_else(|| LauncherError::InstanceNotFound(instance_id.to_string()))?;
+
+            let mut entries = Self::load_mod_entries(&conn, instance_id)?;
+            drop(conn);
+
+            for resource_type in [ResourceType::Resourcepack, ResourceType::Shader] {
+                for res in ResourceManager::list_resources(resource_type, Some(instance_id), false)?
+                {
+                    let path = format!("{}/{}", resource_type.folder_name(), res.file_name);
+                    let version = Some(res.version).filter(|v| v != "local" && v != "unknown");
+                    entries.insert(
+                        path,
+                        DbEntry {
+                            name: res.name,
+                            version,
+                            source: LockSource::from_db(
+                                &res.source,
+                                res.source_id.as_deref(),
+                                res.download_url.as_deref(),
+                            ),
+                            sha1: res.file_hash,
+                        },
+                    );
+                }
+            }
+
+            (minecraft_version, loader, loader_version, entries)
+        };
+
+        let previous: HashMap<&str, &LockedFile> = previous
+            .map(|lock| lock.files.iter().map(|f| (f.path.as_str(), f)).collect())
+            .unwrap_or_default();
+
+        let root = instance_dir(instance_id);
+        let mut files = Vec::new();
+
+        for (path, enabled) in Self::scan_disk(&root).await? {
+            let disk_path = if enabled {
+                root.join(&path)
+            } else {
+                root.join(format!("{}{}", path, DISABLED_SUFFIX))
+            };
+            let size = tokio::fs::metadata(&disk_path).await?.len();
+            let db = db_entries.get(&path);
+
+            // Хеши переиспользуем, только если БД подтверждает, что файл тот же
+            let reused = previous.get(path.as_str()).filter(|prev| {
+                prev.size == size && db.and_then(|d| d.sha1.as_deref()) == Some(prev.sha1.as_str())
+            });
+            let (sha1, sha512) = match reused {
+                Some(prev) => (prev.sha1.clone(), prev.sha512.clone()),
+                None => compute_file_hashes(&disk_path)
+                    .await
+                    .map_err(|e| LauncherError::InvalidConfig(e.to_string()))?,
+            };
+
+            let side = if path.starts_with("mods/") {
+                cached_side_info(&sha1).await.and_then(|info| {
+                    if info.is_client_only() {
+                        Some(ModSide::Client)
+                    } else if info.is_server_only() {
+                        Some(ModSide::Server)
+                    } else if info.client_side == "unknown" && info.server_side == "unknown" {
+                        None
+                    } else {
+                        Some(ModSide::Both)
+                    }
+                })
+            } else {
+                Some(ModSide::Client)
+            };
+
+            let (name, version, source) = match db {
+                Some(entry) => (
+                    entry.name.clone(),
+                    entry.version.clone(),
+                    entry.source.clone(),
+                ),
+                None => (Self::file_stem(&path), None, LockSource::Local),
+            };
+
+            files.push(LockedFile {
+                path,
+                name,
+                version,
+                enabled,
+                side,
+                size,
+                sha1,
+                sha512,
+                source,
+            });
+        }
+
+        Ok(InstanceLock {
+            lock_version: LOCK_VERSION,
+            minecraft_version,
+            loader,
+            loader_version,
+            files,
+        })
+    }
+
+    fn load_mod_entries(
+        conn: &rusqlite::Connection,
+        instance_id: &str,
+    ) -> Result<BTreeMap<String, DbEntry>> {
+        let mut stmt = conn.prepare(
+            "SELECT name, version, source, source_id, download_url, file_name, file_hash
+             FROM mods WHERE instance_id = ?1",
+        )?;
+        let rows = stmt.query_map([instance_id], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?,
+                row.get::<_, String>(2)?,
+                row.get::<_, Option<String>>(3)?,
+                row.get::<_, Option<String>>(4)?,
+                row.get::<_, String>(5)?,
+                row.get::<_, Option<String>>(6)?,
+            ))
+        })?;
+
+        let mut entries = BTreeMap::new();
+        for (name, version, source, source_id, url, file_name, sha1) in rows.flatten() {
+            let file_name = file_name
+                .strip_suffix(DISABLED_SUFFIX)
+                .unwrap_or(&file_name)
+                .to_string();
+            entries.insert(
+                format!("mods/{}", file_name),
+                DbEntry {
+                    name,
+                    version,
+                    source: LockSource::from_db(&source, source_id.as_deref(), url.as_deref()),
+                    sha1,
+                },
+            );
+        }
+        Ok(entries)
+    }
+
+    /// Файлы в отслеживаемых папках: (путь без .disabled, enabled), отсортированы по пути
+    async fn scan_disk(root: &Path) -> Result<BTreeMap<String, bool>> {
+        let mut found = BTreeMap::new();
+
+        for (dir, extension) in TRACKED_DIRS {
+            let dir_path = root.join(dir);
+            if !tokio::fs::try_exists(&dir_path).await.unwrap_or(false) {
+                continue;
+            }
+
+            let mut entries = tokio::fs::read_dir(&dir_path).await?;
+            while let Ok(Some(entry)) = entries.next_entry().await {
+                if !entry
+                    .file_type()
+                    .await
+                    .map(|t| t.is_file())
+                    .unwrap_or(false)
+                {
+                    continue;
+                }
+                let file_name = entry.file_name().to_string_lossy().to_string();
+                let (base, enabled) = match file_name.strip_suffix(DISABLED_SUFFIX) {
+                    Some(base) => (base.to_string(), false),
+                    None => (file_name, true),
+                };
+                if !base.ends_with(extension) {
+                    continue;
+                }
+
+                // Если есть обе копии, реально загружается включённая
+                *found.entry(format!("{}/{}", dir, base)).or_insert(false) |= enabled;
+            }
+        }
+
+        Ok(found)
+    }
+
+    fn file_stem(path: &str) -> String {
+        Path::new(path)
+            .file_stem()
+            .map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_else(|| path.to_string())
+    }
+
+    /// Проверить файлы экземпляра по sha512 из lock
+    pub async fn verify(instance_id: &str, lock: &InstanceLock) -> Result<VerificationResult> {
+        let root = instance_dir(instance_id);
+
+        let mut result = VerificationResult {
+            valid: true,
+            total_files: lock.files.len() as u32,
+            verified_files: 0,
+            failed_files: vec![],
+            missing_files: vec![],
+        };
+
+        for file in &lock.files {
+            let disk_path = file.disk_path(&root, file.enabled);
+
+            if !tokio::fs::try_exists(&disk_path).await.unwrap_or(false) {
+                result.missing_files.push(file.path.clone());
+                result.valid = false;
+                continue;
+            }
+
+            let (_, actual_hash) = compute_file_hashes(&disk_path)
+                .await
+                .map_err(|e| LauncherError::InvalidConfig(e.to_string()))?;
+
+            if actual_hash != file.sha512 {
+                result.failed_files.push(VerificationFailure {
+                    filename: file.path.clone(),
+                    expected_hash: file.sha512.clone(),
+                    actual_hash,
+                });
+                result.valid = false;
+            } else {
+                result.verified_files += 1;
+            }
+        }
+
+        Ok(result)
+    }
+
+    /// Сравнить папку экземпляра с lock
+    pub async fn drift(instance_id: &str) -> Result<LockDrift> {
+        let Some(lock) = Self::read(instance_id).await? else {
+            return Ok(LockDrift {
+                has_lock: false,
+                in_sync: false,
+                missing: vec![],
+                modified: vec![],
+                state_changed: vec![],
+                untracked: vec![],
+            });
+        };
+
+        let root = instance_dir(instance_id);
+        let on_disk = Self::scan_disk(&root).await?;
+
+        let mut missing = Vec::new();
+        let mut modified = Vec::new();
+        let mut state_changed = Vec::new();
+
+        for file in &lock.files {
+            let Some(&enabled) = on_disk.get(&file.path) else {
+                missing.push(file.path.clone());
+                continue;
+            };
+            if enabled != file.enabled {
+                state_changed.push(file.path.clone());
+            }
+
+            let (_, actual_hash) = compute_file_hashes(&file.disk_path(&root, enabled))
+                .await
+                .map_err(|e| LauncherError::InvalidConfig(e.to_string()))?;
+            if actual_hash != file.sha512 {
+                modified.push(VerificationFailure {
+                    filename: file.path.clone(),
+                    expected_hash: file.sha512.clone(),
+                    actual_hash,
+                });
+            }
+        }
+
+        let locked: HashSet<&str> = lock.files.iter().map(|f| f.path.as_str()).collect();
+        let untracked: Vec<String> = on_disk
+            .into_keys()
+            .filter(|path| !locked.contains(path.as_str()))
+            .collect();
+
+        Ok(LockDrift {
+            has_lock: true,
+            in_sync: missing.is_empty()
+                && modified.is_empty()
+                && state_changed.is_empty()
+                && untracked.is_empty(),
+            missing,
+            modified,
+            state_changed,
+            untracked,
+        })
+    }
+
+    /// Относительный путь внутри отслеживаемой папки (защита от `../` в чужом lock)
+    fn validate_path(path: &str) -> Result<()> {
+        let p = Path::new(path);
+        let mut components = p.components();
+        let dir_ok = match components.next() {
+            Some(Component::Normal(dir)) => dir
+                .to_str()
+                .is_some_and(|dir| TRACKED_DIRS.iter().any(|(d, _)| *d == dir)),
+            _ => false,
+        };
+        let rest_ok =
+            matches!(components.next(), Some(Component::Normal(_))) && components.next().is_none();
+
+        if dir_ok && rest_ok {
+            Ok(())
+        } else {
+            Err(LauncherError::InvalidConfig(format!(
+                "Invalid path in {}: {}",
+                LOCK_FILE_NAME, path
+            )))
+        }
+    }
+
+    async fn file_matches(path: &Path, sha512: &str) -> bool {
+        if !tokio::fs::try_exists(path).await.unwrap_or(false) {
+            return false;
+        }
+        matches!(compute_file_hashes(path).await, Ok((_, actual)) if actual == sha512)
+    }
+
+    /// URL для скачивания; для Modrinth без ссылки ищем файл в версии по sha1
+    async fn resolve_url(file: &LockedFile) -> Option<String> {
+        if let Some(url) = file.source.url() {
+            return Some(url.to_string());
+        }
+        if let LockSource::Modrinth {
+            version_id: Some(version_id),
+            ..
+        } = &file.source
+        {
+            let version = ModrinthClient::get_version(version_id).await.ok()?;
+            return version
+                .files
+                .into_iter()
+                .find(|f| f.hashes.sha1 == file.sha1)
+                .map(|f| f.url.clone());
+        }
+        None
+    }
+
+    /// Привести экземпляр к состоянию из lock и проверить результат.
+    /// Лишние и заменяемые файлы не удаляются, а переносятся в
+    /// `.stuzhik/lock_backups/<дата>/`.
+    pub async fn restore_from_lock(
+        instance_id: &str,
+        download_manager: &DownloadManager,
+    ) -> Result<RestoreReport> {
+        Self::ensure_not_running(instance_id).await?;
+
+        // Пока идёт восстановление, lock — эталон: перезапись по промежуточному
+        // состоянию диска потеряла бы файлы, которые не удалось скачать
+        RESTORING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(instance_id.to_string());
+        let result = Self::restore_files(instance_id, download_manager).await;
+        RESTORING
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(instance_id);
+        result
+    }
+
+    async fn restore_files(
+        instance_id: &str,
+        download_manager: &DownloadManager,
+    ) -> Result<RestoreReport> {
+        let lock = Self::read(instance_id).await?.ok_or_else(|| {
+            LauncherError::NotFound(format!("{} for instance {}", LOCK_FILE_NAME, instance_id))
+        })?;
+        for file in &lock.files {
+            Self::validate_path(&file.path)?;
+        }
+
+        let root = instance_dir(instance_id);
+        let backup_dir = root
+            .join(".stuzhik")
+            .join(BACKUP_DIR)
+            .join(Utc::now().format("%Y%m%d_%H%M%S").to_string());
+        let mut downloaded = Vec::new();
+        let mut toggled = Vec::new();
+        let mut backed_up = Vec::new();
+        let mut unrestorable = Vec::new();
+
+        for file in &lock.files {
+            let target = file.disk_path(&root, file.enabled);
+            let other = file.disk_path(&root, !file.enabled);
+
+            if Self::file_matches(&target, &file.sha512).await {
+                // Уже на месте
+            } else if Self::file_matches(&other, &file.sha512).await {
+                if tokio::fs::try_exists(&target).await.unwrap_or(false) {
+                    backed_up.push(Self::move_to_backup(&root, &backup_dir, &target).await?);
+                }
+                tokio::fs::rename(&other, &target).await?;
+                toggled.push(file.path.clone());
+            } else {
+                match Self::resolve_url(file).await {
+                    Some(url) => {
+                        if let Some(parent) = target.parent() {
+                            tokio::fs::create_dir_all(parent).await?;
+                        }
+                        // Качаем рядом и подменяем только проверенный файл:
+                        // при ошибке загрузки текущий файл остаётся на месте
+                        let temp = Self::download_path(&target);
+                        let verified = match download_manager
+                            .download_file(&url, &temp, &file.name, Some(file.sha1.as_str()))
+                            .await
+                        {
+                            Ok(()) => {
+                                let matches = Self::file_matches(&temp, &file.sha512).await;
+                                if !matches {
+                                    log::warn!(
+                                        "Downloaded {} does not match {}",
+                                        file.path,
+                                        LOCK_FILE_NAME
+                                    );
+                                }
+                                matches
+                            }
+                            Err(e) => {
+                                log::warn!("Failed to restore {}: {}", file.path, e);
+                                false
+                            }
+                        };
+
+                        if verified {
+                            if tokio::fs::try_exists(&target).await.unwrap_or(false) {
+                                backed_up
+                                    .push(Self::move_to_backup(&root, &backup_dir, &target).await?);
+                            }
+                            tokio::fs::rename(&temp, &target).await?;
+                            downloaded.push(file.path.clone());
+                        } else {
+                            let _ = tokio::fs::remove_file(&temp).await;
+                            unrestorable.push(file.path.clone());
+                        }
+                    }
+                    None => unrestorable.push(file.path.clone()),
+                }
+            }
+
+            if tokio::fs::try_exists(&other).await.unwrap_or(false) {
+                backed_up.push(Self::move_to_backup(&root, &backup_dir, &other).await?);
+            }
+        }
+
+        // Всё, чего нет в lock, убираем в бэкап
+        let locked: HashSet<&str> = lock.files.iter().map(|f| f.path.as_str()).collect();
+        for (path, _) in Self::scan_disk(&root).await? {
+            if locked.contains(path.as_str()) {
+                continue;
+            }
+            for disk_path in [
+                root.join(&path),
+                root.join(format!("{}{}", path, DISABLED_SUFFIX)),
+            ] {
+                if tokio::fs::try_exists(&disk_path).await.unwrap_or(false) {
+                    backed_up.push(Self::move_to_backup(&root, &backup_dir, &disk_path).await?);
+                }
+            }
+        }
+
+        Self::sync_database(instance_id, &lock).await?;
+
+        let verification = Self::verify(instance_id, &lock).await?;
+        log::info!(
+            "Restored {} from {}: {} downloaded, {} toggled, {} moved to backup, {} unrestorable",
+            instance_id,
+            LOCK_FILE_NAME,
+            downloaded.len(),
+            toggled.len(),
+            backed_up.len(),
+            unrestorable.len()
+        );
+
+        Ok(RestoreReport {
+            downloaded,
+            toggled,
+            backup_dir: (!backed_up.is_empty()).then(|| backup_dir.to_string_lossy().to_string()),
+            backed_up,
+            unrestorable,
+            verification,
+        })
+    }
+
+    /// Восстанавливать поверх запущенного экземпляра нельзя: игра держит jar открытыми
+    async fn ensure_not_running(instance_id: &str) -> Result<()> {
+        let status: Option<String> = {
+            let conn = get_db_conn()?;
+            conn.query_row(
+                "SELECT status FROM instances WHERE id = ?1",
+                [instance_id],
+                |row| row.get(0),
+            )
+            .optional()?
+        };
+
+        let server_running = get_console(instance_id).await.read().await.is_running();
+        if server_running
+            || matches!(
+                status.as_deref(),
+                Some("running" | "starting" | "stopping" | "restarting")
+            )
+        {
+            return Err(LauncherError::InstanceAlreadyRunning);
+        }
+        Ok(())
+    }
+
+    /// Временный путь загрузки рядом с целевым файлом (не попадает под скан)
+    fn download_path(target: &Path) -> PathBuf {
+        let name = target
+            .file_name()
+            .map(|n| n.to_string_lossy().to_string())
+            .unwrap_or_default();
+        target.with_file_name(format!("{}.stuzhik-download", name))
+    }
+
+    /// Перенести файл в бэкап с сохранением относительного пути
+    async fn move_to_backup(root: &Path, backup_dir: &Path, path: &Path) -> Result<String> {
+        let relative = path.strip_prefix(root).unwrap_or(path);
+        let dest = backup_dir.join(relative);
+        if let Some(parent) = dest.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+        tokio::fs::rename(path, &dest).await?;
+        Ok(relative.to_string_lossy().replace('\\', "/"))
+    }
+
+    /// Обновить БД после восстановления; скан регистрирует файлы как local, источник берём из lock
+    async fn sync_database(instance_id: &str, lock: &InstanceLock) -> Result<()> {
+        ModManager::sync_mods_with_folder(instance_id).await?;
+
+        let root = instance_dir(instance_id);
+        for resource_type in [ResourceType::Resourcepack, ResourceType::Shader] {
+            let table = resource_type.table_name();
+            for res in ResourceManager::list_resources(resource_type, Some(instance_id), false)? {
+                let path = format!("{}/{}", resource_type.folder_name(), res.file_name);
+                let enabled_path = root.join(&path);
+                let enabled = tokio::fs::try_exists(&enabled_path).await.unwrap_or(false);
+                let disabled =
+                    tokio::fs::try_exists(root.join(format!("{}{}", path, DISABLED_SUFFIX)))
+                        .await
+                        .unwrap_or(false);
+
+                let conn = get_db_conn()?;
+                if !enabled && !disabled {
+                    conn.execute(&format!("DELETE FROM {} WHERE id = ?1", table), [res.id])?;
+                } else if enabled != res.enabled {
+                    conn.execute(
+                        &format!("UPDATE {} SET enabled = ?1 WHERE id = ?2", table),
+                        params![enabled, res.id],
+                    )?;
+                }
+            }
+            ResourceManager::scan_and_import(resource_type, Some(instance_id), false).await?;
+        }
+
+        let conn = get_db_conn()?;
+        for file in &lock.files {
+            let source = file.source.source_name();
+            if source == "local" {
+                continue;
+            }
+
+            let (table, file_name) = match file.path.split_once('/') {
+                Some(("mods", name)) if file.enabled => ("mods", name.to_string()),
+                Some(("mods", name)) => ("mods", format!("{}{}", name, DISABLED_SUFFIX)),
+                Some((dir, name)) => (dir, name.to_string()),
+                None => continue,
+            };
+            conn.execute(
+                &format!(
+                    "UPDATE {} SET source = ?1, source_id = ?2, download_url = ?3, file_hash = ?4
+                     WHERE instance_id = ?5 AND file_name = ?6 AND source = 'local'",
+                    table
+                ),
+                params![
+                    source,
+                    file.source.source_id(),
+                    file.source.url(),
+                    file.sha1,
+                    instance_id,
+                    file_name
+                ],
+            )?;
+        }
+
+        Ok(())
+    }
+}
+
+// ========== Tauri Commands ==========
+
+/// Прочитать instance.lock
+#[tauri::command]
+pub async fn get_instance_lock(instance_id: String) -> Result<Option<InstanceLock>> {
+    LockManager::read(&instance_id).await
+}
+
+/// Перезаписать instance.lock по текущему состоянию экземпляра
+#[tauri::command]
+pub async fn write_instance_lock(instance_id: String) -> Result<InstanceLock> {
+    LockManager::write(&instance_id).await
+}
+
+/// Отчёт о расхождении папки экземпляра с instance.lock
+#[tauri::command]
+pub async fn get_instance_lock_drift(instance_id: String) -> Result<LockDrift> {
+    LockManager::drift(&instance_id).await
+}
+
+/// Восстановить экземпляр из instance.lock
+#[tauri::command]
+pub async fn restore_from_lock(
+    instance_id: String,
+    app_handle: tauri::AppHandle,
+) -> Result<RestoreReport> {
+    let download_manager = DownloadManager::new(app_handle)?;
+    LockManager::restore_from_lock(&instance_id, &download_manager).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_source_urls() {
+        assert_eq!(
+            modrinth_version_id(
+                "https://cdn.modrinth.com/data/AANobbMI/versions/OihdIimA/sodium.jar"
+            ),
+            Some("OihdIimA".to_string())
+        );
+        assert_eq!(
+            curseforge_file_id("https://edge.forgecdn.net/files/4712/345/jei.jar"),
+            Some(4_712_345)
+        );
+        assert_eq!(
+            curseforge_file_id("https://example.com/files/1/2/x.jar"),
+            None
+        );
+
+        assert!(LockManager::validate_path("mods/sodium.jar").is_ok());
+        assert!(LockManager::validate_path("mods/../options.txt").is_err());
+        assert!(LockManager::validate_path("/etc/passwd").is_err());
+        assert!(LockManager::validate_path("config/x.toml").is_err());
+    }
+
+    #[test]
+    fn test_lock_roundtrip() {
+        let lock = InstanceLock {
+            lock_version: LOCK_VERSION,
+            minecraft_version: "1.20.1".to_string(),
+            loader: "fabric".to_string(),
+            loader_version: Some("0.15.7".to_string()),
+            files: vec![
+                LockedFile {
+                    path: "mods/sodium.jar".to_string(),
+                    name: "Sodium".to_string(),
+                    version: Some("0.5.3".to_string()),
+                    enabled: true,
+                    side: Some(ModSide::Client),
+                    size: 1024,
+                    sha1: "a".repeat(40),
+                    sha512: "b".repeat(128),
+                    source: LockSource::Modrinth {
+                        project_id: "AANobbMI".to_string(),
+                        version_id: Some("OihdIimA".to_string()),
+                        url: None,
+                    },
+                },
+                LockedFile {
+                    path: "resourcepacks/pack.zip".to_string(),
+                    name: "pack".to_string(),
+                    version: None,
+                    enabled: false,
+                    side: None,
+                    size: 2048,
+                    sha1: "c".repeat(40),
+                    sha512: "d".repeat(128),
+                    source: LockSource::Local,
+                },
+            ],
+        };
+
+        let text = toml::to_string_pretty(&lock).unwrap();
+        assert!(text.contains("[[file]]"));
+        let parsed: InstanceLock = toml::from_str(&text).unwrap();
+        assert_eq!(parsed, lock);
+    }
+}
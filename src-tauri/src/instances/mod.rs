@@ -2,7 +2,9 @@
 pub mod execution;
 pub mod installation;
 pub mod lifecycle;
+pub mod lock;
 pub mod utilities;
+pub mod watchdog;
 
 // Re-export what's used through this module level
 pub use lifecycle::{create_instance, get_instance, list_instances};
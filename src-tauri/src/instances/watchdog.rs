@@ -0,0 +1,435 @@
+//! Watchdog серверных процессов
+//!
+//! - Рестарт после краша с экспоненциальной задержкой
+//! - Crash loop (N крашей за M минут, см. `WatchdogSettings`): рестарты прекращаются,
+//!   логи анализируются, результат сохраняется в историю крашей
+//! - Зависание: нет вывода в консоль и нет ответа на `list` — thread dump и рестарт
+
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::path::{Path, PathBuf};
+use std::sync::{LazyLock, Mutex};
+use std::time::{Duration, Instant};
+use tauri::{AppHandle, Emitter};
+
+use crate::error::Result;
+use crate::log_analyzer::{save_crash_record, LogAnalyzer};
+use crate::server::console::{get_console, is_server_ready_line};
+use crate::server::rcon::RCON_CONNECTIONS;
+use crate::settings::{SettingsManager, WatchdogSettings};
+
+use super::execution::{is_process_running, verify_and_kill_orphaned_process};
+use super::lifecycle::get_instance;
+
+/// Задержка перед первым рестартом, дальше удваивается
+const BASE_RESTART_DELAY: Duration = Duration::from_secs(5);
+const MAX_RESTART_DELAY: Duration = Duration::from_secs(5 * 60);
+
+/// Сколько сервер может молчать, прежде чем его проверят на зависание
+const HANG_TIMEOUT: Duration = Duration::from_secs(3 * 60);
+/// Сколько ждать ответа на `list`
+const PROBE_TIMEOUT: Duration = Duration::from_secs(15);
+const HANG_CHECK_INTERVAL: Duration = Duration::from_secs(15);
+/// Таймаут jcmd (на зависшей JVM attach тоже может зависнуть)
+const THREAD_DUMP_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Время крашей по серверам
+static CRASH_HISTORY: LazyLock<Mutex<HashMap<String, VecDeque<Instant>>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Серверы, убитые watchdog'ом из-за зависания — их перезапускаем независимо от auto_restart
+static HANG_RESTARTS: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Серверы, которым рестарты временно запрещены (пробные запуски бисекции):
+/// краш не перезапускает сервер и не идёт в счётчик crash loop
+static SUPPRESSED: LazyLock<Mutex<HashSet<String>>> = LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// Что делать после завершения серверного процесса
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitDecision {
+    /// Не перезапускать
+    Stay,
+    /// Перезапустить через `delay`
+    Restart { delay: Duration, attempt: usize },
+    /// Слишком много крашей подряд — рестарты прекращены
+    CrashLoop { crashes: usize },
+}
+
+pub struct ServerWatchdog;
+
+impl ServerWatchdog {
+    /// Решить судьбу сервера после выхода процесса. Вызывается из монитора процесса.
+    pub fn on_exit(instance_id: &str, crashed: bool, auto_restart: bool) -> ExitDecision {
+        let forced = HANG_RESTARTS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(instance_id);
+        if SUPPRESSED
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .contains(instance_id)
+        {
+            return ExitDecision::Stay;
+        }
+
+        let mut history = CRASH_HISTORY.lock().unwrap_or_else(|e| e.into_inner());
+        if !crashed && !forced {
+            // Штатная остановка — счётчик крашей с чистого листа
+            history.remove(instance_id);
+            return ExitDecision::Stay;
+        }
+
+        let settings = watchdog_settings();
+        let crashes = history.entry(instance_id.to_string()).or_default();
+        let decision = record_crash(crashes, Instant::now(), auto_restart || forced, &settings);
+        if matches!(decision, ExitDecision::CrashLoop { .. }) {
+            // Ручной запуск после разбора проблемы начинается с чистого листа
+            history.remove(instance_id);
+        }
+        decision
+    }
+
+    /// Запретить или снова разрешить рестарты сервера
+    pub fn suppress_restarts(instance_id: &str, suppress: bool) {
+        let mut suppressed = SUPPRESSED.lock().unwrap_or_else(|e| e.into_inner());
+        if suppress {
+            suppressed.insert(instance_id.to_string());
+        } else {
+            suppressed.remove(instance_id);
+        }
+    }
+
+    /// Разобрать логи после crash loop и сохранить результат в историю крашей
+    pub async fn diagnose_crash_loop(app: AppHandle, instance_id: String, crashes: usize) {
+        let window_minutes = watchdog_settings().crash_loop_window_minutes;
+        log::warn!(
+            "Server {} crashed {} times within {} minutes, auto-restart disabled until manual start",
+            instance_id,
+            crashes,
+            window_minutes
+        );
+
+        let problems = match LogAnalyzer::new().analyze_all_logs(&instance_id).await {
+            Ok(result) => result.problems,
+            Err(e) => {
+                log::warn!("Crash loop analysis failed for {}: {}", instance_id, e);
+                Vec::new()
+            }
+        };
+
+        let instance = get_instance(instance_id.clone()).await.ok();
+        let record = save_crash_record(
+            &instance_id,
+            "crash_loop",
+            &problems,
+            instance.as_ref().map(|i| i.version.as_str()),
+            instance.as_ref().map(|i| i.loader.as_str()),
+            instance.as_ref().and_then(|i| i.loader_version.as_deref()),
+        );
+        let record = match record {
+            Ok(record) => Some(record),
+            Err(e) => {
+                log::error!(
+                    "Failed to save crash loop record for {}: {}",
+                    instance_id,
+                    e
+                );
+                None
+            }
+        };
+
+        let _ = app.emit(
+            "server-crash-loop",
+            serde_json::json!({
+                "instance_id": instance_id,
+                "crashes": crashes,
+                "window_minutes": window_minutes,
+                "problems": problems,
+                "record": record,
+            }),
+        );
+    }
+
+    /// Следить за зависаниями сервера, пока жив процесс `pid`
+    pub fn watch_hangs(app: AppHandle, instance_id: String, pid: u32, java_path: PathBuf) {
+        tauri::async_runtime::spawn(async move {
+            let console = get_console(&instance_id).await;
+            let mut last_seen = 0i64;
+            let mut last_output = Instant::now();
+            // До "Done" сервер может долго молчать (генерация мира), не трогаем его
+            let mut ready = false;
+
+            loop {
+                tokio::time::sleep(HANG_CHECK_INTERVAL).await;
+                if !is_process_running(pid) {
+                    break;
+                }
+
+                let new_logs = console.read().await.get_logs_since(last_seen);
+                if let Some(last) = new_logs.last() {
+                    last_seen = last.timestamp;
+                    last_output = Instant::now();
+                    ready |= new_logs.iter().any(|e| is_server_ready_line(&e.line));
+                }
+                if !ready || last_output.elapsed() < HANG_TIMEOUT {
+                    continue;
+                }
+
+                if probe_alive(&instance_id, last_seen).await {
+                    last_output = Instant::now();
+                    continue;
+                }
+                // Процесс мог штатно завершиться, пока ждали ответа
+                if !is_process_running(pid) {
+                    break;
+                }
+
+                Self::handle_hang(&app, &instance_id, pid, &java_path).await;
+                break;
+            }
+        });
+    }
+
+    async fn handle_hang(app: &AppHandle, instance_id: &str, pid: u32, java_path: &Path) {
+        log::error!(
+            "Server {} is frozen: no output for {:?} and no answer to 'list'",
+            instance_id,
+            HANG_TIMEOUT
+        );
+
+        let dump = capture_thread_dump(instance_id, pid, java_path).await;
+        let _ = app.emit(
+            "server-hang-detected",
+            serde_json::json!({
+                "instance_id": instance_id,
+                "thread_dump": dump,
+            }),
+        );
+
+        HANG_RESTARTS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(instance_id.to_string());
+        // Выход процесса обработает монитор: рестарт через on_exit
+        if !verify_and_kill_orphaned_process(pid) {
+            HANG_RESTARTS
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .remove(instance_id);
+            log::warn!("Failed to kill frozen server {} (PID {})", instance_id, pid);
+        }
+    }
+}
+
+/// Настройки crash loop; при ошибке чтения — значения по умолчанию
+fn watchdog_settings() -> WatchdogSettings {
+    SettingsManager::get_watchdog().unwrap_or_else(|e| {
+        log::warn!("Failed to read watchdog settings, using defaults: {}", e);
+        WatchdogSettings::default()
+    })
+}
+
+/// Записать краш и решить, перезапускать ли сервер
+fn record_crash(
+    crashes: &mut VecDeque<Instant>,
+    now: Instant,
+    restart: bool,
+    settings: &WatchdogSettings,
+) -> ExitDecision {
+    let window = Duration::from_secs(u64::from(settings.crash_loop_window_minutes) * 60);
+    while crashes
+        .front()
+        .is_some_and(|t| now.duration_since(*t) > window)
+    {
+        crashes.pop_front();
+    }
+    crashes.push_back(now);
+
+    if !restart {
+        return ExitDecision::Stay;
+    }
+    if crashes.len() >= settings.crash_loop_threshold as usize {
+        return ExitDecision::CrashLoop {
+            crashes: crashes.len(),
+        };
+    }
+    ExitDecision::Restart {
+        delay: restart_delay(crashes.len()),
+        attempt: crashes.len(),
+    }
+}
+
+/// 5s, 10s, 20s, ... до MAX_RESTART_DELAY
+fn restart_delay(attempt: usize) -> Duration {
+    let factor = 1u32 << attempt.saturating_sub(1).min(16);
+    BASE_RESTART_DELAY
+        .saturating_mul(factor)
+        .min(MAX_RESTART_DELAY)
+}
+
+/// Ответит ли сервер на `list`: через RCON, иначе через stdin с ожиданием вывода
+async fn probe_alive(instance_id: &str, last_seen: i64) -> bool {
+    let rcon = RCON_CONNECTIONS.read().await.get(instance_id).cloned();
+    if let Some(client) = rcon {
+        if let Ok(Ok(_)) = tokio::time::timeout(PROBE_TIMEOUT, client.command("list")).await {
+            return true;
+        }
+    }
+
+    let console = get_console(instance_id).await;
+    if console.write().await.send_command("list").is_err() {
+        return false;
+    }
+    tokio::time::sleep(PROBE_TIMEOUT).await;
+    let new_logs = console.read().await.get_logs_since(last_seen);
+    !new_logs.is_empty()
+}
+
+/// Снять thread dump через jcmd рядом с java. Без jcmd (JRE) на Unix просим JVM
+/// вывести дамп в stdout сигналом QUIT — он попадёт в latest-stdout.log.
+async fn capture_thread_dump(instance_id: &str, pid: u32, java_path: &Path) -> Option<PathBuf> {
+    let jcmd = java_path.with_file_name(if cfg!(windows) { "jcmd.exe" } else { "jcmd" });
+    if jcmd.exists() {
+        let mut cmd = tokio::process::Command::new(&jcmd);
+        cmd.args([pid.to_string().as_str(), "Thread.print", "-l"])
+            .kill_on_drop(true);
+        #[cfg(windows)]
+        cmd.creation_flags(0x08000000);
+
+        match tokio::time::timeout(THREAD_DUMP_TIMEOUT, cmd.output()).await {
+            Ok(Ok(output)) if output.status.success() => {
+                let path = crate::paths::instance_logs_dir(instance_id).join(format!(
+                    "thread-dump-{}.txt",
+                    chrono::Local::now().format("%Y%m%d-%H%M%S")
+                ));
+                match tokio::fs::write(&path, &output.stdout).await {
+                    Ok(()) => {
+                        log::info!("Thread dump for {} saved to {:?}", instance_id, path);
+                        return Some(path);
+                    }
+                    Err(e) => log::warn!("Failed to save thread dump: {}", e),
+                }
+            }
+            Ok(Ok(output)) => log::warn!(
+                "jcmd failed for {}: {}",
+                instance_id,
+                String::from_utf8_lossy(&output.stderr)
+            ),
+            Ok(Err(e)) => log::warn!("Failed to run jcmd: {}", e),
+            Err(_) => log::warn!("jcmd timed out for {}", instance_id),
+        }
+    }
+
+    #[cfg(not(windows))]
+    {
+        let _ = tokio::process::Command::new("kill")
+            .args(["-QUIT", &pid.to_string()])
+            .output()
+            .await;
+        // JVM нужно время, чтобы дописать дамп до kill -9
+        tokio::time::sleep(Duration::from_secs(2)).await;
+        log::info!(
+            "Requested thread dump for {} via SIGQUIT (see latest-stdout.log)",
+            instance_id
+        );
+    }
+    None
+}
+
+#[tauri::command]
+pub async fn get_watchdog_settings() -> Result<WatchdogSettings> {
+    SettingsManager::get_watchdog()
+}
+
+#[tauri::command]
+pub async fn save_watchdog_settings(settings: WatchdogSettings) -> Result<()> {
+    SettingsManager::set_watchdog(&settings)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_restart_delay_backoff() {
+        assert_eq!(restart_delay(1), Duration::from_secs(5));
+        assert_eq!(restart_delay(2), Duration::from_secs(10));
+        assert_eq!(restart_delay(3), Duration::from_secs(20));
+        assert_eq!(restart_delay(100), MAX_RESTART_DELAY);
+    }
+
+    #[test]
+    fn test_crash_loop_detection() {
+        let settings = WatchdogSettings::default();
+        let threshold = settings.crash_loop_threshold as usize;
+        let start = Instant::now();
+        let mut crashes = VecDeque::new();
+
+        for i in 1..threshold {
+            let at = start + Duration::from_secs(i as u64);
+            let decision = record_crash(&mut crashes, at, true, &settings);
+            assert!(matches!(decision, ExitDecision::Restart { attempt, .. } if attempt == i));
+        }
+        assert_eq!(
+            record_crash(
+                &mut crashes,
+                start + Duration::from_secs(60),
+                true,
+                &settings
+            ),
+            ExitDecision::CrashLoop { crashes: threshold }
+        );
+    }
+
+    #[test]
+    fn test_crash_loop_uses_settings() {
+        let settings = WatchdogSettings {
+            crash_loop_threshold: 2,
+            crash_loop_window_minutes: 1,
+        };
+        let start = Instant::now();
+        let mut crashes = VecDeque::new();
+
+        record_crash(&mut crashes, start, true, &settings);
+        // Вне окна в 1 минуту — счётчик начинается заново
+        let later = start + Duration::from_secs(120);
+        assert!(matches!(
+            record_crash(&mut crashes, later, true, &settings),
+            ExitDecision::Restart { attempt: 1, .. }
+        ));
+        assert_eq!(
+            record_crash(
+                &mut crashes,
+                later + Duration::from_secs(5),
+                true,
+                &settings
+            ),
+            ExitDecision::CrashLoop { crashes: 2 }
+        );
+    }
+
+    #[test]
+    fn test_old_crashes_expire() {
+        let settings = WatchdogSettings::default();
+        let start = Instant::now();
+        let mut crashes = VecDeque::new();
+        for i in 0..settings.crash_loop_threshold - 1 {
+            let at = start + Duration::from_secs(u64::from(i));
+            record_crash(&mut crashes, at, true, &settings);
+        }
+
+        // Через час старые краши не считаются
+        let later = start + Duration::from_secs(3600);
+        assert_eq!(
+            record_crash(&mut crashes, later, true, &settings),
+            ExitDecision::Restart {
+                delay: BASE_RESTART_DELAY,
+                attempt: 1
+            }
+        );
+        assert_eq!(
+            record_crash(&mut crashes, later, false, &settings),
+            ExitDecision::Stay
+        );
+    }
+}
@@ -776,7 +776,7 @@ fn compare_file_lists(
     (added, removed, modified)
 }
 
-fn extract_mod_slug(filename: &str) -> String {
+pub(crate) fn extract_mod_slug(filename: &str) -> String {
     let name = filename
         .trim_end_matches(".jar")
         .trim_end_matches(".disabled");
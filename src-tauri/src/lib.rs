@@ -89,14 +89,19 @@ use db_crate::db;
 
 // Local modules
 mod api; // API stays in main crate for now
+mod auth;
 mod backup;
 mod cancellation;
+mod cli;
 mod code_editor;
 mod collections;
 mod config_editor;
 mod conflict_predictor;
+mod cron;
+mod dependency_solver;
 mod downloader; // Re-exports SmartDownloader as DownloadManager
 mod error_reporter;
+mod events;
 mod game_settings;
 mod games;
 mod gpu;
@@ -109,6 +114,9 @@ mod launchers;
 mod loaders;
 mod log_analyzer;
 mod minecraft;
+mod mod_bisect;
+mod mod_policy;
+mod mod_versions;
 mod modpack_editor;
 mod modpacks;
 mod mods;
@@ -127,6 +135,7 @@ mod translations;
 mod tray;
 mod utils;
 mod wiki;
+mod worlds;
 
 use error::Result;
 
@@ -1089,6 +1098,24 @@ async fn resolve_dependencies(
     .await
 }
 
+/// Предпросмотр плана установки недостающих зависимостей (без установки)
+#[tauri::command]
+async fn get_dependency_plan(
+    instance_id: String,
+    minecraft_version: String,
+    loader: String,
+) -> Result<dependency_solver::InstallPlan> {
+    dependency_solver::DependencySolver::plan(&dependency_solver::PlanRequest {
+        instance_id: &instance_id,
+        minecraft_version: &minecraft_version,
+        loader: &loader,
+        targets: Vec::new(),
+        follow_dependencies: true,
+        resolve_missing: true,
+    })
+    .await
+}
+
 // ========== Config Editor ==========
 
 #[tauri::command]
@@ -2545,6 +2572,14 @@ async fn get_loader_versions(minecraft_version: String, loader: String) -> Resul
         }
         LoaderType::NeoForge => loaders::NeoForgeInstaller::get_versions(&minecraft_version).await,
         LoaderType::Forge => loaders::ForgeInstaller::get_versions(&minecraft_version).await,
+        LoaderType::Paper | LoaderType::Purpur | LoaderType::Folia | LoaderType::Velocity => {
+            server::installer::get_available_loader_versions(
+                server::ServerLoader::from(&loader_type),
+                &minecraft_version,
+            )
+            .await
+            .map_err(|e| error::LauncherError::ApiError(e.to_string()))
+        }
     }
 }
 
@@ -3647,6 +3682,12 @@ async fn get_discovered_servers() -> Vec<(String, p2p::PublishedServer)> {
         .collect()
 }
 
+/// Refresh online players and MOTD of published and discovered servers
+#[tauri::command]
+async fn refresh_server_statuses() {
+    get_server_sync_manager().refresh_server_status().await;
+}
+
 /// Publish server for P2P discovery
 #[tauri::command]
 async fn publish_server_for_discovery(server: p2p::PublishedServer) {
@@ -4035,6 +4076,11 @@ fn setup_logging(base_dir: &std::path::Path) {
     log::info!("Log file: {:?}", log_file);
 }
 
+/// Headless entry point for the `stuzhik-cli` binary. Returns the process exit code.
+pub fn run_cli() -> i32 {
+    cli::run()
+}
+
 #[cfg_attr(mobile, tauri::mobile_entry_point)]
 pub fn run() {
     tauri::Builder::default()
@@ -4091,6 +4137,18 @@ pub fn run() {
                 log::warn!("Failed to cleanup dead processes: {}", e);
             }
 
+            // Бэкапы по расписанию
+            backup::schedule::BackupScheduler::start();
+
+            // Задачи серверов (рестарты, команды, бэкапы)
+            server::tasks::ServerTaskScheduler::start(app.handle().clone());
+
+            // Admin API (если включён в настройках)
+            server::admin_api::AdminApi::init(app.handle().clone());
+
+            // Раздача resource pack'ов серверов (если включена)
+            server::resource_pack::ResourcePackHost::init();
+
             // Очищаем устаревшие .part файлы и кэш-файлы модпаков
             tauri::async_runtime::spawn(async {
                 modpacks::install::cleanup_stale_cache_files().await;
@@ -4291,6 +4349,10 @@ pub fn run() {
             instances::utilities::open_folder_in_vscode,
             instances::lifecycle::reset_instance_version,
             instances::lifecycle::convert_client_to_server,
+            instances::lock::get_instance_lock,
+            instances::lock::write_instance_lock,
+            instances::lock::get_instance_lock_drift,
+            instances::lock::restore_from_lock,
             // Mods
             search_mods,
             install_mod,
@@ -4310,12 +4372,23 @@ pub fn run() {
             stop_mods_watcher,
             is_watching_mods,
             update_mod,
+            mod_versions::get_mod_versions,
+            mod_versions::rollback_mod,
+            mod_versions::rollback_last_mod_update_batch,
+            mod_versions::release_mod_update_hold,
+            mod_policy::get_mod_update_policies,
+            mod_policy::set_mod_update_policy,
+            mod_bisect::start_mod_bisect,
+            mod_bisect::get_mod_bisect,
+            mod_bisect::run_mod_bisect_step,
+            mod_bisect::cancel_mod_bisect,
             bulk_toggle_mods,
             bulk_remove_mods,
             bulk_toggle_auto_update,
             check_mod_dependencies,
             pre_launch_check,
             resolve_dependencies,
+            get_dependency_plan,
             get_dependency_graph,
             cleanup_duplicate_mods,
             clear_update_cache,
@@ -4393,6 +4466,18 @@ pub fn run() {
             settings::get_settings,
             settings::save_settings,
             settings::reset_settings,
+            // Authentication (Ely.by / Yggdrasil)
+            auth::ely_by_login,
+            auth::ely_by_logout,
+            auth::get_ely_by_session,
+            // Accounts
+            auth::accounts::list_accounts,
+            auth::accounts::add_offline_account,
+            auth::accounts::add_yggdrasil_account,
+            auth::accounts::remove_account,
+            auth::accounts::set_default_account,
+            auth::accounts::get_instance_account,
+            auth::accounts::set_instance_account,
             // Custom Translations
             translations::get_custom_translations,
             translations::save_custom_translations,
@@ -4496,6 +4581,7 @@ pub fn run() {
             modpack_editor::remove_mod_from_project,
             modpack_editor::reorder_project_mods,
             modpack_editor::update_project_mod,
+            modpack_editor::set_project_mod_policy,
             modpack_editor::create_optional_group,
             modpack_editor::add_mod_to_optional_group,
             modpack_editor::delete_optional_group,
@@ -4520,7 +4606,35 @@ pub fn run() {
             backup::create_backup,
             backup::list_backups,
             backup::restore_backup,
+            backup::restore_backup_paths,
+            backup::list_backup_contents,
+            backup::diff_backup,
             backup::delete_backup,
+            backup::create_world_backup,
+            backup::list_instance_worlds,
+            backup::gc_backup_store,
+            backup::verify_backups,
+            backup::destinations::list_backup_destinations,
+            backup::destinations::create_backup_destination,
+            backup::destinations::update_backup_destination,
+            backup::destinations::delete_backup_destination,
+            backup::destinations::test_backup_destination,
+            backup::destinations::upload_backup,
+            backup::destinations::list_remote_backups,
+            backup::destinations::restore_remote_backup,
+            backup::schedule::list_backup_schedules,
+            backup::schedule::create_backup_schedule,
+            backup::schedule::update_backup_schedule,
+            backup::schedule::delete_backup_schedule,
+            backup::schedule::run_backup_schedule_now,
+            // Worlds (level.dat, chunk trimming, upgrade, player data)
+            worlds::get_world_inventory,
+            worlds::trim_world_chunks,
+            worlds::delete_world_regions_outside,
+            worlds::upgrade_world,
+            worlds::playerdata::list_player_data,
+            worlds::playerdata::get_player_data,
+            worlds::playerdata::update_player_data,
             // Performance Profiler
             performance::start_performance_monitoring,
             performance::stop_performance_monitoring,
@@ -4615,10 +4729,28 @@ pub fn run() {
             server::rcon::get_rcon_config,
             server::console::send_rcon_command,
             instances::execution::force_kill_server,
+            instances::watchdog::get_watchdog_settings,
+            instances::watchdog::save_watchdog_settings,
             instances::execution::graceful_stop_server,
             server::metrics::get_server_metrics,
             server::metrics::start_metrics_collection,
             server::metrics::stop_metrics_collection,
+            server::tasks::list_server_tasks,
+            server::tasks::create_server_task,
+            server::tasks::update_server_task,
+            server::tasks::delete_server_task,
+            server::tasks::run_server_task_now,
+            server::admin_api::get_admin_api_settings,
+            server::admin_api::save_admin_api_settings,
+            server::admin_api::get_admin_api_status,
+            server::admin_api::list_admin_api_tokens,
+            server::admin_api::create_admin_api_token,
+            server::admin_api::revoke_admin_api_token,
+            server::resource_pack::get_server_resource_packs,
+            server::resource_pack::publish_server_resource_packs,
+            server::resource_pack::unpublish_server_resource_packs,
+            server::resource_pack::get_resource_pack_host_settings,
+            server::resource_pack::save_resource_pack_host_settings,
             server::client_mods::scan_client_mods,
             server::client_mods::disable_client_mods_for_server,
             server::client_mods::enable_mod_file,
@@ -4638,6 +4770,27 @@ pub fn run() {
             server::installer::install_server_loader,
             server::installer::get_server_loader_versions,
             server::installer::get_latest_loader,
+            server::plugins::list_server_plugins,
+            server::plugins::install_server_plugin,
+            server::plugins::toggle_server_plugin,
+            server::plugins::remove_server_plugin,
+            server::network::list_server_networks,
+            server::network::create_server_network,
+            server::network::update_server_network,
+            server::network::delete_server_network,
+            server::network::apply_server_network,
+            server::network::start_server_network,
+            server::network::stop_server_network,
+            server::network::get_server_network_status,
+            server::network::sync_network_player_lists,
+            server::ping::ping_server,
+            server::ping::ping_instance_servers,
+            server::query::query_server,
+            server::sessions::get_player_sessions,
+            server::sessions::get_players_online_between,
+            server::sessions::get_player_summaries,
+            server::sessions::get_player_concurrency,
+            server::sessions::backfill_player_sessions,
             server::import::detect_server_type,
             server::import::import_existing_server,
             // P2P / Stuzhik Connect
@@ -4724,6 +4877,7 @@ pub fn run() {
             get_all_server_sync_configs,
             get_local_published_servers,
             get_discovered_servers,
+            refresh_server_statuses,
             publish_server_for_discovery,
             unpublish_server,
             authorize_server_sync_peer,
@@ -4769,6 +4923,11 @@ pub fn run() {
             code_editor::search_minecraft_tags,
             code_editor::search_minecraft_entries,
             code_editor::get_minecraft_mods,
+            // Code Editor - KubeJS Language Server
+            code_editor::kubejs_complete,
+            code_editor::kubejs_hover,
+            code_editor::kubejs_diagnostics,
+            code_editor::generate_kubejs_typings,
             // Code Editor - Project Detection
             code_editor::project_detector::get_instance_projects,
             code_editor::project_detector::get_project_templates,
@@ -10,7 +10,6 @@ use std::collections::HashMap;
 use std::path::PathBuf;
 use std::sync::atomic::{AtomicUsize, Ordering};
 use std::sync::{Arc, LazyLock, Mutex};
-use tauri::Emitter;
 use tokio_util::sync::CancellationToken;
 
 /// Windows flag to hide console window
@@ -1151,7 +1150,7 @@ async fn predownload_forge_libraries(
     );
 
     // Эмитим событие о начале предзагрузки
-    let _ = download_manager.app_handle().emit(
+    download_manager.events().emit(
         "instance-install-progress",
         serde_json::json!({
             "id": instance_id,
@@ -1306,7 +1305,7 @@ async fn predownload_forge_libraries(
 
                             // Обновляем прогресс каждые 10 библиотек
                             if current % 10 == 0 || current == to_download_count {
-                                let _ = download_manager.app_handle().emit(
+                                download_manager.events().emit(
                                     "instance-install-progress",
                                     serde_json::json!({
                                         "id": instance_id,
@@ -1831,7 +1830,7 @@ impl ForgeInstaller {
             log::info!("Expected vanilla client at: {:?}", instance_version_jar);
 
             // Эмитим событие о начале установки Forge
-            let _ = download_manager.app_handle().emit(
+            download_manager.events().emit(
                 "instance-install-progress",
                 serde_json::json!({
                     "id": instance_id,
@@ -1867,7 +1866,7 @@ impl ForgeInstaller {
 
             // Читаем stdout в реальном времени для логирования и определения прогресса
             let instance_id_clone = instance_id.to_string();
-            let app_handle = download_manager.app_handle().clone();
+            let events = download_manager.events().clone();
             let stdout_task = tokio::spawn(async move {
                 use tokio::io::{AsyncBufReadExt, BufReader};
                 let mut all_output = String::new();
@@ -1880,7 +1879,7 @@ impl ForgeInstaller {
 
                         // Эмитим прогресс если видим интересные строки
                         if line.contains("Downloading") || line.contains("downloading") {
-                            let _ = app_handle.emit(
+                            events.emit(
                                 "instance-install-progress",
                                 serde_json::json!({
                                     "id": instance_id_clone,
@@ -1889,7 +1888,7 @@ impl ForgeInstaller {
                                 }),
                             );
                         } else if line.contains("Extracting") || line.contains("extracting") {
-                            let _ = app_handle.emit(
+                            events.emit(
                                 "instance-install-progress",
                                 serde_json::json!({
                                     "id": instance_id_clone,
@@ -1898,7 +1897,7 @@ impl ForgeInstaller {
                                 }),
                             );
                         } else if line.contains("patching") || line.contains("Patching") {
-                            let _ = app_handle.emit(
+                            events.emit(
                                 "instance-install-progress",
                                 serde_json::json!({
                                     "id": instance_id_clone,
@@ -2038,7 +2037,7 @@ impl ForgeInstaller {
                     log::info!("Downloading {} Forge libraries...", total_libs);
 
                     // Эмитим событие о начале скачивания библиотек
-                    let _ = download_manager.app_handle().emit(
+                    download_manager.events().emit(
                         "instance-install-progress",
                         serde_json::json!({
                             "id": instance_id,
@@ -2067,7 +2066,7 @@ impl ForgeInstaller {
                                 processed_count += 1;
 
                                 // Update progress for skipped libraries too
-                                let _ = download_manager.app_handle().emit(
+                                download_manager.events().emit(
                                     "instance-install-progress",
                                     serde_json::json!({
                                         "id": instance_id,
@@ -2109,7 +2108,7 @@ impl ForgeInstaller {
 
                             processed_count += 1;
                             // Emit progress after each library download
-                            let _ = download_manager.app_handle().emit(
+                            download_manager.events().emit(
                                 "instance-install-progress",
                                 serde_json::json!({
                                     "id": instance_id,
@@ -2207,6 +2206,7 @@ impl ForgeInstaller {
 // Unified Loader Manager
 // ============================================================================
 
+use crate::server::installer::ServerLoader;
 use crate::types::LoaderType;
 
 pub struct LoaderManager;
@@ -2276,6 +2276,24 @@ impl LoaderManager {
                 )
                 .await
             }
+            LoaderType::Paper | LoaderType::Purpur | LoaderType::Folia | LoaderType::Velocity => {
+                if !is_server {
+                    return Err(LauncherError::InvalidConfig(format!(
+                        "{} is a server-only platform",
+                        loader.as_str()
+                    )));
+                }
+                crate::server::installer::install_plugin_platform(
+                    &instance_dir(instance_id),
+                    ServerLoader::from(&loader),
+                    minecraft_version,
+                    loader_version,
+                    download_manager,
+                )
+                .await
+                .map(|result| result.server_jar)
+                .map_err(|e| LauncherError::DownloadFailed(e.to_string()))
+            }
         }
     }
 
@@ -2289,6 +2307,20 @@ impl LoaderManager {
             LoaderType::Quilt => QuiltInstaller::get_latest_version(minecraft_version).await,
             LoaderType::NeoForge => NeoForgeInstaller::get_latest_version(minecraft_version).await,
             LoaderType::Forge => ForgeInstaller::get_latest_version(minecraft_version).await,
+            LoaderType::Paper | LoaderType::Purpur | LoaderType::Folia | LoaderType::Velocity => {
+                crate::server::installer::get_latest_loader_version(
+                    ServerLoader::from(&loader),
+                    minecraft_version,
+                )
+                .await
+                .map_err(|e| LauncherError::ApiError(e.to_string()))?
+                .ok_or_else(|| {
+                    LauncherError::LoaderVersionNotFound(
+                        loader.as_str().to_string(),
+                        minecraft_version.to_string(),
+                    )
+                })
+            }
         }
     }
 }
@@ -833,9 +833,7 @@ impl MinecraftInstaller {
 
     pub fn get_game_arguments(
         version_id: &str,
-        username: &str,
-        uuid: &str,
-        access_token: &str,
+        auth: &crate::auth::LaunchAuth,
         game_dir: &PathBuf,
         assets_root: &PathBuf,
     ) -> Result<Vec<String>> {
@@ -864,17 +862,17 @@ impl MinecraftInstaller {
         let assets_index = version_json.assets.as_deref().unwrap_or("legacy");
 
         let replacements = [
-            ("${auth_player_name}", username),
+            ("${auth_player_name}", auth.username.as_str()),
             ("${version_name}", version_id),
             ("${game_directory}", &game_dir.to_string_lossy()),
             ("${assets_root}", &assets_root.to_string_lossy()),
             ("${assets_index_name}", assets_index),
-            ("${auth_uuid}", uuid),
-            ("${auth_access_token}", access_token),
-            ("${user_type}", "legacy"),
+            ("${auth_uuid}", auth.uuid.as_str()),
+            ("${auth_access_token}", auth.access_token.as_str()),
+            ("${user_type}", auth.user_type),
             ("${version_type}", &version_json.version_type),
             ("${user_properties}", "{}"),
-            ("${auth_session}", access_token),
+            ("${auth_session}", auth.access_token.as_str()),
             ("${clientid}", ""),
             ("${auth_xuid}", ""),
             ("${resolution_width}", "854"),  // Default resolution
@@ -0,0 +1,802 @@
+//! Поиск мода, из-за которого крашится запуск (bisect)
+//!
+//! Включённые не-библиотечные моды делятся на группы: мод и его обязательные
+//! зависимости из графа зависимостей всегда включаются вместе, чтобы разбиение
+//! не давало ложных крашей из-за отсутствующей зависимости. Библиотеки (и то,
+//! что им нужно) остаются включёнными на всех шагах.
+//!
+//! Шаг включает набор групп через `bulk_toggle_mods`, запускает экземпляр и ждёт
+//! главного меню (у сервера — строки готовности) либо краша от `LiveCrashMonitor`.
+//! Поиск идёт бинарно по префиксу списка групп: каждый виновник стоит ~log2(N)
+//! запусков, после чего проверяется, крашится ли игра только с найденными
+//! группами. Так находятся и конфликты из нескольких модов.
+//!
+//! Состояние сессии сохраняется в БД после каждого шага, поэтому прерванный шаг
+//! (закрыли лаунчер, зависла игра) просто запускается заново. По завершении или
+//! отмене исходные состояния модов восстанавливаются.
+
+use std::collections::{HashMap, HashSet};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, LazyLock, Mutex};
+use std::time::{Duration, Instant};
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use tauri::{AppHandle, Emitter, Listener, Manager};
+
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::instances::execution::{start_instance, stop_instance};
+use crate::instances::lifecycle::{get_instance, ChildMap};
+use crate::instances::watchdog::ServerWatchdog;
+use crate::log_analyzer::live_monitor::get_monitor;
+use crate::log_analyzer::LiveCrashEvent;
+use crate::mods::ModManager;
+use crate::paths::instance_logs_dir;
+use crate::server::console::{get_console, is_server_ready_line};
+use crate::server::tasks::stop_and_wait;
+use crate::types::InstanceType;
+
+/// Сколько ждать главного меню или готовности сервера
+const STEP_TIMEOUT: Duration = Duration::from_secs(15 * 60);
+/// Сколько игра должна проработать после меню, чтобы шаг засчитался как успешный
+const SETTLE_TIME: Duration = Duration::from_secs(15);
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+/// Сколько ждать завершения процесса после остановки
+const STOP_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// Строки лога клиента, после которых игра дошла до главного меню
+const CLIENT_READY_PATTERNS: &[&str] = &[
+    // 1.13+: звуковой движок стартует при выходе на титульный экран
+    "Sound engine started",
+    // Forge 1.12 и старше
+    "Forge Mod Loader has successfully loaded",
+];
+
+/// Экземпляры, на которых сейчас идёт шаг
+static RUNNING_STEPS: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+fn is_client_ready_line(line: &str) -> bool {
+    CLIENT_READY_PATTERNS.iter().any(|p| line.contains(p))
+}
+
+/// Моды, которые включаются и выключаются только вместе
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct BisectGroup {
+    pub mod_ids: Vec<i64>,
+    pub names: Vec<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum StepOutcome {
+    /// Дошла до главного меню (сервер — до готовности)
+    Success,
+    Crash,
+    /// Не дошла до меню за отведённое время — считается крашем
+    Timeout,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BisectResult {
+    /// Виновники найдены (`found`)
+    Culprits,
+    /// Со всеми модами игра запускается
+    NotReproduced,
+    /// Крашится и без подозреваемых модов: библиотеки, загрузчик или конфиги
+    OutsideCandidates,
+    /// Шаги противоречат друг другу (плавающий краш)
+    Inconsistent,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "phase", rename_all = "snake_case")]
+pub enum BisectPhase {
+    /// Все группы: воспроизводится ли краш
+    Baseline,
+    /// Только найденные группы: хватает ли их для краша
+    VerifyFound,
+    /// `found + pool[..hi]` крашится, `found + pool[..lo]` — нет
+    Search {
+        lo: usize,
+        hi: usize,
+    },
+    Finished {
+        result: BisectResult,
+    },
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BisectStep {
+    pub phase: BisectPhase,
+    pub enabled_groups: usize,
+    pub outcome: StepOutcome,
+    pub finished_at: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BisectSession {
+    pub id: String,
+    pub instance_id: String,
+    /// Состояния модов до начала: mod_id -> enabled
+    pub original_states: HashMap<i64, bool>,
+    /// Включены на каждом шаге: библиотеки и их зависимости
+    pub always_enabled: Vec<i64>,
+    /// Подозреваемые группы
+    pub pool: Vec<BisectGroup>,
+    /// Найденные виновники
+    pub found: Vec<BisectGroup>,
+    pub phase: BisectPhase,
+    pub steps: Vec<BisectStep>,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+impl BisectSession {
+    fn new(
+        instance_id: &str,
+        original_states: HashMap<i64, bool>,
+        always_enabled: Vec<i64>,
+        pool: Vec<BisectGroup>,
+    ) -> Self {
+        let now = Utc::now().to_rfc3339();
+        Self {
+            id: uuid::Uuid::new_v4().to_string(),
+            instance_id: instance_id.to_string(),
+            original_states,
+            always_enabled,
+            pool,
+            found: Vec::new(),
+            phase: BisectPhase::Baseline,
+            steps: Vec::new(),
+            created_at: now.clone(),
+            updated_at: now,
+        }
+    }
+
+    pub fn is_finished(&self) -> bool {
+        matches!(self.phase, BisectPhase::Finished { .. })
+    }
+
+    /// Группы, включаемые на текущем шаге
+    pub fn active_groups(&self) -> Vec<&BisectGroup> {
+        let pool = match self.phase {
+            BisectPhase::Baseline => &self.pool[..],
+            BisectPhase::VerifyFound => &self.pool[..0],
+            BisectPhase::Search { lo, hi } => &self.pool[..(lo + hi) / 2],
+            BisectPhase::Finished { .. } => return Vec::new(),
+        };
+        self.found.iter().chain(pool).collect()
+    }
+
+    fn active_mod_ids(&self) -> HashSet<i64> {
+        self.always_enabled
+            .iter()
+            .copied()
+            .chain(
+                self.active_groups()
+                    .into_iter()
+                    .flat_map(|g| g.mod_ids.iter().copied()),
+            )
+            .collect()
+    }
+
+    /// Записать результат шага и перейти к следующему
+    fn record(&mut self, outcome: StepOutcome) {
+        if self.is_finished() {
+            return;
+        }
+        self.steps.push(BisectStep {
+            phase: self.phase,
+            enabled_groups: self.active_groups().len(),
+            outcome,
+            finished_at: Utc::now().to_rfc3339(),
+        });
+
+        let crashed = outcome != StepOutcome::Success;
+        let finished = |result| BisectPhase::Finished { result };
+        let phase = self.phase;
+        self.phase = match phase {
+            BisectPhase::Baseline if crashed => BisectPhase::VerifyFound,
+            BisectPhase::Baseline => finished(BisectResult::NotReproduced),
+            BisectPhase::VerifyFound if crashed => finished(if self.found.is_empty() {
+                BisectResult::OutsideCandidates
+            } else {
+                BisectResult::Culprits
+            }),
+            BisectPhase::VerifyFound if self.pool.is_empty() => {
+                finished(BisectResult::Inconsistent)
+            }
+            BisectPhase::VerifyFound => self.narrow(0, self.pool.len()),
+            BisectPhase::Search { lo, hi } => {
+                let mid = (lo + hi) / 2;
+                if crashed {
+                    self.narrow(lo, mid)
+                } else {
+                    self.narrow(mid, hi)
+                }
+            }
+            BisectPhase::Finished { .. } => unreachable!(),
+        };
+    }
+
+    fn narrow(&mut self, lo: usize, hi: usize) -> BisectPhase {
+        if hi - lo > 1 {
+            return BisectPhase::Search { lo, hi };
+        }
+        // pool[..hi] крашится, pool[..hi - 1] — нет: виновник — последняя группа
+        // префикса, а группы после неё для краша не нужны
+        self.pool.truncate(hi);
+        if let Some(culprit) = self.pool.pop() {
+            self.found.push(culprit);
+        }
+        BisectPhase::VerifyFound
+    }
+}
+
+/// Включённый мод — кандидат в группы
+struct CandidateMod {
+    id: i64,
+    slug: String,
+    name: String,
+    library: bool,
+}
+
+pub struct ModBisect;
+
+impl ModBisect {
+    /// Начать сессию для экземпляра
+    pub fn start(instance_id: &str) -> Result<BisectSession> {
+        if Self::load(instance_id)?.is_some_and(|s| !s.is_finished()) {
+            return Err(LauncherError::InvalidConfig(
+                "Crash bisection is already in progress for this instance".to_string(),
+            ));
+        }
+
+        let mods = ModManager::list_mods(instance_id)?;
+        let graph = ModManager::get_dependency_graph(instance_id)?;
+
+        let libraries: HashSet<&str> = graph
+            .nodes
+            .iter()
+            .filter(|n| n.is_library)
+            .map(|n| n.id.as_str())
+            .collect();
+        let mut candidates: Vec<CandidateMod> = mods
+            .iter()
+            .filter(|m| m.enabled)
+            .map(|m| CandidateMod {
+                id: m.id,
+                slug: m.slug.clone(),
+                name: m.name.clone(),
+                library: libraries.contains(m.slug.as_str()),
+            })
+            .collect();
+        candidates.sort_by_key(|m| m.name.to_lowercase());
+
+        let required: Vec<(&str, &str)> = graph
+            .edges
+            .iter()
+            .filter(|e| e.dependency_type == "required")
+            .map(|e| (e.from.as_str(), e.to.as_str()))
+            .collect();
+
+        let (always_enabled, pool) = group_mods(&candidates, &required);
+        if pool.is_empty() {
+            return Err(LauncherError::InvalidConfig(
+                "No enabled non-library mods to bisect".to_string(),
+            ));
+        }
+
+        let original_states = mods.iter().map(|m| (m.id, m.enabled)).collect();
+        let mut session = BisectSession::new(instance_id, original_states, always_enabled, pool);
+        Self::save(&mut session)?;
+
+        log::info!(
+            "Started crash bisection for {}: {} groups, {} always enabled",
+            instance_id,
+            session.pool.len(),
+            session.always_enabled.len()
+        );
+        Ok(session)
+    }
+
+    pub fn load(instance_id: &str) -> Result<Option<BisectSession>> {
+        let conn = get_db_conn()?;
+        let state: Option<String> = conn
+            .query_row(
+                "SELECT state FROM mod_bisect_sessions WHERE instance_id = ?1",
+                [instance_id],
+                |row| row.get(0),
+            )
+            .optional()?;
+        Ok(state.map(|json| serde_json::from_str(&json)).transpose()?)
+    }
+
+    fn save(session: &mut BisectSession) -> Result<()> {
+        session.updated_at = Utc::now().to_rfc3339();
+        let conn = get_db_conn()?;
+        conn.execute(
+            "INSERT OR REPLACE INTO mod_bisect_sessions (instance_id, id, state, created_at, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5)",
+            params![
+                session.instance_id,
+                session.id,
+                serde_json::to_string(session)?,
+                session.created_at,
+                session.updated_at,
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Выполнить следующий шаг: включить группы, запустить экземпляр, дождаться результата
+    pub async fn run_step(app: &AppHandle, instance_id: &str) -> Result<BisectSession> {
+        if !RUNNING_STEPS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .insert(instance_id.to_string())
+        {
+            return Err(LauncherError::InvalidConfig(
+                "A bisection step is already running for this instance".to_string(),
+            ));
+        }
+
+        let result = Self::run_step_inner(app, instance_id).await;
+
+        RUNNING_STEPS
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .remove(instance_id);
+        result
+    }
+
+    async fn run_step_inner(app: &AppHandle, instance_id: &str) -> Result<BisectSession> {
+        let mut session = Self::load(instance_id)?.ok_or_else(|| {
+            LauncherError::NotFound(format!("No crash bisection for instance {}", instance_id))
+        })?;
+        if session.is_finished() {
+            return Ok(session);
+        }
+        if instance_status(instance_id).as_deref() == Some("running") {
+            return Err(LauncherError::InstanceAlreadyRunning);
+        }
+
+        Self::apply(&session, &session.active_mod_ids()).await?;
+        let outcome = Self::launch_and_judge(app, instance_id).await?;
+
+        log::info!(
+            "Bisection step {} for {}: {:?} with {} groups",
+            session.steps.len() + 1,
+            instance_id,
+            outcome,
+            session.active_groups().len()
+        );
+        session.record(outcome);
+        if session.is_finished() {
+            Self::restore(&session).await;
+        }
+        Self::save(&mut session)?;
+
+        let _ = app.emit("mod-bisect-progress", &session);
+        Ok(session)
+    }
+
+    /// Отменить сессию (или закрыть завершённую) с восстановлением модов
+    pub async fn cancel(instance_id: &str) -> Result<()> {
+        if let Some(session) = Self::load(instance_id)? {
+            Self::restore(&session).await;
+        }
+        let conn = get_db_conn()?;
+        conn.execute(
+            "DELETE FROM mod_bisect_sessions WHERE instance_id = ?1",
+            [instance_id],
+        )?;
+        Ok(())
+    }
+
+    /// Включить только `active` из модов, которые были включены до начала
+    async fn apply(session: &BisectSession, active: &HashSet<i64>) -> Result<()> {
+        let (on, off): (Vec<i64>, Vec<i64>) = session
+            .original_states
+            .iter()
+            .filter(|(_, enabled)| **enabled)
+            .map(|(id, _)| *id)
+            .partition(|id| active.contains(id));
+
+        ModManager::bulk_toggle_mods(&session.instance_id, &off, false).await?;
+        let enabled = ModManager::bulk_toggle_mods(&session.instance_id, &on, true).await?;
+        if enabled.len() < on.len() {
+            log::warn!(
+                "Bisection: {} of {} mods could not be enabled (removed meanwhile?)",
+                on.len() - enabled.len(),
+                on.len()
+            );
+        }
+        Ok(())
+    }
+
+    async fn restore(session: &BisectSession) {
+        for enabled in [true, false] {
+            let ids: Vec<i64> = session
+                .original_states
+                .iter()
+                .filter(|(_, state)| **state == enabled)
+                .map(|(id, _)| *id)
+                .collect();
+            if let Err(e) = ModManager::bulk_toggle_mods(&session.instance_id, &ids, enabled).await
+            {
+                log::error!(
+                    "Failed to restore mod states for {}: {}",
+                    session.instance_id,
+                    e
+                );
+            }
+        }
+    }
+
+    async fn launch_and_judge(app: &AppHandle, instance_id: &str) -> Result<StepOutcome> {
+        let instance = get_instance(instance_id.to_string()).await?;
+        let is_client = matches!(instance.instance_type, InstanceType::Client);
+
+        // Краши приходят от LiveCrashMonitor и от монитора процесса
+        let crashed = Arc::new(AtomicBool::new(false));
+        let listener = {
+            let crashed = Arc::clone(&crashed);
+            let id = instance_id.to_string();
+            app.listen("live-crash-event", move |event| {
+                if let Ok(LiveCrashEvent::CrashDetected { instance_id, .. }) =
+                    serde_json::from_str(event.payload())
+                {
+                    if instance_id == id {
+                        crashed.store(true, Ordering::SeqCst);
+                    }
+                }
+            })
+        };
+
+        // Краш пробного запуска — это результат шага: авто-рестарт сервера подменил бы
+        // следующий шаг и накрутил счётчик crash loop
+        ServerWatchdog::suppress_restarts(instance_id, true);
+        let outcome = Self::watch_launch(app, instance_id, is_client, &crashed).await;
+
+        // Отписываемся до остановки: убитый клиент тоже выглядит как краш
+        app.unlisten(listener);
+        if is_client {
+            if let Ok(mut monitor) = get_monitor().lock() {
+                let _ = monitor.stop_monitoring(instance_id);
+            }
+        }
+        Self::stop(app, instance_id, is_client).await;
+        ServerWatchdog::suppress_restarts(instance_id, false);
+        outcome
+    }
+
+    async fn watch_launch(
+        app: &AppHandle,
+        instance_id: &str,
+        is_client: bool,
+        crashed: &AtomicBool,
+    ) -> Result<StepOutcome> {
+        let mut last_seen = Utc::now().timestamp_millis();
+        start_instance(
+            instance_id.to_string(),
+            app.clone(),
+            app.state::<ChildMap>(),
+        )
+        .await?;
+
+        if is_client {
+            let log_path = instance_logs_dir(instance_id).join("latest.log");
+            if let Ok(mut monitor) = get_monitor().lock() {
+                if let Err(e) = monitor.start_monitoring(instance_id, log_path) {
+                    log::warn!("Failed to start live crash monitoring: {}", e);
+                }
+            }
+        }
+
+        let console = get_console(instance_id).await;
+        let started = Instant::now();
+        let mut ready_at: Option<Instant> = None;
+
+        while started.elapsed() < STEP_TIMEOUT {
+            tokio::time::sleep(POLL_INTERVAL).await;
+            if crashed.load(Ordering::SeqCst) {
+                return Ok(StepOutcome::Crash);
+            }
+
+            if ready_at.is_none() {
+                let new_logs = console.read().await.get_logs_since(last_seen);
+                if let Some(last) = new_logs.last() {
+                    last_seen = last.timestamp;
+                }
+                let ready = new_logs.iter().any(|e| {
+                    if is_client {
+                        is_client_ready_line(&e.line)
+                    } else {
+                        is_server_ready_line(&e.line)
+                    }
+                });
+                if ready {
+                    ready_at = Some(Instant::now());
+                }
+            }
+            if ready_at.is_some_and(|t| t.elapsed() >= SETTLE_TIME) {
+                return Ok(StepOutcome::Success);
+            }
+
+            // Процесс завершился сам: до меню — краш, после меню штатно — успех
+            match instance_status(instance_id).as_deref() {
+                Some("running" | "starting") => {}
+                Some("stopped") if ready_at.is_some() => return Ok(StepOutcome::Success),
+                _ => return Ok(StepOutcome::Crash),
+            }
+        }
+
+        Ok(StepOutcome::Timeout)
+    }
+
+    async fn stop(app: &AppHandle, instance_id: &str, is_client: bool) {
+        let deadline = Instant::now() + STOP_TIMEOUT;
+
+        // Запуск ещё идёт — дожидаемся процесса, иначе останавливать нечего
+        while Instant::now() < deadline
+            && instance_status(instance_id).as_deref() == Some("starting")
+        {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+
+        match instance_status(instance_id).as_deref() {
+            Some("running") => {
+                let result = if is_client {
+                    stop_instance(instance_id.to_string(), app.state::<ChildMap>())
+                } else {
+                    stop_and_wait(app, instance_id).await
+                };
+                if let Err(e) = result {
+                    log::warn!("Failed to stop {} after bisection step: {}", instance_id, e);
+                }
+            }
+            // Отложенный авто-рестарт: монитор отменит его, увидев другой статус
+            Some("restarting") => {
+                if let Ok(conn) = get_db_conn() {
+                    let _ = conn.execute(
+                        "UPDATE instances SET status = 'stopped', updated_at = ?1
+                         WHERE id = ?2 AND status = 'restarting'",
+                        params![Utc::now().to_rfc3339(), instance_id],
+                    );
+                }
+            }
+            _ => return,
+        }
+
+        // Следующий шаг не должен упереться в InstanceAlreadyRunning
+        while Instant::now() < deadline
+            && matches!(
+                instance_status(instance_id).as_deref(),
+                Some("running" | "stopping" | "restarting")
+            )
+        {
+            tokio::time::sleep(POLL_INTERVAL).await;
+        }
+    }
+}
+
+fn instance_status(instance_id: &str) -> Option<String> {
+    get_db_conn()
+        .ok()?
+        .query_row(
+            "SELECT status FROM instances WHERE id = ?1",
+            [instance_id],
+            |row| row.get(0),
+        )
+        .ok()
+}
+
+/// Разбить моды на группы. Возвращает моды, включённые всегда (библиотеки и их
+/// зависимости), и группы подозреваемых, связанных обязательными зависимостями.
+fn group_mods(mods: &[CandidateMod], required: &[(&str, &str)]) -> (Vec<i64>, Vec<BisectGroup>) {
+    let index: HashMap<&str, usize> = mods
+        .iter()
+        .enumerate()
+        .map(|(i, m)| (m.slug.as_str(), i))
+        .collect();
+    let edges: Vec<(usize, usize)> = required
+        .iter()
+        .filter_map(|(from, to)| Some((*index.get(from)?, *index.get(to)?)))
+        .filter(|(from, to)| from != to)
+        .collect();
+
+    let mut always: Vec<bool> = mods.iter().map(|m| m.library).collect();
+    loop {
+        let mut changed = false;
+        for &(from, to) in &edges {
+            if always[from] && !always[to] {
+                always[to] = true;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+
+    let mut parent: Vec<usize> = (0..mods.len()).collect();
+    for &(from, to) in &edges {
+        if !always[from] && !always[to] {
+            let (a, b) = (find_root(&mut parent, from), find_root(&mut parent, to));
+            parent[a] = b;
+        }
+    }
+
+    let mut groups: Vec<BisectGroup> = Vec::new();
+    let mut group_of_root: HashMap<usize, usize> = HashMap::new();
+    for (i, m) in mods.iter().enumerate() {
+        if always[i] {
+            continue;
+        }
+        let root = find_root(&mut parent, i);
+        let group = *group_of_root.entry(root).or_insert_with(|| {
+            groups.push(BisectGroup {
+                mod_ids: Vec::new(),
+                names: Vec::new(),
+            });
+            groups.len() - 1
+        });
+        groups[group].mod_ids.push(m.id);
+        groups[group].names.push(m.name.clone());
+    }
+
+    let always_enabled = mods
+        .iter()
+        .zip(&always)
+        .filter(|(_, always)| **always)
+        .map(|(m, _)| m.id)
+        .collect();
+    (always_enabled, groups)
+}
+
+fn find_root(parent: &mut [usize], mut i: usize) -> usize {
+    while parent[i] != i {
+        parent[i] = parent[parent[i]];
+        i = parent[i];
+    }
+    i
+}
+
+// ========== Tauri Commands ==========
+
+/// Начать поиск мода, из-за которого крашится запуск
+#[tauri::command]
+pub async fn start_mod_bisect(instance_id: String) -> Result<BisectSession> {
+    ModBisect::start(&instance_id)
+}
+
+/// Текущая (или завершённая) сессия экземпляра
+#[tauri::command]
+pub async fn get_mod_bisect(instance_id: String) -> Result<Option<BisectSession>> {
+    ModBisect::load(&instance_id)
+}
+
+/// Выполнить следующий шаг; прерванный шаг выполняется заново
+#[tauri::command]
+pub async fn run_mod_bisect_step(
+    instance_id: String,
+    app_handle: AppHandle,
+) -> Result<BisectSession> {
+    ModBisect::run_step(&app_handle, &instance_id).await
+}
+
+/// Отменить или закрыть сессию, вернув исходные состояния модов
+#[tauri::command]
+pub async fn cancel_mod_bisect(instance_id: String) -> Result<()> {
+    ModBisect::cancel(&instance_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candidate(id: i64, slug: &str, library: bool) -> CandidateMod {
+        CandidateMod {
+            id,
+            slug: slug.to_string(),
+            name: slug.to_string(),
+            library,
+        }
+    }
+
+    fn session(groups: &[&str]) -> BisectSession {
+        let pool = groups
+            .iter()
+            .enumerate()
+            .map(|(i, name)| BisectGroup {
+                mod_ids: vec![i as i64],
+                names: vec![name.to_string()],
+            })
+            .collect();
+        BisectSession::new("test", HashMap::new(), Vec::new(), pool)
+    }
+
+    /// Прогнать сессию, считая крашем наличие всех `culprits` среди включённых групп
+    fn run(mut session: BisectSession, culprits: &[&str]) -> BisectSession {
+        while !session.is_finished() {
+            let active: HashSet<&str> = session
+                .active_groups()
+                .iter()
+                .flat_map(|g| g.names.iter().map(String::as_str))
+                .collect();
+            let crash = !culprits.is_empty() && culprits.iter().all(|c| active.contains(c));
+            session.record(if crash {
+                StepOutcome::Crash
+            } else {
+                StepOutcome::Success
+            });
+            assert!(session.steps.len() < 100, "bisection does not converge");
+        }
+        session
+    }
+
+    fn found(session: &BisectSession) -> Vec<&str> {
+        let mut names: Vec<&str> = session
+            .found
+            .iter()
+            .flat_map(|g| g.names.iter().map(String::as_str))
+            .collect();
+        names.sort();
+        names
+    }
+
+    #[test]
+    fn test_group_mods_keeps_dependencies_together() {
+        let mods = [
+            candidate(1, "create", false),
+            candidate(2, "create-addon", false),
+            candidate(3, "geckolib", true),
+            candidate(4, "lib-helper", false),
+            candidate(5, "sodium", false),
+        ];
+        let required = [("create-addon", "create"), ("geckolib", "lib-helper")];
+
+        let (always, groups) = group_mods(&mods, &required);
+        assert_eq!(always, vec![3, 4]);
+        assert_eq!(groups.len(), 2);
+        assert_eq!(groups[0].mod_ids, vec![1, 2]);
+        assert_eq!(groups[1].mod_ids, vec![5]);
+    }
+
+    #[test]
+    fn test_bisect_finds_single_culprit() {
+        let names: Vec<String> = (0..37).map(|i| format!("mod{:02}", i)).collect();
+        let names: Vec<&str> = names.iter().map(String::as_str).collect();
+
+        let done = run(session(&names), &["mod23"]);
+        assert_eq!(
+            done.phase,
+            BisectPhase::Finished {
+                result: BisectResult::Culprits
+            }
+        );
+        assert_eq!(found(&done), vec!["mod23"]);
+        // Базовый запуск, запуск без подозреваемых, ~log2(37) шагов поиска и проверка
+        assert!(done.steps.len() <= 10);
+    }
+
+    #[test]
+    fn test_bisect_finds_conflicting_pair() {
+        let names = ["a", "b", "c", "d", "e", "f", "g", "h"];
+        let done = run(session(&names), &["c", "f"]);
+        assert_eq!(found(&done), vec!["c", "f"]);
+
+        let done = run(session(&names), &[]);
+        assert_eq!(
+            done.phase,
+            BisectPhase::Finished {
+                result: BisectResult::NotReproduced
+            }
+        );
+    }
+}
@@ -0,0 +1,393 @@
+//! Политики обновления модов
+//!
+//! Для каждого мода экземпляра можно задать канал (release/beta/alpha), пин на
+//! точную версию, потолок версии («оставаться ниже 5.x») и временный hold с
+//! причиной и сроком. Политики хранятся в `mod_update_policies` по slug, чтобы
+//! переживать `update_mod` (он пересоздаёт строку `mods`), и экспортируются в
+//! `ModEntry` манифеста `.stzhk`.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use chrono::{DateTime, NaiveDate, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::api::curseforge::CurseForgeFile;
+use crate::api::modrinth::ModrinthVersion;
+use crate::db::get_db_conn;
+use crate::dependency_solver::compare_mod_versions;
+use crate::error::{LauncherError, Result};
+
+/// Канал релизов: канал допускает свои версии и всё стабильнее
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ReleaseChannel {
+    Release,
+    Beta,
+    Alpha,
+}
+
+impl ReleaseChannel {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ReleaseChannel::Release => "release",
+            ReleaseChannel::Beta => "beta",
+            ReleaseChannel::Alpha => "alpha",
+        }
+    }
+
+    pub fn parse(s: &str) -> Option<Self> {
+        match s.to_lowercase().as_str() {
+            "release" => Some(ReleaseChannel::Release),
+            "beta" => Some(ReleaseChannel::Beta),
+            "alpha" => Some(ReleaseChannel::Alpha),
+            _ => None,
+        }
+    }
+
+    /// `version_type` из Modrinth
+    pub fn from_modrinth(version_type: &str) -> Self {
+        Self::parse(version_type).unwrap_or(ReleaseChannel::Alpha)
+    }
+
+    /// `releaseType` из CurseForge: 1=release, 2=beta, 3=alpha
+    pub fn from_curseforge(release_type: u8) -> Self {
+        match release_type {
+            1 => ReleaseChannel::Release,
+            2 => ReleaseChannel::Beta,
+            _ => ReleaseChannel::Alpha,
+        }
+    }
+
+    pub fn allows(&self, channel: ReleaseChannel) -> bool {
+        channel <= *self
+    }
+}
+
+/// Временная заморозка обновлений
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UpdateHold {
+    pub reason: String,
+    /// RFC 3339 или `YYYY-MM-DD`; без срока hold бессрочный
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub until: Option<String>,
+}
+
+impl UpdateHold {
+    fn parse_until(until: &str) -> Option<DateTime<Utc>> {
+        if let Ok(dt) = DateTime::parse_from_rfc3339(until) {
+            return Some(dt.with_timezone(&Utc));
+        }
+        NaiveDate::parse_from_str(until, "%Y-%m-%d")
+            .ok()
+            .and_then(|d| d.and_hms_opt(0, 0, 0))
+            .map(|dt| dt.and_utc())
+    }
+
+    pub fn is_active(&self, now: DateTime<Utc>) -> bool {
+        match self.until.as_deref() {
+            // Нераспознанный срок - считаем hold активным, а не молча снимаем его
+            Some(until) => Self::parse_until(until).map_or(true, |until| now < until),
+            None => true,
+        }
+    }
+}
+
+/// Политика обновления мода
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct ModUpdatePolicy {
+    /// Без канала допускаются любые версии (как раньше)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub channel: Option<ReleaseChannel>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub pinned_version: Option<String>,
+    /// Верхняя граница, не включительно: "5" - оставаться ниже 5.x
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub version_cap: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hold: Option<UpdateHold>,
+}
+
+impl ModUpdatePolicy {
+    pub fn is_default(&self) -> bool {
+        *self == Self::default()
+    }
+
+    pub fn is_held(&self, now: DateTime<Utc>) -> bool {
+        self.hold.as_ref().is_some_and(|h| h.is_active(now))
+    }
+
+    /// Ограничивает ли политика выбор версии (hold не в счёт)
+    pub fn restricts_versions(&self) -> bool {
+        self.channel.is_some() || self.pinned_version.is_some() || self.version_cap.is_some()
+    }
+
+    /// Проходит ли версия по пину, каналу и потолку
+    pub fn allows(&self, version: &str, channel: ReleaseChannel) -> bool {
+        if let Some(pin) = &self.pinned_version {
+            return version == pin;
+        }
+        if let Some(allowed) = self.channel {
+            if !allowed.allows(channel) {
+                return false;
+            }
+        }
+        if let Some(cap) = &self.version_cap {
+            if compare_mod_versions(version, cap) != Ordering::Less {
+                return false;
+            }
+        }
+        true
+    }
+
+    pub fn allows_modrinth(&self, version: &ModrinthVersion) -> bool {
+        self.allows(
+            &version.version_number,
+            ReleaseChannel::from_modrinth(&version.version_type),
+        )
+    }
+
+    /// У CurseForge нет номера версии: пин сравнивается с именем файла, id или
+    /// display name, потолок - с display name
+    pub fn allows_curseforge(&self, file: &CurseForgeFile) -> bool {
+        if let Some(pin) = &self.pinned_version {
+            return *pin == file.file_name
+                || *pin == file.display_name
+                || *pin == file.id.to_string();
+        }
+        self.allows(
+            &file.display_name,
+            ReleaseChannel::from_curseforge(file.release_type),
+        )
+    }
+
+    /// Версия для обновления из списка (новые первыми); `None` - обновлять нечего.
+    /// Пин может вести и на более старую версию.
+    pub fn select_modrinth<'a>(
+        &self,
+        versions: &'a [ModrinthVersion],
+        current_version: &str,
+    ) -> Option<&'a ModrinthVersion> {
+        let candidate = versions.iter().find(|v| self.allows_modrinth(v))?;
+        if candidate.version_number == current_version {
+            return None;
+        }
+        if self.pinned_version.is_none()
+            && compare_mod_versions(&candidate.version_number, current_version) != Ordering::Greater
+        {
+            return None;
+        }
+        Some(candidate)
+    }
+
+    /// То же для файлов CurseForge (новые первыми); текущий файл ищется по имени или id
+    pub fn select_curseforge<'a>(
+        &self,
+        files: &'a [CurseForgeFile],
+        current_version: &str,
+    ) -> Option<&'a CurseForgeFile> {
+        let is_current = |f: &CurseForgeFile| {
+            f.file_name == current_version || f.id.to_string() == current_version
+        };
+        let current_pos = files.iter().position(is_current);
+        let (pos, candidate) = files
+            .iter()
+            .enumerate()
+            .find(|(_, f)| self.allows_curseforge(f))?;
+
+        if is_current(candidate) {
+            return None;
+        }
+        if self.pinned_version.is_none() && current_pos.is_some_and(|current| pos > current) {
+            return None;
+        }
+        Some(candidate)
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        if let Some(until) = self.hold.as_ref().and_then(|h| h.until.as_deref()) {
+            if UpdateHold::parse_until(until).is_none() {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "Invalid hold expiry '{}': expected RFC 3339 or YYYY-MM-DD",
+                    until
+                )));
+            }
+        }
+        if self
+            .version_cap
+            .as_deref()
+            .is_some_and(|c| c.trim().is_empty())
+            || self
+                .pinned_version
+                .as_deref()
+                .is_some_and(|p| p.trim().is_empty())
+        {
+            return Err(LauncherError::InvalidConfig(
+                "Version pin and cap must not be empty".to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+pub struct ModPolicyManager;
+
+impl ModPolicyManager {
+    fn row_to_policy(row: &rusqlite::Row) -> rusqlite::Result<ModUpdatePolicy> {
+        let channel: Option<String> = row.get(0)?;
+        let hold_reason: Option<String> = row.get(3)?;
+        Ok(ModUpdatePolicy {
+            channel: channel.as_deref().and_then(ReleaseChannel::parse),
+            pinned_version: row.get(1)?,
+            version_cap: row.get(2)?,
+            hold: hold_reason.map(|reason| UpdateHold {
+                reason,
+                until: row.get::<_, Option<String>>(4).ok().flatten(),
+            }),
+        })
+    }
+
+    /// Политика мода (по умолчанию - без ограничений)
+    pub fn get(instance_id: &str, slug: &str) -> Result<ModUpdatePolicy> {
+        let conn = get_db_conn()?;
+        let policy = conn
+            .query_row(
+                "SELECT channel, pinned_version, version_cap, hold_reason, hold_until
+                 FROM mod_update_policies WHERE instance_id = ?1 AND slug = ?2",
+                params![instance_id, slug],
+                Self::row_to_policy,
+            )
+            .optional()?;
+        Ok(policy.unwrap_or_default())
+    }
+
+    /// Все политики экземпляра: slug -> политика
+    pub fn list(instance_id: &str) -> Result<HashMap<String, ModUpdatePolicy>> {
+        let conn = get_db_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT channel, pinned_version, version_cap, hold_reason, hold_until, slug
+             FROM mod_update_policies WHERE instance_id = ?1",
+        )?;
+        let rows = stmt.query_map([instance_id], |row| {
+            Ok((row.get::<_, String>(5)?, Self::row_to_policy(row)?))
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Сохранить политику; политика по умолчанию удаляет запись
+    pub fn set(instance_id: &str, slug: &str, policy: &ModUpdatePolicy) -> Result<()> {
+        policy.validate()?;
+        let conn = get_db_conn()?;
+
+        if policy.is_default() {
+            conn.execute(
+                "DELETE FROM mod_update_policies WHERE instance_id = ?1 AND slug = ?2",
+                params![instance_id, slug],
+            )?;
+            return Ok(());
+        }
+
+        conn.execute(
+            "INSERT OR REPLACE INTO mod_update_policies
+                (instance_id, slug, channel, pinned_version, version_cap, hold_reason, hold_until, updated_at)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)",
+            params![
+                instance_id,
+                slug,
+                policy.channel.map(|c| c.as_str()),
+                policy.pinned_version,
+                policy.version_cap,
+                policy.hold.as_ref().map(|h| h.reason.as_str()),
+                policy.hold.as_ref().and_then(|h| h.until.as_deref()),
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+        Ok(())
+    }
+
+    /// Перенести политику при смене slug (например, после обогащения с Modrinth)
+    pub fn rename_slug(
+        conn: &rusqlite::Connection,
+        instance_id: &str,
+        old_slug: &str,
+        new_slug: &str,
+    ) -> Result<()> {
+        conn.execute(
+            "UPDATE OR IGNORE mod_update_policies SET slug = ?3 WHERE instance_id = ?1 AND slug = ?2",
+            params![instance_id, old_slug, new_slug],
+        )?;
+        Ok(())
+    }
+}
+
+// ========== Tauri Commands ==========
+
+/// Политики обновления модов экземпляра (slug -> политика)
+#[tauri::command]
+pub async fn get_mod_update_policies(
+    instance_id: String,
+) -> Result<HashMap<String, ModUpdatePolicy>> {
+    ModPolicyManager::list(&instance_id)
+}
+
+/// Задать политику обновления мода (пустая политика снимает ограничения)
+#[tauri::command]
+pub async fn set_mod_update_policy(
+    instance_id: String,
+    slug: String,
+    policy: ModUpdatePolicy,
+) -> Result<()> {
+    ModPolicyManager::set(&instance_id, &slug, &policy)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_policy_allows() {
+        let any = ModUpdatePolicy::default();
+        assert!(any.allows("6.0.0-alpha.1", ReleaseChannel::Alpha));
+
+        let beta_below_5 = ModUpdatePolicy {
+            channel: Some(ReleaseChannel::Beta),
+            version_cap: Some("5".to_string()),
+            ..Default::default()
+        };
+        assert!(beta_below_5.allows("4.9.2", ReleaseChannel::Release));
+        assert!(beta_below_5.allows("4.10.0-beta.1", ReleaseChannel::Beta));
+        assert!(!beta_below_5.allows("4.10.0-alpha.1", ReleaseChannel::Alpha));
+        assert!(!beta_below_5.allows("5.0.0", ReleaseChannel::Release));
+
+        let pinned = ModUpdatePolicy {
+            pinned_version: Some("1.2.3".to_string()),
+            channel: Some(ReleaseChannel::Release),
+            ..Default::default()
+        };
+        assert!(pinned.allows("1.2.3", ReleaseChannel::Beta));
+        assert!(!pinned.allows("1.2.4", ReleaseChannel::Release));
+    }
+
+    #[test]
+    fn test_hold_expiry() {
+        let now = DateTime::parse_from_rfc3339("2026-10-17T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let hold = |until: Option<&str>| ModUpdatePolicy {
+            hold: Some(UpdateHold {
+                reason: "waiting for a fix".to_string(),
+                until: until.map(String::from),
+            }),
+            ..Default::default()
+        };
+
+        assert!(hold(None).is_held(now));
+        assert!(hold(Some("2026-11-01")).is_held(now));
+        assert!(!hold(Some("2026-10-01")).is_held(now));
+        assert!(!hold(Some("2026-10-17T11:00:00+00:00")).is_held(now));
+        assert!(hold(Some("2026-11-01")).validate().is_ok());
+        assert!(hold(Some("next week")).validate().is_err());
+        assert!(!ModUpdatePolicy::default().is_held(now));
+    }
+}
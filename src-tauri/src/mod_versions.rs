@@ -0,0 +1,660 @@
+//! Хранилище предыдущих версий модов для отката обновлений
+//!
+//! Перед тем как `update_mod` удалит старый jar, он копируется в
+//! `<instance>/.stuzhik/mod_versions/<slug>/` вместе со снимком строки `mods`.
+//! Для каждого мода хранится не больше [`RETAINED_VERSIONS`] версий.
+//!
+//! Обновления, применённые после одного `check_mod_updates`, получают общий
+//! batch id — так можно откатить всю пачку разом. Откаченный мод ставится на
+//! hold: проверка обновлений пропускает версию, с которой откатились.
+
+use std::path::PathBuf;
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+
+use crate::backup::BackupManager;
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::instances::lock::LockManager;
+use crate::paths::{instance_dir, instance_mods_dir};
+use crate::utils::{calculate_sha1, sanitize_filename};
+
+/// Сколько предыдущих jar хранить на один мод
+pub const RETAINED_VERSIONS: usize = 3;
+
+/// Снимок строки `mods` (без служебных полей проверки обновлений)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct ModRowSnapshot {
+    slug: String,
+    mod_id: Option<String>,
+    name: String,
+    version: String,
+    minecraft_version: String,
+    source: String,
+    source_id: Option<String>,
+    project_url: Option<String>,
+    download_url: Option<String>,
+    file_name: String,
+    file_hash: Option<String>,
+    file_size: Option<i64>,
+    enabled: bool,
+    auto_update: bool,
+    description: Option<String>,
+    author: Option<String>,
+    icon_url: Option<String>,
+    categories: Option<String>,
+    installed_at: String,
+    #[serde(default)]
+    dependencies: Vec<DependencySnapshot>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct DependencySnapshot {
+    slug: String,
+    dependency_type: String,
+    version_requirement: Option<String>,
+    name: Option<String>,
+}
+
+/// Сохранённая версия мода
+#[derive(Debug, Clone, Serialize)]
+pub struct StoredModVersion {
+    pub id: i64,
+    pub slug: String,
+    pub version: String,
+    pub file_name: String,
+    /// Версия, на которую мод был обновлён
+    pub replaced_by: Option<String>,
+    pub batch_id: Option<String>,
+    pub stored_at: String,
+}
+
+/// Версии, доступные для отката
+#[derive(Debug, Clone, Serialize)]
+pub struct ModVersionHistory {
+    pub mod_id: i64,
+    pub current_version: String,
+    pub update_hold: Option<String>,
+    pub versions: Vec<StoredModVersion>,
+}
+
+/// Результат отката одного мода
+#[derive(Debug, Clone, Serialize)]
+pub struct ModRollbackResult {
+    pub slug: String,
+    pub from_version: Option<String>,
+    pub to_version: String,
+    pub mod_id: Option<i64>,
+    pub success: bool,
+    pub error: Option<String>,
+}
+
+pub struct ModVersionStore;
+
+impl ModVersionStore {
+    fn store_dir(instance_id: &str, slug: &str) -> PathBuf {
+        instance_dir(instance_id)
+            .join(".stuzhik")
+            .join("mod_versions")
+            .join(sanitize_filename(slug))
+    }
+
+    /// Новый запуск проверки обновлений — новая пачка
+    pub fn start_batch(instance_id: &str) -> Result<String> {
+        let batch_id = uuid::Uuid::new_v4().to_string();
+        let conn = get_db_conn()?;
+        conn.execute(
+            "UPDATE instances SET update_batch_id = ?1 WHERE id = ?2",
+            params![batch_id, instance_id],
+        )?;
+        Ok(batch_id)
+    }
+
+    /// Версии, на которых мод стоит на hold
+    pub fn holds(instance_id: &str) -> Result<std::collections::HashMap<i64, String>> {
+        let conn = get_db_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, update_hold FROM mods WHERE instance_id = ?1 AND update_hold IS NOT NULL",
+        )?;
+        let rows = stmt.query_map([instance_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Версия, с которой мод откатили (hold): обновление на неё не ставится
+    pub fn held_version(mod_id: i64) -> Result<Option<String>> {
+        let conn = get_db_conn()?;
+        let hold: Option<String> = conn
+            .query_row(
+                "SELECT update_hold FROM mods WHERE id = ?1",
+                [mod_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .flatten();
+        Ok(hold)
+    }
+
+    /// Снять hold — мод снова получает все обновления
+    pub fn release_hold(instance_id: &str, mod_id: i64) -> Result<()> {
+        let conn = get_db_conn()?;
+        conn.execute(
+            "UPDATE mods SET update_hold = NULL WHERE id = ?1 AND instance_id = ?2",
+            params![mod_id, instance_id],
+        )?;
+        Ok(())
+    }
+
+    fn load_snapshot(conn: &rusqlite::Connection, mod_id: i64) -> Result<ModRowSnapshot> {
+        let mut snapshot = conn
+            .query_row(
+                "SELECT slug, mod_id, name, version, minecraft_version, source, source_id,
+                        project_url, download_url, file_name, file_hash, file_size, enabled,
+                        auto_update, description, author, icon_url, categories, installed_at
+                 FROM mods WHERE id = ?1",
+                [mod_id],
+                |row| {
+                    Ok(ModRowSnapshot {
+                        slug: row.get(0)?,
+                        mod_id: row.get(1)?,
+                        name: row.get(2)?,
+                        version: row.get(3)?,
+                        minecraft_version: row.get(4)?,
+                        source: row.get(5)?,
+                        source_id: row.get(6)?,
+                        project_url: row.get(7)?,
+                        download_url: row.get(8)?,
+                        file_name: row.get(9)?,
+                        file_hash: row.get(10)?,
+                        file_size: row.get(11)?,
+                        enabled: row.get::<_, i32>(12)? != 0,
+                        auto_update: row.get::<_, i32>(13)? != 0,
+                        description: row.get(14)?,
+                        author: row.get(15)?,
+                        icon_url: row.get(16)?,
+                        categories: row.get(17)?,
+                        installed_at: row.get(18)?,
+                        dependencies: Vec::new(),
+                    })
+                },
+            )
+            .optional()?
+            .ok_or_else(|| LauncherError::ModNotFound(mod_id.to_string()))?;
+
+        let mut stmt = conn.prepare(
+            "SELECT dependency_slug, dependency_type, version_requirement, dependency_name
+             FROM mod_dependencies WHERE mod_id = ?1",
+        )?;
+        let rows = stmt.query_map([mod_id], |row| {
+            Ok(DependencySnapshot {
+                slug: row.get(0)?,
+                dependency_type: row.get(1)?,
+                version_requirement: row.get(2)?,
+                name: row.get(3)?,
+            })
+        })?;
+        snapshot.dependencies = rows.filter_map(|r| r.ok()).collect();
+
+        Ok(snapshot)
+    }
+
+    /// Сохранить текущий jar мода перед заменой
+    ///
+    /// `batch` — брать batch id последней проверки обновлений (для `update_mod`).
+    pub async fn retain(
+        instance_id: &str,
+        mod_id: i64,
+        replaced_by: &str,
+        batch: bool,
+    ) -> Result<()> {
+        if let Some(slug) = Self::store_current(instance_id, mod_id, replaced_by, batch).await? {
+            Self::prune(instance_id, &slug).await?;
+        }
+        Ok(())
+    }
+
+    /// Скопировать jar мода в хранилище без чистки старых версий.
+    /// Возвращает slug, если файл был сохранён.
+    async fn store_current(
+        instance_id: &str,
+        mod_id: i64,
+        replaced_by: &str,
+        batch: bool,
+    ) -> Result<Option<String>> {
+        let (snapshot, batch_id) = {
+            let conn = get_db_conn()?;
+            let snapshot = Self::load_snapshot(&conn, mod_id)?;
+            let batch_id: Option<String> = if batch {
+                conn.query_row(
+                    "SELECT update_batch_id FROM instances WHERE id = ?1",
+                    [instance_id],
+                    |row| row.get(0),
+                )
+                .optional()?
+                .flatten()
+            } else {
+                None
+            };
+            (snapshot, batch_id)
+        };
+
+        let source = instance_mods_dir(instance_id).join(&snapshot.file_name);
+        if !tokio::fs::try_exists(&source).await.unwrap_or(false) {
+            log::warn!(
+                "Cannot retain {} {}: file {} is missing",
+                snapshot.slug,
+                snapshot.version,
+                snapshot.file_name
+            );
+            return Ok(None);
+        }
+
+        let dir = Self::store_dir(instance_id, &snapshot.slug);
+        tokio::fs::create_dir_all(&dir).await?;
+        tokio::fs::copy(&source, dir.join(&snapshot.file_name)).await?;
+
+        let mod_row = serde_json::to_string(&snapshot)?;
+        {
+            let conn = get_db_conn()?;
+            // Тот же файл мог быть сохранён раньше - оставляем одну запись
+            conn.execute(
+                "DELETE FROM mod_version_store WHERE instance_id = ?1 AND slug = ?2 AND file_name = ?3",
+                params![instance_id, snapshot.slug, snapshot.file_name],
+            )?;
+            conn.execute(
+                "INSERT INTO mod_version_store
+                    (instance_id, slug, version, file_name, file_hash, mod_row, replaced_by, batch_id, stored_at)
+                 VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9)",
+                params![
+                    instance_id,
+                    snapshot.slug,
+                    snapshot.version,
+                    snapshot.file_name,
+                    snapshot.file_hash,
+                    mod_row,
+                    replaced_by,
+                    batch_id,
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
+        }
+
+        Ok(Some(snapshot.slug))
+    }
+
+    /// Удалить версии сверх лимита
+    async fn prune(instance_id: &str, slug: &str) -> Result<()> {
+        let stale: Vec<(i64, String)> = {
+            let conn = get_db_conn()?;
+            let mut stmt = conn.prepare(
+                "SELECT id, file_name FROM mod_version_store
+                 WHERE instance_id = ?1 AND slug = ?2
+                 ORDER BY stored_at DESC, id DESC",
+            )?;
+            let rows = stmt.query_map(params![instance_id, slug], |row| {
+                Ok((row.get(0)?, row.get(1)?))
+            })?;
+            rows.filter_map(|r| r.ok())
+                .skip(RETAINED_VERSIONS)
+                .collect()
+        };
+
+        for (id, file_name) in stale {
+            Self::forget(instance_id, slug, id, &file_name).await?;
+        }
+        Ok(())
+    }
+
+    async fn forget(instance_id: &str, slug: &str, id: i64, file_name: &str) -> Result<()> {
+        let path = Self::store_dir(instance_id, slug).join(file_name);
+        if tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            tokio::fs::remove_file(&path).await?;
+        }
+        let conn = get_db_conn()?;
+        conn.execute("DELETE FROM mod_version_store WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    fn list_stored(instance_id: &str, slug: &str) -> Result<Vec<StoredModVersion>> {
+        let conn = get_db_conn()?;
+        let mut stmt = conn.prepare(
+            "SELECT id, slug, version, file_name, replaced_by, batch_id, stored_at
+             FROM mod_version_store WHERE instance_id = ?1 AND slug = ?2
+             ORDER BY stored_at DESC, id DESC",
+        )?;
+        let rows = stmt.query_map(params![instance_id, slug], |row| {
+            Ok(StoredModVersion {
+                id: row.get(0)?,
+                slug: row.get(1)?,
+                version: row.get(2)?,
+                file_name: row.get(3)?,
+                replaced_by: row.get(4)?,
+                batch_id: row.get(5)?,
+                stored_at: row.get(6)?,
+            })
+        })?;
+        Ok(rows.filter_map(|r| r.ok()).collect())
+    }
+
+    /// Сохранённые версии мода
+    pub fn history(instance_id: &str, mod_id: i64) -> Result<ModVersionHistory> {
+        let (slug, current_version, update_hold): (String, String, Option<String>) = {
+            let conn = get_db_conn()?;
+            conn.query_row(
+                "SELECT slug, version, update_hold FROM mods WHERE id = ?1 AND instance_id = ?2",
+                params![mod_id, instance_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?
+            .ok_or_else(|| LauncherError::ModNotFound(mod_id.to_string()))?
+        };
+
+        Ok(ModVersionHistory {
+            mod_id,
+            current_version,
+            update_hold,
+            versions: Self::list_stored(instance_id, &slug)?,
+        })
+    }
+
+    /// Откатить мод на сохранённую версию
+    pub async fn rollback_mod(
+        instance_id: &str,
+        mod_id: i64,
+        to_version: &str,
+    ) -> Result<ModRollbackResult> {
+        BackupManager::ensure_not_running(instance_id).await?;
+
+        let slug: String = {
+            let conn = get_db_conn()?;
+            conn.query_row(
+                "SELECT slug FROM mods WHERE id = ?1 AND instance_id = ?2",
+                params![mod_id, instance_id],
+                |row| row.get(0),
+            )
+            .optional()?
+            .ok_or_else(|| LauncherError::ModNotFound(mod_id.to_string()))?
+        };
+
+        let stored = Self::list_stored(instance_id, &slug)?
+            .into_iter()
+            .find(|v| v.version == to_version)
+            .ok_or_else(|| {
+                LauncherError::NotFound(format!("Stored version {} of {}", to_version, slug))
+            })?;
+
+        Self::restore(instance_id, &stored).await
+    }
+
+    /// Откатить все обновления последней пачки
+    pub async fn rollback_last_batch(instance_id: &str) -> Result<Vec<ModRollbackResult>> {
+        BackupManager::ensure_not_running(instance_id).await?;
+
+        let entries: Vec<StoredModVersion> = {
+            let conn = get_db_conn()?;
+            let batch_id: Option<String> = conn
+                .query_row(
+                    "SELECT batch_id FROM mod_version_store
+                     WHERE instance_id = ?1 AND batch_id IS NOT NULL
+                     ORDER BY stored_at DESC, id DESC LIMIT 1",
+                    [instance_id],
+                    |row| row.get(0),
+                )
+                .optional()?;
+            let Some(batch_id) = batch_id else {
+                return Err(LauncherError::NotFound(
+                    "No mod update batch to roll back".to_string(),
+                ));
+            };
+
+            let mut stmt = conn.prepare(
+                "SELECT id, slug, version, file_name, replaced_by, batch_id, stored_at
+                 FROM mod_version_store WHERE instance_id = ?1 AND batch_id = ?2
+                 ORDER BY id",
+            )?;
+            let rows = stmt.query_map(params![instance_id, batch_id], |row| {
+                Ok(StoredModVersion {
+                    id: row.get(0)?,
+                    slug: row.get(1)?,
+                    version: row.get(2)?,
+                    file_name: row.get(3)?,
+                    replaced_by: row.get(4)?,
+                    batch_id: row.get(5)?,
+                    stored_at: row.get(6)?,
+                })
+            })?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+
+        let mut results = Vec::with_capacity(entries.len());
+        for entry in &entries {
+            match Self::restore(instance_id, entry).await {
+                Ok(result) => results.push(result),
+                Err(e) => {
+                    log::warn!("Failed to roll back {}: {}", entry.slug, e);
+                    results.push(ModRollbackResult {
+                        slug: entry.slug.clone(),
+                        from_version: entry.replaced_by.clone(),
+                        to_version: entry.version.clone(),
+                        mod_id: None,
+                        success: false,
+                        error: Some(e.to_string()),
+                    });
+                }
+            }
+        }
+
+        Ok(results)
+    }
+
+    /// Вернуть сохранённый jar и строку БД; текущая версия уходит в хранилище
+    async fn restore(instance_id: &str, stored: &StoredModVersion) -> Result<ModRollbackResult> {
+        let stored_path = Self::store_dir(instance_id, &stored.slug).join(&stored.file_name);
+        if !tokio::fs::try_exists(&stored_path).await.unwrap_or(false) {
+            return Err(LauncherError::NotFound(format!(
+                "Stored file {} of {}",
+                stored.file_name, stored.slug
+            )));
+        }
+
+        let snapshot: ModRowSnapshot = {
+            let conn = get_db_conn()?;
+            let mod_row: String = conn.query_row(
+                "SELECT mod_row FROM mod_version_store WHERE id = ?1",
+                [stored.id],
+                |row| row.get(0),
+            )?;
+            serde_json::from_str(&mod_row)?
+        };
+
+        if let Some(expected) = snapshot.file_hash.as_ref().filter(|h| h.len() == 40) {
+            let actual = calculate_sha1(&stored_path)?;
+            if !actual.eq_ignore_ascii_case(expected) {
+                return Err(LauncherError::HashMismatch {
+                    expected: expected.clone(),
+                    actual,
+                });
+            }
+        }
+
+        // Читаем jar в память: если имена совпадают, retain текущей версии перезапишет файл
+        let content = tokio::fs::read(&stored_path).await?;
+
+        // Текущая версия (если мод ещё установлен) тоже сохраняется - откат обратим
+        let current: Option<(i64, String, String)> = {
+            let conn = get_db_conn()?;
+            conn.query_row(
+                "SELECT id, version, file_name FROM mods WHERE instance_id = ?1 AND slug = ?2",
+                params![instance_id, stored.slug],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?
+        };
+        if let Some((current_id, current_version, _)) = &current {
+            Self::store_current(instance_id, *current_id, &stored.version, false).await?;
+            log::info!(
+                "Rolling back {} from {} to {}",
+                stored.slug,
+                current_version,
+                stored.version
+            );
+        }
+
+        // Сначала кладём восстановленный jar, текущий убираем только после смены строки в БД
+        let mods_dir = instance_mods_dir(instance_id);
+        tokio::fs::create_dir_all(&mods_dir).await?;
+        let restored_path = mods_dir.join(&snapshot.file_name);
+        let temp_path = mods_dir.join(format!("{}.stuzhik-rollback", snapshot.file_name));
+        tokio::fs::write(&temp_path, &content).await?;
+        if let Err(e) = tokio::fs::rename(&temp_path, &restored_path).await {
+            let _ = tokio::fs::remove_file(&temp_path).await;
+            return Err(e.into());
+        }
+
+        let current_file = current.as_ref().map(|(_, _, file)| file.clone());
+        let from_version = current.as_ref().map(|(_, v, _)| v.clone());
+        let swapped = Self::swap_mod_row(
+            instance_id,
+            current.as_ref().map(|(id, _, _)| *id),
+            &snapshot,
+            from_version.as_deref(),
+        );
+        let new_id = match swapped {
+            Ok(id) => id,
+            Err(e) => {
+                if current_file.as_deref() != Some(snapshot.file_name.as_str()) {
+                    let _ = tokio::fs::remove_file(&restored_path).await;
+                }
+                return Err(e);
+            }
+        };
+
+        if let Some(file) = current_file.filter(|f| *f != snapshot.file_name) {
+            if let Err(e) = tokio::fs::remove_file(mods_dir.join(&file)).await {
+                log::warn!("Failed to remove replaced jar {}: {}", file, e);
+            }
+        }
+
+        // Откат удался - сохранённая копия больше не нужна. При совпадении имён
+        // её место в хранилище уже заняла сохранённая текущая версия.
+        if current.as_ref().map(|(_, _, file)| file) != Some(&stored.file_name) {
+            Self::forget(instance_id, &stored.slug, stored.id, &stored.file_name).await?;
+        }
+        Self::prune(instance_id, &stored.slug).await?;
+
+        LockManager::schedule_write(instance_id);
+
+        Ok(ModRollbackResult {
+            slug: stored.slug.clone(),
+            from_version,
+            to_version: snapshot.version,
+            mod_id: Some(new_id),
+            success: true,
+            error: None,
+        })
+    }
+
+    /// Заменить строку мода снимком в одной транзакции (зависимости удаляются каскадом)
+    fn swap_mod_row(
+        instance_id: &str,
+        current_id: Option<i64>,
+        snapshot: &ModRowSnapshot,
+        from_version: Option<&str>,
+    ) -> Result<i64> {
+        let now = Utc::now().to_rfc3339();
+        let mut conn = get_db_conn()?;
+        let tx = conn.transaction()?;
+
+        if let Some(current_id) = current_id {
+            tx.execute("DELETE FROM mods WHERE id = ?1", [current_id])?;
+        }
+        tx.execute(
+            "INSERT INTO mods (instance_id, slug, mod_id, name, version, minecraft_version,
+                source, source_id, project_url, download_url, file_name, file_hash, file_size,
+                enabled, auto_update, description, author, icon_url, categories,
+                installed_at, updated_at, update_hold)
+             VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22)",
+            params![
+                instance_id,
+                snapshot.slug,
+                snapshot.mod_id,
+                snapshot.name,
+                snapshot.version,
+                snapshot.minecraft_version,
+                snapshot.source,
+                snapshot.source_id,
+                snapshot.project_url,
+                snapshot.download_url,
+                snapshot.file_name,
+                snapshot.file_hash,
+                snapshot.file_size,
+                snapshot.enabled as i32,
+                snapshot.auto_update as i32,
+                snapshot.description,
+                snapshot.author,
+                snapshot.icon_url,
+                snapshot.categories,
+                snapshot.installed_at,
+                now,
+                from_version,
+            ],
+        )?;
+        let new_id = tx.last_insert_rowid();
+
+        for dep in &snapshot.dependencies {
+            tx.execute(
+                "INSERT INTO mod_dependencies (mod_id, dependency_slug, dependency_type, version_requirement, dependency_name)
+                 VALUES (?1, ?2, ?3, ?4, ?5)",
+                params![
+                    new_id,
+                    dep.slug,
+                    dep.dependency_type,
+                    dep.version_requirement,
+                    dep.name
+                ],
+            )?;
+        }
+
+        if let Some(from) = from_version {
+            tx.execute(
+                "INSERT INTO mod_update_history (mod_id, old_version, new_version, updated_at)
+                 VALUES (?1, ?2, ?3, ?4)",
+                params![new_id, from, snapshot.version, now],
+            )?;
+        }
+
+        tx.commit()?;
+        Ok(new_id)
+    }
+}
+
+// ========== Tauri Commands ==========
+
+/// Сохранённые версии мода и его hold
+#[tauri::command]
+pub async fn get_mod_versions(instance_id: String, mod_id: i64) -> Result<ModVersionHistory> {
+    ModVersionStore::history(&instance_id, mod_id)
+}
+
+/// Откатить мод на сохранённую версию
+#[tauri::command]
+pub async fn rollback_mod(
+    instance_id: String,
+    mod_id: i64,
+    to_version: String,
+) -> Result<ModRollbackResult> {
+    ModVersionStore::rollback_mod(&instance_id, mod_id, &to_version).await
+}
+
+/// Откатить последнюю пачку обновлений
+#[tauri::command]
+pub async fn rollback_last_mod_update_batch(instance_id: String) -> Result<Vec<ModRollbackResult>> {
+    ModVersionStore::rollback_last_batch(&instance_id).await
+}
+
+/// Снять hold с откаченного мода
+#[tauri::command]
+pub async fn release_mod_update_hold(instance_id: String, mod_id: i64) -> Result<()> {
+    ModVersionStore::release_hold(&instance_id, mod_id)
+}
@@ -5,6 +5,7 @@
 
 use crate::db::get_db_conn;
 use crate::error::{LauncherError, Result};
+use crate::mod_policy::{ModPolicyManager, ModUpdatePolicy};
 use crate::paths::instances_dir;
 use crate::stzhk::{
     GameRequirements, ModEntry, ModSide, ModSource, ModpackMeta, OptionalMod, OptionalModGroup,
@@ -61,6 +62,8 @@ pub struct ProjectMod {
     pub side: String,
     pub sort_order: i32,
     pub created_at: String,
+    /// Политика обновления (переносится в ModEntry при экспорте)
+    pub update_policy: Option<ModUpdatePolicy>,
 }
 
 /// Группа опциональных модов в проекте
@@ -310,7 +313,8 @@ pub fn get_modpack_project(project_id: String) -> Result<ModpackProjectFull> {
     // Получаем моды
     let mut stmt = conn.prepare(
         "SELECT id, mod_id, slug, name, version, filename, sha256, size, source,
-                source_id, source_version_id, download_url, icon_url, required, side, sort_order, created_at
+                source_id, source_version_id, download_url, icon_url, required, side, sort_order, created_at,
+                update_policy
          FROM modpack_project_mods WHERE project_id = ?1 ORDER BY sort_order, name"
     )?;
 
@@ -334,6 +338,9 @@ pub fn get_modpack_project(project_id: String) -> Result<ModpackProjectFull> {
                 side: row.get(14)?,
                 sort_order: row.get(15)?,
                 created_at: row.get(16)?,
+                update_policy: row
+                    .get::<_, Option<String>>(17)?
+                    .and_then(|json| serde_json::from_str(&json).ok()),
             })
         })?
         .collect::<std::result::Result<Vec<_>, _>>()?;
@@ -612,6 +619,35 @@ pub fn update_project_mod(
     Ok(())
 }
 
+/// Задать политику обновления мода в проекте
+#[tauri::command]
+pub fn set_project_mod_policy(
+    project_id: String,
+    mod_id: String,
+    policy: ModUpdatePolicy,
+) -> Result<()> {
+    policy.validate()?;
+    let json = if policy.is_default() {
+        None
+    } else {
+        Some(serde_json::to_string(&policy)?)
+    };
+
+    let conn = get_db_conn()?;
+    let updated = conn.execute(
+        "UPDATE modpack_project_mods SET update_policy = ?1 WHERE project_id = ?2 AND mod_id = ?3",
+        rusqlite::params![json, project_id, mod_id],
+    )?;
+    if updated == 0 {
+        return Err(LauncherError::NotFound(format!(
+            "Mod {} not found in project {}",
+            mod_id, project_id
+        )));
+    }
+
+    Ok(())
+}
+
 // ========== Optional Groups ==========
 
 /// Создать группу опциональных модов
@@ -775,6 +811,7 @@ pub async fn export_project_to_stzhk(
             required: pm.required,
             side,
             dependencies: vec![],
+            update_policy: pm.update_policy.clone(),
         });
     }
 
@@ -1002,6 +1039,12 @@ pub async fn create_instance_from_project(
                     now,
                 ],
             );
+
+            if let Some(policy) = &pm.update_policy {
+                if let Err(e) = ModPolicyManager::set(&instance_id, &pm.slug, policy) {
+                    log::warn!("Failed to apply update policy for {}: {}", pm.slug, e);
+                }
+            }
         }
     }
 
@@ -1462,7 +1462,6 @@ impl ModpackManager {
                     instance_name,
                     vec![], // No optional mods selected by default
                     &download_manager,
-                    &app_handle,
                 )
                 .await
             }
@@ -1,9 +1,15 @@
-use crate::api::curseforge::CurseForgeClient;
+use crate::api::curseforge::{CurseForgeClient, CurseForgeFile};
 use crate::api::modrinth::ModrinthClient;
 use crate::code_editor::minecraft_data::jar_parser::JarParser;
 use crate::db::get_db_conn;
+use crate::dependency_solver::{
+    compare_mod_versions, DependencySolver, PlanRequest, PlanTarget, TargetVersions,
+};
 use crate::downloader::DownloadManager;
 use crate::error::{LauncherError, Result};
+use crate::instances::lock::LockManager;
+use crate::mod_policy::{ModPolicyManager, ModUpdatePolicy};
+use crate::mod_versions::ModVersionStore;
 use crate::paths::instance_mods_dir;
 use crate::utils::{calculate_sha1, calculate_sha512, sanitize_filename};
 use chrono::Utc;
@@ -541,6 +547,29 @@ impl ModManager {
         loader: &str,
         version_id: Option<&str>,
         download_manager: &DownloadManager,
+    ) -> Result<InstalledMod> {
+        Self::install_from_modrinth_replacing(
+            instance_id,
+            slug,
+            minecraft_version,
+            loader,
+            version_id,
+            None,
+            download_manager,
+        )
+        .await
+    }
+
+    /// Установка с Modrinth на место мода `replaces` (обновление): старые jar и
+    /// строка БД убираются только после успешной загрузки новой версии
+    async fn install_from_modrinth_replacing(
+        instance_id: &str,
+        slug: &str,
+        minecraft_version: &str,
+        loader: &str,
+        version_id: Option<&str>,
+        replaces: Option<i64>,
+        download_manager: &DownloadManager,
     ) -> Result<InstalledMod> {
         // CRITICAL: Check if mod already exists BEFORE starting download
         // Prevents duplicate downloads and race conditions
@@ -548,8 +577,8 @@ impl ModManager {
             let conn = get_db_conn()?;
             let exists: bool = conn
                 .query_row(
-                    "SELECT EXISTS(SELECT 1 FROM mods WHERE instance_id = ?1 AND slug = ?2)",
-                    params![instance_id, slug],
+                    "SELECT EXISTS(SELECT 1 FROM mods WHERE instance_id = ?1 AND slug = ?2 AND id IS NOT ?3)",
+                    params![instance_id, slug, replaces],
                     |row| row.get(0),
                 )
                 .unwrap_or(false);
@@ -558,11 +587,12 @@ impl ModManager {
                 return Err(LauncherError::ModAlreadyInstalled(slug.to_string()));
             }
         }
+        let replaced_file = Self::replaced_file_name(instance_id, replaces)?;
 
-        // Получаем версию: либо конкретную по ID, либо последнюю совместимую
-        let version = if let Some(vid) = version_id {
+        // Кандидаты: либо конкретная версия по ID, либо все совместимые
+        let candidates = if let Some(vid) = version_id {
             // Используем конкретную версию
-            ModrinthClient::get_version(vid)
+            vec![ModrinthClient::get_version(vid)
                 .await
                 .map_err(|e| match e {
                     LauncherError::ApiError(ref msg)
@@ -574,28 +604,53 @@ impl ModManager {
                         ))
                     }
                     _ => e,
-                })?
+                })?]
         } else {
-            // Автоматический выбор последней release версии
-            ModrinthClient::get_latest_version(slug, minecraft_version, loader)
+            ModrinthClient::get_project_versions(slug, Some(minecraft_version), Some(loader))
                 .await
                 .map_err(|e| {
-                    // Если мод не найден или нет совместимой версии, возвращаем более понятную ошибку
+                    // Если мод не найден, возвращаем более понятную ошибку
                     match e {
                         LauncherError::ApiError(ref msg)
                             if msg.contains("404") || msg.contains("not found") =>
                         {
                             LauncherError::ModNotFound(slug.to_string())
                         }
-                        LauncherError::NotFound(_) => LauncherError::NoCompatibleModVersion {
-                            mod_name: slug.to_string(),
-                            mc_version: minecraft_version.to_string(),
-                            loader: loader.to_string(),
-                        },
                         _ => e,
                     }
                 })?
         };
+        if candidates.is_empty() {
+            return Err(LauncherError::NoCompatibleModVersion {
+                mod_name: slug.to_string(),
+                mc_version: minecraft_version.to_string(),
+                loader: loader.to_string(),
+            });
+        }
+
+        // Выбираем новейшую release версию, которая не ломает уже установленные моды
+        let plan = DependencySolver::plan(&PlanRequest {
+            instance_id,
+            minecraft_version,
+            loader,
+            targets: vec![PlanTarget {
+                slug: slug.to_string(),
+                versions: TargetVersions::Modrinth(candidates.clone()),
+                installed_version: None,
+            }],
+            follow_dependencies: false,
+            resolve_missing: false,
+        })
+        .await?;
+        let version = plan
+            .step(slug)
+            .and_then(|step| candidates.iter().find(|v| v.id == step.version_id))
+            .cloned()
+            .ok_or_else(|| LauncherError::NoCompatibleModVersion {
+                mod_name: slug.to_string(),
+                mc_version: minecraft_version.to_string(),
+                loader: loader.to_string(),
+            })?;
 
         // Находим primary файл
         let file = version
@@ -682,41 +737,6 @@ impl ModManager {
             .unwrap_or_else(|_| (slug_fallback.replace('-', " "), None, None, None))
         };
 
-        // Сохраняем в БД with JAR-derived name
-        let conn = get_db_conn()?;
-        conn.execute(
-            r#"INSERT INTO mods (
-                instance_id, slug, mod_id, name, version, minecraft_version,
-                source, source_id, project_url, download_url,
-                file_name, file_hash, file_size, enabled, auto_update,
-                description, author, icon_url, installed_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)"#,
-            params![
-                instance_id,
-                slug,
-                mod_id_from_jar, // mod_id from JAR for dependency matching
-                mod_name,        // Name from JAR, NOT version.name!
-                version.version_number,
-                minecraft_version,
-                "modrinth",
-                version.project_id, // source_id for API matching
-                format!("https://modrinth.com/mod/{}", slug),
-                file.url,
-                file.filename,
-                file.hashes.sha1,
-                file.size as i64,
-                1, // enabled
-                1, // auto_update
-                mod_description, // From JAR
-                mod_author,      // From JAR
-                None::<String>,  // icon_url - will be set during enrichment
-                Utc::now().to_rfc3339(),
-                Utc::now().to_rfc3339(),
-            ],
-        )?;
-
-        let mod_id = conn.last_insert_rowid();
-
         // Collect dependency project_ids for batch lookup
         let dep_project_ids: Vec<String> = version
             .dependencies
@@ -739,32 +759,80 @@ impl ModManager {
             HashMap::new()
         };
 
-        // Save dependencies with resolved names
-        for dep in &version.dependencies {
-            if dep.dependency_type == "required"
-                || dep.dependency_type == "optional"
-                || dep.dependency_type == "incompatible"
-            {
-                if let Some(project_id) = &dep.project_id {
-                    let dep_name = dep_names
-                        .get(project_id)
-                        .cloned()
-                        .unwrap_or_else(|| Self::humanize_mod_id(project_id));
+        // Сохраняем в БД with JAR-derived name; заменяемая строка удаляется в той же транзакции
+        let saved = (|| -> Result<i64> {
+            let mut conn = get_db_conn()?;
+            let tx = conn.transaction()?;
+            if let Some(old_id) = replaces {
+                tx.execute("DELETE FROM mods WHERE id = ?1", params![old_id])?;
+            }
+            tx.execute(
+                r#"INSERT INTO mods (
+                    instance_id, slug, mod_id, name, version, minecraft_version,
+                    source, source_id, project_url, download_url,
+                    file_name, file_hash, file_size, enabled, auto_update,
+                    description, author, icon_url, installed_at, updated_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20)"#,
+                params![
+                    instance_id,
+                    slug,
+                    mod_id_from_jar, // mod_id from JAR for dependency matching
+                    mod_name,        // Name from JAR, NOT version.name!
+                    version.version_number,
+                    minecraft_version,
+                    "modrinth",
+                    version.project_id, // source_id for API matching
+                    format!("https://modrinth.com/mod/{}", slug),
+                    file.url,
+                    file.filename,
+                    file.hashes.sha1,
+                    file.size as i64,
+                    1, // enabled
+                    1, // auto_update
+                    mod_description, // From JAR
+                    mod_author,      // From JAR
+                    None::<String>,  // icon_url - will be set during enrichment
+                    Utc::now().to_rfc3339(),
+                    Utc::now().to_rfc3339(),
+                ],
+            )?;
 
-                    conn.execute(
-                        "INSERT INTO mod_dependencies (mod_id, dependency_slug, dependency_type, version_requirement, dependency_name)
-                         VALUES (?1, ?2, ?3, ?4, ?5)",
-                        params![
-                            mod_id,
-                            project_id,
-                            dep.dependency_type,
-                            None::<String>,
-                            dep_name,
-                        ],
-                    )?;
+            let mod_id = tx.last_insert_rowid();
+
+            // Save dependencies with resolved names
+            for dep in &version.dependencies {
+                if dep.dependency_type == "required"
+                    || dep.dependency_type == "optional"
+                    || dep.dependency_type == "incompatible"
+                {
+                    if let Some(project_id) = &dep.project_id {
+                        let dep_name = dep_names
+                            .get(project_id)
+                            .cloned()
+                            .unwrap_or_else(|| Self::humanize_mod_id(project_id));
+
+                        tx.execute(
+                            "INSERT INTO mod_dependencies (mod_id, dependency_slug, dependency_type, version_requirement, dependency_name)
+                             VALUES (?1, ?2, ?3, ?4, ?5)",
+                            params![
+                                mod_id,
+                                project_id,
+                                dep.dependency_type,
+                                None::<String>,
+                                dep_name,
+                            ],
+                        )?;
+                    }
                 }
             }
-        }
+
+            tx.commit()?;
+            Ok(mod_id)
+        })();
+        let mod_id =
+            Self::finish_replacement(instance_id, saved, replaced_file, &file.filename).await?;
+
+        LockManager::schedule_write(instance_id);
 
         Ok(InstalledMod {
             id: mod_id,
@@ -796,14 +864,36 @@ impl ModManager {
         loader: &str,
         file_id: Option<u64>,
         download_manager: &DownloadManager,
+    ) -> Result<InstalledMod> {
+        Self::install_from_curseforge_replacing(
+            instance_id,
+            mod_id,
+            minecraft_version,
+            loader,
+            file_id,
+            None,
+            download_manager,
+        )
+        .await
+    }
+
+    /// Установка с CurseForge на место мода `replaces` (см. `install_from_modrinth_replacing`)
+    async fn install_from_curseforge_replacing(
+        instance_id: &str,
+        mod_id: u64,
+        minecraft_version: &str,
+        loader: &str,
+        file_id: Option<u64>,
+        replaces: Option<i64>,
+        download_manager: &DownloadManager,
     ) -> Result<InstalledMod> {
         // CRITICAL: Check if mod already exists BEFORE starting download
         {
             let conn = get_db_conn()?;
             let exists: bool = conn
                 .query_row(
-                    "SELECT EXISTS(SELECT 1 FROM mods WHERE instance_id = ?1 AND source = 'curseforge' AND source_id = ?2)",
-                    params![instance_id, mod_id.to_string()],
+                    "SELECT EXISTS(SELECT 1 FROM mods WHERE instance_id = ?1 AND source = 'curseforge' AND source_id = ?2 AND id IS NOT ?3)",
+                    params![instance_id, mod_id.to_string(), replaces],
                     |row| row.get(0),
                 )
                 .unwrap_or(false);
@@ -812,6 +902,7 @@ impl ModManager {
                 return Err(LauncherError::ModAlreadyInstalled(format!("CF-{}", mod_id)));
             }
         }
+        let replaced_file = Self::replaced_file_name(instance_id, replaces)?;
 
         let client = CurseForgeClient::new()?;
 
@@ -905,40 +996,8 @@ impl ModManager {
         let author = mod_info.authors.first().map(|a| a.name.clone());
         let slug = mod_info.slug.clone();
 
-        let conn = get_db_conn()?;
-        conn.execute(
-            r#"INSERT INTO mods (
-                instance_id, slug, name, version, minecraft_version,
-                source, source_id, project_url, download_url,
-                file_name, file_hash, file_size, enabled, auto_update,
-                description, author, icon_url, installed_at, updated_at
-            ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)"#,
-            params![
-                instance_id,
-                slug,
-                mod_info.name,
-                file.file_name,
-                minecraft_version,
-                "curseforge",
-                mod_id.to_string(),
-                format!("https://www.curseforge.com/minecraft/mc-mods/{}", slug),
-                download_url,
-                file.file_name,
-                file_hash,
-                file.file_length as i64,
-                1, // enabled
-                1, // auto_update
-                Some(mod_info.summary),
-                author,
-                icon_url.clone(),
-                Utc::now().to_rfc3339(),
-                Utc::now().to_rfc3339(),
-            ],
-        )?;
-
-        let db_mod_id = conn.last_insert_rowid();
-
-        // Сохраняем зависимости (резолвим ID в имя мода)
+        // Зависимости (резолвим ID в slug) - до транзакции, это сетевые запросы
+        let mut dependencies = Vec::new();
         for dep in &file.dependencies {
             let dep_type = match dep.relation_type {
                 3 => "required",
@@ -955,13 +1014,63 @@ impl ModManager {
                     format!("cf:{}", dep.mod_id)
                 }
             };
+            dependencies.push((dep_slug, dep_type));
+        }
 
-            conn.execute(
-                "INSERT INTO mod_dependencies (mod_id, dependency_slug, dependency_type)
-                 VALUES (?1, ?2, ?3)",
-                params![db_mod_id, dep_slug, dep_type,],
+        // Заменяемая строка удаляется в той же транзакции
+        let saved = (|| -> Result<i64> {
+            let mut conn = get_db_conn()?;
+            let tx = conn.transaction()?;
+            if let Some(old_id) = replaces {
+                tx.execute("DELETE FROM mods WHERE id = ?1", params![old_id])?;
+            }
+            tx.execute(
+                r#"INSERT INTO mods (
+                    instance_id, slug, name, version, minecraft_version,
+                    source, source_id, project_url, download_url,
+                    file_name, file_hash, file_size, enabled, auto_update,
+                    description, author, icon_url, installed_at, updated_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19)"#,
+                params![
+                    instance_id,
+                    slug,
+                    mod_info.name,
+                    file.file_name,
+                    minecraft_version,
+                    "curseforge",
+                    mod_id.to_string(),
+                    format!("https://www.curseforge.com/minecraft/mc-mods/{}", slug),
+                    download_url,
+                    file.file_name,
+                    file_hash,
+                    file.file_length as i64,
+                    1, // enabled
+                    1, // auto_update
+                    mod_info.summary,
+                    author,
+                    icon_url,
+                    Utc::now().to_rfc3339(),
+                    Utc::now().to_rfc3339(),
+                ],
             )?;
-        }
+
+            let db_mod_id = tx.last_insert_rowid();
+
+            for (dep_slug, dep_type) in &dependencies {
+                tx.execute(
+                    "INSERT INTO mod_dependencies (mod_id, dependency_slug, dependency_type)
+                     VALUES (?1, ?2, ?3)",
+                    params![db_mod_id, dep_slug, dep_type,],
+                )?;
+            }
+
+            tx.commit()?;
+            Ok(db_mod_id)
+        })();
+        let db_mod_id =
+            Self::finish_replacement(instance_id, saved, replaced_file, &file.file_name).await?;
+
+        LockManager::schedule_write(instance_id);
 
         Ok(InstalledMod {
             id: db_mod_id,
@@ -985,6 +1094,45 @@ impl ModManager {
         })
     }
 
+    /// Имя jar мода, который заменяет установка (обновление)
+    fn replaced_file_name(instance_id: &str, replaces: Option<i64>) -> Result<Option<String>> {
+        let Some(old_id) = replaces else {
+            return Ok(None);
+        };
+        let conn = get_db_conn()?;
+        let file_name = conn.query_row(
+            "SELECT file_name FROM mods WHERE id = ?1 AND instance_id = ?2",
+            params![old_id, instance_id],
+            |row| row.get(0),
+        )?;
+        Ok(Some(file_name))
+    }
+
+    /// Завершить замену после записи в БД: при успехе убрать старый jar,
+    /// при ошибке - только что скачанный, чтобы в mods не осталось двух версий
+    async fn finish_replacement(
+        instance_id: &str,
+        saved: Result<i64>,
+        replaced_file: Option<String>,
+        new_file: &str,
+    ) -> Result<i64> {
+        let Some(old_file) = replaced_file.filter(|old| old != new_file) else {
+            return saved;
+        };
+        let mods_dir = instance_mods_dir(instance_id);
+        let stale = if saved.is_ok() {
+            old_file
+        } else {
+            new_file.to_string()
+        };
+        if let Err(e) = tokio::fs::remove_file(mods_dir.join(&stale)).await {
+            if e.kind() != std::io::ErrorKind::NotFound {
+                log::warn!("Failed to remove replaced jar {}: {}", stale, e);
+            }
+        }
+        saved
+    }
+
     /// Установка локального мода
     pub async fn install_local(
         instance_id: &str,
@@ -1148,6 +1296,8 @@ impl ModManager {
             );
         }
 
+        LockManager::schedule_write(instance_id);
+
         Ok(InstalledMod {
             id: mod_id,
             instance_id: instance_id.to_string(),
@@ -1340,6 +1490,8 @@ impl ModManager {
                     );
                 }
 
+                LockManager::schedule_write(instance_id);
+
                 Ok(())
             }
             Err(e) => {
@@ -1573,6 +1725,9 @@ impl ModManager {
         // Update mod_id for mods that don't have it (legacy mods before migration)
         Self::update_missing_mod_ids(instance_id).await?;
 
+        // Папка менялась в обход лаунчера — lock должен это отразить
+        LockManager::schedule_write(instance_id);
+
         Ok(SyncResult {
             added,
             removed,
@@ -1686,6 +1841,8 @@ impl ModManager {
             }
         }
 
+        LockManager::schedule_write(instance_id);
+
         Ok(())
     }
 
@@ -1816,6 +1973,8 @@ impl ModManager {
             conn.execute("DELETE FROM mods WHERE id = ?1", params![mod_id])?;
         }
 
+        LockManager::schedule_write(instance_id);
+
         Ok(())
     }
 
@@ -1845,27 +2004,99 @@ impl ModManager {
             })?
         };
 
+        // Политика мода: hold замораживает обновления, пин/канал/потолок сужают выбор
+        let policy = ModPolicyManager::get(instance_id, &slug)?;
+        if policy.is_held(Utc::now()) {
+            log::info!("Updates for {} are on hold, skipping", slug);
+            return Ok(());
+        }
+        // С этой версии уже откатывались - в кандидаты она не попадает,
+        // план выберет следующую подходящую
+        let rolled_back = ModVersionStore::held_version(mod_id)?;
+
         match source.as_str() {
             "modrinth" => {
                 // Проверяем обновления
-                if let Some(new_version) =
+                let latest =
                     ModrinthClient::check_updates(&slug, &current_version, &mc_version, &loader)
-                        .await?
-                {
-                    // Удаляем старый файл
-                    Self::remove_mod(instance_id, mod_id).await?;
+                        .await?;
+                let pinned = policy
+                    .pinned_version
+                    .as_ref()
+                    .filter(|pin| **pin != current_version);
+                if latest.is_some() || pinned.is_some() {
+                    // Кандидаты - все версии новее текущей (или версия пина), разрешённые
+                    // политикой: план выберет новейшую, которая не ломает остальные моды,
+                    // или объяснит, почему такой нет
+                    let versions: Vec<_> = ModrinthClient::get_project_versions(
+                        &slug,
+                        Some(&mc_version),
+                        Some(&loader),
+                    )
+                    .await?
+                    .into_iter()
+                    .filter(|v| policy.allows_modrinth(v))
+                    .filter(|v| rolled_back.as_deref() != Some(v.version_number.as_str()))
+                    .filter(|v| {
+                        pinned.is_some()
+                            || latest.as_ref().is_some_and(|latest| v.id == latest.id)
+                            || compare_mod_versions(&v.version_number, &current_version)
+                                == std::cmp::Ordering::Greater
+                    })
+                    .collect();
+                    if versions.is_empty() {
+                        return Ok(());
+                    }
+
+                    let plan = DependencySolver::plan(&PlanRequest {
+                        instance_id,
+                        minecraft_version: &mc_version,
+                        loader: &loader,
+                        targets: vec![PlanTarget {
+                            slug: slug.clone(),
+                            versions: TargetVersions::Modrinth(versions),
+                            installed_version: Some(current_version.clone()),
+                        }],
+                        follow_dependencies: true,
+                        resolve_missing: false,
+                    })
+                    .await?;
+                    let Some(new_version) = plan.step(&slug).cloned() else {
+                        return Ok(());
+                    };
 
-                    // Устанавливаем новую версию
-                    Self::install_from_modrinth(
+                    // Старый jar уходит в хранилище версий для отката
+                    ModVersionStore::retain(instance_id, mod_id, &new_version.to_version, true)
+                        .await?;
+
+                    // Ставим выбранную версию; старый файл убирается только после успеха
+                    let installed = Self::install_from_modrinth_replacing(
                         instance_id,
                         &slug,
                         &mc_version,
                         &loader,
-                        None,
+                        Some(new_version.version_id.as_str()),
+                        Some(mod_id),
                         download_manager,
                     )
                     .await?;
 
+                    // Новые зависимости выбранной версии
+                    for step in plan.steps.iter().filter(|s| s.slug != slug) {
+                        if let Err(e) = Self::install_from_modrinth(
+                            instance_id,
+                            &step.slug,
+                            &mc_version,
+                            &loader,
+                            Some(step.version_id.as_str()),
+                            download_manager,
+                        )
+                        .await
+                        {
+                            log::warn!("Failed to install dependency {}: {}", step.slug, e);
+                        }
+                    }
+
                     // Сохраняем историю
                     {
                         let conn = get_db_conn()?;
@@ -1873,9 +2104,9 @@ impl ModManager {
                             "INSERT INTO mod_update_history (mod_id, old_version, new_version, updated_at)
                             VALUES (?1, ?2, ?3, ?4)",
                             params![
-                                mod_id,
+                                installed.id,
                                 current_version,
-                                new_version.version_number,
+                                new_version.to_version,
                                 Utc::now().to_rfc3339(),
                             ],
                         )?;
@@ -1890,47 +2121,79 @@ impl ModManager {
 
                     let client = CurseForgeClient::new()?;
 
-                    // Получаем текущий файл
-                    let current_file_id: u64 = current_version.parse().unwrap_or(0);
-
-                    if current_file_id == 0 {
-                        log::warn!("Cannot parse file ID from version: {}", current_version);
+                    // Кандидаты - файлы новее текущего (или пин), разрешённые политикой;
+                    // список CurseForge отсортирован от новых к старым
+                    let files = client
+                        .get_mod_files(cf_mod_id, Some(&mc_version), Some(&loader))
+                        .await?;
+                    let is_current = |f: &CurseForgeFile| {
+                        f.file_name == current_version || f.id.to_string() == current_version
+                    };
+                    let current_pos = files.iter().position(is_current);
+                    let pinned = policy.pinned_version.is_some();
+                    let candidates: Vec<CurseForgeFile> = files
+                        .iter()
+                        .enumerate()
+                        .filter(|(pos, f)| {
+                            !is_current(f)
+                                && rolled_back.as_deref() != Some(f.file_name.as_str())
+                                && policy.allows_curseforge(f)
+                                && (pinned || current_pos.map_or(true, |current| *pos < current))
+                        })
+                        .map(|(_, f)| f.clone())
+                        .collect();
+                    if candidates.is_empty() {
                         return Ok(());
                     }
 
-                    // Проверяем обновления
-                    if let Some(new_file) = client
-                        .check_updates(cf_mod_id, current_file_id, &mc_version, &loader)
-                        .await?
-                    {
-                        // Удаляем старый файл
-                        Self::remove_mod(instance_id, mod_id).await?;
+                    // Тот же план, что и для Modrinth: файл не должен ломать остальные моды
+                    let plan = DependencySolver::plan(&PlanRequest {
+                        instance_id,
+                        minecraft_version: &mc_version,
+                        loader: &loader,
+                        targets: vec![PlanTarget {
+                            slug: slug.clone(),
+                            versions: TargetVersions::CurseForge(candidates.clone()),
+                            installed_version: Some(current_version.clone()),
+                        }],
+                        follow_dependencies: false,
+                        resolve_missing: false,
+                    })
+                    .await?;
+                    let chosen = plan.step(&slug).map(|step| step.version_id.clone());
+                    let Some(new_file) =
+                        candidates.iter().find(|f| Some(f.id.to_string()) == chosen)
+                    else {
+                        return Ok(());
+                    };
 
-                        // Устанавливаем новую версию
-                        Self::install_from_curseforge(
-                            instance_id,
-                            cf_mod_id,
-                            &mc_version,
-                            &loader,
-                            None,
-                            download_manager,
-                        )
-                        .await?;
+                    ModVersionStore::retain(instance_id, mod_id, &new_file.file_name, true).await?;
 
-                        // Сохраняем историю
-                        {
-                            let conn = get_db_conn()?;
-                            conn.execute(
-                                "INSERT INTO mod_update_history (mod_id, old_version, new_version, updated_at)
-                                VALUES (?1, ?2, ?3, ?4)",
-                                params![
-                                    mod_id,
-                                    current_version,
-                                    new_file.file_name,
-                                    Utc::now().to_rfc3339(),
-                                ],
-                            )?;
-                        }
+                    // Ставим новую версию; старый файл убирается только после успеха
+                    let installed = Self::install_from_curseforge_replacing(
+                        instance_id,
+                        cf_mod_id,
+                        &mc_version,
+                        &loader,
+                        Some(new_file.id),
+                        Some(mod_id),
+                        download_manager,
+                    )
+                    .await?;
+
+                    // Сохраняем историю
+                    {
+                        let conn = get_db_conn()?;
+                        conn.execute(
+                            "INSERT INTO mod_update_history (mod_id, old_version, new_version, updated_at)
+                            VALUES (?1, ?2, ?3, ?4)",
+                            params![
+                                installed.id,
+                                current_version,
+                                new_file.file_name,
+                                Utc::now().to_rfc3339(),
+                            ],
+                        )?;
                     }
                 }
             }
@@ -1962,6 +2225,11 @@ impl ModManager {
         let mut mods_with_updates = Vec::new();
         let now = Utc::now().to_rfc3339();
 
+        // Обновления после этой проверки откатываются одной пачкой
+        let batch_id = ModVersionStore::start_batch(instance_id)?;
+        let holds = ModVersionStore::holds(instance_id)?;
+        let policies = ModPolicyManager::list(instance_id)?;
+
         // Build loaders list - include fabric for Sinytra Connector compatibility
         let loader_normalized = loader.to_lowercase();
         let loaders: Vec<String> = match loader_normalized.as_str() {
@@ -1974,6 +2242,7 @@ impl ModManager {
         let mut hash_to_mod: HashMap<String, &InstalledMod> = HashMap::new();
         let mut modrinth_hashes: Vec<String> = Vec::new();
         let mut cf_mod_ids: Vec<(u64, &InstalledMod)> = Vec::new();
+        let mut policy_mods: Vec<(&InstalledMod, &ModUpdatePolicy)> = Vec::new();
 
         // OPTIMIZATION: Batch load all file_hash values in one query
         let file_hashes: HashMap<i64, Option<String>> = {
@@ -1999,6 +2268,22 @@ impl ModManager {
                 continue;
             }
 
+            // Hold из политики - мод не проверяем
+            let policy = policies.get(&mod_item.slug);
+            if policy.is_some_and(|p| p.is_held(Utc::now())) {
+                skipped += 1;
+                continue;
+            }
+
+            // Batch-запрос отдаёт только последнюю версию - моды с пином, каналом
+            // или потолком проверяем отдельно по списку версий
+            if let Some(policy) = policy.filter(|p| p.restricts_versions()) {
+                if matches!(mod_item.source.as_str(), "modrinth" | "curseforge") {
+                    policy_mods.push((mod_item, policy));
+                    continue;
+                }
+            }
+
             // Use pre-loaded file_hash from batch query
             let hash = file_hashes.get(&mod_item.id).and_then(|h| h.clone());
 
@@ -2033,11 +2318,12 @@ impl ModManager {
             }
         }
 
-        let total_checked = modrinth_hashes.len() + cf_mod_ids.len();
+        let total_checked = modrinth_hashes.len() + cf_mod_ids.len() + policy_mods.len();
         log::info!(
-            "Update check: {} Modrinth hashes, {} CurseForge mods",
+            "Update check: {} Modrinth hashes, {} CurseForge mods, {} with update policies",
             modrinth_hashes.len(),
-            cf_mod_ids.len()
+            cf_mod_ids.len(),
+            policy_mods.len()
         );
 
         // STEP 2: Batch request to Modrinth
@@ -2073,8 +2359,13 @@ impl ModManager {
                                 .map(|l| l.to_lowercase())
                                 .collect();
                             let same_loader = version_loaders.contains(&loader_normalized);
+                            let held =
+                                holds.get(&mod_item.id) == Some(&latest_version.version_number);
+                            let allowed = policies
+                                .get(&mod_item.slug)
+                                .map_or(true, |p| p.allows_modrinth(latest_version));
 
-                            if is_update && same_loader {
+                            if is_update && same_loader && !held && allowed {
                                 // Modrinth includes changelog in batch response
                                 let changelog = latest_version.changelog.clone();
 
@@ -2126,6 +2417,11 @@ impl ModManager {
                         .check_updates(cf_mod_id, current_file_id, minecraft_version, &loader_normalized)
                         .await
                     {
+                        Ok(Some(latest_file))
+                            if holds.get(&mod_item.id) == Some(&latest_file.file_name) =>
+                        {
+                            no_updates.push(mod_item.id);
+                        }
                         Ok(Some(latest_file)) => {
                             // Fetch changelog for CurseForge update (separate API call)
                             let changelog = client
@@ -2164,6 +2460,49 @@ impl ModManager {
             }
         }
 
+        // STEP 3b: Mods with a version policy (pin/channel/cap) - one request per mod
+        if !policy_mods.is_empty() {
+            log::info!("Checking {} mods with update policies...", policy_mods.len());
+            let cf_client = CurseForgeClient::new().ok();
+
+            for (mod_item, policy) in policy_mods {
+                let candidate = Self::check_policy_update(
+                    cf_client.as_ref(),
+                    mod_item,
+                    policy,
+                    minecraft_version,
+                    &loader_normalized,
+                )
+                .await;
+
+                match candidate {
+                    Ok(Some((version, version_id, changelog)))
+                        if holds.get(&mod_item.id) != Some(&version) =>
+                    {
+                        updates_available += 1;
+                        mods_with_updates.push(ModUpdateInfo {
+                            mod_id: mod_item.id,
+                            slug: mod_item.slug.clone(),
+                            name: mod_item.name.clone(),
+                            current_version: mod_item.version.clone(),
+                            latest_version: version.clone(),
+                            latest_version_id: version_id.clone(),
+                            source: mod_item.source.clone(),
+                            changelog: changelog.clone(),
+                        });
+                        updates_to_save.push((version, version_id, changelog, mod_item.id));
+                    }
+                    Ok(_) => {
+                        no_updates.push(mod_item.id);
+                    }
+                    Err(e) => {
+                        log::warn!("Policy update check failed for {}: {}", mod_item.slug, e);
+                        errors += 1;
+                    }
+                }
+            }
+        }
+
         // STEP 4: Batch save all updates in single transaction
         if !updates_to_save.is_empty() || !no_updates.is_empty() {
             let conn = get_db_conn()?;
@@ -2205,9 +2544,58 @@ impl ModManager {
             skipped,
             errors,
             mods_with_updates,
+            batch_id,
         })
     }
 
+    /// Версия, до которой политика разрешает обновить мод: (version, version_id, changelog)
+    async fn check_policy_update(
+        cf_client: Option<&CurseForgeClient>,
+        mod_item: &InstalledMod,
+        policy: &ModUpdatePolicy,
+        minecraft_version: &str,
+        loader: &str,
+    ) -> Result<Option<(String, String, Option<String>)>> {
+        if mod_item.source == "modrinth" {
+            let versions = ModrinthClient::get_project_versions(
+                &mod_item.slug,
+                Some(minecraft_version),
+                Some(loader),
+            )
+            .await?;
+            return Ok(policy
+                .select_modrinth(&versions, &mod_item.version)
+                .map(|v| (v.version_number.clone(), v.id.clone(), v.changelog.clone())));
+        }
+
+        let client = cf_client.ok_or_else(|| {
+            LauncherError::InvalidConfig("CurseForge client is unavailable".to_string())
+        })?;
+        let cf_mod_id: u64 = mod_item
+            .source_id
+            .as_deref()
+            .and_then(|id| id.parse().ok())
+            .ok_or_else(|| LauncherError::InvalidConfig("Invalid CurseForge mod ID".to_string()))?;
+
+        let files = client
+            .get_mod_files(cf_mod_id, Some(minecraft_version), Some(loader))
+            .await?;
+        let Some(file) = policy.select_curseforge(&files, &mod_item.version) else {
+            return Ok(None);
+        };
+        let changelog = client
+            .get_file_changelog(cf_mod_id, file.id)
+            .await
+            .ok()
+            .flatten();
+
+        Ok(Some((
+            file.file_name.clone(),
+            file.id.to_string(),
+            changelog,
+        )))
+    }
+
     /// Проверка зависимостей и конфликтов
     pub fn check_dependencies(instance_id: &str) -> Result<Vec<ModConflict>> {
         let conn = get_db_conn()?;
@@ -2925,59 +3313,55 @@ impl ModManager {
     }
 
     /// Автоматическое разрешение зависимостей
+    ///
+    /// Строит один согласованный план для всех недостающих зависимостей с учётом
+    /// диапазонов версий; если решения нет - возвращает DependencyConflict с объяснением.
     pub async fn auto_resolve_dependencies(
         instance_id: &str,
         minecraft_version: &str,
         loader: &str,
         download_manager: &DownloadManager,
     ) -> Result<Vec<InstalledMod>> {
-        let conflicts = Self::check_dependencies(instance_id)?;
+        let plan = DependencySolver::plan(&PlanRequest {
+            instance_id,
+            minecraft_version,
+            loader,
+            targets: Vec::new(),
+            follow_dependencies: true,
+            resolve_missing: true,
+        })
+        .await?;
         let installed_mods = Self::list_mods(instance_id)?;
         let mut installed = Vec::new();
 
-        for conflict in conflicts {
-            if conflict.conflict_type == "missing_dependency" {
-                if let Some(dep_slug) = conflict.required_slug {
-                    // Проверяем, не установлен ли уже мод с таким slug
-                    let already_installed = installed_mods.iter().any(|m| m.slug == dep_slug);
-
-                    if already_installed {
-                        log::info!("Dependency {} is already installed, skipping", dep_slug);
-                        continue;
-                    }
-
-                    // Проверяем, не установили ли мы его уже в этой итерации
-                    let already_in_installed =
-                        installed.iter().any(|m: &InstalledMod| m.slug == dep_slug);
-
-                    if already_in_installed {
-                        log::info!(
-                            "Dependency {} was just installed in this session, skipping",
-                            dep_slug
-                        );
-                        continue;
-                    }
+        for step in &plan.steps {
+            // Выключенный мод с таким slug уже есть - не дублируем
+            if installed_mods.iter().any(|m| m.slug == step.slug) {
+                log::info!("Dependency {} is already installed, skipping", step.slug);
+                continue;
+            }
 
-                    // Пытаемся установить зависимость из Modrinth
-                    log::info!("Installing missing dependency: {}", dep_slug);
-                    match Self::install_from_modrinth(
-                        instance_id,
-                        &dep_slug,
-                        minecraft_version,
-                        loader,
-                        None,
-                        download_manager,
-                    )
-                    .await
-                    {
-                        Ok(mod_item) => {
-                            log::info!("Successfully installed dependency: {}", dep_slug);
-                            installed.push(mod_item);
-                        }
-                        Err(e) => {
-                            log::warn!("Failed to install dependency {}: {}", dep_slug, e);
-                        }
-                    }
+            log::info!(
+                "Installing missing dependency: {} {}",
+                step.slug,
+                step.to_version
+            );
+            match Self::install_from_modrinth(
+                instance_id,
+                &step.slug,
+                minecraft_version,
+                loader,
+                Some(step.version_id.as_str()),
+                download_manager,
+            )
+            .await
+            {
+                Ok(mod_item) => {
+                    log::info!("Successfully installed dependency: {}", step.slug);
+                    installed.push(mod_item);
+                }
+                Err(e) => {
+                    log::warn!("Failed to install dependency {}: {}", step.slug, e);
                 }
             }
         }
@@ -3258,6 +3642,8 @@ impl ModManager {
             });
         }
 
+        LockManager::schedule_write(instance_id);
+
         Ok(results)
     }
 
@@ -4198,6 +4584,10 @@ impl ModManager {
             rows.into_iter().collect()
         };
 
+        // Политики обновления привязаны к slug: при смене slug переносим, пин сверяем с файлом
+        let policies = ModPolicyManager::list(instance_id)?;
+        let old_slugs: HashMap<i64, &str> = mods.iter().map(|m| (m.id, m.slug.as_str())).collect();
+
         conn.execute_batch("BEGIN TRANSACTION")?;
 
         for (sha1, version) in &modrinth_results {
@@ -4311,6 +4701,23 @@ impl ModManager {
                 continue;
             }
 
+            let old_slug = old_slugs.get(&mod_id).copied().unwrap_or(slug.as_str());
+            if old_slug != slug {
+                if let Err(e) = ModPolicyManager::rename_slug(&conn, instance_id, old_slug, &slug) {
+                    log::warn!("Failed to move update policy {} -> {}: {}", old_slug, slug, e);
+                }
+            }
+            let pinned = policies
+                .get(old_slug)
+                .or_else(|| policies.get(&slug))
+                .and_then(|p| p.pinned_version.as_deref());
+            if let Some(pin) = pinned.filter(|pin| *pin != version.version_number) {
+                errors.push(format!(
+                    "{}: pinned to {} but installed file is {}",
+                    title, pin, version.version_number
+                ));
+            }
+
             // Update assigned_slugs to track this mod now owns this slug
             assigned_slugs.insert(slug.clone(), mod_id);
 
@@ -5320,6 +5727,8 @@ pub struct UpdateCheckResult {
     pub skipped: usize,        // local mods without source
     pub errors: usize,
     pub mods_with_updates: Vec<ModUpdateInfo>,
+    /// Updates applied after this check can be rolled back together
+    pub batch_id: String,
 }
 
 /// Info about an available update for a mod
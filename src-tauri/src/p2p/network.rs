@@ -218,7 +218,7 @@ pub async fn diagnose_network(discovery_port: u16, connect_enabled: bool) -> Net
 }
 
 /// Get local IP address (primary, for internet routing)
-fn get_local_ip() -> Option<String> {
+pub(crate) fn get_local_ip() -> Option<String> {
     let socket = UdpSocket::bind("0.0.0.0:0").ok()?;
     socket.connect("8.8.8.8:80").ok()?;
     socket.local_addr().ok().map(|addr| addr.ip().to_string())
@@ -754,6 +754,40 @@ impl ServerSyncManager {
         servers.retain(|k, _| !k.starts_with(peer_id));
     }
 
+    /// Refresh player counts and MOTD of published and discovered servers
+    /// via Server List Ping. Unreachable servers get their player counts cleared.
+    pub async fn refresh_server_status(&self) {
+        for servers in [&self.published_servers, &self.discovered_servers] {
+            let targets: Vec<(String, String)> = servers
+                .read()
+                .await
+                .iter()
+                .map(|(key, server)| (key.clone(), server.server_address.clone()))
+                .collect();
+
+            let pings = targets.into_iter().map(|(key, address)| async move {
+                let status = match crate::server::ping::parse_address(&address) {
+                    Ok((host, port)) => crate::server::ping::ping(&host, port).await.ok(),
+                    Err(_) => None,
+                };
+                (key, status)
+            });
+            let results = futures::future::join_all(pings).await;
+
+            let mut servers = servers.write().await;
+            for (key, status) in results {
+                let Some(server) = servers.get_mut(&key) else {
+                    continue;
+                };
+                server.online_players = status.as_ref().map(|s| s.online_players);
+                server.max_players = status.as_ref().map(|s| s.max_players);
+                if let Some(status) = status {
+                    server.motd = Some(status.motd);
+                }
+            }
+        }
+    }
+
     // ==================== Peer Authorization ====================
 
     /// Authorize a peer to sync with server
@@ -80,6 +80,11 @@ pub fn cache_dir() -> PathBuf {
     get_base_dir().join("cache")
 }
 
+/// Общее content-addressed хранилище бэкапов (дедупликация между экземплярами)
+pub fn backup_store_dir() -> PathBuf {
+    get_base_dir().join("backup_store")
+}
+
 pub fn logs_dir() -> PathBuf {
     get_base_dir().join("logs")
 }
@@ -97,6 +102,10 @@ pub fn instance_mods_dir(instance_id: &str) -> PathBuf {
     instance_dir(instance_id).join("mods")
 }
 
+pub fn instance_plugins_dir(instance_id: &str) -> PathBuf {
+    instance_dir(instance_id).join("plugins")
+}
+
 pub fn instance_config_dir(instance_id: &str) -> PathBuf {
     instance_dir(instance_id).join("config")
 }
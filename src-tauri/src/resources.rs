@@ -7,6 +7,7 @@ use crate::api::modrinth::ModrinthClient;
 use crate::db::get_db_conn;
 use crate::downloader::DownloadManager;
 use crate::error::{LauncherError, Result};
+use crate::instances::lock::LockManager;
 use crate::paths::{
     global_resourcepacks_dir, global_shaderpacks_dir, instance_resourcepacks_dir,
     instance_shaderpacks_dir,
@@ -412,6 +413,10 @@ impl ResourceManager {
             file.filename
         );
 
+        if let (false, Some(instance_id)) = (is_global, instance_id) {
+            LockManager::schedule_write(instance_id);
+        }
+
         Ok(InstalledResource {
             id,
             resource_type,
@@ -545,6 +550,10 @@ impl ResourceManager {
             id
         );
 
+        if let (false, Some(instance_id)) = (is_global, instance_id) {
+            LockManager::schedule_write(instance_id);
+        }
+
         Ok(InstalledResource {
             id,
             resource_type,
@@ -665,6 +674,10 @@ impl ResourceManager {
             enabled
         );
 
+        if let (false, Some(instance_id)) = (is_global, instance_id.as_deref()) {
+            LockManager::schedule_write(instance_id);
+        }
+
         Ok(())
     }
 
@@ -718,6 +731,10 @@ impl ResourceManager {
             file_name
         );
 
+        if let (false, Some(instance_id)) = (is_global, instance_id.as_deref()) {
+            LockManager::schedule_write(instance_id);
+        }
+
         Ok(())
     }
 
@@ -515,6 +515,26 @@ impl SecureVault {
         Self::exists("ely_by_token")
     }
 
+    /// Store Ely.by (Yggdrasil) access token
+    pub fn store_ely_by_access_token(token: &str) -> Result<()> {
+        Self::store("ely_by_access_token", token)
+    }
+
+    /// Get Ely.by (Yggdrasil) access token
+    pub fn get_ely_by_access_token() -> Result<String> {
+        Self::get("ely_by_access_token")
+    }
+
+    /// Delete Ely.by (Yggdrasil) access token
+    pub fn delete_ely_by_access_token() -> Result<()> {
+        Self::delete("ely_by_access_token")
+    }
+
+    /// Check if Ely.by access token exists
+    pub fn has_ely_by_access_token() -> bool {
+        Self::exists("ely_by_access_token")
+    }
+
     /// Store Microsoft access token
     pub fn store_microsoft_access_token(token: &str) -> Result<()> {
         Self::store("microsoft_access_token", token)
@@ -550,6 +570,74 @@ impl SecureVault {
         Self::delete(&format!("rcon_password_{}", instance_id))
     }
 
+    /// Vault key of a backup destination secret (password, key passphrase or S3 secret key)
+    pub fn backup_destination_secret_key(destination_id: &str) -> String {
+        format!("backup_destination_{}", destination_id)
+    }
+
+    /// Store secret of a backup destination
+    pub fn store_backup_destination_secret(destination_id: &str, secret: &str) -> Result<()> {
+        Self::store(&Self::backup_destination_secret_key(destination_id), secret)
+    }
+
+    /// Get secret of a backup destination
+    pub fn get_backup_destination_secret(destination_id: &str) -> Result<String> {
+        Self::get(&Self::backup_destination_secret_key(destination_id))
+    }
+
+    /// Delete secret of a backup destination
+    pub fn delete_backup_destination_secret(destination_id: &str) -> Result<()> {
+        Self::delete(&Self::backup_destination_secret_key(destination_id))
+    }
+
+    /// Store secret part of an admin API token
+    pub fn store_admin_api_token(token_id: &str, secret: &str) -> Result<()> {
+        Self::store(&format!("admin_api_token_{}", token_id), secret)
+    }
+
+    /// Get secret part of an admin API token
+    pub fn get_admin_api_token(token_id: &str) -> Result<String> {
+        Self::get(&format!("admin_api_token_{}", token_id))
+    }
+
+    /// Delete an admin API token
+    pub fn delete_admin_api_token(token_id: &str) -> Result<()> {
+        Self::delete(&format!("admin_api_token_{}", token_id))
+    }
+
+    /// Store access token of a launcher account
+    pub fn store_account_access_token(account_id: &str, token: &str) -> Result<()> {
+        Self::store(&format!("account_{}_access_token", account_id), token)
+    }
+
+    /// Get access token of a launcher account
+    pub fn get_account_access_token(account_id: &str) -> Result<String> {
+        Self::get(&format!("account_{}_access_token", account_id))
+    }
+
+    /// Delete access token of a launcher account
+    pub fn delete_account_access_token(account_id: &str) -> Result<()> {
+        Self::delete(&format!("account_{}_access_token", account_id))
+    }
+
+    /// Store Yggdrasil client token of a launcher account
+    pub fn store_account_client_token(account_id: &str, token: &str) -> Result<()> {
+        Self::store(&format!("account_{}_client_token", account_id), token)
+    }
+
+    /// Get Yggdrasil client token of a launcher account
+    pub fn get_account_client_token(account_id: &str) -> Result<String> {
+        Self::get(&format!("account_{}_client_token", account_id))
+    }
+
+    /// Delete all secrets of a launcher account
+    pub fn delete_account_secrets(account_id: &str) -> Result<()> {
+        Self::delete_batch(&[
+            &format!("account_{}_access_token", account_id),
+            &format!("account_{}_client_token", account_id),
+        ])
+    }
+
     // ========== Batch Operations ==========
 
     /// Store multiple secrets at once
@@ -0,0 +1,173 @@
+//! Admin API: встроенный HTTP + WebSocket сервер для удалённого управления серверами
+//!
+//! Выключен по умолчанию. Даёт те же операции, что и окно лаунчера: запуск/остановка,
+//! консоль (в том числе живой стрим через WebSocket), whitelist/опы/баны,
+//! server.properties и метрики. Доступ — по токенам с правами (`tokens.rs`).
+//!
+//! TLS нет: для доступа из интернета нужен reverse proxy или VPN.
+
+pub mod routes;
+pub mod tokens;
+
+use serde::Serialize;
+use std::net::{IpAddr, SocketAddr};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tauri::AppHandle;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use crate::error::{LauncherError, Result};
+use crate::settings::{AdminApiSettings, SettingsManager};
+
+pub use tokens::{ApiScope, ApiToken, ApiTokenManager, CreatedApiToken};
+
+/// Запущенный сервер API
+struct RunningApi {
+    address: SocketAddr,
+    shutdown: CancellationToken,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+static RUNNING: LazyLock<Mutex<Option<RunningApi>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Состояние API для UI
+#[derive(Debug, Clone, Serialize)]
+pub struct AdminApiStatus {
+    pub running: bool,
+    pub address: Option<String>,
+}
+
+pub struct AdminApi;
+
+impl AdminApi {
+    /// Запустить при старте лаунчера, если включён в настройках
+    pub fn init(app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            match SettingsManager::get_admin_api() {
+                Ok(settings) if settings.enabled => {
+                    if let Err(e) = Self::start(app, &settings).await {
+                        log::error!("Failed to start admin API: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to read admin API settings: {}", e),
+            }
+        });
+    }
+
+    async fn start(app: AppHandle, settings: &AdminApiSettings) -> Result<()> {
+        Self::stop().await;
+
+        let ip: IpAddr = settings.bind_address.parse().map_err(|_| {
+            LauncherError::InvalidConfig(format!("Invalid bind address: {}", settings.bind_address))
+        })?;
+        let listener = tokio::net::TcpListener::bind(SocketAddr::new(ip, settings.port)).await?;
+        let address = listener.local_addr()?;
+
+        let shutdown = CancellationToken::new();
+        let router = routes::router(routes::ApiState {
+            app,
+            shutdown: shutdown.clone(),
+        });
+        let signal = shutdown.clone().cancelled_owned();
+        let task = tauri::async_runtime::spawn(async move {
+            if let Err(e) = axum::serve(listener, router)
+                .with_graceful_shutdown(signal)
+                .await
+            {
+                log::error!("Admin API server failed: {}", e);
+            }
+            log::info!("Admin API on {} stopped", address);
+        });
+
+        if !ip.is_loopback() {
+            log::warn!(
+                "Admin API listens on {} — it is reachable from the network",
+                address
+            );
+        }
+        log::info!("Admin API listening on {}", address);
+        *RUNNING.lock().await = Some(RunningApi {
+            address,
+            shutdown,
+            task,
+        });
+        Ok(())
+    }
+
+    async fn stop() {
+        let Some(running) = RUNNING.lock().await.take() else {
+            return;
+        };
+        running.shutdown.cancel();
+        // Ждём освобождения порта, чтобы сразу занять его снова при смене настроек
+        if tokio::time::timeout(Duration::from_secs(5), running.task)
+            .await
+            .is_err()
+        {
+            log::warn!("Admin API on {} did not stop in time", running.address);
+        }
+    }
+
+    pub async fn status() -> AdminApiStatus {
+        let running = RUNNING.lock().await;
+        AdminApiStatus {
+            running: running.is_some(),
+            address: running.as_ref().map(|r| r.address.to_string()),
+        }
+    }
+
+    /// Сохранить настройки и перезапустить сервер API
+    pub async fn apply_settings(app: AppHandle, settings: AdminApiSettings) -> Result<()> {
+        SettingsManager::set_admin_api(&settings)?;
+        if settings.enabled {
+            Self::start(app, &settings).await
+        } else {
+            Self::stop().await;
+            Ok(())
+        }
+    }
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_admin_api_settings() -> Result<AdminApiSettings> {
+    SettingsManager::get_admin_api()
+}
+
+#[tauri::command]
+pub async fn save_admin_api_settings(
+    app_handle: AppHandle,
+    settings: AdminApiSettings,
+) -> Result<AdminApiStatus> {
+    AdminApi::apply_settings(app_handle, settings).await?;
+    Ok(AdminApi::status().await)
+}
+
+#[tauri::command]
+pub async fn get_admin_api_status() -> AdminApiStatus {
+    AdminApi::status().await
+}
+
+#[tauri::command]
+pub async fn list_admin_api_tokens() -> Result<Vec<ApiToken>> {
+    ApiTokenManager::list()
+}
+
+#[tauri::command]
+pub async fn create_admin_api_token(
+    name: String,
+    scopes: Vec<ApiScope>,
+    instance_ids: Option<Vec<String>>,
+) -> Result<CreatedApiToken> {
+    ApiTokenManager::create(name, scopes, instance_ids)
+}
+
+#[tauri::command]
+pub async fn revoke_admin_api_token(token_id: String) -> Result<()> {
+    ApiTokenManager::revoke(&token_id).await
+}
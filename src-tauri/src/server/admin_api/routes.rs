@@ -0,0 +1,692 @@
+//! HTTP/WebSocket маршруты admin API
+//!
+//! Все маршруты под `/api/v1`, токен — `Authorization: Bearer <token>`.
+//! Браузер не умеет ставить заголовки на WebSocket, поэтому для консоли
+//! токен можно передать в `?token=`.
+
+use axum::extract::ws::{Message, WebSocket, WebSocketUpgrade};
+use axum::extract::{Path, Query, State};
+use axum::http::{header, HeaderMap, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use tauri::{AppHandle, Manager};
+use tokio::sync::broadcast::error::RecvError;
+use tokio_util::sync::CancellationToken;
+
+use super::tokens::{ApiScope, ApiToken, ApiTokenManager};
+use crate::error::LauncherError;
+use crate::instances::lifecycle::{get_instance, list_instances, ChildMap};
+use crate::server::console::{self, get_console, send_server_command};
+use crate::server::{metrics, players, properties};
+use crate::types::InstanceType;
+
+/// Сколько строк истории отдавать при подключении к консоли
+const CONSOLE_HISTORY_LINES: usize = 200;
+
+/// Ключи server.properties, которые токен только с `read` видит скрытыми
+const SECRET_PROPERTIES: &[&str] = &[
+    "rcon.password",
+    "management-server-secret",
+    "management-server-tls-keystore-password",
+];
+const REDACTED: &str = "********";
+
+#[derive(Clone)]
+pub struct ApiState {
+    pub app: AppHandle,
+    /// Отмена при остановке API — закрывает открытые WebSocket
+    pub shutdown: CancellationToken,
+}
+
+pub fn router(state: ApiState) -> Router {
+    let api = Router::new()
+        .route("/servers", get(list_servers))
+        .route("/servers/{id}", get(server_status))
+        .route("/servers/{id}/start", post(start_server))
+        .route("/servers/{id}/stop", post(stop_server))
+        .route("/servers/{id}/logs", get(server_logs))
+        .route("/servers/{id}/console", get(console_socket))
+        .route("/servers/{id}/command", post(send_command))
+        .route("/servers/{id}/metrics", get(server_metrics))
+        .route(
+            "/servers/{id}/properties",
+            get(get_properties).patch(update_properties),
+        )
+        .route("/servers/{id}/players", get(player_lists))
+        .route(
+            "/servers/{id}/whitelist/{name}",
+            post(whitelist_add).delete(whitelist_remove),
+        )
+        .route("/servers/{id}/ops/{name}", post(op_add).delete(op_remove))
+        .route(
+            "/servers/{id}/bans/{name}",
+            post(player_ban).delete(player_unban),
+        )
+        .route("/servers/{id}/ip-bans/{ip}", post(ip_ban).delete(ip_unban))
+        .route("/token", get(current_token).delete(revoke_current_token));
+
+    Router::new().nest("/api/v1", api).with_state(state)
+}
+
+// ============================================================================
+// Errors & auth
+// ============================================================================
+
+pub struct ApiError {
+    status: StatusCode,
+    message: String,
+}
+
+impl ApiError {
+    fn new(status: StatusCode, message: impl Into<String>) -> Self {
+        Self {
+            status,
+            message: message.into(),
+        }
+    }
+
+    fn bad_request(message: impl Into<String>) -> Self {
+        Self::new(StatusCode::BAD_REQUEST, message)
+    }
+}
+
+impl IntoResponse for ApiError {
+    fn into_response(self) -> Response {
+        (
+            self.status,
+            Json(serde_json::json!({ "error": self.message })),
+        )
+            .into_response()
+    }
+}
+
+impl From<LauncherError> for ApiError {
+    fn from(e: LauncherError) -> Self {
+        let status = match e {
+            LauncherError::NotFound(_) | LauncherError::InstanceNotFound(_) => {
+                StatusCode::NOT_FOUND
+            }
+            LauncherError::InstanceAlreadyRunning | LauncherError::InstanceNotRunning => {
+                StatusCode::CONFLICT
+            }
+            LauncherError::InvalidConfig(_) => StatusCode::BAD_REQUEST,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        Self::new(status, e.to_string())
+    }
+}
+
+type ApiResult<T> = std::result::Result<T, ApiError>;
+
+#[derive(Debug, Default, Deserialize)]
+struct TokenQuery {
+    token: Option<String>,
+}
+
+/// Токен запроса: заголовок или `?token=`
+fn request_token<'a>(headers: &'a HeaderMap, query_token: Option<&'a str>) -> Option<&'a str> {
+    headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "))
+        .or(query_token)
+}
+
+/// Найти и проверить токен запроса
+async fn authenticate(headers: &HeaderMap, query_token: Option<&str>) -> ApiResult<ApiToken> {
+    let Some(token) = request_token(headers, query_token) else {
+        return Err(ApiError::new(StatusCode::UNAUTHORIZED, "Missing API token"));
+    };
+
+    ApiTokenManager::verify(token.trim())
+        .await
+        .ok_or_else(|| ApiError::new(StatusCode::UNAUTHORIZED, "Invalid API token"))
+}
+
+/// Проверить токен, право и доступ к серверу. Возвращает токен для дальнейших проверок.
+async fn authorize(
+    headers: &HeaderMap,
+    query_token: Option<&str>,
+    scope: ApiScope,
+    instance_id: &str,
+) -> ApiResult<ApiToken> {
+    let token = authenticate(headers, query_token).await?;
+    if !token.has_scope(scope) {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            format!("Token lacks '{}' scope", scope.as_str()),
+        ));
+    }
+    // Чужие серверы неотличимы от несуществующих
+    if !token.allows_instance(instance_id) {
+        return Err(ApiError::new(StatusCode::NOT_FOUND, "Server not found"));
+    }
+    ensure_server(instance_id).await?;
+    Ok(token)
+}
+
+/// id пришёл из URL и дальше склеивается с путями — проверяем, что это наш сервер
+async fn ensure_server(instance_id: &str) -> ApiResult<()> {
+    match get_instance(instance_id.to_string()).await {
+        Ok(instance) if matches!(instance.instance_type, InstanceType::Server) => Ok(()),
+        _ => Err(ApiError::new(StatusCode::NOT_FOUND, "Server not found")),
+    }
+}
+
+// ============================================================================
+// Servers
+// ============================================================================
+
+#[derive(Debug, Serialize)]
+struct ServerSummary {
+    id: String,
+    name: String,
+    version: String,
+    loader: String,
+    status: String,
+    running: bool,
+}
+
+async fn list_servers(headers: HeaderMap) -> ApiResult<Json<Vec<ServerSummary>>> {
+    let token = authenticate(&headers, None).await?;
+    if !token.has_scope(ApiScope::Read) {
+        return Err(ApiError::new(
+            StatusCode::FORBIDDEN,
+            "Token lacks 'read' scope",
+        ));
+    }
+
+    let mut servers = Vec::new();
+    for instance in list_instances().await? {
+        if !matches!(instance.instance_type, InstanceType::Server)
+            || !token.allows_instance(&instance.id)
+        {
+            continue;
+        }
+        let running = get_console(&instance.id).await.read().await.is_running();
+        servers.push(ServerSummary {
+            running,
+            status: instance.status.as_str().to_string(),
+            loader: instance.loader.as_str().to_string(),
+            version: instance.version,
+            name: instance.name,
+            id: instance.id,
+        });
+    }
+
+    Ok(Json(servers))
+}
+
+async fn server_status(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<console::ServerStatus>> {
+    authorize(&headers, None, ApiScope::Read, &id).await?;
+    let status = console::get_server_status(id)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(Json(status))
+}
+
+async fn start_server(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Control, &id).await?;
+    crate::instances::execution::start_instance(
+        id,
+        state.app.clone(),
+        state.app.state::<ChildMap>(),
+    )
+    .await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct StopQuery {
+    #[serde(default)]
+    force: bool,
+}
+
+async fn stop_server(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<StopQuery>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Control, &id).await?;
+    if query.force {
+        crate::instances::execution::force_kill_server(
+            id,
+            state.app.state::<ChildMap>(),
+            state.app.clone(),
+        )?;
+    } else {
+        crate::instances::execution::graceful_stop_server(id, state.app.clone())
+            .await
+            .map_err(|e| ApiError::new(StatusCode::CONFLICT, e))?;
+    }
+    Ok(StatusCode::ACCEPTED)
+}
+
+// ============================================================================
+// Console
+// ============================================================================
+
+#[derive(Debug, Default, Deserialize)]
+struct LogsQuery {
+    since: Option<i64>,
+    limit: Option<usize>,
+}
+
+async fn server_logs(
+    Path(id): Path<String>,
+    Query(query): Query<LogsQuery>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Vec<console::ServerLogEntry>>> {
+    authorize(&headers, None, ApiScope::Read, &id).await?;
+    let logs = console::get_server_logs(id, query.since, query.limit)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(Json(logs))
+}
+
+#[derive(Debug, Deserialize)]
+struct CommandBody {
+    command: String,
+}
+
+async fn send_command(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(body): Json<CommandBody>,
+) -> ApiResult<Json<console::CommandResult>> {
+    authorize(&headers, None, ApiScope::Console, &id).await?;
+    let result = send_server_command(id, body.command)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(Json(result))
+}
+
+/// Сообщения сервера в WebSocket консоли
+#[derive(Debug, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum ConsoleEvent {
+    History { logs: Vec<console::ServerLogEntry> },
+    Log { entry: console::ServerLogEntry },
+    CommandResult { result: console::CommandResult },
+    Error { message: String },
+}
+
+async fn console_socket(
+    State(state): State<ApiState>,
+    Path(id): Path<String>,
+    Query(query): Query<TokenQuery>,
+    headers: HeaderMap,
+    ws: WebSocketUpgrade,
+) -> ApiResult<Response> {
+    authorize(&headers, query.token.as_deref(), ApiScope::Read, &id).await?;
+    let raw_token = request_token(&headers, query.token.as_deref())
+        .unwrap_or_default()
+        .trim()
+        .to_string();
+    Ok(ws.on_upgrade(move |socket| run_console(socket, id, raw_token, state.shutdown)))
+}
+
+/// Проверить токен сокета перед командой: его могли отозвать или урезать после подключения.
+/// Err — токен больше не действует, сокет надо закрыть.
+async fn console_permission(raw_token: &str, instance_id: &str) -> Result<bool, ()> {
+    match ApiTokenManager::verify(raw_token).await {
+        Some(token) if token.allows_instance(instance_id) && token.has_scope(ApiScope::Read) => {
+            Ok(token.has_scope(ApiScope::Console))
+        }
+        _ => Err(()),
+    }
+}
+
+/// Стримить консоль в сокет; входящие текстовые сообщения — команды серверу
+async fn run_console(
+    mut socket: WebSocket,
+    instance_id: String,
+    raw_token: String,
+    shutdown: CancellationToken,
+) {
+    // Подписываемся до снятия истории, чтобы не потерять строки между ними
+    let mut logs = console::subscribe_logs();
+    let history = get_console(&instance_id)
+        .await
+        .read()
+        .await
+        .get_recent_logs(CONSOLE_HISTORY_LINES);
+    // Строки, попавшие и в историю, и в подписку, не дублируем. Метка времени
+    // в миллисекундах, поэтому строки с последней меткой истории сверяем по тексту.
+    let last_seen = history.last().map(|e| e.timestamp).unwrap_or(0);
+    let mut seen_at_last: Vec<String> = history
+        .iter()
+        .filter(|e| e.timestamp == last_seen)
+        .map(|e| e.line.clone())
+        .collect();
+    if send_event(&mut socket, &ConsoleEvent::History { logs: history })
+        .await
+        .is_err()
+    {
+        return;
+    }
+
+    loop {
+        tokio::select! {
+            _ = shutdown.cancelled() => break,
+            received = logs.recv() => match received {
+                Ok((id, entry)) if id == instance_id && entry.timestamp >= last_seen => {
+                    if entry.timestamp == last_seen {
+                        if let Some(pos) = seen_at_last.iter().position(|l| *l == entry.line) {
+                            seen_at_last.drain(..=pos);
+                            continue;
+                        }
+                    }
+                    if send_event(&mut socket, &ConsoleEvent::Log { entry }).await.is_err() {
+                        break;
+                    }
+                }
+                Ok(_) => {}
+                Err(RecvError::Lagged(skipped)) => {
+                    log::debug!("Admin API console for {} skipped {} lines", instance_id, skipped);
+                }
+                Err(RecvError::Closed) => break,
+            },
+            incoming = socket.recv() => {
+                let text = match incoming {
+                    Some(Ok(Message::Text(text))) => text.as_str().to_string(),
+                    Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                    Some(Ok(_)) => continue,
+                };
+                let event = match console_permission(&raw_token, &instance_id).await {
+                    Ok(true) => {
+                        match send_server_command(instance_id.clone(), parse_console_input(&text)).await {
+                            Ok(result) => ConsoleEvent::CommandResult { result },
+                            Err(message) => ConsoleEvent::Error { message },
+                        }
+                    }
+                    Ok(false) => ConsoleEvent::Error {
+                        message: "Token lacks 'console' scope".to_string(),
+                    },
+                    Err(()) => {
+                        let _ = send_event(&mut socket, &ConsoleEvent::Error {
+                            message: "API token is no longer valid".to_string(),
+                        })
+                        .await;
+                        break;
+                    }
+                };
+                if send_event(&mut socket, &event).await.is_err() {
+                    break;
+                }
+            }
+        }
+    }
+}
+
+/// Команда как есть или `{"command": "..."}`
+fn parse_console_input(text: &str) -> String {
+    serde_json::from_str::<CommandBody>(text)
+        .map(|body| body.command)
+        .unwrap_or_else(|_| text.trim().to_string())
+}
+
+async fn send_event(socket: &mut WebSocket, event: &ConsoleEvent) -> Result<(), axum::Error> {
+    let json = serde_json::to_string(event).unwrap_or_default();
+    socket.send(Message::Text(json.into())).await
+}
+
+// ============================================================================
+// Metrics & properties
+// ============================================================================
+
+async fn server_metrics(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<Option<metrics::ServerMetrics>>> {
+    authorize(&headers, None, ApiScope::Read, &id).await?;
+    let metrics = metrics::get_server_metrics(id)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(Json(metrics))
+}
+
+async fn get_properties(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<HashMap<String, serde_json::Value>>> {
+    let token = authorize(&headers, None, ApiScope::Read, &id).await?;
+    let mut props = properties::get_server_properties(id)
+        .await
+        .map_err(ApiError::bad_request)?;
+    if !token.has_scope(ApiScope::Properties) && !token.has_scope(ApiScope::Console) {
+        redact_secrets(&mut props);
+    }
+    Ok(Json(props))
+}
+
+/// Скрыть пароли и секреты в server.properties
+fn redact_secrets(props: &mut HashMap<String, serde_json::Value>) {
+    for key in SECRET_PROPERTIES {
+        if let Some(value) = props.get_mut(*key) {
+            if !value.as_str().is_some_and(str::is_empty) {
+                *value = serde_json::Value::String(REDACTED.to_string());
+            }
+        }
+    }
+}
+
+/// Частичное обновление: меняются только переданные ключи
+async fn update_properties(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+    Json(changes): Json<HashMap<String, serde_json::Value>>,
+) -> ApiResult<Json<HashMap<String, serde_json::Value>>> {
+    authorize(&headers, None, ApiScope::Properties, &id).await?;
+    properties::save_server_properties(id.clone(), changes)
+        .await
+        .map_err(ApiError::bad_request)?;
+    let props = properties::get_server_properties(id)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(Json(props))
+}
+
+// ============================================================================
+// Players
+// ============================================================================
+
+async fn player_lists(
+    Path(id): Path<String>,
+    headers: HeaderMap,
+) -> ApiResult<Json<players::PlayerManagement>> {
+    authorize(&headers, None, ApiScope::Read, &id).await?;
+    let lists = players::get_player_management(id)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(Json(lists))
+}
+
+async fn whitelist_add(
+    Path((id, name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Players, &id).await?;
+    players::whitelist_add(id, name)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn whitelist_remove(
+    Path((id, name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Players, &id).await?;
+    players::whitelist_remove(id, name)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Deserialize)]
+struct OpBody {
+    #[serde(default = "default_op_level")]
+    level: u8,
+}
+
+fn default_op_level() -> u8 {
+    4
+}
+
+async fn op_add(
+    Path((id, name)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Option<Json<OpBody>>,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Players, &id).await?;
+    let level = body.map(|Json(b)| b.level).unwrap_or_else(default_op_level);
+    if !(1..=4).contains(&level) {
+        return Err(ApiError::bad_request("Op level must be within 1..4"));
+    }
+    players::op_add(id, name, level)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn op_remove(
+    Path((id, name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Players, &id).await?;
+    players::op_remove(id, name)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct BanBody {
+    #[serde(default)]
+    reason: Option<String>,
+}
+
+async fn player_ban(
+    Path((id, name)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Option<Json<BanBody>>,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Players, &id).await?;
+    let reason = ban_reason(body);
+    players::player_ban(id, name, reason)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn player_unban(
+    Path((id, name)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Players, &id).await?;
+    players::player_unban(id, name)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn ip_ban(
+    Path((id, ip)): Path<(String, String)>,
+    headers: HeaderMap,
+    body: Option<Json<BanBody>>,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Players, &id).await?;
+    if ip.parse::<std::net::IpAddr>().is_err() {
+        return Err(ApiError::bad_request(format!("Invalid IP address: {}", ip)));
+    }
+    let reason = ban_reason(body);
+    players::ip_ban(id, ip, reason)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+async fn ip_unban(
+    Path((id, ip)): Path<(String, String)>,
+    headers: HeaderMap,
+) -> ApiResult<StatusCode> {
+    authorize(&headers, None, ApiScope::Players, &id).await?;
+    players::ip_unban(id, ip)
+        .await
+        .map_err(ApiError::bad_request)?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+fn ban_reason(body: Option<Json<BanBody>>) -> String {
+    body.and_then(|Json(b)| b.reason)
+        .filter(|r| !r.trim().is_empty())
+        .unwrap_or_else(|| "Banned by an operator.".to_string())
+}
+
+// ============================================================================
+// Token self-service
+// ============================================================================
+
+async fn current_token(headers: HeaderMap) -> ApiResult<Json<ApiToken>> {
+    Ok(Json(authenticate(&headers, None).await?))
+}
+
+/// Отозвать токен, которым подписан запрос
+async fn revoke_current_token(headers: HeaderMap) -> ApiResult<StatusCode> {
+    let token = authenticate(&headers, None).await?;
+    ApiTokenManager::revoke(&token.id).await?;
+    Ok(StatusCode::NO_CONTENT)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_console_input() {
+        assert_eq!(parse_console_input("say hi "), "say hi");
+        assert_eq!(parse_console_input(r#"{"command":"list"}"#), "list");
+        // Невалидный JSON уходит как есть
+        assert_eq!(
+            parse_console_input(r#"{"cmd":"list"}"#),
+            r#"{"cmd":"list"}"#
+        );
+    }
+
+    #[test]
+    fn test_redact_secrets() {
+        let mut props = HashMap::from([
+            ("rcon.password".to_string(), serde_json::json!("hunter2")),
+            (
+                "management-server-secret".to_string(),
+                serde_json::json!(""),
+            ),
+            ("motd".to_string(), serde_json::json!("Hello")),
+        ]);
+        redact_secrets(&mut props);
+        assert_eq!(props["rcon.password"], REDACTED);
+        // Пустое значение не маскируем — видно, что пароль не задан
+        assert_eq!(props["management-server-secret"], "");
+        assert_eq!(props["motd"], "Hello");
+    }
+
+    #[test]
+    fn test_error_status_mapping() {
+        let err: ApiError = LauncherError::InstanceAlreadyRunning.into();
+        assert_eq!(err.status, StatusCode::CONFLICT);
+        let err: ApiError = LauncherError::NotFound("x".to_string()).into();
+        assert_eq!(err.status, StatusCode::NOT_FOUND);
+    }
+}
@@ -0,0 +1,319 @@
+//! Токены admin API
+//!
+//! Токен выглядит как `stz_<id>.<secret>`. Метаданные (имя, права, серверы) лежат в БД,
+//! секрет — только в `SecureVault`.
+
+use chrono::Utc;
+use rand::RngCore;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::LazyLock;
+use std::time::{Duration, Instant};
+use tokio::sync::RwLock;
+
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::secrets::SecureVault;
+
+const TOKEN_PREFIX: &str = "stz_";
+
+/// Как часто обновлять last_used_at в БД
+const LAST_USED_RESOLUTION: Duration = Duration::from_secs(60);
+
+/// Права токена
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ApiScope {
+    /// Статус, логи, метрики, списки игроков, server.properties
+    Read,
+    /// Команды в консоль
+    Console,
+    /// Запуск и остановка
+    Control,
+    /// Whitelist, опы, баны
+    Players,
+    /// Изменение server.properties
+    Properties,
+}
+
+impl ApiScope {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiScope::Read => "read",
+            ApiScope::Console => "console",
+            ApiScope::Control => "control",
+            ApiScope::Players => "players",
+            ApiScope::Properties => "properties",
+        }
+    }
+}
+
+/// Токен без секрета
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApiToken {
+    pub id: String,
+    pub name: String,
+    pub scopes: Vec<ApiScope>,
+    /// None — доступ ко всем серверам
+    pub instance_ids: Option<Vec<String>>,
+    pub created_at: String,
+    pub last_used_at: Option<String>,
+}
+
+impl ApiToken {
+    pub fn has_scope(&self, scope: ApiScope) -> bool {
+        self.scopes.contains(&scope)
+    }
+
+    pub fn allows_instance(&self, instance_id: &str) -> bool {
+        self.instance_ids
+            .as_ref()
+            .is_none_or(|ids| ids.iter().any(|id| id == instance_id))
+    }
+}
+
+/// Только что созданный токен. `token` показывается пользователю один раз.
+#[derive(Debug, Clone, Serialize)]
+pub struct CreatedApiToken {
+    pub token: String,
+    pub info: ApiToken,
+}
+
+/// Проверенные секреты, чтобы не ходить в keychain на каждый запрос
+struct CachedSecret {
+    secret: String,
+    last_used_saved: Instant,
+}
+
+static SECRET_CACHE: LazyLock<RwLock<HashMap<String, CachedSecret>>> =
+    LazyLock::new(|| RwLock::new(HashMap::new()));
+
+pub struct ApiTokenManager;
+
+impl ApiTokenManager {
+    /// Создать таблицу токенов если не существует
+    fn ensure_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            r#"CREATE TABLE IF NOT EXISTS admin_api_tokens (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                scopes_json TEXT NOT NULL,
+                instance_ids_json TEXT,
+                created_at TEXT NOT NULL,
+                last_used_at TEXT
+            )"#,
+            [],
+        )?;
+        Ok(())
+    }
+
+    pub fn list() -> Result<Vec<ApiToken>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, name, scopes_json, instance_ids_json, created_at, last_used_at
+               FROM admin_api_tokens ORDER BY created_at"#,
+        )?;
+        let tokens = stmt
+            .query_map([], row_to_token)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(tokens)
+    }
+
+    fn get(id: &str) -> Result<Option<ApiToken>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        Ok(conn
+            .query_row(
+                r#"SELECT id, name, scopes_json, instance_ids_json, created_at, last_used_at
+                   FROM admin_api_tokens WHERE id = ?1"#,
+                [id],
+                row_to_token,
+            )
+            .optional()?)
+    }
+
+    pub fn create(
+        name: String,
+        scopes: Vec<ApiScope>,
+        instance_ids: Option<Vec<String>>,
+    ) -> Result<CreatedApiToken> {
+        if name.trim().is_empty() {
+            return Err(LauncherError::InvalidConfig(
+                "Token name must not be empty".to_string(),
+            ));
+        }
+        if scopes.is_empty() {
+            return Err(LauncherError::InvalidConfig(
+                "Token needs at least one scope".to_string(),
+            ));
+        }
+
+        let id = uuid::Uuid::new_v4().simple().to_string();
+        let mut secret = [0u8; 32];
+        rand::rng().fill_bytes(&mut secret);
+        let secret = hex::encode(secret);
+
+        SecureVault::store_admin_api_token(&id, &secret)?;
+
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        let inserted = conn.execute(
+            r#"INSERT INTO admin_api_tokens (id, name, scopes_json, instance_ids_json, created_at)
+               VALUES (?1, ?2, ?3, ?4, ?5)"#,
+            params![
+                id,
+                name.trim(),
+                serde_json::to_string(&scopes)?,
+                instance_ids
+                    .as_ref()
+                    .map(serde_json::to_string)
+                    .transpose()?,
+                Utc::now().to_rfc3339(),
+            ],
+        );
+        if let Err(e) = inserted {
+            let _ = SecureVault::delete_admin_api_token(&id);
+            return Err(e.into());
+        }
+
+        let info =
+            Self::get(&id)?.ok_or_else(|| LauncherError::NotFound(format!("API token {}", id)))?;
+        Ok(CreatedApiToken {
+            token: format!("{}{}.{}", TOKEN_PREFIX, id, secret),
+            info,
+        })
+    }
+
+    pub async fn revoke(id: &str) -> Result<()> {
+        {
+            let conn = get_db_conn()?;
+            Self::ensure_table(&conn)?;
+            conn.execute("DELETE FROM admin_api_tokens WHERE id = ?1", [id])?;
+        }
+        SECRET_CACHE.write().await.remove(id);
+        SecureVault::delete_admin_api_token(id)?;
+        Ok(())
+    }
+
+    /// Проверить токен из запроса. None — токен неверный или отозван.
+    pub async fn verify(token: &str) -> Option<ApiToken> {
+        let (id, secret) = parse_token(token)?;
+
+        let cached = SECRET_CACHE
+            .read()
+            .await
+            .get(id)
+            .map(|c| (c.secret.clone(), c.last_used_saved));
+        let (expected, last_used_saved) = match cached {
+            Some(cached) => cached,
+            None => {
+                let expected = SecureVault::get_admin_api_token(id).ok()?;
+                // Старое время — чтобы сразу записать last_used_at
+                let long_ago = Instant::now()
+                    .checked_sub(LAST_USED_RESOLUTION)
+                    .unwrap_or_else(Instant::now);
+                (expected, long_ago)
+            }
+        };
+
+        if !constant_time_eq(expected.as_bytes(), secret.as_bytes()) {
+            return None;
+        }
+
+        // Отозванный токен пропадает из БД раньше, чем из кэша другого запроса
+        let info = Self::get(id).ok()??;
+
+        if last_used_saved.elapsed() >= LAST_USED_RESOLUTION {
+            if let Ok(conn) = get_db_conn() {
+                let _ = conn.execute(
+                    "UPDATE admin_api_tokens SET last_used_at = ?1 WHERE id = ?2",
+                    params![Utc::now().to_rfc3339(), id],
+                );
+            }
+            SECRET_CACHE.write().await.insert(
+                id.to_string(),
+                CachedSecret {
+                    secret: expected,
+                    last_used_saved: Instant::now(),
+                },
+            );
+        }
+
+        Some(info)
+    }
+}
+
+/// `stz_<id>.<secret>` -> (id, secret)
+fn parse_token(token: &str) -> Option<(&str, &str)> {
+    let (id, secret) = token.strip_prefix(TOKEN_PREFIX)?.split_once('.')?;
+    let valid = |s: &str| !s.is_empty() && s.bytes().all(|b| b.is_ascii_hexdigit());
+    (valid(id) && valid(secret)).then_some((id, secret))
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
+}
+
+fn row_to_token(row: &rusqlite::Row) -> rusqlite::Result<ApiToken> {
+    let scopes_json: String = row.get(2)?;
+    let instance_ids_json: Option<String> = row.get(3)?;
+
+    let json_error = |idx: usize, e: serde_json::Error| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    };
+
+    Ok(ApiToken {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        scopes: serde_json::from_str(&scopes_json).map_err(|e| json_error(2, e))?,
+        instance_ids: instance_ids_json
+            .map(|json| serde_json::from_str(&json))
+            .transpose()
+            .map_err(|e| json_error(3, e))?,
+        created_at: row.get(4)?,
+        last_used_at: row.get(5)?,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_token() {
+        assert_eq!(
+            parse_token("stz_abc123.deadbeef"),
+            Some(("abc123", "deadbeef"))
+        );
+        assert_eq!(parse_token("abc123.deadbeef"), None);
+        assert_eq!(parse_token("stz_abc123"), None);
+        assert_eq!(parse_token("stz_.deadbeef"), None);
+        assert_eq!(parse_token("stz_abc/../x.deadbeef"), None);
+    }
+
+    #[test]
+    fn test_token_restrictions() {
+        let token = ApiToken {
+            id: "1".to_string(),
+            name: "phone".to_string(),
+            scopes: vec![ApiScope::Read, ApiScope::Console],
+            instance_ids: Some(vec!["survival".to_string()]),
+            created_at: String::new(),
+            last_used_at: None,
+        };
+        assert!(token.has_scope(ApiScope::Console));
+        assert!(!token.has_scope(ApiScope::Control));
+        assert!(token.allows_instance("survival"));
+        assert!(!token.allows_instance("creative"));
+
+        assert!(constant_time_eq(b"secret", b"secret"));
+        assert!(!constant_time_eq(b"secret", b"secreT"));
+        assert!(!constant_time_eq(b"secret", b"secret2"));
+    }
+}
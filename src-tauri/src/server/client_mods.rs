@@ -4,10 +4,11 @@
 //! and automatically disables them when running as a server.
 //!
 //! Detection priority:
-//! 1. Modrinth API via SHA-1/SHA-512 hash lookup (most accurate)
-//! 2. CurseForge API via fingerprint lookup
-//! 3. Local cache of previously detected mods
-//! 4. Fallback: heuristics only for unknown mods (with warning)
+//! 1. Local cache of previously detected mods (by SHA-1)
+//! 2. Modrinth API via SHA-1/SHA-512 hash lookup
+//! 3. CurseForge API via fingerprint lookup
+//! 4. Bytecode scan of the jar (see `side_scan`), combined with the API result
+//!    into a confidence score — metadata alone is often wrong
 
 use std::collections::HashMap;
 use std::path::{Path, PathBuf};
@@ -19,8 +20,15 @@ use tokio::fs;
 use tokio::io::AsyncReadExt;
 use tokio::sync::RwLock;
 
+use super::side_scan::{self, SideScan, SideVerdict};
 use super::{ServerError, ServerResult};
 
+/// Уверенность в client_side/server_side из Modrinth без подтверждения байткодом
+const MODRINTH_CONFIDENCE: f32 = 0.8;
+
+/// Ниже этого порога мод только показывается, но не отключается автоматически
+pub(crate) const AUTO_DISABLE_MIN_CONFIDENCE: f32 = 0.5;
+
 /// Information about a mod's side compatibility
 #[derive(Debug, Clone, serde::Serialize, serde::Deserialize)]
 pub struct ModSideInfo {
@@ -38,9 +46,29 @@ pub struct ModSideInfo {
     pub mod_name: Option<String>,
     /// Detection source
     pub source: DetectionSource,
+    /// Confidence in client_side/server_side (0.0..=1.0)
+    #[serde(default)]
+    pub confidence: f32,
+    /// Why the sides were chosen (bytecode findings, API disagreement)
+    #[serde(default)]
+    pub evidence: Vec<String>,
 }
 
 impl ModSideInfo {
+    fn unknown(sha1: &str, sha512: &str) -> Self {
+        Self {
+            sha1: sha1.to_string(),
+            sha512: sha512.to_string(),
+            client_side: "unknown".to_string(),
+            server_side: "unknown".to_string(),
+            mod_id: None,
+            mod_name: None,
+            source: DetectionSource::Unknown,
+            confidence: 0.0,
+            evidence: Vec::new(),
+        }
+    }
+
     /// Returns true if this mod is client-only (doesn't work on server)
     pub fn is_client_only(&self) -> bool {
         // Client-only: client is required/optional AND server is unsupported
@@ -66,6 +94,8 @@ pub enum DetectionSource {
     Cache,
     /// User manually marked
     UserMarked,
+    /// Jar bytecode scan (overrides or replaces API metadata)
+    Bytecode,
     /// Unknown mod (not found in any API)
     Unknown,
 }
@@ -85,6 +115,9 @@ pub struct ClientModInfo {
     pub source: DetectionSource,
     /// Reason it was detected as client-only
     pub reason: String,
+    /// Confidence of the detection (0.0..=1.0)
+    #[serde(default)]
+    pub confidence: f32,
     /// Whether it was auto-disabled
     pub disabled: bool,
 }
@@ -99,7 +132,7 @@ pub struct ModSideCache {
 }
 
 impl ModSideCache {
-    const CURRENT_VERSION: u32 = 1;
+    const CURRENT_VERSION: u32 = 2;
 
     pub fn new() -> Self {
         Self {
@@ -203,6 +236,8 @@ async fn lookup_modrinth(sha1: &str, sha512: &str) -> Option<ModSideInfo> {
         mod_id: Some(project.slug.clone()),
         mod_name: Some(project.title.clone()),
         source: DetectionSource::Modrinth,
+        confidence: MODRINTH_CONFIDENCE,
+        evidence: Vec::new(),
     })
 }
 
@@ -251,6 +286,8 @@ async fn lookup_modrinth_batch(hashes: &[(String, String)]) -> HashMap<String, M
                     mod_id: Some(project.slug.clone()),
                     mod_name: Some(project.title.clone()),
                     source: DetectionSource::Modrinth,
+                    confidence: MODRINTH_CONFIDENCE,
+                    evidence: Vec::new(),
                 },
             );
         }
@@ -279,7 +316,7 @@ async fn lookup_curseforge(sha1: &str, sha512: &str, path: &Path) -> Option<ModS
     // CurseForge doesn't expose client_side/server_side directly
     // We need to check the mod categories instead
     // Category 435 = Client, Category 434 = Server
-    // For now, we'll use the mod info but can't determine side accurately
+    // Side itself comes from the bytecode scan (see `combine_side_info`)
 
     // Note: CurseForge API doesn't have a direct hash lookup
     // The fingerprint endpoint would need to be added to curseforge.rs
@@ -349,7 +386,145 @@ fn murmur2_hash(data: &[u8], seed: u32) -> u32 {
     h
 }
 
-/// Scan mods folder and detect client-only mods using API
+/// Run the bytecode scan on the blocking pool; failures are only logged
+async fn scan_side(path: &Path) -> Option<SideScan> {
+    let jar = path.to_path_buf();
+    match tokio::task::spawn_blocking(move || side_scan::scan_jar(&jar)).await {
+        Ok(Ok(scan)) => {
+            log::debug!(
+                "Bytecode scan of {}: {:?} ({:.2}), {}/{} client classes",
+                path.display(),
+                scan.verdict,
+                scan.confidence,
+                scan.client_classes,
+                scan.classes
+            );
+            Some(scan)
+        }
+        Ok(Err(e)) => {
+            log::warn!("Bytecode scan failed for {}: {}", path.display(), e);
+            None
+        }
+        Err(e) => {
+            log::warn!("Bytecode scan task failed for {}: {}", path.display(), e);
+            None
+        }
+    }
+}
+
+/// client_side/server_side for a bytecode verdict
+fn sides_for(verdict: SideVerdict) -> (&'static str, &'static str) {
+    match verdict {
+        SideVerdict::ClientOnly => ("required", "unsupported"),
+        SideVerdict::ServerOnly => ("unsupported", "required"),
+        SideVerdict::Both => ("optional", "optional"),
+        SideVerdict::Unknown => ("unknown", "unknown"),
+    }
+}
+
+/// Bytecode verdict matching API client_side/server_side
+fn verdict_for(info: &ModSideInfo) -> SideVerdict {
+    match (info.is_client_only(), info.is_server_only()) {
+        (true, false) => SideVerdict::ClientOnly,
+        (false, true) => SideVerdict::ServerOnly,
+        _ if info.client_side == "unknown" || info.server_side == "unknown" => SideVerdict::Unknown,
+        _ => SideVerdict::Both,
+    }
+}
+
+/// Combine API metadata with the bytecode scan into one confidence-scored entry.
+///
+/// Agreement of two independent sources raises confidence. On disagreement the
+/// more confident source wins (the jar wins ties — it is what actually runs),
+/// discounted by a quarter of the loser's confidence.
+fn combine_side_info(
+    api: Option<ModSideInfo>,
+    scan: Option<&SideScan>,
+    sha1: &str,
+    sha512: &str,
+) -> ModSideInfo {
+    let scan = scan.filter(|s| s.verdict != SideVerdict::Unknown);
+
+    let Some(mut info) = api else {
+        let mut info = ModSideInfo::unknown(sha1, sha512);
+        if let Some(scan) = scan {
+            let (client_side, server_side) = sides_for(scan.verdict);
+            info.client_side = client_side.to_string();
+            info.server_side = server_side.to_string();
+            info.source = DetectionSource::Bytecode;
+            info.confidence = scan.confidence;
+            info.evidence = scan.evidence.clone();
+        }
+        return info;
+    };
+    let Some(scan) = scan else {
+        return info;
+    };
+
+    info.evidence = scan.evidence.clone();
+    if verdict_for(&info) == scan.verdict {
+        info.confidence = 1.0 - (1.0 - info.confidence) * (1.0 - scan.confidence);
+    } else if scan.confidence >= info.confidence {
+        info.evidence.push(format!(
+            "overrides {:?} client_side={}, server_side={}",
+            info.source, info.client_side, info.server_side
+        ));
+        let (client_side, server_side) = sides_for(scan.verdict);
+        info.client_side = client_side.to_string();
+        info.server_side = server_side.to_string();
+        info.source = DetectionSource::Bytecode;
+        info.confidence = scan.confidence - info.confidence / 4.0;
+    } else {
+        info.evidence.push(format!(
+            "bytecode suggests {:?}, kept {:?} sides",
+            scan.verdict, info.source
+        ));
+        info.confidence -= scan.confidence / 4.0;
+    }
+    info
+}
+
+/// UI entry for a detected client-only mod
+fn client_mod_info(path: &Path, info: &ModSideInfo, source: DetectionSource) -> ClientModInfo {
+    let file_name = path
+        .file_name()
+        .and_then(|n| n.to_str())
+        .unwrap_or("")
+        .to_string();
+
+    let origin = match source {
+        DetectionSource::Cache => "cached",
+        DetectionSource::Modrinth => "Modrinth",
+        DetectionSource::CurseForge => "CurseForge",
+        DetectionSource::Bytecode => "bytecode",
+        DetectionSource::UserMarked => "user",
+        DetectionSource::Unknown => "unknown",
+    };
+    let mut reason = format!(
+        "client_side={}, server_side={} ({}, {:.0}%)",
+        info.client_side,
+        info.server_side,
+        origin,
+        info.confidence * 100.0
+    );
+    if !info.evidence.is_empty() {
+        reason.push_str(": ");
+        reason.push_str(&info.evidence.join("; "));
+    }
+
+    ClientModInfo {
+        file_name,
+        sha1: info.sha1.clone(),
+        mod_id: info.mod_id.clone(),
+        name: info.mod_name.clone(),
+        source,
+        reason,
+        confidence: info.confidence,
+        disabled: false,
+    }
+}
+
+/// Scan mods folder and detect client-only mods (API + bytecode)
 pub async fn scan_for_client_mods(mods_dir: impl AsRef<Path>) -> ServerResult<Vec<ClientModInfo>> {
     let mods_dir = mods_dir.as_ref();
     let mut client_mods = Vec::new();
@@ -412,75 +587,31 @@ pub async fn scan_for_client_mods(mods_dir: impl AsRef<Path>) -> ServerResult<Ve
         .collect();
     let api_results = lookup_modrinth_batch(&hashes).await;
 
-    // Update cache with new results
-    {
-        let mut cache = get_cache().write().await;
-        for (sha1, info) in &api_results {
-            cache.insert(sha1.clone(), info.clone());
-        }
+    // Combine API answers with bytecode scan and cache the result
+    let mut scanned: Vec<(PathBuf, ModSideInfo)> = Vec::new();
+    for (path, sha1, sha512) in uncached {
+        let scan = scan_side(&path).await;
+        let info = combine_side_info(
+            api_results.get(&sha1).cloned(),
+            scan.as_ref(),
+            &sha1,
+            &sha512,
+        );
+        get_cache().write().await.insert(sha1, info.clone());
+        scanned.push((path, info));
     }
 
     // Process cached results
     for (path, info) in cached_results {
         if info.is_client_only() {
-            let file_name = path
-                .file_name()
-                .and_then(|n| n.to_str())
-                .unwrap_or("")
-                .to_string();
-
-            client_mods.push(ClientModInfo {
-                file_name,
-                sha1: info.sha1.clone(),
-                mod_id: info.mod_id.clone(),
-                name: info.mod_name.clone(),
-                source: DetectionSource::Cache,
-                reason: format!(
-                    "client_side={}, server_side={} (cached)",
-                    info.client_side, info.server_side
-                ),
-                disabled: false,
-            });
+            client_mods.push(client_mod_info(&path, &info, DetectionSource::Cache));
         }
     }
 
-    // Process API results
-    for (path, sha1, sha512) in &uncached {
-        let file_name = path
-            .file_name()
-            .and_then(|n| n.to_str())
-            .unwrap_or("")
-            .to_string();
-
-        if let Some(info) = api_results.get(sha1) {
-            if info.is_client_only() {
-                client_mods.push(ClientModInfo {
-                    file_name,
-                    sha1: sha1.clone(),
-                    mod_id: info.mod_id.clone(),
-                    name: info.mod_name.clone(),
-                    source: info.source.clone(),
-                    reason: format!(
-                        "client_side={}, server_side={} (Modrinth)",
-                        info.client_side, info.server_side
-                    ),
-                    disabled: false,
-                });
-            }
-        } else {
-            // Not found in Modrinth - add to cache as unknown
-            let unknown_info = ModSideInfo {
-                sha1: sha1.clone(),
-                sha512: sha512.clone(),
-                client_side: "unknown".to_string(),
-                server_side: "unknown".to_string(),
-                mod_id: None,
-                mod_name: None,
-                source: DetectionSource::Unknown,
-            };
-
-            let mut cache = get_cache().write().await;
-            cache.insert(sha1.clone(), unknown_info);
+    // Process fresh results
+    for (path, info) in scanned {
+        if info.is_client_only() {
+            client_mods.push(client_mod_info(&path, &info, info.source.clone()));
         }
     }
 
@@ -501,33 +632,25 @@ pub async fn get_mod_side_info(path: &Path) -> ServerResult<Option<ModSideInfo>>
         return Ok(Some(info.clone()));
     }
 
-    // Try Modrinth
-    if let Some(info) = lookup_modrinth(&sha1, &sha512).await {
-        get_cache().write().await.insert(sha1.clone(), info.clone());
-        return Ok(Some(info));
-    }
-
-    // Try CurseForge
-    if let Some(info) = lookup_curseforge(&sha1, &sha512, path).await {
-        get_cache().write().await.insert(sha1.clone(), info.clone());
-        return Ok(Some(info));
+    // Try Modrinth, then CurseForge
+    let mut api = lookup_modrinth(&sha1, &sha512).await;
+    if api.is_none() {
+        api = lookup_curseforge(&sha1, &sha512, path).await;
     }
 
-    // Unknown mod
-    let info = ModSideInfo {
-        sha1: sha1.clone(),
-        sha512,
-        client_side: "unknown".to_string(),
-        server_side: "unknown".to_string(),
-        mod_id: None,
-        mod_name: None,
-        source: DetectionSource::Unknown,
-    };
+    // Metadata is checked against the jar itself
+    let scan = scan_side(path).await;
+    let info = combine_side_info(api, scan.as_ref(), &sha1, &sha512);
     get_cache().write().await.insert(sha1, info.clone());
 
     Ok(Some(info))
 }
 
+/// Get cached side info by SHA-1 without hitting APIs or scanning the jar
+pub async fn cached_side_info(sha1: &str) -> Option<ModSideInfo> {
+    get_cache().read().await.get(sha1).cloned()
+}
+
 /// Disable a mod by renaming .jar to .jar.disabled
 pub async fn disable_mod(mods_dir: impl AsRef<Path>, file_name: &str) -> ServerResult<()> {
     let mods_dir = mods_dir.as_ref();
@@ -566,12 +689,16 @@ pub async fn enable_mod(mods_dir: impl AsRef<Path>, file_name: &str) -> ServerRe
     Ok(())
 }
 
-/// Auto-disable all detected client mods
+/// Auto-disable detected client mods that are confident enough
 pub async fn auto_disable_client_mods(
     mods_dir: impl AsRef<Path>,
 ) -> ServerResult<Vec<ClientModInfo>> {
     let mods_dir = mods_dir.as_ref();
-    let mut client_mods = scan_for_client_mods(mods_dir).await?;
+    let mut client_mods: Vec<ClientModInfo> = scan_for_client_mods(mods_dir)
+        .await?
+        .into_iter()
+        .filter(|m| m.confidence >= AUTO_DISABLE_MIN_CONFIDENCE)
+        .collect();
 
     for mod_info in &mut client_mods {
         disable_mod(mods_dir, &mod_info.file_name).await?;
@@ -661,6 +788,8 @@ pub async fn mark_as_client_mod(mods_dir: impl AsRef<Path>, file_name: &str) ->
         mod_id: None,
         mod_name: Some(file_name.to_string()),
         source: DetectionSource::UserMarked,
+        confidence: 1.0,
+        evidence: Vec::new(),
     };
 
     get_cache().write().await.insert(sha1, info);
@@ -688,6 +817,8 @@ pub async fn unmark_as_client_mod(mods_dir: impl AsRef<Path>, file_name: &str) -
         mod_id: None,
         mod_name: Some(file_name.to_string()),
         source: DetectionSource::UserMarked,
+        confidence: 1.0,
+        evidence: Vec::new(),
     };
 
     get_cache().write().await.insert(sha1, info);
@@ -794,3 +925,83 @@ pub async fn mark_mod_as_server_compatible(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn modrinth(client_side: &str, server_side: &str) -> ModSideInfo {
+        ModSideInfo {
+            client_side: client_side.to_string(),
+            server_side: server_side.to_string(),
+            source: DetectionSource::Modrinth,
+            confidence: MODRINTH_CONFIDENCE,
+            ..ModSideInfo::unknown("sha1", "sha512")
+        }
+    }
+
+    fn scan(verdict: SideVerdict, confidence: f32) -> SideScan {
+        SideScan {
+            verdict,
+            confidence,
+            evidence: vec!["fabric.mod.json: environment = client".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_combine_side_info() {
+        // Согласие повышает уверенность
+        let agreed = combine_side_info(
+            Some(modrinth("required", "unsupported")),
+            Some(&scan(SideVerdict::ClientOnly, 0.6)),
+            "sha1",
+            "sha512",
+        );
+        assert!(agreed.is_client_only());
+        assert!(agreed.confidence > MODRINTH_CONFIDENCE);
+
+        // Жёсткая улика из JAR перекрывает неверные метаданные
+        let overridden = combine_side_info(
+            Some(modrinth("required", "optional")),
+            Some(&scan(SideVerdict::ClientOnly, 0.95)),
+            "sha1",
+            "sha512",
+        );
+        assert!(overridden.is_client_only());
+        assert_eq!(overridden.source, DetectionSource::Bytecode);
+        assert!(overridden.confidence >= AUTO_DISABLE_MIN_CONFIDENCE);
+
+        // Слабый сигнал не перебивает Modrinth
+        let kept = combine_side_info(
+            Some(modrinth("required", "optional")),
+            Some(&scan(SideVerdict::ClientOnly, 0.6)),
+            "sha1",
+            "sha512",
+        );
+        assert!(!kept.is_client_only());
+        assert_eq!(kept.source, DetectionSource::Modrinth);
+
+        // "Обе стороны" и ServerOnly — это расхождение, а не согласие
+        let server_only = combine_side_info(
+            Some(modrinth("required", "required")),
+            Some(&scan(SideVerdict::ServerOnly, 0.6)),
+            "sha1",
+            "sha512",
+        );
+        assert_eq!(server_only.source, DetectionSource::Modrinth);
+        assert!(server_only.confidence < MODRINTH_CONFIDENCE);
+
+        let scanned_only = combine_side_info(
+            None,
+            Some(&scan(SideVerdict::ClientOnly, 0.95)),
+            "sha1",
+            "sha512",
+        );
+        assert!(scanned_only.is_client_only());
+        assert_eq!(scanned_only.confidence, 0.95);
+
+        let unknown = combine_side_info(None, None, "sha1", "sha512");
+        assert_eq!(unknown.source, DetectionSource::Unknown);
+    }
+}
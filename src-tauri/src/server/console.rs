@@ -14,7 +14,7 @@ use std::collections::{HashMap, VecDeque};
 use std::io::Write;
 use std::sync::{Arc, LazyLock};
 use tauri::{AppHandle, Emitter};
-use tokio::sync::RwLock;
+use tokio::sync::{broadcast, RwLock};
 
 use super::rcon::RCON_CONNECTIONS;
 use super::ServerResult;
@@ -334,6 +334,15 @@ static CONSOLES: LazyLock<RwLock<HashMap<String, Arc<RwLock<ServerConsole>>>>> =
 /// App handle for emitting events
 static APP_HANDLE: LazyLock<RwLock<Option<AppHandle>>> = LazyLock::new(|| RwLock::new(None));
 
+/// Log stream for in-process subscribers (admin API WebSocket)
+static LOG_STREAM: LazyLock<broadcast::Sender<(String, ServerLogEntry)>> =
+    LazyLock::new(|| broadcast::channel(1024).0);
+
+/// Subscribe to new log entries of all servers as `(instance_id, entry)`
+pub fn subscribe_logs() -> broadcast::Receiver<(String, ServerLogEntry)> {
+    LOG_STREAM.subscribe()
+}
+
 /// Initialize console module
 pub fn init(app: &AppHandle) {
     let mut handle = APP_HANDLE.blocking_write();
@@ -365,6 +374,15 @@ pub async fn register_server_stdin(instance_id: &str, stdin: std::process::Child
     let mut console = console.write().await;
     console.start_with_stdin(stdin);
     log::info!("Registered stdin for server {}", instance_id);
+
+    // Сессии прошлого запуска, выход из которых не успели записать
+    if let Err(e) = super::sessions::PlayerSessionManager::close_stale(instance_id) {
+        log::warn!(
+            "Failed to close stale player sessions for {}: {}",
+            instance_id,
+            e
+        );
+    }
 }
 
 /// Mark server as stopped (sync version for use from stop_instance)
@@ -375,6 +393,7 @@ pub fn mark_server_stopped_sync(instance_id: &str) {
         console.mark_stopped();
         log::info!("Marked server {} as stopped", instance_id);
     }
+    end_player_sessions(instance_id);
 }
 
 /// Mark server as stopped (async version)
@@ -383,6 +402,14 @@ pub async fn mark_server_stopped(instance_id: &str) {
     let mut console = console.write().await;
     console.mark_stopped();
     log::info!("Marked server {} as stopped", instance_id);
+    drop(console);
+    end_player_sessions(instance_id);
+}
+
+fn end_player_sessions(instance_id: &str) {
+    if let Err(e) = super::sessions::PlayerSessionManager::end_all(instance_id, "Server stopped") {
+        log::warn!("Failed to close player sessions for {}: {}", instance_id, e);
+    }
 }
 
 /// Add log line and emit event
@@ -395,6 +422,11 @@ pub async fn add_log_and_emit(instance_id: &str, line: &str) {
         console.logs.back().cloned()
     };
 
+    // Входы и выходы игроков
+    if let Err(e) = super::sessions::PlayerSessionManager::record_line(instance_id, line) {
+        log::warn!("Failed to record player session for {}: {}", instance_id, e);
+    }
+
     // Check for server ready pattern - try to auto-connect RCON
     if is_server_ready_line(line) {
         log::info!(
@@ -446,6 +478,9 @@ pub async fn add_log_and_emit(instance_id: &str, line: &str) {
 
     // Emit log event to frontend with instance-specific event name
     if let Some(entry) = entry {
+        if LOG_STREAM.receiver_count() > 0 {
+            let _ = LOG_STREAM.send((instance_id.to_string(), entry.clone()));
+        }
         if let Some(ref app) = *APP_HANDLE.read().await {
             let _ = app.emit(&format!("server-log:{}", instance_id), &entry);
         }
@@ -2,8 +2,8 @@
 //!
 //! Import existing server directories with bulletproof loader detection.
 //! Uses multiple detection methods:
-//! - Loader-specific files (fabric.json, forge config, etc.)
-//! - JAR manifest inspection
+//! - Loader-specific files (fabric.json, forge config, paper-global.yml, velocity.toml, etc.)
+//! - JAR manifest inspection (including paperclip `META-INF/versions.list`)
 //! - Libraries folder analysis
 //! - Version JSON parsing
 
@@ -35,6 +35,8 @@ pub struct DetectedServer {
     pub evidence: Vec<String>,
     /// Detected mods count
     pub mods_count: usize,
+    /// Detected plugins count (Paper/Purpur/Folia/Velocity)
+    pub plugins_count: usize,
     /// Has EULA been accepted
     pub eula_accepted: bool,
 }
@@ -127,6 +129,15 @@ pub async fn detect_server(server_dir: impl AsRef<Path>) -> ServerResult<Detecte
         confidence = confidence.saturating_add(10);
     }
 
+    // Method 6: Check plugins folder (Bukkit/Paper or Velocity plugins)
+    if let Some((l, ev)) = detect_from_plugins(server_dir).await {
+        if confidence == 0 || loader == ServerLoader::Vanilla {
+            loader = l;
+        }
+        evidence.extend(ev);
+        confidence = confidence.saturating_add(10);
+    }
+
     // Load server.properties
     let properties = super::properties::load_properties(server_dir).await.ok();
 
@@ -136,8 +147,9 @@ pub async fn detect_server(server_dir: impl AsRef<Path>) -> ServerResult<Detecte
         .map(|status| status.accepted)
         .unwrap_or(false);
 
-    // Count mods
+    // Count mods and plugins
     let mods_count = count_mods(server_dir).await;
+    let plugins_count = count_plugins(server_dir).await;
 
     // Clamp confidence
     confidence = confidence.min(100);
@@ -151,6 +163,7 @@ pub async fn detect_server(server_dir: impl AsRef<Path>) -> ServerResult<Detecte
         confidence,
         evidence,
         mods_count,
+        plugins_count,
         eula_accepted,
     })
 }
@@ -161,6 +174,35 @@ async fn detect_from_indicator_files(
 ) -> Option<(ServerLoader, Option<String>, Vec<String>)> {
     let mut evidence = Vec::new();
 
+    // Velocity: velocity.toml (proxy, no worlds)
+    if fs::try_exists(server_dir.join("velocity.toml"))
+        .await
+        .unwrap_or(false)
+    {
+        evidence.push("Found velocity.toml".into());
+        return Some((ServerLoader::Velocity, None, evidence));
+    }
+
+    // Purpur writes purpur.yml next to the Paper configs, so check it first
+    if fs::try_exists(server_dir.join("purpur.yml"))
+        .await
+        .unwrap_or(false)
+    {
+        evidence.push("Found purpur.yml".into());
+        return Some((ServerLoader::Purpur, None, evidence));
+    }
+
+    // Paper: config/paper-global.yml (1.19+) or paper.yml (older)
+    for paper_config in ["config/paper-global.yml", "paper.yml"] {
+        if fs::try_exists(server_dir.join(paper_config))
+            .await
+            .unwrap_or(false)
+        {
+            evidence.push(format!("Found {}", paper_config));
+            return Some((ServerLoader::Paper, None, evidence));
+        }
+    }
+
     // Fabric: .fabric folder or fabric-server-launcher.properties
     let fabric_folder = server_dir.join(".fabric");
     let fabric_props = server_dir.join("fabric-server-launcher.properties");
@@ -421,6 +463,17 @@ async fn detect_from_jars(
             return Some((path, ServerLoader::Quilt, None, None, evidence));
         }
 
+        // Paper/Purpur/Folia/Velocity: <platform>-<version>-<build>.jar
+        if let Some((loader, version, build)) = parse_platform_jar_name(&name) {
+            evidence.push(format!("Found {} server JAR: {}", loader, name));
+            let (mc_version, loader_version) = if loader.is_proxy() {
+                (None, Some(version))
+            } else {
+                (Some(version), build)
+            };
+            return Some((path, loader, mc_version, loader_version, evidence));
+        }
+
         if name.starts_with("forge-") && name.contains("universal") {
             evidence.push(format!("Found Forge universal JAR: {}", name));
             // Try to parse version from name: forge-1.20.1-47.2.0-universal.jar
@@ -442,6 +495,31 @@ async fn detect_from_jars(
     None
 }
 
+/// Parse Paper-style JAR name: `paper-1.21.1-123.jar` -> (Paper, "1.21.1", Some("123")).
+/// For Velocity the version keeps its suffix: `velocity-3.4.0-SNAPSHOT-436.jar` -> "3.4.0-SNAPSHOT".
+fn parse_platform_jar_name(name: &str) -> Option<(ServerLoader, String, Option<String>)> {
+    let stem = name.strip_suffix(".jar")?;
+    let (platform, rest) = stem.split_once('-')?;
+    let loader = match platform.to_lowercase().as_str() {
+        "paper" => ServerLoader::Paper,
+        "purpur" => ServerLoader::Purpur,
+        "folia" => ServerLoader::Folia,
+        "velocity" => ServerLoader::Velocity,
+        _ => return None,
+    };
+
+    if !rest.starts_with(|c: char| c.is_ascii_digit()) {
+        return None;
+    }
+
+    match rest.rsplit_once('-') {
+        Some((version, build)) if build.chars().all(|c| c.is_ascii_digit()) => {
+            Some((loader, version.to_string(), Some(build.to_string())))
+        }
+        _ => Some((loader, rest.to_string(), None)),
+    }
+}
+
 /// Parse Forge JAR name for versions
 fn parse_forge_jar_name(name: &str) -> Option<(String, String)> {
     // Format: forge-1.20.1-47.2.0-universal.jar
@@ -467,6 +545,27 @@ async fn inspect_jar_manifest(
 
         let mut evidence = Vec::new();
 
+        // Paperclip (Paper/Purpur/Folia) lists the bundled server JAR in versions.list:
+        // "<sha256>\t<id>\tpaper-1.21.1.jar"
+        let mut platform = None;
+        if let Ok(mut versions_list) = archive.by_name("META-INF/versions.list") {
+            let mut content = String::new();
+            versions_list.read_to_string(&mut content).ok()?;
+
+            platform = content
+                .lines()
+                .filter_map(|line| line.split('\t').nth(2))
+                .find_map(|jar| match jar.split('-').next() {
+                    Some("paper") => Some(ServerLoader::Paper),
+                    Some("purpur") => Some(ServerLoader::Purpur),
+                    Some("folia") => Some(ServerLoader::Folia),
+                    _ => None,
+                });
+            if let Some(loader) = platform {
+                evidence.push(format!("Paperclip bundles a {} server", loader));
+            }
+        }
+
         // Check MANIFEST.MF
         if let Ok(mut manifest) = archive.by_name("META-INF/MANIFEST.MF") {
             let mut content = String::new();
@@ -477,6 +576,10 @@ async fn inspect_jar_manifest(
                 if line.starts_with("Implementation-Version:") {
                     evidence.push(format!("Manifest version: {}", line));
                 }
+                if line.contains("com.velocitypowered.proxy") {
+                    evidence.push("Manifest indicates Velocity".into());
+                    return Some((None, ServerLoader::Velocity, evidence));
+                }
                 if line.contains("FabricLoader") {
                     evidence.push("Manifest indicates Fabric".into());
                     return Some((None, ServerLoader::Fabric, evidence));
@@ -496,7 +599,8 @@ async fn inspect_jar_manifest(
             if let Ok(json) = serde_json::from_str::<serde_json::Value>(&content) {
                 if let Some(id) = json["id"].as_str() {
                     evidence.push(format!("Found version.json with id: {}", id));
-                    return Some((Some(id.to_string()), ServerLoader::Vanilla, evidence));
+                    let loader = platform.unwrap_or(ServerLoader::Vanilla);
+                    return Some((Some(id.to_string()), loader, evidence));
                 }
             }
         }
@@ -514,7 +618,7 @@ async fn inspect_jar_manifest(
         }
 
         if !evidence.is_empty() {
-            Some((None, ServerLoader::Vanilla, evidence))
+            Some((None, platform.unwrap_or(ServerLoader::Vanilla), evidence))
         } else {
             None
         }
@@ -623,6 +727,59 @@ async fn detect_from_mods(server_dir: &Path) -> Option<(ServerLoader, Vec<String
     None
 }
 
+/// Detect from plugins folder: Bukkit-style plugins mean Paper, Velocity plugins mean Velocity
+async fn detect_from_plugins(server_dir: &Path) -> Option<(ServerLoader, Vec<String>)> {
+    let plugins_dir = server_dir.join("plugins");
+    let mut entries = fs::read_dir(&plugins_dir).await.ok()?;
+
+    let mut bukkit_count = 0;
+    let mut velocity_count = 0;
+
+    while let Ok(Some(entry)) = entries.next_entry().await {
+        let path = entry.path();
+        if !path.extension().map_or(false, |e| e == "jar") {
+            continue;
+        }
+
+        let jar_path = path.clone();
+        let kind = tokio::task::spawn_blocking(move || {
+            let file = std::fs::File::open(&jar_path).ok()?;
+            let mut archive = ZipArchive::new(file).ok()?;
+            if archive.by_name("velocity-plugin.json").is_ok() {
+                return Some("velocity");
+            }
+            if archive.by_name("paper-plugin.yml").is_ok() || archive.by_name("plugin.yml").is_ok()
+            {
+                return Some("bukkit");
+            }
+            None
+        })
+        .await
+        .ok()
+        .flatten();
+
+        match kind {
+            Some("velocity") => velocity_count += 1,
+            Some("bukkit") => bukkit_count += 1,
+            _ => {}
+        }
+    }
+
+    if bukkit_count == 0 && velocity_count == 0 {
+        return None;
+    }
+
+    let evidence = vec![format!(
+        "Plugins folder analysis: {} Bukkit/Paper, {} Velocity plugins",
+        bukkit_count, velocity_count
+    )];
+    if velocity_count > bukkit_count {
+        Some((ServerLoader::Velocity, evidence))
+    } else {
+        Some((ServerLoader::Paper, evidence))
+    }
+}
+
 /// Detect mod type from JAR
 async fn detect_mod_type(jar_path: &Path) -> Option<String> {
     let jar_path = jar_path.to_path_buf();
@@ -671,6 +828,19 @@ async fn count_mods(server_dir: &Path) -> usize {
     count
 }
 
+/// Count plugins in plugins folder
+async fn count_plugins(server_dir: &Path) -> usize {
+    let mut count = 0;
+    if let Ok(mut entries) = fs::read_dir(server_dir.join("plugins")).await {
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            if entry.path().extension().map_or(false, |e| e == "jar") {
+                count += 1;
+            }
+        }
+    }
+    count
+}
+
 /// Progress event for import
 #[derive(Debug, Clone, serde::Serialize)]
 pub struct ImportProgress {
@@ -938,6 +1108,23 @@ where
         log::info!("Successfully synced mods for imported server");
     }
 
+    if detected.loader.is_plugin_platform() {
+        if let Err(e) = super::plugins::PluginManager::sync_with_folder(&instance_id).await {
+            log::warn!("Failed to sync plugins after import: {}", e);
+        }
+    }
+
+    // История игроков из старых логов — в фоне, архивы бывают большими
+    let backfill_id = instance_id.clone();
+    let backfill_dir = target_dir.clone();
+    tokio::task::spawn_blocking(move || {
+        if let Err(e) =
+            super::sessions::PlayerSessionManager::backfill_from_logs(&backfill_id, &backfill_dir)
+        {
+            log::warn!("Failed to backfill player sessions after import: {}", e);
+        }
+    });
+
     log::info!(
         "Imported server '{}' (id: {}, loader: {:?}, files: {}, size: {} bytes)",
         instance_name,
@@ -986,3 +1173,34 @@ pub async fn import_existing_server(
     .await
     .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_platform_jar_name() {
+        assert_eq!(
+            parse_platform_jar_name("paper-1.21.1-123.jar"),
+            Some((ServerLoader::Paper, "1.21.1".into(), Some("123".into())))
+        );
+        assert_eq!(
+            parse_platform_jar_name("purpur-1.20.4-2176.jar"),
+            Some((ServerLoader::Purpur, "1.20.4".into(), Some("2176".into())))
+        );
+        assert_eq!(
+            parse_platform_jar_name("velocity-3.4.0-SNAPSHOT-436.jar"),
+            Some((
+                ServerLoader::Velocity,
+                "3.4.0-SNAPSHOT".into(),
+                Some("436".into())
+            ))
+        );
+        assert_eq!(
+            parse_platform_jar_name("folia-1.20.6.jar"),
+            Some((ServerLoader::Folia, "1.20.6".into(), None))
+        );
+        assert_eq!(parse_platform_jar_name("paper-api.jar"), None);
+        assert_eq!(parse_platform_jar_name("server.jar"), None);
+    }
+}
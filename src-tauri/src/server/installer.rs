@@ -6,7 +6,10 @@
 //! - Forge (from Forge Maven)
 //! - NeoForge (from NeoForge Maven)
 //! - Quilt (from Quilt Meta)
+//! - Paper, Folia, Velocity (from PaperMC Fill API)
+//! - Purpur (from Purpur API)
 
+use std::collections::HashMap;
 use std::path::{Path, PathBuf};
 use std::process::Stdio;
 use tokio::fs;
@@ -21,8 +24,16 @@ const CREATE_NO_WINDOW: u32 = 0x08000000;
 
 use super::{ServerError, ServerResult};
 
+use crate::smart_downloader::SmartDownloader;
+use crate::types::LoaderType;
 use crate::utils::SHARED_HTTP_CLIENT as SERVER_HTTP_CLIENT;
 
+/// PaperMC Fill API (Paper, Folia, Velocity)
+const PAPERMC_API: &str = "https://fill.papermc.io/v3/projects";
+
+/// Purpur API
+const PURPUR_API: &str = "https://api.purpurmc.org/v2/purpur";
+
 /// Loader type for server
 #[derive(Debug, Clone, Copy, PartialEq, Eq, serde::Serialize, serde::Deserialize)]
 #[serde(rename_all = "lowercase")]
@@ -32,6 +43,44 @@ pub enum ServerLoader {
     Forge,
     NeoForge,
     Quilt,
+    Paper,
+    Purpur,
+    Folia,
+    Velocity,
+}
+
+impl ServerLoader {
+    /// Plugin-based platform: server JAR is downloaded as is, extensions live in `plugins/`
+    pub fn is_plugin_platform(&self) -> bool {
+        matches!(
+            self,
+            ServerLoader::Paper
+                | ServerLoader::Purpur
+                | ServerLoader::Folia
+                | ServerLoader::Velocity
+        )
+    }
+
+    /// Proxy (Velocity): no worlds, no EULA
+    pub fn is_proxy(&self) -> bool {
+        matches!(self, ServerLoader::Velocity)
+    }
+}
+
+impl From<&LoaderType> for ServerLoader {
+    fn from(loader: &LoaderType) -> Self {
+        match loader {
+            LoaderType::Vanilla => ServerLoader::Vanilla,
+            LoaderType::Fabric => ServerLoader::Fabric,
+            LoaderType::Forge => ServerLoader::Forge,
+            LoaderType::NeoForge => ServerLoader::NeoForge,
+            LoaderType::Quilt => ServerLoader::Quilt,
+            LoaderType::Paper => ServerLoader::Paper,
+            LoaderType::Purpur => ServerLoader::Purpur,
+            LoaderType::Folia => ServerLoader::Folia,
+            LoaderType::Velocity => ServerLoader::Velocity,
+        }
+    }
 }
 
 impl std::fmt::Display for ServerLoader {
@@ -42,6 +91,10 @@ impl std::fmt::Display for ServerLoader {
             ServerLoader::Forge => write!(f, "forge"),
             ServerLoader::NeoForge => write!(f, "neoforge"),
             ServerLoader::Quilt => write!(f, "quilt"),
+            ServerLoader::Paper => write!(f, "paper"),
+            ServerLoader::Purpur => write!(f, "purpur"),
+            ServerLoader::Folia => write!(f, "folia"),
+            ServerLoader::Velocity => write!(f, "velocity"),
         }
     }
 }
@@ -56,6 +109,10 @@ impl std::str::FromStr for ServerLoader {
             "forge" => Ok(ServerLoader::Forge),
             "neoforge" => Ok(ServerLoader::NeoForge),
             "quilt" => Ok(ServerLoader::Quilt),
+            "paper" => Ok(ServerLoader::Paper),
+            "purpur" => Ok(ServerLoader::Purpur),
+            "folia" => Ok(ServerLoader::Folia),
+            "velocity" => Ok(ServerLoader::Velocity),
             _ => Err(ServerError::Config(format!("Unknown loader: {}", s))),
         }
     }
@@ -76,7 +133,10 @@ pub struct InstallResult {
     pub java_args: Vec<String>,
 }
 
-/// Get latest loader version for a Minecraft version
+/// Get latest loader version for a Minecraft version.
+///
+/// For Paper/Purpur/Folia the "loader version" is a build number,
+/// for Velocity it is the Velocity version itself (the MC version is ignored).
 pub async fn get_latest_loader_version(
     loader: ServerLoader,
     mc_version: &str,
@@ -87,6 +147,18 @@ pub async fn get_latest_loader_version(
         ServerLoader::Forge => get_latest_forge_version(mc_version).await,
         ServerLoader::NeoForge => get_latest_neoforge_version(mc_version).await,
         ServerLoader::Quilt => get_latest_quilt_loader(mc_version).await,
+        ServerLoader::Paper | ServerLoader::Folia => {
+            let builds = get_papermc_builds(papermc_project(loader), mc_version).await?;
+            Ok(builds
+                .iter()
+                .find(|b| b.channel.eq_ignore_ascii_case("stable"))
+                .or_else(|| builds.first())
+                .map(|b| b.id.to_string()))
+        }
+        ServerLoader::Purpur => Ok(get_purpur_builds(mc_version)
+            .await?
+            .map(|builds| builds.latest)),
+        ServerLoader::Velocity => Ok(get_papermc_versions("velocity").await?.into_iter().next()),
     }
 }
 
@@ -143,6 +215,15 @@ pub async fn install_server(
             };
             install_quilt(server_dir, mc_version, &loader_ver, java_path).await
         }
+        ServerLoader::Paper
+        | ServerLoader::Purpur
+        | ServerLoader::Folia
+        | ServerLoader::Velocity => {
+            let downloader =
+                SmartDownloader::headless().map_err(|e| ServerError::Network(e.to_string()))?;
+            install_plugin_platform(server_dir, loader, mc_version, loader_version, &downloader)
+                .await
+        }
     }
 }
 
@@ -738,6 +819,282 @@ async fn install_quilt(
     })
 }
 
+// ============================================================================
+// Paper / Purpur / Folia / Velocity
+// ============================================================================
+
+/// Build of a PaperMC project (Fill API v3)
+#[derive(Debug, Clone, serde::Deserialize)]
+struct PaperMcBuild {
+    id: u32,
+    channel: String,
+    downloads: HashMap<String, PaperMcDownload>,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct PaperMcDownload {
+    name: String,
+    checksums: PaperMcChecksums,
+    url: String,
+}
+
+#[derive(Debug, Clone, serde::Deserialize)]
+struct PaperMcChecksums {
+    sha256: String,
+}
+
+/// Builds of a Purpur version
+#[derive(Debug, Clone, serde::Deserialize)]
+struct PurpurBuilds {
+    latest: String,
+    all: Vec<String>,
+}
+
+/// Resolved platform JAR download
+struct PlatformDownload {
+    url: String,
+    file_name: String,
+    /// SHA256 (PaperMC) or MD5 (Purpur)
+    hash: String,
+    /// Value stored as loader version (build, or Velocity version)
+    loader_version: String,
+}
+
+fn papermc_project(loader: ServerLoader) -> &'static str {
+    match loader {
+        ServerLoader::Folia => "folia",
+        ServerLoader::Velocity => "velocity",
+        _ => "paper",
+    }
+}
+
+/// Builds for a project version, newest first. Empty if the version is unknown.
+async fn get_papermc_builds(project: &str, version: &str) -> ServerResult<Vec<PaperMcBuild>> {
+    let url = format!("{}/{}/versions/{}/builds", PAPERMC_API, project, version);
+    let mut builds: Vec<PaperMcBuild> = fetch_api_json(&url).await?.unwrap_or_default();
+    builds.sort_by(|a, b| b.id.cmp(&a.id));
+    Ok(builds)
+}
+
+/// All versions of a project, newest first
+async fn get_papermc_versions(project: &str) -> ServerResult<Vec<String>> {
+    let url = format!("{}/{}", PAPERMC_API, project);
+    let data: serde_json::Value = fetch_api_json(&url)
+        .await?
+        .ok_or_else(|| ServerError::NotFound(format!("PaperMC project {}", project)))?;
+
+    // {"versions": {"3.4.0": ["3.4.0-SNAPSHOT"], ...}}
+    let mut versions: Vec<String> = data["versions"]
+        .as_object()
+        .map(|groups| {
+            groups
+                .values()
+                .filter_map(|v| v.as_array())
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .map(String::from)
+                .collect()
+        })
+        .unwrap_or_default();
+    versions.sort_by(|a, b| compare_platform_versions(b, a));
+    Ok(versions)
+}
+
+async fn get_purpur_builds(mc_version: &str) -> ServerResult<Option<PurpurBuilds>> {
+    let url = format!("{}/{}", PURPUR_API, mc_version);
+    let data: Option<serde_json::Value> = fetch_api_json(&url).await?;
+    Ok(data.and_then(|d| serde_json::from_value(d["builds"].clone()).ok()))
+}
+
+async fn resolve_papermc_download(
+    loader: ServerLoader,
+    mc_version: &str,
+    loader_version: Option<&str>,
+) -> ServerResult<PlatformDownload> {
+    let project = papermc_project(loader);
+
+    // Velocity is versioned on its own, builds are always the latest one
+    let (version, build_id) = if loader.is_proxy() {
+        let version = match loader_version {
+            Some(v) => v.to_string(),
+            None => get_papermc_versions(project)
+                .await?
+                .into_iter()
+                .next()
+                .ok_or_else(|| ServerError::NotFound("No Velocity versions".into()))?,
+        };
+        (version, None)
+    } else {
+        (mc_version.to_string(), loader_version)
+    };
+
+    let builds = get_papermc_builds(project, &version).await?;
+    let build = match build_id {
+        Some(id) => builds.iter().find(|b| b.id.to_string() == id),
+        None => builds
+            .iter()
+            .find(|b| b.channel.eq_ignore_ascii_case("stable"))
+            .or_else(|| builds.first()),
+    }
+    .ok_or_else(|| {
+        ServerError::NotFound(format!(
+            "No {} build {} for {}",
+            loader,
+            build_id.unwrap_or("(latest)"),
+            version
+        ))
+    })?;
+
+    let download = build
+        .downloads
+        .get("server:default")
+        .or_else(|| build.downloads.values().next())
+        .ok_or_else(|| {
+            ServerError::NotFound(format!("{} build {} has no files", loader, build.id))
+        })?;
+
+    Ok(PlatformDownload {
+        url: download.url.clone(),
+        file_name: download.name.clone(),
+        hash: download.checksums.sha256.clone(),
+        loader_version: if loader.is_proxy() {
+            version
+        } else {
+            build.id.to_string()
+        },
+    })
+}
+
+async fn resolve_purpur_download(
+    mc_version: &str,
+    loader_version: Option<&str>,
+) -> ServerResult<PlatformDownload> {
+    let build = match loader_version {
+        Some(b) => b.to_string(),
+        None => get_purpur_builds(mc_version)
+            .await?
+            .map(|builds| builds.latest)
+            .ok_or_else(|| ServerError::NotFound(format!("No Purpur for MC {}", mc_version)))?,
+    };
+
+    let info_url = format!("{}/{}/{}", PURPUR_API, mc_version, build);
+    let info: serde_json::Value = fetch_api_json(&info_url).await?.ok_or_else(|| {
+        ServerError::NotFound(format!("Purpur build {} for {}", build, mc_version))
+    })?;
+
+    if info["result"].as_str().is_some_and(|r| r != "SUCCESS") {
+        return Err(ServerError::NotFound(format!(
+            "Purpur build {} for {} is a failed build",
+            build, mc_version
+        )));
+    }
+    let hash = info["md5"]
+        .as_str()
+        .ok_or_else(|| ServerError::Config(format!("Purpur build {} has no checksum", build)))?;
+
+    Ok(PlatformDownload {
+        url: format!("{}/download", info_url),
+        file_name: format!("purpur-{}-{}.jar", mc_version, build),
+        hash: hash.to_string(),
+        loader_version: build,
+    })
+}
+
+/// Install Paper, Purpur, Folia or Velocity.
+///
+/// The platform JAR is downloaded as is (paperclip fetches the Mojang server on first start)
+/// and verified by the checksum published by the API.
+pub async fn install_plugin_platform(
+    server_dir: &Path,
+    loader: ServerLoader,
+    mc_version: &str,
+    loader_version: Option<&str>,
+    downloader: &SmartDownloader,
+) -> ServerResult<InstallResult> {
+    log::info!(
+        "Installing {} server: MC {} build {}",
+        loader,
+        mc_version,
+        loader_version.unwrap_or("latest")
+    );
+
+    let download = match loader {
+        ServerLoader::Purpur => resolve_purpur_download(mc_version, loader_version).await?,
+        ServerLoader::Paper | ServerLoader::Folia | ServerLoader::Velocity => {
+            resolve_papermc_download(loader, mc_version, loader_version).await?
+        }
+        _ => {
+            return Err(ServerError::Config(format!(
+                "{} is not a plugin platform",
+                loader
+            )))
+        }
+    };
+
+    fs::create_dir_all(server_dir).await?;
+    let server_jar = server_dir.join(&download.file_name);
+    downloader
+        .download(
+            &download.url,
+            &server_jar,
+            &download.file_name,
+            Some(&download.hash),
+        )
+        .await
+        .map_err(|e| ServerError::InstallerError(e.to_string()))?;
+
+    // Remove JARs of previous builds so the launcher does not pick an old one
+    if let Ok(mut entries) = fs::read_dir(server_dir).await {
+        let prefix = format!("{}-", loader);
+        while let Ok(Some(entry)) = entries.next_entry().await {
+            let name = entry.file_name().to_string_lossy().to_string();
+            if name != download.file_name && name.starts_with(&prefix) && name.ends_with(".jar") {
+                let _ = fs::remove_file(entry.path()).await;
+            }
+        }
+    }
+    fs::create_dir_all(server_dir.join("plugins")).await?;
+
+    log::info!("{} server installed at {:?}", loader, server_jar);
+
+    let mut java_args = vec!["-jar".to_string(), download.file_name.clone()];
+    if !loader.is_proxy() {
+        java_args.push("nogui".into());
+    }
+
+    Ok(InstallResult {
+        server_jar,
+        loader,
+        minecraft_version: mc_version.to_string(),
+        loader_version: Some(download.loader_version),
+        java_args,
+    })
+}
+
+/// Find the installed platform JAR (`paper-1.21.1-123.jar`, `velocity-3.4.0-SNAPSHOT-436.jar`, ...)
+pub fn find_platform_jar(server_dir: &Path, loader: ServerLoader) -> Option<PathBuf> {
+    let prefix = format!("{}-", loader);
+    std::fs::read_dir(server_dir)
+        .ok()?
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let name = e.file_name().to_string_lossy().to_lowercase();
+            name.starts_with(&prefix) && name.ends_with(".jar") && !name.contains("installer")
+        })
+        .max_by_key(|e| e.metadata().and_then(|m| m.modified()).ok())
+        .map(|e| e.path())
+}
+
+/// Compare versions like `3.4.0-SNAPSHOT` / `1.21.4`: numbers first, release above snapshot
+fn compare_platform_versions(a: &str, b: &str) -> std::cmp::Ordering {
+    let parse = |v: &str| -> (Vec<u32>, bool) {
+        let (numbers, suffix) = v.split_once('-').unwrap_or((v, ""));
+        let parts = numbers.split('.').filter_map(|p| p.parse().ok()).collect();
+        (parts, suffix.is_empty())
+    };
+    parse(a).cmp(&parse(b))
+}
+
 // ============================================================================
 // Helpers
 // ============================================================================
@@ -768,6 +1125,32 @@ async fn download_file(url: &str, path: &Path) -> ServerResult<()> {
     Ok(())
 }
 
+/// GET JSON from an API. `None` on 404 (unknown version/build).
+async fn fetch_api_json<T: serde::de::DeserializeOwned>(url: &str) -> ServerResult<Option<T>> {
+    let response = SERVER_HTTP_CLIENT
+        .get(url)
+        .send()
+        .await
+        .map_err(|e| ServerError::Network(e.to_string()))?;
+
+    if response.status() == reqwest::StatusCode::NOT_FOUND {
+        return Ok(None);
+    }
+    if !response.status().is_success() {
+        return Err(ServerError::Network(format!(
+            "{} returned {}",
+            url,
+            response.status()
+        )));
+    }
+
+    response
+        .json()
+        .await
+        .map(Some)
+        .map_err(|e| ServerError::Network(e.to_string()))
+}
+
 /// Get available loader versions for a Minecraft version
 pub async fn get_available_loader_versions(
     loader: ServerLoader,
@@ -808,6 +1191,15 @@ pub async fn get_available_loader_versions(
                 .map(String::from)
                 .collect())
         }
+        ServerLoader::Paper | ServerLoader::Folia => {
+            let builds = get_papermc_builds(papermc_project(loader), mc_version).await?;
+            Ok(builds.iter().map(|b| b.id.to_string()).collect())
+        }
+        ServerLoader::Purpur => Ok(get_purpur_builds(mc_version)
+            .await?
+            .map(|builds| builds.all.into_iter().rev().collect())
+            .unwrap_or_default()),
+        ServerLoader::Velocity => get_papermc_versions("velocity").await,
         ServerLoader::Forge | ServerLoader::NeoForge => {
             // These require more complex version listing
             // For now, just return the latest
@@ -868,3 +1260,48 @@ pub async fn get_latest_loader(
         .await
         .map_err(|e| e.to_string())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_loader_roundtrip() {
+        for loader in [
+            ServerLoader::Paper,
+            ServerLoader::Purpur,
+            ServerLoader::Folia,
+            ServerLoader::Velocity,
+        ] {
+            assert_eq!(loader.to_string().parse::<ServerLoader>().unwrap(), loader);
+            assert!(loader.is_plugin_platform());
+            assert_eq!(
+                ServerLoader::from(&LoaderType::parse(&loader.to_string()).unwrap()),
+                loader
+            );
+        }
+        assert!(!ServerLoader::Fabric.is_plugin_platform());
+        assert!(ServerLoader::Velocity.is_proxy());
+        assert!(!ServerLoader::Paper.is_proxy());
+    }
+
+    #[test]
+    fn test_compare_platform_versions() {
+        let mut versions = vec![
+            "3.3.0-SNAPSHOT",
+            "3.4.0",
+            "3.4.0-SNAPSHOT",
+            "3.10.0-SNAPSHOT",
+        ];
+        versions.sort_by(|a, b| compare_platform_versions(b, a));
+        assert_eq!(
+            versions,
+            vec![
+                "3.10.0-SNAPSHOT",
+                "3.4.0",
+                "3.4.0-SNAPSHOT",
+                "3.3.0-SNAPSHOT"
+            ]
+        );
+    }
+}
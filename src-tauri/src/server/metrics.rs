@@ -357,6 +357,58 @@ pub fn parse_memory_output(output: &str) -> Option<(u64, u64)> {
     }
 }
 
+/// Player count and names from the running server itself.
+/// Query (if `enable-query=true`) gives the full list, Server List Ping only a sample.
+async fn collect_player_metrics(instance_id: &str) -> PlayerMetrics {
+    let server_dir = crate::paths::instance_dir(instance_id);
+    let (host, port, query_port) = match super::properties::load_properties(&server_dir).await {
+        Ok(props) => {
+            let host = props
+                .get("server-ip")
+                .filter(|ip| !ip.is_empty() && ip.as_str() != "0.0.0.0")
+                .cloned()
+                .unwrap_or_else(|| "127.0.0.1".to_string());
+            let query_port = (props.get_bool("enable-query") == Some(true)).then(|| {
+                props
+                    .get_i32("query.port")
+                    .map_or(props.port(), |p| p as u16)
+            });
+            (host, props.port(), query_port)
+        }
+        // Velocity has no server.properties
+        Err(_) => match crate::instances::lifecycle::get_instance(instance_id.to_string()).await {
+            Ok(instance) => (
+                "127.0.0.1".to_string(),
+                instance
+                    .port
+                    .map_or(super::ping::DEFAULT_PORT, |p| p as u16),
+                None,
+            ),
+            Err(_) => return PlayerMetrics::default(),
+        },
+    };
+
+    if let Some(query_port) = query_port {
+        if let Ok(result) = super::query::query(&host, query_port).await {
+            return PlayerMetrics {
+                online: result.online_players,
+                max: result.max_players,
+                players: result.players,
+            };
+        }
+    }
+
+    match super::ping::ping(&host, port).await {
+        Ok(result) => PlayerMetrics {
+            online: result.online_players,
+            max: result.max_players,
+            players: result.sample.into_iter().map(|p| p.name).collect(),
+        },
+        // Server is still starting or not accepting connections
+        Err(_) => PlayerMetrics::default(),
+    }
+}
+
 // Tauri commands
 #[tauri::command]
 pub async fn get_server_metrics(instance_id: String) -> Result<Option<ServerMetrics>, String> {
@@ -376,6 +428,9 @@ pub async fn get_tps_history(instance_id: String) -> Result<Vec<TpsData>, String
 
 #[tauri::command]
 pub async fn collect_server_metrics(instance_id: String) -> Result<ServerMetrics, String> {
+    // Before taking the collector lock: the ping may wait for the server
+    let players = collect_player_metrics(&instance_id).await;
+
     let collector = get_collector(&instance_id).await;
     let mut collector = collector.write().await;
 
@@ -392,7 +447,7 @@ pub async fn collect_server_metrics(instance_id: String) -> Result<ServerMetrics
         uptime_seconds: uptime,
         tps,
         memory,
-        players: PlayerMetrics::default(),
+        players,
         world: WorldMetrics::default(),
         dimensions: Vec::new(),
         mspt: None,
@@ -2,25 +2,39 @@
 //!
 //! This module provides comprehensive server functionality:
 //! - Server instance management (create, start, stop, delete)
-//! - Loader installation (Fabric, Forge, NeoForge, Quilt, Vanilla)
+//! - Loader installation (Fabric, Forge, NeoForge, Quilt, Vanilla, Paper, Purpur, Folia, Velocity)
+//! - Plugin management for Paper/Purpur/Folia/Velocity (Modrinth, Hangar)
 //! - Real-time console with log streaming
 //! - RCON client for remote commands
+//! - Server List Ping and GameSpy4 Query clients (status of any server)
 //! - server.properties parser and editor
+//! - Resource pack hosting (built from the server folder, sha1 wired into server.properties)
 //! - EULA handling
-//! - Client mod detection and auto-disable
+//! - Client mod detection (API metadata + jar bytecode scan) and auto-disable
 //! - Player management (whitelist, ops, bans)
+//! - Player sessions and playtime analytics (who was online and when)
+//! - Server networks: Velocity proxy + backends (ports, forwarding, ordered start/stop)
 //! - Server import from existing directories
 //! - Detailed metrics for debugging (TPS, RAM, players, entities)
 
+pub mod admin_api;
 pub mod client_mods;
 pub mod console;
 pub mod eula;
 pub mod import;
 pub mod installer;
 pub mod metrics;
+pub mod network;
+pub mod ping;
 pub mod players;
+pub mod plugins;
 pub mod properties;
+pub mod query;
 pub mod rcon;
+pub mod resource_pack;
+pub mod sessions;
+pub mod side_scan;
+pub mod tasks;
 
 use tauri::AppHandle;
 
@@ -31,9 +45,15 @@ pub use eula::EulaStatus;
 pub use import::{DetectedServer, ImportResult};
 pub use installer::{InstallResult, ServerLoader};
 pub use metrics::{MemoryMetrics, PlayerMetrics, ServerMetrics, TpsData, WorldMetrics};
+pub use network::{NetworkManager, ServerNetwork};
+pub use ping::ServerPingResult;
 pub use players::{BannedIp, BannedPlayer, OpEntry, PlayerManagement, WhitelistEntry};
+pub use plugins::{InstalledPlugin, PluginManager};
 pub use properties::{ServerProperties, ServerPropertiesUI};
+pub use query::QueryResult;
 pub use rcon::RconClient;
+pub use resource_pack::{ResourcePackHost, ResourcePackManager};
+pub use sessions::{PlayerSession, PlayerSessionManager, PlayerSummary};
 
 /// Initialize server module
 pub fn init(app: &AppHandle) {
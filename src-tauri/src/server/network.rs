@@ -0,0 +1,1117 @@
+//! Server networks: Velocity proxy + backend servers
+//!
+//! Сеть объединяет прокси и несколько бэкендов (лобби, выживание, креатив...).
+//! `apply` раздаёт бэкендам порты, пишет forwarding secret, секцию `[servers]`
+//! в velocity.toml и `proxies.velocity` в конфиг Paper. Запуск идёт от бэкендов
+//! к прокси, остановка — в обратном порядке. Whitelist и баны можно сделать общими
+//! для всех бэкендов (см. `players.rs`).
+
+use chrono::Utc;
+use rand::RngCore;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use super::console::{get_console, is_server_ready_line};
+use super::metrics::get_collector;
+use super::players;
+use super::properties::{load_properties, ServerProperties};
+use super::tasks::stop_and_wait;
+use super::ServerError;
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::instances::lifecycle::{get_instance, ChildMap};
+use crate::paths::instance_dir;
+use crate::types::{InstanceType, LoaderType};
+
+/// С какого порта раздавать порты бэкендам (как в velocity.toml по умолчанию)
+const FIRST_BACKEND_PORT: u16 = 30066;
+
+const FORWARDING_SECRET_FILE: &str = "forwarding.secret";
+
+/// Сколько ждать "Done (...)" от сервера при запуске сети (генерация мира бывает долгой)
+const READY_TIMEOUT: Duration = Duration::from_secs(600);
+
+/// Бэкенд в сети
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct NetworkMember {
+    pub instance_id: String,
+    /// Имя сервера в velocity.toml (`lobby`, `survival`)
+    pub name: String,
+    /// Порт бэкенда, назначается при `apply`
+    #[serde(default)]
+    pub port: Option<u16>,
+}
+
+/// Сеть серверов за прокси
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerNetwork {
+    pub id: String,
+    pub name: String,
+    pub proxy_instance_id: String,
+    /// Бэкенды в порядке подключения: первый — сервер по умолчанию
+    pub members: Vec<NetworkMember>,
+    /// Общие whitelist и баны для всех бэкендов
+    pub shared_player_lists: bool,
+    pub created_at: String,
+    pub updated_at: String,
+}
+
+/// Результат записи конфигов сети
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkApplyResult {
+    pub members: Vec<NetworkMember>,
+    pub proxy_port: u16,
+    /// Что не удалось настроить автоматически
+    pub warnings: Vec<String>,
+}
+
+/// Состояние одного сервера сети
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkMemberStatus {
+    pub instance_id: String,
+    pub name: String,
+    pub running: bool,
+    pub port: Option<u16>,
+    pub players_online: u32,
+    pub players: Vec<String>,
+    pub tps: Option<f64>,
+    pub mspt: Option<f64>,
+    pub heap_used: Option<u64>,
+}
+
+/// Сводное состояние сети
+#[derive(Debug, Clone, Serialize)]
+pub struct NetworkStatus {
+    pub network_id: String,
+    pub proxy: NetworkMemberStatus,
+    pub backends: Vec<NetworkMemberStatus>,
+    /// Сумма игроков на бэкендах
+    pub players_online: u32,
+    /// Худший TPS среди запущенных бэкендов
+    pub min_tps: Option<f64>,
+}
+
+pub struct NetworkManager;
+
+impl NetworkManager {
+    /// Создать таблицу сетей если не существует
+    fn ensure_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            r#"CREATE TABLE IF NOT EXISTS server_networks (
+                id TEXT PRIMARY KEY,
+                name TEXT NOT NULL,
+                proxy_instance_id TEXT NOT NULL,
+                members_json TEXT NOT NULL,
+                shared_player_lists INTEGER NOT NULL DEFAULT 1,
+                created_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL
+            )"#,
+            [],
+        )?;
+        Ok(())
+    }
+
+    pub fn list() -> Result<Vec<ServerNetwork>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, name, proxy_instance_id, members_json, shared_player_lists,
+                      created_at, updated_at
+               FROM server_networks ORDER BY created_at"#,
+        )?;
+        let networks = stmt
+            .query_map([], row_to_network)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(networks)
+    }
+
+    pub fn get(id: &str) -> Result<ServerNetwork> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        conn.query_row(
+            r#"SELECT id, name, proxy_instance_id, members_json, shared_player_lists,
+                      created_at, updated_at
+               FROM server_networks WHERE id = ?1"#,
+            [id],
+            row_to_network,
+        )
+        .optional()?
+        .ok_or_else(|| LauncherError::NotFound(format!("Server network {}", id)))
+    }
+
+    /// Сеть, в которую входит сервер (как прокси или как бэкенд)
+    pub fn find_by_instance(instance_id: &str) -> Result<Option<ServerNetwork>> {
+        Ok(Self::list()?.into_iter().find(|n| {
+            n.proxy_instance_id == instance_id
+                || n.members.iter().any(|m| m.instance_id == instance_id)
+        }))
+    }
+
+    pub async fn create(
+        name: String,
+        proxy_instance_id: String,
+        members: Vec<NetworkMember>,
+        shared_player_lists: bool,
+    ) -> Result<ServerNetwork> {
+        let id = uuid::Uuid::new_v4().to_string();
+        Self::validate(&id, &name, &proxy_instance_id, &members).await?;
+
+        let now = Utc::now().to_rfc3339();
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        conn.execute(
+            r#"INSERT INTO server_networks
+               (id, name, proxy_instance_id, members_json, shared_player_lists, created_at, updated_at)
+               VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?6)"#,
+            params![
+                id,
+                name.trim(),
+                proxy_instance_id,
+                serde_json::to_string(&members)?,
+                shared_player_lists,
+                now,
+            ],
+        )?;
+
+        Self::get(&id)
+    }
+
+    pub async fn update(network: ServerNetwork) -> Result<ServerNetwork> {
+        Self::validate(
+            &network.id,
+            &network.name,
+            &network.proxy_instance_id,
+            &network.members,
+        )
+        .await?;
+
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        let updated = conn.execute(
+            r#"UPDATE server_networks
+               SET name = ?1, proxy_instance_id = ?2, members_json = ?3,
+                   shared_player_lists = ?4, updated_at = ?5
+               WHERE id = ?6"#,
+            params![
+                network.name.trim(),
+                network.proxy_instance_id,
+                serde_json::to_string(&network.members)?,
+                network.shared_player_lists,
+                Utc::now().to_rfc3339(),
+                network.id,
+            ],
+        )?;
+        if updated == 0 {
+            return Err(LauncherError::NotFound(format!(
+                "Server network {}",
+                network.id
+            )));
+        }
+
+        Self::get(&network.id)
+    }
+
+    /// Удалить сеть. Серверы и их конфиги не трогаются.
+    pub fn delete(id: &str) -> Result<()> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        conn.execute("DELETE FROM server_networks WHERE id = ?1", [id])?;
+        Ok(())
+    }
+
+    async fn validate(
+        network_id: &str,
+        name: &str,
+        proxy_instance_id: &str,
+        members: &[NetworkMember],
+    ) -> Result<()> {
+        if name.trim().is_empty() {
+            return Err(LauncherError::InvalidConfig(
+                "Network name must not be empty".to_string(),
+            ));
+        }
+
+        let proxy = get_instance(proxy_instance_id.to_string()).await?;
+        if !matches!(proxy.loader, LoaderType::Velocity) {
+            return Err(LauncherError::InvalidConfig(format!(
+                "'{}' is not a Velocity proxy",
+                proxy.name
+            )));
+        }
+
+        let mut ids = HashSet::new();
+        let mut names = HashSet::new();
+        for member in members {
+            if !is_valid_server_name(&member.name) {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "Invalid server name '{}': use letters, digits, '-' and '_'",
+                    member.name
+                )));
+            }
+            if !names.insert(member.name.to_lowercase()) {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "Server name '{}' is used twice",
+                    member.name
+                )));
+            }
+            if member.instance_id == proxy_instance_id || !ids.insert(&member.instance_id) {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "Instance {} is added to the network twice",
+                    member.instance_id
+                )));
+            }
+
+            let instance = get_instance(member.instance_id.clone()).await?;
+            if !matches!(instance.instance_type, InstanceType::Server) {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "'{}' is not a server",
+                    instance.name
+                )));
+            }
+            if matches!(instance.loader, LoaderType::Velocity) {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "'{}' is a proxy and cannot be a backend",
+                    instance.name
+                )));
+            }
+        }
+
+        // Один сервер — одна сеть: иначе сети будут переписывать друг другу порты
+        for other in Self::list()?.iter().filter(|n| n.id != network_id) {
+            let taken = std::iter::once(proxy_instance_id)
+                .chain(members.iter().map(|m| m.instance_id.as_str()))
+                .find(|id| {
+                    other.proxy_instance_id == *id
+                        || other.members.iter().any(|m| m.instance_id == *id)
+                });
+            if let Some(id) = taken {
+                return Err(LauncherError::InvalidConfig(format!(
+                    "Instance {} already belongs to network '{}'",
+                    id, other.name
+                )));
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Папки серверов, между которыми общие whitelist и баны.
+    /// Для сервера вне сети (или с раздельными списками) — только его папка.
+    pub fn player_list_dirs(instance_id: &str) -> Vec<PathBuf> {
+        match Self::find_by_instance(instance_id) {
+            Ok(Some(network))
+                if network.shared_player_lists
+                    && network.members.iter().any(|m| m.instance_id == instance_id) =>
+            {
+                network
+                    .members
+                    .iter()
+                    .map(|m| instance_dir(&m.instance_id))
+                    .collect()
+            }
+            Ok(_) => vec![instance_dir(instance_id)],
+            Err(e) => {
+                log::warn!("Failed to look up network of {}: {}", instance_id, e);
+                vec![instance_dir(instance_id)]
+            }
+        }
+    }
+
+    /// Назначить порты и записать конфиги прокси и бэкендов
+    pub async fn apply(id: &str) -> Result<NetworkApplyResult> {
+        let mut network = Self::get(id)?;
+        let mut warnings = Vec::new();
+
+        let proxy = get_instance(network.proxy_instance_id.clone()).await?;
+        let proxy_port = proxy.port.map(|p| p as u16).unwrap_or(25565);
+
+        let backend_ids: HashSet<&str> = network
+            .members
+            .iter()
+            .map(|m| m.instance_id.as_str())
+            .collect();
+        let mut used = Self::reserved_ports(&backend_ids)?;
+        used.insert(proxy_port);
+        let ports = assign_ports(&network.members, &used);
+        for (member, port) in network.members.iter_mut().zip(ports) {
+            member.port = Some(port);
+        }
+
+        // Прокси: секрет и velocity.toml
+        let proxy_dir = instance_dir(&proxy.id);
+        let secret = Self::ensure_forwarding_secret(&proxy_dir).await?;
+        let velocity_toml = proxy_dir.join("velocity.toml");
+        let mut proxy_online_mode = true;
+        if tokio::fs::try_exists(&velocity_toml).await.unwrap_or(false) {
+            let content = tokio::fs::read_to_string(&velocity_toml).await?;
+            proxy_online_mode = toml_root_value(&content, "online-mode")
+                .map(|v| v != "false")
+                .unwrap_or(true);
+            let servers: Vec<(String, u16)> = network
+                .members
+                .iter()
+                .map(|m| (m.name.clone(), m.port.unwrap_or_default()))
+                .collect();
+            tokio::fs::write(
+                &velocity_toml,
+                rewrite_velocity_toml(&content, proxy_port, &servers),
+            )
+            .await?;
+        } else {
+            warnings.push(format!(
+                "{}: velocity.toml not found — start the proxy once and apply the network again",
+                proxy.name
+            ));
+        }
+
+        // Бэкенды: порт, online-mode=false, приём форвардинга от прокси
+        for member in &network.members {
+            let instance = get_instance(member.instance_id.clone()).await?;
+            let server_dir = instance_dir(&instance.id);
+            let port = member.port.unwrap_or_default();
+
+            let mut props = load_properties(&server_dir)
+                .await
+                .unwrap_or_else(|_| ServerProperties::default_properties());
+            props.set_i32("server-port", port as i32);
+            props.set_bool("online-mode", false);
+            // Бэкенд доступен только через прокси
+            props.set("server-ip", "127.0.0.1");
+            props
+                .save(server_dir.join("server.properties"))
+                .await
+                .map_err(server_error)?;
+
+            {
+                let conn = get_db_conn()?;
+                conn.execute(
+                    "UPDATE instances SET port = ?1 WHERE id = ?2",
+                    params![port as i32, instance.id],
+                )?;
+            }
+
+            if instance.loader.is_plugin_platform() {
+                if let Some(warning) =
+                    configure_paper_forwarding(&server_dir, &secret, proxy_online_mode).await?
+                {
+                    warnings.push(format!("{}: {}", instance.name, warning));
+                }
+            } else {
+                warnings.push(format!(
+                    "{}: {} has no built-in Velocity support — install a forwarding mod \
+                     (FabricProxy-Lite, Proxy-Compatible-Forge) and give it the secret from {}",
+                    instance.name,
+                    instance.loader.as_str(),
+                    FORWARDING_SECRET_FILE
+                ));
+            }
+        }
+
+        if network.shared_player_lists {
+            Self::sync_player_lists(&network).await?;
+        }
+
+        let network = Self::update(network).await?;
+        log::info!(
+            "Applied network '{}': proxy on {}, {} backends",
+            network.name,
+            proxy_port,
+            network.members.len()
+        );
+
+        Ok(NetworkApplyResult {
+            members: network.members,
+            proxy_port,
+            warnings,
+        })
+    }
+
+    /// Порты, занятые другими серверами и RCON
+    fn reserved_ports(backend_ids: &HashSet<&str>) -> Result<HashSet<u16>> {
+        let conn = get_db_conn()?;
+        let mut stmt = conn.prepare("SELECT id, port, rcon_port FROM instances")?;
+        let rows = stmt.query_map([], |row| {
+            Ok((
+                row.get::<_, String>(0)?,
+                row.get::<_, Option<i32>>(1)?,
+                row.get::<_, Option<i32>>(2)?,
+            ))
+        })?;
+
+        let mut used = HashSet::new();
+        for (id, port, rcon_port) in rows.filter_map(|r| r.ok()) {
+            // Порты бэкендов этой сети раздаются заново
+            if let Some(port) = port.filter(|_| !backend_ids.contains(id.as_str())) {
+                used.insert(port as u16);
+            }
+            if let Some(rcon_port) = rcon_port {
+                used.insert(rcon_port as u16);
+            }
+        }
+        Ok(used)
+    }
+
+    async fn ensure_forwarding_secret(proxy_dir: &Path) -> Result<String> {
+        let path = proxy_dir.join(FORWARDING_SECRET_FILE);
+        if let Ok(existing) = tokio::fs::read_to_string(&path).await {
+            let existing = existing.trim();
+            if !existing.is_empty() {
+                return Ok(existing.to_string());
+            }
+        }
+
+        let mut bytes = [0u8; 16];
+        rand::rng().fill_bytes(&mut bytes);
+        let secret = hex::encode(bytes);
+        tokio::fs::create_dir_all(proxy_dir).await?;
+        tokio::fs::write(&path, &secret).await?;
+        Ok(secret)
+    }
+
+    /// Объединить whitelist и баны всех бэкендов и записать каждому
+    pub async fn sync_player_lists(network: &ServerNetwork) -> Result<()> {
+        let dirs: Vec<PathBuf> = network
+            .members
+            .iter()
+            .map(|m| instance_dir(&m.instance_id))
+            .collect();
+
+        let mut whitelist: Vec<players::WhitelistEntry> = Vec::new();
+        let mut banned_players: Vec<players::BannedPlayer> = Vec::new();
+        let mut banned_ips: Vec<players::BannedIp> = Vec::new();
+        for dir in &dirs {
+            let lists = players::load_all(dir).await.map_err(server_error)?;
+            for entry in lists.whitelist {
+                if !whitelist.iter().any(|e| e.uuid == entry.uuid) {
+                    whitelist.push(entry);
+                }
+            }
+            for entry in lists.banned_players {
+                if !banned_players.iter().any(|e| e.uuid == entry.uuid) {
+                    banned_players.push(entry);
+                }
+            }
+            for entry in lists.banned_ips {
+                if !banned_ips.iter().any(|e| e.ip == entry.ip) {
+                    banned_ips.push(entry);
+                }
+            }
+        }
+
+        for dir in &dirs {
+            players::save_whitelist(dir, &whitelist)
+                .await
+                .map_err(server_error)?;
+            players::save_banned_players(dir, &banned_players)
+                .await
+                .map_err(server_error)?;
+            players::save_banned_ips(dir, &banned_ips)
+                .await
+                .map_err(server_error)?;
+        }
+        Ok(())
+    }
+
+    /// Запустить бэкенды по очереди, затем прокси
+    pub async fn start(app: &AppHandle, id: &str) -> Result<()> {
+        let network = Self::get(id)?;
+        for member in &network.members {
+            start_and_wait(app, &member.instance_id, READY_TIMEOUT).await?;
+        }
+        start_and_wait(app, &network.proxy_instance_id, READY_TIMEOUT).await?;
+        log::info!("Network '{}' started", network.name);
+        Ok(())
+    }
+
+    /// Остановить прокси, затем бэкенды в обратном порядке
+    pub async fn stop(app: &AppHandle, id: &str) -> Result<()> {
+        let network = Self::get(id)?;
+        let order = std::iter::once(network.proxy_instance_id.as_str())
+            .chain(network.members.iter().rev().map(|m| m.instance_id.as_str()));
+        for instance_id in order {
+            if get_console(instance_id).await.read().await.is_running() {
+                stop_and_wait(app, instance_id).await?;
+            }
+        }
+        log::info!("Network '{}' stopped", network.name);
+        Ok(())
+    }
+
+    pub async fn status(id: &str) -> Result<NetworkStatus> {
+        let network = Self::get(id)?;
+
+        let proxy = get_instance(network.proxy_instance_id.clone()).await?;
+        let proxy_status = member_status(
+            &network.proxy_instance_id,
+            &proxy.name,
+            proxy.port.map(|p| p as u16),
+        )
+        .await;
+
+        let mut backends = Vec::with_capacity(network.members.len());
+        for member in &network.members {
+            backends.push(member_status(&member.instance_id, &member.name, member.port).await);
+        }
+
+        Ok(NetworkStatus {
+            network_id: network.id,
+            players_online: backends.iter().map(|b| b.players_online).sum(),
+            min_tps: backends
+                .iter()
+                .filter_map(|b| b.tps)
+                .min_by(|a, b| a.total_cmp(b)),
+            proxy: proxy_status,
+            backends,
+        })
+    }
+}
+
+/// Запустить сервер и дождаться строки готовности
+pub(crate) async fn start_and_wait(
+    app: &AppHandle,
+    instance_id: &str,
+    timeout: Duration,
+) -> Result<()> {
+    if get_console(instance_id).await.read().await.is_running() {
+        return Ok(());
+    }
+
+    let since = Utc::now().timestamp_millis();
+    crate::instances::execution::start_instance(
+        instance_id.to_string(),
+        app.clone(),
+        app.state::<ChildMap>(),
+    )
+    .await?;
+
+    let deadline = tokio::time::Instant::now() + timeout;
+    let mut seen_running = false;
+    while tokio::time::Instant::now() < deadline {
+        tokio::time::sleep(Duration::from_secs(1)).await;
+
+        let console = get_console(instance_id).await;
+        let console = console.read().await;
+        if console
+            .get_logs_since(since)
+            .iter()
+            .any(|e| is_server_ready_line(&e.line))
+        {
+            return Ok(());
+        }
+        if console.is_running() {
+            seen_running = true;
+        } else if seen_running {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Server {} stopped during startup",
+                instance_id
+            )));
+        }
+    }
+
+    Err(LauncherError::InvalidConfig(format!(
+        "Server {} did not start within {:?}",
+        instance_id, timeout
+    )))
+}
+
+async fn member_status(instance_id: &str, name: &str, port: Option<u16>) -> NetworkMemberStatus {
+    let running = get_console(instance_id).await.read().await.is_running();
+    let metrics = if running {
+        get_collector(instance_id)
+            .await
+            .read()
+            .await
+            .get_last_metrics()
+            .cloned()
+    } else {
+        None
+    };
+
+    NetworkMemberStatus {
+        instance_id: instance_id.to_string(),
+        name: name.to_string(),
+        running,
+        port,
+        players_online: metrics.as_ref().map(|m| m.players.online).unwrap_or(0),
+        players: metrics
+            .as_ref()
+            .map(|m| m.players.players.clone())
+            .unwrap_or_default(),
+        tps: metrics.as_ref().and_then(|m| m.tps.as_ref().map(|t| t.tps)),
+        mspt: metrics.as_ref().and_then(|m| m.mspt),
+        heap_used: metrics.as_ref().map(|m| m.memory.heap_used),
+    }
+}
+
+/// Включить modern forwarding в конфиге Paper (1.19+: paper-global.yml, раньше: paper.yml).
+/// Возвращает предупреждение, если настроить не удалось.
+async fn configure_paper_forwarding(
+    server_dir: &Path,
+    secret: &str,
+    online_mode: bool,
+) -> Result<Option<String>> {
+    let candidates = [
+        (
+            server_dir.join("config").join("paper-global.yml"),
+            ["proxies", "velocity"],
+        ),
+        (
+            server_dir.join("paper.yml"),
+            ["settings", "velocity-support"],
+        ),
+    ];
+
+    for (path, section) in candidates {
+        if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+            continue;
+        }
+
+        let mut content = tokio::fs::read_to_string(&path).await?;
+        let values = [
+            ("enabled", "true".to_string()),
+            ("online-mode", online_mode.to_string()),
+            ("secret", format!("'{}'", secret)),
+        ];
+        for (key, value) in values {
+            let key_path = [section[0], section[1], key];
+            match set_yaml_value(&content, &key_path, &value) {
+                Some(updated) => content = updated,
+                None => {
+                    return Ok(Some(format!(
+                        "{} has no {} key — set it manually",
+                        path.display(),
+                        key_path.join(".")
+                    )))
+                }
+            }
+        }
+        tokio::fs::write(&path, content).await?;
+        return Ok(None);
+    }
+
+    Ok(Some(
+        "Paper config not found — start the server once and apply the network again".to_string(),
+    ))
+}
+
+/// Порты бэкендов: уже назначенный порт сохраняется, если он свободен
+fn assign_ports(members: &[NetworkMember], reserved: &HashSet<u16>) -> Vec<u16> {
+    let mut used = reserved.clone();
+    let mut kept: Vec<Option<u16>> = Vec::with_capacity(members.len());
+    for member in members {
+        let port = member.port.filter(|p| !used.contains(p));
+        if let Some(port) = port {
+            used.insert(port);
+        }
+        kept.push(port);
+    }
+
+    let mut next = FIRST_BACKEND_PORT;
+    kept.into_iter()
+        .map(|port| {
+            port.unwrap_or_else(|| {
+                while used.contains(&next) {
+                    next += 1;
+                }
+                used.insert(next);
+                next
+            })
+        })
+        .collect()
+}
+
+fn is_valid_server_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+/// Строка TOML: заголовок секции, `key = value` (возможно многострочный массив) или прочее
+enum TomlItem {
+    Header(String, String),
+    Entry(String, Vec<String>),
+    Other(String),
+}
+
+fn parse_toml_items(content: &str) -> Vec<TomlItem> {
+    let mut items = Vec::new();
+    let mut depth = 0i32;
+
+    for line in content.lines() {
+        if depth > 0 {
+            depth += bracket_delta(line);
+            if let Some(TomlItem::Entry(_, lines)) = items.last_mut() {
+                lines.push(line.to_string());
+            }
+            continue;
+        }
+
+        let trimmed = line.trim();
+        if trimmed.starts_with('[') {
+            let name = trimmed.trim_matches(|c| c == '[' || c == ']').trim();
+            items.push(TomlItem::Header(name.to_string(), line.to_string()));
+        } else if let Some((key, value)) = trimmed
+            .split_once('=')
+            .filter(|_| !trimmed.starts_with('#'))
+        {
+            depth = bracket_delta(value).max(0);
+            let key = key.trim().trim_matches('"').to_string();
+            items.push(TomlItem::Entry(key, vec![line.to_string()]));
+        } else {
+            items.push(TomlItem::Other(line.to_string()));
+        }
+    }
+
+    items
+}
+
+/// Баланс `[`/`]` вне строк и комментариев
+fn bracket_delta(s: &str) -> i32 {
+    let mut delta = 0;
+    let mut quote: Option<char> = None;
+    for c in s.chars() {
+        match (quote, c) {
+            (Some(q), c) if c == q => quote = None,
+            (Some(_), _) => {}
+            (None, '"' | '\'') => quote = Some(c),
+            (None, '#') => break,
+            (None, '[') => delta += 1,
+            (None, ']') => delta -= 1,
+            _ => {}
+        }
+    }
+    delta
+}
+
+/// Строки в кавычках из значения (`["lobby", "survival"]` -> lobby, survival)
+fn quoted_strings(lines: &[String]) -> Vec<String> {
+    let joined = lines.join("\n");
+    let value = joined.split_once('=').map(|(_, v)| v).unwrap_or("");
+    value
+        .split('"')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
+
+/// Значение ключа верхнего уровня без кавычек
+fn toml_root_value(content: &str, key: &str) -> Option<String> {
+    for item in parse_toml_items(content) {
+        match item {
+            TomlItem::Header(..) => return None,
+            TomlItem::Entry(k, lines) if k == key => {
+                let (_, value) = lines[0].split_once('=')?;
+                return Some(value.trim().trim_matches('"').to_string());
+            }
+            _ => {}
+        }
+    }
+    None
+}
+
+/// Переписать velocity.toml под сеть: bind, modern forwarding, `[servers]` и `try`.
+/// Остальной файл и комментарии сохраняются; forced-hosts на удалённые серверы убираются
+/// (иначе Velocity не запустится).
+fn rewrite_velocity_toml(content: &str, bind_port: u16, servers: &[(String, u16)]) -> String {
+    let root_values = [
+        ("bind", format!("\"0.0.0.0:{}\"", bind_port)),
+        ("player-info-forwarding-mode", "\"modern\"".to_string()),
+        (
+            "forwarding-secret-file",
+            format!("\"{}\"", FORWARDING_SECRET_FILE),
+        ),
+    ];
+    let known: HashSet<&str> = servers.iter().map(|(name, _)| name.as_str()).collect();
+
+    let mut servers_block: Vec<String> = servers
+        .iter()
+        .map(|(name, port)| format!("{} = \"127.0.0.1:{}\"", name, port))
+        .collect();
+    servers_block.push("try = [".to_string());
+    servers_block.extend(servers.iter().map(|(name, _)| format!("    \"{}\",", name)));
+    servers_block.push("]".to_string());
+
+    let mut out: Vec<String> = Vec::new();
+    let mut section: Option<String> = None;
+    let mut written_root: HashSet<&str> = HashSet::new();
+    let mut servers_written = false;
+
+    // Закрыть текущую секцию: дописать недостающее перед хвостом из пустых строк
+    let close_section = |out: &mut Vec<String>,
+                         section: &Option<String>,
+                         written_root: &HashSet<&str>,
+                         servers_written: &mut bool| {
+        let trailing = out.iter().rev().take_while(|l| l.trim().is_empty()).count();
+        let at = out.len() - trailing;
+        let insert: Vec<String> = match section.as_deref() {
+            None => root_values
+                .iter()
+                .filter(|(key, _)| !written_root.contains(key))
+                .map(|(key, value)| format!("{} = {}", key, value))
+                .collect(),
+            Some("servers") => {
+                *servers_written = true;
+                servers_block.clone()
+            }
+            _ => Vec::new(),
+        };
+        let tail = out.split_off(at);
+        out.extend(insert);
+        out.extend(tail);
+    };
+
+    for item in parse_toml_items(content) {
+        match item {
+            TomlItem::Header(name, raw) => {
+                close_section(&mut out, &section, &written_root, &mut servers_written);
+                section = Some(name);
+                out.push(raw);
+            }
+            TomlItem::Entry(key, lines) => match section.as_deref() {
+                None => match root_values.iter().find(|(k, _)| *k == key) {
+                    Some((k, value)) => {
+                        written_root.insert(*k);
+                        out.push(format!("{} = {}", k, value));
+                    }
+                    None => out.extend(lines),
+                },
+                Some("servers") => {}
+                Some("forced-hosts") => {
+                    if quoted_strings(&lines)
+                        .iter()
+                        .all(|name| known.contains(name.as_str()))
+                    {
+                        out.extend(lines);
+                    }
+                }
+                _ => out.extend(lines),
+            },
+            TomlItem::Other(line) => out.push(line),
+        }
+    }
+    close_section(&mut out, &section, &written_root, &mut servers_written);
+
+    if !servers_written {
+        if out.last().is_some_and(|l| !l.trim().is_empty()) {
+            out.push(String::new());
+        }
+        out.push("[servers]".to_string());
+        out.extend(servers_block.iter().cloned());
+    }
+
+    let mut result = out.join("\n");
+    result.push('\n');
+    result
+}
+
+/// Заменить значение ключа YAML по пути (`proxies.velocity.secret`), не трогая остальной файл.
+/// None — ключа нет.
+fn set_yaml_value(content: &str, path: &[&str], value: &str) -> Option<String> {
+    let mut lines: Vec<String> = content.lines().map(str::to_string).collect();
+    let mut stack: Vec<(usize, String)> = Vec::new();
+
+    let index = lines.iter().position(|line| {
+        let trimmed = line.trim_start();
+        if trimmed.is_empty() || trimmed.starts_with('#') || trimmed.starts_with('-') {
+            return false;
+        }
+        let Some((key, _)) = trimmed.split_once(':') else {
+            return false;
+        };
+        let indent = line.len() - trimmed.len();
+        while stack.last().is_some_and(|(i, _)| *i >= indent) {
+            stack.pop();
+        }
+        stack.push((indent, key.trim().trim_matches('\'').to_string()));
+        stack.len() == path.len() && stack.iter().zip(path).all(|((_, k), p)| k == p)
+    })?;
+
+    let (indent, key) = stack.pop()?;
+    lines[index] = format!("{}{}: {}", " ".repeat(indent), key, value);
+
+    let mut result = lines.join("\n");
+    if content.ends_with('\n') {
+        result.push('\n');
+    }
+    Some(result)
+}
+
+fn server_error(e: ServerError) -> LauncherError {
+    LauncherError::InvalidConfig(e.to_string())
+}
+
+fn row_to_network(row: &rusqlite::Row) -> rusqlite::Result<ServerNetwork> {
+    let members_json: String = row.get(3)?;
+
+    Ok(ServerNetwork {
+        id: row.get(0)?,
+        name: row.get(1)?,
+        proxy_instance_id: row.get(2)?,
+        members: serde_json::from_str(&members_json).map_err(|e| {
+            rusqlite::Error::FromSqlConversionFailure(3, rusqlite::types::Type::Text, Box::new(e))
+        })?,
+        shared_player_lists: row.get(4)?,
+        created_at: row.get(5)?,
+        updated_at: row.get(6)?,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_server_networks() -> Result<Vec<ServerNetwork>> {
+    NetworkManager::list()
+}
+
+#[tauri::command]
+pub async fn create_server_network(
+    name: String,
+    proxy_instance_id: String,
+    members: Vec<NetworkMember>,
+    shared_player_lists: bool,
+) -> Result<ServerNetwork> {
+    NetworkManager::create(name, proxy_instance_id, members, shared_player_lists).await
+}
+
+#[tauri::command]
+pub async fn update_server_network(network: ServerNetwork) -> Result<ServerNetwork> {
+    NetworkManager::update(network).await
+}
+
+#[tauri::command]
+pub async fn delete_server_network(network_id: String) -> Result<()> {
+    NetworkManager::delete(&network_id)
+}
+
+#[tauri::command]
+pub async fn apply_server_network(network_id: String) -> Result<NetworkApplyResult> {
+    NetworkManager::apply(&network_id).await
+}
+
+#[tauri::command]
+pub async fn start_server_network(app_handle: AppHandle, network_id: String) -> Result<()> {
+    NetworkManager::start(&app_handle, &network_id).await
+}
+
+#[tauri::command]
+pub async fn stop_server_network(app_handle: AppHandle, network_id: String) -> Result<()> {
+    NetworkManager::stop(&app_handle, &network_id).await
+}
+
+#[tauri::command]
+pub async fn get_server_network_status(network_id: String) -> Result<NetworkStatus> {
+    NetworkManager::status(&network_id).await
+}
+
+#[tauri::command]
+pub async fn sync_network_player_lists(network_id: String) -> Result<()> {
+    let network = NetworkManager::get(&network_id)?;
+    NetworkManager::sync_player_lists(&network).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_velocity_toml() {
+        let content = r#"config-version = "2.7"
+bind = "0.0.0.0:25577"
+motd = "[Hub] network"
+player-info-forwarding-mode = "NONE"
+
+[servers]
+# Configure your servers here.
+lobby = "127.0.0.1:30066"
+factions = "127.0.0.1:30067"
+try = [
+    "lobby"
+]
+
+[forced-hosts]
+"lobby.example.com" = [
+    "lobby"
+]
+"factions.example.com" = [
+    "factions"
+]
+
+[advanced]
+compression-threshold = 256
+"#;
+        let servers = vec![
+            ("lobby".to_string(), 30066),
+            ("survival".to_string(), 30068),
+        ];
+        let result = rewrite_velocity_toml(content, 25565, &servers);
+
+        assert!(result.contains("bind = \"0.0.0.0:25565\""));
+        assert!(result.contains("motd = \"[Hub] network\""));
+        assert!(result.contains("player-info-forwarding-mode = \"modern\""));
+        assert!(result.contains("forwarding-secret-file = \"forwarding.secret\""));
+        assert!(result.contains("# Configure your servers here."));
+        assert!(result.contains("survival = \"127.0.0.1:30068\""));
+        assert!(!result.contains("factions"));
+        assert!(result.contains("\"lobby.example.com\" = [\n    \"lobby\"\n]"));
+        assert!(result.contains("try = [\n    \"lobby\",\n    \"survival\",\n]\n\n[forced-hosts]"));
+        assert!(result.contains("compression-threshold = 256"));
+        // Ключи верхнего уровня остаются до первой секции
+        let forwarding = result.find("forwarding-secret-file").unwrap();
+        assert!(forwarding < result.find("[servers]").unwrap());
+
+        assert_eq!(
+            toml_root_value(content, "bind").as_deref(),
+            Some("0.0.0.0:25577")
+        );
+        assert_eq!(toml_root_value(content, "compression-threshold"), None);
+    }
+
+    #[test]
+    fn test_set_yaml_value() {
+        let content = "_version: 29\nproxies:\n  bungee-cord:\n    online-mode: true\n  velocity:\n    enabled: false\n    online-mode: false\n    secret: ''\nscheduling:\n  enabled: true\n";
+
+        let updated = set_yaml_value(content, &["proxies", "velocity", "enabled"], "true").unwrap();
+        let updated =
+            set_yaml_value(&updated, &["proxies", "velocity", "secret"], "'abc'").unwrap();
+
+        assert!(updated.contains(
+            "  velocity:\n    enabled: true\n    online-mode: false\n    secret: 'abc'\n"
+        ));
+        assert!(updated.contains("  bungee-cord:\n    online-mode: true\n"));
+        assert!(updated.contains("scheduling:\n  enabled: true\n"));
+        assert!(set_yaml_value(content, &["proxies", "waterfall", "enabled"], "true").is_none());
+    }
+
+    #[test]
+    fn test_assign_ports() {
+        let member = |port| NetworkMember {
+            instance_id: String::new(),
+            name: String::new(),
+            port,
+        };
+        let reserved: HashSet<u16> = [30066, 30068].into_iter().collect();
+
+        let ports = assign_ports(
+            &[
+                member(None),
+                member(Some(30070)),
+                member(Some(30068)),
+                member(None),
+            ],
+            &reserved,
+        );
+        assert_eq!(ports, vec![30067, 30070, 30069, 30071]);
+    }
+}
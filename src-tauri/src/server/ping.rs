@@ -0,0 +1,591 @@
+//! Server List Ping client
+//!
+//! Тот же запрос, что делает список серверов в игре: MOTD, иконка, версия,
+//! онлайн и несколько ников. Работает для любого адреса, не только для своих серверов.
+//! Современный протокол (1.7+) с откатом на legacy-пинг 1.6 (`0xFE 0x01`).
+//!
+//! SRV-записи (`_minecraft._tcp`) не резолвятся: для таких адресов нужен явный порт.
+
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::path::Path;
+use std::time::{Duration, Instant};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+
+use super::{ServerError, ServerResult};
+use crate::worlds::nbt::{self, Tag};
+
+pub const DEFAULT_PORT: u16 = 25565;
+
+/// Таймаут на подключение и ответ
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Версия протокола в handshake: -1 — сервер ответит своей
+const HANDSHAKE_PROTOCOL: i32 = -1;
+
+/// Ответ с модлистом Forge бывает большим, но не мегабайты
+const MAX_PACKET_LEN: usize = 2 * 1024 * 1024;
+
+/// Протокол 1.6.4 для legacy-пинга
+const LEGACY_PROTOCOL: u8 = 74;
+
+/// Игрок из выборки в ответе сервера
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct PlayerSample {
+    pub name: String,
+    pub id: String,
+}
+
+/// Ответ на Server List Ping
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerPingResult {
+    /// "1.21.1", "Paper 1.21.1", "Velocity 3.3.0-SNAPSHOT"
+    pub version_name: String,
+    pub protocol: i32,
+    pub online_players: u32,
+    pub max_players: u32,
+    /// Несколько игроков онлайн (сервер отдаёт не больше ~12)
+    pub sample: Vec<PlayerSample>,
+    /// MOTD без кодов форматирования
+    pub motd: String,
+    /// MOTD как есть (строка или chat component) — для цветного отображения
+    pub description: Value,
+    /// data:image/png;base64,...
+    pub favicon: Option<String>,
+    pub latency_ms: Option<u32>,
+    /// Ответил только на legacy-пинг (сервер до 1.7)
+    pub legacy: bool,
+}
+
+/// "host", "host:port", "[::1]:port" -> (host, port)
+pub fn parse_address(address: &str) -> ServerResult<(String, u16)> {
+    let address = address.trim();
+    let invalid = || ServerError::Config(format!("Invalid server address: '{}'", address));
+
+    let (host, port) = if let Some(rest) = address.strip_prefix('[') {
+        let (host, rest) = rest.split_once(']').ok_or_else(invalid)?;
+        match rest.strip_prefix(':') {
+            Some(port) => (host, Some(port)),
+            None if rest.is_empty() => (host, None),
+            None => return Err(invalid()),
+        }
+    } else {
+        match address.rsplit_once(':') {
+            // Голый IPv6 без скобок — это хост без порта
+            Some((host, _)) if host.contains(':') => (address, None),
+            Some((host, port)) => (host, Some(port)),
+            None => (address, None),
+        }
+    };
+
+    if host.is_empty() {
+        return Err(invalid());
+    }
+    let port = match port {
+        Some(port) => port.parse().map_err(|_| invalid())?,
+        None => DEFAULT_PORT,
+    };
+    Ok((host.to_string(), port))
+}
+
+/// Опросить сервер. Сначала современный протокол, при неудаче — legacy.
+pub async fn ping(host: &str, port: u16) -> ServerResult<ServerPingResult> {
+    let modern = with_timeout(ping_modern(host, port)).await;
+    if modern.is_ok() {
+        return modern;
+    }
+
+    // Серверы до 1.7 не понимают handshake и закрывают соединение
+    match with_timeout(ping_legacy(host, port)).await {
+        Ok(result) => Ok(result),
+        Err(_) => modern,
+    }
+}
+
+async fn with_timeout<T>(
+    future: impl std::future::Future<Output = ServerResult<T>>,
+) -> ServerResult<T> {
+    tokio::time::timeout(TIMEOUT, future)
+        .await
+        .map_err(|_| ServerError::Network("Server did not respond in time".to_string()))?
+}
+
+async fn ping_modern(host: &str, port: u16) -> ServerResult<ServerPingResult> {
+    let mut stream = connect(host, port).await?;
+
+    let mut handshake = Vec::new();
+    write_varint(&mut handshake, 0x00);
+    write_varint(&mut handshake, HANDSHAKE_PROTOCOL);
+    write_string(&mut handshake, host);
+    handshake.extend_from_slice(&port.to_be_bytes());
+    write_varint(&mut handshake, 1); // next state: status
+    send_packet(&mut stream, &handshake).await?;
+    send_packet(&mut stream, &[0x00]).await?;
+
+    let packet = read_packet(&mut stream).await?;
+    let mut cursor = packet.as_slice();
+    if read_varint(&mut cursor) != Some(0x00) {
+        return Err(ServerError::Network(
+            "Unexpected status response".to_string(),
+        ));
+    }
+    let json = read_string(&mut cursor)
+        .ok_or_else(|| ServerError::Network("Malformed status response".to_string()))?;
+    let mut result = parse_status_json(&json)?;
+
+    // Пинг необязателен: часть прокси закрывает соединение сразу после статуса
+    let started = Instant::now();
+    let payload = chrono::Utc::now().timestamp_millis();
+    let mut ping = vec![0x01];
+    ping.extend_from_slice(&payload.to_be_bytes());
+    if send_packet(&mut stream, &ping).await.is_ok() {
+        if let Ok(pong) = read_packet(&mut stream).await {
+            if pong.len() == 9 && pong[0] == 0x01 && pong[1..] == payload.to_be_bytes() {
+                result.latency_ms = Some(started.elapsed().as_millis() as u32);
+            }
+        }
+    }
+
+    Ok(result)
+}
+
+async fn ping_legacy(host: &str, port: u16) -> ServerResult<ServerPingResult> {
+    let started = Instant::now();
+    let mut stream = connect(host, port).await?;
+
+    let host_utf16: Vec<u16> = host.encode_utf16().collect();
+    let mut request = vec![0xFE, 0x01, 0xFA];
+    write_utf16(
+        &mut request,
+        &"MC|PingHost".encode_utf16().collect::<Vec<_>>(),
+    );
+    request.extend_from_slice(&((7 + 2 * host_utf16.len()) as u16).to_be_bytes());
+    request.push(LEGACY_PROTOCOL);
+    write_utf16(&mut request, &host_utf16);
+    request.extend_from_slice(&(port as i32).to_be_bytes());
+    stream.write_all(&request).await?;
+
+    if stream.read_u8().await? != 0xFF {
+        return Err(ServerError::Network(
+            "Unexpected legacy ping response".to_string(),
+        ));
+    }
+    let len = stream.read_u16().await? as usize;
+    let mut data = vec![0u8; len * 2];
+    stream.read_exact(&mut data).await?;
+    let units: Vec<u16> = data
+        .chunks_exact(2)
+        .map(|c| u16::from_be_bytes([c[0], c[1]]))
+        .collect();
+
+    let mut result = parse_legacy_response(&String::from_utf16_lossy(&units))?;
+    result.latency_ms = Some(started.elapsed().as_millis() as u32);
+    Ok(result)
+}
+
+async fn connect(host: &str, port: u16) -> ServerResult<TcpStream> {
+    let stream = TcpStream::connect((host, port)).await.map_err(|e| {
+        ServerError::Network(format!("Failed to connect to {}:{}: {}", host, port, e))
+    })?;
+    let _ = stream.set_nodelay(true);
+    Ok(stream)
+}
+
+/// JSON из ответа на статус
+fn parse_status_json(json: &str) -> ServerResult<ServerPingResult> {
+    let status: Value = serde_json::from_str(json)?;
+
+    let players = &status["players"];
+    let sample = players["sample"]
+        .as_array()
+        .map(|sample| {
+            sample
+                .iter()
+                .filter_map(|p| {
+                    Some(PlayerSample {
+                        name: p["name"].as_str()?.to_string(),
+                        id: p["id"].as_str().unwrap_or_default().to_string(),
+                    })
+                })
+                .collect()
+        })
+        .unwrap_or_default();
+    let description = status.get("description").cloned().unwrap_or(Value::Null);
+
+    Ok(ServerPingResult {
+        version_name: status["version"]["name"]
+            .as_str()
+            .unwrap_or_default()
+            .to_string(),
+        protocol: status["version"]["protocol"].as_i64().unwrap_or(-1) as i32,
+        online_players: players["online"].as_u64().unwrap_or(0) as u32,
+        max_players: players["max"].as_u64().unwrap_or(0) as u32,
+        sample,
+        motd: strip_formatting(&chat_to_text(&description)),
+        description,
+        favicon: status["favicon"].as_str().map(str::to_string),
+        latency_ms: None,
+        legacy: false,
+    })
+}
+
+/// `§1\0protocol\0version\0motd\0online\0max` (1.4+) или `motd§online§max` (старше)
+fn parse_legacy_response(text: &str) -> ServerResult<ServerPingResult> {
+    let malformed = || ServerError::Network("Malformed legacy ping response".to_string());
+
+    let (protocol, version_name, motd, online, max) = if let Some(rest) = text.strip_prefix("§1\0")
+    {
+        let parts: Vec<&str> = rest.split('\0').collect();
+        if parts.len() < 5 {
+            return Err(malformed());
+        }
+        (
+            parts[0].parse().unwrap_or(-1),
+            parts[1].to_string(),
+            parts[2],
+            parts[3],
+            parts[4],
+        )
+    } else {
+        let mut parts = text.rsplitn(3, '§');
+        let max = parts.next().ok_or_else(malformed)?;
+        let online = parts.next().ok_or_else(malformed)?;
+        let motd = parts.next().ok_or_else(malformed)?;
+        (-1, String::new(), motd, online, max)
+    };
+
+    Ok(ServerPingResult {
+        version_name,
+        protocol,
+        online_players: online.trim().parse().map_err(|_| malformed())?,
+        max_players: max.trim().parse().map_err(|_| malformed())?,
+        sample: Vec::new(),
+        motd: strip_formatting(motd),
+        description: Value::String(motd.to_string()),
+        favicon: None,
+        latency_ms: None,
+        legacy: true,
+    })
+}
+
+/// Текст chat component: строка, объект с `text`/`extra` или массив
+fn chat_to_text(component: &Value) -> String {
+    match component {
+        Value::String(s) => s.clone(),
+        Value::Array(parts) => parts.iter().map(chat_to_text).collect(),
+        Value::Object(obj) => {
+            let mut text = obj
+                .get("text")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string();
+            if let Some(extra) = obj.get("extra") {
+                text.push_str(&chat_to_text(extra));
+            }
+            text
+        }
+        _ => String::new(),
+    }
+}
+
+/// Убрать коды форматирования `§x`
+fn strip_formatting(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    let mut chars = text.chars();
+    while let Some(c) = chars.next() {
+        if c == '§' {
+            chars.next();
+        } else {
+            out.push(c);
+        }
+    }
+    out
+}
+
+// ============================================================================
+// Wire format
+// ============================================================================
+
+fn write_varint(buf: &mut Vec<u8>, value: i32) {
+    let mut value = value as u32;
+    loop {
+        if value & !0x7F == 0 {
+            buf.push(value as u8);
+            return;
+        }
+        buf.push((value & 0x7F) as u8 | 0x80);
+        value >>= 7;
+    }
+}
+
+fn read_varint(buf: &mut &[u8]) -> Option<i32> {
+    let mut value = 0u32;
+    for i in 0..5 {
+        let (&byte, rest) = buf.split_first()?;
+        *buf = rest;
+        value |= ((byte & 0x7F) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            return Some(value as i32);
+        }
+    }
+    None
+}
+
+fn write_string(buf: &mut Vec<u8>, s: &str) {
+    write_varint(buf, s.len() as i32);
+    buf.extend_from_slice(s.as_bytes());
+}
+
+fn read_string(buf: &mut &[u8]) -> Option<String> {
+    let len = usize::try_from(read_varint(buf)?).ok()?;
+    if buf.len() < len {
+        return None;
+    }
+    let (s, rest) = buf.split_at(len);
+    *buf = rest;
+    Some(String::from_utf8_lossy(s).into_owned())
+}
+
+fn write_utf16(buf: &mut Vec<u8>, units: &[u16]) {
+    buf.extend_from_slice(&(units.len() as u16).to_be_bytes());
+    for unit in units {
+        buf.extend_from_slice(&unit.to_be_bytes());
+    }
+}
+
+async fn send_packet(stream: &mut TcpStream, payload: &[u8]) -> ServerResult<()> {
+    let mut packet = Vec::with_capacity(payload.len() + 5);
+    write_varint(&mut packet, payload.len() as i32);
+    packet.extend_from_slice(payload);
+    stream.write_all(&packet).await?;
+    Ok(())
+}
+
+async fn read_packet(stream: &mut (impl AsyncRead + Unpin)) -> ServerResult<Vec<u8>> {
+    let mut len = 0u32;
+    for i in 0..5 {
+        let byte = stream.read_u8().await?;
+        len |= ((byte & 0x7F) as u32) << (7 * i);
+        if byte & 0x80 == 0 {
+            break;
+        }
+        if i == 4 {
+            return Err(ServerError::Network("Invalid packet length".to_string()));
+        }
+    }
+
+    let len = len as usize;
+    if len == 0 || len > MAX_PACKET_LEN {
+        return Err(ServerError::Network(format!(
+            "Invalid packet length: {}",
+            len
+        )));
+    }
+    let mut packet = vec![0u8; len];
+    stream.read_exact(&mut packet).await?;
+    Ok(packet)
+}
+
+// ============================================================================
+// servers.dat (список серверов клиента)
+// ============================================================================
+
+/// Сервер из списка в игре
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SavedServer {
+    pub name: String,
+    pub address: String,
+}
+
+/// Сервер из списка клиента с результатом опроса
+#[derive(Debug, Clone, Serialize)]
+pub struct SavedServerStatus {
+    pub name: String,
+    pub address: String,
+    pub status: Option<ServerPingResult>,
+    pub error: Option<String>,
+}
+
+/// Прочитать servers.dat (несжатый NBT: `{servers: [{name, ip, ...}]}`)
+pub async fn read_servers_dat(path: impl AsRef<Path>) -> ServerResult<Vec<SavedServer>> {
+    let data = match tokio::fs::read(path.as_ref()).await {
+        Ok(data) => data,
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+        Err(e) => return Err(e.into()),
+    };
+
+    parse_servers_dat(&data)
+        .ok_or_else(|| ServerError::Config("Failed to parse servers.dat".to_string()))
+}
+
+fn parse_servers_dat(data: &[u8]) -> Option<Vec<SavedServer>> {
+    let (_, root) = nbt::parse(data).ok()?;
+    let servers = root
+        .get("servers")
+        .and_then(Tag::as_list)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Tag::as_compound)
+        .filter_map(|server| {
+            let address = server.str("ip").filter(|ip| !ip.is_empty())?;
+            Some(SavedServer {
+                name: server.str("name").unwrap_or_default().to_string(),
+                address: address.to_string(),
+            })
+        })
+        .collect();
+
+    Some(servers)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn ping_server(address: String) -> Result<ServerPingResult, String> {
+    let (host, port) = parse_address(&address).map_err(|e| e.to_string())?;
+    ping(&host, port).await.map_err(|e| e.to_string())
+}
+
+/// Опросить все серверы из servers.dat клиентского экземпляра
+#[tauri::command]
+pub async fn ping_instance_servers(instance_id: String) -> Result<Vec<SavedServerStatus>, String> {
+    let path = crate::paths::instance_dir(&instance_id).join("servers.dat");
+    let servers = read_servers_dat(&path).await.map_err(|e| e.to_string())?;
+
+    let pings = servers.into_iter().map(|server| async move {
+        let status = match parse_address(&server.address) {
+            Ok((host, port)) => ping(&host, port).await,
+            Err(e) => Err(e),
+        };
+        SavedServerStatus {
+            name: server.name,
+            address: server.address,
+            error: status.as_ref().err().map(|e| e.to_string()),
+            status: status.ok(),
+        }
+    });
+
+    Ok(futures::future::join_all(pings).await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tokio::net::TcpListener;
+
+    #[test]
+    fn test_parse_address() {
+        assert_eq!(
+            parse_address("mc.example.com").unwrap(),
+            ("mc.example.com".to_string(), 25565)
+        );
+        assert_eq!(
+            parse_address("127.0.0.1:25570").unwrap(),
+            ("127.0.0.1".to_string(), 25570)
+        );
+        assert_eq!(
+            parse_address("[::1]:25570").unwrap(),
+            ("::1".to_string(), 25570)
+        );
+        assert_eq!(parse_address("::1").unwrap(), ("::1".to_string(), 25565));
+        assert!(parse_address("host:port").is_err());
+        assert!(parse_address(":25565").is_err());
+    }
+
+    #[test]
+    fn test_parse_legacy_response() {
+        let result = parse_legacy_response("§1\u{0}74\u{0}1.6.4\u{0}§aHello\u{0}3\u{0}20").unwrap();
+        assert_eq!(result.protocol, 74);
+        assert_eq!(result.version_name, "1.6.4");
+        assert_eq!(result.motd, "Hello");
+        assert_eq!((result.online_players, result.max_players), (3, 20));
+
+        let old = parse_legacy_response("A § server§5§10").unwrap();
+        assert_eq!(old.motd, "Aserver");
+        assert_eq!((old.online_players, old.max_players), (5, 10));
+    }
+
+    #[test]
+    fn test_parse_servers_dat() {
+        let mut data = vec![10, 0, 0];
+        data.push(9);
+        data.extend_from_slice(&7u16.to_be_bytes());
+        data.extend_from_slice(b"servers");
+        data.push(10);
+        data.extend_from_slice(&1i32.to_be_bytes());
+        for (key, value) in [
+            ("icon", "AAAA"),
+            ("ip", "play.example.com:25570"),
+            ("name", "Example"),
+        ] {
+            data.push(8);
+            data.extend_from_slice(&(key.len() as u16).to_be_bytes());
+            data.extend_from_slice(key.as_bytes());
+            data.extend_from_slice(&(value.len() as u16).to_be_bytes());
+            data.extend_from_slice(value.as_bytes());
+        }
+        data.extend_from_slice(&[1, 0, 6]);
+        data.extend_from_slice(b"hidden");
+        data.push(0);
+        data.push(0); // конец сервера
+        data.push(0); // конец корня
+
+        let servers = parse_servers_dat(&data).unwrap();
+        assert_eq!(servers.len(), 1);
+        assert_eq!(servers[0].name, "Example");
+        assert_eq!(servers[0].address, "play.example.com:25570");
+        assert!(parse_servers_dat(&data[..data.len() - 3]).is_none());
+    }
+
+    /// Фейковый сервер: отвечает на статус и пинг как ванилла
+    async fn fake_server(status: &'static str) -> u16 {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let (mut stream, _) = listener.accept().await.unwrap();
+
+            let handshake = read_packet(&mut stream).await.unwrap();
+            let mut cursor = handshake.as_slice();
+            assert_eq!(read_varint(&mut cursor), Some(0x00));
+            assert_eq!(read_varint(&mut cursor), Some(HANDSHAKE_PROTOCOL));
+            assert_eq!(read_string(&mut cursor).as_deref(), Some("127.0.0.1"));
+            assert_eq!(read_packet(&mut stream).await.unwrap(), vec![0x00]);
+
+            let mut response = Vec::new();
+            write_varint(&mut response, 0x00);
+            write_string(&mut response, status);
+            send_packet(&mut stream, &response).await.unwrap();
+
+            let ping = read_packet(&mut stream).await.unwrap();
+            send_packet(&mut stream, &ping).await.unwrap();
+        });
+        port
+    }
+
+    #[tokio::test]
+    async fn test_ping_fake_server() {
+        let port = fake_server(
+            r#"{"version":{"name":"Paper 1.21.1","protocol":767},
+                "players":{"max":50,"online":2,"sample":[{"name":"Steve","id":"8667ba71-b85a-4004-af54-457a9734eed7"}]},
+                "description":{"text":"§6Lobby","extra":[{"text":" — welcome"}]},
+                "favicon":"data:image/png;base64,AAAA"}"#,
+        )
+        .await;
+
+        let result = ping("127.0.0.1", port).await.unwrap();
+        assert!(!result.legacy);
+        assert_eq!(result.version_name, "Paper 1.21.1");
+        assert_eq!(result.protocol, 767);
+        assert_eq!((result.online_players, result.max_players), (2, 50));
+        assert_eq!(result.sample[0].name, "Steve");
+        assert_eq!(result.motd, "Lobby — welcome");
+        assert_eq!(
+            result.favicon.as_deref(),
+            Some("data:image/png;base64,AAAA")
+        );
+        assert!(result.latency_ms.is_some());
+    }
+}
@@ -4,7 +4,8 @@
 //! Minecraft stores these in JSON files: whitelist.json, ops.json, banned-players.json, banned-ips.json
 
 use serde::{Deserialize, Serialize};
-use std::path::Path;
+use std::collections::HashMap;
+use std::path::{Path, PathBuf};
 use tokio::fs;
 
 use super::{ServerError, ServerResult};
@@ -52,6 +53,15 @@ pub struct BannedIp {
     pub reason: String,
 }
 
+/// usercache.json entry: every player who has joined, with the name they last used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UserCacheEntry {
+    pub name: String,
+    pub uuid: String,
+    #[serde(rename = "expiresOn", default)]
+    pub expires_on: String,
+}
+
 /// Combined player management state
 #[derive(Debug, Clone, Serialize, Deserialize, Default)]
 pub struct PlayerManagement {
@@ -174,6 +184,42 @@ pub async fn load_all(server_dir: impl AsRef<Path>) -> ServerResult<PlayerManage
     })
 }
 
+/// Load usercache.json (written by the game on both servers and clients)
+pub async fn load_usercache(server_dir: impl AsRef<Path>) -> ServerResult<Vec<UserCacheEntry>> {
+    let path = server_dir.as_ref().join("usercache.json");
+
+    if !tokio::fs::try_exists(&path).await.unwrap_or(false) {
+        return Ok(Vec::new());
+    }
+
+    let content = fs::read_to_string(&path).await?;
+    let cache: Vec<UserCacheEntry> = serde_json::from_str(&content)
+        .map_err(|e| ServerError::Config(format!("Failed to parse usercache.json: {}", e)))?;
+
+    Ok(cache)
+}
+
+/// Known player names by lowercase UUID: usercache.json first, then whitelist, ops and bans
+pub async fn known_player_names(server_dir: impl AsRef<Path>) -> HashMap<String, String> {
+    let server_dir = server_dir.as_ref();
+    let lists = load_all(server_dir).await.unwrap_or_default();
+    let cache = load_usercache(server_dir).await.unwrap_or_default();
+
+    let mut names = HashMap::new();
+    let entries = cache
+        .into_iter()
+        .map(|e| (e.uuid, e.name))
+        .chain(lists.whitelist.into_iter().map(|e| (e.uuid, e.name)))
+        .chain(lists.ops.into_iter().map(|e| (e.uuid, e.name)))
+        .chain(lists.banned_players.into_iter().map(|e| (e.uuid, e.name)));
+    for (uuid, name) in entries {
+        if !uuid.is_empty() {
+            names.entry(uuid.to_lowercase()).or_insert(name);
+        }
+    }
+    names
+}
+
 /// Add player to whitelist
 pub async fn add_to_whitelist(
     server_dir: impl AsRef<Path>,
@@ -427,6 +473,12 @@ pub async fn get_op_level(server_dir: impl AsRef<Path>, name: &str) -> ServerRes
         .unwrap_or(0))
 }
 
+/// Whitelist and bans are shared across backends of a server network;
+/// ops stay per-server
+fn shared_list_dirs(instance_id: &str) -> Vec<PathBuf> {
+    super::network::NetworkManager::player_list_dirs(instance_id)
+}
+
 // Tauri commands
 
 #[tauri::command]
@@ -439,28 +491,28 @@ pub async fn get_player_management(instance_id: String) -> Result<PlayerManageme
 
 #[tauri::command]
 pub async fn whitelist_add(instance_id: String, username: String) -> Result<(), String> {
-    let instances_dir = crate::paths::instances_dir();
-    let server_dir = instances_dir.join(&instance_id);
-
     // Try to lookup UUID from Mojang
     let uuid = lookup_uuid(&username)
         .await
         .map_err(|e| e.to_string())?
         .ok_or_else(|| format!("Player '{}' not found", username))?;
 
-    add_to_whitelist(&server_dir, &uuid, &username)
-        .await
-        .map_err(|e| e.to_string())
+    for server_dir in shared_list_dirs(&instance_id) {
+        add_to_whitelist(&server_dir, &uuid, &username)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn whitelist_remove(instance_id: String, username: String) -> Result<(), String> {
-    let instances_dir = crate::paths::instances_dir();
-    let server_dir = instances_dir.join(&instance_id);
-
-    remove_from_whitelist(&server_dir, &username)
-        .await
-        .map_err(|e| e.to_string())
+    for server_dir in shared_list_dirs(&instance_id) {
+        remove_from_whitelist(&server_dir, &username)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -495,46 +547,48 @@ pub async fn player_ban(
     username: String,
     reason: String,
 ) -> Result<(), String> {
-    let instances_dir = crate::paths::instances_dir();
-    let server_dir = instances_dir.join(&instance_id);
-
     // Try to lookup UUID from Mojang
     let uuid = lookup_uuid(&username)
         .await
         .map_err(|e| e.to_string())?
         .ok_or_else(|| format!("Player '{}' not found", username))?;
 
-    ban_player(&server_dir, &uuid, &username, &reason, "Stuzhik")
-        .await
-        .map_err(|e| e.to_string())
+    for server_dir in shared_list_dirs(&instance_id) {
+        ban_player(&server_dir, &uuid, &username, &reason, "Stuzhik")
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn player_unban(instance_id: String, username: String) -> Result<(), String> {
-    let instances_dir = crate::paths::instances_dir();
-    let server_dir = instances_dir.join(&instance_id);
-
-    unban_player(&server_dir, &username)
-        .await
-        .map_err(|e| e.to_string())
+    for server_dir in shared_list_dirs(&instance_id) {
+        unban_player(&server_dir, &username)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn ip_ban(instance_id: String, ip: String, reason: String) -> Result<(), String> {
-    let instances_dir = crate::paths::instances_dir();
-    let server_dir = instances_dir.join(&instance_id);
-
-    ban_ip(&server_dir, &ip, &reason, "Stuzhik")
-        .await
-        .map_err(|e| e.to_string())
+    for server_dir in shared_list_dirs(&instance_id) {
+        ban_ip(&server_dir, &ip, &reason, "Stuzhik")
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
 pub async fn ip_unban(instance_id: String, ip: String) -> Result<(), String> {
-    let instances_dir = crate::paths::instances_dir();
-    let server_dir = instances_dir.join(&instance_id);
-
-    unban_ip(&server_dir, &ip).await.map_err(|e| e.to_string())
+    for server_dir in shared_list_dirs(&instance_id) {
+        unban_ip(&server_dir, &ip)
+            .await
+            .map_err(|e| e.to_string())?;
+    }
+    Ok(())
 }
 
 #[tauri::command]
@@ -0,0 +1,803 @@
+//! Server plugins (Paper, Purpur, Folia, Velocity)
+//!
+//! Плагины лежат в `plugins/` и учитываются в БД так же, как моды: источник
+//! (Modrinth, Hangar или локальный файл), версия, хеш, включён/выключен.
+//! Выключенный плагин переименовывается в `.jar.disabled` — сервер его не загрузит.
+
+use chrono::Utc;
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::io::Read;
+use std::path::Path;
+
+use super::installer::ServerLoader;
+use crate::api::modrinth::{ModrinthClient, ModrinthVersion};
+use crate::db::get_db_conn;
+use crate::downloader::{fetch_json, DownloadManager};
+use crate::error::{LauncherError, Result};
+use crate::paths::instance_plugins_dir;
+
+const HANGAR_API: &str = "https://hangar.papermc.io/api/v1";
+
+/// Установленный плагин
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InstalledPlugin {
+    pub id: i64,
+    pub instance_id: String,
+    pub slug: String,
+    pub name: String,
+    pub version: String,
+    /// modrinth / hangar / local
+    pub source: String,
+    pub source_id: Option<String>,
+    pub file_name: String,
+    pub file_hash: Option<String>,
+    pub enabled: bool,
+    pub author: Option<String>,
+    pub description: Option<String>,
+    pub installed_at: String,
+    pub updated_at: String,
+}
+
+/// Результат синхронизации папки plugins/ с БД
+#[derive(Debug, Clone, Serialize)]
+pub struct PluginSyncResult {
+    pub added: usize,
+    pub removed: usize,
+}
+
+/// Метаданные из plugin.yml / paper-plugin.yml / velocity-plugin.json
+#[derive(Debug, Clone, Default, PartialEq)]
+struct PluginDescriptor {
+    name: String,
+    version: Option<String>,
+    author: Option<String>,
+    description: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HangarVersions {
+    result: Vec<HangarVersion>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarVersion {
+    name: String,
+    channel: HangarChannel,
+    /// Ключ — платформа (PAPER, VELOCITY, WATERFALL)
+    downloads: HashMap<String, HangarDownload>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HangarChannel {
+    name: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarDownload {
+    file_info: Option<HangarFileInfo>,
+    external_url: Option<String>,
+    download_url: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct HangarFileInfo {
+    name: String,
+    sha256_hash: String,
+}
+
+pub struct PluginManager;
+
+impl PluginManager {
+    /// Создать таблицу плагинов если не существует
+    fn ensure_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            r#"CREATE TABLE IF NOT EXISTS server_plugins (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                instance_id TEXT NOT NULL,
+                slug TEXT NOT NULL,
+                name TEXT NOT NULL,
+                version TEXT NOT NULL,
+                source TEXT NOT NULL,
+                source_id TEXT,
+                file_name TEXT NOT NULL,
+                file_hash TEXT,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                author TEXT,
+                description TEXT,
+                installed_at TEXT NOT NULL,
+                updated_at TEXT NOT NULL,
+                UNIQUE(instance_id, slug)
+            )"#,
+            [],
+        )?;
+        Ok(())
+    }
+
+    pub fn list(instance_id: &str) -> Result<Vec<InstalledPlugin>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, instance_id, slug, name, version, source, source_id, file_name,
+                      file_hash, enabled, author, description, installed_at, updated_at
+               FROM server_plugins WHERE instance_id = ?1 ORDER BY name COLLATE NOCASE"#,
+        )?;
+        let plugins = stmt
+            .query_map([instance_id], row_to_plugin)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(plugins)
+    }
+
+    fn get(instance_id: &str, plugin_id: i64) -> Result<InstalledPlugin> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        conn.query_row(
+            r#"SELECT id, instance_id, slug, name, version, source, source_id, file_name,
+                      file_hash, enabled, author, description, installed_at, updated_at
+               FROM server_plugins WHERE id = ?1 AND instance_id = ?2"#,
+            params![plugin_id, instance_id],
+            row_to_plugin,
+        )
+        .optional()?
+        .ok_or_else(|| LauncherError::NotFound(format!("Plugin {}", plugin_id)))
+    }
+
+    /// Платформа и версия MC сервера. Ошибка, если это не сервер с плагинами.
+    fn server_platform(instance_id: &str) -> Result<(ServerLoader, String)> {
+        let conn = get_db_conn()?;
+        let (loader, version, instance_type): (String, String, String) = conn
+            .query_row(
+                "SELECT loader, version, instance_type FROM instances WHERE id = ?1",
+                [instance_id],
+                |row| Ok((row.get(0)?, row.get(1)?, row.get(2)?)),
+            )
+            .optional()?
+            .ok_or_else(|| LauncherError::InstanceNotFound(instance_id.to_string()))?;
+
+        let loader = loader
+            .parse::<ServerLoader>()
+            .ok()
+            .filter(|l| instance_type == "server" && l.is_plugin_platform())
+            .ok_or_else(|| {
+                LauncherError::InvalidConfig(format!(
+                    "Instance {} does not support plugins (loader: {})",
+                    instance_id, loader
+                ))
+            })?;
+
+        Ok((loader, version))
+    }
+
+    fn ensure_not_installed(instance_id: &str, slug: &str) -> Result<()> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        let exists: bool = conn.query_row(
+            "SELECT EXISTS(SELECT 1 FROM server_plugins WHERE instance_id = ?1 AND slug = ?2)",
+            params![instance_id, slug],
+            |row| row.get(0),
+        )?;
+        if exists {
+            return Err(LauncherError::ModAlreadyInstalled(slug.to_string()));
+        }
+        Ok(())
+    }
+
+    /// Установка плагина с Modrinth
+    pub async fn install_from_modrinth(
+        instance_id: &str,
+        slug: &str,
+        version_id: Option<&str>,
+        download_manager: &DownloadManager,
+    ) -> Result<InstalledPlugin> {
+        let (loader, mc_version) = Self::server_platform(instance_id)?;
+        Self::ensure_not_installed(instance_id, slug)?;
+
+        let version = match version_id {
+            Some(id) => ModrinthClient::get_version(id).await?,
+            None => {
+                // Velocity-плагины почти не привязаны к версии MC
+                let mc_filter = (!loader.is_proxy()).then_some(mc_version.as_str());
+                let versions = ModrinthClient::get_project_versions(slug, mc_filter, None).await?;
+                pick_modrinth_version(versions, loader).ok_or_else(|| {
+                    LauncherError::NoCompatibleModVersion {
+                        mod_name: slug.to_string(),
+                        mc_version: mc_version.clone(),
+                        loader: loader.to_string(),
+                    }
+                })?
+            }
+        };
+
+        let file = version
+            .files
+            .iter()
+            .find(|f| f.primary)
+            .or_else(|| version.files.first())
+            .ok_or_else(|| {
+                LauncherError::ModDownloadFailed(format!(
+                    "No files in {} {}",
+                    slug, version.version_number
+                ))
+            })?;
+
+        let plugins_dir = instance_plugins_dir(instance_id);
+        tokio::fs::create_dir_all(&plugins_dir).await?;
+        download_manager
+            .download_file(
+                &file.url,
+                plugins_dir.join(&file.filename),
+                slug,
+                Some(&file.hashes.sha1),
+            )
+            .await?;
+
+        Self::register(
+            instance_id,
+            slug,
+            &version.version_number,
+            "modrinth",
+            Some(&version.project_id),
+            &file.filename,
+            Some(&file.hashes.sha1),
+        )
+        .await
+    }
+
+    /// Установка плагина с Hangar (hangar.papermc.io)
+    pub async fn install_from_hangar(
+        instance_id: &str,
+        slug: &str,
+        version_name: Option<&str>,
+        download_manager: &DownloadManager,
+    ) -> Result<InstalledPlugin> {
+        let (loader, mc_version) = Self::server_platform(instance_id)?;
+        Self::ensure_not_installed(instance_id, slug)?;
+
+        let platform = hangar_platform(loader);
+        let version: HangarVersion = match version_name {
+            Some(name) => {
+                fetch_json(&format!(
+                    "{}/projects/{}/versions/{}",
+                    HANGAR_API, slug, name
+                ))
+                .await?
+            }
+            None => {
+                let mut query = vec![("limit", "25".to_string()), ("platform", platform.into())];
+                if !loader.is_proxy() {
+                    query.push(("platformVersion", mc_version.clone()));
+                }
+                let url = reqwest::Url::parse_with_params(
+                    &format!("{}/projects/{}/versions", HANGAR_API, slug),
+                    &query,
+                )
+                .map_err(|e| LauncherError::ApiError(format!("Failed to build URL: {}", e)))?;
+
+                let versions: HangarVersions = fetch_json(url.as_str()).await?;
+                pick_hangar_version(versions.result).ok_or_else(|| {
+                    LauncherError::NoCompatibleModVersion {
+                        mod_name: slug.to_string(),
+                        mc_version: mc_version.clone(),
+                        loader: loader.to_string(),
+                    }
+                })?
+            }
+        };
+
+        let download = version.downloads.get(platform).ok_or_else(|| {
+            LauncherError::ModDownloadFailed(format!(
+                "{} {} has no {} download",
+                slug, version.name, platform
+            ))
+        })?;
+        let (url, file_info) = match (&download.download_url, &download.file_info) {
+            (Some(url), Some(info)) => (url, info),
+            _ => {
+                return Err(LauncherError::ModDownloadFailed(format!(
+                    "{} is hosted externally, download it manually: {}",
+                    slug,
+                    download.external_url.as_deref().unwrap_or("-")
+                )))
+            }
+        };
+
+        let plugins_dir = instance_plugins_dir(instance_id);
+        tokio::fs::create_dir_all(&plugins_dir).await?;
+        download_manager
+            .download_file(
+                url,
+                plugins_dir.join(&file_info.name),
+                slug,
+                Some(&file_info.sha256_hash),
+            )
+            .await?;
+
+        Self::register(
+            instance_id,
+            slug,
+            &version.name,
+            "hangar",
+            Some(slug),
+            &file_info.name,
+            Some(&file_info.sha256_hash),
+        )
+        .await
+    }
+
+    /// Записать скачанный плагин в БД. Имя и автор берутся из дескриптора в JAR.
+    async fn register(
+        instance_id: &str,
+        slug: &str,
+        version: &str,
+        source: &str,
+        source_id: Option<&str>,
+        file_name: &str,
+        file_hash: Option<&str>,
+    ) -> Result<InstalledPlugin> {
+        let path = instance_plugins_dir(instance_id).join(file_name);
+        let descriptor = tokio::task::spawn_blocking(move || read_descriptor(&path))
+            .await
+            .map_err(|e| LauncherError::Join(e.to_string()))?
+            .unwrap_or_default();
+
+        let name = if descriptor.name.is_empty() {
+            slug.to_string()
+        } else {
+            descriptor.name
+        };
+        let now = Utc::now().to_rfc3339();
+
+        let plugin_id = {
+            let conn = get_db_conn()?;
+            Self::ensure_table(&conn)?;
+            conn.execute(
+                r#"INSERT INTO server_plugins (
+                    instance_id, slug, name, version, source, source_id, file_name, file_hash,
+                    enabled, author, description, installed_at, updated_at
+                ) VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, 1, ?9, ?10, ?11, ?11)"#,
+                params![
+                    instance_id,
+                    slug,
+                    name,
+                    version,
+                    source,
+                    source_id,
+                    file_name,
+                    file_hash,
+                    descriptor.author,
+                    descriptor.description,
+                    now,
+                ],
+            )?;
+            conn.last_insert_rowid()
+        };
+
+        Self::get(instance_id, plugin_id)
+    }
+
+    /// Синхронизировать plugins/ с БД: добавить файлы, положенные вручную, и убрать удалённые
+    pub async fn sync_with_folder(instance_id: &str) -> Result<PluginSyncResult> {
+        let plugins_dir = instance_plugins_dir(instance_id);
+        let known = Self::list(instance_id)?;
+
+        let files: Vec<String> = tokio::task::spawn_blocking({
+            let plugins_dir = plugins_dir.clone();
+            move || {
+                std::fs::read_dir(&plugins_dir)
+                    .map(|entries| {
+                        entries
+                            .filter_map(|e| e.ok())
+                            .filter(|e| e.file_type().is_ok_and(|t| t.is_file()))
+                            .map(|e| e.file_name().to_string_lossy().to_string())
+                            .filter(|name| {
+                                name.ends_with(".jar") || name.ends_with(".jar.disabled")
+                            })
+                            .collect()
+                    })
+                    .unwrap_or_default()
+            }
+        })
+        .await
+        .map_err(|e| LauncherError::Join(e.to_string()))?;
+
+        // Дескрипторы читаем до открытия соединения с БД
+        let mut new_plugins = Vec::new();
+        for file_name in files
+            .iter()
+            .filter(|f| !known.iter().any(|p| &p.file_name == *f))
+        {
+            let path = plugins_dir.join(file_name);
+            let descriptor = tokio::task::spawn_blocking(move || read_descriptor(&path))
+                .await
+                .map_err(|e| LauncherError::Join(e.to_string()))?;
+            new_plugins.push((file_name, descriptor));
+        }
+
+        let on_disk: HashSet<&str> = files.iter().map(String::as_str).collect();
+        let conn = get_db_conn()?;
+
+        let mut removed = 0;
+        for plugin in known
+            .iter()
+            .filter(|p| !on_disk.contains(p.file_name.as_str()))
+        {
+            removed += conn.execute("DELETE FROM server_plugins WHERE id = ?1", [plugin.id])?;
+        }
+
+        let mut added = 0;
+        let now = Utc::now().to_rfc3339();
+        for (file_name, descriptor) in new_plugins {
+            let stem = file_name
+                .trim_end_matches(".disabled")
+                .trim_end_matches(".jar");
+            let (name, version, author, description) = match descriptor {
+                Some(d) => (
+                    d.name,
+                    d.version.unwrap_or_else(|| "unknown".to_string()),
+                    d.author,
+                    d.description,
+                ),
+                None => (stem.to_string(), "unknown".to_string(), None, None),
+            };
+
+            // OR IGNORE: два файла одного плагина (старая и новая версия) — учитываем первый
+            added += conn.execute(
+                r#"INSERT OR IGNORE INTO server_plugins (
+                    instance_id, slug, name, version, source, file_name,
+                    enabled, author, description, installed_at, updated_at
+                ) VALUES (?1, ?2, ?3, ?4, 'local', ?5, ?6, ?7, ?8, ?9, ?9)"#,
+                params![
+                    instance_id,
+                    plugin_slug(&name),
+                    name,
+                    version,
+                    file_name,
+                    !file_name.ends_with(".disabled"),
+                    author,
+                    description,
+                    now,
+                ],
+            )?;
+        }
+
+        if added > 0 || removed > 0 {
+            log::info!(
+                "Synced plugins for {}: {} added, {} removed",
+                instance_id,
+                added,
+                removed
+            );
+        }
+
+        Ok(PluginSyncResult { added, removed })
+    }
+
+    /// Включение/отключение плагина (переименование в .jar.disabled)
+    pub async fn toggle(instance_id: &str, plugin_id: i64, enabled: bool) -> Result<()> {
+        let plugin = Self::get(instance_id, plugin_id)?;
+        let is_disabled = plugin.file_name.ends_with(".disabled");
+        if enabled != is_disabled {
+            return Ok(());
+        }
+
+        let new_name = if enabled {
+            plugin.file_name.trim_end_matches(".disabled").to_string()
+        } else {
+            format!("{}.disabled", plugin.file_name)
+        };
+
+        let plugins_dir = instance_plugins_dir(instance_id);
+        tokio::fs::rename(
+            plugins_dir.join(&plugin.file_name),
+            plugins_dir.join(&new_name),
+        )
+        .await?;
+
+        let conn = get_db_conn()?;
+        conn.execute(
+            "UPDATE server_plugins SET enabled = ?1, file_name = ?2, updated_at = ?3 WHERE id = ?4",
+            params![enabled, new_name, Utc::now().to_rfc3339(), plugin_id],
+        )?;
+        Ok(())
+    }
+
+    /// Удаление плагина. Папка с его конфигами (plugins/<Name>/) не трогается.
+    pub async fn remove(instance_id: &str, plugin_id: i64) -> Result<()> {
+        let plugin = Self::get(instance_id, plugin_id)?;
+
+        let path = instance_plugins_dir(instance_id).join(&plugin.file_name);
+        match tokio::fs::remove_file(&path).await {
+            Ok(()) => {}
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {}
+            Err(e) => return Err(e.into()),
+        }
+
+        let conn = get_db_conn()?;
+        conn.execute("DELETE FROM server_plugins WHERE id = ?1", [plugin_id])?;
+        Ok(())
+    }
+}
+
+/// Загрузчики Modrinth, чьи плагины запускаются на платформе
+fn modrinth_loaders(loader: ServerLoader) -> &'static [&'static str] {
+    match loader {
+        ServerLoader::Velocity => &["velocity"],
+        // Folia ломает большинство обычных плагинов — только явно совместимые
+        ServerLoader::Folia => &["folia"],
+        ServerLoader::Purpur => &["purpur", "paper", "spigot", "bukkit"],
+        _ => &["paper", "spigot", "bukkit"],
+    }
+}
+
+fn hangar_platform(loader: ServerLoader) -> &'static str {
+    match loader {
+        ServerLoader::Velocity => "VELOCITY",
+        _ => "PAPER",
+    }
+}
+
+/// Последний релиз (или любая последняя версия), совместимый с платформой
+fn pick_modrinth_version(
+    versions: Vec<ModrinthVersion>,
+    loader: ServerLoader,
+) -> Option<ModrinthVersion> {
+    let compatible = modrinth_loaders(loader);
+    let mut versions: Vec<ModrinthVersion> = versions
+        .into_iter()
+        .filter(|v| v.loaders.iter().any(|l| compatible.contains(&l.as_str())))
+        .collect();
+
+    let release = versions.iter().position(|v| v.version_type == "release");
+    match release {
+        Some(idx) => Some(versions.swap_remove(idx)),
+        None if !versions.is_empty() => Some(versions.swap_remove(0)),
+        None => None,
+    }
+}
+
+/// Hangar отдаёт версии от новых к старым; предпочитаем канал Release
+fn pick_hangar_version(mut versions: Vec<HangarVersion>) -> Option<HangarVersion> {
+    let release = versions
+        .iter()
+        .position(|v| v.channel.name.eq_ignore_ascii_case("release"));
+    match release {
+        Some(idx) => Some(versions.swap_remove(idx)),
+        None if !versions.is_empty() => Some(versions.swap_remove(0)),
+        None => None,
+    }
+}
+
+/// "Simple Voice Chat" -> "simple-voice-chat"
+fn plugin_slug(name: &str) -> String {
+    name.to_lowercase()
+        .split(|c: char| !c.is_ascii_alphanumeric())
+        .filter(|part| !part.is_empty())
+        .collect::<Vec<_>>()
+        .join("-")
+}
+
+/// Прочитать дескриптор плагина из JAR
+fn read_descriptor(jar_path: &Path) -> Option<PluginDescriptor> {
+    let file = std::fs::File::open(jar_path).ok()?;
+    let mut archive = zip::ZipArchive::new(file).ok()?;
+
+    let mut read_entry = |name: &str| -> Option<String> {
+        let mut entry = archive.by_name(name).ok()?;
+        let mut content = String::new();
+        entry.read_to_string(&mut content).ok()?;
+        Some(content)
+    };
+
+    if let Some(json) = read_entry("velocity-plugin.json") {
+        return parse_velocity_plugin_json(&json);
+    }
+    read_entry("paper-plugin.yml")
+        .or_else(|| read_entry("plugin.yml"))
+        .and_then(|yml| parse_plugin_yml(&yml))
+}
+
+/// Разбор верхнего уровня plugin.yml без полноценного YAML-парсера:
+/// нужны только name, version, description, author/authors.
+fn parse_plugin_yml(content: &str) -> Option<PluginDescriptor> {
+    let mut descriptor = PluginDescriptor::default();
+    let mut lines = content.lines().peekable();
+
+    while let Some(line) = lines.next() {
+        // Вложенные ключи (commands:, permissions:) и комментарии пропускаем
+        if line.starts_with(char::is_whitespace) || line.starts_with('#') {
+            continue;
+        }
+        let Some((key, value)) = line.split_once(':') else {
+            continue;
+        };
+        let value = unquote(value);
+
+        match key.trim() {
+            "name" => descriptor.name = value,
+            "version" => descriptor.version = Some(value).filter(|v| !v.is_empty()),
+            "description" => descriptor.description = Some(value).filter(|v| !v.is_empty()),
+            "author" if descriptor.author.is_none() && !value.is_empty() => {
+                descriptor.author = Some(value)
+            }
+            "authors" => {
+                let authors: Vec<String> = if value.is_empty() {
+                    // Блочный список: "  - Name"
+                    let mut items = Vec::new();
+                    while let Some(item) =
+                        lines.peek().and_then(|l| l.trim_start().strip_prefix('-'))
+                    {
+                        items.push(unquote(item));
+                        lines.next();
+                    }
+                    items
+                } else {
+                    value
+                        .trim_start_matches('[')
+                        .trim_end_matches(']')
+                        .split(',')
+                        .map(unquote)
+                        .filter(|a| !a.is_empty())
+                        .collect()
+                };
+                if !authors.is_empty() {
+                    descriptor.author = Some(authors.join(", "));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    (!descriptor.name.is_empty()).then_some(descriptor)
+}
+
+fn parse_velocity_plugin_json(content: &str) -> Option<PluginDescriptor> {
+    let json: serde_json::Value = serde_json::from_str(content).ok()?;
+    let name = json["name"].as_str().or_else(|| json["id"].as_str())?;
+    let authors: Vec<&str> = json["authors"]
+        .as_array()
+        .map(|a| a.iter().filter_map(|v| v.as_str()).collect())
+        .unwrap_or_default();
+
+    Some(PluginDescriptor {
+        name: name.to_string(),
+        version: json["version"].as_str().map(String::from),
+        author: (!authors.is_empty()).then(|| authors.join(", ")),
+        description: json["description"].as_str().map(String::from),
+    })
+}
+
+fn unquote(value: &str) -> String {
+    value
+        .trim()
+        .trim_matches(|c| c == '"' || c == '\'')
+        .to_string()
+}
+
+fn row_to_plugin(row: &rusqlite::Row) -> rusqlite::Result<InstalledPlugin> {
+    Ok(InstalledPlugin {
+        id: row.get(0)?,
+        instance_id: row.get(1)?,
+        slug: row.get(2)?,
+        name: row.get(3)?,
+        version: row.get(4)?,
+        source: row.get(5)?,
+        source_id: row.get(6)?,
+        file_name: row.get(7)?,
+        file_hash: row.get(8)?,
+        enabled: row.get(9)?,
+        author: row.get(10)?,
+        description: row.get(11)?,
+        installed_at: row.get(12)?,
+        updated_at: row.get(13)?,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_server_plugins(instance_id: String) -> Result<Vec<InstalledPlugin>> {
+    if let Err(e) = PluginManager::sync_with_folder(&instance_id).await {
+        log::warn!("Failed to sync plugins for {}: {}", instance_id, e);
+    }
+    PluginManager::list(&instance_id)
+}
+
+#[tauri::command]
+pub async fn install_server_plugin(
+    instance_id: String,
+    source: String,
+    slug: String,
+    version_id: Option<String>,
+    app_handle: tauri::AppHandle,
+) -> Result<InstalledPlugin> {
+    let download_manager = DownloadManager::new(app_handle)?;
+
+    match source.as_str() {
+        "modrinth" => {
+            PluginManager::install_from_modrinth(
+                &instance_id,
+                &slug,
+                version_id.as_deref(),
+                &download_manager,
+            )
+            .await
+        }
+        "hangar" => {
+            PluginManager::install_from_hangar(
+                &instance_id,
+                &slug,
+                version_id.as_deref(),
+                &download_manager,
+            )
+            .await
+        }
+        _ => Err(LauncherError::InvalidConfig(format!(
+            "Unknown plugin source: {}",
+            source
+        ))),
+    }
+}
+
+#[tauri::command]
+pub async fn toggle_server_plugin(
+    instance_id: String,
+    plugin_id: i64,
+    enabled: bool,
+) -> Result<()> {
+    PluginManager::toggle(&instance_id, plugin_id, enabled).await
+}
+
+#[tauri::command]
+pub async fn remove_server_plugin(instance_id: String, plugin_id: i64) -> Result<()> {
+    PluginManager::remove(&instance_id, plugin_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_plugin_yml() {
+        let yml = r#"
+name: LuckPerms
+version: "5.4.102"
+main: me.lucko.luckperms.bukkit.loader.BukkitLoaderPlugin
+description: A permissions plugin
+authors: [Luck, "Emily"]
+commands:
+  lp:
+    description: Manage permissions
+"#;
+        let d = parse_plugin_yml(yml).unwrap();
+        assert_eq!(d.name, "LuckPerms");
+        assert_eq!(d.version.as_deref(), Some("5.4.102"));
+        assert_eq!(d.description.as_deref(), Some("A permissions plugin"));
+        assert_eq!(d.author.as_deref(), Some("Luck, Emily"));
+
+        let block = "name: Essentials\nauthors:\n  - zenexer\n  - 'md_5'\nversion: 2.20\n";
+        let d = parse_plugin_yml(block).unwrap();
+        assert_eq!(d.author.as_deref(), Some("zenexer, md_5"));
+        assert_eq!(d.version.as_deref(), Some("2.20"));
+
+        assert_eq!(parse_plugin_yml("version: 1.0\n"), None);
+    }
+
+    #[test]
+    fn test_parse_velocity_plugin_json() {
+        let json = r#"{"id":"luckperms","name":"LuckPerms","version":"5.4","authors":["Luck"]}"#;
+        let d = parse_velocity_plugin_json(json).unwrap();
+        assert_eq!(d.name, "LuckPerms");
+        assert_eq!(d.author.as_deref(), Some("Luck"));
+        assert_eq!(plugin_slug("Simple Voice-Chat 2"), "simple-voice-chat-2");
+    }
+}
@@ -0,0 +1,237 @@
+//! GameSpy4 Query client (UDP)
+//!
+//! Включается на сервере через `enable-query=true` в server.properties (порт `query.port`).
+//! В отличие от Server List Ping отдаёт полный список игроков, карту и плагины.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::Duration;
+use tokio::net::UdpSocket;
+
+use super::{ServerError, ServerResult};
+
+const TIMEOUT: Duration = Duration::from_secs(5);
+
+const MAGIC: [u8; 2] = [0xFE, 0xFD];
+const TYPE_HANDSHAKE: u8 = 0x09;
+const TYPE_STAT: u8 = 0x00;
+
+/// Заголовок full stat перед парами ключ/значение
+const FULL_STAT_PADDING: &[u8] = b"splitnum\x00\x80\x00";
+/// Разделитель между парами и списком игроков
+const PLAYERS_PADDING: &[u8] = b"\x01player_\x00\x00";
+
+/// Ответ full stat
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QueryResult {
+    pub motd: String,
+    pub game_type: String,
+    pub version: String,
+    /// "Paper on 1.21.1: LuckPerms 5.4; Vault 1.7" — если сервер сообщает плагины
+    pub plugins: Option<String>,
+    pub map: String,
+    pub online_players: u32,
+    pub max_players: u32,
+    pub host_ip: String,
+    pub host_port: u16,
+    /// Все игроки онлайн
+    pub players: Vec<String>,
+}
+
+/// Запросить full stat у сервера
+pub async fn query(host: &str, port: u16) -> ServerResult<QueryResult> {
+    tokio::time::timeout(TIMEOUT, query_full(host, port))
+        .await
+        .map_err(|_| ServerError::Network("Query did not respond in time".to_string()))?
+}
+
+async fn query_full(host: &str, port: u16) -> ServerResult<QueryResult> {
+    let addr = tokio::net::lookup_host((host, port))
+        .await?
+        .next()
+        .ok_or_else(|| ServerError::Network(format!("Cannot resolve {}", host)))?;
+    let bind = if addr.is_ipv6() {
+        "[::]:0"
+    } else {
+        "0.0.0.0:0"
+    };
+    let socket = UdpSocket::bind(bind).await?;
+    socket.connect(addr).await?;
+
+    // Старшие биты каждого байта сервер всё равно обнуляет
+    let session_id = rand::random::<i32>() & 0x0F0F_0F0F;
+
+    let mut request = MAGIC.to_vec();
+    request.push(TYPE_HANDSHAKE);
+    request.extend_from_slice(&session_id.to_be_bytes());
+    socket.send(&request).await?;
+
+    let mut buf = vec![0u8; 64];
+    let len = socket.recv(&mut buf).await?;
+    let token = parse_handshake(&buf[..len], session_id)?;
+
+    let mut request = MAGIC.to_vec();
+    request.push(TYPE_STAT);
+    request.extend_from_slice(&session_id.to_be_bytes());
+    request.extend_from_slice(&token.to_be_bytes());
+    request.extend_from_slice(&[0, 0, 0, 0]); // padding = full stat
+    socket.send(&request).await?;
+
+    let mut buf = vec![0u8; 65535];
+    let len = socket.recv(&mut buf).await?;
+    parse_full_stat(&buf[..len], session_id)
+}
+
+/// Ответ на handshake: тип, session id, challenge token строкой
+fn parse_handshake(data: &[u8], session_id: i32) -> ServerResult<i32> {
+    let mut cursor = check_header(data, TYPE_HANDSHAKE, session_id)?;
+    read_cstring(&mut cursor)
+        .and_then(|token| token.trim().parse().ok())
+        .ok_or_else(|| ServerError::Network("Invalid query challenge token".to_string()))
+}
+
+fn parse_full_stat(data: &[u8], session_id: i32) -> ServerResult<QueryResult> {
+    let malformed = || ServerError::Network("Malformed query response".to_string());
+
+    let cursor = check_header(data, TYPE_STAT, session_id)?;
+    let mut cursor = cursor
+        .strip_prefix(FULL_STAT_PADDING)
+        .ok_or_else(malformed)?;
+
+    let mut values = HashMap::new();
+    loop {
+        let key = read_cstring(&mut cursor).ok_or_else(malformed)?;
+        if key.is_empty() {
+            break;
+        }
+        let value = read_cstring(&mut cursor).ok_or_else(malformed)?;
+        values.insert(key, value);
+    }
+
+    let mut cursor = cursor.strip_prefix(PLAYERS_PADDING).ok_or_else(malformed)?;
+    let mut players = Vec::new();
+    while let Some(name) = read_cstring(&mut cursor) {
+        if name.is_empty() {
+            break;
+        }
+        players.push(name);
+    }
+
+    let mut take = |key: &str| values.remove(key).unwrap_or_default();
+    Ok(QueryResult {
+        motd: take("hostname"),
+        game_type: take("gametype"),
+        version: take("version"),
+        plugins: Some(take("plugins")).filter(|p| !p.is_empty()),
+        map: take("map"),
+        online_players: take("numplayers").parse().unwrap_or(players.len() as u32),
+        max_players: take("maxplayers").parse().unwrap_or(0),
+        host_ip: take("hostip"),
+        host_port: take("hostport").parse().unwrap_or(0),
+        players,
+    })
+}
+
+/// Проверить тип и session id, вернуть остаток пакета
+fn check_header(data: &[u8], packet_type: u8, session_id: i32) -> ServerResult<&[u8]> {
+    if data.len() < 5 || data[0] != packet_type || data[1..5] != session_id.to_be_bytes() {
+        return Err(ServerError::Network(
+            "Unexpected query response".to_string(),
+        ));
+    }
+    Ok(&data[5..])
+}
+
+fn read_cstring(cursor: &mut &[u8]) -> Option<String> {
+    let end = cursor.iter().position(|&b| b == 0)?;
+    let s = String::from_utf8_lossy(&cursor[..end]).into_owned();
+    *cursor = &cursor[end + 1..];
+    Some(s)
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+/// `address` — адрес query-порта ("host:port", порт по умолчанию 25565)
+#[tauri::command]
+pub async fn query_server(address: String) -> Result<QueryResult, String> {
+    let (host, port) = super::ping::parse_address(&address).map_err(|e| e.to_string())?;
+    query(&host, port).await.map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn full_stat_response(session_id: i32) -> Vec<u8> {
+        let mut data = vec![TYPE_STAT];
+        data.extend_from_slice(&session_id.to_be_bytes());
+        data.extend_from_slice(FULL_STAT_PADDING);
+        for (key, value) in [
+            ("hostname", "A Minecraft Server"),
+            ("gametype", "SMP"),
+            ("game_id", "MINECRAFT"),
+            ("version", "1.21.1"),
+            ("plugins", ""),
+            ("map", "world"),
+            ("numplayers", "2"),
+            ("maxplayers", "20"),
+            ("hostport", "25565"),
+            ("hostip", "127.0.0.1"),
+        ] {
+            data.extend_from_slice(key.as_bytes());
+            data.push(0);
+            data.extend_from_slice(value.as_bytes());
+            data.push(0);
+        }
+        data.push(0);
+        data.extend_from_slice(PLAYERS_PADDING);
+        data.extend_from_slice(b"Steve\x00Alex\x00\x00");
+        data
+    }
+
+    #[test]
+    fn test_parse_full_stat() {
+        let result = parse_full_stat(&full_stat_response(7), 7).unwrap();
+        assert_eq!(result.motd, "A Minecraft Server");
+        assert_eq!(result.version, "1.21.1");
+        assert_eq!(result.plugins, None);
+        assert_eq!((result.online_players, result.max_players), (2, 20));
+        assert_eq!(result.host_port, 25565);
+        assert_eq!(result.players, vec!["Steve", "Alex"]);
+
+        assert!(parse_full_stat(&full_stat_response(7), 8).is_err());
+        assert!(parse_full_stat(&full_stat_response(7)[..20], 7).is_err());
+    }
+
+    #[tokio::test]
+    async fn test_query_fake_server() {
+        let server = UdpSocket::bind("127.0.0.1:0").await.unwrap();
+        let port = server.local_addr().unwrap().port();
+        tokio::spawn(async move {
+            let mut buf = [0u8; 64];
+
+            let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+            assert_eq!(&buf[..3], &[0xFE, 0xFD, TYPE_HANDSHAKE]);
+            let session = i32::from_be_bytes(buf[3..7].try_into().unwrap());
+            assert_eq!(len, 7);
+            let mut response = vec![TYPE_HANDSHAKE];
+            response.extend_from_slice(&session.to_be_bytes());
+            response.extend_from_slice(b"9513307\x00");
+            server.send_to(&response, peer).await.unwrap();
+
+            let (len, peer) = server.recv_from(&mut buf).await.unwrap();
+            assert_eq!(len, 15);
+            assert_eq!(i32::from_be_bytes(buf[7..11].try_into().unwrap()), 9513307);
+            server
+                .send_to(&full_stat_response(session), peer)
+                .await
+                .unwrap();
+        });
+
+        let result = query("127.0.0.1", port).await.unwrap();
+        assert_eq!(result.map, "world");
+        assert_eq!(result.players, vec!["Steve", "Alex"]);
+    }
+}
@@ -0,0 +1,861 @@
+//! Resource pack сервера: сборка из папки сервера и раздача встроенным HTTP
+//!
+//! Источники — `<server>/resourcepacks/`: папки с `pack.mcmeta` и готовые `.zip`.
+//! Папки собираются в детерминированный zip (сортировка, фиксированное время),
+//! поэтому sha1 меняется только вместе с содержимым. Собранные паки лежат в
+//! `.stuzhik/resource-packs/<sha1>.zip`, и sha1 входит в URL — при изменении
+//! пака меняется и URL, клиент не возьмёт старую версию из кэша.
+//!
+//! Vanilla-сервер отдаёт один пак из server.properties, поэтому несколько
+//! паков сливаются в один (первый в списке — высший приоритет). На 1.20.3+
+//! клиент держит стек паков по UUID: в server.properties пишется стабильный
+//! `resource-pack-id` (клиент заменяет пак, а не копит версии), а каждый
+//! исходный пак раздаётся и отдельно — со своим URL, sha1 и id для плагинов
+//! и прокси, которые умеют отправлять несколько паков.
+//!
+//! Пересборка — при публикации и перед каждым запуском сервера.
+
+use axum::body::Bytes;
+use axum::extract::Path as UrlPath;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use serde::{Deserialize, Serialize};
+use sha1::{Digest, Sha1};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::net::{IpAddr, SocketAddr};
+use std::path::{Path, PathBuf};
+use std::sync::LazyLock;
+use std::time::Duration;
+use tokio::sync::Mutex;
+use tokio_util::sync::CancellationToken;
+
+use super::properties::{load_properties, ServerProperties};
+use crate::error::{LauncherError, Result};
+use crate::instances::lifecycle::get_instance;
+use crate::paths::{instance_dir, instances_dir};
+use crate::settings::{ResourcePackHostSettings, SettingsManager};
+use crate::types::InstanceType;
+
+const SOURCES_DIR: &str = "resourcepacks";
+const CONFIG_FILE: &str = "resource_pack.json";
+const BUILD_DIR: &str = "resource-packs";
+/// Имя слитого пака в статусе
+const MERGED_NAME: &str = "merged";
+/// С этой версии клиент держит несколько паков и понимает `resource-pack-id`
+const PACK_ID_VERSION: &str = "1.20.3";
+
+/// Что публиковать
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ResourcePackConfig {
+    /// Имена из resourcepacks/ по приоритету (первый — высший).
+    /// Пусто — все источники по алфавиту.
+    #[serde(default)]
+    pub packs: Vec<String>,
+    /// require-resource-pack: без пака игрока отключит
+    #[serde(default)]
+    pub required: bool,
+    /// resource-pack-prompt (обычный текст)
+    #[serde(default)]
+    pub prompt: Option<String>,
+}
+
+/// Пак, который раздаёт лаунчер
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostedPack {
+    /// Имя источника; для слитого — "merged"
+    pub name: String,
+    pub sha1: String,
+    pub size: u64,
+    pub url: String,
+    /// UUID пака для 1.20.3+ (не меняется между пересборками)
+    pub id: String,
+}
+
+/// Опубликованное состояние (`.stuzhik/resource_pack.json`)
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PublishedPacks {
+    pub config: ResourcePackConfig,
+    /// Каждый источник отдельно
+    #[serde(default)]
+    pub packs: Vec<HostedPack>,
+    /// Записан в server.properties
+    #[serde(default)]
+    pub active: Option<HostedPack>,
+}
+
+/// Состояние для UI
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourcePackStatus {
+    /// Источники в resourcepacks/
+    pub sources: Vec<String>,
+    /// None — не опубликован
+    pub published: Option<PublishedPacks>,
+    /// Адрес HTTP-сервера, если запущен
+    pub host_address: Option<String>,
+}
+
+/// Состояние HTTP-сервера для UI
+#[derive(Debug, Clone, Serialize)]
+pub struct ResourcePackHostStatus {
+    pub running: bool,
+    pub address: Option<String>,
+}
+
+/// Собранный пак (до выдачи URL)
+#[derive(Debug, Clone, PartialEq)]
+struct BuiltPack {
+    name: String,
+    sha1: String,
+    size: u64,
+}
+
+pub struct ResourcePackManager;
+
+impl ResourcePackManager {
+    pub async fn status(instance_id: &str) -> Result<ResourcePackStatus> {
+        let server_dir = instance_dir(instance_id);
+        Ok(ResourcePackStatus {
+            sources: list_sources(&server_dir),
+            published: load_published(&server_dir)?,
+            host_address: ResourcePackHost::address().await.map(|a| a.to_string()),
+        })
+    }
+
+    /// Сохранить выбор паков, включить раздачу и прописать пак в server.properties
+    pub async fn publish(
+        instance_id: &str,
+        config: ResourcePackConfig,
+    ) -> Result<ResourcePackStatus> {
+        let instance = get_instance(instance_id.to_string()).await?;
+        if !matches!(instance.instance_type, InstanceType::Server) {
+            return Err(LauncherError::InvalidConfig(
+                "Resource packs can only be hosted for server instances".to_string(),
+            ));
+        }
+
+        let server_dir = instance_dir(instance_id);
+        let mut published = load_published(&server_dir)?.unwrap_or_default();
+        published.config = config;
+        save_published(&server_dir, &published)?;
+
+        let mut settings = SettingsManager::get_resource_pack_host()?;
+        if !settings.enabled {
+            settings.enabled = true;
+            SettingsManager::set_resource_pack_host(&settings)?;
+        }
+        ResourcePackHost::ensure_running(&settings).await?;
+
+        Self::sync(instance_id).await?;
+        Self::status(instance_id).await
+    }
+
+    /// Убрать пак из server.properties и удалить собранные файлы
+    pub async fn unpublish(instance_id: &str) -> Result<()> {
+        let server_dir = instance_dir(instance_id);
+        if load_published(&server_dir)?.is_none() {
+            return Ok(());
+        }
+
+        let mut props = load_properties(&server_dir)
+            .await
+            .unwrap_or_else(|_| ServerProperties::default_properties());
+        props.set("resource-pack", "");
+        props.set("resource-pack-sha1", "");
+        if props.get("resource-pack-id").is_some() {
+            props.set("resource-pack-id", "");
+        }
+        props.set("resource-pack-prompt", "");
+        props.set_bool("require-resource-pack", false);
+        props
+            .save(server_dir.join("server.properties"))
+            .await
+            .map_err(|e| LauncherError::InvalidConfig(e.to_string()))?;
+
+        let stuzhik_dir = server_dir.join(".stuzhik");
+        std::fs::remove_file(stuzhik_dir.join(CONFIG_FILE))?;
+        if stuzhik_dir.join(BUILD_DIR).exists() {
+            std::fs::remove_dir_all(stuzhik_dir.join(BUILD_DIR))?;
+        }
+        forget_missing_cached();
+        log::info!("Resource pack of {} unpublished", instance_id);
+        Ok(())
+    }
+
+    /// Пересобрать паки и обновить server.properties, если что-то изменилось.
+    /// None — для сервера ничего не опубликовано.
+    pub async fn sync(instance_id: &str) -> Result<Option<HostedPack>> {
+        let server_dir = instance_dir(instance_id);
+        let Some(mut published) = load_published(&server_dir)? else {
+            return Ok(None);
+        };
+        let instance = get_instance(instance_id.to_string()).await?;
+
+        let dir = server_dir.clone();
+        let config = published.config.clone();
+        let (packs, active) = tokio::task::spawn_blocking(move || build(&dir, &config))
+            .await
+            .map_err(|e| LauncherError::Join(e.to_string()))??;
+        forget_missing_cached();
+
+        let base_url = base_url(&SettingsManager::get_resource_pack_host()?);
+        let hosted = |pack: &BuiltPack, id_key: &str| HostedPack {
+            name: pack.name.clone(),
+            sha1: pack.sha1.clone(),
+            size: pack.size,
+            url: format!("{}/packs/{}/{}.zip", base_url, instance_id, pack.sha1),
+            id: pack_id(instance_id, id_key).to_string(),
+        };
+        published.packs = packs.iter().map(|p| hosted(p, &p.name)).collect();
+        // id в server.properties один на сервер: смена набора паков — тоже замена
+        let active = hosted(&active, "server.properties");
+
+        let mut props = load_properties(&server_dir)
+            .await
+            .unwrap_or_else(|_| ServerProperties::default_properties());
+        let mut wanted = vec![
+            ("resource-pack", active.url.clone()),
+            ("resource-pack-sha1", active.sha1.clone()),
+            (
+                "require-resource-pack",
+                published.config.required.to_string(),
+            ),
+            (
+                "resource-pack-prompt",
+                published
+                    .config
+                    .prompt
+                    .as_deref()
+                    .filter(|p| !p.is_empty())
+                    .map(|p| serde_json::Value::String(p.to_string()).to_string())
+                    .unwrap_or_default(),
+            ),
+        ];
+        if crate::utils::compare_versions(&instance.version, PACK_ID_VERSION)
+            != std::cmp::Ordering::Less
+        {
+            wanted.push(("resource-pack-id", active.id.clone()));
+        }
+
+        let changed = wanted
+            .iter()
+            .any(|(key, value)| props.get(key) != Some(value));
+        if changed {
+            for (key, value) in wanted {
+                props.set(key, value);
+            }
+            props
+                .save(server_dir.join("server.properties"))
+                .await
+                .map_err(|e| LauncherError::InvalidConfig(e.to_string()))?;
+            log::info!(
+                "Resource pack of {} updated: {} ({})",
+                instance_id,
+                active.url,
+                active.sha1
+            );
+        }
+
+        published.active = Some(active.clone());
+        save_published(&server_dir, &published)?;
+        Ok(Some(active))
+    }
+
+    /// Перед запуском сервера: подхватить изменения пака и поднять раздачу.
+    /// Ошибки не мешают запуску — только в лог.
+    pub(crate) async fn sync_before_start(instance_id: &str) {
+        match Self::sync(instance_id).await {
+            Ok(Some(_)) => {}
+            Ok(None) => return,
+            Err(e) => {
+                log::warn!("Failed to rebuild resource pack of {}: {}", instance_id, e);
+                return;
+            }
+        }
+        match SettingsManager::get_resource_pack_host() {
+            Ok(settings) => {
+                if let Err(e) = ResourcePackHost::ensure_running(&settings).await {
+                    log::warn!("Failed to start resource pack host: {}", e);
+                }
+            }
+            Err(e) => log::warn!("Failed to read resource pack host settings: {}", e),
+        }
+    }
+
+    /// Серверы с опубликованными паками
+    fn published_instances() -> Vec<String> {
+        let Ok(entries) = std::fs::read_dir(instances_dir()) else {
+            return Vec::new();
+        };
+        entries
+            .filter_map(|e| e.ok())
+            .filter(|e| e.path().join(".stuzhik").join(CONFIG_FILE).exists())
+            .filter_map(|e| e.file_name().to_str().map(String::from))
+            .collect()
+    }
+}
+
+// ============================================================================
+// HTTP host
+// ============================================================================
+
+struct RunningHost {
+    address: SocketAddr,
+    settings: ResourcePackHostSettings,
+    shutdown: CancellationToken,
+    task: tauri::async_runtime::JoinHandle<()>,
+}
+
+static RUNNING: LazyLock<Mutex<Option<RunningHost>>> = LazyLock::new(|| Mutex::new(None));
+
+/// Отданные паки в памяти: при входе толпы игроков файл не читается заново.
+/// Ключ — путь `<sha1>.zip`, содержимое по нему не меняется.
+static PACK_CACHE: LazyLock<std::sync::Mutex<HashMap<PathBuf, Bytes>>> =
+    LazyLock::new(|| std::sync::Mutex::new(HashMap::new()));
+
+/// Предел памяти под PACK_CACHE; паки крупнее отдаются с диска
+const PACK_CACHE_MAX_BYTES: usize = 256 * 1024 * 1024;
+
+pub struct ResourcePackHost;
+
+impl ResourcePackHost {
+    /// Запустить при старте лаунчера, если раздача включена
+    pub fn init() {
+        tauri::async_runtime::spawn(async move {
+            match SettingsManager::get_resource_pack_host() {
+                Ok(settings) if settings.enabled => {
+                    if let Err(e) = Self::ensure_running(&settings).await {
+                        log::error!("Failed to start resource pack host: {}", e);
+                    }
+                }
+                Ok(_) => {}
+                Err(e) => log::warn!("Failed to read resource pack host settings: {}", e),
+            }
+        });
+    }
+
+    /// Запустить, если не запущен или запущен с другими настройками
+    async fn ensure_running(settings: &ResourcePackHostSettings) -> Result<()> {
+        if let Some(running) = RUNNING.lock().await.as_ref() {
+            if running.settings.bind_address == settings.bind_address
+                && running.settings.port == settings.port
+            {
+                return Ok(());
+            }
+        }
+        Self::start(settings).await
+    }
+
+    async fn start(settings: &ResourcePackHostSettings) -> Result<()> {
+        Self::stop().await;
+
+        let ip: IpAddr = settings.bind_address.parse().map_err(|_| {
+            LauncherError::InvalidConfig(format!("Invalid bind address: {}", settings.bind_address))
+        })?;
+        let listener = tokio::net::TcpListener::bind(SocketAddr::new(ip, settings.port)).await?;
+        let address = listener.local_addr()?;
+
+        let router = Router::new().route("/packs/{instance_id}/{file}", get(serve_pack));
+        let shutdown = CancellationToken::new();
+        let signal = shutdown.clone().cancelled_owned();
+        let task = tauri::async_runtime::spawn(async move {
+            if let Err(e) = axum::serve(listener, router)
+                .with_graceful_shutdown(signal)
+                .await
+            {
+                log::error!("Resource pack host failed: {}", e);
+            }
+            log::info!("Resource pack host on {} stopped", address);
+        });
+
+        log::info!("Resource pack host listening on {}", address);
+        *RUNNING.lock().await = Some(RunningHost {
+            address,
+            settings: settings.clone(),
+            shutdown,
+            task,
+        });
+        Ok(())
+    }
+
+    async fn stop() {
+        let Some(running) = RUNNING.lock().await.take() else {
+            return;
+        };
+        running.shutdown.cancel();
+        if tokio::time::timeout(Duration::from_secs(5), running.task)
+            .await
+            .is_err()
+        {
+            log::warn!(
+                "Resource pack host on {} did not stop in time",
+                running.address
+            );
+        }
+    }
+
+    pub async fn address() -> Option<SocketAddr> {
+        RUNNING.lock().await.as_ref().map(|r| r.address)
+    }
+
+    pub async fn status() -> ResourcePackHostStatus {
+        let address = Self::address().await;
+        ResourcePackHostStatus {
+            running: address.is_some(),
+            address: address.map(|a| a.to_string()),
+        }
+    }
+
+    /// Сохранить настройки, перезапустить раздачу и обновить URL у серверов
+    pub async fn apply_settings(settings: ResourcePackHostSettings) -> Result<()> {
+        SettingsManager::set_resource_pack_host(&settings)?;
+        if settings.enabled {
+            Self::start(&settings).await?;
+        } else {
+            Self::stop().await;
+        }
+
+        for instance_id in ResourcePackManager::published_instances() {
+            if let Err(e) = ResourcePackManager::sync(&instance_id).await {
+                log::warn!("Failed to update resource pack of {}: {}", instance_id, e);
+            }
+        }
+        Ok(())
+    }
+}
+
+async fn serve_pack(UrlPath((instance_id, file)): UrlPath<(String, String)>) -> Response {
+    let valid_instance = !instance_id.is_empty()
+        && instance_id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    let sha1 = file
+        .strip_suffix(".zip")
+        .filter(|s| s.len() == 40 && s.chars().all(|c| c.is_ascii_hexdigit()));
+    let (true, Some(sha1)) = (valid_instance, sha1) else {
+        return StatusCode::NOT_FOUND.into_response();
+    };
+
+    let path = build_dir(&instance_dir(&instance_id)).join(format!("{}.zip", sha1));
+    let cached = PACK_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .get(&path)
+        .cloned();
+    let bytes = match cached {
+        Some(bytes) => bytes,
+        None => match tokio::fs::read(&path).await {
+            Ok(data) => {
+                let bytes = Bytes::from(data);
+                cache_pack(&path, &bytes);
+                bytes
+            }
+            Err(_) => return StatusCode::NOT_FOUND.into_response(),
+        },
+    };
+
+    log::debug!("Serving resource pack {}", path.display());
+    (
+        [
+            (header::CONTENT_TYPE, "application/zip"),
+            // Имя содержит sha1 — содержимое по URL никогда не меняется
+            (header::CACHE_CONTROL, "public, max-age=31536000, immutable"),
+        ],
+        bytes,
+    )
+        .into_response()
+}
+
+/// Положить пак в кэш: старые сборки того же экземпляра вытесняются, общий
+/// объём не превышает PACK_CACHE_MAX_BYTES
+fn cache_pack(path: &Path, bytes: &Bytes) {
+    if bytes.len() > PACK_CACHE_MAX_BYTES {
+        return;
+    }
+    let mut cache = PACK_CACHE.lock().unwrap_or_else(|e| e.into_inner());
+    cache.retain(|cached, _| cached.parent() != path.parent());
+    while cache.values().map(Bytes::len).sum::<usize>() + bytes.len() > PACK_CACHE_MAX_BYTES {
+        let Some(evicted) = cache.keys().next().cloned() else {
+            break;
+        };
+        cache.remove(&evicted);
+    }
+    cache.insert(path.to_path_buf(), bytes.clone());
+}
+
+fn forget_missing_cached() {
+    PACK_CACHE
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .retain(|path, _| path.exists());
+}
+
+/// `http://<адрес>:<порт>` для URL в server.properties
+fn base_url(settings: &ResourcePackHostSettings) -> String {
+    let host = settings
+        .public_address
+        .clone()
+        .or_else(crate::p2p::network::get_local_ip)
+        .unwrap_or_else(|| "127.0.0.1".to_string());
+    let host = if host.contains(':') && !host.starts_with('[') {
+        format!("[{}]", host)
+    } else {
+        host
+    };
+    format!("http://{}:{}", host, settings.port)
+}
+
+/// Стабильный UUID пака: один и тот же для сервера и имени
+fn pack_id(instance_id: &str, name: &str) -> uuid::Uuid {
+    uuid::Uuid::new_v5(
+        &uuid::Uuid::NAMESPACE_URL,
+        format!("stuzhik:resource-pack:{}:{}", instance_id, name).as_bytes(),
+    )
+}
+
+// ============================================================================
+// Storage & build
+// ============================================================================
+
+fn build_dir(server_dir: &Path) -> PathBuf {
+    server_dir.join(".stuzhik").join(BUILD_DIR)
+}
+
+fn load_published(server_dir: &Path) -> Result<Option<PublishedPacks>> {
+    let path = server_dir.join(".stuzhik").join(CONFIG_FILE);
+    if !path.exists() {
+        return Ok(None);
+    }
+    let content = std::fs::read_to_string(&path)?;
+    Ok(Some(serde_json::from_str(&content)?))
+}
+
+fn save_published(server_dir: &Path, published: &PublishedPacks) -> Result<()> {
+    let dir = server_dir.join(".stuzhik");
+    std::fs::create_dir_all(&dir)?;
+    crate::utils::atomic_write(
+        dir.join(CONFIG_FILE),
+        serde_json::to_string_pretty(published)?.as_bytes(),
+    )
+}
+
+/// Папки с pack.mcmeta и .zip из resourcepacks/, по алфавиту
+fn list_sources(server_dir: &Path) -> Vec<String> {
+    let Ok(entries) = std::fs::read_dir(server_dir.join(SOURCES_DIR)) else {
+        return Vec::new();
+    };
+    let mut sources: Vec<String> = entries
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            let path = e.path();
+            if path.is_dir() {
+                path.join("pack.mcmeta").is_file()
+            } else {
+                path.extension().is_some_and(|ext| ext == "zip")
+            }
+        })
+        .filter_map(|e| e.file_name().to_str().map(String::from))
+        .collect();
+    sources.sort();
+    sources
+}
+
+/// Собрать все источники и пак для server.properties (слитый, если их несколько).
+/// Лишние файлы в каталоге сборки удаляются.
+fn build(server_dir: &Path, config: &ResourcePackConfig) -> Result<(Vec<BuiltPack>, BuiltPack)> {
+    let available = list_sources(server_dir);
+    let names = if config.packs.is_empty() {
+        available.clone()
+    } else {
+        config.packs.clone()
+    };
+    if names.is_empty() {
+        return Err(LauncherError::InvalidConfig(format!(
+            "No resource packs in {}",
+            server_dir.join(SOURCES_DIR).display()
+        )));
+    }
+
+    let out_dir = build_dir(server_dir);
+    std::fs::create_dir_all(&out_dir)?;
+
+    let mut packs = Vec::new();
+    for name in &names {
+        if !available.contains(name) {
+            return Err(LauncherError::NotFound(format!(
+                "Resource pack {} not found in {}",
+                name, SOURCES_DIR
+            )));
+        }
+        let source = server_dir.join(SOURCES_DIR).join(name);
+        let (sha1, size) = if source.is_dir() {
+            let tmp = out_dir.join("building.tmp");
+            zip_folder(&source, &tmp)?;
+            store(&out_dir, &tmp, true)?
+        } else {
+            store(&out_dir, &source, false)?
+        };
+        packs.push(BuiltPack {
+            name: name.clone(),
+            sha1,
+            size,
+        });
+    }
+
+    let active = if packs.len() == 1 {
+        packs[0].clone()
+    } else {
+        let sources: Vec<PathBuf> = packs
+            .iter()
+            .map(|p| out_dir.join(format!("{}.zip", p.sha1)))
+            .collect();
+        let tmp = out_dir.join("merging.tmp");
+        merge_packs(&sources, &tmp)?;
+        let (sha1, size) = store(&out_dir, &tmp, true)?;
+        BuiltPack {
+            name: MERGED_NAME.to_string(),
+            sha1,
+            size,
+        }
+    };
+
+    let keep: HashSet<String> = packs
+        .iter()
+        .chain(std::iter::once(&active))
+        .map(|p| format!("{}.zip", p.sha1))
+        .collect();
+    for entry in std::fs::read_dir(&out_dir)?.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if !keep.contains(&name) {
+            std::fs::remove_file(entry.path()).ok();
+        }
+    }
+
+    Ok((packs, active))
+}
+
+/// Положить файл в каталог сборки как `<sha1>.zip`.
+/// `take` — файл временный: переместить, а не копировать.
+fn store(out_dir: &Path, file: &Path, take: bool) -> Result<(String, u64)> {
+    let (sha1, size) = sha1_file(file)?;
+    let target = out_dir.join(format!("{}.zip", sha1));
+    if target.exists() {
+        if take {
+            std::fs::remove_file(file)?;
+        }
+    } else if take {
+        std::fs::rename(file, &target)?;
+    } else {
+        std::fs::copy(file, &target)?;
+    }
+    Ok((sha1, size))
+}
+
+fn sha1_file(path: &Path) -> Result<(String, u64)> {
+    let mut file = std::fs::File::open(path)?;
+    let mut hasher = Sha1::new();
+    let size = std::io::copy(&mut file, &mut hasher)?;
+    Ok((hex::encode(hasher.finalize()), size))
+}
+
+fn zip_options() -> zip::write::SimpleFileOptions {
+    zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .last_modified_time(zip::DateTime::default())
+        .unix_permissions(0o644)
+}
+
+/// Детерминированный zip папки: одинаковое содержимое — одинаковый sha1
+fn zip_folder(dir: &Path, out: &Path) -> Result<()> {
+    let mut files = Vec::new();
+    collect_files(dir, "", &mut files)?;
+    files.sort();
+
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(out)?);
+    for relative in files {
+        zip.start_file(relative.as_str(), zip_options())?;
+        let mut file = std::fs::File::open(dir.join(&relative))?;
+        std::io::copy(&mut file, &mut zip)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+/// Файлы папки с путями через `/`; скрытые (.git, .DS_Store) пропускаются
+fn collect_files(dir: &Path, prefix: &str, out: &mut Vec<String>) -> Result<()> {
+    for entry in std::fs::read_dir(dir)?.filter_map(|e| e.ok()) {
+        let name = entry.file_name().to_string_lossy().to_string();
+        if name.starts_with('.') {
+            continue;
+        }
+        let relative = format!("{}{}", prefix, name);
+        if entry.file_type()?.is_dir() {
+            collect_files(&entry.path(), &format!("{}/", relative), out)?;
+        } else {
+            out.push(relative);
+        }
+    }
+    Ok(())
+}
+
+/// Слить паки в один: файл берётся из пака с наивысшим приоритетом (первого),
+/// включая pack.mcmeta. Данные копируются без пережатия.
+fn merge_packs(packs: &[PathBuf], out: &Path) -> Result<()> {
+    let mut archives = packs
+        .iter()
+        .map(|p| Ok(zip::ZipArchive::new(std::fs::File::open(p)?)?))
+        .collect::<Result<Vec<_>>>()?;
+
+    let mut entries: BTreeMap<String, (usize, usize)> = BTreeMap::new();
+    for (pack, archive) in archives.iter_mut().enumerate() {
+        for index in 0..archive.len() {
+            let name = archive.by_index_raw(index)?.name().to_string();
+            if !name.ends_with('/') {
+                entries.entry(name).or_insert((pack, index));
+            }
+        }
+    }
+
+    let mut zip = zip::ZipWriter::new(std::fs::File::create(out)?);
+    for (pack, index) in entries.into_values() {
+        zip.raw_copy_file(archives[pack].by_index_raw(index)?)?;
+    }
+    zip.finish()?;
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_server_resource_packs(instance_id: String) -> Result<ResourcePackStatus> {
+    ResourcePackManager::status(&instance_id).await
+}
+
+#[tauri::command]
+pub async fn publish_server_resource_packs(
+    instance_id: String,
+    config: ResourcePackConfig,
+) -> Result<ResourcePackStatus> {
+    ResourcePackManager::publish(&instance_id, config).await
+}
+
+#[tauri::command]
+pub async fn unpublish_server_resource_packs(instance_id: String) -> Result<()> {
+    ResourcePackManager::unpublish(&instance_id).await
+}
+
+#[tauri::command]
+pub async fn get_resource_pack_host_settings() -> Result<ResourcePackHostSettings> {
+    SettingsManager::get_resource_pack_host()
+}
+
+#[tauri::command]
+pub async fn save_resource_pack_host_settings(
+    settings: ResourcePackHostSettings,
+) -> Result<ResourcePackHostStatus> {
+    ResourcePackHost::apply_settings(settings).await?;
+    Ok(ResourcePackHost::status().await)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Read;
+
+    fn write(path: &Path, content: &str) {
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, content).unwrap();
+    }
+
+    fn read_entry(zip_path: &Path, name: &str) -> String {
+        let mut archive = zip::ZipArchive::new(std::fs::File::open(zip_path).unwrap()).unwrap();
+        let mut content = String::new();
+        archive
+            .by_name(name)
+            .unwrap()
+            .read_to_string(&mut content)
+            .unwrap();
+        content
+    }
+
+    #[test]
+    fn test_build_is_deterministic_and_merges_by_priority() {
+        let server =
+            std::env::temp_dir().join(format!("stuzhik-resource-pack-{}", std::process::id()));
+        let sources = server.join(SOURCES_DIR);
+        write(
+            &sources.join("base/pack.mcmeta"),
+            r#"{"pack":{"pack_format":15}}"#,
+        );
+        write(
+            &sources.join("base/assets/minecraft/lang/en_us.json"),
+            "base",
+        );
+        write(
+            &sources.join("base/assets/minecraft/texts/splashes.txt"),
+            "hi",
+        );
+        write(
+            &sources.join("top/pack.mcmeta"),
+            r#"{"pack":{"pack_format":34}}"#,
+        );
+        write(&sources.join("top/assets/minecraft/lang/en_us.json"), "top");
+        write(&sources.join("notes.txt"), "not a pack");
+        assert_eq!(list_sources(&server), vec!["base", "top"]);
+
+        let config = ResourcePackConfig {
+            packs: vec!["top".to_string(), "base".to_string()],
+            ..Default::default()
+        };
+        let (packs, active) = build(&server, &config).unwrap();
+        assert_eq!(packs.len(), 2);
+        assert_eq!(active.name, MERGED_NAME);
+
+        // Та же сборка — тот же sha1 (и тот же URL)
+        let (_, again) = build(&server, &config).unwrap();
+        assert_eq!(active, again);
+
+        let merged = build_dir(&server).join(format!("{}.zip", active.sha1));
+        assert_eq!(
+            read_entry(&merged, "assets/minecraft/lang/en_us.json"),
+            "top"
+        );
+        assert_eq!(
+            read_entry(&merged, "assets/minecraft/texts/splashes.txt"),
+            "hi"
+        );
+        assert!(read_entry(&merged, "pack.mcmeta").contains("34"));
+
+        // Изменение источника меняет sha1, старые файлы убираются
+        write(
+            &sources.join("top/assets/minecraft/lang/en_us.json"),
+            "top v2",
+        );
+        let (_, changed) = build(&server, &config).unwrap();
+        assert_ne!(changed.sha1, active.sha1);
+        assert!(!merged.exists());
+        assert_eq!(std::fs::read_dir(build_dir(&server)).unwrap().count(), 3);
+
+        std::fs::remove_dir_all(&server).ok();
+    }
+
+    #[test]
+    fn test_base_url_and_pack_id() {
+        let mut settings = ResourcePackHostSettings {
+            public_address: Some("mc.example.org".to_string()),
+            ..Default::default()
+        };
+        assert_eq!(base_url(&settings), "http://mc.example.org:25591");
+        settings.public_address = Some("2001:db8::1".to_string());
+        assert_eq!(base_url(&settings), "http://[2001:db8::1]:25591");
+
+        assert_eq!(
+            pack_id("srv", "server.properties"),
+            pack_id("srv", "server.properties")
+        );
+        assert_ne!(pack_id("srv", "a"), pack_id("srv", "b"));
+    }
+}
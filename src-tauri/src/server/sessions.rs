@@ -0,0 +1,960 @@
+//! Player sessions: кто, когда и откуда заходил на сервер
+//!
+//! Сессии собираются из строк консоли (`UUID of player`, `logged in with entity id`,
+//! `lost connection`, `left the game`) и хранятся в БД. Поверх них — история игрока,
+//! общее время в игре, «последний раз в сети» и график онлайна. Для импортированных
+//! серверов история восстанавливается из `logs/*.log.gz`.
+
+use chrono::{DateTime, Local, NaiveDate, NaiveTime, SecondsFormat, TimeZone, Utc};
+use regex::Regex;
+use rusqlite::params;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+use std::sync::{LazyLock, Mutex};
+
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+
+/// Ник Java-игрока; `.`/`*` — префиксы Bedrock-игроков через Geyser/Floodgate
+const NAME: &str = r"[.*]?[A-Za-z0-9_]{1,16}";
+
+static RE_UUID: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(
+        r"^UUID of player ({}) is ([0-9a-fA-F-]{{32,36}})$",
+        NAME
+    ))
+    .unwrap()
+});
+static RE_LOGIN: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(&format!(r"^({})\[/?(.*?)\] logged in with entity id", NAME)).unwrap()
+});
+static RE_LOST: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"^({}) lost connection: (.*)$", NAME)).unwrap());
+static RE_JOINED: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"^({}) joined the game$", NAME)).unwrap());
+static RE_LEFT: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(&format!(r"^({}) left the game$", NAME)).unwrap());
+static RE_LOG_TIME: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^\[(\d{2}):(\d{2}):(\d{2})").unwrap());
+
+/// Трекеры живых серверов: UUID приходит раньше входа, а сессии открыты между строками
+static TRACKERS: LazyLock<Mutex<HashMap<String, SessionTracker>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Сессия игрока
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerSession {
+    pub id: i64,
+    pub instance_id: String,
+    pub player_name: String,
+    pub player_uuid: Option<String>,
+    pub ip: Option<String>,
+    pub joined_at: String,
+    /// None — игрок ещё на сервере
+    pub left_at: Option<String>,
+    pub disconnect_reason: Option<String>,
+}
+
+/// Сводка по игроку
+#[derive(Debug, Clone, Serialize)]
+pub struct PlayerSummary {
+    pub player_name: String,
+    pub player_uuid: Option<String>,
+    pub sessions: u32,
+    pub total_playtime_secs: i64,
+    pub first_seen: String,
+    pub last_seen: String,
+    pub last_ip: Option<String>,
+    pub online: bool,
+}
+
+/// Пик онлайна за интервал графика
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConcurrencyPoint {
+    pub timestamp: String,
+    pub players: u32,
+}
+
+/// Событие игрока из строки лога
+#[derive(Debug, Clone, PartialEq)]
+enum PlayerEvent {
+    Uuid { name: String, uuid: String },
+    Login { name: String, ip: Option<String> },
+    Joined { name: String },
+    LostConnection { name: String, reason: String },
+    Left { name: String },
+}
+
+/// Открытая сессия в трекере
+#[derive(Debug, Clone)]
+struct OpenSession {
+    /// id в БД (только для живых серверов)
+    id: Option<i64>,
+    name: String,
+    uuid: Option<String>,
+    ip: Option<String>,
+    joined_at: DateTime<Utc>,
+    reason: Option<String>,
+}
+
+/// Завершённая сессия
+#[derive(Debug, Clone)]
+struct ClosedSession {
+    session: OpenSession,
+    left_at: DateTime<Utc>,
+}
+
+/// Собирает сессии из потока событий
+#[derive(Debug, Default)]
+struct SessionTracker {
+    pending_uuids: HashMap<String, String>,
+    open: HashMap<String, OpenSession>,
+}
+
+impl SessionTracker {
+    /// Обработать событие. Возвращает новую открытую сессию (ключ) и закрытые сессии.
+    fn handle(
+        &mut self,
+        event: PlayerEvent,
+        at: DateTime<Utc>,
+    ) -> (Option<String>, Vec<ClosedSession>) {
+        let mut closed = Vec::new();
+        let opened = match event {
+            PlayerEvent::Uuid { name, uuid } => {
+                self.pending_uuids.insert(name.to_lowercase(), uuid);
+                None
+            }
+            PlayerEvent::Login { name, ip } => {
+                // Повторный вход без выхода — прошлая сессия оборвалась
+                closed.extend(self.close(&name, at));
+                Some(self.open(name, ip, at))
+            }
+            PlayerEvent::Joined { name } => {
+                // На части серверов строки "logged in" нет
+                (!self.open.contains_key(&name.to_lowercase())).then(|| self.open(name, None, at))
+            }
+            PlayerEvent::LostConnection { name, reason } => {
+                if let Some(session) = self.open.get_mut(&name.to_lowercase()) {
+                    session.reason = Some(reason);
+                }
+                closed.extend(self.close(&name, at));
+                None
+            }
+            PlayerEvent::Left { name } => {
+                closed.extend(self.close(&name, at));
+                None
+            }
+        };
+        (opened, closed)
+    }
+
+    fn open(&mut self, name: String, ip: Option<String>, at: DateTime<Utc>) -> String {
+        let key = name.to_lowercase();
+        let uuid = self.pending_uuids.remove(&key);
+        self.open.insert(
+            key.clone(),
+            OpenSession {
+                id: None,
+                name,
+                uuid,
+                ip,
+                joined_at: at,
+                reason: None,
+            },
+        );
+        key
+    }
+
+    fn close(&mut self, name: &str, at: DateTime<Utc>) -> Option<ClosedSession> {
+        self.open
+            .remove(&name.to_lowercase())
+            .map(|session| ClosedSession {
+                session,
+                left_at: at,
+            })
+    }
+
+    /// Закрыть все сессии (сервер остановился или лог закончился)
+    fn close_all(&mut self, at: DateTime<Utc>, reason: &str) -> Vec<ClosedSession> {
+        self.pending_uuids.clear();
+        self.open
+            .drain()
+            .map(|(_, mut session)| {
+                session.reason.get_or_insert_with(|| reason.to_string());
+                ClosedSession {
+                    session,
+                    left_at: at,
+                }
+            })
+            .collect()
+    }
+}
+
+pub struct PlayerSessionManager;
+
+impl PlayerSessionManager {
+    /// Создать таблицу сессий если не существует
+    fn ensure_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute_batch(
+            r#"CREATE TABLE IF NOT EXISTS player_sessions (
+                id INTEGER PRIMARY KEY AUTOINCREMENT,
+                instance_id TEXT NOT NULL,
+                player_name TEXT NOT NULL,
+                player_uuid TEXT,
+                ip TEXT,
+                joined_at TEXT NOT NULL,
+                left_at TEXT,
+                disconnect_reason TEXT,
+                UNIQUE(instance_id, player_name, joined_at)
+            );
+            CREATE INDEX IF NOT EXISTS idx_player_sessions_time
+                ON player_sessions(instance_id, joined_at);"#,
+        )?;
+        Ok(())
+    }
+
+    /// Обработать строку консоли живого сервера
+    pub fn record_line(instance_id: &str, line: &str) -> Result<()> {
+        let Some(event) = parse_player_event(line) else {
+            return Ok(());
+        };
+
+        // Трекер держим только на время разбора: БД ниже работает без блокировки
+        let now = Utc::now();
+        let (opened, closed) = {
+            let mut trackers = TRACKERS.lock().unwrap_or_else(|e| e.into_inner());
+            let tracker = trackers.entry(instance_id.to_string()).or_default();
+            let (opened, closed) = tracker.handle(event, now);
+            let opened = opened.and_then(|key| tracker.open.get(&key).cloned().map(|s| (key, s)));
+            (opened, closed)
+        };
+
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        for session in &closed {
+            Self::store_closed(&conn, instance_id, session)?;
+        }
+        if let Some((key, session)) = opened {
+            let id = Self::insert_open(&conn, instance_id, &session)?;
+            let mut trackers = TRACKERS.lock().unwrap_or_else(|e| e.into_inner());
+            if let Some(open) = trackers
+                .get_mut(instance_id)
+                .and_then(|tracker| tracker.open.get_mut(&key))
+                .filter(|open| open.joined_at == session.joined_at)
+            {
+                open.id = Some(id);
+            }
+        }
+        Ok(())
+    }
+
+    /// Записать открытую сессию и вернуть её id. Перезаход в ту же секунду попадает
+    /// в уже существующую строку (UNIQUE по joined_at) — тогда берём её id.
+    fn insert_open(
+        conn: &rusqlite::Connection,
+        instance_id: &str,
+        session: &OpenSession,
+    ) -> Result<i64> {
+        let joined_at = format_time(session.joined_at);
+        let inserted = conn.execute(
+            r#"INSERT OR IGNORE INTO player_sessions
+               (instance_id, player_name, player_uuid, ip, joined_at)
+               VALUES (?1, ?2, ?3, ?4, ?5)"#,
+            params![
+                instance_id,
+                session.name,
+                session.uuid,
+                session.ip,
+                joined_at,
+            ],
+        )?;
+        if inserted == 1 {
+            return Ok(conn.last_insert_rowid());
+        }
+
+        let id = conn.query_row(
+            r#"SELECT id FROM player_sessions
+               WHERE instance_id = ?1 AND player_name = ?2 AND joined_at = ?3"#,
+            params![instance_id, session.name, joined_at],
+            |row| row.get(0),
+        )?;
+        // Сессия снова открыта
+        conn.execute(
+            "UPDATE player_sessions SET left_at = NULL, disconnect_reason = NULL WHERE id = ?1",
+            [id],
+        )?;
+        Ok(id)
+    }
+
+    /// Закрыть открытые сессии сервера (сервер остановлен)
+    pub fn end_all(instance_id: &str, reason: &str) -> Result<()> {
+        let closed = {
+            let mut trackers = TRACKERS.lock().unwrap_or_else(|e| e.into_inner());
+            match trackers.get_mut(instance_id) {
+                Some(tracker) => tracker.close_all(Utc::now(), reason),
+                None => return Ok(()),
+            }
+        };
+        if closed.is_empty() {
+            return Ok(());
+        }
+
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        for session in &closed {
+            Self::store_closed(&conn, instance_id, session)?;
+        }
+        Ok(())
+    }
+
+    /// Сессии, оставшиеся открытыми с прошлого запуска (лаунчер закрылся раньше сервера).
+    /// Время выхода неизвестно — сессия закрывается моментом входа.
+    pub fn close_stale(instance_id: &str) -> Result<()> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        conn.execute(
+            r#"UPDATE player_sessions
+               SET left_at = joined_at, disconnect_reason = 'Session end not recorded'
+               WHERE instance_id = ?1 AND left_at IS NULL"#,
+            [instance_id],
+        )?;
+        Ok(())
+    }
+
+    fn store_closed(
+        conn: &rusqlite::Connection,
+        instance_id: &str,
+        closed: &ClosedSession,
+    ) -> Result<()> {
+        let session = &closed.session;
+        match session.id {
+            Some(id) => {
+                conn.execute(
+                    "UPDATE player_sessions SET left_at = ?1, disconnect_reason = ?2 WHERE id = ?3",
+                    params![format_time(closed.left_at), session.reason, id],
+                )?;
+            }
+            None => {
+                conn.execute(
+                    r#"INSERT OR IGNORE INTO player_sessions
+                       (instance_id, player_name, player_uuid, ip, joined_at, left_at, disconnect_reason)
+                       VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+                    params![
+                        instance_id,
+                        session.name,
+                        session.uuid,
+                        session.ip,
+                        format_time(session.joined_at),
+                        format_time(closed.left_at),
+                        session.reason,
+                    ],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
+    /// История сессий, новые первыми
+    pub fn list(instance_id: &str, player: Option<&str>, limit: u32) -> Result<Vec<PlayerSession>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, instance_id, player_name, player_uuid, ip, joined_at, left_at, disconnect_reason
+               FROM player_sessions
+               WHERE instance_id = ?1 AND (?2 IS NULL OR player_name = ?2 COLLATE NOCASE)
+               ORDER BY joined_at DESC LIMIT ?3"#,
+        )?;
+        let sessions = stmt
+            .query_map(params![instance_id, player, limit], row_to_session)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(sessions)
+    }
+
+    /// Кто был на сервере в промежутке [from, to]
+    pub fn online_between(instance_id: &str, from: &str, to: &str) -> Result<Vec<PlayerSession>> {
+        let (from, to) = (parse_time(from)?, parse_time(to)?);
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, instance_id, player_name, player_uuid, ip, joined_at, left_at, disconnect_reason
+               FROM player_sessions
+               WHERE instance_id = ?1 AND joined_at <= ?2 AND (left_at IS NULL OR left_at >= ?3)
+               ORDER BY joined_at"#,
+        )?;
+        let sessions = stmt
+            .query_map(
+                params![instance_id, format_time(to), format_time(from)],
+                row_to_session,
+            )?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(sessions)
+    }
+
+    fn all(instance_id: &str) -> Result<Vec<PlayerSession>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, instance_id, player_name, player_uuid, ip, joined_at, left_at, disconnect_reason
+               FROM player_sessions WHERE instance_id = ?1 ORDER BY joined_at"#,
+        )?;
+        let sessions = stmt
+            .query_map([instance_id], row_to_session)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(sessions)
+    }
+
+    /// Сводка по всем игрокам: время в игре, последний вход, онлайн сейчас
+    pub fn summaries(instance_id: &str) -> Result<Vec<PlayerSummary>> {
+        Ok(summarize(&Self::all(instance_id)?, Utc::now()))
+    }
+
+    /// Пиковый онлайн по интервалам `bucket_minutes`
+    pub fn concurrency(
+        instance_id: &str,
+        from: &str,
+        to: &str,
+        bucket_minutes: u32,
+    ) -> Result<Vec<ConcurrencyPoint>> {
+        let (from, to) = (parse_time(from)?, parse_time(to)?);
+        let sessions = Self::online_between(instance_id, &format_time(from), &format_time(to))?;
+        Ok(peak_concurrency(
+            &sessions,
+            from,
+            to,
+            chrono::Duration::minutes(bucket_minutes.max(1) as i64),
+            Utc::now(),
+        ))
+    }
+
+    /// Восстановить историю из `logs/*.log.gz` и `logs/latest.log`.
+    /// Повторный запуск не создаёт дублей. Возвращает число новых сессий.
+    pub fn backfill_from_logs(instance_id: &str, server_dir: &Path) -> Result<usize> {
+        let logs_dir = server_dir.join("logs");
+        let Ok(entries) = std::fs::read_dir(&logs_dir) else {
+            return Ok(0);
+        };
+
+        // 2024-05-01-3.log.gz: дата начала лога и номер за день
+        let mut archives: Vec<(NaiveDate, u32, std::path::PathBuf)> = entries
+            .filter_map(|e| e.ok())
+            .filter_map(|e| {
+                let name = e.file_name().to_string_lossy().to_string();
+                let stem = name.strip_suffix(".log.gz")?;
+                let date = NaiveDate::parse_from_str(stem.get(..10)?, "%Y-%m-%d").ok()?;
+                let index = stem.get(11..).and_then(|i| i.parse().ok()).unwrap_or(0);
+                Some((date, index, e.path()))
+            })
+            .collect();
+        archives.sort();
+
+        let mut sessions = Vec::new();
+        for (date, _, path) in &archives {
+            match read_gz_lines(path) {
+                Ok(lines) => sessions.extend(replay_log(&lines, *date)),
+                Err(e) => log::warn!("Failed to read {}: {}", path.display(), e),
+            }
+        }
+
+        // latest.log: известна только дата последнего изменения, считаем назад по переходам через полночь
+        let latest = logs_dir.join("latest.log");
+        if let (Ok(data), Ok(meta)) = (std::fs::read(&latest), std::fs::metadata(&latest)) {
+            let lines: Vec<String> = String::from_utf8_lossy(&data)
+                .lines()
+                .map(str::to_string)
+                .collect();
+            if let Ok(modified) = meta.modified() {
+                let end_date = DateTime::<Local>::from(modified).date_naive();
+                let start_date = end_date - chrono::Days::new(midnight_rollovers(&lines) as u64);
+                sessions.extend(replay_log(&lines, start_date));
+            }
+        }
+
+        let mut conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        let tx = conn.transaction()?;
+        let mut inserted = 0;
+        for closed in &sessions {
+            let session = &closed.session;
+            inserted += tx.execute(
+                r#"INSERT OR IGNORE INTO player_sessions
+                   (instance_id, player_name, player_uuid, ip, joined_at, left_at, disconnect_reason)
+                   VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7)"#,
+                params![
+                    instance_id,
+                    session.name,
+                    session.uuid,
+                    session.ip,
+                    format_time(session.joined_at),
+                    format_time(closed.left_at),
+                    session.reason,
+                ],
+            )?;
+        }
+        tx.commit()?;
+
+        log::info!(
+            "Backfilled {} player sessions for {} from {} log files",
+            inserted,
+            instance_id,
+            archives.len()
+        );
+        Ok(inserted)
+    }
+}
+
+/// Событие игрока из строки консоли
+fn parse_player_event(line: &str) -> Option<PlayerEvent> {
+    // Сообщение после заголовка "[time] [thread/LEVEL]: " или "[time] [thread/LEVEL] [source]: "
+    let message = line
+        .split_once("]: ")
+        .map(|(_, m)| m)
+        .unwrap_or(line)
+        .trim_end();
+
+    if let Some(c) = RE_UUID.captures(message) {
+        return Some(PlayerEvent::Uuid {
+            name: c[1].to_string(),
+            uuid: c[2].to_string(),
+        });
+    }
+    if let Some(c) = RE_LOGIN.captures(message) {
+        return Some(PlayerEvent::Login {
+            name: c[1].to_string(),
+            ip: parse_login_address(&c[2]),
+        });
+    }
+    if let Some(c) = RE_LOST.captures(message) {
+        return Some(PlayerEvent::LostConnection {
+            name: c[1].to_string(),
+            reason: c[2].trim().to_string(),
+        });
+    }
+    if let Some(c) = RE_JOINED.captures(message) {
+        return Some(PlayerEvent::Joined {
+            name: c[1].to_string(),
+        });
+    }
+    RE_LEFT.captures(message).map(|c| PlayerEvent::Left {
+        name: c[1].to_string(),
+    })
+}
+
+/// "127.0.0.1:54321", "[::1]:54321" -> IP без порта; "local" (одиночная игра) -> None
+fn parse_login_address(address: &str) -> Option<String> {
+    let host = match address.rsplit_once(':') {
+        Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+        _ => address,
+    };
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    (!host.is_empty() && host != "local").then(|| host.to_string())
+}
+
+/// Сессии из строк одного файла лога, начатого в день `start_date` (местное время)
+fn replay_log(lines: &[String], start_date: NaiveDate) -> Vec<ClosedSession> {
+    let mut tracker = SessionTracker::default();
+    let mut closed = Vec::new();
+    let mut date = start_date;
+    let mut last_time: Option<NaiveTime> = None;
+    let mut last_at: Option<DateTime<Utc>> = None;
+
+    for line in lines {
+        let Some(time) = parse_log_time(line) else {
+            continue;
+        };
+        if last_time.is_some_and(|last| time < last) {
+            date = date.succ_opt().unwrap_or(date);
+        }
+        last_time = Some(time);
+        let Some(at) = Local
+            .from_local_datetime(&date.and_time(time))
+            .earliest()
+            .map(|t| t.with_timezone(&Utc))
+        else {
+            continue;
+        };
+        last_at = Some(at);
+
+        if let Some(event) = parse_player_event(line) {
+            closed.extend(tracker.handle(event, at).1);
+        }
+    }
+
+    // Каждый запуск сервера пишет свой лог: всё открытое закончилось с последней строкой
+    if let Some(at) = last_at {
+        closed.extend(tracker.close_all(at, "Server stopped"));
+    }
+    closed
+}
+
+fn parse_log_time(line: &str) -> Option<NaiveTime> {
+    let c = RE_LOG_TIME.captures(line)?;
+    NaiveTime::from_hms_opt(c[1].parse().ok()?, c[2].parse().ok()?, c[3].parse().ok()?)
+}
+
+/// Сколько раз время в логе перескакивало через полночь
+fn midnight_rollovers(lines: &[String]) -> usize {
+    let mut count = 0;
+    let mut last: Option<NaiveTime> = None;
+    for time in lines.iter().filter_map(|l| parse_log_time(l)) {
+        if last.is_some_and(|last| time < last) {
+            count += 1;
+        }
+        last = Some(time);
+    }
+    count
+}
+
+fn read_gz_lines(path: &Path) -> std::io::Result<Vec<String>> {
+    let mut data = Vec::new();
+    flate2::read::GzDecoder::new(std::fs::File::open(path)?).read_to_end(&mut data)?;
+    Ok(String::from_utf8_lossy(&data)
+        .lines()
+        .map(str::to_string)
+        .collect())
+}
+
+/// Сводка по игрокам; открытые сессии считаются до `now`
+fn summarize(sessions: &[PlayerSession], now: DateTime<Utc>) -> Vec<PlayerSummary> {
+    let mut players: HashMap<String, PlayerSummary> = HashMap::new();
+
+    for session in sessions {
+        let Ok(joined) = parse_time(&session.joined_at) else {
+            continue;
+        };
+        let left = session
+            .left_at
+            .as_deref()
+            .and_then(|t| parse_time(t).ok())
+            .unwrap_or(now);
+        let online = session.left_at.is_none();
+
+        let summary = players
+            .entry(session.player_name.to_lowercase())
+            .or_insert_with(|| PlayerSummary {
+                player_name: session.player_name.clone(),
+                player_uuid: None,
+                sessions: 0,
+                total_playtime_secs: 0,
+                first_seen: session.joined_at.clone(),
+                last_seen: session.joined_at.clone(),
+                last_ip: None,
+                online: false,
+            });
+
+        // Сессии отсортированы по входу: последние значения — самые свежие
+        summary.player_name = session.player_name.clone();
+        summary.sessions += 1;
+        summary.total_playtime_secs += (left - joined).num_seconds().max(0);
+        summary.online |= online;
+        if session.player_uuid.is_some() {
+            summary.player_uuid = session.player_uuid.clone();
+        }
+        if session.ip.is_some() {
+            summary.last_ip = session.ip.clone();
+        }
+        let last_seen = format_time(left);
+        if last_seen > summary.last_seen {
+            summary.last_seen = last_seen;
+        }
+    }
+
+    let mut summaries: Vec<PlayerSummary> = players.into_values().collect();
+    summaries.sort_by(|a, b| b.last_seen.cmp(&a.last_seen));
+    summaries
+}
+
+/// Пиковый онлайн в каждом интервале [from, to)
+fn peak_concurrency(
+    sessions: &[PlayerSession],
+    from: DateTime<Utc>,
+    to: DateTime<Utc>,
+    bucket: chrono::Duration,
+    now: DateTime<Utc>,
+) -> Vec<ConcurrencyPoint> {
+    // +1 на входе, -1 на выходе; выходы раньше входов в тот же момент
+    let mut events: Vec<(DateTime<Utc>, i32)> = Vec::new();
+    for session in sessions {
+        let Ok(joined) = parse_time(&session.joined_at) else {
+            continue;
+        };
+        let left = session
+            .left_at
+            .as_deref()
+            .and_then(|t| parse_time(t).ok())
+            .unwrap_or(now);
+        events.push((joined, 1));
+        events.push((left, -1));
+    }
+    events.sort();
+
+    let mut points = Vec::new();
+    let mut online = 0i32;
+    let mut events = events.into_iter().peekable();
+    let mut bucket_start = from;
+    while bucket_start < to {
+        let bucket_end = bucket_start + bucket;
+        // Состояние на начало интервала
+        while let Some((_, delta)) = events.next_if(|(t, _)| *t <= bucket_start) {
+            online += delta;
+        }
+        let mut peak = online;
+        while let Some((_, delta)) = events.next_if(|(t, _)| *t < bucket_end) {
+            online += delta;
+            peak = peak.max(online);
+        }
+        points.push(ConcurrencyPoint {
+            timestamp: format_time(bucket_start),
+            players: peak.max(0) as u32,
+        });
+        bucket_start = bucket_end;
+    }
+    points
+}
+
+fn format_time(time: DateTime<Utc>) -> String {
+    time.to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn parse_time(time: &str) -> Result<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(time)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|_| LauncherError::InvalidConfig(format!("Invalid time: {}", time)))
+}
+
+fn row_to_session(row: &rusqlite::Row) -> rusqlite::Result<PlayerSession> {
+    Ok(PlayerSession {
+        id: row.get(0)?,
+        instance_id: row.get(1)?,
+        player_name: row.get(2)?,
+        player_uuid: row.get(3)?,
+        ip: row.get(4)?,
+        joined_at: row.get(5)?,
+        left_at: row.get(6)?,
+        disconnect_reason: row.get(7)?,
+    })
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_player_sessions(
+    instance_id: String,
+    player: Option<String>,
+    limit: Option<u32>,
+) -> Result<Vec<PlayerSession>> {
+    PlayerSessionManager::list(&instance_id, player.as_deref(), limit.unwrap_or(200))
+}
+
+/// Кто был онлайн в промежутке (время в RFC 3339)
+#[tauri::command]
+pub async fn get_players_online_between(
+    instance_id: String,
+    from: String,
+    to: String,
+) -> Result<Vec<PlayerSession>> {
+    PlayerSessionManager::online_between(&instance_id, &from, &to)
+}
+
+#[tauri::command]
+pub async fn get_player_summaries(instance_id: String) -> Result<Vec<PlayerSummary>> {
+    PlayerSessionManager::summaries(&instance_id)
+}
+
+#[tauri::command]
+pub async fn get_player_concurrency(
+    instance_id: String,
+    from: String,
+    to: String,
+    bucket_minutes: u32,
+) -> Result<Vec<ConcurrencyPoint>> {
+    PlayerSessionManager::concurrency(&instance_id, &from, &to, bucket_minutes)
+}
+
+#[tauri::command]
+pub async fn backfill_player_sessions(instance_id: String) -> Result<usize> {
+    let server_dir = crate::paths::instance_dir(&instance_id);
+    tokio::task::spawn_blocking(move || {
+        PlayerSessionManager::backfill_from_logs(&instance_id, &server_dir)
+    })
+    .await
+    .map_err(|e| LauncherError::Join(e.to_string()))?
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_player_event() {
+        assert_eq!(
+            parse_player_event("[12:00:01] [User Authenticator #1/INFO]: UUID of player Steve is 8667ba71-b85a-4004-af54-457a9734eed7"),
+            Some(PlayerEvent::Uuid {
+                name: "Steve".to_string(),
+                uuid: "8667ba71-b85a-4004-af54-457a9734eed7".to_string()
+            })
+        );
+        assert_eq!(
+            parse_player_event("[12:00:01] [Server thread/INFO]: Steve[/192.168.1.5:53012] logged in with entity id 312 at (0.5, 64.0, 0.5)"),
+            Some(PlayerEvent::Login {
+                name: "Steve".to_string(),
+                ip: Some("192.168.1.5".to_string())
+            })
+        );
+        assert_eq!(
+            parse_player_event("[12:00:01 INFO]: .BedrockGuy[/[0:0:0:0:0:0:0:1]:53012] logged in with entity id 5 at (1, 2, 3)"),
+            Some(PlayerEvent::Login {
+                name: ".BedrockGuy".to_string(),
+                ip: Some("0:0:0:0:0:0:0:1".to_string())
+            })
+        );
+        assert_eq!(
+            parse_player_event("[12:30:00] [Server thread/INFO] [minecraft/ServerGamePacketListenerImpl]: Steve lost connection: Disconnected"),
+            Some(PlayerEvent::LostConnection {
+                name: "Steve".to_string(),
+                reason: "Disconnected".to_string()
+            })
+        );
+        assert_eq!(
+            parse_player_event("[12:30:00] [Server thread/INFO]: Alex joined the game"),
+            Some(PlayerEvent::Joined {
+                name: "Alex".to_string()
+            })
+        );
+        // Чат не должен выглядеть как вход
+        assert_eq!(
+            parse_player_event("[12:30:00] [Server thread/INFO]: <Alex> Bob joined the game"),
+            None
+        );
+        assert_eq!(parse_login_address("local"), None);
+    }
+
+    #[test]
+    fn test_replay_log() {
+        let lines: Vec<String> = [
+            "[23:50:00] [User Authenticator #1/INFO]: UUID of player Steve is 8667ba71-b85a-4004-af54-457a9734eed7",
+            "[23:50:00] [Server thread/INFO]: Steve[/10.0.0.2:50000] logged in with entity id 1 at (0, 64, 0)",
+            "[23:50:00] [Server thread/INFO]: Steve joined the game",
+            "[23:55:00] [Server thread/INFO]: Alex joined the game",
+            "[00:10:00] [Server thread/INFO]: Steve lost connection: Timed out",
+            "[00:10:00] [Server thread/INFO]: Steve left the game",
+            "[00:20:00] [Server thread/INFO]: Stopping server",
+        ]
+        .iter()
+        .map(|s| s.to_string())
+        .collect();
+
+        let date = NaiveDate::from_ymd_opt(2026, 10, 15).unwrap();
+        let mut sessions = replay_log(&lines, date);
+        sessions.sort_by_key(|s| s.session.joined_at);
+        assert_eq!(sessions.len(), 2);
+
+        let steve = &sessions[0];
+        assert_eq!(steve.session.name, "Steve");
+        assert_eq!(
+            steve.session.uuid.as_deref(),
+            Some("8667ba71-b85a-4004-af54-457a9734eed7")
+        );
+        assert_eq!(steve.session.ip.as_deref(), Some("10.0.0.2"));
+        assert_eq!(steve.session.reason.as_deref(), Some("Timed out"));
+        assert_eq!((steve.left_at - steve.session.joined_at).num_minutes(), 20);
+
+        let alex = &sessions[1];
+        assert_eq!(alex.session.reason.as_deref(), Some("Server stopped"));
+        assert_eq!((alex.left_at - alex.session.joined_at).num_minutes(), 25);
+        assert_eq!(midnight_rollovers(&lines), 1);
+    }
+
+    #[test]
+    fn test_summaries_and_concurrency() {
+        let session = |name: &str, joined: &str, left: Option<&str>| PlayerSession {
+            id: 0,
+            instance_id: "srv".to_string(),
+            player_name: name.to_string(),
+            player_uuid: None,
+            ip: None,
+            joined_at: joined.to_string(),
+            left_at: left.map(str::to_string),
+            disconnect_reason: None,
+        };
+        let sessions = vec![
+            session(
+                "Steve",
+                "2026-10-16T10:00:00Z",
+                Some("2026-10-16T11:00:00Z"),
+            ),
+            session("Alex", "2026-10-16T10:30:00Z", Some("2026-10-16T10:45:00Z")),
+            session("steve", "2026-10-16T12:00:00Z", None),
+        ];
+        let now = parse_time("2026-10-16T12:30:00Z").unwrap();
+
+        let summaries = summarize(&sessions, now);
+        assert_eq!(summaries.len(), 2);
+        assert_eq!(summaries[0].player_name, "steve");
+        assert_eq!(summaries[0].sessions, 2);
+        assert_eq!(summaries[0].total_playtime_secs, 90 * 60);
+        assert!(summaries[0].online);
+        assert_eq!(summaries[1].last_seen, "2026-10-16T10:45:00Z");
+
+        let points = peak_concurrency(
+            &sessions,
+            parse_time("2026-10-16T10:00:00Z").unwrap(),
+            parse_time("2026-10-16T13:00:00Z").unwrap(),
+            chrono::Duration::hours(1),
+            now,
+        );
+        let players: Vec<u32> = points.iter().map(|p| p.players).collect();
+        assert_eq!(players, vec![2, 0, 1]);
+    }
+
+    #[test]
+    fn test_relog_same_second_reuses_row() {
+        let conn = rusqlite::Connection::open_in_memory().unwrap();
+        PlayerSessionManager::ensure_table(&conn).unwrap();
+
+        let session = OpenSession {
+            id: None,
+            name: "Steve".to_string(),
+            uuid: None,
+            ip: None,
+            joined_at: Utc.with_ymd_and_hms(2024, 5, 1, 12, 0, 0).unwrap(),
+            reason: None,
+        };
+        let first = PlayerSessionManager::insert_open(&conn, "srv", &session).unwrap();
+        PlayerSessionManager::store_closed(
+            &conn,
+            "srv",
+            &ClosedSession {
+                session: OpenSession {
+                    id: Some(first),
+                    ..session.clone()
+                },
+                left_at: session.joined_at,
+            },
+        )
+        .unwrap();
+
+        let second = PlayerSessionManager::insert_open(&conn, "srv", &session).unwrap();
+        assert_eq!(first, second);
+        let left_at: Option<String> = conn
+            .query_row(
+                "SELECT left_at FROM player_sessions WHERE id = ?1",
+                [second],
+                |row| row.get(0),
+            )
+            .unwrap();
+        assert_eq!(left_at, None);
+    }
+}
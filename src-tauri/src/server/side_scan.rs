@@ -0,0 +1,931 @@
+//! Bytecode side detection for mod jars
+//!
+//! Modrinth `client_side`/`server_side` заполняют авторы, и ошибаются они часто —
+//! отсюда краши сервера от клиентских модов. Сканер смотрит в сам JAR:
+//! - ссылки классов на `net/minecraft/client/**` (и blaze3d, Fabric API client)
+//! - аннотации `@Environment(EnvType.CLIENT)` / `@OnlyIn(Dist.CLIENT)` на классах
+//! - `environment` в fabric.mod.json/quilt.mod.json, `clientSideOnly` в mods.toml
+//! - entrypoint'ы (только client) и mixin-конфиги (только client, или общие
+//!   mixin'ы с целью в клиентском классе — гарантированный краш на сервере)
+//!
+//! Каждый признак — сигнал с уверенностью; итог — сторона с самым сильным
+//! сигналом, ослабленная самым сильным противоположным.
+
+use std::collections::HashMap;
+use std::io::Read;
+use std::path::Path;
+
+use super::ServerResult;
+
+/// Префиксы классов, которых нет на выделенном сервере.
+/// Fabric в проде использует intermediary-имена (`net/minecraft/class_310`),
+/// поэтому для него остаются только Fabric API client-пакеты и аннотации.
+const CLIENT_PACKAGES: &[&str] = &[
+    "net/minecraft/client/",
+    "com/mojang/blaze3d/",
+    "net/fabricmc/fabric/api/client/",
+];
+
+/// Аннотации "только клиент" с enum-значением `CLIENT`
+const ENVIRONMENT_ANNOTATIONS: &[&str] = &[
+    "Lnet/fabricmc/api/Environment;",
+    "Lnet/minecraftforge/api/distmarker/OnlyIn;",
+    "Lnet/neoforged/api/distmarker/OnlyIn;",
+];
+const QUILT_CLIENT_ONLY: &str = "Lorg/quiltmc/loader/api/minecraft/ClientOnly;";
+
+const MOD_ANNOTATIONS: &[&str] = &[
+    "Lnet/minecraftforge/fml/common/Mod;",
+    "Lnet/neoforged/fml/common/Mod;",
+];
+const MIXIN_ANNOTATION: &str = "Lorg/spongepowered/asm/mixin/Mixin;";
+
+/// Уверенность сигнала "общая точка входа ссылается на клиент" (без аннотации)
+const CLIENT_REFERENCE_CONFIDENCE: f32 = 0.4;
+
+/// Сколько примеров класть в evidence для одного признака
+const MAX_EXAMPLES: usize = 3;
+
+/// Классы крупнее этого не читаем: реальные .class на порядки меньше
+const MAX_CLASS_SIZE: u64 = 8 * 1024 * 1024;
+
+/// Предел вложенности аннотаций (защита от переполнения стека)
+const MAX_DEPTH: usize = 64;
+
+/// Вердикт по стороне
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum SideVerdict {
+    /// Не работает на выделенном сервере
+    ClientOnly,
+    /// Не нужен клиенту
+    ServerOnly,
+    /// Работает на обеих сторонах
+    Both,
+    #[default]
+    Unknown,
+}
+
+/// Результат сканирования JAR
+#[derive(Debug, Clone, Default)]
+pub struct SideScan {
+    /// Всего классов
+    pub classes: usize,
+    /// Классы со ссылками на клиент или с клиентской аннотацией
+    pub client_classes: usize,
+    pub verdict: SideVerdict,
+    /// 0.0..=1.0
+    pub confidence: f32,
+    /// Человекочитаемые причины вердикта
+    pub evidence: Vec<String>,
+}
+
+/// Один признак
+#[derive(Debug, Clone)]
+struct Signal {
+    verdict: SideVerdict,
+    confidence: f32,
+    reason: String,
+}
+
+impl Signal {
+    fn new(verdict: SideVerdict, confidence: f32, reason: impl Into<String>) -> Self {
+        Self {
+            verdict,
+            confidence,
+            reason: reason.into(),
+        }
+    }
+}
+
+/// Что нужно знать о классе
+#[derive(Debug, Default)]
+struct ClassInfo {
+    references_client: bool,
+    /// Аннотация уровня класса "только клиент"
+    client_only: bool,
+    /// Forge/NeoForge `@Mod`
+    mod_entry: bool,
+    /// Цели `@Mixin` (internal names)
+    mixin_targets: Vec<String>,
+}
+
+impl ClassInfo {
+    fn is_client(&self) -> bool {
+        self.references_client || self.client_only
+    }
+}
+
+/// Метаданные мода, важные для стороны
+#[derive(Debug, Default)]
+struct ModMetadata {
+    /// "fabric.mod.json: environment = client" и т.п.
+    environment: Option<(SideVerdict, String)>,
+    /// Имя точки входа ("main", "client", "server") -> классы
+    entrypoints: HashMap<String, Vec<String>>,
+    /// Mixin-конфиги; true — конфиг помечен как клиентский в метаданных
+    mixin_configs: Vec<(String, bool)>,
+}
+
+/// Просканировать JAR мода (блокирующе — вызывать из spawn_blocking)
+pub fn scan_jar(path: &Path) -> ServerResult<SideScan> {
+    let file = std::fs::File::open(path)?;
+    let mut archive = zip::ZipArchive::new(file)
+        .map_err(|e| std::io::Error::new(std::io::ErrorKind::InvalidData, e))?;
+
+    let mut classes: HashMap<String, ClassInfo> = HashMap::new();
+    let mut buf = Vec::new();
+    for i in 0..archive.len() {
+        let Ok(mut entry) = archive.by_index(i) else {
+            continue;
+        };
+        let name = entry.name().to_string();
+        // Вложенные JiJ-библиотеки (META-INF/jars) сюда не попадают — это другие JAR
+        if !name.ends_with(".class") || name.starts_with("META-INF/") {
+            continue;
+        }
+        buf.clear();
+        if (&mut entry)
+            .take(MAX_CLASS_SIZE + 1)
+            .read_to_end(&mut buf)
+            .is_err()
+            || buf.len() as u64 > MAX_CLASS_SIZE
+        {
+            continue;
+        }
+        if let Some(info) = parse_class(&buf) {
+            classes.insert(name.trim_end_matches(".class").to_string(), info);
+        }
+    }
+
+    let metadata = read_metadata(&mut archive);
+    let mut signals = metadata_signals(&metadata, &classes);
+    signals.extend(mixin_signals(&mut archive, &metadata, &classes));
+    signals.extend(class_signals(&classes));
+
+    let mut scan = SideScan {
+        classes: classes.len(),
+        client_classes: classes.values().filter(|c| c.is_client()).count(),
+        ..Default::default()
+    };
+    let (verdict, confidence) = decide(&signals);
+    scan.verdict = verdict;
+    scan.confidence = confidence;
+    scan.evidence = signals.into_iter().map(|s| s.reason).collect();
+    Ok(scan)
+}
+
+/// Итог: сильнейший сигнал минус половина сильнейшего противоречащего
+fn decide(signals: &[Signal]) -> (SideVerdict, f32) {
+    let strongest = |verdict: SideVerdict| {
+        signals
+            .iter()
+            .filter(|s| s.verdict == verdict)
+            .map(|s| s.confidence)
+            .fold(0.0f32, f32::max)
+    };
+    let mut sides = [
+        (SideVerdict::ClientOnly, strongest(SideVerdict::ClientOnly)),
+        (SideVerdict::ServerOnly, strongest(SideVerdict::ServerOnly)),
+        (SideVerdict::Both, strongest(SideVerdict::Both)),
+    ];
+    sides.sort_by(|a, b| b.1.total_cmp(&a.1));
+
+    let (verdict, best) = sides[0];
+    if best <= 0.0 {
+        return (SideVerdict::Unknown, 0.0);
+    }
+    let confidence = (best - sides[1].1 / 2.0).clamp(0.1, 1.0);
+    (verdict, confidence)
+}
+
+fn is_client_class(internal_name: &str) -> bool {
+    CLIENT_PACKAGES.iter().any(|p| internal_name.starts_with(p))
+}
+
+/// "a.b.C::field" / "La/b/C;" -> "a/b/C"
+fn internal_name(name: &str) -> String {
+    let name = name.split("::").next().unwrap_or(name);
+    let name = name
+        .strip_prefix('L')
+        .and_then(|n| n.strip_suffix(';'))
+        .unwrap_or(name);
+    name.replace('.', "/")
+}
+
+fn read_text(archive: &mut zip::ZipArchive<std::fs::File>, name: &str) -> Option<String> {
+    let mut entry = archive.by_name(name).ok()?;
+    let mut content = String::new();
+    entry.read_to_string(&mut content).ok()?;
+    Some(content)
+}
+
+// ==================== Metadata ====================
+
+fn read_metadata(archive: &mut zip::ZipArchive<std::fs::File>) -> ModMetadata {
+    let mut meta = ModMetadata::default();
+
+    if let Some(json) = read_text(archive, "fabric.mod.json")
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    {
+        parse_fabric_metadata(&json, &mut meta);
+    }
+
+    if let Some(json) = read_text(archive, "quilt.mod.json")
+        .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+    {
+        if meta.environment.is_none() {
+            meta.environment = environment_verdict(
+                json.pointer("/minecraft/environment")
+                    .and_then(|v| v.as_str()),
+                "quilt.mod.json",
+            );
+        }
+        let mixins = match json.get("mixin") {
+            Some(serde_json::Value::String(s)) => vec![s.clone()],
+            Some(serde_json::Value::Array(items)) => items
+                .iter()
+                .filter_map(|v| v.as_str().map(String::from))
+                .collect(),
+            _ => Vec::new(),
+        };
+        meta.mixin_configs
+            .extend(mixins.into_iter().map(|config| (config, false)));
+    }
+
+    for toml_name in ["META-INF/mods.toml", "META-INF/neoforge.mods.toml"] {
+        let Some(table) =
+            read_text(archive, toml_name).and_then(|s| toml::from_str::<toml::Table>(&s).ok())
+        else {
+            continue;
+        };
+        parse_mods_toml(&table, toml_name, &mut meta);
+    }
+
+    // Forge (до NeoForge-шного [[mixins]]) объявляет конфиги в манифесте
+    if let Some(manifest) = read_text(archive, "META-INF/MANIFEST.MF") {
+        for config in manifest_mixin_configs(&manifest) {
+            if !meta.mixin_configs.iter().any(|(c, _)| *c == config) {
+                meta.mixin_configs.push((config, false));
+            }
+        }
+    }
+
+    meta
+}
+
+fn environment_verdict(value: Option<&str>, file: &str) -> Option<(SideVerdict, String)> {
+    let verdict = match value? {
+        "client" => SideVerdict::ClientOnly,
+        "server" => SideVerdict::ServerOnly,
+        _ => return None,
+    };
+    Some((verdict, format!("{}: environment = {}", file, value?)))
+}
+
+fn parse_fabric_metadata(json: &serde_json::Value, meta: &mut ModMetadata) {
+    meta.environment = environment_verdict(
+        json.get("environment").and_then(|v| v.as_str()),
+        "fabric.mod.json",
+    );
+
+    if let Some(entrypoints) = json.get("entrypoints").and_then(|v| v.as_object()) {
+        for (kind, values) in entrypoints {
+            let classes: Vec<String> = values
+                .as_array()
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str().or_else(|| v.get("value")?.as_str()))
+                .map(internal_name)
+                .collect();
+            if !classes.is_empty() {
+                meta.entrypoints.insert(kind.clone(), classes);
+            }
+        }
+    }
+
+    for mixin in json
+        .get("mixins")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        if let Some(config) = mixin.as_str() {
+            meta.mixin_configs.push((config.to_string(), false));
+        } else if let Some(config) = mixin.get("config").and_then(|v| v.as_str()) {
+            let client = mixin.get("environment").and_then(|v| v.as_str()) == Some("client");
+            meta.mixin_configs.push((config.to_string(), client));
+        }
+    }
+}
+
+fn parse_mods_toml(table: &toml::Table, file: &str, meta: &mut ModMetadata) {
+    if table.get("clientSideOnly").and_then(|v| v.as_bool()) == Some(true)
+        && meta.environment.is_none()
+    {
+        meta.environment = Some((
+            SideVerdict::ClientOnly,
+            format!(
+                "{}: clientSideOnly = true",
+                file.trim_start_matches("META-INF/")
+            ),
+        ));
+    }
+
+    // NeoForge: [[mixins]] config = "modid.mixins.json"
+    for mixin in table
+        .get("mixins")
+        .and_then(|v| v.as_array())
+        .into_iter()
+        .flatten()
+    {
+        if let Some(config) = mixin.get("config").and_then(|v| v.as_str()) {
+            meta.mixin_configs.push((config.to_string(), false));
+        }
+    }
+}
+
+/// `MixinConfigs: a.mixins.json,b.mixins.json` (с переносами строк манифеста)
+fn manifest_mixin_configs(manifest: &str) -> Vec<String> {
+    let mut value: Option<String> = None;
+    for line in manifest.lines() {
+        let line = line.trim_end_matches('\r');
+        match &mut value {
+            // Продолжение значения начинается с одного пробела
+            Some(v) if line.starts_with(' ') => v.push_str(&line[1..]),
+            Some(_) => break,
+            None => {
+                if let Some(rest) = line.strip_prefix("MixinConfigs:") {
+                    value = Some(rest.trim_start().to_string());
+                }
+            }
+        }
+    }
+    value
+        .unwrap_or_default()
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
+
+fn metadata_signals(meta: &ModMetadata, classes: &HashMap<String, ClassInfo>) -> Vec<Signal> {
+    let mut signals = Vec::new();
+
+    if let Some((verdict, reason)) = &meta.environment {
+        let confidence = if *verdict == SideVerdict::ClientOnly {
+            0.95
+        } else {
+            0.9
+        };
+        signals.push(Signal::new(*verdict, confidence, reason.clone()));
+    }
+
+    // Fabric: "main" работает везде, "server" — только на сервере
+    let common: Vec<&String> = ["main", "server"]
+        .iter()
+        .filter_map(|kind| meta.entrypoints.get(*kind))
+        .flatten()
+        .collect();
+    if meta.entrypoints.contains_key("client") && common.is_empty() {
+        signals.push(Signal::new(
+            SideVerdict::ClientOnly,
+            0.85,
+            "only client entrypoints",
+        ));
+    }
+    for class in common {
+        match classes.get(class) {
+            Some(info) if info.client_only => signals.push(Signal::new(
+                SideVerdict::ClientOnly,
+                0.9,
+                format!("entrypoint {} is client-only", class),
+            )),
+            info => signals.extend(common_entry_signals(
+                info.is_some_and(|i| i.references_client),
+                format!("entrypoint {}", class),
+            )),
+        }
+    }
+
+    // Forge/NeoForge: точка входа — класс с @Mod
+    for (name, info) in classes.iter().filter(|(_, c)| c.mod_entry) {
+        if info.client_only {
+            signals.push(Signal::new(
+                SideVerdict::ClientOnly,
+                0.9,
+                format!("@Mod class {} is client-only", name),
+            ));
+        } else {
+            signals.extend(common_entry_signals(
+                info.references_client,
+                format!("@Mod class {}", name),
+            ));
+        }
+    }
+
+    signals
+}
+
+/// Общая точка входа грузится на обеих сторонах. Ссылки из неё на клиентские
+/// классы обычно стоят за проверкой стороны (`DistExecutor`, `FMLEnvironment.dist`),
+/// поэтому дают лишь слабый сигнал — ниже порога автоотключения.
+fn common_entry_signals(references_client: bool, entry: String) -> Vec<Signal> {
+    let mut signals = vec![Signal::new(
+        SideVerdict::Both,
+        0.5,
+        format!("common {}", entry),
+    )];
+    if references_client {
+        signals.push(Signal::new(
+            SideVerdict::ClientOnly,
+            CLIENT_REFERENCE_CONFIDENCE,
+            format!("{} references client classes", entry),
+        ));
+    }
+    signals
+}
+
+// ==================== Mixins ====================
+
+fn mixin_signals(
+    archive: &mut zip::ZipArchive<std::fs::File>,
+    meta: &ModMetadata,
+    classes: &HashMap<String, ClassInfo>,
+) -> Vec<Signal> {
+    let mut signals = Vec::new();
+    let mut client_configs = 0;
+    let mut client_targets = Vec::new();
+
+    for (config_name, client_env) in &meta.mixin_configs {
+        let Some(config) = read_text(archive, config_name)
+            .and_then(|s| serde_json::from_str::<serde_json::Value>(&s).ok())
+        else {
+            continue;
+        };
+        let package = config.get("package").and_then(|v| v.as_str()).unwrap_or("");
+        let list = |key: &str| -> Vec<String> {
+            config
+                .get(key)
+                .and_then(|v| v.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|v| v.as_str())
+                .map(|m| internal_name(&format!("{}.{}", package, m)))
+                .collect()
+        };
+        let common = if *client_env {
+            Vec::new()
+        } else {
+            [list("mixins"), list("server")].concat()
+        };
+
+        if common.is_empty() && (*client_env || !list("client").is_empty()) {
+            client_configs += 1;
+            continue;
+        }
+
+        // Общий mixin в клиентский класс: на сервере цель не найдётся
+        for mixin in &common {
+            let Some(info) = classes.get(mixin) else {
+                continue;
+            };
+            if let Some(target) = info.mixin_targets.iter().find(|t| is_client_class(t)) {
+                client_targets.push(format!("{} -> {}", mixin, target));
+            }
+        }
+    }
+
+    if !client_targets.is_empty() {
+        signals.push(Signal::new(
+            SideVerdict::ClientOnly,
+            0.8,
+            format!(
+                "{} common mixins target client classes ({})",
+                client_targets.len(),
+                examples(&client_targets)
+            ),
+        ));
+    }
+    if client_configs > 0 && client_configs == meta.mixin_configs.len() {
+        signals.push(Signal::new(
+            SideVerdict::ClientOnly,
+            0.6,
+            "all mixin configs are client-only",
+        ));
+    }
+    signals
+}
+
+fn class_signals(classes: &HashMap<String, ClassInfo>) -> Vec<Signal> {
+    if classes.is_empty() {
+        return Vec::new();
+    }
+    let client = classes.values().filter(|c| c.is_client()).count();
+    let ratio = client as f32 / classes.len() as f32;
+    let reason = format!("{}/{} classes use client code", client, classes.len());
+
+    if ratio >= 0.9 {
+        vec![Signal::new(SideVerdict::ClientOnly, 0.6, reason)]
+    } else if ratio <= 0.5 {
+        vec![Signal::new(SideVerdict::Both, 0.3, reason)]
+    } else {
+        Vec::new()
+    }
+}
+
+fn examples(items: &[String]) -> String {
+    let mut shown = items
+        .iter()
+        .take(MAX_EXAMPLES)
+        .cloned()
+        .collect::<Vec<_>>()
+        .join(", ");
+    if items.len() > MAX_EXAMPLES {
+        shown.push_str(", ...");
+    }
+    shown
+}
+
+// ==================== Class files ====================
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn bytes(&mut self, len: usize) -> Option<&'a [u8]> {
+        let slice = self.data.get(self.pos..self.pos.checked_add(len)?)?;
+        self.pos += len;
+        Some(slice)
+    }
+
+    fn u8(&mut self) -> Option<u8> {
+        Some(self.bytes(1)?[0])
+    }
+
+    fn u16(&mut self) -> Option<u16> {
+        Some(u16::from_be_bytes(self.bytes(2)?.try_into().ok()?))
+    }
+
+    fn u32(&mut self) -> Option<u32> {
+        Some(u32::from_be_bytes(self.bytes(4)?.try_into().ok()?))
+    }
+}
+
+enum Constant {
+    Utf8(String),
+    Class(u16),
+    Other,
+}
+
+/// Аннотация уровня класса: дескриптор и плоский список (имя, значение).
+/// Значения enum — имя константы, class — дескриптор, массивы разворачиваются.
+struct Annotation {
+    descriptor: String,
+    values: Vec<(String, String)>,
+}
+
+impl Annotation {
+    fn values<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        self.values
+            .iter()
+            .filter(move |(k, _)| k == key)
+            .map(|(_, v)| v.as_str())
+    }
+}
+
+/// Разобрать .class: constant pool + аннотации класса. None — не class-файл.
+fn parse_class(data: &[u8]) -> Option<ClassInfo> {
+    let mut r = Reader { data, pos: 0 };
+    if r.u32()? != 0xCAFE_BABE {
+        return None;
+    }
+    r.bytes(4)?; // minor, major
+
+    let count = r.u16()? as usize;
+    let mut pool: Vec<Constant> = Vec::with_capacity(count);
+    pool.push(Constant::Other); // индексы с 1
+    while pool.len() < count {
+        let tag = r.u8()?;
+        let constant = match tag {
+            1 => {
+                let len = r.u16()? as usize;
+                // Modified UTF-8; для имён классов достаточно lossy
+                Constant::Utf8(String::from_utf8_lossy(r.bytes(len)?).into_owned())
+            }
+            7 => Constant::Class(r.u16()?),
+            8 | 16 | 19 | 20 => {
+                r.bytes(2)?;
+                Constant::Other
+            }
+            15 => {
+                r.bytes(3)?;
+                Constant::Other
+            }
+            3 | 4 | 9 | 10 | 11 | 12 | 17 | 18 => {
+                r.bytes(4)?;
+                Constant::Other
+            }
+            5 | 6 => {
+                // Long/Double занимают два слота
+                r.bytes(8)?;
+                pool.push(Constant::Other);
+                Constant::Other
+            }
+            _ => return None,
+        };
+        pool.push(constant);
+    }
+
+    let utf8 = |index: u16| match pool.get(index as usize) {
+        Some(Constant::Utf8(s)) => Some(s.as_str()),
+        _ => None,
+    };
+
+    let mut info = ClassInfo {
+        references_client: pool.iter().any(|c| match c {
+            Constant::Utf8(s) => CLIENT_PACKAGES.iter().any(|p| s.contains(p)),
+            _ => false,
+        }),
+        ..Default::default()
+    };
+
+    r.bytes(6)?; // access flags, this, super
+    let interfaces = r.u16()? as usize;
+    r.bytes(interfaces * 2)?;
+    // Поля и методы: access, name, descriptor, атрибуты
+    for _ in 0..2 {
+        let members = r.u16()?;
+        for _ in 0..members {
+            r.bytes(6)?;
+            skip_attributes(&mut r)?;
+        }
+    }
+
+    let mut annotations = Vec::new();
+    let attributes = r.u16()?;
+    for _ in 0..attributes {
+        let name = utf8(r.u16()?);
+        let len = r.u32()? as usize;
+        let body = r.bytes(len)?;
+        if matches!(
+            name,
+            Some("RuntimeVisibleAnnotations") | Some("RuntimeInvisibleAnnotations")
+        ) {
+            let mut ar = Reader { data: body, pos: 0 };
+            let count = ar.u16()?;
+            for _ in 0..count {
+                annotations.push(read_annotation(&mut ar, &utf8, 0)?);
+            }
+        }
+    }
+
+    for annotation in annotations {
+        let descriptor = annotation.descriptor.as_str();
+        if ENVIRONMENT_ANNOTATIONS.contains(&descriptor) {
+            info.client_only |= annotation.values("value").any(|v| v == "CLIENT");
+        } else if descriptor == QUILT_CLIENT_ONLY {
+            info.client_only = true;
+        } else if MOD_ANNOTATIONS.contains(&descriptor) {
+            info.mod_entry = true;
+            // NeoForge: @Mod(value = "id", dist = Dist.CLIENT)
+            let dists: Vec<&str> = annotation.values("dist").collect();
+            if !dists.is_empty() && dists.iter().all(|d| *d == "CLIENT") {
+                info.client_only = true;
+            }
+        } else if descriptor == MIXIN_ANNOTATION {
+            info.mixin_targets = annotation
+                .values("value")
+                .chain(annotation.values("targets"))
+                .map(internal_name)
+                .collect();
+        }
+    }
+
+    Some(info)
+}
+
+fn skip_attributes(r: &mut Reader) -> Option<()> {
+    let count = r.u16()?;
+    for _ in 0..count {
+        r.bytes(2)?;
+        let len = r.u32()? as usize;
+        r.bytes(len)?;
+    }
+    Some(())
+}
+
+fn read_annotation<'p>(
+    r: &mut Reader,
+    utf8: &impl Fn(u16) -> Option<&'p str>,
+    depth: usize,
+) -> Option<Annotation> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    let descriptor = utf8(r.u16()?).unwrap_or_default().to_string();
+    let pairs = r.u16()?;
+    let mut values = Vec::new();
+    for _ in 0..pairs {
+        let name = utf8(r.u16()?).unwrap_or_default().to_string();
+        read_element_value(r, utf8, &name, &mut values, depth + 1)?;
+    }
+    Some(Annotation { descriptor, values })
+}
+
+fn read_element_value<'p>(
+    r: &mut Reader,
+    utf8: &impl Fn(u16) -> Option<&'p str>,
+    name: &str,
+    out: &mut Vec<(String, String)>,
+    depth: usize,
+) -> Option<()> {
+    if depth > MAX_DEPTH {
+        return None;
+    }
+    match r.u8()? {
+        // Строки и class-литералы нужны (цели mixin'ов), прочие константы — нет
+        b's' | b'c' => {
+            if let Some(value) = utf8(r.u16()?) {
+                out.push((name.to_string(), value.to_string()));
+            }
+        }
+        b'B' | b'C' | b'D' | b'F' | b'I' | b'J' | b'S' | b'Z' => {
+            r.u16()?;
+        }
+        b'e' => {
+            r.u16()?; // тип enum
+            if let Some(constant) = utf8(r.u16()?) {
+                out.push((name.to_string(), constant.to_string()));
+            }
+        }
+        b'@' => {
+            read_annotation(r, utf8, depth + 1)?;
+        }
+        b'[' => {
+            let count = r.u16()?;
+            for _ in 0..count {
+                read_element_value(r, utf8, name, out, depth + 1)?;
+            }
+        }
+        _ => return None,
+    }
+    Some(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Минимальный class-файл: constant pool из Utf8/Class и аннотации класса
+    fn class_file(name: &str, extra_utf8: &[&str], annotation: Option<(&str, &str)>) -> Vec<u8> {
+        let mut strings = vec![name.to_string(), "RuntimeInvisibleAnnotations".to_string()];
+        strings.extend(extra_utf8.iter().map(|s| s.to_string()));
+        if let Some((descriptor, constant)) = annotation {
+            strings.extend([
+                descriptor.to_string(),
+                "value".to_string(),
+                "Lnet/fabricmc/api/EnvType;".to_string(),
+                constant.to_string(),
+            ]);
+        }
+        let index = |s: &str| strings.iter().position(|x| x == s).unwrap() as u16 + 1;
+
+        let mut out = 0xCAFE_BABEu32.to_be_bytes().to_vec();
+        out.extend([0, 0, 0, 61]);
+        // Utf8 x N + Class(name)
+        out.extend((strings.len() as u16 + 2).to_be_bytes());
+        for s in &strings {
+            out.push(1);
+            out.extend((s.len() as u16).to_be_bytes());
+            out.extend(s.as_bytes());
+        }
+        let class_index = strings.len() as u16 + 1;
+        out.push(7);
+        out.extend(1u16.to_be_bytes());
+
+        out.extend(0x21u16.to_be_bytes());
+        out.extend(class_index.to_be_bytes());
+        out.extend(0u16.to_be_bytes()); // super
+        out.extend([0, 0, 0, 0, 0, 0]); // interfaces, fields, methods
+
+        match annotation {
+            Some((descriptor, constant)) => {
+                let mut body = 1u16.to_be_bytes().to_vec();
+                body.extend(index(descriptor).to_be_bytes());
+                body.extend(1u16.to_be_bytes());
+                body.extend(index("value").to_be_bytes());
+                body.push(b'e');
+                body.extend(index("Lnet/fabricmc/api/EnvType;").to_be_bytes());
+                body.extend(index(constant).to_be_bytes());
+
+                out.extend(1u16.to_be_bytes());
+                out.extend(index("RuntimeInvisibleAnnotations").to_be_bytes());
+                out.extend((body.len() as u32).to_be_bytes());
+                out.extend(body);
+            }
+            None => out.extend(0u16.to_be_bytes()),
+        }
+        out
+    }
+
+    #[test]
+    fn test_parse_class() {
+        let plain = parse_class(&class_file("a/b/Common", &["java/lang/Object"], None)).unwrap();
+        assert!(!plain.is_client());
+
+        let refs = parse_class(&class_file(
+            "a/b/Hud",
+            &["(Lnet/minecraft/client/gui/GuiGraphics;)V"],
+            None,
+        ))
+        .unwrap();
+        assert!(refs.references_client && !refs.client_only);
+
+        let annotated = parse_class(&class_file(
+            "a/b/Renderer",
+            &[],
+            Some(("Lnet/fabricmc/api/Environment;", "CLIENT")),
+        ))
+        .unwrap();
+        assert!(annotated.client_only);
+
+        let server = parse_class(&class_file(
+            "a/b/Server",
+            &[],
+            Some(("Lnet/fabricmc/api/Environment;", "SERVER")),
+        ))
+        .unwrap();
+        assert!(!server.is_client());
+
+        assert!(parse_class(b"not a class").is_none());
+    }
+
+    #[test]
+    fn test_decide_and_metadata() {
+        let signals = vec![
+            Signal::new(SideVerdict::ClientOnly, 0.95, "environment = client"),
+            Signal::new(SideVerdict::Both, 0.3, "ratio"),
+        ];
+        let (verdict, confidence) = decide(&signals);
+        assert_eq!(verdict, SideVerdict::ClientOnly);
+        assert!((confidence - 0.8).abs() < 1e-6);
+        assert_eq!(decide(&[]), (SideVerdict::Unknown, 0.0));
+
+        let json = serde_json::json!({
+            "environment": "*",
+            "entrypoints": { "client": ["a.b.ClientInit"], "modmenu": ["a.b.Menu"] },
+            "mixins": ["a.mixins.json", { "config": "a.client.mixins.json", "environment": "client" }]
+        });
+        let mut meta = ModMetadata::default();
+        parse_fabric_metadata(&json, &mut meta);
+        assert!(meta.environment.is_none());
+        assert_eq!(
+            meta.entrypoints["client"],
+            vec!["a/b/ClientInit".to_string()]
+        );
+        assert_eq!(
+            meta.mixin_configs[1],
+            ("a.client.mixins.json".to_string(), true)
+        );
+
+        let signals = metadata_signals(&meta, &HashMap::new());
+        assert_eq!(signals[0].verdict, SideVerdict::ClientOnly);
+
+        // Общий мод, главный класс которого лишь ссылается на клиент (за проверкой
+        // стороны), не должен набирать уверенность для автоотключения
+        let mut classes = HashMap::new();
+        classes.insert(
+            "a/b/Main".to_string(),
+            ClassInfo {
+                references_client: true,
+                ..Default::default()
+            },
+        );
+        classes.insert(
+            "a/b/ForgeMod".to_string(),
+            ClassInfo {
+                references_client: true,
+                mod_entry: true,
+                ..Default::default()
+            },
+        );
+        let mut meta = ModMetadata::default();
+        meta.entrypoints
+            .insert("main".to_string(), vec!["a/b/Main".to_string()]);
+        let mut signals = metadata_signals(&meta, &classes);
+        signals.push(Signal::new(SideVerdict::Both, 0.3, "ratio"));
+        assert!(signals
+            .iter()
+            .filter(|s| s.verdict == SideVerdict::ClientOnly)
+            .all(|s| s.confidence < crate::server::client_mods::AUTO_DISABLE_MIN_CONFIDENCE));
+        assert_eq!(decide(&signals).0, SideVerdict::Both);
+
+        let manifest = "Manifest-Version: 1.0\r\nMixinConfigs: a.mixins.json,b.mi\r\n xins.json\r\nOther: x\r\n";
+        assert_eq!(
+            manifest_mixin_configs(manifest),
+            vec!["a.mixins.json".to_string(), "b.mixins.json".to_string()]
+        );
+    }
+}
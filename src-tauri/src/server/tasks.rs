@@ -0,0 +1,723 @@
+//! Scheduled tasks for server instances
+//!
+//! Задача = триггер (интервал, cron или условие по TPS) + действие (рестарт с
+//! обратным отсчётом в чате, остановка, команды, бэкап). Задачи хранятся в БД,
+//! фоновый цикл работает всё время жизни лаунчера, в том числе когда он свёрнут в трей.
+
+use chrono::{DateTime, Duration, Local, Utc};
+use rusqlite::{params, OptionalExtension};
+use serde::{Deserialize, Serialize};
+use std::collections::{HashMap, HashSet};
+use std::sync::LazyLock;
+use tauri::{AppHandle, Manager};
+use tokio::sync::Mutex;
+
+use super::console::{get_console, send_server_command};
+use super::metrics::{get_collector, TpsData};
+use super::rcon::RCON_CONNECTIONS;
+use crate::backup::retention::RetentionPolicy;
+use crate::backup::schedule::{BackupScheduler, ScheduleSpec};
+use crate::backup::{BackupManager, BackupScope, BackupTrigger};
+use crate::db::get_db_conn;
+use crate::error::{LauncherError, Result};
+use crate::instances::lifecycle::ChildMap;
+use crate::settings::SettingsManager;
+
+/// Как часто проверять задачи
+const TICK_INTERVAL: std::time::Duration = std::time::Duration::from_secs(15);
+
+/// Сколько ждать остановки сервера перед принудительным завершением
+const STOP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(120);
+
+/// Замер TPS старше этого считается устаревшим и запрашивается заново
+const TPS_SAMPLE_MAX_AGE_MS: i64 = 60_000;
+
+/// Когда выполнять задачу
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskTrigger {
+    /// Каждые N минут
+    Interval { minutes: u32 },
+    /// Cron-выражение (5 полей, локальное время)
+    Cron { expression: String },
+    /// TPS ниже порога непрерывно N минут
+    TpsBelow { threshold: f64, minutes: u32 },
+}
+
+impl TaskTrigger {
+    /// Расписание для триггеров по времени
+    fn schedule(&self) -> Option<ScheduleSpec> {
+        match self {
+            TaskTrigger::Interval { minutes } => Some(ScheduleSpec::Interval { minutes: *minutes }),
+            TaskTrigger::Cron { expression } => Some(ScheduleSpec::Cron {
+                expression: expression.clone(),
+            }),
+            TaskTrigger::TpsBelow { .. } => None,
+        }
+    }
+
+    pub fn validate(&self) -> Result<()> {
+        match self {
+            TaskTrigger::TpsBelow { threshold, minutes } => {
+                if !(0.0..=20.0).contains(threshold) || *minutes == 0 {
+                    return Err(LauncherError::InvalidConfig(
+                        "TPS threshold must be within 0..20 and duration positive".to_string(),
+                    ));
+                }
+                Ok(())
+            }
+            _ => self.schedule().map_or(Ok(()), |s| s.validate()),
+        }
+    }
+
+    pub fn next_run(&self, after: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.schedule()?.next_run(after)
+    }
+}
+
+/// Что делать
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TaskAction {
+    /// Перезапуск с предупреждениями в чате
+    Restart {
+        #[serde(default = "default_warnings")]
+        warnings: Vec<u32>,
+        /// Текст предупреждения, `{time}` заменяется на оставшееся время
+        #[serde(default)]
+        message: Option<String>,
+    },
+    /// Остановка с предупреждениями в чате
+    Stop {
+        #[serde(default = "default_warnings")]
+        warnings: Vec<u32>,
+        #[serde(default)]
+        message: Option<String>,
+    },
+    /// Команды консоли по порядку
+    Commands { commands: Vec<String> },
+    /// Бэкап сервера
+    Backup {
+        #[serde(default)]
+        scope: BackupScope,
+        /// Хранение бэкапов этой задачи; пустая политика — последние `backup_max_count`
+        #[serde(default)]
+        retention: RetentionPolicy,
+    },
+}
+
+/// За сколько секунд предупреждать по умолчанию
+fn default_warnings() -> Vec<u32> {
+    vec![300, 60, 30, 10, 5, 4, 3, 2, 1]
+}
+
+impl TaskAction {
+    /// Действие имеет смысл только на запущенном сервере
+    fn requires_running(&self) -> bool {
+        !matches!(self, TaskAction::Backup { .. })
+    }
+}
+
+/// Задача сервера
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ServerTask {
+    pub id: String,
+    pub instance_id: String,
+    pub name: String,
+    pub enabled: bool,
+    pub trigger: TaskTrigger,
+    pub action: TaskAction,
+    pub last_run_at: Option<String>,
+    pub next_run_at: Option<String>,
+    pub last_error: Option<String>,
+    pub created_at: String,
+}
+
+/// Параметры создания/изменения задачи
+#[derive(Debug, Clone, Deserialize)]
+pub struct ServerTaskInput {
+    pub instance_id: String,
+    pub name: String,
+    #[serde(default = "default_enabled")]
+    pub enabled: bool,
+    pub trigger: TaskTrigger,
+    pub action: TaskAction,
+}
+
+fn default_enabled() -> bool {
+    true
+}
+
+/// Выполняющиеся сейчас задачи (рестарт с отсчётом длится минутами)
+static RUNNING_TASKS: LazyLock<Mutex<HashSet<String>>> =
+    LazyLock::new(|| Mutex::new(HashSet::new()));
+
+/// С какого момента (unix ms) TPS ниже порога, по задачам
+static LOW_TPS_SINCE: LazyLock<Mutex<HashMap<String, i64>>> =
+    LazyLock::new(|| Mutex::new(HashMap::new()));
+
+/// Планировщик задач серверов
+pub struct ServerTaskScheduler;
+
+impl ServerTaskScheduler {
+    /// Запустить фоновый цикл
+    pub fn start(app: AppHandle) {
+        tauri::async_runtime::spawn(async move {
+            let mut interval = tokio::time::interval(TICK_INTERVAL);
+            loop {
+                interval.tick().await;
+                if let Err(e) = Self::tick(&app).await {
+                    log::warn!("Server task scheduler tick failed: {}", e);
+                }
+            }
+        });
+    }
+
+    async fn tick(app: &AppHandle) -> Result<()> {
+        let now = Local::now();
+        for task in Self::list(None)?.into_iter().filter(|t| t.enabled) {
+            let due = match &task.trigger {
+                TaskTrigger::TpsBelow { threshold, minutes } => {
+                    Self::tps_condition_met(&task, *threshold, *minutes).await
+                }
+                _ => match task.next_run_at.as_deref().and_then(parse_time) {
+                    Some(next) => next <= now,
+                    None => {
+                        // next_run_at не посчитан (например, после ошибки) — пересчитать
+                        Self::store_run_state(&task.id, None, now, None)?;
+                        false
+                    }
+                },
+            };
+            if due {
+                Self::spawn_run(app.clone(), task);
+            }
+        }
+        Ok(())
+    }
+
+    /// Проверить условие по TPS. Последний замер берётся из истории `MetricsCollector`,
+    /// если он свежий, иначе запрашивается через RCON и дописывается в историю.
+    async fn tps_condition_met(task: &ServerTask, threshold: f64, minutes: u32) -> bool {
+        let now = Utc::now().timestamp_millis();
+        let running = get_console(&task.instance_id)
+            .await
+            .read()
+            .await
+            .is_running();
+
+        let tps = if running {
+            Self::current_tps(&task.instance_id, now).await
+        } else {
+            None
+        };
+
+        let mut streaks = LOW_TPS_SINCE.lock().await;
+        let since = streaks.get(&task.id).copied();
+        let (since, met) = evaluate_low_tps(since, tps, threshold, minutes, now);
+        match since {
+            Some(since) => streaks.insert(task.id.clone(), since),
+            None => streaks.remove(&task.id),
+        };
+        met
+    }
+
+    async fn current_tps(instance_id: &str, now: i64) -> Option<f64> {
+        let collector = get_collector(instance_id).await;
+        if let Some(sample) = collector.read().await.get_tps_history().last() {
+            if now - sample.timestamp <= TPS_SAMPLE_MAX_AGE_MS {
+                return Some(sample.tps);
+            }
+        }
+
+        let client = RCON_CONNECTIONS.read().await.get(instance_id).cloned()?;
+        let tps = client.get_tps().await.ok()??;
+        collector.write().await.add_tps(TpsData {
+            timestamp: now,
+            tps,
+            tps_1m: None,
+            tps_5m: None,
+            tps_15m: None,
+        });
+        Some(tps)
+    }
+
+    /// Запустить задачу в фоне, если она ещё не выполняется
+    fn spawn_run(app: AppHandle, task: ServerTask) {
+        tauri::async_runtime::spawn(async move {
+            if !RUNNING_TASKS.lock().await.insert(task.id.clone()) {
+                return;
+            }
+            if let Err(e) = Self::run(&app, &task).await {
+                log::warn!("Server task {} ({}) failed: {}", task.name, task.id, e);
+            }
+            RUNNING_TASKS.lock().await.remove(&task.id);
+        });
+    }
+
+    /// Выполнить задачу вне расписания
+    pub async fn run_now(app: &AppHandle, task_id: &str) -> Result<()> {
+        let task = Self::get(task_id)?;
+        if !RUNNING_TASKS.lock().await.insert(task.id.clone()) {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Task {} is already running",
+                task.name
+            )));
+        }
+        let result = Self::run(app, &task).await;
+        RUNNING_TASKS.lock().await.remove(&task.id);
+        result
+    }
+
+    async fn run(app: &AppHandle, task: &ServerTask) -> Result<()> {
+        let running = get_console(&task.instance_id)
+            .await
+            .read()
+            .await
+            .is_running();
+        let result = if task.action.requires_running() && !running {
+            log::info!(
+                "Skipping task {} for {}: server is not running",
+                task.name,
+                task.instance_id
+            );
+            Ok(())
+        } else {
+            log::info!("Running task {} for {}", task.name, task.instance_id);
+            Self::execute(app, task).await
+        };
+
+        let finished = Local::now();
+        let error = result.as_ref().err().map(|e| e.to_string());
+        Self::store_run_state(&task.id, Some(finished), finished, error)?;
+        result
+    }
+
+    async fn execute(app: &AppHandle, task: &ServerTask) -> Result<()> {
+        let instance_id = &task.instance_id;
+        match &task.action {
+            TaskAction::Restart { warnings, message } => {
+                let template = message.as_deref().unwrap_or("Server restarts in {time}");
+                countdown(instance_id, warnings, template).await;
+                stop_and_wait(app, instance_id).await?;
+                crate::instances::execution::start_instance(
+                    instance_id.clone(),
+                    app.clone(),
+                    app.state::<ChildMap>(),
+                )
+                .await
+            }
+            TaskAction::Stop { warnings, message } => {
+                let template = message.as_deref().unwrap_or("Server stops in {time}");
+                countdown(instance_id, warnings, template).await;
+                stop_and_wait(app, instance_id).await
+            }
+            TaskAction::Commands { commands } => {
+                for command in commands {
+                    let result = send_server_command(instance_id.clone(), command.clone())
+                        .await
+                        .map_err(LauncherError::InvalidConfig)?;
+                    if !result.success {
+                        return Err(LauncherError::InvalidConfig(format!(
+                            "Command '{}' failed: {}",
+                            command,
+                            result.error.unwrap_or_default()
+                        )));
+                    }
+                }
+                Ok(())
+            }
+            TaskAction::Backup { scope, retention } => {
+                let trigger = BackupTrigger::Task {
+                    task_id: task.id.clone(),
+                };
+                BackupManager::create_scoped_backup(instance_id, trigger, scope, None).await?;
+
+                let retention = if retention.is_empty() {
+                    RetentionPolicy {
+                        keep_last: Some(SettingsManager::get_all()?.backup_max_count.max(0) as u32),
+                        ..Default::default()
+                    }
+                } else {
+                    retention.clone()
+                };
+                let pruned =
+                    BackupScheduler::apply_task_retention(instance_id, &task.id, &retention)
+                        .await?;
+                if pruned > 0 {
+                    log::info!("Retention for task {} removed {} backups", task.id, pruned);
+                }
+                Ok(())
+            }
+        }
+    }
+
+    /// Сохранить время запуска, ошибку и пересчитать следующий запуск
+    fn store_run_state(
+        task_id: &str,
+        ran_at: Option<DateTime<Local>>,
+        now: DateTime<Local>,
+        error: Option<String>,
+    ) -> Result<()> {
+        let task = Self::get(task_id)?;
+        let next_run = task.trigger.next_run(now).map(|t| t.to_rfc3339());
+
+        let conn = get_db_conn()?;
+        conn.execute(
+            r#"UPDATE server_tasks
+               SET last_run_at = COALESCE(?2, last_run_at), next_run_at = ?3,
+                   last_error = CASE WHEN ?2 IS NULL THEN last_error ELSE ?4 END
+               WHERE id = ?1"#,
+            params![task_id, ran_at.map(|t| t.to_rfc3339()), next_run, error],
+        )?;
+        Ok(())
+    }
+
+    /// Создать таблицу задач если не существует
+    fn ensure_table(conn: &rusqlite::Connection) -> Result<()> {
+        conn.execute(
+            r#"CREATE TABLE IF NOT EXISTS server_tasks (
+                id TEXT PRIMARY KEY,
+                instance_id TEXT NOT NULL,
+                name TEXT NOT NULL,
+                enabled INTEGER NOT NULL DEFAULT 1,
+                trigger_json TEXT NOT NULL,
+                action_json TEXT NOT NULL,
+                last_run_at TEXT,
+                next_run_at TEXT,
+                last_error TEXT,
+                created_at TEXT NOT NULL,
+                FOREIGN KEY (instance_id) REFERENCES instances(id) ON DELETE CASCADE
+            )"#,
+            [],
+        )?;
+        Ok(())
+    }
+
+    /// Список задач (всех или одного сервера)
+    pub fn list(instance_id: Option<&str>) -> Result<Vec<ServerTask>> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        let mut stmt = conn.prepare(
+            r#"SELECT id, instance_id, name, enabled, trigger_json, action_json,
+                      last_run_at, next_run_at, last_error, created_at
+               FROM server_tasks
+               WHERE ?1 IS NULL OR instance_id = ?1
+               ORDER BY created_at"#,
+        )?;
+
+        let tasks = stmt
+            .query_map([instance_id], row_to_task)?
+            .filter_map(|r| r.ok())
+            .collect();
+
+        Ok(tasks)
+    }
+
+    pub fn get(task_id: &str) -> Result<ServerTask> {
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+
+        conn.query_row(
+            r#"SELECT id, instance_id, name, enabled, trigger_json, action_json,
+                      last_run_at, next_run_at, last_error, created_at
+               FROM server_tasks WHERE id = ?1"#,
+            [task_id],
+            row_to_task,
+        )
+        .optional()?
+        .ok_or_else(|| LauncherError::NotFound(format!("Server task {}", task_id)))
+    }
+
+    pub fn create(input: ServerTaskInput) -> Result<ServerTask> {
+        input.trigger.validate()?;
+
+        let id = uuid::Uuid::new_v4().to_string();
+        let next_run = input.trigger.next_run(Local::now()).map(|t| t.to_rfc3339());
+
+        let conn = get_db_conn()?;
+        Self::ensure_table(&conn)?;
+        conn.execute(
+            r#"INSERT INTO server_tasks
+                (id, instance_id, name, enabled, trigger_json, action_json, next_run_at, created_at)
+                VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8)"#,
+            params![
+                id,
+                input.instance_id,
+                input.name,
+                input.enabled,
+                serde_json::to_string(&input.trigger)?,
+                serde_json::to_string(&input.action)?,
+                next_run,
+                Utc::now().to_rfc3339(),
+            ],
+        )?;
+
+        Self::get(&id)
+    }
+
+    /// Изменить задачу. Следующий запуск пересчитывается от текущего момента.
+    pub async fn update(task_id: &str, input: ServerTaskInput) -> Result<ServerTask> {
+        input.trigger.validate()?;
+        let next_run = input.trigger.next_run(Local::now()).map(|t| t.to_rfc3339());
+
+        {
+            let conn = get_db_conn()?;
+            Self::ensure_table(&conn)?;
+            let updated = conn.execute(
+                r#"UPDATE server_tasks
+                   SET name = ?2, enabled = ?3, trigger_json = ?4, action_json = ?5,
+                       next_run_at = ?6
+                   WHERE id = ?1"#,
+                params![
+                    task_id,
+                    input.name,
+                    input.enabled,
+                    serde_json::to_string(&input.trigger)?,
+                    serde_json::to_string(&input.action)?,
+                    next_run,
+                ],
+            )?;
+            if updated == 0 {
+                return Err(LauncherError::NotFound(format!("Server task {}", task_id)));
+            }
+        }
+        LOW_TPS_SINCE.lock().await.remove(task_id);
+
+        Self::get(task_id)
+    }
+
+    pub async fn delete(task_id: &str) -> Result<()> {
+        {
+            let conn = get_db_conn()?;
+            Self::ensure_table(&conn)?;
+            conn.execute("DELETE FROM server_tasks WHERE id = ?1", [task_id])?;
+        }
+        LOW_TPS_SINCE.lock().await.remove(task_id);
+        Ok(())
+    }
+}
+
+/// Обновить серию низкого TPS. Возвращает (начало серии, условие выполнено).
+/// Нет замера — серия прерывается: без данных рестартовать нельзя.
+fn evaluate_low_tps(
+    since: Option<i64>,
+    tps: Option<f64>,
+    threshold: f64,
+    minutes: u32,
+    now: i64,
+) -> (Option<i64>, bool) {
+    match tps {
+        Some(tps) if tps < threshold => {
+            let since = since.unwrap_or(now);
+            if now - since >= minutes as i64 * 60_000 {
+                // Сбрасываем серию, чтобы не сработать повторно на следующем тике
+                (None, true)
+            } else {
+                (Some(since), false)
+            }
+        }
+        _ => (None, false),
+    }
+}
+
+/// Предупреждать игроков в чате, пока не истечёт самое долгое из `warnings`
+async fn countdown(instance_id: &str, warnings: &[u32], template: &str) {
+    let mut warnings: Vec<u32> = warnings.to_vec();
+    warnings.sort_unstable_by(|a, b| b.cmp(a));
+    warnings.dedup();
+
+    let Some(&total) = warnings.first() else {
+        return;
+    };
+    let deadline = tokio::time::Instant::now() + std::time::Duration::from_secs(total as u64);
+
+    for seconds in warnings {
+        tokio::time::sleep_until(deadline - std::time::Duration::from_secs(seconds as u64)).await;
+        let message = template.replace("{time}", &format_countdown(seconds));
+        broadcast(instance_id, &message).await;
+    }
+    tokio::time::sleep_until(deadline).await;
+}
+
+/// Сообщение в чат: через RCON, если подключён, иначе `say` в консоль
+async fn broadcast(instance_id: &str, message: &str) {
+    let rcon = RCON_CONNECTIONS.read().await.get(instance_id).cloned();
+    let sent = match rcon {
+        Some(client) => client
+            .say(message)
+            .await
+            .map(|_| ())
+            .map_err(|e| e.to_string()),
+        None => send_server_command(instance_id.to_string(), format!("say {}", message))
+            .await
+            .map(|_| ()),
+    };
+    if let Err(e) = sent {
+        log::warn!("[{}] Failed to broadcast '{}': {}", instance_id, message, e);
+    }
+}
+
+/// Остановить сервер командой `stop`, при зависании — принудительно
+pub(crate) async fn stop_and_wait(app: &AppHandle, instance_id: &str) -> Result<()> {
+    crate::instances::execution::graceful_stop_server(instance_id.to_string(), app.clone())
+        .await
+        .map_err(LauncherError::InvalidConfig)?;
+
+    let console = get_console(instance_id).await;
+    let deadline = tokio::time::Instant::now() + STOP_TIMEOUT;
+    while tokio::time::Instant::now() < deadline {
+        if !console.read().await.is_running() {
+            return Ok(());
+        }
+        tokio::time::sleep(std::time::Duration::from_secs(1)).await;
+    }
+
+    log::warn!(
+        "Server {} did not stop within {:?}, killing it",
+        instance_id,
+        STOP_TIMEOUT
+    );
+    crate::instances::execution::force_kill_server(
+        instance_id.to_string(),
+        app.state::<ChildMap>(),
+        app.clone(),
+    )
+}
+
+/// "5 min", "1 min 30 s", "10 s"
+fn format_countdown(seconds: u32) -> String {
+    match (seconds / 60, seconds % 60) {
+        (0, s) => format!("{} s", s),
+        (m, 0) => format!("{} min", m),
+        (m, s) => format!("{} min {} s", m, s),
+    }
+}
+
+fn row_to_task(row: &rusqlite::Row) -> rusqlite::Result<ServerTask> {
+    let trigger_json: String = row.get(4)?;
+    let action_json: String = row.get(5)?;
+
+    let json_error = |idx: usize, e: serde_json::Error| {
+        rusqlite::Error::FromSqlConversionFailure(idx, rusqlite::types::Type::Text, Box::new(e))
+    };
+
+    Ok(ServerTask {
+        id: row.get(0)?,
+        instance_id: row.get(1)?,
+        name: row.get(2)?,
+        enabled: row.get::<_, i32>(3)? != 0,
+        trigger: serde_json::from_str(&trigger_json).map_err(|e| json_error(4, e))?,
+        action: serde_json::from_str(&action_json).map_err(|e| json_error(5, e))?,
+        last_run_at: row.get(6)?,
+        next_run_at: row.get(7)?,
+        last_error: row.get(8)?,
+        created_at: row.get(9)?,
+    })
+}
+
+fn parse_time(value: &str) -> Option<DateTime<Local>> {
+    DateTime::parse_from_rfc3339(value)
+        .ok()
+        .map(|t| t.with_timezone(&Local))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_server_tasks(instance_id: Option<String>) -> Result<Vec<ServerTask>> {
+    ServerTaskScheduler::list(instance_id.as_deref())
+}
+
+#[tauri::command]
+pub async fn create_server_task(input: ServerTaskInput) -> Result<ServerTask> {
+    ServerTaskScheduler::create(input)
+}
+
+#[tauri::command]
+pub async fn update_server_task(task_id: String, input: ServerTaskInput) -> Result<ServerTask> {
+    ServerTaskScheduler::update(&task_id, input).await
+}
+
+#[tauri::command]
+pub async fn delete_server_task(task_id: String) -> Result<()> {
+    ServerTaskScheduler::delete(&task_id).await
+}
+
+#[tauri::command]
+pub async fn run_server_task_now(app_handle: AppHandle, task_id: String) -> Result<()> {
+    ServerTaskScheduler::run_now(&app_handle, &task_id).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_low_tps_streak() {
+        let minute = 60_000;
+        // Серия начинается с первого низкого замера
+        assert_eq!(
+            evaluate_low_tps(None, Some(8.0), 10.0, 5, 0),
+            (Some(0), false)
+        );
+        assert_eq!(
+            evaluate_low_tps(Some(0), Some(9.5), 10.0, 5, 4 * minute),
+            (Some(0), false)
+        );
+        // 5 минут подряд — срабатывает и сбрасывает серию
+        assert_eq!(
+            evaluate_low_tps(Some(0), Some(9.5), 10.0, 5, 5 * minute),
+            (None, true)
+        );
+        // Нормальный TPS или отсутствие замера прерывают серию
+        assert_eq!(
+            evaluate_low_tps(Some(0), Some(19.9), 10.0, 5, 4 * minute),
+            (None, false)
+        );
+        assert_eq!(
+            evaluate_low_tps(Some(0), None, 10.0, 5, 6 * minute),
+            (None, false)
+        );
+    }
+
+    #[test]
+    fn test_trigger_and_action_serialization() {
+        let trigger: TaskTrigger =
+            serde_json::from_str(r#"{"type":"tps_below","threshold":10,"minutes":5}"#).unwrap();
+        assert!(trigger.validate().is_ok());
+        assert_eq!(trigger.next_run(Local::now()), None);
+
+        let cron = TaskTrigger::Cron {
+            expression: "0 4 * * *".to_string(),
+        };
+        let now = Local
+            .with_ymd_and_hms(2024, 6, 1, 10, 0, 0)
+            .single()
+            .unwrap();
+        assert_eq!(
+            cron.next_run(now),
+            Local.with_ymd_and_hms(2024, 6, 2, 4, 0, 0).single()
+        );
+
+        let action: TaskAction = serde_json::from_str(r#"{"type":"restart"}"#).unwrap();
+        assert_eq!(
+            action,
+            TaskAction::Restart {
+                warnings: default_warnings(),
+                message: None
+            }
+        );
+    }
+
+    #[test]
+    fn test_format_countdown() {
+        assert_eq!(format_countdown(300), "5 min");
+        assert_eq!(format_countdown(90), "1 min 30 s");
+        assert_eq!(format_countdown(5), "5 s");
+    }
+}
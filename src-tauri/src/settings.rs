@@ -35,6 +35,68 @@ impl LaunchBehavior {
     }
 }
 
+/// Встроенный HTTP/WebSocket API для удалённого управления серверами
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct AdminApiSettings {
+    /// Выключен по умолчанию
+    pub enabled: bool,
+    /// 127.0.0.1 — только этот компьютер, 0.0.0.0 — вся сеть
+    pub bind_address: String,
+    pub port: u16,
+}
+
+impl Default for AdminApiSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "127.0.0.1".to_string(),
+            port: 25590,
+        }
+    }
+}
+
+/// Встроенная раздача resource pack'ов серверов игрокам
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ResourcePackHostSettings {
+    /// Включается автоматически при публикации пака
+    pub enabled: bool,
+    /// 0.0.0.0 — игроки из сети должны достучаться до пака
+    pub bind_address: String,
+    pub port: u16,
+    /// Адрес для URL в server.properties (домен или внешний IP).
+    /// None — локальный IP этого компьютера.
+    pub public_address: Option<String>,
+}
+
+impl Default for ResourcePackHostSettings {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            bind_address: "0.0.0.0".to_string(),
+            port: 25591,
+            public_address: None,
+        }
+    }
+}
+
+/// Watchdog серверных процессов: когда серия крашей считается crash loop
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct WatchdogSettings {
+    /// Сколько крашей в окне останавливают авто-рестарт
+    pub crash_loop_threshold: u32,
+    /// Окно подсчёта крашей, в минутах
+    pub crash_loop_window_minutes: u32,
+}
+
+impl Default for WatchdogSettings {
+    fn default() -> Self {
+        Self {
+            crash_loop_threshold: 4,
+            crash_loop_window_minutes: 10,
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Settings {
     // Интерфейс — код языка (например "ru", "en", "de", "es" и т.д.)
@@ -331,6 +393,35 @@ impl SettingsManager {
             .unwrap_or_else(|| "https://authserver.ely.by".to_string()))
     }
 
+    /// Профиль Ely.by (uuid без дефисов, ник) последнего входа
+    pub fn get_ely_by_profile() -> Result<Option<(String, String)>> {
+        match (
+            Self::get_setting("ely_by_profile_id")?,
+            Self::get_setting("ely_by_profile_name")?,
+        ) {
+            (Some(id), Some(name)) => Ok(Some((id, name))),
+            _ => Ok(None),
+        }
+    }
+
+    /// Сохранить профиль Ely.by (None = выход из аккаунта)
+    pub fn set_ely_by_profile(profile: Option<(&str, &str)>) -> Result<()> {
+        match profile {
+            Some((id, name)) => {
+                Self::set_setting("ely_by_profile_id", id)?;
+                Self::set_setting("ely_by_profile_name", name)?;
+            }
+            None => {
+                let conn = get_db_conn()?;
+                conn.execute(
+                    "DELETE FROM settings WHERE key IN ('ely_by_profile_id', 'ely_by_profile_name')",
+                    [],
+                )?;
+            }
+        }
+        Ok(())
+    }
+
     /// Получить выбранный GPU (None = автоматический выбор)
     pub fn get_selected_gpu() -> Result<Option<String>> {
         Self::get_setting("selected_gpu")
@@ -342,6 +433,112 @@ impl SettingsManager {
             .and_then(|s| s.parse().ok())
             .unwrap_or(false))
     }
+
+    /// Настройки admin API
+    pub fn get_admin_api() -> Result<AdminApiSettings> {
+        let default = AdminApiSettings::default();
+        Ok(AdminApiSettings {
+            enabled: Self::get_setting("admin_api_enabled")?
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(default.enabled),
+            bind_address: Self::get_setting("admin_api_bind_address")?
+                .unwrap_or(default.bind_address),
+            port: Self::get_setting("admin_api_port")?
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(default.port),
+        })
+    }
+
+    /// Сохранить настройки admin API
+    pub fn set_admin_api(settings: &AdminApiSettings) -> Result<()> {
+        settings
+            .bind_address
+            .parse::<std::net::IpAddr>()
+            .map_err(|_| {
+                crate::error::LauncherError::InvalidConfig(format!(
+                    "Invalid bind address: {}",
+                    settings.bind_address
+                ))
+            })?;
+
+        Self::set_setting("admin_api_enabled", &settings.enabled.to_string())?;
+        Self::set_setting("admin_api_bind_address", &settings.bind_address)?;
+        Self::set_setting("admin_api_port", &settings.port.to_string())?;
+        Ok(())
+    }
+
+    /// Настройки раздачи resource pack'ов
+    pub fn get_resource_pack_host() -> Result<ResourcePackHostSettings> {
+        let default = ResourcePackHostSettings::default();
+        Ok(ResourcePackHostSettings {
+            enabled: Self::get_setting("resource_pack_host_enabled")?
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(default.enabled),
+            bind_address: Self::get_setting("resource_pack_host_bind_address")?
+                .unwrap_or(default.bind_address),
+            port: Self::get_setting("resource_pack_host_port")?
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(default.port),
+            public_address: Self::get_setting("resource_pack_host_public_address")?
+                .filter(|s| !s.is_empty()),
+        })
+    }
+
+    /// Сохранить настройки раздачи resource pack'ов
+    pub fn set_resource_pack_host(settings: &ResourcePackHostSettings) -> Result<()> {
+        settings
+            .bind_address
+            .parse::<std::net::IpAddr>()
+            .map_err(|_| {
+                crate::error::LauncherError::InvalidConfig(format!(
+                    "Invalid bind address: {}",
+                    settings.bind_address
+                ))
+            })?;
+
+        Self::set_setting("resource_pack_host_enabled", &settings.enabled.to_string())?;
+        Self::set_setting("resource_pack_host_bind_address", &settings.bind_address)?;
+        Self::set_setting("resource_pack_host_port", &settings.port.to_string())?;
+        Self::set_setting(
+            "resource_pack_host_public_address",
+            settings.public_address.as_deref().unwrap_or(""),
+        )?;
+        Ok(())
+    }
+
+    /// Настройки watchdog серверов
+    pub fn get_watchdog() -> Result<WatchdogSettings> {
+        let default = WatchdogSettings::default();
+        Ok(WatchdogSettings {
+            crash_loop_threshold: Self::get_setting("watchdog_crash_loop_threshold")?
+                .and_then(|s| s.parse::<u32>().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default.crash_loop_threshold),
+            crash_loop_window_minutes: Self::get_setting("watchdog_crash_loop_window_minutes")?
+                .and_then(|s| s.parse::<u32>().ok())
+                .filter(|n| *n > 0)
+                .unwrap_or(default.crash_loop_window_minutes),
+        })
+    }
+
+    /// Сохранить настройки watchdog серверов
+    pub fn set_watchdog(settings: &WatchdogSettings) -> Result<()> {
+        if settings.crash_loop_threshold == 0 || settings.crash_loop_window_minutes == 0 {
+            return Err(crate::error::LauncherError::InvalidConfig(
+                "Crash loop threshold and window must be positive".to_string(),
+            ));
+        }
+
+        Self::set_setting(
+            "watchdog_crash_loop_threshold",
+            &settings.crash_loop_threshold.to_string(),
+        )?;
+        Self::set_setting(
+            "watchdog_crash_loop_window_minutes",
+            &settings.crash_loop_window_minutes.to_string(),
+        )?;
+        Ok(())
+    }
 }
 
 // Tauri commands
@@ -33,13 +33,13 @@ pub use types::{DownloadConfig, DownloadStatus, MirrorInfo, ResourceType};
 // pub use DownloadTask; - already in scope as it's in this module
 
 use crate::error::{LauncherError, Result};
+use crate::events::EventSink;
 use crate::utils::verify_file_hash;
 use futures::StreamExt;
 use reqwest::Client;
 use std::path::Path;
 use std::sync::Arc;
 use std::time::Instant;
-use tauri::Emitter;
 use tokio::io::{AsyncSeekExt, AsyncWriteExt};
 use tokio::sync::Semaphore;
 use tokio_util::sync::CancellationToken;
@@ -314,7 +314,7 @@ impl RateLimiter {
 #[derive(Clone)]
 pub struct SmartDownloader {
     client: Client,
-    app_handle: tauri::AppHandle,
+    events: EventSink,
     registry: Arc<MirrorRegistry>,
     config: DownloadConfig,
     semaphores: Arc<DownloadSemaphores>,
@@ -326,8 +326,18 @@ impl SmartDownloader {
         Self::with_config(app_handle, DownloadConfig::default())
     }
 
+    /// Создать SmartDownloader без UI (CLI): прогресс пишется только в лог
+    pub fn headless() -> Result<Self> {
+        Self::with_events(EventSink::Headless)
+    }
+
+    /// Создать SmartDownloader, отправляющий прогресс в указанный `EventSink`
+    pub fn with_events(events: EventSink) -> Result<Self> {
+        Self::with_config(events, DownloadConfig::default())
+    }
+
     /// Создать SmartDownloader с кастомной конфигурацией
-    pub fn with_config(app_handle: tauri::AppHandle, config: DownloadConfig) -> Result<Self> {
+    pub fn with_config(events: impl Into<EventSink>, config: DownloadConfig) -> Result<Self> {
         let client = Client::builder()
             .user_agent(crate::USER_AGENT)
             .timeout(config.request_timeout)
@@ -339,7 +349,7 @@ impl SmartDownloader {
 
         Ok(Self {
             client,
-            app_handle,
+            events: events.into(),
             registry: Arc::new(MirrorRegistry::new()),
             config,
             semaphores: Arc::new(DownloadSemaphores::new()),
@@ -348,7 +358,7 @@ impl SmartDownloader {
 
     /// Создать с кастомным реестром зеркал
     pub fn with_registry(
-        app_handle: tauri::AppHandle,
+        events: impl Into<EventSink>,
         registry: MirrorRegistry,
         config: DownloadConfig,
     ) -> Result<Self> {
@@ -363,16 +373,16 @@ impl SmartDownloader {
 
         Ok(Self {
             client,
-            app_handle,
+            events: events.into(),
             registry: Arc::new(registry),
             config,
             semaphores: Arc::new(DownloadSemaphores::new()),
         })
     }
 
-    /// Получить ссылку на app_handle
-    pub fn app_handle(&self) -> &tauri::AppHandle {
-        &self.app_handle
+    /// Куда отправляются события прогресса (GUI или лог)
+    pub fn events(&self) -> &EventSink {
+        &self.events
     }
 
     /// Получить ссылку на реестр зеркал
@@ -1307,7 +1317,7 @@ impl SmartDownloader {
             source: source.map(String::from),
         };
 
-        let _ = self.app_handle.emit("download-progress", progress);
+        self.events.emit("download-progress", progress);
     }
 
     // ========================================
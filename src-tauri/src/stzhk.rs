@@ -9,6 +9,8 @@
 
 use crate::downloader::DownloadManager;
 use crate::error::{LauncherError, Result};
+use crate::events::EventSink;
+use crate::mod_policy::{ModPolicyManager, ModUpdatePolicy};
 use crate::paths::instances_dir;
 use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256, Sha512};
@@ -167,6 +169,10 @@ pub struct ModEntry {
     /// Зависимости (ID модов)
     #[serde(default)]
     pub dependencies: Vec<String>,
+
+    /// Политика обновления (канал, пин, потолок, hold)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub update_policy: Option<ModUpdatePolicy>,
 }
 
 fn default_true() -> bool {
@@ -648,6 +654,7 @@ impl StzhkManager {
                     required: true,
                     side: ModSide::Both,
                     dependencies: vec![],
+                    update_policy: None,
                 });
             }
             return Ok(entries);
@@ -678,6 +685,7 @@ impl StzhkManager {
                 required: true,
                 side: ModSide::Both,
                 dependencies: vec![],
+                update_policy: None,
             });
         }
 
@@ -1025,14 +1033,14 @@ impl StzhkManager {
         instance_name: String,
         _selected_optionals: Vec<String>,
         download_manager: &DownloadManager,
-        app_handle: &tauri::AppHandle,
     ) -> Result<String> {
         log::info!("Installing STZHK modpack from {:?}", path);
+        let events = download_manager.events();
 
         let manifest = Self::read_manifest(path).await?;
 
         // Создаём экземпляр
-        let instance = crate::instances::create_instance(
+        let instance = crate::instances::lifecycle::create_instance_with_events(
             crate::types::CreateInstanceRequest {
                 name: instance_name.clone(),
                 game_type: Some("minecraft".to_string()),
@@ -1048,7 +1056,7 @@ impl StzhkManager {
                 username: None,
                 notes: None,
             },
-            app_handle.clone(),
+            events.clone(),
         )
         .await?;
 
@@ -1063,7 +1071,7 @@ impl StzhkManager {
         let failed: Arc<Mutex<Vec<FailedDownload>>> = Arc::new(Mutex::new(Vec::new()));
 
         // ========== PHASE 1: Extract embedded mods (sequential, archive is not thread-safe) ==========
-        let _ = app_handle.emit(
+        events.emit(
             "modpack-install-progress",
             serde_json::json!({
                 "stage": "extracting_overrides",
@@ -1105,7 +1113,7 @@ impl StzhkManager {
 
         // Write embedded mods and check hashes
         for (mod_entry, content) in embedded_mods_data {
-            let _ = app_handle.emit(
+            events.emit(
                 "modpack-install-progress",
                 serde_json::json!({
                     "stage": "extracting_overrides",
@@ -1210,7 +1218,7 @@ impl StzhkManager {
                 download_tasks.len()
             );
 
-            let _ = app_handle.emit(
+            events.emit(
                 "modpack-install-progress",
                 serde_json::json!({
                     "stage": "downloading_mods",
@@ -1230,7 +1238,7 @@ impl StzhkManager {
                 let sem = Arc::clone(&semaphore);
                 let dm = download_manager.clone();
                 let mods_path = mods_path.clone();
-                let app = app_handle.clone();
+                let app = events.clone();
                 let installed_counter = Arc::clone(&installed);
                 let failed_list = Arc::clone(&failed);
                 let total = total_mods;
@@ -1257,7 +1265,7 @@ impl StzhkManager {
 
                     // Emit progress
                     let current = installed_counter.load(Ordering::SeqCst);
-                    let _ = app.emit(
+                    app.emit(
                         "modpack-install-progress",
                         serde_json::json!({
                             "stage": "downloading_mods",
@@ -1296,7 +1304,7 @@ impl StzhkManager {
         }
 
         // ========== PHASE 3: Extract overrides ==========
-        let _ = app_handle.emit(
+        events.emit(
             "modpack-install-progress",
             serde_json::json!({
                 "stage": "extracting_overrides",
@@ -1337,6 +1345,9 @@ impl StzhkManager {
                 log::info!("Registered {} mods in database", mod_files_info.len());
             }
         }
+        if let Err(e) = Self::apply_update_policies(&instance_id, &manifest.mods) {
+            log::warn!("Failed to apply STZHK mod update policies: {}", e);
+        }
 
         // Final results
         let final_installed = installed.load(Ordering::SeqCst);
@@ -1350,7 +1361,7 @@ impl StzhkManager {
         let total_expected = total_mods + overrides_mods_installed as usize;
 
         // Emit completion event
-        let _ = app_handle.emit(
+        events.emit(
             "modpack-install-progress",
             serde_json::json!({
                 "stage": "completed",
@@ -1525,6 +1536,52 @@ impl StzhkManager {
         Ok(result)
     }
 
+    /// Записать политики обновления модов экземпляра в манифест
+    fn attach_update_policies(instance_id: &str, mods: &mut [ModEntry]) -> Result<()> {
+        let policies = ModPolicyManager::list(instance_id)?;
+        if policies.is_empty() {
+            return Ok(());
+        }
+
+        let slugs: HashMap<String, String> = {
+            let conn = crate::db::get_db_conn()?;
+            let mut stmt =
+                conn.prepare("SELECT file_name, slug FROM mods WHERE instance_id = ?1")?;
+            let rows = stmt.query_map([instance_id], |row| Ok((row.get(0)?, row.get(1)?)))?;
+            rows.filter_map(|r| r.ok()).collect()
+        };
+
+        for entry in mods.iter_mut() {
+            entry.update_policy = slugs
+                .get(&entry.filename)
+                .and_then(|slug| policies.get(slug))
+                .cloned();
+        }
+        Ok(())
+    }
+
+    /// Перенести политики обновления из манифеста в установленный экземпляр
+    fn apply_update_policies(instance_id: &str, mods: &[ModEntry]) -> Result<()> {
+        let conn = crate::db::get_db_conn()?;
+        for entry in mods {
+            let Some(policy) = &entry.update_policy else {
+                continue;
+            };
+            let slug: Option<String> = conn
+                .query_row(
+                    "SELECT slug FROM mods WHERE instance_id = ?1 AND file_name = ?2",
+                    [instance_id, entry.filename.as_str()],
+                    |row| row.get(0),
+                )
+                .ok();
+            match slug {
+                Some(slug) => ModPolicyManager::set(instance_id, &slug, policy)?,
+                None => log::warn!("No installed mod for policy of {}", entry.filename),
+            }
+        }
+        Ok(())
+    }
+
     /// Вычислить SHA256 хеш
     pub fn calculate_sha256(data: &[u8]) -> String {
         let mut hasher = Sha256::new();
@@ -1544,7 +1601,7 @@ impl StzhkManager {
         instance_id: &str,
         output_path: &Path,
         options: &ExportOptions,
-        app_handle: &tauri::AppHandle,
+        events: &EventSink,
     ) -> Result<PathBuf> {
         let conn = crate::db::get_db_conn()?;
 
@@ -1601,6 +1658,7 @@ impl StzhkManager {
                         required: true,
                         side: ModSide::Both,
                         dependencies: vec![],
+                        update_policy: None,
                     })
                     .collect()
             } else {
@@ -1610,10 +1668,11 @@ impl StzhkManager {
         };
 
         // Filter out excluded mods
-        let mods: Vec<ModEntry> = all_mods
+        let mut mods: Vec<ModEntry> = all_mods
             .into_iter()
             .filter(|m| !excluded_mods.contains(m.filename.as_str()))
             .collect();
+        Self::attach_update_policies(instance_id, &mut mods)?;
 
         log::info!("Exporting {} mods (after filtering)", mods.len());
 
@@ -1737,7 +1796,7 @@ impl StzhkManager {
             &mods_path,
             options.embed_mods,
             &override_dirs,
-            app_handle,
+            events,
         )
         .await?;
 
@@ -1784,7 +1843,7 @@ impl StzhkManager {
         mods_path: &Path,
         _embed_mods: bool,
         override_dirs: &[(&str, PathBuf)],
-        app_handle: &tauri::AppHandle,
+        events: &EventSink,
     ) -> Result<()> {
         // Calculate total steps for progress
         let mods_to_embed = manifest
@@ -1799,7 +1858,7 @@ impl StzhkManager {
         // ========== Phase 1: Async data collection ==========
 
         // Send initial progress
-        let _ = app_handle.emit(
+        events.emit(
             "stzhk-export-progress",
             serde_json::json!({
                 "stage": "manifest",
@@ -1823,7 +1882,7 @@ impl StzhkManager {
 
                 if tokio::fs::try_exists(&mod_file_path).await.unwrap_or(false) {
                     // Send progress before processing
-                    let _ = app_handle.emit(
+                    events.emit(
                         "stzhk-export-progress",
                         serde_json::json!({
                             "stage": "mods",
@@ -1864,7 +1923,7 @@ impl StzhkManager {
 
         for (name, path) in override_dirs {
             // Send progress
-            let _ = app_handle.emit(
+            events.emit(
                 "stzhk-export-progress",
                 serde_json::json!({
                     "stage": "overrides",
@@ -1918,7 +1977,7 @@ impl StzhkManager {
         );
 
         // Send finishing progress
-        let _ = app_handle.emit(
+        events.emit(
             "stzhk-export-progress",
             serde_json::json!({
                 "stage": "finishing",
@@ -2531,7 +2590,6 @@ pub async fn install_stzhk(
         instance_name,
         selected_optionals,
         &download_manager,
-        &app_handle,
     )
     .await
 }
@@ -2572,7 +2630,7 @@ pub async fn export_stzhk(
         &instance_id,
         &PathBuf::from(output_path),
         &options,
-        &app_handle,
+        &app_handle.into(),
     )
     .await?;
 
@@ -2627,7 +2685,6 @@ pub async fn install_stzhk_from_url(
         instance_name,
         selected_optionals,
         &download_manager,
-        &app_handle,
     )
     .await?;
 
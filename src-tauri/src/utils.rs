@@ -1,4 +1,5 @@
 use crate::error::Result;
+use md5::Md5;
 use rand::{rngs::OsRng, TryRngCore};
 use sha1::{Digest, Sha1};
 use sha2::{Sha256, Sha512};
@@ -77,12 +78,17 @@ pub fn calculate_sha512<P: AsRef<Path>>(path: P) -> Result<String> {
     impl_file_hash!(Sha512, path)
 }
 
+/// Вычисление MD5 хеша файла (Purpur API отдаёт только MD5)
+pub fn calculate_md5<P: AsRef<Path>>(path: P) -> Result<String> {
+    impl_file_hash!(Md5, path)
+}
+
 /// Верификация файла по хешу
 pub fn verify_file_hash<P: AsRef<Path>>(path: P, expected: &str) -> Result<bool> {
-    let actual = if expected.len() == 40 {
-        calculate_sha1(&path)?
-    } else {
-        calculate_sha256(&path)?
+    let actual = match expected.len() {
+        32 => calculate_md5(&path)?,
+        40 => calculate_sha1(&path)?,
+        _ => calculate_sha256(&path)?,
     };
 
     Ok(actual.eq_ignore_ascii_case(expected))
@@ -140,62 +146,13 @@ pub fn compare_versions(a: &str, b: &str) -> std::cmp::Ordering {
 }
 
 /// Проверка соответствия версии требованию
+///
+/// Maven-диапазоны (`[1.0,2.0)`) и semver-выражения (`>=1.0 <2`, `^1.2`, `1.20.x`)
+/// разбирает `dependency_solver::VersionReq`. Нераспознанные требования (например
+/// `${version}` из необработанного mods.toml) считаются выполненными.
 pub fn version_matches_requirement(version: &str, requirement: &str) -> bool {
-    // Handle wildcards - any version matches
-    if requirement == "*" || requirement.is_empty() {
-        return true;
-    }
-
-    let version = match parse_version(version) {
-        Some(v) => v,
-        None => return false,
-    };
-
-    // Парсим requirement (>=1.0.0, [1.0.0,2.0.0), etc.)
-    if requirement.starts_with(">=") {
-        if let Some(req_ver) = parse_version(&requirement[2..]) {
-            return version >= req_ver;
-        }
-    } else if requirement.starts_with("<=") {
-        if let Some(req_ver) = parse_version(&requirement[2..]) {
-            return version <= req_ver;
-        }
-    } else if requirement.starts_with('>') {
-        if let Some(req_ver) = parse_version(&requirement[1..]) {
-            return version > req_ver;
-        }
-    } else if requirement.starts_with('<') {
-        if let Some(req_ver) = parse_version(&requirement[1..]) {
-            return version < req_ver;
-        }
-    } else if requirement.starts_with('[') && requirement.ends_with(')') {
-        // Range [min,max)
-        let inner = &requirement[1..requirement.len() - 1];
-        if let Some((min_str, max_str)) = inner.split_once(',') {
-            if let (Some(min), Some(max)) =
-                (parse_version(min_str.trim()), parse_version(max_str.trim()))
-            {
-                return version >= min && version < max;
-            }
-        }
-    } else if requirement.starts_with('[') && requirement.ends_with(']') {
-        // Range [min,max]
-        let inner = &requirement[1..requirement.len() - 1];
-        if let Some((min_str, max_str)) = inner.split_once(',') {
-            if let (Some(min), Some(max)) =
-                (parse_version(min_str.trim()), parse_version(max_str.trim()))
-            {
-                return version >= min && version <= max;
-            }
-        }
-    } else {
-        // Точное совпадение
-        if let Some(req_ver) = parse_version(requirement) {
-            return version == req_ver;
-        }
-    }
-
-    false
+    crate::dependency_solver::VersionReq::parse(requirement)
+        .map_or(true, |req| req.matches(version))
 }
 
 /// Sanitize имени файла (удаление опасных символов)
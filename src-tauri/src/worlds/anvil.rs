@@ -0,0 +1,243 @@
+//! Anvil region files (`r.<x>.<z>.mca`)
+//!
+//! Регион — 32×32 чанка. Заголовок: 1024 записи о положении (3 байта — смещение
+//! в секторах по 4 КиБ, 1 байт — число секторов) и 1024 отметки времени.
+//! Данные чанка: длина (4 байта), тип сжатия (1 байт) и сжатый NBT.
+//! Тип с флагом 128 — чанк вынесен во внешний файл `c.<x>.<z>.mcc` рядом с регионом.
+
+use std::path::{Path, PathBuf};
+
+use super::nbt::{self, Compound};
+use crate::error::{LauncherError, Result};
+
+pub const SECTOR_SIZE: usize = 4096;
+const HEADER_SIZE: usize = 2 * SECTOR_SIZE;
+pub const CHUNKS_PER_REGION: i32 = 32;
+
+const COMPRESSION_GZIP: u8 = 1;
+const COMPRESSION_ZLIB: u8 = 2;
+const COMPRESSION_NONE: u8 = 3;
+const COMPRESSION_LZ4: u8 = 4;
+const EXTERNAL_FLAG: u8 = 128;
+
+/// Координаты региона из имени файла `r.<x>.<z>.mca`
+pub fn parse_region_name(file_name: &str) -> Option<(i32, i32)> {
+    let rest = file_name.strip_prefix("r.")?.strip_suffix(".mca")?;
+    let (x, z) = rest.split_once('.')?;
+    Some((x.parse().ok()?, z.parse().ok()?))
+}
+
+/// Все регионы директории с координатами
+pub fn list_regions(dir: &Path) -> Vec<(i32, i32, PathBuf)> {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return Vec::new();
+    };
+    let mut regions: Vec<_> = entries
+        .filter_map(|e| e.ok())
+        .filter_map(|e| {
+            let (x, z) = parse_region_name(&e.file_name().to_string_lossy())?;
+            Some((x, z, e.path()))
+        })
+        .collect();
+    regions.sort();
+    regions
+}
+
+/// Чанк региона в сыром виде (без распаковки)
+#[derive(Debug, Clone)]
+pub struct RawChunk {
+    /// Локальные координаты внутри региона (0..32)
+    pub local_x: i32,
+    pub local_z: i32,
+    pub timestamp: u32,
+    pub compression: u8,
+    /// Сжатые данные; пусто, если чанк во внешнем `.mcc`
+    pub data: Vec<u8>,
+}
+
+impl RawChunk {
+    pub fn is_external(&self) -> bool {
+        self.compression & EXTERNAL_FLAG != 0
+    }
+
+    /// Распаковать NBT чанка. `region_path` нужен для внешних `.mcc`.
+    /// None — формат не поддерживается (LZ4).
+    pub fn read_nbt(&self, region_path: &Path, region: (i32, i32)) -> Result<Option<Compound>> {
+        let external;
+        let data = if self.is_external() {
+            let (x, z) = self.world_coords(region);
+            external = std::fs::read(region_path.with_file_name(format!("c.{}.{}.mcc", x, z)))?;
+            &external[..]
+        } else {
+            &self.data[..]
+        };
+
+        match self.compression & !EXTERNAL_FLAG {
+            COMPRESSION_GZIP | COMPRESSION_ZLIB | COMPRESSION_NONE => {
+                Ok(Some(nbt::from_bytes(data)?.1))
+            }
+            COMPRESSION_LZ4 => Ok(None),
+            other => Err(LauncherError::InvalidConfig(format!(
+                "Unknown chunk compression {} in {}",
+                other,
+                region_path.display()
+            ))),
+        }
+    }
+
+    /// Координаты чанка в мире
+    pub fn world_coords(&self, region: (i32, i32)) -> (i32, i32) {
+        (
+            region.0 * CHUNKS_PER_REGION + self.local_x,
+            region.1 * CHUNKS_PER_REGION + self.local_z,
+        )
+    }
+}
+
+/// Прочитать все чанки региона. Битые записи заголовка пропускаются.
+pub fn read_region(path: &Path) -> Result<Vec<RawChunk>> {
+    let data = std::fs::read(path)?;
+    if data.is_empty() {
+        return Ok(Vec::new());
+    }
+    if data.len() < HEADER_SIZE {
+        return Err(LauncherError::InvalidConfig(format!(
+            "Region file is truncated: {}",
+            path.display()
+        )));
+    }
+
+    let mut chunks = Vec::new();
+    for index in 0..1024 {
+        let entry = &data[index * 4..index * 4 + 4];
+        let offset = u32::from_be_bytes([0, entry[0], entry[1], entry[2]]) as usize * SECTOR_SIZE;
+        let sectors = entry[3] as usize;
+        if offset == 0 || sectors == 0 {
+            continue;
+        }
+        let timestamp = u32::from_be_bytes(
+            data[SECTOR_SIZE + index * 4..SECTOR_SIZE + index * 4 + 4]
+                .try_into()
+                .unwrap(),
+        );
+
+        let Some(header) = data.get(offset..offset + 5) else {
+            log::warn!("Chunk {} points past the end of {}", index, path.display());
+            continue;
+        };
+        let length = u32::from_be_bytes(header[..4].try_into().unwrap()) as usize;
+        let compression = header[4];
+        // length включает байт сжатия
+        let Some(payload) = length
+            .checked_sub(1)
+            .and_then(|len| data.get(offset + 5..offset + 5 + len))
+        else {
+            log::warn!("Chunk {} has invalid length in {}", index, path.display());
+            continue;
+        };
+
+        chunks.push(RawChunk {
+            local_x: (index % 32) as i32,
+            local_z: (index / 32) as i32,
+            timestamp,
+            compression,
+            data: payload.to_vec(),
+        });
+    }
+    Ok(chunks)
+}
+
+/// Записать регион заново из списка чанков (плотно, без дыр).
+/// Пустой список удаляет файл — игра создаст регион при необходимости.
+pub fn write_region(path: &Path, chunks: &[RawChunk]) -> Result<()> {
+    if chunks.is_empty() {
+        if path.exists() {
+            std::fs::remove_file(path)?;
+        }
+        return Ok(());
+    }
+
+    let mut out = vec![0u8; HEADER_SIZE];
+    for chunk in chunks {
+        let index = (chunk.local_x + chunk.local_z * 32) as usize;
+        let offset_sectors = out.len() / SECTOR_SIZE;
+        let length = chunk.data.len() + 1;
+        let sectors = (4 + length).div_ceil(SECTOR_SIZE);
+        if sectors > 255 {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Chunk {} in {} is too large for inline storage",
+                index,
+                path.display()
+            )));
+        }
+
+        out[index * 4..index * 4 + 3].copy_from_slice(&(offset_sectors as u32).to_be_bytes()[1..]);
+        out[index * 4 + 3] = sectors as u8;
+        out[SECTOR_SIZE + index * 4..SECTOR_SIZE + index * 4 + 4]
+            .copy_from_slice(&chunk.timestamp.to_be_bytes());
+
+        out.extend_from_slice(&(length as u32).to_be_bytes());
+        out.push(chunk.compression);
+        out.extend_from_slice(&chunk.data);
+        out.resize(out.len().div_ceil(SECTOR_SIZE) * SECTOR_SIZE, 0);
+    }
+
+    let tmp = path.with_extension("mca.tmp");
+    std::fs::write(&tmp, out)?;
+    std::fs::rename(&tmp, path)?;
+    Ok(())
+}
+
+/// Сколько тиков игроки провели в чанке (суммарно по всем игрокам рядом)
+pub fn inhabited_time(chunk: &Compound) -> Option<i64> {
+    // 1.18+: поля в корне; раньше — внутри "Level"
+    chunk
+        .i64("InhabitedTime")
+        .or_else(|| chunk.compound("Level")?.i64("InhabitedTime"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::worlds::nbt::{Compression, Tag};
+
+    fn chunk(local_x: i32, local_z: i32, inhabited: i64) -> RawChunk {
+        let mut root = Compound::default();
+        root.insert("DataVersion", Tag::Int(3955));
+        root.insert("InhabitedTime", Tag::Long(inhabited));
+        RawChunk {
+            local_x,
+            local_z,
+            timestamp: 1_700_000_000,
+            compression: COMPRESSION_ZLIB,
+            data: nbt::to_bytes("", &root, Compression::Zlib).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_region_roundtrip() {
+        let dir = std::env::temp_dir().join(format!("stuzhik-anvil-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("r.-1.2.mca");
+        assert_eq!(parse_region_name("r.-1.2.mca"), Some((-1, 2)));
+        assert_eq!(parse_region_name("r.1.mca"), None);
+
+        write_region(&path, &[chunk(0, 0, 10), chunk(31, 31, 5000)]).unwrap();
+        let chunks = read_region(&path).unwrap();
+        assert_eq!(chunks.len(), 2);
+        assert_eq!(
+            std::fs::metadata(&path).unwrap().len() as usize % SECTOR_SIZE,
+            0
+        );
+
+        let last = &chunks[1];
+        assert_eq!(last.world_coords((-1, 2)), (-1, 95));
+        let nbt = last.read_nbt(&path, (-1, 2)).unwrap().unwrap();
+        assert_eq!(inhabited_time(&nbt), Some(5000));
+        assert_eq!(last.timestamp, 1_700_000_000);
+
+        write_region(&path, &[]).unwrap();
+        assert!(!path.exists());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,740 @@
+//! World management
+//!
+//! Миры экземпляров: список с сидом, режимом, версией и размером по измерениям,
+//! обрезка чанков, где игроки почти не бывали, удаление регионов за радиусом
+//! и обновление мира до версии сервера (`--forceUpgrade`).
+//!
+//! Всё, что меняет мир, требует остановленного экземпляра и сначала делает бэкап мира.
+
+pub mod anvil;
+pub mod nbt;
+pub mod playerdata;
+
+use chrono::{TimeZone, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use std::time::Duration;
+use tauri::{AppHandle, Manager};
+
+use crate::backup::{BackupManager, BackupScope, BackupTrigger};
+use crate::error::{LauncherError, Result};
+use crate::instances::lifecycle::{get_instance, ChildMap};
+use crate::server::console::get_console;
+use crate::types::InstanceType;
+
+use nbt::Compound;
+
+/// `--forceUpgrade` больших миров идёт часами
+const UPGRADE_TIMEOUT: Duration = Duration::from_secs(6 * 3600);
+
+/// Подкаталоги измерения, в которых лежат регионы (чанки, сущности, POI)
+const REGION_KINDS: &[&str] = &["region", "entities", "poi"];
+
+/// Измерение мира
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DimensionInfo {
+    /// "minecraft:overworld", "minecraft:the_nether", "minecraft:the_end" или из datapack'а
+    pub id: String,
+    /// Путь относительно директории мира ("" для верхнего мира)
+    pub path: String,
+    pub region_count: u32,
+    /// region + entities + poi
+    pub size_bytes: u64,
+}
+
+/// Мир экземпляра
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldInfo {
+    /// Имя директории
+    pub name: String,
+    pub path: String,
+    /// Имя мира из level.dat
+    pub level_name: Option<String>,
+    pub seed: Option<i64>,
+    /// survival, creative, adventure, spectator
+    pub game_mode: Option<String>,
+    pub hardcore: bool,
+    pub last_played: Option<String>,
+    pub data_version: Option<i32>,
+    pub version_name: Option<String>,
+    pub size_bytes: u64,
+    pub dimensions: Vec<DimensionInfo>,
+}
+
+/// Результат обрезки мира
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TrimReport {
+    pub dry_run: bool,
+    pub backup_id: Option<String>,
+    pub regions_scanned: u32,
+    pub regions_deleted: u32,
+    pub chunks_removed: u32,
+    /// Чанки, которые не удалось прочитать (оставлены как есть)
+    pub chunks_skipped: u32,
+    pub bytes_freed: u64,
+}
+
+impl TrimReport {
+    fn merge(&mut self, other: TrimReport) {
+        self.regions_scanned += other.regions_scanned;
+        self.regions_deleted += other.regions_deleted;
+        self.chunks_removed += other.chunks_removed;
+        self.chunks_skipped += other.chunks_skipped;
+        self.bytes_freed += other.bytes_freed;
+    }
+}
+
+/// Результат обновления мира
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct WorldUpgradeReport {
+    pub backup_id: String,
+    pub data_version_before: Option<i32>,
+    pub data_version_after: Option<i32>,
+    pub version_name: Option<String>,
+}
+
+pub struct WorldManager;
+
+impl WorldManager {
+    /// Миры экземпляра с метаданными из level.dat
+    pub async fn list(instance_id: &str) -> Result<Vec<WorldInfo>> {
+        let mut dirs = Vec::new();
+        for name in BackupManager::list_worlds(instance_id).await? {
+            dirs.push((
+                name.clone(),
+                BackupManager::world_dir(instance_id, &name).await?,
+            ));
+        }
+
+        tokio::task::spawn_blocking(move || {
+            dirs.into_iter()
+                .map(|(name, dir)| world_info(&name, &dir))
+                .collect::<Vec<_>>()
+        })
+        .await
+        .map_err(|e| LauncherError::Join(e.to_string()))
+    }
+
+    /// Удалить чанки, в которых игроки провели меньше `max_inhabited_ticks`.
+    /// Игра сгенерирует их заново, когда кто-нибудь туда придёт.
+    pub async fn trim_chunks(
+        app: &AppHandle,
+        instance_id: &str,
+        world: &str,
+        dimension: Option<&str>,
+        max_inhabited_ticks: i64,
+        dry_run: bool,
+    ) -> Result<TrimReport> {
+        let world_dir = BackupManager::world_dir(instance_id, world).await?;
+        let roots = select_dimensions(&world_dir, dimension)?;
+        let backup_id = if dry_run {
+            None
+        } else {
+            ensure_stopped(app, instance_id).await?;
+            Some(backup_world(instance_id, world, "trim_chunks").await?)
+        };
+
+        let mut report = tokio::task::spawn_blocking(move || {
+            let mut report = TrimReport::default();
+            for (_, root) in roots {
+                report.merge(trim_dimension(&root, max_inhabited_ticks, dry_run)?);
+            }
+            Ok::<_, LauncherError>(report)
+        })
+        .await
+        .map_err(|e| LauncherError::Join(e.to_string()))??;
+
+        report.dry_run = dry_run;
+        report.backup_id = backup_id;
+        log::info!(
+            "Trimmed world {} of {}: {} chunks, {} regions, {} bytes (dry run: {})",
+            world,
+            instance_id,
+            report.chunks_removed,
+            report.regions_deleted,
+            report.bytes_freed,
+            dry_run
+        );
+        Ok(report)
+    }
+
+    /// Удалить регионы, целиком лежащие дальше `radius` блоков от центра.
+    /// Центр по умолчанию — точка спавна (в Незере — делённая на 8), в координатах измерения.
+    pub async fn delete_regions_outside(
+        app: &AppHandle,
+        instance_id: &str,
+        world: &str,
+        dimension: Option<&str>,
+        radius: u32,
+        center: Option<(i32, i32)>,
+        dry_run: bool,
+    ) -> Result<TrimReport> {
+        if radius == 0 {
+            return Err(LauncherError::InvalidConfig(
+                "Radius must be greater than zero".to_string(),
+            ));
+        }
+        let world_dir = BackupManager::world_dir(instance_id, world).await?;
+        let roots = select_dimensions(&world_dir, dimension)?;
+        let backup_id = if dry_run {
+            None
+        } else {
+            ensure_stopped(app, instance_id).await?;
+            Some(backup_world(instance_id, world, "delete_regions").await?)
+        };
+
+        let mut report = tokio::task::spawn_blocking(move || {
+            let spawn = read_level_data(&world_dir)
+                .ok()
+                .map(|data| spawn_point(&data));
+            let mut report = TrimReport::default();
+            for (id, root) in roots {
+                let center = center.unwrap_or_else(|| match (id.as_str(), spawn) {
+                    ("minecraft:the_nether", Some((x, z))) => (x / 8, z / 8),
+                    ("minecraft:the_end", _) => (0, 0),
+                    (_, spawn) => spawn.unwrap_or((0, 0)),
+                });
+                report.merge(delete_outside(&root, center, radius as i64, dry_run)?);
+            }
+            Ok::<_, LauncherError>(report)
+        })
+        .await
+        .map_err(|e| LauncherError::Join(e.to_string()))??;
+
+        report.dry_run = dry_run;
+        report.backup_id = backup_id;
+        log::info!(
+            "Deleted {} regions outside radius {} in world {} of {} (dry run: {})",
+            report.regions_deleted,
+            radius,
+            world,
+            instance_id,
+            dry_run
+        );
+        Ok(report)
+    }
+
+    /// Обновить мир сервера до его текущей версии: бэкап мира, затем запуск с
+    /// `--forceUpgrade` (конвертирует все чанки) и остановка после старта.
+    pub async fn upgrade(
+        app: &AppHandle,
+        instance_id: &str,
+        world: &str,
+        erase_cache: bool,
+    ) -> Result<WorldUpgradeReport> {
+        let instance = get_instance(instance_id.to_string()).await?;
+        if !matches!(instance.instance_type, InstanceType::Server) {
+            return Err(LauncherError::InvalidConfig(
+                "World upgrade is only available for servers. On a client, use \"Optimize world\" in the world settings".to_string(),
+            ));
+        }
+        ensure_stopped(app, instance_id).await?;
+
+        let world_dir = BackupManager::world_dir(instance_id, world).await?;
+        let level_dir = world_dir.clone();
+        let data_version_before = tokio::task::spawn_blocking(move || {
+            read_level_data(&level_dir)
+                .ok()
+                .and_then(|data| data_version(&data))
+        })
+        .await
+        .map_err(|e| LauncherError::Join(e.to_string()))?;
+
+        let backup_id = backup_world(instance_id, world, "upgrade").await?;
+
+        let mut args = vec![
+            "--forceUpgrade".to_string(),
+            "--world".to_string(),
+            world.to_string(),
+        ];
+        if erase_cache {
+            args.push("--eraseCache".to_string());
+        }
+        crate::instances::execution::set_next_launch_server_args(instance_id, args);
+
+        log::info!("Upgrading world {} of {}", world, instance_id);
+        let started =
+            crate::server::network::start_and_wait(app, instance_id, UPGRADE_TIMEOUT).await;
+        // Если сервер так и не стартовал, аргументы не должны достаться обычному запуску
+        crate::instances::execution::set_next_launch_server_args(instance_id, Vec::new());
+        started?;
+        crate::server::tasks::stop_and_wait(app, instance_id).await?;
+
+        let data = tokio::task::spawn_blocking(move || read_level_data(&world_dir))
+            .await
+            .map_err(|e| LauncherError::Join(e.to_string()))??;
+        let report = WorldUpgradeReport {
+            backup_id,
+            data_version_before,
+            data_version_after: data_version(&data),
+            version_name: data
+                .path(&["Version", "Name"])
+                .and_then(|t| t.as_str())
+                .map(str::to_string),
+        };
+        log::info!(
+            "World {} of {} upgraded: data version {:?} -> {:?}",
+            world,
+            instance_id,
+            report.data_version_before,
+            report.data_version_after
+        );
+        Ok(report)
+    }
+}
+
+const GAME_MODES: &[&str] = &["survival", "creative", "adventure", "spectator"];
+
+/// `GameType` / `playerGameType` -> имя режима
+fn game_mode_name(mode: i64) -> &'static str {
+    usize::try_from(mode)
+        .ok()
+        .and_then(|i| GAME_MODES.get(i))
+        .copied()
+        .unwrap_or("unknown")
+}
+
+fn game_mode_id(name: &str) -> Option<i32> {
+    GAME_MODES.iter().position(|m| *m == name).map(|i| i as i32)
+}
+
+/// Менять мир запущенного экземпляра нельзя: игра перезапишет регионы из памяти
+async fn ensure_stopped(app: &AppHandle, instance_id: &str) -> Result<()> {
+    let has_process = app
+        .state::<ChildMap>()
+        .lock()
+        .unwrap_or_else(|e| e.into_inner())
+        .contains_key(instance_id);
+    if has_process || get_console(instance_id).await.read().await.is_running() {
+        return Err(LauncherError::InstanceAlreadyRunning);
+    }
+    Ok(())
+}
+
+async fn backup_world(instance_id: &str, world: &str, action: &str) -> Result<String> {
+    let record = BackupManager::create_scoped_backup(
+        instance_id,
+        BackupTrigger::BeforeWorldEdit {
+            world: world.to_string(),
+            action: action.to_string(),
+        },
+        &BackupScope::World {
+            world: world.to_string(),
+        },
+        None,
+    )
+    .await?;
+    Ok(record.id)
+}
+
+/// Compound `Data` из level.dat
+fn read_level_data(world_dir: &Path) -> Result<Compound> {
+    let (_, mut root, _) = nbt::read_file(&world_dir.join("level.dat"))?;
+    match root.remove("Data") {
+        Some(nbt::Tag::Compound(data)) => Ok(data),
+        _ => Err(LauncherError::InvalidConfig(format!(
+            "level.dat without Data in {}",
+            world_dir.display()
+        ))),
+    }
+}
+
+fn data_version(data: &Compound) -> Option<i32> {
+    data.i64("DataVersion").map(|v| v as i32)
+}
+
+/// Спавн мира: `SpawnX`/`SpawnZ` или (1.21.9+) `spawn.pos`
+fn spawn_point(data: &Compound) -> (i32, i32) {
+    if let (Some(x), Some(z)) = (data.i64("SpawnX"), data.i64("SpawnZ")) {
+        return (x as i32, z as i32);
+    }
+    match data.path(&["spawn", "pos"]) {
+        Some(nbt::Tag::IntArray(pos)) if pos.len() == 3 => (pos[0], pos[2]),
+        _ => (0, 0),
+    }
+}
+
+fn world_info(name: &str, dir: &Path) -> WorldInfo {
+    let data = read_level_data(dir)
+        .map_err(|e| log::warn!("Failed to read level.dat of {}: {}", dir.display(), e))
+        .ok();
+    let data = data.as_ref();
+
+    let dimensions = dimension_dirs(dir)
+        .into_iter()
+        .map(|(id, root)| {
+            let (size_bytes, region_count) = REGION_KINDS.iter().fold((0, 0), |acc, kind| {
+                let region_dir = root.join(kind);
+                let regions = if *kind == "region" {
+                    anvil::list_regions(&region_dir).len() as u32
+                } else {
+                    0
+                };
+                (acc.0 + dir_size(&region_dir), acc.1 + regions)
+            });
+            DimensionInfo {
+                id,
+                path: root
+                    .strip_prefix(dir)
+                    .map(|p| p.to_string_lossy().replace('\\', "/"))
+                    .unwrap_or_default(),
+                region_count,
+                size_bytes,
+            }
+        })
+        .collect();
+
+    WorldInfo {
+        name: name.to_string(),
+        path: dir.to_string_lossy().to_string(),
+        level_name: data.and_then(|d| d.str("LevelName")).map(str::to_string),
+        // 1.16+: WorldGenSettings.seed, раньше — RandomSeed
+        seed: data.and_then(|d| {
+            d.path(&["WorldGenSettings", "seed"])
+                .and_then(|t| t.as_i64())
+                .or_else(|| d.i64("RandomSeed"))
+        }),
+        game_mode: data
+            .and_then(|d| d.i64("GameType"))
+            .map(|mode| game_mode_name(mode).to_string()),
+        hardcore: data.and_then(|d| d.i64("hardcore")).unwrap_or(0) != 0,
+        last_played: data
+            .and_then(|d| d.i64("LastPlayed"))
+            .and_then(|ms| Utc.timestamp_millis_opt(ms).single())
+            .map(|t| t.to_rfc3339()),
+        data_version: data.and_then(data_version),
+        version_name: data
+            .and_then(|d| d.path(&["Version", "Name"]))
+            .and_then(|t| t.as_str())
+            .map(str::to_string),
+        size_bytes: dir_size(dir),
+        dimensions,
+    }
+}
+
+/// Измерения мира с регионами: верхний мир, DIM-1/DIM1 и `dimensions/<ns>/<name>`.
+/// У Bukkit-серверов Незер и Энд — отдельные миры (`world_nether/DIM-1`).
+fn dimension_dirs(world_dir: &Path) -> Vec<(String, PathBuf)> {
+    let mut dims = vec![
+        ("minecraft:overworld".to_string(), world_dir.to_path_buf()),
+        ("minecraft:the_nether".to_string(), world_dir.join("DIM-1")),
+        ("minecraft:the_end".to_string(), world_dir.join("DIM1")),
+    ];
+
+    if let Ok(namespaces) = std::fs::read_dir(world_dir.join("dimensions")) {
+        for namespace in namespaces.filter_map(|e| e.ok()) {
+            let Ok(names) = std::fs::read_dir(namespace.path()) else {
+                continue;
+            };
+            for name in names.filter_map(|e| e.ok()) {
+                dims.push((
+                    format!(
+                        "{}:{}",
+                        namespace.file_name().to_string_lossy(),
+                        name.file_name().to_string_lossy()
+                    ),
+                    name.path(),
+                ));
+            }
+        }
+    }
+
+    dims.retain(|(_, root)| root.join("region").is_dir());
+    dims.sort_by(|a, b| a.0.cmp(&b.0));
+    dims
+}
+
+fn select_dimensions(world_dir: &Path, dimension: Option<&str>) -> Result<Vec<(String, PathBuf)>> {
+    let dims = dimension_dirs(world_dir);
+    match dimension {
+        None => Ok(dims),
+        Some(id) => {
+            let selected: Vec<_> = dims.into_iter().filter(|(d, _)| d == id).collect();
+            if selected.is_empty() {
+                return Err(LauncherError::NotFound(format!("Dimension {}", id)));
+            }
+            Ok(selected)
+        }
+    }
+}
+
+fn dir_size(dir: &Path) -> u64 {
+    let Ok(entries) = std::fs::read_dir(dir) else {
+        return 0;
+    };
+    entries
+        .filter_map(|e| e.ok())
+        .map(|e| match e.file_type() {
+            Ok(t) if t.is_dir() => dir_size(&e.path()),
+            Ok(_) => e.metadata().map(|m| m.len()).unwrap_or(0),
+            Err(_) => 0,
+        })
+        .sum()
+}
+
+fn file_size(path: &Path) -> u64 {
+    std::fs::metadata(path).map(|m| m.len()).unwrap_or(0)
+}
+
+/// Обрезать одно измерение: чанки с InhabitedTime < порога удаляются из region/,
+/// entities/ и poi/ с теми же координатами
+fn trim_dimension(root: &Path, max_inhabited_ticks: i64, dry_run: bool) -> Result<TrimReport> {
+    let mut report = TrimReport::default();
+
+    for (rx, rz, path) in anvil::list_regions(&root.join("region")) {
+        report.regions_scanned += 1;
+        let chunks = match anvil::read_region(&path) {
+            Ok(chunks) => chunks,
+            Err(e) => {
+                log::warn!("Skipping unreadable region {}: {}", path.display(), e);
+                continue;
+            }
+        };
+
+        let mut removed = Vec::new();
+        for chunk in &chunks {
+            match chunk.read_nbt(&path, (rx, rz)) {
+                Ok(Some(nbt)) => {
+                    if anvil::inhabited_time(&nbt).unwrap_or(0) < max_inhabited_ticks {
+                        removed.push((chunk.local_x, chunk.local_z));
+                    }
+                }
+                _ => report.chunks_skipped += 1,
+            }
+        }
+        if removed.is_empty() {
+            continue;
+        }
+        report.chunks_removed += removed.len() as u32;
+        if removed.len() == chunks.len() {
+            report.regions_deleted += 1;
+        }
+
+        let file_name = path.file_name().unwrap_or_default().to_owned();
+        for kind in REGION_KINDS {
+            let kind_path = root.join(kind).join(&file_name);
+            if !kind_path.exists() {
+                continue;
+            }
+            let before = file_size(&kind_path);
+            let kind_chunks = anvil::read_region(&kind_path)?;
+            let (gone, kept): (Vec<_>, Vec<_>) = kind_chunks
+                .into_iter()
+                .partition(|c| removed.contains(&(c.local_x, c.local_z)));
+            let mut freed = 0;
+            for chunk in gone.iter().filter(|c| c.is_external()) {
+                let (x, z) = chunk.world_coords((rx, rz));
+                let external = kind_path.with_file_name(format!("c.{}.{}.mcc", x, z));
+                freed += file_size(&external);
+                if !dry_run {
+                    std::fs::remove_file(&external).ok();
+                }
+            }
+            if dry_run {
+                let kept_bytes: usize = kept.iter().map(|c| c.data.len() + 5).sum();
+                freed += before.saturating_sub(kept_bytes as u64);
+            } else {
+                anvil::write_region(&kind_path, &kept)?;
+                freed += before.saturating_sub(file_size(&kind_path));
+            }
+            report.bytes_freed += freed;
+        }
+    }
+
+    Ok(report)
+}
+
+/// Удалить регионы (и внешние чанки), лежащие целиком дальше `radius` от центра
+fn delete_outside(
+    root: &Path,
+    center: (i32, i32),
+    radius: i64,
+    dry_run: bool,
+) -> Result<TrimReport> {
+    let mut report = TrimReport::default();
+    let region_blocks = (anvil::CHUNKS_PER_REGION * 16) as i64;
+    let outside = |rx: i32, rz: i32| {
+        let nearest = |region: i32, c: i32| {
+            let min = region as i64 * region_blocks;
+            (c as i64).clamp(min, min + region_blocks - 1) - c as i64
+        };
+        let (dx, dz) = (nearest(rx, center.0), nearest(rz, center.1));
+        dx * dx + dz * dz > radius * radius
+    };
+
+    for kind in REGION_KINDS {
+        let dir = root.join(kind);
+        let Ok(entries) = std::fs::read_dir(&dir) else {
+            continue;
+        };
+        let entries: Vec<_> = entries.filter_map(|e| e.ok()).collect();
+        for entry in entries {
+            let name = entry.file_name().to_string_lossy().to_string();
+            let region = if let Some(coords) = anvil::parse_region_name(&name) {
+                if *kind == "region" {
+                    report.regions_scanned += 1;
+                }
+                coords
+            } else if let Some((x, z)) = parse_external_chunk_name(&name) {
+                (
+                    x.div_euclid(anvil::CHUNKS_PER_REGION),
+                    z.div_euclid(anvil::CHUNKS_PER_REGION),
+                )
+            } else {
+                continue;
+            };
+            if !outside(region.0, region.1) {
+                continue;
+            }
+
+            if *kind == "region" && name.ends_with(".mca") {
+                report.regions_deleted += 1;
+            }
+            report.bytes_freed += entry.metadata().map(|m| m.len()).unwrap_or(0);
+            if !dry_run {
+                std::fs::remove_file(entry.path())?;
+            }
+        }
+    }
+
+    Ok(report)
+}
+
+/// `c.<x>.<z>.mcc` — чанк, не поместившийся в регион
+fn parse_external_chunk_name(file_name: &str) -> Option<(i32, i32)> {
+    let rest = file_name.strip_prefix("c.")?.strip_suffix(".mcc")?;
+    let (x, z) = rest.split_once('.')?;
+    Some((x.parse().ok()?, z.parse().ok()?))
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn get_world_inventory(instance_id: String) -> Result<Vec<WorldInfo>> {
+    WorldManager::list(&instance_id).await
+}
+
+#[tauri::command]
+pub async fn trim_world_chunks(
+    app: AppHandle,
+    instance_id: String,
+    world: String,
+    dimension: Option<String>,
+    max_inhabited_ticks: i64,
+    dry_run: bool,
+) -> Result<TrimReport> {
+    WorldManager::trim_chunks(
+        &app,
+        &instance_id,
+        &world,
+        dimension.as_deref(),
+        max_inhabited_ticks,
+        dry_run,
+    )
+    .await
+}
+
+#[tauri::command]
+#[allow(clippy::too_many_arguments)]
+pub async fn delete_world_regions_outside(
+    app: AppHandle,
+    instance_id: String,
+    world: String,
+    dimension: Option<String>,
+    radius: u32,
+    center_x: Option<i32>,
+    center_z: Option<i32>,
+    dry_run: bool,
+) -> Result<TrimReport> {
+    let center = center_x.zip(center_z);
+    WorldManager::delete_regions_outside(
+        &app,
+        &instance_id,
+        &world,
+        dimension.as_deref(),
+        radius,
+        center,
+        dry_run,
+    )
+    .await
+}
+
+#[tauri::command]
+pub async fn upgrade_world(
+    app: AppHandle,
+    instance_id: String,
+    world: String,
+    erase_cache: Option<bool>,
+) -> Result<WorldUpgradeReport> {
+    WorldManager::upgrade(&app, &instance_id, &world, erase_cache.unwrap_or(false)).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anvil::RawChunk;
+    use nbt::{Compression, Tag};
+
+    fn chunk(local_x: i32, local_z: i32, inhabited: i64) -> RawChunk {
+        let mut root = Compound::default();
+        root.insert("InhabitedTime", Tag::Long(inhabited));
+        RawChunk {
+            local_x,
+            local_z,
+            timestamp: 0,
+            compression: 2,
+            data: nbt::to_bytes("", &root, Compression::Zlib).unwrap(),
+        }
+    }
+
+    #[test]
+    fn test_trim_and_delete_outside() {
+        let root = std::env::temp_dir().join(format!("stuzhik-worlds-{}", std::process::id()));
+        for kind in REGION_KINDS {
+            std::fs::create_dir_all(root.join(kind)).unwrap();
+        }
+        let region = root.join("region").join("r.0.0.mca");
+        anvil::write_region(&region, &[chunk(0, 0, 0), chunk(1, 0, 72_000)]).unwrap();
+        anvil::write_region(&root.join("entities").join("r.0.0.mca"), &[chunk(0, 0, 0)]).unwrap();
+        anvil::write_region(&root.join("region").join("r.5.-3.mca"), &[chunk(3, 3, 0)]).unwrap();
+        std::fs::write(root.join("region").join("c.170.-90.mcc"), b"external").unwrap();
+
+        let dry = trim_dimension(&root, 1200, true).unwrap();
+        assert_eq!(dry.chunks_removed, 2);
+        assert_eq!(anvil::read_region(&region).unwrap().len(), 2);
+
+        let report = trim_dimension(&root, 1200, false).unwrap();
+        assert_eq!((report.regions_scanned, report.chunks_removed), (2, 2));
+        assert_eq!(report.regions_deleted, 1);
+        let kept = anvil::read_region(&region).unwrap();
+        assert_eq!((kept.len(), kept[0].local_x), (1, 1));
+        // Сущности удалённого чанка ушли вместе с ним
+        assert!(!root.join("entities").join("r.0.0.mca").exists());
+
+        // r.5.-3 начинается с x=2560: за радиусом 1000 от (0, 0), r.0.0 — внутри
+        anvil::write_region(&root.join("region").join("r.5.-3.mca"), &[chunk(3, 3, 0)]).unwrap();
+        let report = delete_outside(&root, (0, 0), 1000, false).unwrap();
+        assert_eq!(report.regions_deleted, 1);
+        assert!(region.exists());
+        assert!(!root.join("region").join("r.5.-3.mca").exists());
+        assert!(!root.join("region").join("c.170.-90.mcc").exists());
+
+        std::fs::remove_dir_all(&root).ok();
+    }
+
+    #[test]
+    fn test_spawn_point() {
+        let mut data = Compound::default();
+        data.insert("SpawnX", Tag::Int(100));
+        data.insert("SpawnZ", Tag::Int(-40));
+        assert_eq!(spawn_point(&data), (100, -40));
+
+        let mut spawn = Compound::default();
+        spawn.insert("pos", Tag::IntArray(vec![7, 64, 9]));
+        let mut data = Compound::default();
+        data.insert("spawn", Tag::Compound(spawn));
+        assert_eq!(spawn_point(&data), (7, 9));
+    }
+}
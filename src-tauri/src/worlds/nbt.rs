@@ -0,0 +1,563 @@
+//! NBT (Named Binary Tag) reader/writer
+//!
+//! Формат level.dat, playerdata, servers.dat и чанков в регионах.
+//! Строки — Java Modified UTF-8 (NUL как `C0 80`, символы вне BMP — суррогатными парами).
+
+use flate2::read::{GzDecoder, ZlibDecoder};
+use flate2::write::{GzEncoder, ZlibEncoder};
+use serde::{Deserialize, Serialize};
+use std::io::{Read, Write};
+use std::path::Path;
+
+use crate::error::{LauncherError, Result};
+
+pub const TAG_END: u8 = 0;
+pub const TAG_BYTE: u8 = 1;
+pub const TAG_SHORT: u8 = 2;
+pub const TAG_INT: u8 = 3;
+pub const TAG_LONG: u8 = 4;
+pub const TAG_FLOAT: u8 = 5;
+pub const TAG_DOUBLE: u8 = 6;
+pub const TAG_BYTE_ARRAY: u8 = 7;
+pub const TAG_STRING: u8 = 8;
+pub const TAG_LIST: u8 = 9;
+pub const TAG_COMPOUND: u8 = 10;
+pub const TAG_INT_ARRAY: u8 = 11;
+pub const TAG_LONG_ARRAY: u8 = 12;
+
+/// Защита от бесконечной рекурсии на битых файлах (у Minecraft тот же лимит)
+const MAX_DEPTH: usize = 512;
+
+/// Значение NBT
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum Tag {
+    Byte(i8),
+    Short(i16),
+    Int(i32),
+    Long(i64),
+    Float(f32),
+    Double(f64),
+    ByteArray(Vec<i8>),
+    String(String),
+    List(Vec<Tag>),
+    Compound(Compound),
+    IntArray(Vec<i32>),
+    LongArray(Vec<i64>),
+}
+
+impl Tag {
+    pub fn id(&self) -> u8 {
+        match self {
+            Tag::Byte(_) => TAG_BYTE,
+            Tag::Short(_) => TAG_SHORT,
+            Tag::Int(_) => TAG_INT,
+            Tag::Long(_) => TAG_LONG,
+            Tag::Float(_) => TAG_FLOAT,
+            Tag::Double(_) => TAG_DOUBLE,
+            Tag::ByteArray(_) => TAG_BYTE_ARRAY,
+            Tag::String(_) => TAG_STRING,
+            Tag::List(_) => TAG_LIST,
+            Tag::Compound(_) => TAG_COMPOUND,
+            Tag::IntArray(_) => TAG_INT_ARRAY,
+            Tag::LongArray(_) => TAG_LONG_ARRAY,
+        }
+    }
+
+    /// Целое любой ширины (флаги хранятся то байтом, то int)
+    pub fn as_i64(&self) -> Option<i64> {
+        match *self {
+            Tag::Byte(v) => Some(v as i64),
+            Tag::Short(v) => Some(v as i64),
+            Tag::Int(v) => Some(v as i64),
+            Tag::Long(v) => Some(v),
+            _ => None,
+        }
+    }
+
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            Tag::String(s) => Some(s),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound(&self) -> Option<&Compound> {
+        match self {
+            Tag::Compound(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn as_compound_mut(&mut self) -> Option<&mut Compound> {
+        match self {
+            Tag::Compound(c) => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn as_list(&self) -> Option<&[Tag]> {
+        match self {
+            Tag::List(items) => Some(items),
+            _ => None,
+        }
+    }
+}
+
+/// TAG_Compound с сохранением порядка ключей (файл после правки отличается минимально)
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Compound(pub Vec<(String, Tag)>);
+
+impl Compound {
+    pub fn get(&self, key: &str) -> Option<&Tag> {
+        self.0.iter().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    pub fn get_mut(&mut self, key: &str) -> Option<&mut Tag> {
+        self.0.iter_mut().find(|(k, _)| k == key).map(|(_, v)| v)
+    }
+
+    /// Заменить значение (на том же месте) или добавить в конец
+    pub fn insert(&mut self, key: impl Into<String>, value: Tag) -> Option<Tag> {
+        let key = key.into();
+        match self.get_mut(&key) {
+            Some(existing) => Some(std::mem::replace(existing, value)),
+            None => {
+                self.0.push((key, value));
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: &str) -> Option<Tag> {
+        let index = self.0.iter().position(|(k, _)| k == key)?;
+        Some(self.0.remove(index).1)
+    }
+
+    pub fn compound(&self, key: &str) -> Option<&Compound> {
+        self.get(key)?.as_compound()
+    }
+
+    pub fn i64(&self, key: &str) -> Option<i64> {
+        self.get(key)?.as_i64()
+    }
+
+    pub fn str(&self, key: &str) -> Option<&str> {
+        self.get(key)?.as_str()
+    }
+
+    /// Значение по пути через compound'ы: `["Data", "Version", "Name"]`
+    pub fn path(&self, path: &[&str]) -> Option<&Tag> {
+        let (last, parents) = path.split_last()?;
+        let mut current = self;
+        for key in parents {
+            current = current.compound(key)?;
+        }
+        current.get(last)
+    }
+}
+
+/// Сжатие NBT-файла
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Compression {
+    None,
+    Gzip,
+    Zlib,
+}
+
+impl Compression {
+    /// Определить по первым байтам
+    pub fn detect(data: &[u8]) -> Self {
+        match data {
+            [0x1f, 0x8b, ..] => Compression::Gzip,
+            [0x78, 0x01 | 0x5e | 0x9c | 0xda, ..] => Compression::Zlib,
+            _ => Compression::None,
+        }
+    }
+}
+
+/// Распаковать (если нужно) и разобрать NBT. Возвращает имя корня и корневой compound.
+pub fn from_bytes(data: &[u8]) -> Result<(String, Compound)> {
+    match Compression::detect(data) {
+        Compression::None => parse(data),
+        Compression::Gzip => parse(&decompress(GzDecoder::new(data))?),
+        Compression::Zlib => parse(&decompress(ZlibDecoder::new(data))?),
+    }
+}
+
+/// Сериализовать с нужным сжатием
+pub fn to_bytes(name: &str, root: &Compound, compression: Compression) -> Result<Vec<u8>> {
+    let raw = serialize(name, root);
+    Ok(match compression {
+        Compression::None => raw,
+        Compression::Gzip => {
+            let mut encoder = GzEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&raw)?;
+            encoder.finish()?
+        }
+        Compression::Zlib => {
+            let mut encoder = ZlibEncoder::new(Vec::new(), flate2::Compression::default());
+            encoder.write_all(&raw)?;
+            encoder.finish()?
+        }
+    })
+}
+
+/// Прочитать NBT-файл. Сжатие возвращается, чтобы записать файл обратно тем же форматом.
+pub fn read_file(path: &Path) -> Result<(String, Compound, Compression)> {
+    let data = std::fs::read(path)?;
+    let (name, root) = from_bytes(&data)
+        .map_err(|e| LauncherError::InvalidConfig(format!("{}: {}", path.display(), e)))?;
+    Ok((name, root, Compression::detect(&data)))
+}
+
+/// Записать NBT-файл атомарно ([`crate::utils::atomic_write`]).
+/// Прежняя версия остаётся рядом как `<file>_old`, как это делает сама игра.
+pub fn write_file(
+    path: &Path,
+    name: &str,
+    root: &Compound,
+    compression: Compression,
+) -> Result<()> {
+    let data = to_bytes(name, root, compression)?;
+    let file_name = path
+        .file_name()
+        .map(|n| n.to_string_lossy().to_string())
+        .unwrap_or_default();
+    if path.exists() {
+        std::fs::copy(path, path.with_file_name(format!("{}_old", file_name)))?;
+    }
+    crate::utils::atomic_write(path, &data)
+}
+
+fn decompress(mut reader: impl Read) -> Result<Vec<u8>> {
+    let mut data = Vec::new();
+    reader.read_to_end(&mut data)?;
+    Ok(data)
+}
+
+fn malformed(what: &str) -> LauncherError {
+    LauncherError::InvalidConfig(format!("Malformed NBT: {}", what))
+}
+
+/// Разобрать несжатый NBT с корневым compound
+pub fn parse(data: &[u8]) -> Result<(String, Compound)> {
+    let mut reader = Reader { data, pos: 0 };
+    if reader.u8()? != TAG_COMPOUND {
+        return Err(malformed("root is not a compound"));
+    }
+    let name = reader.string()?;
+    let root = reader.compound(0)?;
+    Ok((name, root))
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8]> {
+        let end = self
+            .pos
+            .checked_add(len)
+            .filter(|&end| end <= self.data.len())
+            .ok_or_else(|| malformed("unexpected end of data"))?;
+        let bytes = &self.data[self.pos..end];
+        self.pos = end;
+        Ok(bytes)
+    }
+
+    fn array<const N: usize>(&mut self) -> Result<[u8; N]> {
+        Ok(self.take(N)?.try_into().expect("length checked"))
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn i32(&mut self) -> Result<i32> {
+        Ok(i32::from_be_bytes(self.array()?))
+    }
+
+    fn len(&mut self) -> Result<usize> {
+        let len = self.i32()?;
+        usize::try_from(len).map_err(|_| malformed("negative length"))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = u16::from_be_bytes(self.array()?) as usize;
+        Ok(decode_mutf8(self.take(len)?))
+    }
+
+    fn compound(&mut self, depth: usize) -> Result<Compound> {
+        if depth > MAX_DEPTH {
+            return Err(malformed("nesting too deep"));
+        }
+        let mut entries = Vec::new();
+        loop {
+            let id = self.u8()?;
+            if id == TAG_END {
+                return Ok(Compound(entries));
+            }
+            let name = self.string()?;
+            entries.push((name, self.payload(id, depth + 1)?));
+        }
+    }
+
+    fn payload(&mut self, id: u8, depth: usize) -> Result<Tag> {
+        Ok(match id {
+            TAG_BYTE => Tag::Byte(self.u8()? as i8),
+            TAG_SHORT => Tag::Short(i16::from_be_bytes(self.array()?)),
+            TAG_INT => Tag::Int(self.i32()?),
+            TAG_LONG => Tag::Long(i64::from_be_bytes(self.array()?)),
+            TAG_FLOAT => Tag::Float(f32::from_be_bytes(self.array()?)),
+            TAG_DOUBLE => Tag::Double(f64::from_be_bytes(self.array()?)),
+            TAG_BYTE_ARRAY => {
+                let len = self.len()?;
+                Tag::ByteArray(self.take(len)?.iter().map(|&b| b as i8).collect())
+            }
+            TAG_STRING => Tag::String(self.string()?),
+            TAG_LIST => {
+                if depth > MAX_DEPTH {
+                    return Err(malformed("nesting too deep"));
+                }
+                let item = self.u8()?;
+                let len = self.len()?;
+                if item == TAG_END && len > 0 {
+                    return Err(malformed("list of TAG_End"));
+                }
+                // Ёмкость ограничена остатком данных: битая длина не должна съесть память
+                let mut items = Vec::with_capacity(len.min(self.data.len() - self.pos));
+                for _ in 0..len {
+                    items.push(self.payload(item, depth + 1)?);
+                }
+                Tag::List(items)
+            }
+            TAG_COMPOUND => Tag::Compound(self.compound(depth)?),
+            TAG_INT_ARRAY => {
+                let len = self.len()?;
+                let bytes = self.take(len.checked_mul(4).ok_or_else(|| malformed("length"))?)?;
+                Tag::IntArray(
+                    bytes
+                        .chunks_exact(4)
+                        .map(|c| i32::from_be_bytes(c.try_into().unwrap()))
+                        .collect(),
+                )
+            }
+            TAG_LONG_ARRAY => {
+                let len = self.len()?;
+                let bytes = self.take(len.checked_mul(8).ok_or_else(|| malformed("length"))?)?;
+                Tag::LongArray(
+                    bytes
+                        .chunks_exact(8)
+                        .map(|c| i64::from_be_bytes(c.try_into().unwrap()))
+                        .collect(),
+                )
+            }
+            other => return Err(malformed(&format!("unknown tag id {}", other))),
+        })
+    }
+}
+
+/// Сериализовать в несжатый NBT
+pub fn serialize(name: &str, root: &Compound) -> Vec<u8> {
+    let mut out = vec![TAG_COMPOUND];
+    write_string(&mut out, name);
+    write_compound(&mut out, root);
+    out
+}
+
+fn write_string(out: &mut Vec<u8>, s: &str) {
+    let bytes = encode_mutf8(s);
+    // Длиннее u16 строка в NBT не помещается — обрезаем как можно аккуратнее
+    let len = bytes.len().min(u16::MAX as usize);
+    out.extend_from_slice(&(len as u16).to_be_bytes());
+    out.extend_from_slice(&bytes[..len]);
+}
+
+fn write_compound(out: &mut Vec<u8>, compound: &Compound) {
+    for (name, tag) in &compound.0 {
+        out.push(tag.id());
+        write_string(out, name);
+        write_payload(out, tag);
+    }
+    out.push(TAG_END);
+}
+
+fn write_payload(out: &mut Vec<u8>, tag: &Tag) {
+    match tag {
+        Tag::Byte(v) => out.push(*v as u8),
+        Tag::Short(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Int(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Long(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Float(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::Double(v) => out.extend_from_slice(&v.to_be_bytes()),
+        Tag::ByteArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            out.extend(values.iter().map(|&b| b as u8));
+        }
+        Tag::String(s) => write_string(out, s),
+        Tag::List(items) => {
+            // Пустой список пишется с типом TAG_End, как у игры
+            out.push(items.first().map(Tag::id).unwrap_or(TAG_END));
+            out.extend_from_slice(&(items.len() as i32).to_be_bytes());
+            for item in items {
+                write_payload(out, item);
+            }
+        }
+        Tag::Compound(compound) => write_compound(out, compound),
+        Tag::IntArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for v in values {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+        Tag::LongArray(values) => {
+            out.extend_from_slice(&(values.len() as i32).to_be_bytes());
+            for v in values {
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+        }
+    }
+}
+
+/// Java Modified UTF-8 -> String (невалидные последовательности заменяются на U+FFFD)
+fn decode_mutf8(bytes: &[u8]) -> String {
+    // Быстрый путь: обычный UTF-8 без NUL и 4-байтовых символов совпадает с MUTF-8
+    if let Ok(s) = std::str::from_utf8(bytes) {
+        return s.to_string();
+    }
+
+    let mut units: Vec<u16> = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        let b = bytes[i];
+        let (unit, width) = if b < 0x80 {
+            (b as u16, 1)
+        } else if b & 0xE0 == 0xC0 && i + 1 < bytes.len() {
+            ((((b & 0x1F) as u16) << 6) | (bytes[i + 1] & 0x3F) as u16, 2)
+        } else if b & 0xF0 == 0xE0 && i + 2 < bytes.len() {
+            (
+                (((b & 0x0F) as u16) << 12)
+                    | (((bytes[i + 1] & 0x3F) as u16) << 6)
+                    | (bytes[i + 2] & 0x3F) as u16,
+                3,
+            )
+        } else {
+            (0xFFFD, 1)
+        };
+        units.push(unit);
+        i += width;
+    }
+    String::from_utf16_lossy(&units)
+}
+
+/// String -> Java Modified UTF-8
+fn encode_mutf8(s: &str) -> Vec<u8> {
+    if !s.chars().any(|c| c == '\0' || c as u32 > 0xFFFF) {
+        return s.as_bytes().to_vec();
+    }
+
+    let mut out = Vec::with_capacity(s.len() + 8);
+    for unit in s.encode_utf16() {
+        match unit {
+            0x0001..=0x007F => out.push(unit as u8),
+            0x0000 | 0x0080..=0x07FF => {
+                out.push(0xC0 | (unit >> 6) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+            _ => {
+                out.push(0xE0 | (unit >> 12) as u8);
+                out.push(0x80 | ((unit >> 6) & 0x3F) as u8);
+                out.push(0x80 | (unit & 0x3F) as u8);
+            }
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn sample() -> Compound {
+        let mut version = Compound::default();
+        version.insert("Name", Tag::String("1.21.1".to_string()));
+        version.insert("Id", Tag::Int(3955));
+
+        let mut data = Compound::default();
+        data.insert("LevelName", Tag::String("Мир \0 😀".to_string()));
+        data.insert("hardcore", Tag::Byte(1));
+        data.insert("LastPlayed", Tag::Long(1_760_000_000_000));
+        data.insert("BorderSize", Tag::Double(5.9e7));
+        data.insert("Version", Tag::Compound(version));
+        data.insert(
+            "ServerBrands",
+            Tag::List(vec![Tag::String("fabric".to_string())]),
+        );
+        data.insert("DisabledDataPacks", Tag::List(Vec::new()));
+        data.insert("Heights", Tag::LongArray(vec![-1, 0, i64::MAX]));
+        data.insert("UUID", Tag::IntArray(vec![1, 2, 3, 4]));
+
+        let mut root = Compound::default();
+        root.insert("Data", Tag::Compound(data));
+        root
+    }
+
+    #[test]
+    fn test_roundtrip() {
+        let root = sample();
+        for compression in [Compression::None, Compression::Gzip, Compression::Zlib] {
+            let bytes = to_bytes("", &root, compression).unwrap();
+            assert_eq!(Compression::detect(&bytes), compression);
+            let (name, parsed) = from_bytes(&bytes).unwrap();
+            assert_eq!(name, "");
+            assert_eq!(parsed, root);
+        }
+
+        assert_eq!(
+            root.path(&["Data", "Version", "Name"])
+                .and_then(Tag::as_str),
+            Some("1.21.1")
+        );
+        assert_eq!(
+            root.path(&["Data", "hardcore"]).and_then(Tag::as_i64),
+            Some(1)
+        );
+    }
+
+    #[test]
+    fn test_modified_utf8() {
+        let encoded = encode_mutf8("a\0😀");
+        // NUL — два байта, эмодзи — суррогатная пара по 3 байта
+        assert_eq!(encoded.len(), 1 + 2 + 6);
+        assert!(!encoded.contains(&0));
+        assert_eq!(decode_mutf8(&encoded), "a\0😀");
+        assert_eq!(decode_mutf8("обычный".as_bytes()), "обычный");
+    }
+
+    #[test]
+    fn test_malformed() {
+        let bytes = serialize("", &sample());
+        assert!(parse(&bytes[..bytes.len() - 1]).is_err());
+        assert!(parse(&[TAG_LIST, 0, 0]).is_err());
+        // Список из 2^31-1 элементов в 10 байтах не должен выделять память под них
+        assert!(parse(&[
+            TAG_COMPOUND,
+            0,
+            0,
+            TAG_LIST,
+            0,
+            1,
+            b'x',
+            TAG_INT,
+            0x7F,
+            0xFF,
+            0xFF,
+            0xFF
+        ])
+        .is_err());
+    }
+}
@@ -0,0 +1,614 @@
+//! Player data editor
+//!
+//! `playerdata/<uuid>.dat` мира: позиция, измерение, инвентарь, эндер-сундук,
+//! опыт и режим игры. В одиночном мире данные хозяина лежат ещё и в `Data.Player`
+//! level.dat — игра читает именно их, поэтому правка идёт в оба места.
+//!
+//! Правка только при остановленном экземпляре: игра перезапишет файл при выходе игрока.
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::path::{Path, PathBuf};
+use tauri::AppHandle;
+
+use super::nbt::{self, Compound, Tag};
+use super::{data_version, ensure_stopped, game_mode_id, game_mode_name};
+use crate::backup::BackupManager;
+use crate::error::{LauncherError, Result};
+use crate::paths::instance_dir;
+
+/// DataVersion 1.20.5: `Count` (byte) у предметов стал `count` (int)
+const ITEM_COUNT_INT_VERSION: i32 = 3837;
+
+const ENDER_CHEST_SLOTS: i8 = 27;
+
+/// Файл игрока в мире
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerDataSummary {
+    pub uuid: String,
+    /// Из usercache.json, whitelist, ops или банов
+    pub name: Option<String>,
+    /// Хозяин одиночного мира (данные в level.dat)
+    pub singleplayer_host: bool,
+    pub modified: Option<String>,
+}
+
+/// Предмет в инвентаре
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ItemStack {
+    pub slot: i8,
+    pub id: String,
+    pub count: i32,
+    /// Остальные поля предмета (`components` или `tag`) — переносятся без изменений
+    #[serde(default)]
+    pub extra: Compound,
+}
+
+/// Состояние игрока
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlayerData {
+    pub uuid: String,
+    pub name: Option<String>,
+    pub singleplayer_host: bool,
+    pub data_version: Option<i32>,
+    pub position: [f64; 3],
+    pub dimension: String,
+    pub game_mode: String,
+    pub xp_level: i32,
+    pub xp_progress: f32,
+    pub xp_total: i32,
+    pub health: f32,
+    /// Слоты 0–35; броня 100–103 и вторая рука -106 до 1.21.5 (позже — в `equipment`)
+    pub inventory: Vec<ItemStack>,
+    pub ender_chest: Vec<ItemStack>,
+}
+
+/// Изменения: None — оставить как есть
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlayerDataEdit {
+    pub position: Option<[f64; 3]>,
+    pub dimension: Option<String>,
+    pub game_mode: Option<String>,
+    pub xp_level: Option<i32>,
+    pub xp_progress: Option<f32>,
+    pub xp_total: Option<i32>,
+    pub inventory: Option<Vec<ItemStack>>,
+    pub ender_chest: Option<Vec<ItemStack>>,
+}
+
+pub struct PlayerDataManager;
+
+impl PlayerDataManager {
+    /// Игроки мира: файлы playerdata и хозяин одиночного мира
+    pub async fn list(instance_id: &str, world: &str) -> Result<Vec<PlayerDataSummary>> {
+        let world_dir = BackupManager::world_dir(instance_id, world).await?;
+        let names = crate::server::players::known_player_names(instance_dir(instance_id)).await;
+
+        let mut players = tokio::task::spawn_blocking(move || {
+            let host = read_host(&world_dir).ok().flatten().map(|(uuid, _)| uuid);
+            let mut players = Vec::new();
+
+            if let Ok(entries) = std::fs::read_dir(world_dir.join("playerdata")) {
+                for entry in entries.filter_map(|e| e.ok()) {
+                    let file_name = entry.file_name().to_string_lossy().to_string();
+                    let Some(uuid) = file_name.strip_suffix(".dat").filter(|u| is_uuid(u)) else {
+                        continue;
+                    };
+                    let uuid = uuid.to_lowercase();
+                    players.push(PlayerDataSummary {
+                        singleplayer_host: host.as_deref() == Some(uuid.as_str()),
+                        modified: entry
+                            .metadata()
+                            .and_then(|m| m.modified())
+                            .ok()
+                            .map(|t| DateTime::<Utc>::from(t).to_rfc3339()),
+                        name: None,
+                        uuid,
+                    });
+                }
+            }
+
+            // Старые одиночные миры: хозяин есть только в level.dat
+            if let Some(host) = host {
+                if !players.iter().any(|p| p.uuid == host) {
+                    players.push(PlayerDataSummary {
+                        uuid: host,
+                        name: None,
+                        singleplayer_host: true,
+                        modified: None,
+                    });
+                }
+            }
+            players
+        })
+        .await
+        .map_err(|e| LauncherError::Join(e.to_string()))?;
+
+        for player in &mut players {
+            player.name = names.get(&player.uuid).cloned();
+        }
+        players.sort_by(|a, b| {
+            (!a.singleplayer_host, &a.name, &a.uuid).cmp(&(!b.singleplayer_host, &b.name, &b.uuid))
+        });
+        Ok(players)
+    }
+
+    /// Прочитать данные игрока
+    pub async fn get(instance_id: &str, world: &str, uuid: &str) -> Result<PlayerData> {
+        let uuid = normalize_uuid(uuid)?;
+        let world_dir = BackupManager::world_dir(instance_id, world).await?;
+        let names = crate::server::players::known_player_names(instance_dir(instance_id)).await;
+
+        let mut data = tokio::task::spawn_blocking(move || {
+            let (root, host) = load_player(&world_dir, &uuid)?;
+            Ok::<_, LauncherError>(read_player(&uuid, &root, host))
+        })
+        .await
+        .map_err(|e| LauncherError::Join(e.to_string()))??;
+
+        data.name = names.get(&data.uuid).cloned();
+        Ok(data)
+    }
+
+    /// Изменить данные игрока (экземпляр должен быть остановлен)
+    pub async fn update(
+        app: &AppHandle,
+        instance_id: &str,
+        world: &str,
+        uuid: &str,
+        edit: PlayerDataEdit,
+    ) -> Result<PlayerData> {
+        let uuid = normalize_uuid(uuid)?;
+        ensure_stopped(app, instance_id).await?;
+        let world_dir = BackupManager::world_dir(instance_id, world).await?;
+
+        let log_uuid = uuid.clone();
+        tokio::task::spawn_blocking(move || {
+            let file = player_file(&world_dir, &uuid);
+            let mut found = false;
+
+            if file.exists() {
+                let (name, mut root, compression) = nbt::read_file(&file)?;
+                let version = data_version(&root);
+                apply_edit(&mut root, &edit, version)?;
+                nbt::write_file(&file, &name, &root, compression)?;
+                found = true;
+            }
+
+            let level_path = world_dir.join("level.dat");
+            if let Some((host_uuid, _)) = read_host(&world_dir)? {
+                if host_uuid == uuid {
+                    let (name, mut root, compression) = nbt::read_file(&level_path)?;
+                    // DataVersion у хозяина — на уровне Data, не в самом Player
+                    let data = root.get_mut("Data").and_then(Tag::as_compound_mut);
+                    let version = data.as_ref().and_then(|d| data_version(d));
+                    let player = data
+                        .and_then(|data| data.get_mut("Player"))
+                        .and_then(Tag::as_compound_mut)
+                        .ok_or_else(|| {
+                            LauncherError::InvalidConfig("level.dat without Data.Player".into())
+                        })?;
+                    apply_edit(player, &edit, version)?;
+                    nbt::write_file(&level_path, &name, &root, compression)?;
+                    found = true;
+                }
+            }
+
+            if !found {
+                return Err(LauncherError::NotFound(format!("Player data {}", uuid)));
+            }
+            Ok(())
+        })
+        .await
+        .map_err(|e| LauncherError::Join(e.to_string()))??;
+
+        log::info!(
+            "Edited player data {} in world {} of {}",
+            log_uuid,
+            world,
+            instance_id
+        );
+        Self::get(instance_id, world, &log_uuid).await
+    }
+}
+
+fn player_file(world_dir: &Path, uuid: &str) -> PathBuf {
+    world_dir.join("playerdata").join(format!("{}.dat", uuid))
+}
+
+/// Данные игрока: у хозяина одиночного мира приоритет у level.dat, как в игре
+fn load_player(world_dir: &Path, uuid: &str) -> Result<(Compound, bool)> {
+    if let Some((host_uuid, player)) = read_host(world_dir)? {
+        if host_uuid == uuid {
+            return Ok((player, true));
+        }
+    }
+
+    let file = player_file(world_dir, uuid);
+    if !file.exists() {
+        return Err(LauncherError::NotFound(format!("Player data {}", uuid)));
+    }
+    Ok((nbt::read_file(&file)?.1, false))
+}
+
+/// Хозяин одиночного мира из level.dat (`Data.Player`)
+fn read_host(world_dir: &Path) -> Result<Option<(String, Compound)>> {
+    let level_path = world_dir.join("level.dat");
+    if !level_path.exists() {
+        return Ok(None);
+    }
+    let mut data = super::read_level_data(world_dir)?;
+    let Some(Tag::Compound(player)) = data.remove("Player") else {
+        return Ok(None);
+    };
+    Ok(entity_uuid(&player).map(|uuid| (uuid, player)))
+}
+
+/// UUID сущности: `UUID` (int[4], 1.16+) или `UUIDMost`/`UUIDLeast`
+fn entity_uuid(entity: &Compound) -> Option<String> {
+    let bits: u128 = match entity.get("UUID") {
+        Some(Tag::IntArray(parts)) if parts.len() == 4 => parts
+            .iter()
+            .fold(0u128, |acc, &p| (acc << 32) | p as u32 as u128),
+        _ => {
+            let most = entity.i64("UUIDMost")? as u64 as u128;
+            let least = entity.i64("UUIDLeast")? as u64 as u128;
+            (most << 64) | least
+        }
+    };
+    let hex = format!("{:032x}", bits);
+    Some(format!(
+        "{}-{}-{}-{}-{}",
+        &hex[0..8],
+        &hex[8..12],
+        &hex[12..16],
+        &hex[16..20],
+        &hex[20..32]
+    ))
+}
+
+fn is_uuid(value: &str) -> bool {
+    value.len() == 36
+        && value.chars().enumerate().all(|(i, c)| match i {
+            8 | 13 | 18 | 23 => c == '-',
+            _ => c.is_ascii_hexdigit(),
+        })
+}
+
+/// UUID из аргумента команды — он же имя файла, поэтому строго проверяем формат
+fn normalize_uuid(uuid: &str) -> Result<String> {
+    if !is_uuid(uuid) {
+        return Err(LauncherError::InvalidConfig(format!(
+            "Invalid player UUID: {}",
+            uuid
+        )));
+    }
+    Ok(uuid.to_lowercase())
+}
+
+fn read_player(uuid: &str, root: &Compound, singleplayer_host: bool) -> PlayerData {
+    let float = |key: &str| match root.get(key) {
+        Some(Tag::Float(v)) => *v,
+        Some(Tag::Double(v)) => *v as f32,
+        _ => 0.0,
+    };
+    let position = match root.get("Pos").and_then(Tag::as_list) {
+        Some([Tag::Double(x), Tag::Double(y), Tag::Double(z)]) => [*x, *y, *z],
+        _ => [0.0; 3],
+    };
+    let dimension = match root.get("Dimension") {
+        Some(Tag::String(id)) => id.clone(),
+        Some(tag) => legacy_dimension_name(tag.as_i64().unwrap_or(0)).to_string(),
+        None => "minecraft:overworld".to_string(),
+    };
+
+    PlayerData {
+        uuid: uuid.to_string(),
+        name: None,
+        singleplayer_host,
+        data_version: data_version(root),
+        position,
+        dimension,
+        game_mode: game_mode_name(root.i64("playerGameType").unwrap_or(0)).to_string(),
+        xp_level: root.i64("XpLevel").unwrap_or(0) as i32,
+        xp_progress: float("XpP"),
+        xp_total: root.i64("XpTotal").unwrap_or(0) as i32,
+        health: float("Health"),
+        inventory: read_items(root.get("Inventory")),
+        ender_chest: read_items(root.get("EnderItems")),
+    }
+}
+
+fn read_items(list: Option<&Tag>) -> Vec<ItemStack> {
+    list.and_then(Tag::as_list)
+        .unwrap_or_default()
+        .iter()
+        .filter_map(Tag::as_compound)
+        .filter_map(|item| {
+            let mut extra = item.clone();
+            let slot = extra.remove("Slot")?.as_i64()? as i8;
+            let id = extra.remove("id")?.as_str()?.to_string();
+            let count = extra
+                .remove("count")
+                .or_else(|| extra.remove("Count"))
+                .and_then(|c| c.as_i64())
+                .unwrap_or(1) as i32;
+            Some(ItemStack {
+                slot,
+                id,
+                count,
+                extra,
+            })
+        })
+        .collect()
+}
+
+fn write_items(items: &[ItemStack], data_version: Option<i32>) -> Tag {
+    let modern = data_version.unwrap_or(i32::MAX) >= ITEM_COUNT_INT_VERSION;
+    Tag::List(
+        items
+            .iter()
+            .map(|item| {
+                let mut compound = Compound::default();
+                compound.insert("Slot", Tag::Byte(item.slot));
+                compound.insert("id", Tag::String(item.id.clone()));
+                if modern {
+                    compound.insert("count", Tag::Int(item.count));
+                } else {
+                    compound.insert("Count", Tag::Byte(item.count as i8));
+                }
+                for (key, value) in &item.extra.0 {
+                    compound.insert(key.clone(), value.clone());
+                }
+                Tag::Compound(compound)
+            })
+            .collect(),
+    )
+}
+
+fn validate_items(items: &[ItemStack], valid_slot: impl Fn(i8) -> bool) -> Result<()> {
+    let mut seen = std::collections::HashSet::new();
+    for item in items {
+        if !valid_slot(item.slot) || !seen.insert(item.slot) {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Invalid or duplicate slot {}",
+                item.slot
+            )));
+        }
+        if item.id.is_empty() || !(1..=99).contains(&item.count) {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Invalid item in slot {}: {} x{}",
+                item.slot, item.id, item.count
+            )));
+        }
+    }
+    Ok(())
+}
+
+fn legacy_dimension_name(id: i64) -> &'static str {
+    match id {
+        -1 => "minecraft:the_nether",
+        1 => "minecraft:the_end",
+        _ => "minecraft:overworld",
+    }
+}
+
+/// `version` — DataVersion файла (определяет формат предметов)
+fn apply_edit(root: &mut Compound, edit: &PlayerDataEdit, version: Option<i32>) -> Result<()> {
+    if let Some(position) = edit.position {
+        if position.iter().any(|v| !v.is_finite()) {
+            return Err(LauncherError::InvalidConfig(
+                "Position must be finite".to_string(),
+            ));
+        }
+        root.insert("Pos", Tag::List(position.map(Tag::Double).to_vec()));
+        // Иначе игрок продолжит падать с накопленной скоростью
+        root.insert("Motion", Tag::List(vec![Tag::Double(0.0); 3]));
+        if root.get("FallDistance").is_some() {
+            root.insert("FallDistance", Tag::Float(0.0));
+        }
+        if root.get("fall_distance").is_some() {
+            root.insert("fall_distance", Tag::Double(0.0));
+        }
+    }
+
+    if let Some(dimension) = &edit.dimension {
+        let (namespace, path) = dimension.split_once(':').ok_or_else(|| {
+            LauncherError::InvalidConfig(format!("Invalid dimension: {}", dimension))
+        })?;
+        if namespace.is_empty() || path.is_empty() {
+            return Err(LauncherError::InvalidConfig(format!(
+                "Invalid dimension: {}",
+                dimension
+            )));
+        }
+        let value = match root.get("Dimension") {
+            // До 1.16 измерение — число; своих измерений тогда не было
+            Some(Tag::Int(_)) => Tag::Int(match dimension.as_str() {
+                "minecraft:overworld" => 0,
+                "minecraft:the_nether" => -1,
+                "minecraft:the_end" => 1,
+                _ => {
+                    return Err(LauncherError::InvalidConfig(format!(
+                        "Dimension {} is not supported by this world version",
+                        dimension
+                    )))
+                }
+            }),
+            _ => Tag::String(dimension.clone()),
+        };
+        root.insert("Dimension", value);
+    }
+
+    if let Some(mode) = &edit.game_mode {
+        let id = game_mode_id(mode)
+            .ok_or_else(|| LauncherError::InvalidConfig(format!("Unknown game mode: {}", mode)))?;
+        root.insert("playerGameType", Tag::Int(id));
+    }
+
+    if let Some(level) = edit.xp_level {
+        root.insert("XpLevel", Tag::Int(level.max(0)));
+    }
+    if let Some(progress) = edit.xp_progress {
+        root.insert("XpP", Tag::Float(progress.clamp(0.0, 1.0)));
+    }
+    if let Some(total) = edit.xp_total {
+        root.insert("XpTotal", Tag::Int(total.max(0)));
+    }
+
+    if let Some(items) = &edit.inventory {
+        validate_items(items, |slot| {
+            (0..36).contains(&slot) || (100..=103).contains(&slot) || slot == -106
+        })?;
+        root.insert("Inventory", write_items(items, version));
+    }
+    if let Some(items) = &edit.ender_chest {
+        validate_items(items, |slot| (0..ENDER_CHEST_SLOTS).contains(&slot))?;
+        root.insert("EnderItems", write_items(items, version));
+    }
+
+    Ok(())
+}
+
+// ============================================================================
+// Tauri Commands
+// ============================================================================
+
+#[tauri::command]
+pub async fn list_player_data(
+    instance_id: String,
+    world: String,
+) -> Result<Vec<PlayerDataSummary>> {
+    PlayerDataManager::list(&instance_id, &world).await
+}
+
+#[tauri::command]
+pub async fn get_player_data(
+    instance_id: String,
+    world: String,
+    uuid: String,
+) -> Result<PlayerData> {
+    PlayerDataManager::get(&instance_id, &world, &uuid).await
+}
+
+#[tauri::command]
+pub async fn update_player_data(
+    app: AppHandle,
+    instance_id: String,
+    world: String,
+    uuid: String,
+    edit: PlayerDataEdit,
+) -> Result<PlayerData> {
+    PlayerDataManager::update(&app, &instance_id, &world, &uuid, edit).await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn player(data_version: i32) -> Compound {
+        let mut item = Compound::default();
+        item.insert("Slot", Tag::Byte(0));
+        item.insert("id", Tag::String("minecraft:diamond_sword".to_string()));
+        item.insert("count", Tag::Int(1));
+        let mut components = Compound::default();
+        components.insert("minecraft:damage", Tag::Int(12));
+        item.insert("components", Tag::Compound(components));
+
+        let mut root = Compound::default();
+        root.insert("DataVersion", Tag::Int(data_version));
+        root.insert("UUID", Tag::IntArray(vec![0x1234_5678, -1, 0, 42]));
+        root.insert(
+            "Pos",
+            Tag::List(vec![
+                Tag::Double(1.5),
+                Tag::Double(-200.0),
+                Tag::Double(3.5),
+            ]),
+        );
+        root.insert("Dimension", Tag::String("mymod:void".to_string()));
+        root.insert("FallDistance", Tag::Float(180.0));
+        root.insert("playerGameType", Tag::Int(0));
+        root.insert("Inventory", Tag::List(vec![Tag::Compound(item)]));
+        root
+    }
+
+    #[test]
+    fn test_read_player() {
+        let data = read_player("u", &player(3955), false);
+        assert_eq!(data.position, [1.5, -200.0, 3.5]);
+        assert_eq!(data.dimension, "mymod:void");
+        assert_eq!(data.game_mode, "survival");
+        assert_eq!(data.inventory.len(), 1);
+        assert_eq!(data.inventory[0].count, 1);
+        assert!(data.inventory[0].extra.get("components").is_some());
+
+        assert_eq!(
+            entity_uuid(&player(3955)).as_deref(),
+            Some("12345678-ffff-ffff-0000-00000000002a")
+        );
+        assert!(normalize_uuid("../../level").is_err());
+        assert_eq!(
+            normalize_uuid("12345678-FFFF-ffff-0000-00000000002A").unwrap(),
+            "12345678-ffff-ffff-0000-00000000002a"
+        );
+    }
+
+    #[test]
+    fn test_rescue_from_void() {
+        let mut root = player(3955);
+        let edit = PlayerDataEdit {
+            position: Some([0.5, 80.0, 0.5]),
+            dimension: Some("minecraft:overworld".to_string()),
+            game_mode: Some("creative".to_string()),
+            ..Default::default()
+        };
+        let version = data_version(&root);
+        apply_edit(&mut root, &edit, version).unwrap();
+
+        let data = read_player("u", &root, false);
+        assert_eq!(data.position, [0.5, 80.0, 0.5]);
+        assert_eq!(data.dimension, "minecraft:overworld");
+        assert_eq!(data.game_mode, "creative");
+        assert_eq!(root.get("FallDistance"), Some(&Tag::Float(0.0)));
+        // Предметы не тронуты
+        assert_eq!(data.inventory.len(), 1);
+
+        let bad = PlayerDataEdit {
+            dimension: Some("void".to_string()),
+            ..Default::default()
+        };
+        assert!(apply_edit(&mut root, &bad, None).is_err());
+    }
+
+    #[test]
+    fn test_edit_items_keeps_format() {
+        let mut root = player(3465); // 1.20.1: Count — байт
+        let mut sword = read_player("u", &root, false).inventory.remove(0);
+        sword.slot = 5;
+        sword.count = 2;
+        let edit = PlayerDataEdit {
+            ender_chest: Some(vec![sword.clone()]),
+            inventory: Some(Vec::new()),
+            ..Default::default()
+        };
+        let version = data_version(&root);
+        apply_edit(&mut root, &edit, version).unwrap();
+
+        let items = root.get("EnderItems").and_then(Tag::as_list).unwrap();
+        let stored = items[0].as_compound().unwrap();
+        assert_eq!(stored.get("Count"), Some(&Tag::Byte(2)));
+        assert!(stored.get("components").is_some());
+        assert_eq!(
+            read_player("u", &root, false).ender_chest,
+            vec![sword.clone()]
+        );
+
+        sword.slot = 30;
+        let edit = PlayerDataEdit {
+            ender_chest: Some(vec![sword]),
+            ..Default::default()
+        };
+        assert!(apply_edit(&mut root, &edit, None).is_err());
+    }
+}
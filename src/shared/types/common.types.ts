@@ -1,5 +1,14 @@
 export type GameType = "minecraft" | "hytale";
-export type LoaderType = "vanilla" | "forge" | "neoforge" | "fabric" | "quilt";
+export type LoaderType =
+  | "vanilla"
+  | "forge"
+  | "neoforge"
+  | "fabric"
+  | "quilt"
+  | "paper"
+  | "purpur"
+  | "folia"
+  | "velocity";
 export type InstanceType = "client" | "server";
 export type InstanceStatus = "stopped" | "starting" | "running" | "stopping" | "error" | "installing" | "crashed";
 export type ModSource = "modrinth" | "curseforge" | "local";